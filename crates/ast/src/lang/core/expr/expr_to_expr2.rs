@@ -650,6 +650,11 @@ pub fn expr_to_expr2<'a>(
             //            (RuntimeError(MalformedClosure(region)), Output::default())
             todo!()
         }
+        Malformed(_source) => {
+            //            use roc_problem::can::RuntimeError::*;
+            //            (RuntimeError(MalformedExpr(region)), Output::default())
+            todo!()
+        }
         MalformedIdent(_name, _problem) => {
             //            use roc_problem::can::RuntimeError::*;
             //
@@ -576,6 +576,7 @@ pub fn to_type2<'a>(
             //                    }
             Type2::AsAlias(symbol, vars, alias.actual)
         }
+        Tuple { .. } => todo!("tuple types"),
         Where { .. } => todo_abilities!(),
         SpaceBefore(nested, _) | SpaceAfter(nested, _) => {
             to_type2(env, scope, references, nested, region)
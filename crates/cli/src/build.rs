@@ -1,6 +1,6 @@
 use bumpalo::Bump;
 use roc_build::{
-    link::{link, preprocess_host_wasm32, rebuild_host, LinkType, LinkingStrategy},
+    link::{link, preprocess_host_wasm32, rebuild_host, HostKind, LinkType, LinkingStrategy},
     program::{self, Problems},
 };
 use roc_builtins::bitcode;
@@ -11,10 +11,12 @@ use roc_load::{
 };
 use roc_module::symbol::{Interns, ModuleId};
 use roc_mono::ir::OptLevel;
+use roc_problem::can::{DbgIgnoredReason, Problem};
 use roc_reporting::report::RenderTarget;
 use roc_target::TargetInfo;
+use std::path::{Path, PathBuf};
+use std::thread::JoinHandle;
 use std::time::{Duration, Instant};
-use std::{path::PathBuf, thread::JoinHandle};
 use target_lexicon::Triple;
 use tempfile::Builder;
 
@@ -36,6 +38,8 @@ pub struct BuiltFile {
     pub total_time: Duration,
     pub expectations: VecMap<ModuleId, Expectations>,
     pub interns: Interns,
+    /// The modules that were loaded, and the sources of the platform's host
+    pub inputs: Vec<PathBuf>,
 }
 
 pub enum BuildOrdering {
@@ -69,6 +73,8 @@ pub fn build_file<'a>(
     threading: Threading,
     wasm_dev_stack_bytes: Option<u32>,
     order: BuildOrdering,
+    render_target: RenderTarget,
+    cache_dir: Option<PathBuf>,
 ) -> Result<BuiltFile, BuildFileError<'a>> {
     let compilation_start = Instant::now();
     let target_info = TargetInfo::from(target);
@@ -83,10 +89,10 @@ pub fn build_file<'a>(
 
     let load_config = LoadConfig {
         target_info,
-        // TODO: expose this from CLI?
-        render: RenderTarget::ColorTerminal,
+        render: render_target,
         threading,
         exec_mode,
+        cache_dir,
     };
     let load_result = roc_load::load_and_monomorphize(
         arena,
@@ -173,6 +179,13 @@ pub fn build_file<'a>(
         })
         .collect();
 
+    let mut inputs: Vec<PathBuf> = loaded
+        .sources
+        .values()
+        .map(|(path, _)| path.clone())
+        .collect();
+    inputs.extend(host_sources(&host_input_path, prebuilt));
+
     let preprocessed_host_path = if emit_wasm {
         host_input_path.with_file_name("preprocessedhost.o")
     } else {
@@ -246,7 +259,21 @@ pub fn build_file<'a>(
     // This only needs to be mutable for report_problems. This can't be done
     // inside a nested scope without causing a borrow error!
     let mut loaded = loaded;
-    let problems = program::report_problems_monomorphized(&mut loaded);
+
+    if let Some(reason) = dbg_ignored_reason(target, opt_level) {
+        for (module_id, expectations) in loaded.expectations.iter() {
+            let problems = loaded.can_problems.entry(*module_id).or_default();
+
+            problems.extend(
+                expectations
+                    .dbgs
+                    .keys()
+                    .map(|&region| Problem::DbgIgnored { region, reason }),
+            );
+        }
+    }
+
+    let problems = program::report_problems_monomorphized(&mut loaded, render_target);
     let expectations = std::mem::take(&mut loaded.expectations);
     let loaded = loaded;
 
@@ -388,9 +415,34 @@ pub fn build_file<'a>(
         total_time,
         interns,
         expectations,
+        inputs,
     })
 }
 
+/// Programs can only send the value of a `dbg` to `roc run` on 64-bit Linux; see
+/// `LlvmBackendMode::BinaryDev`, and the `dbg_*` builtins that the dev backend calls.
+fn dbg_ignored_reason(target: &Triple, opt_level: OptLevel) -> Option<DbgIgnoredReason> {
+    use target_lexicon::{OperatingSystem, PointerWidth};
+
+    let supported_target = target.operating_system == OperatingSystem::Linux
+        && target.pointer_width() == Ok(PointerWidth::U64);
+
+    match opt_level {
+        OptLevel::Optimize => Some(DbgIgnoredReason::Optimize),
+        _ if !supported_target => Some(DbgIgnoredReason::Unsupported),
+        OptLevel::Normal | OptLevel::Size | OptLevel::Development => None,
+    }
+}
+
+/// The files that `spawn_rebuild_thread` builds the platform's host from. A prebuilt host is not
+/// rebuilt, so its sources don't matter.
+fn host_sources(host_input_path: &Path, prebuilt: bool) -> Vec<PathBuf> {
+    match HostKind::find(host_input_path) {
+        Some(kind) if !prebuilt => kind.sources(host_input_path),
+        _ => Vec::new(),
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn spawn_rebuild_thread(
     opt_level: OptLevel,
@@ -462,7 +514,9 @@ pub fn check_file(
     roc_file_path: PathBuf,
     emit_timings: bool,
     threading: Threading,
-) -> Result<(program::Problems, Duration), LoadingProblem> {
+    render_target: RenderTarget,
+    cache_dir: Option<PathBuf>,
+) -> Result<(program::Problems, Duration, Vec<PathBuf>), LoadingProblem> {
     let compilation_start = Instant::now();
 
     // only used for generating errors. We don't do code generation, so hardcoding should be fine
@@ -474,10 +528,10 @@ pub fn check_file(
 
     let load_config = LoadConfig {
         target_info,
-        // TODO: expose this from CLI?
-        render: RenderTarget::ColorTerminal,
+        render: render_target,
         threading,
         exec_mode: ExecutionMode::Check,
+        cache_dir,
     };
     let mut loaded =
         roc_load::load_and_typecheck(arena, roc_file_path, subs_by_module, load_config)?;
@@ -525,8 +579,98 @@ pub fn check_file(
         println!("Finished checking in {} ms\n", compilation_end.as_millis(),);
     }
 
+    let sources = loaded
+        .sources
+        .values()
+        .map(|(path, _)| path.clone())
+        .collect();
+
     Ok((
-        program::report_problems_typechecked(&mut loaded),
+        program::report_problems_typechecked(&mut loaded, render_target),
         compilation_end,
+        sources,
     ))
 }
+
+#[cfg(test)]
+mod test {
+    use super::host_sources;
+    use std::path::{Path, PathBuf};
+
+    fn create(dir: &Path, files: &[&str]) {
+        for file in files {
+            let path = dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+    }
+
+    fn paths(dir: &Path, files: &[&str]) -> Vec<PathBuf> {
+        files.iter().map(|file| dir.join(file)).collect()
+    }
+
+    #[test]
+    fn zig_host_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        create(
+            dir.path(),
+            &[
+                "main.roc",
+                "host.zig",
+                "glue.zig",
+                "host.c",
+                "README.md",
+                "zig-cache/o/cached.zig",
+            ],
+        );
+
+        let sources = host_sources(&dir.path().join("host.o"), false);
+
+        assert_eq!(sources, paths(dir.path(), &["glue.zig", "host.zig"]));
+    }
+
+    #[test]
+    fn cargo_host_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        create(
+            dir.path(),
+            &[
+                "main.roc",
+                "Cargo.toml",
+                "host.c",
+                "src/lib.rs",
+                "src/glue/mod.rs",
+                "src/notes.txt",
+                "target/debug/build/out.rs",
+            ],
+        );
+
+        let sources = host_sources(&dir.path().join("host.o"), false);
+
+        assert_eq!(
+            sources,
+            paths(
+                dir.path(),
+                &["Cargo.toml", "host.c", "src/glue/mod.rs", "src/lib.rs"]
+            )
+        );
+    }
+
+    #[test]
+    fn c_host_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        create(dir.path(), &["main.roc", "host.c", "roc.h", "other.c"]);
+
+        let sources = host_sources(&dir.path().join("host.o"), false);
+
+        assert_eq!(sources, paths(dir.path(), &["host.c", "roc.h"]));
+    }
+
+    #[test]
+    fn prebuilt_host_has_no_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        create(dir.path(), &["main.roc", "host.zig"]);
+
+        assert!(host_sources(&dir.path().join("host.o"), true).is_empty());
+    }
+}
@@ -0,0 +1,115 @@
+use std::path::{Path, PathBuf};
+
+use bumpalo::Bump;
+use roc_can::symbol_index::Occurrence;
+use roc_load::{ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, Threading};
+use roc_region::all::{LineColumn, LineInfo};
+use roc_reporting::report::RenderTarget;
+use roc_target::TargetInfo;
+
+/// Prints where the value at `line` and `column` (both 1-based, like in error reports) of
+/// `roc_file_path` is defined, followed by everywhere it is used, one `path:line:column` per line.
+pub fn find_refs(
+    arena: &Bump,
+    roc_file_path: PathBuf,
+    line: u32,
+    column: u32,
+    threading: Threading,
+) -> Result<i32, LoadingProblem> {
+    let load_config = LoadConfig {
+        // we only need types, so any target will do
+        target_info: TargetInfo::default_x86_64(),
+        render: RenderTarget::ColorTerminal,
+        threading,
+        exec_mode: ExecutionMode::Check,
+        cache_dir: None,
+    };
+    let loaded = roc_load::load_and_typecheck(
+        arena,
+        roc_file_path.clone(),
+        Default::default(),
+        load_config,
+    )?;
+
+    let home = loaded.module_id;
+    let index = &loaded.symbol_index;
+
+    let symbol = {
+        let (_, src) = &loaded.sources[&home];
+        let line_info = LineInfo::new(src);
+        let end = line_info.convert_offset(src.len() as u32);
+
+        let line_column = LineColumn {
+            line: line.saturating_sub(1),
+            column: column.saturating_sub(1),
+        };
+
+        if line == 0 || line_column.line > end.line {
+            None
+        } else {
+            index.symbol_at(home, line_info.convert_line_column(line_column))
+        }
+    };
+
+    let symbol = match symbol {
+        Some(symbol) => symbol,
+        None => {
+            eprintln!(
+                "Nothing is defined or used at {}:{}:{}",
+                roc_file_path.display(),
+                line,
+                column
+            );
+
+            return Ok(1);
+        }
+    };
+
+    if let Some(definition) = index.definition(symbol) {
+        println!("{} (definition)", describe(&loaded, definition));
+    }
+
+    let mut references: Vec<_> = index
+        .references(symbol)
+        .iter()
+        .map(|occurrence| {
+            let (path, _) = &loaded.sources[&occurrence.module_id];
+
+            (path.as_path(), occurrence.region, *occurrence)
+        })
+        .collect();
+
+    references.sort_by(|(path1, region1, _), (path2, region2, _)| {
+        path1.cmp(path2).then(region1.cmp(region2))
+    });
+
+    for (_, _, occurrence) in references {
+        println!("{}", describe(&loaded, occurrence));
+    }
+
+    Ok(0)
+}
+
+fn describe(loaded: &LoadedModule, occurrence: Occurrence) -> String {
+    let (path, src) = &loaded.sources[&occurrence.module_id];
+    let start = LineInfo::new(src).convert_pos(occurrence.region.start());
+
+    format!(
+        "{}:{}:{}",
+        display_path(path),
+        start.line + 1,
+        start.column + 1
+    )
+}
+
+fn display_path(path: &Path) -> String {
+    // prefer paths relative to where `roc` was run, like the ones users type in
+    let relative = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok().map(Path::to_path_buf));
+
+    relative
+        .unwrap_or_else(|| path.to_path_buf())
+        .display()
+        .to_string()
+}
@@ -5,11 +5,16 @@ use build::BuiltFile;
 use bumpalo::Bump;
 use clap::{Arg, ArgMatches, Command, ValueSource};
 use roc_build::link::{LinkType, LinkingStrategy};
+use roc_build::program::Problems;
 use roc_collections::VecMap;
 use roc_error_macros::{internal_error, user_error};
-use roc_load::{Expectations, LoadingProblem, Threading};
-use roc_module::symbol::{Interns, ModuleId};
+use roc_glue::Lang;
+use roc_load::{Expectations, LoadedModule, LoadingProblem, MonomorphizedModule, Threading};
+use roc_module::ident::ModuleName;
+use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_mono::ir::OptLevel;
+use roc_region::all::LineInfo;
+use roc_reporting::report::RenderTarget;
 use std::env;
 use std::ffi::{CString, OsStr};
 use std::io;
@@ -17,6 +22,7 @@ use std::mem::ManuallyDrop;
 use std::os::raw::{c_char, c_int};
 use std::path::{Path, PathBuf};
 use std::process;
+use std::time::Duration;
 use target_lexicon::BinaryFormat;
 use target_lexicon::{
     Architecture, Environment, OperatingSystem, Triple, Vendor, X86_32Architecture,
@@ -25,10 +31,14 @@ use target_lexicon::{
 use tempfile::TempDir;
 
 pub mod build;
+mod find_refs;
 mod format;
+pub mod watch;
+pub use find_refs::find_refs;
 pub use format::format;
 
 use crate::build::{BuildFileError, BuildOrdering};
+use crate::watch::Run;
 
 const DEFAULT_ROC_FILENAME: &str = "main.roc";
 
@@ -43,6 +53,8 @@ pub const CMD_VERSION: &str = "version";
 pub const CMD_FORMAT: &str = "format";
 pub const CMD_TEST: &str = "test";
 pub const CMD_GLUE: &str = "glue";
+pub const CMD_LSP: &str = "lsp";
+pub const CMD_FIND_REFS: &str = "find-refs";
 
 pub const FLAG_DEBUG: &str = "debug";
 pub const FLAG_DEV: &str = "dev";
@@ -57,11 +69,19 @@ pub const FLAG_LINKER: &str = "linker";
 pub const FLAG_PREBUILT: &str = "prebuilt-platform";
 pub const FLAG_CHECK: &str = "check";
 pub const FLAG_WASM_STACK_SIZE_KB: &str = "wasm-stack-size-kb";
+pub const FLAG_FORMAT: &str = "format";
+pub const FLAG_NO_CACHE: &str = "no-cache";
+pub const FLAG_WATCH: &str = "watch";
+pub const FLAG_FILTER: &str = "filter";
+pub const FLAG_LIST: &str = "list";
+pub const FLAG_LANG: &str = "lang";
 pub const ROC_FILE: &str = "ROC_FILE";
 pub const ROC_DIR: &str = "ROC_DIR";
 pub const GLUE_FILE: &str = "GLUE_FILE";
 pub const DIRECTORY_OR_FILES: &str = "DIRECTORY_OR_FILES";
 pub const ARGS_FOR_APP: &str = "ARGS_FOR_APP";
+pub const LINE: &str = "LINE";
+pub const COLUMN: &str = "COLUMN";
 
 const VERSION: &str = include_str!("../../../version.txt");
 
@@ -90,7 +110,7 @@ pub fn build_app<'a>() -> Command<'a> {
 
     let flag_debug = Arg::new(FLAG_DEBUG)
         .long(FLAG_DEBUG)
-        .help("Store LLVM debug information in the generated program")
+        .help("Store debug information that points to the Roc source in the generated program")
         .required(false);
 
     let flag_time = Arg::new(FLAG_TIME)
@@ -117,6 +137,25 @@ pub fn build_app<'a>() -> Command<'a> {
         .validator(|s| s.parse::<u32>())
         .required(false);
 
+    let flag_format = Arg::new(FLAG_FORMAT)
+        .long(FLAG_FORMAT)
+        .help("Choose how to print the problems that were found\n(`json` prints each problem as a JSON object on its own line, for other tools to read.)")
+        .possible_values(["human", "json"])
+        .default_value("human")
+        .required(false);
+
+    let flag_no_cache = Arg::new(FLAG_NO_CACHE)
+        .long(FLAG_NO_CACHE)
+        .help("Solve the types of every interface module from scratch, instead of reusing the ones cached by earlier builds\n(Only solving is cached: every module is still parsed and canonicalized.)")
+        .required(false);
+
+    let flag_watch = Arg::new(FLAG_WATCH)
+        .long(FLAG_WATCH)
+        .help(
+            "Run again whenever the .roc file, a module it imports, or the platform's host changes",
+        )
+        .required(false);
+
     let roc_file_to_run = Arg::new(ROC_FILE)
         .help("The .roc file of an app to run")
         .allow_invalid_utf8(true)
@@ -144,6 +183,8 @@ pub fn build_app<'a>() -> Command<'a> {
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
+            .arg(flag_format.clone())
+            .arg(flag_no_cache.clone())
             .arg(flag_wasm_stack_size_kb.clone())
             .arg(
                 Arg::new(FLAG_TARGET)
@@ -183,6 +224,22 @@ pub fn build_app<'a>() -> Command<'a> {
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
+            .arg(flag_format.clone())
+            .arg(flag_no_cache.clone())
+            .arg(flag_watch.clone())
+            .arg(
+                Arg::new(FLAG_FILTER)
+                    .long(FLAG_FILTER)
+                    .help("Only run the expectations in the module of this name, or whose name contains this\n(An `expect` is named after the top-level def above it, like `Main.parse`.)")
+                    .takes_value(true)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_LIST)
+                    .long(FLAG_LIST)
+                    .help("List the expectations that would run, without running them")
+                    .required(false),
+            )
             .arg(
                 Arg::new(ROC_FILE)
                     .help("The .roc file for the main module")
@@ -205,6 +262,8 @@ pub fn build_app<'a>() -> Command<'a> {
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
+            .arg(flag_format.clone())
+            .arg(flag_no_cache.clone())
             .arg(roc_file_to_run.clone())
             .arg(args_for_app.clone())
         )
@@ -218,6 +277,9 @@ pub fn build_app<'a>() -> Command<'a> {
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
+            .arg(flag_format.clone())
+            .arg(flag_no_cache.clone())
+            .arg(flag_watch.clone())
             .arg(roc_file_to_run.clone())
             .arg(args_for_app.clone())
         )
@@ -242,6 +304,9 @@ pub fn build_app<'a>() -> Command<'a> {
             .about("Check the code for problems, but don’t build or run it")
             .arg(flag_time.clone())
             .arg(flag_max_threads.clone())
+            .arg(flag_format.clone())
+            .arg(flag_no_cache.clone())
+            .arg(flag_watch.clone())
             .arg(
                 Arg::new(ROC_FILE)
                     .help("The .roc file of an app to check")
@@ -270,10 +335,43 @@ pub fn build_app<'a>() -> Command<'a> {
             )
             .arg(
                 Arg::new(GLUE_FILE)
-                    .help("The filename for the generated glue code\n(Currently, this must be a .rs file because only Rust glue generation is supported so far.)")
+                    .help("The filename for the generated glue code\n(The language is inferred from the extension: .rs for Rust, .h for C, or .zig for Zig.)")
                     .allow_invalid_utf8(true)
                     .required(true)
             )
+            .arg(
+                Arg::new(FLAG_LANG)
+                    .long(FLAG_LANG)
+                    .help("Generate glue code in this language, regardless of the glue file's extension")
+                    .takes_value(true)
+                    .possible_values(Lang::OPTIONS)
+                    .required(false)
+            )
+        )
+        .subcommand(Command::new(CMD_FIND_REFS)
+            .about("Print where the value at a position is defined, and everywhere it is used")
+            .arg(
+                Arg::new(ROC_FILE)
+                    .help("The .roc file the position is in")
+                    .allow_invalid_utf8(true)
+                    .required(true)
+            )
+            .arg(
+                Arg::new(LINE)
+                    .help("The line of the position, starting from 1")
+                    .validator(|s| s.parse::<u32>())
+                    .required(true)
+            )
+            .arg(
+                Arg::new(COLUMN)
+                    .help("The column of the position, starting from 1")
+                    .validator(|s| s.parse::<u32>())
+                    .required(true)
+            )
+        )
+        .subcommand(Command::new(CMD_LSP)
+            .about("Start a language server, spoken over stdin and stdout")
+            .arg(flag_no_cache.clone())
         )
         .trailing_var_arg(true)
         .arg(flag_optimize)
@@ -284,6 +382,9 @@ pub fn build_app<'a>() -> Command<'a> {
         .arg(flag_time)
         .arg(flag_linker)
         .arg(flag_prebuilt)
+        .arg(flag_format)
+        .arg(flag_no_cache)
+        .arg(flag_watch)
         .arg(roc_file_to_run.required(false))
         .arg(args_for_app);
 
@@ -315,6 +416,48 @@ pub enum FormatMode {
     CheckOnly,
 }
 
+/// How the problems the compiler finds should be printed, according to `--format`
+pub fn render_target(matches: &ArgMatches) -> RenderTarget {
+    match matches.value_of(FLAG_FORMAT) {
+        Some("json") => RenderTarget::Json,
+        _ => RenderTarget::ColorTerminal,
+    }
+}
+
+/// Where to cache solved modules between builds, unless `--no-cache` was given
+pub fn cache_dir(matches: &ArgMatches) -> Option<PathBuf> {
+    if matches.is_present(FLAG_NO_CACHE) {
+        None
+    } else {
+        roc_load::default_cache_dir()
+    }
+}
+
+/// Print a summary that is meant for people, like the number of problems found.
+/// With `--format=json`, stdout only has the reports, so summaries go to stderr instead.
+pub fn print_summary(render_target: RenderTarget, summary: std::fmt::Arguments) {
+    match render_target {
+        RenderTarget::Json => eprintln!("{}", summary),
+        RenderTarget::ColorTerminal | RenderTarget::Generic => println!("{}", summary),
+    }
+}
+
+/// The text in an ANSI color, like 32 for green. Only a color terminal gets the escape codes.
+fn colored(render_target: RenderTarget, color: u8, text: impl std::fmt::Display) -> String {
+    match render_target {
+        RenderTarget::ColorTerminal => format!("\x1B[{}m{}\x1B[39m", color, text),
+        RenderTarget::Json | RenderTarget::Generic => text.to_string(),
+    }
+}
+
+/// Print a report that was already rendered while loading, e.g. for a syntax error
+pub fn print_formatted_report(render_target: RenderTarget, report: &str) {
+    match render_target {
+        RenderTarget::Json => println!("{}", report),
+        RenderTarget::ColorTerminal | RenderTarget::Generic => print!("{}", report),
+    }
+}
+
 #[cfg(windows)]
 pub fn test(_matches: &ArgMatches, _triple: Triple) -> io::Result<i32> {
     todo!("running tests does not work on windows right now")
@@ -323,13 +466,12 @@ pub fn test(_matches: &ArgMatches, _triple: Triple) -> io::Result<i32> {
 #[cfg(not(windows))]
 pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
     use roc_gen_llvm::llvm::build::LlvmBackendMode;
-    use roc_load::{ExecutionMode, LoadConfig};
+    use roc_load::{ExecutionMode, LoadConfig, LoadMonomorphizedError};
     use roc_target::TargetInfo;
     use std::time::Instant;
 
-    let start_time = Instant::now();
-    let arena = Bump::new();
     let filename = matches.value_of_os(ROC_FILE).unwrap();
+    let render_target = render_target(matches);
     let opt_level = match (
         matches.is_present(FLAG_OPTIMIZE),
         matches.is_present(FLAG_OPT_SIZE),
@@ -372,83 +514,274 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
         process::exit(1);
     }
 
-    let arena = &arena;
     let target = &triple;
-    let opt_level = opt_level;
     let target_info = TargetInfo::from(target);
 
-    // Step 1: compile the app and generate the .o file
-    let subs_by_module = Default::default();
+    let run_tests = || -> io::Result<Run> {
+        let start_time = Instant::now();
+        let arena = Bump::new();
+        let arena = &arena;
 
-    let load_config = LoadConfig {
-        target_info,
-        // TODO: expose this from CLI?
-        render: roc_reporting::report::RenderTarget::ColorTerminal,
-        threading,
-        exec_mode: ExecutionMode::Test,
-    };
-    let loaded =
-        roc_load::load_and_monomorphize(arena, path.to_path_buf(), subs_by_module, load_config)
+        // Step 1: compile the app and generate the .o file
+        let subs_by_module = Default::default();
+
+        let load_config = LoadConfig {
+            target_info,
+            render: render_target,
+            threading,
+            exec_mode: ExecutionMode::Test,
+            cache_dir: cache_dir(matches),
+        };
+        let load_result =
+            roc_load::load_and_monomorphize(arena, path.to_path_buf(), subs_by_module, load_config);
+
+        let mut loaded = match load_result {
+            Ok(loaded) => loaded,
+            Err(LoadMonomorphizedError::LoadingProblem(LoadingProblem::FormattedReport(
+                report,
+            ))) => {
+                print_formatted_report(render_target, &report);
+
+                return Ok(Run::finished(1, Vec::new()));
+            }
+            Err(other) => {
+                panic!("loading the tests failed with error:\n{:?}", other);
+            }
+        };
+
+        let sources = loaded
+            .sources
+            .values()
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        let labels = expect_labels(&loaded);
+        let filter = matches.value_of(FLAG_FILTER);
+        let is_selected = |symbol: &Symbol| match filter {
+            None => true,
+            Some(filter) => labels
+                .get(symbol)
+                .map_or(false, |label| label.matches(filter)),
+        };
+
+        if matches.is_present(FLAG_LIST) {
+            let mut selected: Vec<&ExpectLabel> = labels
+                .iter()
+                .filter(|(symbol, _)| is_selected(symbol))
+                .map(|(_, label)| label)
+                .collect();
+            selected.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+
+            for label in selected.iter() {
+                println!("{}", label);
+            }
+
+            print_summary(
+                render_target,
+                format_args!(
+                    "\n{} {}",
+                    selected.len(),
+                    if selected.len() == 1 {
+                        "expectation"
+                    } else {
+                        "expectations"
+                    }
+                ),
+            );
+
+            return Ok(Run::finished(0, sources));
+        }
+
+        let mut expectations = std::mem::take(&mut loaded.expectations);
+        let loaded = loaded;
+
+        let interns = loaded.interns.clone();
+
+        let (lib, mut expects, layout_interner) =
+            roc_repl_expect::run::expect_mono_module_to_dylib(
+                arena,
+                target.clone(),
+                loaded,
+                opt_level,
+                LlvmBackendMode::CliTest,
+            )
             .unwrap();
 
-    let mut loaded = loaded;
-    let mut expectations = std::mem::take(&mut loaded.expectations);
-    let loaded = loaded;
+        expects.pure.retain(|expect| is_selected(&expect.symbol));
+        expects.fx.retain(|expect| is_selected(&expect.symbol));
 
-    let interns = loaded.interns.clone();
+        let arena = &bumpalo::Bump::new();
+        let interns = arena.alloc(interns);
 
-    let (lib, expects, layout_interner) = roc_repl_expect::run::expect_mono_module_to_dylib(
-        arena,
-        target.clone(),
-        loaded,
-        opt_level,
-        LlvmBackendMode::CliTest,
-    )
-    .unwrap();
+        let mut writer = std::io::stdout();
 
-    let arena = &bumpalo::Bump::new();
-    let interns = arena.alloc(interns);
+        let outcomes = roc_repl_expect::run::run_expects(
+            &mut writer,
+            render_target,
+            arena,
+            interns,
+            &layout_interner.into_global(),
+            &lib,
+            &mut expectations,
+            expects,
+        )
+        .unwrap();
 
-    let mut writer = std::io::stdout();
+        let total_time = start_time.elapsed();
+
+        let failed = outcomes.iter().filter(|outcome| !outcome.passed).count();
+        let passed = outcomes.len() - failed;
+
+        if matches.is_present(FLAG_TIME) {
+            for outcome in outcomes.iter() {
+                let (color, verdict) = if outcome.passed {
+                    (32, "passed") // green
+                } else {
+                    (31, "failed") // red
+                };
+
+                print_summary(
+                    render_target,
+                    format_args!(
+                        "{:9.3} ms   {}   {}",
+                        outcome.duration.as_secs_f64() * 1000.0,
+                        colored(render_target, color, verdict),
+                        labels.get(&outcome.symbol).unwrap(),
+                    ),
+                );
+            }
+        }
 
-    let (failed, passed) = roc_repl_expect::run::run_expects(
-        &mut writer,
-        roc_reporting::report::RenderTarget::ColorTerminal,
-        arena,
-        interns,
-        &layout_interner.into_global(),
-        &lib,
-        &mut expectations,
-        expects,
-    )
-    .unwrap();
-
-    let total_time = start_time.elapsed();
-
-    if failed == 0 && passed == 0 {
-        // TODO print this in a more nicely formatted way!
-        println!("No expectations were found.");
-
-        // If no tests ran, treat that as an error. This is perhaps
-        // briefly annoying at the very beginning of a project when
-        // you actually have zero tests, but it can save you from
-        // having a change to your CI script accidentally stop
-        // running tests altogether!
-        Ok(2)
+        let exit_code = if failed == 0 && passed == 0 {
+            // TODO print this in a more nicely formatted way!
+            match filter {
+                Some(filter) => print_summary(
+                    render_target,
+                    format_args!("No expectations matched `--{} {}`.", FLAG_FILTER, filter),
+                ),
+                None => print_summary(render_target, format_args!("No expectations were found.")),
+            }
+
+            // If no tests ran, treat that as an error. This is perhaps
+            // briefly annoying at the very beginning of a project when
+            // you actually have zero tests, but it can save you from
+            // having a change to your CI script accidentally stop
+            // running tests altogether!
+            2
+        } else {
+            let failed_color = if failed == 0 {
+                32 // green
+            } else {
+                31 // red
+            };
+
+            print_summary(
+                render_target,
+                format_args!(
+                    "\n{} failed and {} passed in {} ms.\n",
+                    colored(render_target, failed_color, failed),
+                    colored(render_target, 32, passed),
+                    total_time.as_millis(),
+                ),
+            );
+
+            (failed > 0) as i32
+        };
+
+        Ok(Run::finished(exit_code, sources))
+    };
+
+    if matches.is_present(FLAG_WATCH) {
+        watch::watch(render_target, path, run_tests)
     } else {
-        let failed_color = if failed == 0 {
-            32 // green
+        run_tests().map(|run| run.exit_code)
+    }
+}
+
+/// How `roc test` refers to a top-level `expect`
+#[cfg_attr(windows, allow(dead_code))]
+struct ExpectLabel {
+    module: String,
+    /// The top-level def written above the `expect`, if there is one
+    def: Option<String>,
+    path: PathBuf,
+    /// starting from 1
+    line: u32,
+}
+
+#[cfg_attr(windows, allow(dead_code))]
+impl ExpectLabel {
+    /// Whether `roc test --filter` selects this expectation: either all of its module is,
+    /// or part of its name like `Main.parse` is given.
+    fn matches(&self, filter: &str) -> bool {
+        match &self.def {
+            _ if self.module == filter => true,
+            Some(def) => format!("{}.{}", self.module, def).contains(filter),
+            None => false,
+        }
+    }
+}
+
+impl std::fmt::Display for ExpectLabel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.def {
+            Some(def) => write!(f, "{}.{}", self.module, def)?,
+            None => write!(f, "{}", self.module)?,
+        }
+
+        // If possible, report the path relative to the current dir.
+        let path = env::current_dir()
+            .ok()
+            .and_then(|dir| self.path.strip_prefix(dir).ok())
+            .unwrap_or(&self.path);
+
+        write!(f, " ({}:{})", path.display(), self.line)
+    }
+}
+
+#[cfg_attr(windows, allow(dead_code))]
+fn expect_labels(loaded: &MonomorphizedModule) -> VecMap<Symbol, ExpectLabel> {
+    let toplevel_expects = &loaded.toplevel_expects;
+    let all_expects = toplevel_expects
+        .pure
+        .iter()
+        .chain(toplevel_expects.fx.iter());
+
+    let mut labels = VecMap::with_capacity(toplevel_expects.pure.len() + toplevel_expects.fx.len());
+
+    for (symbol, region) in all_expects {
+        let module_id = symbol.module_id();
+        let (path, src) = &loaded.sources[&module_id];
+
+        let module_name = loaded.interns.module_name(module_id);
+        let module = if module_name.as_str() == ModuleName::APP {
+            // the app module has no name of its own, so go by its file name instead
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default()
         } else {
-            31 // red
+            module_name.to_string()
         };
 
-        println!(
-            "\n\x1B[{failed_color}m{failed}\x1B[39m failed and \x1B[32m{passed}\x1B[39m passed in {} ms.\n",
-            total_time.as_millis(),
-        );
+        let def = loaded
+            .expectations
+            .get(&module_id)
+            .and_then(|expectations| expectations.toplevel_defs.get(symbol))
+            .map(|def| def.as_str(&loaded.interns).to_string());
 
-        Ok((failed > 0) as i32)
+        let line = LineInfo::new(src).convert_pos(region.start()).line + 1;
+
+        let label = ExpectLabel {
+            module,
+            def,
+            path: path.clone(),
+            line,
+        };
+
+        labels.insert(*symbol, label);
     }
+
+    labels
 }
 
 pub fn build(
@@ -475,6 +808,7 @@ pub fn build(
     };
     let emit_debug_info = matches.is_present(FLAG_DEBUG);
     let emit_timings = matches.is_present(FLAG_TIME);
+    let render_target = render_target(matches);
 
     let threading = match matches
         .value_of(FLAG_MAX_THREADS)
@@ -534,6 +868,73 @@ pub fn build(
         .and_then(|s| s.parse::<u32>().ok())
         .map(|x| x * 1024);
 
+    if config == BuildAndRunIfNoErrors && matches.is_present(FLAG_WATCH) {
+        return watch::watch(render_target, path, || {
+            let arena = Bump::new();
+            let result = build_file(
+                &arena,
+                &triple,
+                path.to_path_buf(),
+                opt_level,
+                emit_debug_info,
+                emit_timings,
+                link_type,
+                linking_strategy,
+                prebuilt,
+                threading,
+                wasm_dev_stack_bytes,
+                BuildOrdering::BuildIfChecks,
+                render_target,
+                cache_dir(matches),
+            );
+
+            match result {
+                Ok(BuiltFile {
+                    binary_path,
+                    problems,
+                    total_time,
+                    inputs,
+                    ..
+                }) => {
+                    print_warnings_before_running(render_target, problems, total_time);
+
+                    // Unlike `roc dev` without `--watch`, this has to stay around to rebuild the
+                    // program, so it runs as a child process rather than replacing this one.
+                    let args = matches.values_of_os(ARGS_FOR_APP).unwrap_or_default();
+                    let program = process::Command::new(&binary_path).args(args).spawn()?;
+
+                    Ok(Run {
+                        exit_code: 0,
+                        files: inputs,
+                        program: Some(program),
+                    })
+                }
+                Err(BuildFileError::ErrorModule {
+                    mut module,
+                    total_time,
+                }) => {
+                    let files = module
+                        .sources
+                        .values()
+                        .map(|(path, _)| path.clone())
+                        .collect();
+                    let exit_code =
+                        report_error_module(&mut module, total_time, filename, render_target);
+
+                    Ok(Run::finished(exit_code, files))
+                }
+                Err(BuildFileError::LoadingProblem(LoadingProblem::FormattedReport(report))) => {
+                    print_formatted_report(render_target, &report);
+
+                    Ok(Run::finished(1, Vec::new()))
+                }
+                Err(other) => {
+                    panic!("build_file failed with error:\n{:?}", other);
+                }
+            }
+        });
+    }
+
     let build_ordering = match config {
         BuildAndRunIfNoErrors => BuildOrdering::BuildIfChecks,
         _ => BuildOrdering::AlwaysBuild,
@@ -551,6 +952,8 @@ pub fn build(
         threading,
         wasm_dev_stack_bytes,
         build_ordering,
+        render_target,
+        cache_dir(matches),
     );
 
     match res_binary_path {
@@ -560,6 +963,7 @@ pub fn build(
             total_time,
             expectations,
             interns,
+            ..
         }) => {
             match config {
                 BuildOnly => {
@@ -572,41 +976,10 @@ pub fn build(
                     // since the process is about to exit anyway.
                     std::mem::forget(arena);
 
-                    println!(
-                        "\x1B[{}m{}\x1B[39m {} and \x1B[{}m{}\x1B[39m {} found in {} ms while successfully building:\n\n    {}",
-                        if problems.errors == 0 {
-                            32 // green
-                        } else {
-                            33 // yellow
-                        },
-                        problems.errors,
-                        if problems.errors == 1 {
-                            "error"
-                        } else {
-                            "errors"
-                        },
-                        if problems.warnings == 0 {
-                            32 // green
-                        } else {
-                            33 // yellow
-                        },
-                        problems.warnings,
-                        if problems.warnings == 1 {
-                            "warning"
-                        } else {
-                            "warnings"
-                        },
-                        total_time.as_millis(),
-                        generated_filename.to_str().unwrap()
-                    );
-
-                    // Return a nonzero exit code if there were problems
-                    Ok(problems.exit_code())
-                }
-                BuildAndRun => {
-                    if problems.errors > 0 || problems.warnings > 0 {
-                        println!(
-                            "\x1B[{}m{}\x1B[39m {} and \x1B[{}m{}\x1B[39m {} found in {} ms.\n\nRunning program anyway…\n\n\x1B[36m{}\x1B[39m",
+                    print_summary(
+                        render_target,
+                        format_args!(
+                            "\x1B[{}m{}\x1B[39m {} and \x1B[{}m{}\x1B[39m {} found in {} ms while successfully building:\n\n    {}",
                             if problems.errors == 0 {
                                 32 // green
                             } else {
@@ -630,7 +1003,44 @@ pub fn build(
                                 "warnings"
                             },
                             total_time.as_millis(),
-                            "─".repeat(80)
+                            generated_filename.to_str().unwrap()
+                        ),
+                    );
+
+                    // Return a nonzero exit code if there were problems
+                    Ok(problems.exit_code())
+                }
+                BuildAndRun => {
+                    if problems.errors > 0 || problems.warnings > 0 {
+                        print_summary(
+                            render_target,
+                            format_args!(
+                                "\x1B[{}m{}\x1B[39m {} and \x1B[{}m{}\x1B[39m {} found in {} ms.\n\nRunning program anyway…\n\n\x1B[36m{}\x1B[39m",
+                                if problems.errors == 0 {
+                                    32 // green
+                                } else {
+                                    33 // yellow
+                                },
+                                problems.errors,
+                                if problems.errors == 1 {
+                                    "error"
+                                } else {
+                                    "errors"
+                                },
+                                if problems.warnings == 0 {
+                                    32 // green
+                                } else {
+                                    33 // yellow
+                                },
+                                problems.warnings,
+                                if problems.warnings == 1 {
+                                    "warning"
+                                } else {
+                                    "warnings"
+                                },
+                                total_time.as_millis(),
+                                "─".repeat(80)
+                            ),
                         );
                     }
 
@@ -655,19 +1065,7 @@ pub fn build(
                         problems.errors == 0,
                         "if there are errors, they should have been returned as an error variant"
                     );
-                    if problems.warnings > 0 {
-                        println!(
-                            "\x1B[32m0\x1B[39m errors and \x1B[33m{}\x1B[39m {} found in {} ms.\n\nRunning program…\n\n\x1B[36m{}\x1B[39m",
-                            problems.warnings,
-                            if problems.warnings == 1 {
-                                "warning"
-                            } else {
-                                "warnings"
-                            },
-                            total_time.as_millis(),
-                            "─".repeat(80)
-                        );
-                    }
+                    print_warnings_before_running(render_target, problems, total_time);
 
                     let args = matches.values_of_os(ARGS_FOR_APP).unwrap_or_default();
 
@@ -681,29 +1079,82 @@ pub fn build(
         Err(BuildFileError::ErrorModule {
             mut module,
             total_time,
-        }) => {
-            debug_assert!(module.total_problems() > 0);
+        }) => Ok(report_error_module(
+            &mut module,
+            total_time,
+            filename,
+            render_target,
+        )),
+        Err(BuildFileError::LoadingProblem(LoadingProblem::FormattedReport(report))) => {
+            print_formatted_report(render_target, &report);
 
-            let problems = roc_build::program::report_problems_typechecked(&mut module);
+            Ok(1)
+        }
+        Err(other) => {
+            panic!("build_file failed with error:\n{:?}", other);
+        }
+    }
+}
 
-            let mut output = format!(
-                "\x1B[{}m{}\x1B[39m {} and \x1B[{}m{}\x1B[39m {} found in {} ms.\n\nYou can run the program anyway with \x1B[32mroc run",
-                if problems.errors == 0 {
-                    32 // green
-                } else {
-                    33 // yellow
-                },
-                problems.errors,
-                if problems.errors == 1 {
-                    "error"
-                } else {
-                    "errors"
-                },
-                if problems.warnings == 0 {
-                    32 // green
-                } else {
-                    33 // yellow
-                },
+/// Reports the problems that stopped `roc dev` from running the program, and returns the exit code.
+fn report_error_module(
+    module: &mut LoadedModule,
+    total_time: Duration,
+    filename: &OsStr,
+    render_target: RenderTarget,
+) -> i32 {
+    debug_assert!(module.total_problems() > 0);
+
+    let problems = roc_build::program::report_problems_typechecked(module, render_target);
+
+    let mut output = format!(
+        "\x1B[{}m{}\x1B[39m {} and \x1B[{}m{}\x1B[39m {} found in {} ms.\n\nYou can run the program anyway with \x1B[32mroc run",
+        if problems.errors == 0 {
+            32 // green
+        } else {
+            33 // yellow
+        },
+        problems.errors,
+        if problems.errors == 1 {
+            "error"
+        } else {
+            "errors"
+        },
+        if problems.warnings == 0 {
+            32 // green
+        } else {
+            33 // yellow
+        },
+        problems.warnings,
+        if problems.warnings == 1 {
+            "warning"
+        } else {
+            "warnings"
+        },
+        total_time.as_millis(),
+    );
+    // If you're running "main.roc" then you can just do `roc run`
+    // to re-run the program.
+    if filename != DEFAULT_ROC_FILENAME {
+        output.push(' ');
+        output.push_str(&filename.to_string_lossy());
+    }
+
+    print_summary(render_target, format_args!("{}\x1B[39m", output));
+
+    problems.exit_code()
+}
+
+fn print_warnings_before_running(
+    render_target: RenderTarget,
+    problems: Problems,
+    total_time: Duration,
+) {
+    if problems.warnings > 0 {
+        print_summary(
+            render_target,
+            format_args!(
+                "\x1B[32m0\x1B[39m errors and \x1B[33m{}\x1B[39m {} found in {} ms.\n\nRunning program…\n\n\x1B[36m{}\x1B[39m",
                 problems.warnings,
                 if problems.warnings == 1 {
                     "warning"
@@ -711,26 +1162,9 @@ pub fn build(
                     "warnings"
                 },
                 total_time.as_millis(),
-            );
-            // If you're running "main.roc" then you can just do `roc run`
-            // to re-run the program.
-            if filename != DEFAULT_ROC_FILENAME {
-                output.push(' ');
-                output.push_str(&filename.to_string_lossy());
-            }
-
-            println!("{}\x1B[39m", output);
-
-            Ok(problems.exit_code())
-        }
-        Err(BuildFileError::LoadingProblem(LoadingProblem::FormattedReport(report))) => {
-            print!("{}", report);
-
-            Ok(1)
-        }
-        Err(other) => {
-            panic!("build_file failed with error:\n{:?}", other);
-        }
+                "─".repeat(80)
+            ),
+        );
     }
 }
 
@@ -854,17 +1288,23 @@ fn roc_run_native<I: IntoIterator<Item = S>, S: AsRef<OsStr>>(
             .chain([std::ptr::null()])
             .collect_in(&arena);
 
+        // only programs that were not optimized send their `dbg`s to us
+        let has_dbgs = expectations.values().any(|data| !data.dbgs.is_empty());
+
         match opt_level {
             OptLevel::Development => {
                 roc_run_native_debug(executable, &argv, &envp, expectations, interns)
             }
+            OptLevel::Normal | OptLevel::Size if has_dbgs => {
+                roc_run_native_debug(executable, &argv, &envp, expectations, interns)
+            }
             OptLevel::Normal | OptLevel::Size | OptLevel::Optimize => {
                 roc_run_native_fast(executable, &argv, &envp);
+
+                Ok(1)
             }
         }
     }
-
-    Ok(1)
 }
 
 unsafe fn roc_run_native_fast(
@@ -935,13 +1375,18 @@ impl ExecutableFile {
 // with Expect
 #[cfg(target_family = "unix")]
 unsafe fn roc_run_native_debug(
-    _executable: ExecutableFile,
-    _argv: &[*const c_char],
-    _envp: &[*const c_char],
-    _expectations: VecMap<ModuleId, Expectations>,
-    _interns: Interns,
-) {
-    todo!()
+    executable: ExecutableFile,
+    argv: &[*const c_char],
+    envp: &[*const c_char],
+    mut expectations: VecMap<ModuleId, Expectations>,
+    interns: Interns,
+) -> io::Result<i32> {
+    let arena = Bump::new();
+    let mut writer = std::io::stdout();
+
+    roc_repl_expect::run::run_with_dbgs(&mut writer, &arena, &interns, &mut expectations, || {
+        roc_run_native_fast(executable, argv, envp)
+    })
 }
 
 #[cfg(target_os = "linux")]
@@ -1,13 +1,16 @@
 use roc_build::link::LinkType;
 use roc_cli::build::check_file;
+use roc_cli::watch::{watch, Run};
 use roc_cli::{
-    build_app, format, test, BuildConfig, FormatMode, Target, CMD_BUILD, CMD_CHECK, CMD_DEV,
-    CMD_DOCS, CMD_EDIT, CMD_FORMAT, CMD_GLUE, CMD_REPL, CMD_RUN, CMD_TEST, CMD_VERSION,
-    DIRECTORY_OR_FILES, FLAG_CHECK, FLAG_LIB, FLAG_NO_LINK, FLAG_TARGET, FLAG_TIME, GLUE_FILE,
-    ROC_FILE,
+    build_app, cache_dir, find_refs, format, print_formatted_report, print_summary, render_target,
+    test, BuildConfig, FormatMode, Target, CMD_BUILD, CMD_CHECK, CMD_DEV, CMD_DOCS, CMD_EDIT,
+    CMD_FIND_REFS, CMD_FORMAT, CMD_GLUE, CMD_LSP, CMD_REPL, CMD_RUN, CMD_TEST, CMD_VERSION, COLUMN,
+    DIRECTORY_OR_FILES, FLAG_CHECK, FLAG_LANG, FLAG_LIB, FLAG_NO_LINK, FLAG_TARGET, FLAG_TIME,
+    FLAG_WATCH, GLUE_FILE, LINE, ROC_FILE,
 };
 use roc_docs::generate_docs_html;
 use roc_error_macros::user_error;
+use roc_glue::Lang;
 use roc_load::{LoadingProblem, Threading};
 use std::fs::{self, FileType};
 use std::io;
@@ -85,12 +88,18 @@ fn main() -> io::Result<()> {
             let input_path = Path::new(matches.value_of_os(ROC_FILE).unwrap());
             let output_path = Path::new(matches.value_of_os(GLUE_FILE).unwrap());
 
-            if Some("rs") == output_path.extension().and_then(OsStr::to_str) {
-                roc_glue::generate(input_path, output_path)
-            } else {
-                eprintln!("Currently, `roc glue` only supports generating Rust glue files (with the .rs extension). In the future, the plan is to decouple `roc glue` from any particular output format, by having it accept a second .roc file which gets executed as a plugin to generate glue code for any desired language. However, this has not yet been implemented, and for now only .rs is supported.");
+            let lang = match matches.value_of(FLAG_LANG) {
+                Some(lang) => lang.parse().ok(),
+                None => Lang::from_path(output_path),
+            };
 
-                Ok(1)
+            match lang {
+                Some(lang) => roc_glue::generate(input_path, output_path, lang),
+                None => {
+                    eprintln!("`roc glue` can't tell what language to generate from the glue file's extension. Use a .rs file for Rust glue, a .h file for C glue, or a .zig file for Zig glue, or pass --{} with one of: {}", FLAG_LANG, Lang::OPTIONS.join(", "));
+
+                    Ok(1)
+                }
             }
         }
         Some((CMD_BUILD, matches)) => {
@@ -114,9 +123,8 @@ fn main() -> io::Result<()> {
             )?)
         }
         Some((CMD_CHECK, matches)) => {
-            let arena = bumpalo::Bump::new();
-
             let emit_timings = matches.is_present(FLAG_TIME);
+            let render_target = render_target(matches);
             let filename = matches.value_of_os(ROC_FILE).unwrap();
             let roc_file_path = PathBuf::from(filename);
             let threading = match matches
@@ -129,46 +137,66 @@ fn main() -> io::Result<()> {
                 Some(n) => Threading::AtMost(n),
             };
 
-            match check_file(&arena, roc_file_path, emit_timings, threading) {
-                Ok((problems, total_time)) => {
-                    println!(
-                        "\x1B[{}m{}\x1B[39m {} and \x1B[{}m{}\x1B[39m {} found in {} ms.",
-                        if problems.errors == 0 {
-                            32 // green
-                        } else {
-                            33 // yellow
-                        },
-                        problems.errors,
-                        if problems.errors == 1 {
-                            "error"
-                        } else {
-                            "errors"
-                        },
-                        if problems.warnings == 0 {
-                            32 // green
-                        } else {
-                            33 // yellow
-                        },
-                        problems.warnings,
-                        if problems.warnings == 1 {
-                            "warning"
-                        } else {
-                            "warnings"
-                        },
-                        total_time.as_millis(),
-                    );
-
-                    Ok(problems.exit_code())
-                }
+            let check = || -> io::Result<Run> {
+                let arena = bumpalo::Bump::new();
+
+                match check_file(
+                    &arena,
+                    roc_file_path.clone(),
+                    emit_timings,
+                    threading,
+                    render_target,
+                    cache_dir(matches),
+                ) {
+                    Ok((problems, total_time, sources)) => {
+                        print_summary(
+                            render_target,
+                            format_args!(
+                                "\x1B[{}m{}\x1B[39m {} and \x1B[{}m{}\x1B[39m {} found in {} ms.",
+                                if problems.errors == 0 {
+                                    32 // green
+                                } else {
+                                    33 // yellow
+                                },
+                                problems.errors,
+                                if problems.errors == 1 {
+                                    "error"
+                                } else {
+                                    "errors"
+                                },
+                                if problems.warnings == 0 {
+                                    32 // green
+                                } else {
+                                    33 // yellow
+                                },
+                                problems.warnings,
+                                if problems.warnings == 1 {
+                                    "warning"
+                                } else {
+                                    "warnings"
+                                },
+                                total_time.as_millis(),
+                            ),
+                        );
+
+                        Ok(Run::finished(problems.exit_code(), sources))
+                    }
 
-                Err(LoadingProblem::FormattedReport(report)) => {
-                    print!("{}", report);
+                    Err(LoadingProblem::FormattedReport(report)) => {
+                        print_formatted_report(render_target, &report);
 
-                    Ok(1)
-                }
-                Err(other) => {
-                    panic!("build_file failed with error:\n{:?}", other);
+                        Ok(Run::finished(1, Vec::new()))
+                    }
+                    Err(other) => {
+                        panic!("build_file failed with error:\n{:?}", other);
+                    }
                 }
+            };
+
+            if matches.is_present(FLAG_WATCH) {
+                watch(render_target, &roc_file_path, check)
+            } else {
+                check().map(|run| run.exit_code)
             }
         }
         Some((CMD_REPL, _)) => {
@@ -276,6 +304,26 @@ fn main() -> io::Result<()> {
 
             Ok(format_exit_code)
         }
+        Some((CMD_FIND_REFS, matches)) => {
+            let arena = bumpalo::Bump::new();
+
+            let roc_file_path = PathBuf::from(matches.value_of_os(ROC_FILE).unwrap());
+            let line = matches.value_of_t_or_exit(LINE);
+            let column = matches.value_of_t_or_exit(COLUMN);
+
+            match find_refs(&arena, roc_file_path, line, column, Threading::AllAvailable) {
+                Ok(exit_code) => Ok(exit_code),
+                Err(LoadingProblem::FormattedReport(report)) => {
+                    print!("{}", report);
+
+                    Ok(1)
+                }
+                Err(other) => {
+                    panic!("find-refs failed with error:\n{:?}", other);
+                }
+            }
+        }
+        Some((CMD_LSP, matches)) => Ok(roc_lang_srv::run_stdio(cache_dir(matches))?),
         Some((CMD_VERSION, _)) => {
             print!(
                 "{}",
@@ -0,0 +1,221 @@
+//! `--watch`: run a command again whenever one of the files it read changes.
+use roc_collections::MutMap;
+use roc_reporting::report::RenderTarget;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Child;
+use std::time::{Duration, SystemTime};
+
+/// How often to look at the watched files. Polling keeps this working the same way on every OS,
+/// and a few hundred files are cheap to `stat`.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Editors often save a file in several steps, so give them a moment before reading it.
+const SETTLE_TIME: Duration = Duration::from_millis(50);
+
+/// What one run of the watched command leaves behind
+pub struct Run {
+    pub exit_code: i32,
+    /// Every file the run read: the modules that were loaded, and the host sources of the
+    /// platform. Empty when the run failed before it knew which files those are, e.g. because of
+    /// a syntax error in a header.
+    pub files: Vec<PathBuf>,
+    /// The program the run started, which is stopped before the next run
+    pub program: Option<Child>,
+}
+
+impl Run {
+    pub fn finished(exit_code: i32, files: Vec<PathBuf>) -> Self {
+        Run {
+            exit_code,
+            files,
+            program: None,
+        }
+    }
+}
+
+/// Runs `run` now, and again after every change to a file it read. This only returns if the
+/// files can't be watched.
+pub fn watch(
+    render_target: RenderTarget,
+    main_module: &Path,
+    mut run: impl FnMut() -> io::Result<Run>,
+) -> io::Result<i32> {
+    let mut files = vec![main_module.to_path_buf()];
+
+    loop {
+        // Remember what the files looked like before this run, so that a change made while it
+        // was running is not missed.
+        let mut modified = modification_times(&files);
+
+        clear_screen(render_target)?;
+
+        let Run {
+            exit_code: _,
+            files: read,
+            program,
+        } = run()?;
+
+        // A run that failed early does not know all the modules, so keep watching the ones we
+        // know about until it succeeds again.
+        if !read.is_empty() {
+            files = read;
+
+            if !files.iter().any(|file| file == main_module) {
+                files.push(main_module.to_path_buf());
+            }
+
+            for file in files.iter() {
+                if !modified.contains_key(file) {
+                    modified.insert(file.clone(), modification_time(file));
+                }
+            }
+        }
+
+        eprintln!("\nWatching {} files for changes…", files.len());
+
+        wait_for_change(&files, &modified, program)?;
+    }
+}
+
+fn wait_for_change(
+    files: &[PathBuf],
+    modified: &MutMap<PathBuf, Option<SystemTime>>,
+    mut program: Option<Child>,
+) -> io::Result<()> {
+    loop {
+        let changed = files
+            .iter()
+            .any(|file| modified.get(file).copied().flatten() != modification_time(file));
+
+        if changed {
+            break;
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+    }
+
+    if let Some(program) = program.as_mut() {
+        if program.try_wait()?.is_none() {
+            // it may have exited in the meantime, so failing to kill it is fine
+            let _ = program.kill();
+        }
+
+        program.wait()?;
+    }
+
+    std::thread::sleep(SETTLE_TIME);
+
+    Ok(())
+}
+
+fn modification_times(files: &[PathBuf]) -> MutMap<PathBuf, Option<SystemTime>> {
+    files
+        .iter()
+        .map(|file| (file.clone(), modification_time(file)))
+        .collect()
+}
+
+/// `None` if the file does not exist (anymore), which is a change too.
+fn modification_time(file: &Path) -> Option<SystemTime> {
+    std::fs::metadata(file)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// The reports of the previous run are out of date, so don't leave them on the screen.
+/// Other tools read JSON reports one line at a time, so there we just keep appending.
+fn clear_screen(render_target: RenderTarget) -> io::Result<()> {
+    match render_target {
+        RenderTarget::ColorTerminal => {
+            let mut stdout = io::stdout();
+
+            // clear the screen and the scrollback, then move the cursor to the top left
+            stdout.write_all(b"\x1B[2J\x1B[3J\x1B[H")?;
+            stdout.flush()
+        }
+        RenderTarget::Generic | RenderTarget::Json => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{modification_times, wait_for_change};
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn wait_in_background(files: Vec<PathBuf>) -> std::sync::mpsc::Receiver<()> {
+        let modified = modification_times(&files);
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        std::thread::spawn(move || {
+            wait_for_change(&files, &modified, None).unwrap();
+            sender.send(()).unwrap();
+        });
+
+        receiver
+    }
+
+    #[test]
+    fn missing_files_have_no_modification_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let present = dir.path().join("Present.roc");
+        let missing = dir.path().join("Missing.roc");
+        std::fs::write(&present, "").unwrap();
+
+        let times = modification_times(&[present.clone(), missing.clone()]);
+
+        assert!(times[&present].is_some());
+        assert_eq!(times[&missing], None);
+    }
+
+    #[test]
+    fn removing_a_file_is_a_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("Main.roc");
+        std::fs::write(&file, "").unwrap();
+
+        let changed = wait_in_background(vec![file.clone()]);
+
+        // nothing happened yet
+        assert!(changed.recv_timeout(Duration::from_millis(500)).is_err());
+
+        std::fs::remove_file(&file).unwrap();
+
+        changed.recv_timeout(Duration::from_secs(5)).unwrap();
+    }
+
+    #[test]
+    fn creating_a_missing_file_is_a_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("Dep.roc");
+
+        let changed = wait_in_background(vec![file.clone()]);
+
+        std::fs::write(&file, "").unwrap();
+
+        changed.recv_timeout(Duration::from_secs(5)).unwrap();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn a_change_stops_the_program() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("Main.roc");
+        std::fs::write(&file, "").unwrap();
+
+        let files = vec![file.clone()];
+        let modified = modification_times(&files);
+        let program = std::process::Command::new("sleep")
+            .arg("60")
+            .spawn()
+            .unwrap();
+        let start = std::time::Instant::now();
+
+        std::fs::remove_file(&file).unwrap();
+        wait_for_change(&files, &modified, Some(program)).unwrap();
+
+        // the program was killed rather than waited for until it exited on its own
+        assert!(start.elapsed() < Duration::from_secs(30));
+    }
+}
@@ -16,7 +16,7 @@ mod cli_run {
     };
     use const_format::concatcp;
     use indoc::indoc;
-    use roc_cli::{CMD_BUILD, CMD_CHECK, CMD_FORMAT, CMD_RUN};
+    use roc_cli::{CMD_BUILD, CMD_CHECK, CMD_FORMAT, CMD_RUN, CMD_TEST};
     use roc_test_utils::assert_multiline_str_eq;
     use serial_test::serial;
     use std::iter;
@@ -25,9 +25,13 @@ mod cli_run {
     use strum_macros::EnumIter;
 
     const OPTIMIZE_FLAG: &str = concatcp!("--", roc_cli::FLAG_OPTIMIZE);
+    const DEV_FLAG: &str = concatcp!("--", roc_cli::FLAG_DEV);
     const LINKER_FLAG: &str = concatcp!("--", roc_cli::FLAG_LINKER);
     const CHECK_FLAG: &str = concatcp!("--", roc_cli::FLAG_CHECK);
     const PREBUILT_PLATFORM: &str = concatcp!("--", roc_cli::FLAG_PREBUILT, "=true");
+    const FILTER_FLAG: &str = concatcp!("--", roc_cli::FLAG_FILTER);
+    const LIST_FLAG: &str = concatcp!("--", roc_cli::FLAG_LIST);
+    const TIME_FLAG: &str = concatcp!("--", roc_cli::FLAG_TIME);
     #[allow(dead_code)]
     const TARGET_FLAG: &str = concatcp!("--", roc_cli::FLAG_TARGET);
 
@@ -960,6 +964,178 @@ mod cli_run {
         );
     }
 
+    #[test]
+    #[serial(multi_dep_str)]
+    #[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+    fn run_shows_dbg() {
+        let out = run_roc_on(&fixture_file("dbg", "Main.roc"), [CMD_RUN], &[], &[]);
+
+        assert!(
+            out.stdout
+                .ends_with("Main.roc:8] Str.concat \"Ro\" \"c\" = \"Roc\"\nHello, Roc!\n"),
+            "unexpected output: {:?}",
+            out.stdout
+        );
+    }
+
+    #[test]
+    #[serial(multi_dep_str)]
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    fn run_shows_dbg_with_dev_backend() {
+        let out = run_roc_on(
+            &fixture_file("dbg", "Main.roc"),
+            [CMD_RUN, DEV_FLAG],
+            &[],
+            &[],
+        );
+
+        assert!(
+            out.stdout
+                .ends_with("Main.roc:8] Str.concat \"Ro\" \"c\" = \"Roc\"\nHello, Roc!\n"),
+            "unexpected output: {:?}",
+            out.stdout
+        );
+    }
+
+    #[test]
+    #[serial(multi_dep_str)]
+    fn optimized_build_warns_about_dbg() {
+        let file = fixture_file("dbg", "Main.roc");
+        let out = run_roc([CMD_BUILD, OPTIMIZE_FLAG, file.to_str().unwrap()], &[]);
+        let stdout = strip_colors(&out.stdout);
+
+        assert!(
+            stdout.contains("── DBG IGNORED ─"),
+            "unexpected output: {:?}",
+            stdout
+        );
+        assert!(stdout.contains("0 errors and 1 warning found"));
+    }
+
+    /// The expectations `roc test --list` printed, as `Module.def:line`
+    #[cfg(not(windows))]
+    fn listed_expects(args: &[&str]) -> Vec<String> {
+        let file = fixture_file("expects", "Parse.roc");
+        let out = run_roc(
+            iter::once(CMD_TEST)
+                .chain([LIST_FLAG])
+                .chain(args.iter().copied())
+                .chain([file.to_str().unwrap()]),
+            &[],
+        );
+        assert!(out.status.success(), "unexpected output: {:?}", out);
+
+        strip_colors(&out.stdout)
+            .lines()
+            .filter_map(|line| {
+                let (name, location) = line.split_once(" (")?;
+                let (_, line_number) = location.strip_suffix(')')?.rsplit_once(':')?;
+
+                Some(format!("{}:{}", name, line_number))
+            })
+            .collect()
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_list_names_expects_after_the_def_above_them() {
+        assert_eq!(
+            listed_expects(&[]),
+            [
+                "Parse:5",
+                "Parse.bytes:10",
+                "Parse.graphemes:15",
+                "Parse.graphemes:17",
+                "Util.double:8",
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_filter_by_module() {
+        assert_eq!(
+            listed_expects(&[FILTER_FLAG, "Parse"]),
+            [
+                "Parse:5",
+                "Parse.bytes:10",
+                "Parse.graphemes:15",
+                "Parse.graphemes:17",
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_filter_by_part_of_name() {
+        assert_eq!(
+            listed_expects(&[FILTER_FLAG, "Parse.graph"]),
+            ["Parse.graphemes:15", "Parse.graphemes:17"]
+        );
+
+        // only a whole module name selects the expects above its first def
+        assert_eq!(
+            listed_expects(&[FILTER_FLAG, "Pars"]),
+            ["Parse.bytes:10", "Parse.graphemes:15", "Parse.graphemes:17",]
+        );
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_time_reports_each_expect() {
+        let file = fixture_file("expects", "Parse.roc");
+        let out = run_roc(
+            [
+                CMD_TEST,
+                TIME_FLAG,
+                FILTER_FLAG,
+                "graphemes",
+                file.to_str().unwrap(),
+            ],
+            &[],
+        );
+        assert!(out.status.success(), "unexpected output: {:?}", out);
+
+        let stdout = strip_colors(&out.stdout);
+        let timings: Vec<&str> = stdout
+            .lines()
+            .filter(|line| line.contains(" ms   passed   "))
+            .collect();
+
+        assert_eq!(timings.len(), 2, "unexpected output: {:?}", stdout);
+        for timing in timings {
+            let (ms, label) = timing.split_once(" ms   passed   ").unwrap();
+
+            assert!(ms.trim().parse::<f64>().is_ok(), "bad timing: {:?}", timing);
+            assert!(label.starts_with("Parse.graphemes ("));
+        }
+
+        assert!(stdout.contains("0 failed and 2 passed"));
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_time_without_colors_for_json() {
+        let file = fixture_file("expects", "Parse.roc");
+        let out = run_roc(
+            [
+                CMD_TEST,
+                TIME_FLAG,
+                "--format=json",
+                FILTER_FLAG,
+                "graphemes",
+                file.to_str().unwrap(),
+            ],
+            &[],
+        );
+        assert!(out.status.success(), "unexpected output: {:?}", out);
+
+        // the summaries go to stderr, so stdout only has reports
+        assert!(!out.stderr.contains('\x1B'), "unexpected colors: {:?}", out);
+        assert!(out.stderr.contains(" ms   passed   Parse.graphemes ("));
+        assert!(out.stderr.contains("0 failed and 2 passed"));
+    }
+
     #[test]
     fn known_type_error() {
         check_compile_error(
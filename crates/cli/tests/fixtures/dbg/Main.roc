@@ -0,0 +1,10 @@
+app "dbg"
+    packages { pf: "../multi-dep-str/platform/main.roc" }
+    imports []
+    provides [main] to pf
+
+main : Str
+main =
+    name = dbg Str.concat "Ro" "c"
+
+    "Hello, \(name)!"
@@ -0,0 +1,17 @@
+interface Parse
+    exposes [bytes, graphemes]
+    imports [Util]
+
+expect Util.double 2 == 4
+
+bytes : Str -> Nat
+bytes = \str -> Str.countUtf8Bytes str
+
+expect bytes "ab" == 2
+
+graphemes : Str -> Nat
+graphemes = \str -> Str.countGraphemes str
+
+expect graphemes "ab" == 2
+
+expect graphemes "" == 0
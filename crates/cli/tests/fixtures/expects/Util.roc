@@ -0,0 +1,8 @@
+interface Util
+    exposes [double]
+    imports []
+
+double : I64 -> I64
+double = \n -> n * 2
+
+expect double 3 == 6
@@ -421,6 +421,10 @@ pub fn build_c_host_native(
             "-lrt",
             "-lutil",
         ]);
+        if cfg!(target_os = "macos") {
+            // leave room for the load commands the surgical linker adds
+            command.arg("-Wl,-headerpad,0x1000");
+        }
     } else {
         command.args(&["-fPIC", "-c"]);
     }
@@ -469,6 +473,115 @@ pub fn build_swift_host_native(
     command.output().unwrap()
 }
 
+/// The kinds of host that [rebuild_host] can build, in the order it looks for them
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HostKind {
+    /// `host.zig`
+    Zig,
+    /// a Cargo project, plus `host.c`
+    Cargo,
+    /// `host.rs`, plus `host.c`
+    Rust,
+    /// `host.c`
+    C,
+    /// `host.swift`, plus `host.h` if it exists
+    Swift,
+}
+
+impl HostKind {
+    /// The kind of host next to `host_input_path`, or `None` if there is nothing to build
+    pub fn find(host_input_path: &Path) -> Option<Self> {
+        let exists = |file_name| host_input_path.with_file_name(file_name).exists();
+
+        if exists("host.zig") {
+            Some(HostKind::Zig)
+        } else if exists("Cargo.toml") {
+            Some(HostKind::Cargo)
+        } else if exists("host.rs") {
+            Some(HostKind::Rust)
+        } else if exists("host.c") {
+            Some(HostKind::C)
+        } else if exists("host.swift") {
+            Some(HostKind::Swift)
+        } else {
+            None
+        }
+    }
+
+    /// The files that building this host reads. Besides the files [rebuild_host] passes to the
+    /// compiler, that is whatever those can include: the `.zig` or `.h` files next to them, and
+    /// the sources of a Cargo project.
+    pub fn sources(self, host_input_path: &Path) -> Vec<PathBuf> {
+        let platform_dir = match host_input_path.parent() {
+            Some(dir) => dir,
+            None => return Vec::new(),
+        };
+
+        let mut sources = Vec::new();
+
+        match self {
+            HostKind::Zig => {
+                add_files_with_extension(platform_dir, "zig", false, &mut sources);
+            }
+            HostKind::Cargo => {
+                sources.push(platform_dir.join("Cargo.toml"));
+                sources.push(platform_dir.join("Cargo.lock"));
+                sources.push(platform_dir.join("build.rs"));
+                sources.push(platform_dir.join("host.c"));
+                add_files_with_extension(&platform_dir.join("src"), "rs", true, &mut sources);
+            }
+            HostKind::Rust => {
+                sources.push(platform_dir.join("host.rs"));
+                sources.push(platform_dir.join("host.c"));
+            }
+            HostKind::C => {
+                sources.push(platform_dir.join("host.c"));
+                add_files_with_extension(platform_dir, "h", false, &mut sources);
+            }
+            HostKind::Swift => {
+                sources.push(platform_dir.join("host.swift"));
+                sources.push(platform_dir.join("host.h"));
+            }
+        }
+
+        sources.retain(|path| path.is_file());
+        sources.sort();
+        sources.dedup();
+
+        sources
+    }
+}
+
+fn add_files_with_extension(
+    dir: &Path,
+    extension: &str,
+    recursive: bool,
+    files: &mut Vec<PathBuf>,
+) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => {
+                if recursive {
+                    add_files_with_extension(&path, extension, recursive, files);
+                }
+            }
+            Ok(_) => {
+                if path.extension().and_then(|ext| ext.to_str()) == Some(extension) {
+                    files.push(path);
+                }
+            }
+            Err(_) => {}
+        }
+    }
+}
+
 pub fn rebuild_host(
     opt_level: OptLevel,
     target: &Triple,
@@ -480,7 +593,6 @@ pub fn rebuild_host(
     let zig_host_src = host_input_path.with_file_name("host.zig");
     let rust_host_src = host_input_path.with_file_name("host.rs");
     let rust_host_dest = host_input_path.with_file_name("rust_host.o");
-    let cargo_host_src = host_input_path.with_file_name("Cargo.toml");
     let swift_host_src = host_input_path.with_file_name("host.swift");
     let swift_host_header_src = host_input_path.with_file_name("host.h");
 
@@ -520,245 +632,260 @@ pub fn rebuild_host(
     let env_home = env::var("HOME").unwrap_or_else(|_| "".to_string());
     let env_cpath = env::var("CPATH").unwrap_or_else(|_| "".to_string());
 
-    if zig_host_src.exists() {
-        // Compile host.zig
+    match HostKind::find(host_input_path) {
+        Some(HostKind::Zig) => {
+            // Compile host.zig
+
+            let zig_str_path = find_zig_str_path();
+
+            debug_assert!(
+                std::path::Path::exists(&zig_str_path),
+                "Cannot find str.zig, looking at {:?}",
+                &zig_str_path
+            );
 
-        let zig_str_path = find_zig_str_path();
+            let output = match target.architecture {
+                Architecture::Wasm32 => {
+                    let emit_bin = if matches!(opt_level, OptLevel::Development) {
+                        format!("-femit-bin={}", host_dest.to_str().unwrap())
+                    } else {
+                        format!("-femit-llvm-ir={}", host_dest.to_str().unwrap())
+                    };
+                    build_zig_host_wasm32(
+                        &env_path,
+                        &env_home,
+                        &emit_bin,
+                        zig_host_src.to_str().unwrap(),
+                        zig_str_path.to_str().unwrap(),
+                        opt_level,
+                        shared_lib_path,
+                    )
+                }
+                Architecture::X86_64 => {
+                    let emit_bin = format!("-femit-bin={}", host_dest.to_str().unwrap());
+
+                    let target = match target.operating_system {
+                        OperatingSystem::Windows => "x86_64-windows-gnu",
+                        _ => "native",
+                    };
+
+                    build_zig_host_native(
+                        &env_path,
+                        &env_home,
+                        &emit_bin,
+                        zig_host_src.to_str().unwrap(),
+                        zig_str_path.to_str().unwrap(),
+                        target,
+                        opt_level,
+                        shared_lib_path,
+                    )
+                }
+                Architecture::X86_32(_) => {
+                    let emit_bin = format!("-femit-bin={}", host_dest.to_str().unwrap());
+                    build_zig_host_native(
+                        &env_path,
+                        &env_home,
+                        &emit_bin,
+                        zig_host_src.to_str().unwrap(),
+                        zig_str_path.to_str().unwrap(),
+                        "i386-linux-musl",
+                        opt_level,
+                        shared_lib_path,
+                    )
+                }
 
-        debug_assert!(
-            std::path::Path::exists(&zig_str_path),
-            "Cannot find str.zig, looking at {:?}",
-            &zig_str_path
-        );
+                Architecture::Aarch64(_) => {
+                    let emit_bin = format!("-femit-bin={}", host_dest.to_str().unwrap());
+                    build_zig_host_native(
+                        &env_path,
+                        &env_home,
+                        &emit_bin,
+                        zig_host_src.to_str().unwrap(),
+                        zig_str_path.to_str().unwrap(),
+                        target_zig_str(target),
+                        opt_level,
+                        shared_lib_path,
+                    )
+                }
+                _ => panic!("Unsupported architecture {:?}", target.architecture),
+            };
 
-        let output = match target.architecture {
-            Architecture::Wasm32 => {
-                let emit_bin = if matches!(opt_level, OptLevel::Development) {
-                    format!("-femit-bin={}", host_dest.to_str().unwrap())
+            validate_output("host.zig", &zig_executable(), output)
+        }
+        Some(HostKind::Cargo) => {
+            // Compile and link Cargo.toml, if it exists
+            let cargo_dir = host_input_path.parent().unwrap();
+            let cargo_out_dir = cargo_dir.join("target").join(
+                if matches!(opt_level, OptLevel::Optimize | OptLevel::Size) {
+                    "release"
                 } else {
-                    format!("-femit-llvm-ir={}", host_dest.to_str().unwrap())
-                };
-                build_zig_host_wasm32(
-                    &env_path,
-                    &env_home,
-                    &emit_bin,
-                    zig_host_src.to_str().unwrap(),
-                    zig_str_path.to_str().unwrap(),
-                    opt_level,
-                    shared_lib_path,
-                )
+                    "debug"
+                },
+            );
+
+            let mut command = Command::new("cargo");
+            command.arg("build").current_dir(cargo_dir);
+            // Rust doesn't expose size without editing the cargo.toml. Instead just use release.
+            if matches!(opt_level, OptLevel::Optimize | OptLevel::Size) {
+                command.arg("--release");
             }
-            Architecture::X86_64 => {
-                let emit_bin = format!("-femit-bin={}", host_dest.to_str().unwrap());
+            let source_file = if shared_lib_path.is_some() {
+                if cfg!(target_os = "macos") {
+                    // leave room for the load commands the surgical linker adds
+                    command.env(
+                        "RUSTFLAGS",
+                        "-C link-dead-code -C link-arg=-Wl,-headerpad,0x1000",
+                    );
+                } else {
+                    command.env("RUSTFLAGS", "-C link-dead-code");
+                }
+                command.args(&["--bin", "host"]);
+                "src/main.rs"
+            } else {
+                command.arg("--lib");
+                "src/lib.rs"
+            };
+            let output = command.output().unwrap();
 
-                let target = match target.operating_system {
-                    OperatingSystem::Windows => "x86_64-windows-gnu",
-                    _ => "native",
-                };
+            validate_output(source_file, "cargo build", output);
 
-                build_zig_host_native(
+            if shared_lib_path.is_some() {
+                // For surgical linking, just copy the dynamically linked rust app.
+                std::fs::copy(cargo_out_dir.join("host"), &host_dest).unwrap();
+            } else {
+                // Cargo hosts depend on a c wrapper for the api. Compile host.c as well.
+
+                let output = build_c_host_native(
                     &env_path,
                     &env_home,
-                    &emit_bin,
-                    zig_host_src.to_str().unwrap(),
-                    zig_str_path.to_str().unwrap(),
-                    target,
+                    &env_cpath,
+                    c_host_dest.to_str().unwrap(),
+                    &[c_host_src.to_str().unwrap()],
                     opt_level,
                     shared_lib_path,
-                )
+                );
+                validate_output("host.c", "clang", output);
+
+                let output = Command::new("ld")
+                    .env_clear()
+                    .env("PATH", &env_path)
+                    .args(&[
+                        "-r",
+                        "-L",
+                        cargo_out_dir.to_str().unwrap(),
+                        c_host_dest.to_str().unwrap(),
+                        "-lhost",
+                        "-o",
+                        host_dest.to_str().unwrap(),
+                    ])
+                    .output()
+                    .unwrap();
+                validate_output("c_host.o", "ld", output);
+
+                // Clean up c_host.o
+                if c_host_dest.exists() {
+                    std::fs::remove_file(c_host_dest).unwrap();
+                }
             }
-            Architecture::X86_32(_) => {
-                let emit_bin = format!("-femit-bin={}", host_dest.to_str().unwrap());
-                build_zig_host_native(
+        }
+        Some(HostKind::Rust) => {
+            // Compile and link host.rs, if it exists
+            let mut command = Command::new("rustc");
+            command.args(&[
+                rust_host_src.to_str().unwrap(),
+                "-o",
+                rust_host_dest.to_str().unwrap(),
+            ]);
+            if matches!(opt_level, OptLevel::Optimize) {
+                command.arg("-O");
+            } else if matches!(opt_level, OptLevel::Size) {
+                command.arg("-C opt-level=s");
+            }
+            let output = command.output().unwrap();
+
+            validate_output("host.rs", "rustc", output);
+
+            // Rust hosts depend on a c wrapper for the api. Compile host.c as well.
+            if shared_lib_path.is_some() {
+                // If compiling to executable, let c deal with linking as well.
+                let output = build_c_host_native(
                     &env_path,
                     &env_home,
-                    &emit_bin,
-                    zig_host_src.to_str().unwrap(),
-                    zig_str_path.to_str().unwrap(),
-                    "i386-linux-musl",
+                    &env_cpath,
+                    host_dest.to_str().unwrap(),
+                    &[
+                        c_host_src.to_str().unwrap(),
+                        rust_host_dest.to_str().unwrap(),
+                    ],
                     opt_level,
                     shared_lib_path,
-                )
-            }
-
-            Architecture::Aarch64(_) => {
-                let emit_bin = format!("-femit-bin={}", host_dest.to_str().unwrap());
-                build_zig_host_native(
+                );
+                validate_output("host.c", "clang", output);
+            } else {
+                let output = build_c_host_native(
                     &env_path,
                     &env_home,
-                    &emit_bin,
-                    zig_host_src.to_str().unwrap(),
-                    zig_str_path.to_str().unwrap(),
-                    target_zig_str(target),
+                    &env_cpath,
+                    c_host_dest.to_str().unwrap(),
+                    &[c_host_src.to_str().unwrap()],
                     opt_level,
                     shared_lib_path,
-                )
+                );
+
+                validate_output("host.c", "clang", output);
+                let output = Command::new("ld")
+                    .env_clear()
+                    .env("PATH", &env_path)
+                    .args(&[
+                        "-r",
+                        c_host_dest.to_str().unwrap(),
+                        rust_host_dest.to_str().unwrap(),
+                        "-o",
+                        host_dest.to_str().unwrap(),
+                    ])
+                    .output()
+                    .unwrap();
+
+                validate_output("rust_host.o", "ld", output);
             }
-            _ => panic!("Unsupported architecture {:?}", target.architecture),
-        };
-
-        validate_output("host.zig", &zig_executable(), output)
-    } else if cargo_host_src.exists() {
-        // Compile and link Cargo.toml, if it exists
-        let cargo_dir = host_input_path.parent().unwrap();
-        let cargo_out_dir = cargo_dir.join("target").join(
-            if matches!(opt_level, OptLevel::Optimize | OptLevel::Size) {
-                "release"
-            } else {
-                "debug"
-            },
-        );
-
-        let mut command = Command::new("cargo");
-        command.arg("build").current_dir(cargo_dir);
-        // Rust doesn't expose size without editing the cargo.toml. Instead just use release.
-        if matches!(opt_level, OptLevel::Optimize | OptLevel::Size) {
-            command.arg("--release");
-        }
-        let source_file = if shared_lib_path.is_some() {
-            command.env("RUSTFLAGS", "-C link-dead-code");
-            command.args(&["--bin", "host"]);
-            "src/main.rs"
-        } else {
-            command.arg("--lib");
-            "src/lib.rs"
-        };
-        let output = command.output().unwrap();
 
-        validate_output(source_file, "cargo build", output);
-
-        if shared_lib_path.is_some() {
-            // For surgical linking, just copy the dynamically linked rust app.
-            std::fs::copy(cargo_out_dir.join("host"), &host_dest).unwrap();
-        } else {
-            // Cargo hosts depend on a c wrapper for the api. Compile host.c as well.
-
-            let output = build_c_host_native(
-                &env_path,
-                &env_home,
-                &env_cpath,
-                c_host_dest.to_str().unwrap(),
-                &[c_host_src.to_str().unwrap()],
-                opt_level,
-                shared_lib_path,
-            );
-            validate_output("host.c", "clang", output);
-
-            let output = Command::new("ld")
-                .env_clear()
-                .env("PATH", &env_path)
-                .args(&[
-                    "-r",
-                    "-L",
-                    cargo_out_dir.to_str().unwrap(),
-                    c_host_dest.to_str().unwrap(),
-                    "-lhost",
-                    "-o",
-                    host_dest.to_str().unwrap(),
-                ])
-                .output()
-                .unwrap();
-            validate_output("c_host.o", "ld", output);
-
-            // Clean up c_host.o
+            // Clean up rust_host.o and c_host.o
             if c_host_dest.exists() {
                 std::fs::remove_file(c_host_dest).unwrap();
             }
+            if rust_host_dest.exists() {
+                std::fs::remove_file(rust_host_dest).unwrap();
+            }
         }
-    } else if rust_host_src.exists() {
-        // Compile and link host.rs, if it exists
-        let mut command = Command::new("rustc");
-        command.args(&[
-            rust_host_src.to_str().unwrap(),
-            "-o",
-            rust_host_dest.to_str().unwrap(),
-        ]);
-        if matches!(opt_level, OptLevel::Optimize) {
-            command.arg("-O");
-        } else if matches!(opt_level, OptLevel::Size) {
-            command.arg("-C opt-level=s");
-        }
-        let output = command.output().unwrap();
-
-        validate_output("host.rs", "rustc", output);
-
-        // Rust hosts depend on a c wrapper for the api. Compile host.c as well.
-        if shared_lib_path.is_some() {
-            // If compiling to executable, let c deal with linking as well.
+        Some(HostKind::C) => {
+            // Compile host.c, if it exists
             let output = build_c_host_native(
                 &env_path,
                 &env_home,
                 &env_cpath,
                 host_dest.to_str().unwrap(),
-                &[
-                    c_host_src.to_str().unwrap(),
-                    rust_host_dest.to_str().unwrap(),
-                ],
+                &[c_host_src.to_str().unwrap()],
                 opt_level,
                 shared_lib_path,
             );
             validate_output("host.c", "clang", output);
-        } else {
-            let output = build_c_host_native(
+        }
+        Some(HostKind::Swift) => {
+            // Compile host.swift, if it exists
+            let output = build_swift_host_native(
                 &env_path,
                 &env_home,
-                &env_cpath,
-                c_host_dest.to_str().unwrap(),
-                &[c_host_src.to_str().unwrap()],
+                host_dest.to_str().unwrap(),
+                &[swift_host_src.to_str().unwrap()],
                 opt_level,
                 shared_lib_path,
+                swift_host_header_src
+                    .exists()
+                    .then(|| swift_host_header_src.to_str().unwrap()),
             );
-
-            validate_output("host.c", "clang", output);
-            let output = Command::new("ld")
-                .env_clear()
-                .env("PATH", &env_path)
-                .args(&[
-                    "-r",
-                    c_host_dest.to_str().unwrap(),
-                    rust_host_dest.to_str().unwrap(),
-                    "-o",
-                    host_dest.to_str().unwrap(),
-                ])
-                .output()
-                .unwrap();
-
-            validate_output("rust_host.o", "ld", output);
-        }
-
-        // Clean up rust_host.o and c_host.o
-        if c_host_dest.exists() {
-            std::fs::remove_file(c_host_dest).unwrap();
-        }
-        if rust_host_dest.exists() {
-            std::fs::remove_file(rust_host_dest).unwrap();
+            validate_output("host.swift", "swiftc", output);
         }
-    } else if c_host_src.exists() {
-        // Compile host.c, if it exists
-        let output = build_c_host_native(
-            &env_path,
-            &env_home,
-            &env_cpath,
-            host_dest.to_str().unwrap(),
-            &[c_host_src.to_str().unwrap()],
-            opt_level,
-            shared_lib_path,
-        );
-        validate_output("host.c", "clang", output);
-    } else if swift_host_src.exists() {
-        // Compile host.swift, if it exists
-        let output = build_swift_host_native(
-            &env_path,
-            &env_home,
-            host_dest.to_str().unwrap(),
-            &[swift_host_src.to_str().unwrap()],
-            opt_level,
-            shared_lib_path,
-            swift_host_header_src
-                .exists()
-                .then(|| swift_host_header_src.to_str().unwrap()),
-        );
-        validate_output("host.swift", "swiftc", output);
+        None => {}
     }
 
     host_dest
@@ -5,6 +5,7 @@ use roc_load::{EntryPoint, LoadedModule, MonomorphizedModule};
 use roc_module::symbol::{Interns, ModuleId};
 use roc_mono::ir::OptLevel;
 use roc_region::all::LineInfo;
+use roc_reporting::report::RenderTarget;
 use roc_solve_problem::TypeError;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
@@ -19,23 +20,33 @@ pub struct CodeGenTiming {
     pub emit_o_file: Duration,
 }
 
-pub fn report_problems_monomorphized(loaded: &mut MonomorphizedModule) -> Problems {
+pub fn report_problems_monomorphized(
+    loaded: &mut MonomorphizedModule,
+    render_target: RenderTarget,
+) -> Problems {
     report_problems_help(
         loaded.total_problems(),
         &loaded.sources,
         &loaded.interns,
+        &mut loaded.syntax_problems,
         &mut loaded.can_problems,
         &mut loaded.type_problems,
+        render_target,
     )
 }
 
-pub fn report_problems_typechecked(loaded: &mut LoadedModule) -> Problems {
+pub fn report_problems_typechecked(
+    loaded: &mut LoadedModule,
+    render_target: RenderTarget,
+) -> Problems {
     report_problems_help(
         loaded.total_problems(),
         &loaded.sources,
         &loaded.interns,
+        &mut loaded.syntax_problems,
         &mut loaded.can_problems,
         &mut loaded.type_problems,
+        render_target,
     )
 }
 
@@ -60,8 +71,10 @@ fn report_problems_help(
     total_problems: usize,
     sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
     interns: &Interns,
+    syntax_problems: &mut MutMap<ModuleId, Vec<String>>,
     can_problems: &mut MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     type_problems: &mut MutMap<ModuleId, Vec<TypeError>>,
+    render_target: RenderTarget,
 ) -> Problems {
     use roc_reporting::report::{
         can_problem, type_problem, Report, RocDocAllocator, Severity::*, DEFAULT_PALETTE,
@@ -74,6 +87,9 @@ fn report_problems_help(
     let mut errors = Vec::with_capacity(total_problems);
 
     for (home, (module_path, src)) in sources.iter() {
+        // Syntax errors are rendered as soon as the module is parsed, and are always errors
+        errors.extend(syntax_problems.remove(home).unwrap_or_default());
+
         let mut src_lines: Vec<&str> = Vec::new();
 
         src_lines.extend(src.split('\n'));
@@ -90,7 +106,7 @@ fn report_problems_help(
             let severity = report.severity;
             let mut buf = String::new();
 
-            report.render_color_terminal(&mut buf, &alloc, &palette);
+            report.render(render_target, &mut buf, &alloc, &palette);
 
             match severity {
                 Warning => {
@@ -109,7 +125,7 @@ fn report_problems_help(
                 let severity = report.severity;
                 let mut buf = String::new();
 
-                report.render_color_terminal(&mut buf, &alloc, &palette);
+                report.render(render_target, &mut buf, &alloc, &palette);
 
                 match severity {
                     Warning => {
@@ -123,31 +139,32 @@ fn report_problems_help(
         }
     }
 
-    let problems_reported;
-
     // Only print warnings if there are no errors
-    if errors.is_empty() {
-        problems_reported = warnings.len();
+    let problems_to_report = if errors.is_empty() {
+        &warnings
+    } else {
+        &errors
+    };
 
-        for warning in warnings.iter() {
-            println!("\n{}\n", warning);
+    if let RenderTarget::Json = render_target {
+        // Every report is a line of JSON, with nothing in between
+        for problem in problems_to_report.iter() {
+            println!("{}", problem);
         }
     } else {
-        problems_reported = errors.len();
-
-        for error in errors.iter() {
-            println!("\n{}\n", error);
+        for problem in problems_to_report.iter() {
+            println!("\n{}\n", problem);
         }
-    }
 
-    // If we printed any problems, print a horizontal rule at the end,
-    // and then clear any ANSI escape codes (e.g. colors) we've used.
-    //
-    // The horizontal rule is nice when running the program right after
-    // compiling it, as it lets you clearly see where the compiler
-    // errors/warnings end and the program output begins.
-    if problems_reported > 0 {
-        println!("{}\u{001B}[0m\n", Report::horizontal_rule(&palette));
+        // If we printed any problems, print a horizontal rule at the end,
+        // and then clear any ANSI escape codes (e.g. colors) we've used.
+        //
+        // The horizontal rule is nice when running the program right after
+        // compiling it, as it lets you clearly see where the compiler
+        // errors/warnings end and the program output begins.
+        if !problems_to_report.is_empty() {
+            println!("{}\u{001B}[0m\n", Report::horizontal_rule(&palette));
+        }
     }
 
     Problems {
@@ -253,6 +270,13 @@ pub fn gen_from_mono_module_llvm(
     let (dibuilder, compile_unit) = roc_gen_llvm::llvm::build::Env::new_debug_info(module);
     let (mpm, _fpm) = roc_gen_llvm::llvm::build::construct_optimization_passes(module, opt_level);
 
+    // only point debug info at the Roc source when it was asked for
+    let debug_sources = if emit_debug_info {
+        roc_gen_llvm::llvm::build::Env::new_debug_sources(&dibuilder, &loaded.sources)
+    } else {
+        Default::default()
+    };
+
     // Compile and add all the Procs before adding main
     let env = roc_gen_llvm::llvm::build::Env {
         arena,
@@ -264,8 +288,12 @@ pub fn gen_from_mono_module_llvm(
         interns: loaded.interns,
         module,
         target_info,
-        mode: LlvmBackendMode::Binary,
+        mode: match opt_level {
+            OptLevel::Optimize => LlvmBackendMode::Binary,
+            OptLevel::Normal | OptLevel::Size | OptLevel::Development => LlvmBackendMode::BinaryDev,
+        },
         exposed_to_host: loaded.exposed_to_host.values.keys().copied().collect(),
+        debug_sources,
     };
 
     // does not add any externs for this mode (we have a host) but cleans up some functions around
@@ -289,8 +317,22 @@ pub fn gen_from_mono_module_llvm(
 
     env.dibuilder.finalize();
 
-    // we don't use the debug info, and it causes weird errors.
-    module.strip_debug_info();
+    if emit_debug_info {
+        use inkwell::debug_info::debug_metadata_version;
+        use inkwell::module::FlagBehavior;
+
+        // LLVM drops debug info from modules that don't say which version of it they contain.
+        // The builtins may already have set this flag, and it must not be set twice.
+        if module.get_flag("Debug Info Version").is_none() {
+            let version = context
+                .i32_type()
+                .const_int(debug_metadata_version() as u64, false);
+            module.add_basic_value_flag("Debug Info Version", FlagBehavior::Warning, version);
+        }
+    } else {
+        // we don't use the debug info, and it causes weird errors.
+        module.strip_debug_info();
+    }
 
     // Uncomment this to see the module's optimized LLVM instruction output:
     // env.module.print_to_stderr();
@@ -315,98 +357,27 @@ pub fn gen_from_mono_module_llvm(
     let code_gen = code_gen_start.elapsed();
     let emit_o_file_start = Instant::now();
 
-    // annotate the LLVM IR output with debug info
-    // so errors are reported with the line number of the LLVM source
-    if emit_debug_info {
-        module.strip_debug_info();
-
-        let mut app_ll_dbg_file = PathBuf::from(roc_file_path);
-        app_ll_dbg_file.set_extension("dbg.ll");
-
-        let mut app_bc_file = PathBuf::from(roc_file_path);
-        app_bc_file.set_extension("bc");
-
-        use std::process::Command;
-
-        // write the ll code to a file, so we can modify it
-        module.print_to_file(&app_ll_file).unwrap();
-
-        // run the debugir https://github.com/vaivaswatha/debugir tool
-        match Command::new("debugir")
-            .args(&["-instnamer", app_ll_file.to_str().unwrap()])
-            .output()
-        {
-            Ok(_) => {}
-            Err(error) => {
-                use std::io::ErrorKind;
-                match error.kind() {
-                    ErrorKind::NotFound => panic!(
-                        r"I could not find the `debugir` tool on the PATH, install it from https://github.com/vaivaswatha/debugir"
-                    ),
-                    _ => panic!("{:?}", error),
-                }
-            }
+    // Emit the .o file
+    use target_lexicon::Architecture;
+    match target.architecture {
+        Architecture::X86_64 | Architecture::X86_32(_) | Architecture::Aarch64(_) => {
+            let reloc = RelocMode::PIC;
+            let target_machine =
+                target::target_machine(target, convert_opt_level(opt_level), reloc).unwrap();
+
+            target_machine
+                .write_to_file(env.module, FileType::Object, app_o_file)
+                .expect("Writing .o file failed");
         }
-
-        use target_lexicon::Architecture;
-        match target.architecture {
-            Architecture::X86_64
-            | Architecture::X86_32(_)
-            | Architecture::Aarch64(_)
-            | Architecture::Wasm32 => {
-                let ll_to_bc = Command::new("llvm-as")
-                    .args(&[
-                        app_ll_dbg_file.to_str().unwrap(),
-                        "-o",
-                        app_bc_file.to_str().unwrap(),
-                    ])
-                    .output()
-                    .unwrap();
-
-                assert!(ll_to_bc.stderr.is_empty(), "{:#?}", ll_to_bc);
-
-                let llc_args = &[
-                    "-relocation-model=pic",
-                    "-filetype=obj",
-                    app_bc_file.to_str().unwrap(),
-                    "-o",
-                    app_o_file.to_str().unwrap(),
-                ];
-
-                // write the .o file. Note that this builds the .o for the local machine,
-                // and ignores the `target_machine` entirely.
-                //
-                // different systems name this executable differently, so we shotgun for
-                // the most common ones and then give up.
-                let bc_to_object = Command::new("llc").args(llc_args).output().unwrap();
-
-                assert!(bc_to_object.stderr.is_empty(), "{:#?}", bc_to_object);
-            }
-            _ => unreachable!(),
-        }
-    } else {
-        // Emit the .o file
-        use target_lexicon::Architecture;
-        match target.architecture {
-            Architecture::X86_64 | Architecture::X86_32(_) | Architecture::Aarch64(_) => {
-                let reloc = RelocMode::PIC;
-                let target_machine =
-                    target::target_machine(target, convert_opt_level(opt_level), reloc).unwrap();
-
-                target_machine
-                    .write_to_file(env.module, FileType::Object, app_o_file)
-                    .expect("Writing .o file failed");
-            }
-            Architecture::Wasm32 => {
-                // Useful for debugging
-                // module.print_to_file(app_ll_file);
-                module.write_bitcode_to_path(app_o_file);
-            }
-            _ => panic!(
-                "TODO gracefully handle unsupported architecture: {:?}",
-                target.architecture
-            ),
+        Architecture::Wasm32 => {
+            // Useful for debugging
+            // module.print_to_file(app_ll_file);
+            module.write_bitcode_to_path(app_o_file);
         }
+        _ => panic!(
+            "TODO gracefully handle unsupported architecture: {:?}",
+            target.architecture
+        ),
     }
 
     let emit_o_file = emit_o_file_start.elapsed();
@@ -1,16 +1,27 @@
 const std = @import("std");
+const builtin = @import("builtin");
+const str = @import("str.zig");
+const RocStr = str.RocStr;
+const RocList = @import("list.zig").RocList;
 
 const SIGUSR1: c_int = 10;
 
 const O_RDWR: c_int = 2;
 const O_CREAT: c_int = 64;
 
+pub const PROT_READ: c_int = 1;
 pub const PROT_WRITE: c_int = 2;
 pub const MAP_SHARED: c_int = 0x0001;
 
 // IMPORTANT: shared memory object names must begin with / and contain no other slashes!
 var SHARED_BUFFER: []u8 = undefined;
 
+// must match `ExpectMemory::SHM_SIZE` in the repl_expect crate
+const SHARED_FILE_SIZE: usize = 1024;
+
+var PARENT_BUFFER: ?[*]u8 = null;
+var PARENT_BUFFER_OPENED: bool = false;
+
 pub fn setSharedBuffer(ptr: [*]u8, length: usize) callconv(.C) usize {
     SHARED_BUFFER = ptr[0..length];
 
@@ -21,3 +32,152 @@ pub fn setSharedBuffer(ptr: [*]u8, length: usize) callconv(.C) usize {
 pub fn expectFailedStart() callconv(.C) [*]u8 {
     return SHARED_BUFFER.ptr;
 }
+
+// A program that `roc run` started finds the buffer that `roc` created for it by the pid of its
+// parent. Programs started any other way get null, and leave their `dbg`s out.
+pub fn expectFailedStartSharedFile() callconv(.C) ?[*]u8 {
+    if (!PARENT_BUFFER_OPENED) {
+        PARENT_BUFFER_OPENED = true;
+        PARENT_BUFFER = openParentBuffer();
+    }
+
+    return PARENT_BUFFER;
+}
+
+fn openParentBuffer() ?[*]u8 {
+    if (builtin.os.tag == .linux) {
+        // raw syscalls, so that the host does not need to provide any of these functions
+        const linux = std.os.linux;
+
+        const parent_pid = @intCast(i32, linux.syscall0(.getppid));
+
+        // this is the file that `shm_open("/roc_dbg_buffer_{pid}")` in the parent opened
+        var path_buffer: [64]u8 = undefined;
+        const path = std.fmt.bufPrintZ(&path_buffer, "/dev/shm/roc_dbg_buffer_{d}", .{parent_pid}) catch return null;
+
+        const fd = linux.open(path, @intCast(u32, O_RDWR), 0);
+        if (linux.getErrno(fd) != .SUCCESS) {
+            return null;
+        }
+
+        const ptr = linux.mmap(null, SHARED_FILE_SIZE, @intCast(usize, PROT_READ | PROT_WRITE), @intCast(u32, MAP_SHARED), @intCast(i32, fd), 0);
+        _ = linux.close(@intCast(i32, fd));
+
+        if (linux.getErrno(ptr) != .SUCCESS) {
+            return null;
+        }
+
+        return @intToPtr([*]u8, ptr);
+    } else {
+        return null;
+    }
+}
+
+// Tells the parent that a frame was written to its buffer, and waits until the parent has read it.
+// The parent signals that by setting the frame count back to zero.
+pub fn expectFailedFinalize() callconv(.C) void {
+    if (builtin.os.tag == .linux) {
+        const linux = std.os.linux;
+
+        const parent_pid = @intCast(i32, linux.syscall0(.getppid));
+        _ = linux.kill(parent_pid, SIGUSR1);
+
+        const count = @ptrCast(*usize, @alignCast(@alignOf(usize), PARENT_BUFFER.?));
+        while (@atomicLoad(usize, count, .SeqCst) != 0) {
+            _ = linux.sched_yield();
+        }
+    }
+}
+
+// The dev backend cannot clone values into the buffer itself, so it sends the value of a `dbg` with
+// one of the functions below. They write the same frame as the LLVM backend's `send_dbg_to_parent`.
+
+// For values without any pointers in them, which are copied as they are.
+pub fn dbgBytes(region_start: u32, region_end: u32, module_id: u32, bytes: [*]const u8, length: usize) callconv(.C) void {
+    const buffer = expectFailedStartSharedFile() orelse return;
+    const value_offset = startDbgFrame(buffer, region_start, region_end, module_id);
+    const end = value_offset + length;
+
+    if (end > SHARED_FILE_SIZE) {
+        return;
+    }
+
+    @memcpy(buffer + value_offset, bytes, length);
+
+    finishDbgFrame(buffer, end);
+}
+
+pub fn dbgStr(region_start: u32, region_end: u32, module_id: u32, string: RocStr) callconv(.C) void {
+    const buffer = expectFailedStartSharedFile() orelse return;
+    const value_offset = startDbgFrame(buffer, region_start, region_end, module_id);
+    const elements_offset = value_offset + @sizeOf(RocStr);
+
+    if (elements_offset + string.len() > SHARED_FILE_SIZE) {
+        return;
+    }
+
+    const end = str.strCloneTo(string, buffer, value_offset, elements_offset);
+
+    finishDbgFrame(buffer, end);
+}
+
+// For lists whose elements have no pointers in them. Like the LLVM backend, this leaves out the
+// extra capacity, and puts the elements right after the list.
+pub fn dbgList(region_start: u32, region_end: u32, module_id: u32, list: RocList, element_width: usize) callconv(.C) void {
+    const buffer = expectFailedStartSharedFile() orelse return;
+    const value_offset = startDbgFrame(buffer, region_start, region_end, module_id);
+    const elements_offset = value_offset + @sizeOf(RocList);
+    const elements_width = list.len() * element_width;
+    const end = elements_offset + elements_width;
+
+    if (end > SHARED_FILE_SIZE) {
+        return;
+    }
+
+    writeUsize(buffer, value_offset, elements_offset);
+    writeUsize(buffer, value_offset + @sizeOf(usize), list.len());
+    writeUsize(buffer, value_offset + 2 * @sizeOf(usize), list.len());
+
+    if (list.bytes) |elements| {
+        @memcpy(buffer + elements_offset, elements, elements_width);
+    }
+
+    finishDbgFrame(buffer, end);
+}
+
+// Writes the region and module of a frame with a single value, and returns where the value goes.
+fn startDbgFrame(buffer: [*]u8, region_start: u32, region_end: u32, module_id: u32) usize {
+    const offset = dbgState(buffer)[1];
+
+    std.mem.writeIntNative(u32, buffer[offset..][0..4], region_start);
+    std.mem.writeIntNative(u32, buffer[offset + 4 ..][0..4], region_end);
+    std.mem.writeIntNative(u32, buffer[offset + 8 ..][0..4], module_id);
+
+    // the end of the frame comes next, and is only written by `finishDbgFrame`
+    const lookups_offset = offset + 12 + @sizeOf(usize);
+    const value_offset = lookups_offset + @sizeOf(usize);
+
+    writeUsize(buffer, lookups_offset, value_offset);
+
+    return value_offset;
+}
+
+fn finishDbgFrame(buffer: [*]u8, end: usize) void {
+    const state = dbgState(buffer);
+
+    writeUsize(buffer, state[1] + 12, end);
+
+    state[0] += 1;
+    state[1] = end;
+
+    expectFailedFinalize();
+}
+
+// The number of frames in the buffer, and the offset at which the next one goes.
+fn dbgState(buffer: [*]u8) [*]usize {
+    return @ptrCast([*]usize, @alignCast(@alignOf(usize), buffer));
+}
+
+fn writeUsize(buffer: [*]u8, offset: usize, value: usize) void {
+    std.mem.writeIntNative(usize, buffer[offset..][0..@sizeOf(usize)], value);
+}
@@ -5,6 +5,7 @@
 // and substantial portions of the software.
 const std = @import("std");
 const str = @import("str.zig");
+const RocList = @import("list.zig").RocList;
 const mem = std.mem;
 
 pub fn wyhash(seed: u64, bytes: ?[*]const u8, length: usize) callconv(.C) u64 {
@@ -20,6 +21,21 @@ pub fn wyhash_rocstr(seed: u64, input: str.RocStr) callconv(.C) u64 {
     return wyhash_hash(seed, input.asSlice());
 }
 
+// The list comes first, because that is how the backends pass lists to bitcode functions.
+pub fn wyhashList(list: RocList, seed: u64) callconv(.C) u64 {
+    if (list.bytes) |bytes| {
+        return wyhash_hash(seed, bytes[0..list.len()]);
+    } else {
+        return wyhash_hash(seed, &[_]u8{});
+    }
+}
+
+// Hashes the 8 little-endian bytes of `value`, without putting them in a list first.
+pub fn wyhashU64(value: u64, seed: u64) callconv(.C) u64 {
+    const bytes = mem.toBytes(mem.nativeToLittle(u64, value));
+    return wyhash_hash(seed, &bytes);
+}
+
 const primes = [_]u64{
     0xa0761d6478bd642f,
     0xe7037ed1a0b428db,
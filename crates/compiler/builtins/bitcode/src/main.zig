@@ -55,6 +55,14 @@ comptime {
     exportListFn(list.listIsUnique, "is_unique");
 }
 
+// Dict Module
+const hash = @import("hash.zig");
+
+comptime {
+    exportDictFn(hash.wyhashList, "hash_bytes");
+    exportDictFn(hash.wyhashU64, "hash_u64");
+}
+
 // Num Module
 const num = @import("num.zig");
 
@@ -168,6 +176,11 @@ comptime {
 
     if (builtin.target.cpu.arch != .wasm32) {
         exportUtilsFn(expect.expectFailedStart, "expect_failed_start");
+        exportUtilsFn(expect.expectFailedStartSharedFile, "expect_failed_start_shared_file");
+        exportUtilsFn(expect.expectFailedFinalize, "expect_failed_finalize");
+        exportUtilsFn(expect.dbgBytes, "dbg_bytes");
+        exportUtilsFn(expect.dbgStr, "dbg_str");
+        exportUtilsFn(expect.dbgList, "dbg_list");
 
         // sets the buffer used for expect failures
         @export(expect.setSharedBuffer, .{ .name = "set_shared_buffer", .linkage = .Weak });
@@ -1,9 +1,25 @@
 interface Bool
-    exposes [Bool, and, or, not, isEq, isNotEq]
+    exposes [Bool, Eq, and, or, not, isEq, isNotEq, structuralEq, structuralNotEq]
     imports []
 
 Bool : [True, False]
 
+## Defines a type that can be compared for equality.
+##
+## Every type whose values do not contain functions has an [Eq] implementation: records, tags,
+## [Str], [List] and numbers are compared by [structuralEq], and so are opaque types that do not
+## implement [Eq] themselves. [Dict] and [Set] values are equal when they have the same entries,
+## no matter in which order those were inserted. An opaque type can implement [Eq] to give its
+## values a different notion of equality.
+##
+## Custom implementations, and those of [Dict] and [Set], are only used when the values are
+## compared directly. Records, tags and lists that contain them are still compared structurally.
+##
+## [isEq] returns `True` if the two values are equal, and `False` otherwise.
+## `a == b` is shorthand for `Bool.isEq a b`.
+Eq has
+    isEq : a, a -> Bool | a has Eq
+
 ## Returns `True` when given `True` and `True`, and `False` when either argument is `False`.
 ##
 ## `a && b` is shorthand for `Bool.and a b`
@@ -61,23 +77,24 @@ not : Bool -> Bool
 
 ## Returns `True` if the two values are *structurally equal*, and `False` otherwise.
 ##
-## `a == b` is shorthand for `Bool.isEq a b`
-##
 ## Structural equality works as follows:
 ##
 ## 1. Tags are equal if they have the same tag name, and also their contents (if any) are equal.
 ## 2. Records are equal if all their fields are equal.
-## 3. Collections ([Str], [List], [Dict], and [Set]) are equal if they are the same length, and also all their corresponding elements are equal.
-## 4. [Num](Num#Num) values are equal if their numbers are equal, with one exception: if both arguments to `isEq` are *NaN*, then `isEq` returns `False`. See `Num.isNaN` for more about *NaN*.
+## 3. Collections ([Str] and [List]) are equal if they are the same length, and also all their corresponding elements are equal.
+## 4. [Num](Num#Num) values are equal if their numbers are equal, with one exception: if both arguments to `structuralEq` are *NaN*, then `structuralEq` returns `False`. See `Num.isNaN` for more about *NaN*.
 ##
-## Note that `isEq` takes `'val` instead of `val`, which means `isEq` does not
+## This is the [Eq] implementation of every type that does not implement [Eq] itself.
+##
+## Note that `structuralEq` takes `'val` instead of `val`, which means `structuralEq` does not
 ## accept arguments whose types contain functions.
-isEq : a, a -> Bool
+structuralEq : a, a -> Bool
+
+## Calls [structuralEq] on the given values, then calls [not] on the result.
+structuralNotEq : a, a -> Bool
 
 ## Calls [isEq] on the given values, then calls [not] on the result.
 ##
 ## `a != b` is shorthand for `Bool.isNotEq a b`
-##
-## Note that `isNotEq` takes `'val` instead of `val`, which means `isNotEq` does not
-## accept arguments whose types contain functions.
-isNotEq : a, a -> Bool
+isNotEq : a, a -> Bool | a has Eq
+isNotEq = \a, b -> not (isEq a b)
@@ -1,6 +1,7 @@
 interface Decode
     exposes [
         DecodeError,
+        DecodePathSegment,
         DecodeResult,
         Decoder,
         Decoding,
@@ -23,16 +24,35 @@ interface Decode
         string,
         list,
         record,
+        tag,
         custom,
         decodeWith,
         fromBytesPartial,
         fromBytes,
+        expected,
+        missing,
+        prependPath,
+        errorToStr,
     ]
     imports [
         List,
+        Str,
+        Num,
     ]
 
-DecodeError : [TooShort]
+## A step from a value being decoded into one of its parts, such as a field of a record.
+DecodePathSegment : [Field Str, Index Nat, Tag Str]
+
+## Describes why decoding failed. The `path` leads from the decoded value to the part of it
+## that could not be decoded; it is empty when the failure is in the value itself.
+##
+## * `Expected` - the input did not have the shape required by the type being decoded, such as
+##   `"ab"` where a `U8` was expected. `found` describes the input at the point of failure.
+## * `Missing` - a record or tag ended before the value at `path` was decoded.
+DecodeError : [
+    Expected { path : List DecodePathSegment, expected : Str, found : Str },
+    Missing { path : List DecodePathSegment },
+]
 
 DecodeResult val : { result : Result val DecodeError, rest : List U8 }
 
@@ -59,6 +79,7 @@ DecoderFormatting has
     string : Decoder Str fmt | fmt has DecoderFormatting
     list : Decoder elem fmt -> Decoder (List elem) fmt | fmt has DecoderFormatting
     record : state, (state, Str -> [Keep (Decoder state fmt), Skip]), (state -> Result val DecodeError) -> Decoder val fmt | fmt has DecoderFormatting
+    tag : state, (state, Str, Nat -> [Keep (Decoder state fmt), Skip]), (state, Str -> Result val DecodeError) -> Decoder val fmt | fmt has DecoderFormatting
 
 custom : (List U8, fmt -> DecodeResult val) -> Decoder val fmt | fmt has DecoderFormatting
 custom = \decode -> @Decoder decode
@@ -76,6 +97,49 @@ fromBytes = \bytes, fmt ->
             if List.isEmpty rest then
                 when result is
                     Ok val -> Ok val
-                    Err TooShort -> Err TooShort
+                    Err (Expected problem) -> Err (Expected problem)
+                    Err (Missing problem) -> Err (Missing problem)
             else
                 Err (Leftover rest)
+
+## An `Expected` error for the value currently being decoded.
+expected : Str, Str -> DecodeError
+expected = \expectedStr, found -> Expected { path: [], expected: expectedStr, found }
+
+## A `Missing` error for the part of the value currently being decoded at `segment`.
+missing : DecodePathSegment -> DecodeError
+missing = \segment -> Missing { path: [segment] }
+
+## Records that `err` happened while decoding the part of a value at `segment`. Decoders of
+## records, tags and lists use this to wrap errors from decoding their fields and elements.
+prependPath : DecodeError, DecodePathSegment -> DecodeError
+prependPath = \err, segment ->
+    when err is
+        Expected { path, expected: expectedStr, found } ->
+            Expected { path: List.prepend path segment, expected: expectedStr, found }
+
+        Missing { path } -> Missing { path: List.prepend path segment }
+
+## Renders a decoding error as a message such as
+## `value.items[2]: expected U8, found "ab"]}`.
+errorToStr : DecodeError -> Str
+errorToStr = \err ->
+    when err is
+        Expected { path, expected: expectedStr, found } ->
+            pathToStr path
+            |> Str.concat ": expected "
+            |> Str.concat expectedStr
+            |> Str.concat ", found "
+            |> Str.concat found
+
+        Missing { path } ->
+            pathToStr path
+            |> Str.concat ": missing"
+
+pathToStr : List DecodePathSegment -> Str
+pathToStr = \path ->
+    List.walk path "value" \str, segment ->
+        when segment is
+            Field name -> str |> Str.concat "." |> Str.concat name
+            Index index -> str |> Str.concat "[" |> Str.concat (Num.toStr index) |> Str.concat "]"
+            Tag name -> str |> Str.concat "." |> Str.concat name
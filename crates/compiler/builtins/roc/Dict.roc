@@ -15,11 +15,13 @@ interface Dict
         insertAll,
         keepShared,
         removeAll,
+        isEq,
     ]
     imports [
-        Bool.{ Bool },
+        Bool.{ Bool, Eq },
         Result.{ Result },
         List,
+        Hash.{ Hash, Hasher },
     ]
 
 ## A [dictionary](https://en.wikipedia.org/wiki/Associative_array) that lets you can associate keys with values.
@@ -40,6 +42,10 @@ interface Dict
 ##         |> Dict.insert "Delhi" 16_787_941
 ##         |> Dict.insert "Amsterdam" 872_680
 ##
+## Dictionaries are hash tables, so [Dict.get], [Dict.insert], [Dict.remove] and [Dict.contains]
+## take constant time on average, no matter how many entries the dictionary holds. Keys must
+## implement the [Hash] ability, which Roc can generate for most types.
+##
 ## ### Accessing keys or values
 ##
 ## We can use [Dict.keys] and [Dict.values] functions to get only the keys or only the values.
@@ -63,118 +69,404 @@ interface Dict
 ## [Dict.remove] does: it removes an element and moves the most recent insertion into the vacated spot.
 ##
 ## This move is done as a performance optimization, and it lets [remove] have
-## [constant time complexity](https://en.wikipedia.org/wiki/Time_complexity#Constant_time). ##
+## [constant time complexity](https://en.wikipedia.org/wiki/Time_complexity#Constant_time).
 ##
 ## ### Equality
 ##
-## When comparing two dictionaries for equality, they are `==` only if their both their contents and their
-## orderings match. This preserves the property that if `dict1 == dict2`, you should be able to rely on
-## `fn dict1 == fn dict2` also being `True`, even if `fn` relies on the dictionary's ordering.
-Dict k v := List [Pair k v]
+## Two dictionaries are `==` when they have the same keys, and each key is associated with equal
+## values in both of them. The order of their associations does not matter, so two dictionaries
+## can be `==` while [Dict.keys] returns their keys in different orders.
+##
+## Keys are compared with `==` too: every type that implements [Hash] can also be compared
+## for equality.
+Dict k v := {
+    # Open addressing with linear probing. Each bucket is either 0 (empty), or one more than
+    # the index of its association in `data`.
+    buckets : List Nat,
+    # The associations, in the order described above.
+    data : List [Pair k v],
+}
+
+## Checks whether two dictionaries have the same keys, with equal values for each of them.
+## This is what `==` does for dictionaries.
+isEq : Dict k v, Dict k v -> Bool | k has Hash, v has Eq
+isEq = \xs, @Dict { data } ->
+    if len xs != List.len data then
+        False
+    else
+        List.all
+            data
+            (\Pair key value ->
+                when get xs key is
+                    Ok otherValue -> otherValue == value
+                    Err KeyNotFound -> False)
 
 ## An empty dictionary.
 empty : Dict k v
-empty = @Dict []
+empty = @Dict { buckets: [], data: [] }
 
 withCapacity : Nat -> Dict k v
-withCapacity = \n -> @Dict (List.withCapacity n)
+withCapacity = \n ->
+    @Dict {
+        buckets: List.repeat 0 (bucketCountFor n),
+        data: List.withCapacity n,
+    }
+
+get : Dict k v, k -> Result v [KeyNotFound]* | k has Hash
+get = \@Dict { buckets, data }, needle ->
+    when find buckets data needle is
+        Found _ dataIndex ->
+            when List.get data dataIndex is
+                Ok (Pair _ v) ->
+                    Ok v
 
-get : Dict k v, k -> Result v [KeyNotFound]*
-get = \@Dict list, needle ->
-    when List.findFirst list (\Pair key _ -> key == needle) is
-        Ok (Pair _ v) ->
-            Ok v
+                Err OutOfBounds ->
+                    Err KeyNotFound
 
-        Err NotFound ->
+        Vacant _ ->
             Err KeyNotFound
 
 walk : Dict k v, state, (state, k, v -> state) -> state
-walk = \@Dict list, initialState, transform ->
-    List.walk list initialState (\state, Pair k v -> transform state k v)
+walk = \@Dict { data }, initialState, transform ->
+    List.walk data initialState (\state, Pair k v -> transform state k v)
 
-insert : Dict k v, k, v -> Dict k v
-insert = \@Dict list, k, v ->
-    when List.findFirstIndex list (\Pair key _ -> key == k) is
-        Err NotFound ->
-            insertFresh (@Dict list) k v
+insert : Dict k v, k, v -> Dict k v | k has Hash
+insert = \@Dict { buckets, data }, key, value ->
+    when find buckets data key is
+        Found _ dataIndex ->
+            @Dict { buckets, data: List.set data dataIndex (Pair key value) }
 
-        Ok index ->
-            list
-            |> List.set index (Pair k v)
-            |> @Dict
+        Vacant bucketIndex ->
+            if isOverloaded (List.len data + 1) (List.len buckets) then
+                @Dict { buckets, data }
+                |> grow
+                |> appendAssociation key value
+            else
+                @Dict {
+                    buckets: List.set buckets bucketIndex (List.len data + 1),
+                    data: List.append data (Pair key value),
+                }
 
 len : Dict k v -> Nat
-len = \@Dict list ->
-    List.len list
-
-remove : Dict k v, k -> Dict k v
-remove = \@Dict list, key ->
-    when List.findFirstIndex list (\Pair k _ -> k == key) is
-        Err NotFound ->
-            @Dict list
-
-        Ok index ->
-            lastIndex = List.len list - 1
-
-            list
-            |> List.swap index lastIndex
-            |> List.dropLast
-            |> @Dict
-
-contains : Dict k v, k -> Bool
-contains = \@Dict list, needle ->
-    step = \_, Pair key _val ->
-        if key == needle then
-            Break {}
-        else
-            Continue {}
+len = \@Dict { data } ->
+    List.len data
+
+remove : Dict k v, k -> Dict k v | k has Hash
+remove = \@Dict { buckets, data }, key ->
+    when find buckets data key is
+        Vacant _ ->
+            @Dict { buckets, data }
+
+        Found bucketIndex dataIndex ->
+            lastIndex = List.len data - 1
+
+            # The last association moves into the spot of the removed one.
+            newBuckets =
+                buckets
+                |> removeBucket data bucketIndex
+                |> moveAssociation data lastIndex dataIndex
 
-    when List.iterate list {} step is
-        Continue _ -> False
-        Break _ -> True
+            newData =
+                data
+                |> List.swap dataIndex lastIndex
+                |> List.dropLast
 
-single : k, v -> Dict k v
+            @Dict { buckets: newBuckets, data: newData }
+
+contains : Dict k v, k -> Bool | k has Hash
+contains = \@Dict { buckets, data }, needle ->
+    when find buckets data needle is
+        Found _ _ -> True
+        Vacant _ -> False
+
+single : k, v -> Dict k v | k has Hash
 single = \key, value ->
-    @Dict [Pair key value]
+    insert empty key value
 
 ## Returns a [List] of the dictionary's keys.
 keys : Dict k v -> List k
-keys = \@Dict list ->
-    List.map list (\Pair k _ -> k)
+keys = \@Dict { data } ->
+    List.map data (\Pair k _ -> k)
 
 ## Returns a [List] of the Dict's values
 values : Dict k v -> List v
-values = \@Dict list ->
-    List.map list (\Pair _ v -> v)
+values = \@Dict { data } ->
+    List.map data (\Pair _ v -> v)
 
 # union : Dict k v, Dict k v -> Dict k v
-insertAll : Dict k v, Dict k v -> Dict k v
-insertAll = \xs, @Dict ys ->
-    List.walk ys xs (\state, Pair k v -> Dict.insertIfVacant state k v)
+insertAll : Dict k v, Dict k v -> Dict k v | k has Hash
+insertAll = \xs, @Dict { data } ->
+    List.walk data xs (\state, Pair k v -> insertIfVacant state k v)
 
 # intersection : Dict k v, Dict k v -> Dict k v
-keepShared : Dict k v, Dict k v -> Dict k v
-keepShared = \@Dict xs, ys ->
-    List.keepIf xs (\Pair k _ -> Dict.contains ys k)
-    |> @Dict
+keepShared : Dict k v, Dict k v -> Dict k v | k has Hash
+keepShared = \@Dict { data }, ys ->
+    List.walk
+        data
+        empty
+        (\state, Pair k v ->
+            if contains ys k then
+                insertFresh state k v
+            else
+                state)
 
 # difference : Dict k v, Dict k v -> Dict k v
-removeAll : Dict k v, Dict k v -> Dict k v
-removeAll = \xs, @Dict ys ->
-    List.walk ys xs (\state, Pair k _ -> Dict.remove state k)
+removeAll : Dict k v, Dict k v -> Dict k v | k has Hash
+removeAll = \xs, @Dict { data } ->
+    List.walk data xs (\state, Pair k _ -> remove state k)
 
 ## Internal helper function to insert a new association
 ##
 ## Precondition: `k` should not exist in the Dict yet.
-insertFresh : Dict k v, k, v -> Dict k v
-insertFresh = \@Dict list, k, v ->
-    list
-    |> List.append (Pair k v)
-    |> @Dict
+insertFresh : Dict k v, k, v -> Dict k v | k has Hash
+insertFresh = \dict, key, value ->
+    if isOverloaded (len dict + 1) (bucketCount dict) then
+        appendAssociation (grow dict) key value
+    else
+        appendAssociation dict key value
+
+## Internal helper: adds a new association at the end of `data`.
+##
+## Precondition: `k` should not exist in the Dict yet, and there is room for one more association.
+appendAssociation : Dict k v, k, v -> Dict k v | k has Hash
+appendAssociation = \@Dict { buckets, data }, key, value ->
+    bucketIndex = findVacant buckets (bucketIndexFor key (List.len buckets))
+
+    @Dict {
+        buckets: List.set buckets bucketIndex (List.len data + 1),
+        data: List.append data (Pair key value),
+    }
 
-insertIfVacant : Dict k v, k, v -> Dict k v
+insertIfVacant : Dict k v, k, v -> Dict k v | k has Hash
 insertIfVacant = \dict, key, value ->
-    if Dict.contains dict key then
+    if contains dict key then
         dict
     else
-        Dict.insert dict key value
+        insertFresh dict key value
+
+## Internal helper: finds the bucket and index in `data` of the association for `key`, or the
+## empty bucket where an association for `key` would go.
+find : List Nat, List [Pair k v], k -> [Found Nat Nat, Vacant Nat] | k has Hash
+find = \buckets, data, key ->
+    if List.isEmpty buckets then
+        Vacant 0
+    else
+        findHelp buckets data key (bucketIndexFor key (List.len buckets))
+
+findHelp : List Nat, List [Pair k v], k, Nat -> [Found Nat Nat, Vacant Nat] | k has Hash
+findHelp = \buckets, data, key, bucketIndex ->
+    when List.get buckets bucketIndex is
+        Ok 0 | Err OutOfBounds ->
+            Vacant bucketIndex
+
+        Ok stored ->
+            dataIndex = stored - 1
+
+            when List.get data dataIndex is
+                Ok (Pair storedKey _) if storedKey == key ->
+                    Found bucketIndex dataIndex
+
+                _ ->
+                    findHelp buckets data key (nextBucket bucketIndex (List.len buckets))
+
+## Internal helper: the first empty bucket at or after `bucketIndex`.
+findVacant : List Nat, Nat -> Nat
+findVacant = \buckets, bucketIndex ->
+    when List.get buckets bucketIndex is
+        Ok 0 | Err OutOfBounds ->
+            bucketIndex
+
+        Ok _ ->
+            findVacant buckets (nextBucket bucketIndex (List.len buckets))
+
+## Internal helper: empties the bucket at `hole`, then moves the associations after it back
+## where needed, so that every association is still reachable from the bucket its key hashes to.
+removeBucket : List Nat, List [Pair k v], Nat -> List Nat | k has Hash
+removeBucket = \buckets, data, hole ->
+    removeBucketHelp (List.set buckets hole 0) data hole (nextBucket hole (List.len buckets))
+
+removeBucketHelp : List Nat, List [Pair k v], Nat, Nat -> List Nat | k has Hash
+removeBucketHelp = \buckets, data, hole, bucketIndex ->
+    when List.get buckets bucketIndex is
+        Ok 0 | Err OutOfBounds ->
+            buckets
+
+        Ok stored ->
+            count = List.len buckets
+            next = nextBucket bucketIndex count
+
+            when List.get data (stored - 1) is
+                Ok (Pair key _) ->
+                    home = bucketIndexFor key count
+
+                    if distance home bucketIndex count >= distance hole bucketIndex count then
+                        buckets
+                        |> List.set hole stored
+                        |> List.set bucketIndex 0
+                        |> removeBucketHelp data bucketIndex next
+                    else
+                        removeBucketHelp buckets data hole next
+
+                Err OutOfBounds ->
+                    buckets
+
+## Internal helper: points the bucket of the association at index `from` in `data` to index `to`.
+moveAssociation : List Nat, List [Pair k v], Nat, Nat -> List Nat | k has Hash
+moveAssociation = \buckets, data, from, to ->
+    if from == to then
+        buckets
+    else
+        when List.get data from is
+            Ok (Pair key _) ->
+                bucketIndex = findStored buckets (from + 1) (bucketIndexFor key (List.len buckets))
+
+                List.set buckets bucketIndex (to + 1)
+
+            Err OutOfBounds ->
+                buckets
+
+findStored : List Nat, Nat, Nat -> Nat
+findStored = \buckets, stored, bucketIndex ->
+    when List.get buckets bucketIndex is
+        Ok current if current == stored ->
+            bucketIndex
+
+        Ok _ ->
+            findStored buckets stored (nextBucket bucketIndex (List.len buckets))
+
+        Err OutOfBounds ->
+            bucketIndex
+
+## Internal helper: doubles the number of buckets.
+grow : Dict k v -> Dict k v | k has Hash
+grow = \@Dict { buckets, data } ->
+    newCount =
+        if List.isEmpty buckets then
+            minBucketCount
+        else
+            List.len buckets * 2
+
+    @Dict { buckets: rehash data newCount, data }
+
+rehash : List [Pair k v], Nat -> List Nat | k has Hash
+rehash = \data, count ->
+    rehashHelp data (List.repeat 0 count) 0
+
+rehashHelp : List [Pair k v], List Nat, Nat -> List Nat | k has Hash
+rehashHelp = \data, buckets, dataIndex ->
+    when List.get data dataIndex is
+        Ok (Pair key _) ->
+            bucketIndex = findVacant buckets (bucketIndexFor key (List.len buckets))
+            newBuckets = List.set buckets bucketIndex (dataIndex + 1)
+
+            rehashHelp data newBuckets (dataIndex + 1)
+
+        Err OutOfBounds ->
+            buckets
+
+bucketCount : Dict k v -> Nat
+bucketCount = \@Dict { buckets } ->
+    List.len buckets
+
+minBucketCount : Nat
+minBucketCount = 8
+
+## Internal helper: the number of buckets needed to hold `n` associations.
+bucketCountFor : Nat -> Nat
+bucketCountFor = \n ->
+    if n == 0 then
+        0
+    else
+        bucketCountForHelp minBucketCount n
+
+bucketCountForHelp : Nat, Nat -> Nat
+bucketCountForHelp = \count, n ->
+    if isOverloaded n count then
+        bucketCountForHelp (count * 2) n
+    else
+        count
+
+# Linear probing gets slow as buckets fill up, so we use at most 3/4 of them.
+isOverloaded : Nat, Nat -> Bool
+isOverloaded = \associations, count ->
+    associations * 4 > count * 3
+
+# The number of buckets is always a power of two, so we can mask instead of taking a remainder.
+bucketIndexFor : k, Nat -> Nat | k has Hash
+bucketIndexFor = \key, count ->
+    hashKey key
+    |> Num.toNat
+    |> Num.bitwiseAnd (count - 1)
+
+nextBucket : Nat, Nat -> Nat
+nextBucket = \bucketIndex, count ->
+    Num.bitwiseAnd (bucketIndex + 1) (count - 1)
+
+# How many buckets a probe takes to get from `from` to `to`.
+distance : Nat, Nat, Nat -> Nat
+distance = \from, to, count ->
+    Num.bitwiseAnd (to + count - from) (count - 1)
+
+hashKey : k -> U64 | k has Hash
+hashKey = \key ->
+    createLowLevelHasher {}
+    |> Hash.hash key
+    |> Hash.complete
+
+# A hasher that hashes everything it is given right away, with the wyhash from the builtins
+# bitcode. Its state is the hash so far, which seeds the hash of whatever is added next.
+LowLevelHasher := { state : U64 } has [
+         Hasher {
+             addBytes: hasherAddBytes,
+             addU8: hasherAddU8,
+             addU16: hasherAddU16,
+             addU32: hasherAddU32,
+             addU64: hasherAddU64,
+             addU128: hasherAddU128,
+             complete: hasherComplete,
+         },
+     ]
+
+# The seed is fixed, so hashes (and with them, iteration orders) are the same on every run.
+createLowLevelHasher : {} -> LowLevelHasher
+createLowLevelHasher = \{} ->
+    @LowLevelHasher { state: 0x2d35_8dcc_aa6c_78a5 }
+
+# Hashes the bytes with the given seed, using the wyhash from the builtins bitcode.
+hashBytesLowlevel : List U8, U64 -> U64
+
+# Hashes the 8 little-endian bytes of a U64 with the given seed, without allocating a list.
+hashU64Lowlevel : U64, U64 -> U64
+
+hasherAddBytes : LowLevelHasher, List U8 -> LowLevelHasher
+hasherAddBytes = \@LowLevelHasher { state }, bytes ->
+    @LowLevelHasher { state: hashBytesLowlevel bytes state }
+
+hasherAddU8 : LowLevelHasher, U8 -> LowLevelHasher
+hasherAddU8 = \hasher, n ->
+    hasherAddU64 hasher (Num.toU64 n)
+
+hasherAddU16 : LowLevelHasher, U16 -> LowLevelHasher
+hasherAddU16 = \hasher, n ->
+    hasherAddU64 hasher (Num.toU64 n)
+
+hasherAddU32 : LowLevelHasher, U32 -> LowLevelHasher
+hasherAddU32 = \hasher, n ->
+    hasherAddU64 hasher (Num.toU64 n)
+
+hasherAddU64 : LowLevelHasher, U64 -> LowLevelHasher
+hasherAddU64 = \@LowLevelHasher { state }, n ->
+    @LowLevelHasher { state: hashU64Lowlevel n state }
+
+hasherAddU128 : LowLevelHasher, U128 -> LowLevelHasher
+hasherAddU128 = \hasher, n ->
+    lower = Num.toU64 n
+    upper = Num.toU64 (Num.shiftRightZfBy n 64)
+
+    hasher
+    |> hasherAddU64 lower
+    |> hasherAddU64 upper
+
+hasherComplete : LowLevelHasher -> U64
+hasherComplete = \@LowLevelHasher { state } ->
+    state
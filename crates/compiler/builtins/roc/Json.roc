@@ -16,6 +16,7 @@ interface Json
         Decode,
         Decode.{
             DecoderFormatting,
+            DecodeError,
             DecodeResult,
         },
     ]
@@ -59,6 +60,7 @@ Json := {} has [
              string: decodeString,
              list: decodeList,
              record: decodeRecord,
+             tag: decodeTag,
          },
      ]
 
@@ -213,91 +215,91 @@ decodeU8 = Decode.custom \bytes, @Json {} ->
 
     when Str.fromUtf8 taken |> Result.try Str.toU8 is
         Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
+        Err _ -> { result: Err (Decode.expected "U8" (describeInput bytes)), rest }
 
 decodeU16 = Decode.custom \bytes, @Json {} ->
     { taken, rest } = takeDigits bytes
 
     when Str.fromUtf8 taken |> Result.try Str.toU16 is
         Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
+        Err _ -> { result: Err (Decode.expected "U16" (describeInput bytes)), rest }
 
 decodeU32 = Decode.custom \bytes, @Json {} ->
     { taken, rest } = takeDigits bytes
 
     when Str.fromUtf8 taken |> Result.try Str.toU32 is
         Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
+        Err _ -> { result: Err (Decode.expected "U32" (describeInput bytes)), rest }
 
 decodeU64 = Decode.custom \bytes, @Json {} ->
     { taken, rest } = takeDigits bytes
 
     when Str.fromUtf8 taken |> Result.try Str.toU64 is
         Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
+        Err _ -> { result: Err (Decode.expected "U64" (describeInput bytes)), rest }
 
 decodeU128 = Decode.custom \bytes, @Json {} ->
     { taken, rest } = takeDigits bytes
 
     when Str.fromUtf8 taken |> Result.try Str.toU128 is
         Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
+        Err _ -> { result: Err (Decode.expected "U128" (describeInput bytes)), rest }
 
 decodeI8 = Decode.custom \bytes, @Json {} ->
     { taken, rest } = takeDigits bytes
 
     when Str.fromUtf8 taken |> Result.try Str.toI8 is
         Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
+        Err _ -> { result: Err (Decode.expected "I8" (describeInput bytes)), rest }
 
 decodeI16 = Decode.custom \bytes, @Json {} ->
     { taken, rest } = takeDigits bytes
 
     when Str.fromUtf8 taken |> Result.try Str.toI16 is
         Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
+        Err _ -> { result: Err (Decode.expected "I16" (describeInput bytes)), rest }
 
 decodeI32 = Decode.custom \bytes, @Json {} ->
     { taken, rest } = takeDigits bytes
 
     when Str.fromUtf8 taken |> Result.try Str.toI32 is
         Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
+        Err _ -> { result: Err (Decode.expected "I32" (describeInput bytes)), rest }
 
 decodeI64 = Decode.custom \bytes, @Json {} ->
     { taken, rest } = takeDigits bytes
 
     when Str.fromUtf8 taken |> Result.try Str.toI64 is
         Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
+        Err _ -> { result: Err (Decode.expected "I64" (describeInput bytes)), rest }
 
 decodeI128 = Decode.custom \bytes, @Json {} ->
     { taken, rest } = takeDigits bytes
 
     when Str.fromUtf8 taken |> Result.try Str.toI128 is
         Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
+        Err _ -> { result: Err (Decode.expected "I128" (describeInput bytes)), rest }
 
 decodeF32 = Decode.custom \bytes, @Json {} ->
     { taken, rest } = takeFloat bytes
 
     when Str.fromUtf8 taken |> Result.try Str.toF32 is
         Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
+        Err _ -> { result: Err (Decode.expected "F32" (describeInput bytes)), rest }
 
 decodeF64 = Decode.custom \bytes, @Json {} ->
     { taken, rest } = takeFloat bytes
 
     when Str.fromUtf8 taken |> Result.try Str.toF64 is
         Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
+        Err _ -> { result: Err (Decode.expected "F64" (describeInput bytes)), rest }
 
 decodeDec = Decode.custom \bytes, @Json {} ->
     { taken, rest } = takeFloat bytes
 
     when Str.fromUtf8 taken |> Result.try Str.toDec is
         Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
+        Err _ -> { result: Err (Decode.expected "Dec" (describeInput bytes)), rest }
 
 decodeBool = Decode.custom \bytes, @Json {} ->
     { before: maybeFalse, others: afterFalse } = List.split bytes 5
@@ -316,7 +318,7 @@ decodeBool = Decode.custom \bytes, @Json {} ->
         then
             { result: Ok True, rest: afterTrue }
         else
-            { result: Err TooShort, rest: bytes }
+            { result: Err (Decode.expected "Bool" (describeInput bytes)), rest: bytes }
 
 jsonString : List U8 -> DecodeResult Str
 jsonString = \bytes ->
@@ -334,9 +336,9 @@ jsonString = \bytes ->
 
                 { result: Ok s, rest: afterEndingQuote }
 
-            Err _ -> { result: Err TooShort, rest }
+            Err _ -> { result: Err (Decode.expected "valid UTF-8" (describeInput afterStartingQuote)), rest }
     else
-        { result: Err TooShort, rest: bytes }
+        { result: Err (Decode.expected "Str" (describeInput bytes)), rest: bytes }
 
 decodeString = Decode.custom \bytes, @Json {} ->
     jsonString bytes
@@ -357,7 +359,7 @@ decodeList = \decodeElem -> Decode.custom \bytes, @Json {} ->
                             else
                                 Done (List.append accum val) rest
 
-                        Err e -> Errored e rest
+                        Err e -> Errored (Decode.prependPath e (Index (List.len accum))) rest
 
         { before, others: afterStartingBrace } = List.split bytes 1
 
@@ -375,9 +377,9 @@ decodeList = \decodeElem -> Decode.custom \bytes, @Json {} ->
                     then
                         { result: Ok vals, rest: afterEndingBrace }
                     else
-                        { result: Err TooShort, rest }
+                        { result: Err (expectedChar (asciiByte ']') rest), rest }
         else
-            { result: Err TooShort, rest: bytes }
+            { result: Err (Decode.expected "List" (describeInput bytes)), rest: bytes }
 
 parseExactChar : List U8, U8 -> DecodeResult {}
 parseExactChar = \bytes, char ->
@@ -388,9 +390,35 @@ parseExactChar = \bytes, char ->
             then
                 { result: Ok {}, rest: (List.split bytes 1).others }
             else
-                { result: Err TooShort, rest: bytes }
+                { result: Err (expectedChar char bytes), rest: bytes }
 
-        Err _ -> { result: Err TooShort, rest: bytes }
+        Err _ -> { result: Err (expectedChar char bytes), rest: bytes }
+
+expectedChar : U8, List U8 -> DecodeError
+expectedChar = \char, bytes ->
+    when Str.fromUtf8 [char] is
+        Ok str -> Decode.expected (Str.concat "'" str |> Str.concat "'") (describeInput bytes)
+        Err _ -> Decode.expected "a delimiter" (describeInput bytes)
+
+# Describes the start of `bytes` for decoding errors, e.g. `"ab"]}` or `end of input`.
+describeInput : List U8 -> Str
+describeInput = \bytes ->
+    if List.isEmpty bytes then
+        "end of input"
+    else
+        when Str.fromUtf8 (List.takeFirst bytes (charBoundaryBefore bytes 20)) is
+            Ok str -> str
+            Err _ -> "bytes that are not valid UTF-8"
+
+# The last index at or before `index` that does not split up a UTF-8 encoded character.
+charBoundaryBefore : List U8, Nat -> Nat
+charBoundaryBefore = \bytes, index ->
+    when List.get bytes index is
+        # continuation bytes look like 0b10xx_xxxx
+        Ok byte if index > 0 && Num.bitwiseAnd byte 0xC0 == 0x80 ->
+            charBoundaryBefore bytes (index - 1)
+
+        _ -> index
 
 openBrace : List U8 -> DecodeResult {}
 openBrace = \bytes -> parseExactChar bytes (asciiByte '{')
@@ -398,11 +426,18 @@ openBrace = \bytes -> parseExactChar bytes (asciiByte '{')
 closingBrace : List U8 -> DecodeResult {}
 closingBrace = \bytes -> parseExactChar bytes (asciiByte '}')
 
+openBracket : List U8 -> DecodeResult {}
+openBracket = \bytes -> parseExactChar bytes (asciiByte '[')
+
+closingBracket : List U8 -> DecodeResult {}
+closingBracket = \bytes -> parseExactChar bytes (asciiByte ']')
+
 recordKey : List U8 -> DecodeResult Str
 recordKey = \bytes -> jsonString bytes
 
+# TODO: skip over any JSON value, so that unknown fields can be ignored
 anything : List U8 -> DecodeResult {}
-anything = \bytes -> { result: Err TooShort, rest: bytes }
+anything = \bytes -> { result: Err (Decode.expected "a known field" (describeInput bytes)), rest: bytes }
 
 colon : List U8 -> DecodeResult {}
 colon = \bytes -> parseExactChar bytes (asciiByte ':')
@@ -410,6 +445,12 @@ colon = \bytes -> parseExactChar bytes (asciiByte ':')
 comma : List U8 -> DecodeResult {}
 comma = \bytes -> parseExactChar bytes (asciiByte ',')
 
+inField : DecodeResult a, Str -> DecodeResult a
+inField = \{ result, rest }, key ->
+    when result is
+        Ok val -> { result: Ok val, rest }
+        Err e -> { result: Err (Decode.prependPath e (Field key)), rest }
+
 tryDecode : DecodeResult a, ({ val : a, rest : List U8 } -> DecodeResult b) -> DecodeResult b
 tryDecode = \{ result, rest }, mapper ->
     when result is
@@ -425,7 +466,7 @@ decodeRecord = \initialState, stepField, finalizer -> Decode.custom \bytes, @Jso
                     (
                         when stepper state key is
                             Skip ->
-                                { rest: beforeCommaOrBreak } <- afterColonBytes |> anything |> tryDecode
+                                { rest: beforeCommaOrBreak } <- afterColonBytes |> anything |> inField key |> tryDecode
                                 { result: Ok state, rest: beforeCommaOrBreak }
 
                             Keep decoder ->
@@ -447,3 +488,51 @@ decodeRecord = \initialState, stepField, finalizer -> Decode.custom \bytes, @Jso
         when finalizer endStateResult is
             Ok val -> { result: Ok val, rest: afterRecordBytes }
             Err e -> { result: Err e, rest: afterRecordBytes }
+
+decodeTag = \initialState, stepPayload, finalizer -> Decode.custom \bytes, @Json {} ->
+        # Decodes `A v1 v2` from `{"A":[v1,v2]}`, the shape produced by `encodeTag`.
+        # NB: the stepper function must be passed explicitly until #2894 is resolved.
+        decodePayload = \stepper, state, name, index, payloadBytes ->
+            when stepper state name index is
+                Skip ->
+                    err =
+                        Decode.expected "no more payload values" (describeInput payloadBytes)
+                        |> Decode.prependPath (Index index)
+                        |> Decode.prependPath (Tag name)
+
+                    { result: Err err, rest: payloadBytes }
+
+                Keep decoder ->
+                    { val: newState, rest: beforeCommaOrBreak } <- Decode.decodeWith payloadBytes decoder (@Json {}) |> tryDecode
+
+                    { result: commaResult, rest: nextBytes } = comma beforeCommaOrBreak
+
+                    when commaResult is
+                        Ok {} -> decodePayload stepPayload newState name (index + 1) nextBytes
+                        Err _ -> { result: Ok newState, rest: nextBytes }
+
+        { rest: afterBraceBytes } <- bytes |> openBrace |> tryDecode
+
+        { val: name, rest: afterNameBytes } <- recordKey afterBraceBytes |> tryDecode
+
+        { rest: afterColonBytes } <- colon afterNameBytes |> tryDecode
+
+        { rest: afterBracketBytes } <- afterColonBytes |> openBracket |> tryDecode
+
+        { val: endState, rest: beforeClosingBracketBytes } <- tryDecode
+                (
+                    when List.get afterBracketBytes 0 is
+                        Ok 93 -> # 93 = ]
+                            { result: Ok initialState, rest: afterBracketBytes }
+
+                        _ ->
+                            decodePayload stepPayload initialState name 0 afterBracketBytes
+                )
+
+        { rest: afterPayloadBytes } <- beforeClosingBracketBytes |> closingBracket |> tryDecode
+
+        { rest: afterTagBytes } <- afterPayloadBytes |> closingBrace |> tryDecode
+
+        when finalizer endState name is
+            Ok val -> { result: Ok val, rest: afterTagBytes }
+            Err e -> { result: Err e, rest: afterTagBytes }
@@ -13,11 +13,18 @@ interface Set
         union,
         intersection,
         difference,
+        isEq,
     ]
-    imports [List, Bool.{ Bool }, Dict.{ Dict }]
+    imports [List, Bool.{ Bool }, Dict.{ Dict }, Hash.{ Hash }]
 
 Set k := Dict.Dict k {}
 
+## Checks whether two sets have the same elements, no matter in which order those were inserted.
+## This is what `==` does for sets.
+isEq : Set k, Set k -> Bool | k has Hash
+isEq = \@Set xs, @Set ys ->
+    Dict.isEq xs ys
+
 fromDict : Dict k {} -> Set k
 fromDict = \dict -> @Set dict
 
@@ -28,14 +35,14 @@ toDict = \@Set dict -> dict
 empty : Set k
 empty = fromDict Dict.empty
 
-single : k -> Set k
+single : k -> Set k | k has Hash
 single = \key ->
     @Set (Dict.single key {})
 
 ## Make sure never to insert a *NaN* to a [Set]! Because *NaN* is defined to be
 ## unequal to *NaN*, adding a *NaN* results in an entry that can never be
 ## retrieved or removed from the [Set].
-insert : Set k, k -> Set k
+insert : Set k, k -> Set k | k has Hash
 insert = \@Set dict, key ->
     dict
     |> Dict.insert key {}
@@ -75,11 +82,11 @@ expect
     actual == 3
 
 ## Drops the given element from the set.
-remove : Set k, k -> Set k
+remove : Set k, k -> Set k | k has Hash
 remove = \@Set dict, key ->
     @Set (Dict.remove dict key)
 
-contains : Set k, k -> Bool
+contains : Set k, k -> Bool | k has Hash
 contains = \set, key ->
     set
     |> Set.toDict
@@ -89,21 +96,21 @@ toList : Set k -> List k
 toList = \@Set dict ->
     Dict.keys dict
 
-fromList : List k -> Set k
+fromList : List k -> Set k | k has Hash
 fromList = \list ->
     initial = @Set (Dict.withCapacity (List.len list))
 
     List.walk list initial \set, key -> Set.insert set key
 
-union : Set k, Set k -> Set k
+union : Set k, Set k -> Set k | k has Hash
 union = \@Set dict1, @Set dict2 ->
     @Set (Dict.insertAll dict1 dict2)
 
-intersection : Set k, Set k -> Set k
+intersection : Set k, Set k -> Set k | k has Hash
 intersection = \@Set dict1, @Set dict2 ->
     @Set (Dict.keepShared dict1 dict2)
 
-difference : Set k, Set k -> Set k
+difference : Set k, Set k -> Set k | k has Hash
 difference = \@Set dict1, @Set dict2 ->
     @Set (Dict.removeAll dict1 dict2)
 
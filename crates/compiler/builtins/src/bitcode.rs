@@ -379,6 +379,9 @@ pub const LIST_PREPEND: &str = "roc_builtins.list.prepend";
 pub const LIST_APPEND_UNSAFE: &str = "roc_builtins.list.append_unsafe";
 pub const LIST_RESERVE: &str = "roc_builtins.list.reserve";
 
+pub const DICT_HASH_BYTES: &str = "roc_builtins.dict.hash_bytes";
+pub const DICT_HASH_U64: &str = "roc_builtins.dict.hash_u64";
+
 pub const DEC_FROM_STR: &str = "roc_builtins.dec.from_str";
 pub const DEC_TO_STR: &str = "roc_builtins.dec.to_str";
 pub const DEC_FROM_F64: &str = "roc_builtins.dec.from_f64";
@@ -403,7 +406,12 @@ pub const UTILS_DECREF: &str = "roc_builtins.utils.decref";
 pub const UTILS_DECREF_CHECK_NULL: &str = "roc_builtins.utils.decref_check_null";
 
 pub const UTILS_EXPECT_FAILED_START: &str = "roc_builtins.utils.expect_failed_start";
+pub const UTILS_EXPECT_FAILED_START_SHARED_FILE: &str =
+    "roc_builtins.utils.expect_failed_start_shared_file";
 pub const UTILS_EXPECT_FAILED_FINALIZE: &str = "roc_builtins.utils.expect_failed_finalize";
+pub const UTILS_DBG_BYTES: &str = "roc_builtins.utils.dbg_bytes";
+pub const UTILS_DBG_STR: &str = "roc_builtins.utils.dbg_str";
+pub const UTILS_DBG_LIST: &str = "roc_builtins.utils.dbg_list";
 
 pub const UTILS_LONGJMP: &str = "longjmp";
 pub const UTILS_SETJMP: &str = "setjmp";
@@ -469,6 +469,13 @@ impl IAbilitiesStore<Resolved> {
     pub fn get_resolved(&self, id: SpecializationId) -> Option<Symbol> {
         self.resolved_specializations.get(&id).copied()
     }
+
+    /// Whether any specialization the store knows about is named by a symbol matching `pred`.
+    pub fn mentions_symbol(&self, mut pred: impl FnMut(&Symbol) -> bool) -> bool {
+        self.specialization_to_root.keys().any(&mut pred)
+            || self.specializations.values().any(|info| pred(&info.symbol))
+            || self.resolved_specializations.values().any(pred)
+    }
 }
 
 impl IAbilitiesStore<Pending> {
@@ -660,3 +667,281 @@ impl IAbilitiesStore<Pending> {
         }
     }
 }
+
+/// Lengths of the flattened parts of a serialized [AbilitiesStore].
+#[derive(Clone, Copy, Debug)]
+struct AbilitiesStoreHeader {
+    members_of_ability: u64,
+    specialization_to_root: u64,
+    ability_members: u64,
+    declared_implementations: u64,
+    specializations: u64,
+    specialization_lambda_sets: u64,
+    resolved_specializations: u64,
+    next_specialization_id: u64,
+}
+
+/// A [MemberSpecializationInfo], with its lambda sets stored elsewhere as the given slice.
+#[derive(Clone, Copy, Debug)]
+struct SerializedSpecialization {
+    symbol: Symbol,
+    lambda_sets_start: u32,
+    lambda_sets_length: u32,
+}
+
+impl SerializedSpecialization {
+    fn new(
+        info: &MemberSpecializationInfo<Resolved>,
+        lambda_sets: &mut Vec<(u8, Variable)>,
+    ) -> Self {
+        let lambda_sets_start = lambda_sets.len() as u32;
+        lambda_sets.extend(
+            info.specialization_lambda_sets
+                .iter()
+                .map(|(r, v)| (*r, *v)),
+        );
+
+        Self {
+            symbol: info.symbol,
+            lambda_sets_start,
+            lambda_sets_length: info.specialization_lambda_sets.len() as u32,
+        }
+    }
+
+    fn to_info(self, lambda_sets: &[(u8, Variable)]) -> MemberSpecializationInfo<Resolved> {
+        let start = self.lambda_sets_start as usize;
+        let lambda_sets = &lambda_sets[start..][..self.lambda_sets_length as usize];
+
+        MemberSpecializationInfo::new(self.symbol, lambda_sets.iter().copied().collect())
+    }
+}
+
+/// A [ResolvedImpl], flattened the same way as [SerializedSpecialization].
+#[derive(Clone, Copy, Debug)]
+enum SerializedResolvedImpl {
+    Impl(SerializedSpecialization),
+    Derived,
+    Error,
+}
+
+impl IAbilitiesStore<Resolved> {
+    /// Writes the store in a form that [Self::deserialize] can read back, so that it can be cached
+    /// together with the [Subs](roc_types::subs::Subs) its variables live in.
+    pub fn serialize(
+        &self,
+        writer: &mut impl std::io::Write,
+        written: usize,
+    ) -> std::io::Result<usize> {
+        use roc_types::subs::Subs;
+
+        let members_of_ability: Vec<(Symbol, Symbol)> = self
+            .members_of_ability
+            .iter()
+            .flat_map(|(ability, members)| members.iter().map(|member| (*ability, *member)))
+            .collect();
+        let specialization_to_root: Vec<(Symbol, ImplKey)> = self
+            .specialization_to_root
+            .iter()
+            .map(|(symbol, key)| (*symbol, *key))
+            .collect();
+        let ability_members: Vec<(Symbol, Symbol, Region, Variable)> = self
+            .ability_members
+            .iter()
+            .map(|(member, data)| (*member, data.parent_ability, data.region, data.typ.0))
+            .collect();
+        let declared_implementations: Vec<(ImplKey, MemberImpl)> = self
+            .declared_implementations
+            .iter()
+            .map(|(key, member_impl)| (*key, *member_impl))
+            .collect();
+
+        let mut lambda_sets = Vec::new();
+        let specializations: Vec<(Symbol, SerializedSpecialization)> = self
+            .specializations
+            .iter()
+            .map(|(symbol, info)| {
+                (
+                    *symbol,
+                    SerializedSpecialization::new(info, &mut lambda_sets),
+                )
+            })
+            .collect();
+
+        let resolved_specializations: Vec<(SpecializationId, Symbol)> = self
+            .resolved_specializations
+            .iter()
+            .map(|(id, symbol)| (*id, *symbol))
+            .collect();
+
+        let header = AbilitiesStoreHeader {
+            members_of_ability: members_of_ability.len() as u64,
+            specialization_to_root: specialization_to_root.len() as u64,
+            ability_members: ability_members.len() as u64,
+            declared_implementations: declared_implementations.len() as u64,
+            specializations: specializations.len() as u64,
+            specialization_lambda_sets: lambda_sets.len() as u64,
+            resolved_specializations: resolved_specializations.len() as u64,
+            next_specialization_id: self.next_specialization_id.get() as u64,
+        };
+
+        let written = Subs::serialize_slice(&[header], writer, written)?;
+        let written = Subs::serialize_slice(&members_of_ability, writer, written)?;
+        let written = Subs::serialize_slice(&specialization_to_root, writer, written)?;
+        let written = Subs::serialize_slice(&ability_members, writer, written)?;
+        let written = Subs::serialize_slice(&declared_implementations, writer, written)?;
+        let written = Subs::serialize_slice(&specializations, writer, written)?;
+        let written = Subs::serialize_slice(&lambda_sets, writer, written)?;
+        let written = Subs::serialize_slice(&resolved_specializations, writer, written)?;
+
+        Ok(written)
+    }
+
+    pub fn deserialize(bytes: &[u8], offset: usize) -> (Self, usize) {
+        use roc_types::subs::Subs;
+
+        let (header, offset) = Subs::deserialize_slice::<AbilitiesStoreHeader>(bytes, 1, offset);
+        let header = header[0];
+
+        let (members_of_ability, offset) = Subs::deserialize_slice::<(Symbol, Symbol)>(
+            bytes,
+            header.members_of_ability as usize,
+            offset,
+        );
+        let (specialization_to_root, offset) = Subs::deserialize_slice::<(Symbol, ImplKey)>(
+            bytes,
+            header.specialization_to_root as usize,
+            offset,
+        );
+        let (ability_members, offset) = Subs::deserialize_slice::<(Symbol, Symbol, Region, Variable)>(
+            bytes,
+            header.ability_members as usize,
+            offset,
+        );
+        let (declared_implementations, offset) = Subs::deserialize_slice::<(ImplKey, MemberImpl)>(
+            bytes,
+            header.declared_implementations as usize,
+            offset,
+        );
+        let (specializations, offset) = Subs::deserialize_slice::<(Symbol, SerializedSpecialization)>(
+            bytes,
+            header.specializations as usize,
+            offset,
+        );
+        let (lambda_sets, offset) = Subs::deserialize_slice::<(u8, Variable)>(
+            bytes,
+            header.specialization_lambda_sets as usize,
+            offset,
+        );
+        let (resolved_specializations, offset) =
+            Subs::deserialize_slice::<(SpecializationId, Symbol)>(
+                bytes,
+                header.resolved_specializations as usize,
+                offset,
+            );
+
+        let mut store = Self::default();
+
+        for (ability, member) in members_of_ability {
+            store
+                .members_of_ability
+                .entry(*ability)
+                .or_default()
+                .push(*member);
+        }
+
+        store.specialization_to_root = specialization_to_root.iter().copied().collect();
+
+        store.ability_members = ability_members
+            .iter()
+            .map(|(member, parent_ability, region, var)| {
+                let data = AbilityMemberData {
+                    parent_ability: *parent_ability,
+                    region: *region,
+                    typ: ResolvedMemberType(*var),
+                };
+
+                (*member, data)
+            })
+            .collect();
+
+        store.declared_implementations = declared_implementations.iter().copied().collect();
+
+        store.specializations = specializations
+            .iter()
+            .map(|(symbol, serialized)| (*symbol, serialized.to_info(lambda_sets)))
+            .collect();
+
+        store.resolved_specializations = resolved_specializations.iter().copied().collect();
+
+        store.next_specialization_id = NonZeroU32::new(header.next_specialization_id as u32)
+            .expect("specialization ids start at 1");
+
+        (store, offset)
+    }
+}
+
+/// Serializes the implementations a module resolved during solving, for caching solved modules.
+pub fn serialize_resolved_implementations(
+    implementations: &VecMap<ImplKey, ResolvedImpl>,
+    writer: &mut impl std::io::Write,
+    written: usize,
+) -> std::io::Result<usize> {
+    use roc_types::subs::Subs;
+
+    let mut lambda_sets = Vec::new();
+    let serialized: Vec<(ImplKey, SerializedResolvedImpl)> = implementations
+        .iter()
+        .map(|(key, resolved)| {
+            let resolved = match resolved {
+                ResolvedImpl::Impl(info) => SerializedResolvedImpl::Impl(
+                    SerializedSpecialization::new(info, &mut lambda_sets),
+                ),
+                ResolvedImpl::Derived => SerializedResolvedImpl::Derived,
+                ResolvedImpl::Error => SerializedResolvedImpl::Error,
+            };
+
+            (*key, resolved)
+        })
+        .collect();
+
+    let lengths = [serialized.len() as u64, lambda_sets.len() as u64];
+
+    let written = Subs::serialize_slice(&lengths, writer, written)?;
+    let written = Subs::serialize_slice(&serialized, writer, written)?;
+    let written = Subs::serialize_slice(&lambda_sets, writer, written)?;
+
+    Ok(written)
+}
+
+pub fn deserialize_resolved_implementations(
+    bytes: &[u8],
+    offset: usize,
+) -> (VecMap<ImplKey, ResolvedImpl>, usize) {
+    use roc_types::subs::Subs;
+
+    let (lengths, offset) = Subs::deserialize_slice::<u64>(bytes, 2, offset);
+    let (serialized, offset) = Subs::deserialize_slice::<(ImplKey, SerializedResolvedImpl)>(
+        bytes,
+        lengths[0] as usize,
+        offset,
+    );
+    let (lambda_sets, offset) =
+        Subs::deserialize_slice::<(u8, Variable)>(bytes, lengths[1] as usize, offset);
+
+    let implementations = serialized
+        .iter()
+        .map(|(key, resolved)| {
+            let resolved = match resolved {
+                SerializedResolvedImpl::Impl(specialization) => {
+                    ResolvedImpl::Impl(specialization.to_info(lambda_sets))
+                }
+                SerializedResolvedImpl::Derived => ResolvedImpl::Derived,
+                SerializedResolvedImpl::Error => ResolvedImpl::Error,
+            };
+
+            (*key, resolved)
+        })
+        .collect();
+
+    (implementations, offset)
+}
@@ -418,6 +418,15 @@ pub fn find_type_def_symbols(
                     stack.push(&t.value);
                 }
             }
+            Tuple { elems, ext } => {
+                for t in elems.iter() {
+                    stack.push(&t.value);
+                }
+
+                for t in ext.iter() {
+                    stack.push(&t.value);
+                }
+            }
             TagUnion { ext, tags } => {
                 let mut inner_stack = Vec::with_capacity(tags.items.len());
 
@@ -812,6 +821,42 @@ fn can_annotation_help(
                 Type::Record(field_types, TypeExtension::from_type(ext_type))
             }
         }
+        Tuple { elems, ext } => {
+            let ext_type = can_extension_type(
+                env,
+                scope,
+                var_store,
+                introduced_variables,
+                local_aliases,
+                references,
+                ext,
+                roc_problem::can::ExtensionTypeKind::Tuple,
+            );
+
+            let mut elem_types = VecMap::with_capacity(elems.len());
+
+            for (index, loc_elem) in elems.iter().enumerate() {
+                let elem_type = can_annotation_help(
+                    env,
+                    &loc_elem.value,
+                    loc_elem.region,
+                    scope,
+                    var_store,
+                    introduced_variables,
+                    local_aliases,
+                    references,
+                );
+
+                elem_types.insert(index, elem_type);
+            }
+
+            let ext = match ext {
+                Some(_) => TypeExtension::from_type(ext_type),
+                None => TypeExtension::Closed,
+            };
+
+            Type::Tuple(elem_types, ext)
+        }
         TagUnion { tags, ext, .. } => {
             let ext_type = can_extension_type(
                 env,
@@ -988,6 +1033,12 @@ fn can_extension_type<'a>(
             Type::EmptyRec | Type::Record(..) | Type::Variable(..) | Type::Erroneous(..)
         )
     }
+    fn valid_tuple_ext_type(typ: &Type) -> bool {
+        matches!(
+            typ,
+            Type::Tuple(..) | Type::Variable(..) | Type::Erroneous(..)
+        )
+    }
     fn valid_tag_ext_type(typ: &Type) -> bool {
         matches!(
             typ,
@@ -1000,6 +1051,10 @@ fn can_extension_type<'a>(
     let (empty_ext_type, valid_extension_type): (_, fn(&Type) -> bool) = match ext_problem_kind {
         ExtensionTypeKind::Record => (Type::EmptyRec, valid_record_ext_type),
         ExtensionTypeKind::TagUnion => (Type::EmptyTagUnion, valid_tag_ext_type),
+        ExtensionTypeKind::Tuple => (
+            Type::Tuple(VecMap::default(), TypeExtension::Closed),
+            valid_tuple_ext_type,
+        ),
     };
 
     match opt_ext {
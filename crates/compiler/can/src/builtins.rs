@@ -55,6 +55,10 @@ macro_rules! map_symbol_to_lowlevel_and_arity {
                 Symbol::NUM_TO_F32_CHECKED => Some(to_num_checked(Symbol::NUM_TO_F32_CHECKED, var_store, LowLevel::NumToFloatChecked)),
                 Symbol::NUM_TO_F64_CHECKED => Some(to_num_checked(Symbol::NUM_TO_F64_CHECKED, var_store, LowLevel::NumToFloatChecked)),
 
+                Symbol::HASH_F32_TO_BITS_LOWLEVEL => Some(lowlevel_1(Symbol::HASH_F32_TO_BITS_LOWLEVEL, LowLevel::NumToBits, var_store)),
+                Symbol::HASH_F64_TO_BITS_LOWLEVEL => Some(lowlevel_1(Symbol::HASH_F64_TO_BITS_LOWLEVEL, LowLevel::NumToBits, var_store)),
+                Symbol::HASH_DEC_TO_BITS_LOWLEVEL => Some(lowlevel_1(Symbol::HASH_DEC_TO_BITS_LOWLEVEL, LowLevel::NumToBits, var_store)),
+
                 _ => None,
             }
         }
@@ -78,9 +82,9 @@ macro_rules! map_symbol_to_lowlevel_and_arity {
                 LowLevel::NumToFloatCast => unreachable!(),
                 LowLevel::NumToIntChecked => unreachable!(),
                 LowLevel::NumToFloatChecked => unreachable!(),
+                LowLevel::NumToBits => unreachable!(),
 
                 // these are used internally and not tied to a symbol
-                LowLevel::Hash => unimplemented!(),
                 LowLevel::PtrCast => unimplemented!(),
                 LowLevel::RefCountInc => unimplemented!(),
                 LowLevel::RefCountDec => unimplemented!(),
@@ -188,11 +192,13 @@ map_symbol_to_lowlevel_and_arity! {
     NumShiftRightZfBy; NUM_SHIFT_RIGHT_ZERO_FILL; 2,
     NumToStr; NUM_TO_STR; 1,
 
-    Eq; BOOL_EQ; 2,
-    NotEq; BOOL_NEQ; 2,
+    Eq; BOOL_STRUCTURAL_EQ; 2,
+    NotEq; BOOL_STRUCTURAL_NOT_EQ; 2,
     And; BOOL_AND; 2,
     Or; BOOL_OR; 2,
     Not; BOOL_NOT; 1,
+    Hash; DICT_HASH_BYTES_LOWLEVEL; 2,
+    HashU64; DICT_HASH_U64_LOWLEVEL; 2,
     BoxExpr; BOX_BOX_FUNCTION; 1,
     UnboxExpr; BOX_UNBOX; 1,
     Unreachable; LIST_UNREACHABLE; 1,
@@ -1,7 +1,7 @@
 use crate::{
     def::Def,
     expr::{AccessorData, ClosureData, Expr, Field, OpaqueWrapFunctionData, WhenBranchPattern},
-    pattern::{DestructType, Pattern, RecordDestruct},
+    pattern::{DestructType, ListPatterns, Pattern, RecordDestruct, TupleDestruct},
 };
 use roc_module::{
     ident::{Lowercase, TagName},
@@ -10,7 +10,7 @@ use roc_module::{
 use roc_types::{
     subs::{
         self, AliasVariables, Descriptor, GetSubsSlice, OptVariable, RecordFields, Subs, SubsIndex,
-        SubsSlice, UnionLambdas, UnionTags, Variable, VariableSubsSlice,
+        SubsSlice, TupleElems, UnionLambdas, UnionTags, Variable, VariableSubsSlice,
     },
     types::{RecordField, Uls},
 };
@@ -72,6 +72,8 @@ trait CopyEnv {
         &mut self,
         record_fields: SubsSlice<RecordField<()>>,
     ) -> SubsSlice<RecordField<()>>;
+
+    fn clone_tuple_elem_indices(&mut self, elem_indices: SubsSlice<usize>) -> SubsSlice<usize>;
 }
 
 impl CopyEnv for Subs {
@@ -122,6 +124,11 @@ impl CopyEnv for Subs {
     ) -> SubsSlice<RecordField<()>> {
         record_fields
     }
+
+    #[inline(always)]
+    fn clone_tuple_elem_indices(&mut self, elem_indices: SubsSlice<usize>) -> SubsSlice<usize> {
+        elem_indices
+    }
 }
 
 struct AcrossSubs<'a> {
@@ -189,6 +196,14 @@ impl<'a> CopyEnv for AcrossSubs<'a> {
             self.source.get_subs_slice(record_fields).iter().copied(),
         )
     }
+
+    #[inline(always)]
+    fn clone_tuple_elem_indices(&mut self, elem_indices: SubsSlice<usize>) -> SubsSlice<usize> {
+        SubsSlice::extend_new(
+            &mut self.target.tuple_elem_indices,
+            self.source.get_subs_slice(elem_indices).iter().copied(),
+        )
+    }
 }
 
 pub fn deep_copy_type_vars_into_expr(
@@ -483,6 +498,28 @@ fn deep_copy_expr_help<C: CopyEnv>(env: &mut C, copied: &mut Vec<Variable>, expr
             field: field.clone(),
         },
 
+        Tuple { tuple_var, elems } => Tuple {
+            tuple_var: sub!(*tuple_var),
+            elems: elems
+                .iter()
+                .map(|(var, loc_expr)| (sub!(*var), Box::new(loc_expr.map(|e| go_help!(e)))))
+                .collect(),
+        },
+
+        TupleAccess {
+            tuple_var,
+            ext_var,
+            elem_var,
+            loc_expr,
+            index,
+        } => TupleAccess {
+            tuple_var: sub!(*tuple_var),
+            ext_var: sub!(*ext_var),
+            elem_var: sub!(*elem_var),
+            loc_expr: Box::new(loc_expr.map(|e| go_help!(e))),
+            index: *index,
+        },
+
         Accessor(AccessorData {
             name,
             function_var,
@@ -623,6 +660,18 @@ fn deep_copy_expr_help<C: CopyEnv>(env: &mut C, copied: &mut Vec<Variable>, expr
             lookups_in_cond: lookups_in_cond.to_vec(),
         },
 
+        Dbg {
+            loc_message,
+            loc_continuation,
+            variable,
+            symbol,
+        } => Dbg {
+            loc_message: Box::new(loc_message.map(|e| go_help!(e))),
+            loc_continuation: Box::new(loc_continuation.map(|e| go_help!(e))),
+            variable: sub!(*variable),
+            symbol: *symbol,
+        },
+
         TypedHole(v) => TypedHole(sub!(*v)),
 
         RuntimeError(err) => RuntimeError(err.clone()),
@@ -719,6 +768,42 @@ fn deep_copy_pattern_help<C: CopyEnv>(
                 })
                 .collect(),
         },
+        TupleDestructure {
+            whole_var,
+            ext_var,
+            destructs,
+        } => TupleDestructure {
+            whole_var: sub!(*whole_var),
+            ext_var: sub!(*ext_var),
+            destructs: destructs
+                .iter()
+                .map(|ltd| {
+                    ltd.map(
+                        |TupleDestruct {
+                             var,
+                             destruct_index,
+                             typ: (tyvar, pat),
+                         }| TupleDestruct {
+                            var: sub!(*var),
+                            destruct_index: *destruct_index,
+                            typ: (sub!(*tyvar), pat.map(|p| go_help!(p))),
+                        },
+                    )
+                })
+                .collect(),
+        },
+        List {
+            list_var,
+            elem_var,
+            patterns: ListPatterns { patterns, opt_rest },
+        } => List {
+            list_var: sub!(*list_var),
+            elem_var: sub!(*elem_var),
+            patterns: ListPatterns {
+                patterns: patterns.iter().map(|lp| lp.map(|p| go_help!(p))).collect(),
+                opt_rest: *opt_rest,
+            },
+        },
         NumLiteral(var, s, n, bound) => NumLiteral(sub!(*var), s.clone(), *n, *bound),
         IntLiteral(v1, v2, s, n, bound) => IntLiteral(sub!(*v1), sub!(*v2), s.clone(), *n, *bound),
         FloatLiteral(v1, v2, s, n, bound) => {
@@ -841,7 +926,7 @@ fn deep_copy_type_vars<C: CopyEnv>(
 
             // Everything else is a mechanical descent.
             Structure(flat_type) => match flat_type {
-                EmptyRecord | EmptyTagUnion | Erroneous(_) => Structure(flat_type),
+                EmptyRecord | EmptyTuple | EmptyTagUnion | Erroneous(_) => Structure(flat_type),
                 Apply(symbol, arguments) => {
                     descend_slice!(arguments);
 
@@ -883,6 +968,26 @@ fn deep_copy_type_vars<C: CopyEnv>(
                         Structure(Record(new_fields, new_ext_var))
                     })
                 }
+                Tuple(elems, ext_var) => {
+                    let new_ext_var = descend_var!(ext_var);
+
+                    descend_slice!(elems.variables());
+
+                    perform_clone!({
+                        let new_variables = clone_var_slice!(elems.variables());
+                        let new_elem_indices = env.clone_tuple_elem_indices(elems.elem_indices());
+
+                        let new_elems = {
+                            TupleElems {
+                                length: elems.length,
+                                variables_start: new_variables.start,
+                                elem_index_start: new_elem_indices.start,
+                            }
+                        };
+
+                        Structure(Tuple(new_elems, new_ext_var))
+                    })
+                }
                 TagUnion(tags, ext_var) => {
                     let new_ext_var = descend_var!(ext_var);
 
@@ -928,6 +928,7 @@ fn canonicalize_value_defs<'a>(
                 pending_value_defs.push(pending_def);
             }
             PendingValue::SignatureDefMismatch => { /* skip */ }
+            PendingValue::Malformed => { /* skip */ }
             PendingValue::Expect(pending_expect) => {
                 pending_expects.push(pending_expect);
             }
@@ -1811,6 +1812,27 @@ fn pattern_to_vars_by_symbol(
             }
         }
 
+        TupleDestructure { destructs, .. } => {
+            for destruct in destructs {
+                let (var, nested) = &destruct.value.typ;
+                pattern_to_vars_by_symbol(vars_by_symbol, &nested.value, *var);
+            }
+        }
+
+        List {
+            list_var,
+            elem_var,
+            patterns,
+        } => {
+            for pat in patterns.patterns.iter() {
+                pattern_to_vars_by_symbol(vars_by_symbol, &pat.value, *elem_var);
+            }
+
+            if let Some((_, Some(rest_sym))) = &patterns.opt_rest {
+                vars_by_symbol.insert(rest_sym.value, *list_var);
+            }
+        }
+
         NumLiteral(..)
         | IntLiteral(..)
         | FloatLiteral(..)
@@ -2444,6 +2466,8 @@ enum PendingValue<'a> {
     Expect(PendingExpect<'a>),
     ExpectFx(PendingExpect<'a>),
     SignatureDefMismatch,
+    /// A def that failed to parse, and does not define anything. The parser already reported it.
+    Malformed,
 }
 
 struct PendingExpect<'a> {
@@ -2482,6 +2506,16 @@ fn to_pending_value_def<'a>(
                 loc_ann,
             ))
         }
+        Body(
+            Loc {
+                value: ast::Pattern::Malformed(_),
+                ..
+            },
+            Loc {
+                value: ast::Expr::Malformed(_),
+                ..
+            },
+        ) => PendingValue::Malformed,
         Body(loc_pattern, loc_expr) => {
             // This takes care of checking for shadowing and adding idents to scope.
             let loc_can_pattern = canonicalize_def_header_pattern(
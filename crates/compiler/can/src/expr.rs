@@ -182,6 +182,20 @@ pub enum Expr {
         updates: SendMap<Lowercase, Field>,
     },
 
+    Tuple {
+        tuple_var: Variable,
+        elems: Vec<(Variable, Box<Loc<Expr>>)>,
+    },
+
+    /// Look up exactly one element on a tuple, e.g. (expr).0.
+    TupleAccess {
+        tuple_var: Variable,
+        ext_var: Variable,
+        elem_var: Variable,
+        loc_expr: Box<Loc<Expr>>,
+        index: usize,
+    },
+
     // Sum Types
     Tag {
         tag_union_var: Variable,
@@ -238,6 +252,15 @@ pub enum Expr {
         lookups_in_cond: Vec<(Symbol, Variable)>,
     },
 
+    /// `dbg` binds `loc_message` to `symbol` so its value can be printed before the
+    /// continuation runs. A `dbg` without a continuation evaluates to its message.
+    Dbg {
+        loc_message: Box<Loc<Expr>>,
+        loc_continuation: Box<Loc<Expr>>,
+        variable: Variable,
+        symbol: Symbol,
+    },
+
     /// Rendered as empty box in editor
     TypedHole(Variable),
 
@@ -269,6 +292,8 @@ impl Expr {
             Self::Access { field, .. } => Category::Access(field.clone()),
             Self::Accessor(data) => Category::Accessor(data.field.clone()),
             Self::Update { .. } => Category::Record,
+            Self::Tuple { .. } => Category::Tuple,
+            Self::TupleAccess { index, .. } => Category::TupleAccess(*index),
             Self::Tag {
                 name, arguments, ..
             } => Category::TagApply {
@@ -285,6 +310,9 @@ impl Expr {
             }
             Self::Expect { .. } => Category::Expect,
             Self::ExpectFx { .. } => Category::Expect,
+            Self::Dbg {
+                loc_continuation, ..
+            } => loc_continuation.value.category(),
 
             // these nodes place no constraints on the expression's type
             Self::TypedHole(_) | Self::RuntimeError(..) => Category::Unknown,
@@ -912,6 +940,42 @@ pub fn canonicalize_expr<'a>(
                 output,
             )
         }
+        ast::Expr::Tuple(elems) => {
+            let mut output = Output::default();
+            let mut can_elems = Vec::with_capacity(elems.len());
+
+            for loc_elem in elems.iter() {
+                let (can_elem, elem_out) =
+                    canonicalize_expr(env, var_store, scope, loc_elem.region, &loc_elem.value);
+
+                output.references.union_mut(&elem_out.references);
+                can_elems.push((var_store.fresh(), Box::new(can_elem)));
+            }
+
+            (
+                Tuple {
+                    tuple_var: var_store.fresh(),
+                    elems: can_elems,
+                },
+                output,
+            )
+        }
+        ast::Expr::TupleAccess(tuple_expr, index) => {
+            let (loc_expr, output) = canonicalize_expr(env, var_store, scope, region, tuple_expr);
+
+            (
+                TupleAccess {
+                    tuple_var: var_store.fresh(),
+                    elem_var: var_store.fresh(),
+                    ext_var: var_store.fresh(),
+                    loc_expr: Box::new(loc_expr),
+                    // The parser only accepts digits here; an index too large to fit in a usize
+                    // can never be in bounds, which type checking will report.
+                    index: index.parse().unwrap_or(usize::MAX),
+                },
+                output,
+            )
+        }
         ast::Expr::AccessorFunction(field) => (
             Accessor(AccessorData {
                 name: scope.gen_unique_symbol(),
@@ -1006,6 +1070,43 @@ pub fn canonicalize_expr<'a>(
                 output,
             )
         }
+        ast::Expr::Dbg(message, continuation) => {
+            let mut output = Output::default();
+
+            let (loc_message, output1) =
+                canonicalize_expr(env, var_store, scope, message.region, &message.value);
+
+            output.union(output1);
+
+            let symbol = scope.gen_unique_symbol();
+
+            let loc_continuation = match continuation {
+                Some(continuation) => {
+                    let (loc_continuation, output2) = canonicalize_expr(
+                        env,
+                        var_store,
+                        scope,
+                        continuation.region,
+                        &continuation.value,
+                    );
+
+                    output.union(output2);
+
+                    loc_continuation
+                }
+                None => Loc::at(message.region, Var(symbol)),
+            };
+
+            (
+                Dbg {
+                    loc_message: Box::new(loc_message),
+                    loc_continuation: Box::new(loc_continuation),
+                    variable: var_store.fresh(),
+                    symbol,
+                },
+                output,
+            )
+        }
         ast::Expr::If(if_thens, final_else_branch) => {
             let mut branches = Vec::with_capacity(if_thens.len());
             let mut output = Output::default();
@@ -1085,6 +1186,12 @@ pub fn canonicalize_expr<'a>(
             use roc_problem::can::RuntimeError::*;
             (RuntimeError(MalformedClosure(region)), Output::default())
         }
+        ast::Expr::Malformed(_) => {
+            use roc_problem::can::RuntimeError::*;
+
+            // the syntax error that produced this was already reported by the parser
+            (RuntimeError(MalformedExpr(region)), Output::default())
+        }
         ast::Expr::MalformedIdent(name, bad_ident) => {
             use roc_problem::can::RuntimeError::*;
 
@@ -1802,6 +1909,30 @@ pub fn inline_calls(var_store: &mut VarStore, scope: &mut Scope, expr: Expr) ->
             }
         }
 
+        Dbg {
+            loc_message,
+            loc_continuation,
+            variable,
+            symbol,
+        } => {
+            let loc_message = Loc {
+                region: loc_message.region,
+                value: inline_calls(var_store, scope, loc_message.value),
+            };
+
+            let loc_continuation = Loc {
+                region: loc_continuation.region,
+                value: inline_calls(var_store, scope, loc_continuation.value),
+            };
+
+            Dbg {
+                loc_message: Box::new(loc_message),
+                loc_continuation: Box::new(loc_continuation),
+                variable,
+                symbol,
+            }
+        }
+
         LetRec(defs, loc_expr, mark) => {
             let mut new_defs = Vec::with_capacity(defs.len());
 
@@ -1892,6 +2023,24 @@ pub fn inline_calls(var_store: &mut VarStore, scope: &mut Scope, expr: Expr) ->
             todo!("Inlining for Access with record_var {:?}, ext_var {:?}, field_var {:?}, loc_expr {:?}, field {:?}", record_var, ext_var, field_var, loc_expr, field);
         }
 
+        Tuple { tuple_var, elems } => {
+            todo!(
+                "Inlining for Tuple with tuple_var {:?} and elems {:?}",
+                tuple_var,
+                elems
+            );
+        }
+
+        TupleAccess {
+            tuple_var,
+            ext_var,
+            elem_var,
+            loc_expr,
+            index,
+        } => {
+            todo!("Inlining for TupleAccess with tuple_var {:?}, ext_var {:?}, elem_var {:?}, loc_expr {:?}, index {:?}", tuple_var, ext_var, elem_var, loc_expr, index);
+        }
+
         Tag {
             tag_union_var: variant_var,
             ext_var,
@@ -2042,7 +2191,9 @@ fn flatten_str_literal<'a>(
 pub fn is_valid_interpolation(expr: &ast::Expr<'_>) -> bool {
     match expr {
         ast::Expr::Var { .. } => true,
-        ast::Expr::Access(sub_expr, _) => is_valid_interpolation(sub_expr),
+        ast::Expr::Access(sub_expr, _) | ast::Expr::TupleAccess(sub_expr, _) => {
+            is_valid_interpolation(sub_expr)
+        }
         _ => false,
     }
 }
@@ -2521,9 +2672,9 @@ impl Declarations {
             })
     }
 
-    pub fn expects(&self) -> VecMap<Region, Vec<(Symbol, Variable)>> {
+    pub fn expects(&self) -> ExpectLookups {
         let mut collector = ExpectCollector {
-            expects: VecMap::default(),
+            lookups: ExpectLookups::default(),
         };
 
         let var = Variable::EMPTY_RECORD;
@@ -2556,7 +2707,7 @@ impl Declarations {
             }
         }
 
-        collector.expects
+        collector.lookups
     }
 }
 
@@ -2678,6 +2829,7 @@ fn get_lookup_symbols(expr: &Expr, var_store: &mut VarStore) -> Vec<(Symbol, Var
                 stack.push(&argument.1.value);
             }
             Expr::Access { loc_expr, .. }
+            | Expr::TupleAccess { loc_expr, .. }
             | Expr::Closure(ClosureData {
                 loc_body: loc_expr, ..
             }) => {
@@ -2686,6 +2838,9 @@ fn get_lookup_symbols(expr: &Expr, var_store: &mut VarStore) -> Vec<(Symbol, Var
             Expr::Record { fields, .. } => {
                 stack.extend(fields.iter().map(|(_, field)| &field.loc_expr.value));
             }
+            Expr::Tuple { elems, .. } => {
+                stack.extend(elems.iter().map(|(_, elem)| &elem.value));
+            }
             Expr::Expect {
                 loc_continuation, ..
             }
@@ -2697,6 +2852,21 @@ fn get_lookup_symbols(expr: &Expr, var_store: &mut VarStore) -> Vec<(Symbol, Var
                 // Intentionally ignore the lookups in the nested `expect` condition itself,
                 // because they couldn't possibly influence the outcome of this `expect`!
             }
+            Expr::Dbg {
+                loc_message,
+                loc_continuation,
+                symbol,
+                ..
+            } => {
+                stack.push(&loc_message.value);
+
+                // a `dbg` without a continuation looks up its own message symbol,
+                // which is not in scope outside of the `dbg`
+                match &loc_continuation.value {
+                    Expr::Var(lookup) if lookup == symbol => {}
+                    continuation => stack.push(continuation),
+                }
+            }
             Expr::Num(_, _, _, _)
             | Expr::Float(_, _, _, _, _)
             | Expr::Int(_, _, _, _, _)
@@ -2807,8 +2977,22 @@ fn toplevel_expect_to_inline_expect_help(mut loc_expr: Loc<Expr>, has_effects: b
     loc_expr
 }
 
+/// The values that must be read back when an `expect` fails or a `dbg` is reached,
+/// keyed by the region of the expectation's condition or the `dbg` message.
+#[derive(Debug, Default)]
+pub struct ExpectLookups {
+    pub expects: VecMap<Region, Vec<(Symbol, Variable)>>,
+    pub dbgs: VecMap<Region, (Symbol, Variable)>,
+}
+
+impl ExpectLookups {
+    pub fn is_empty(&self) -> bool {
+        self.expects.is_empty() && self.dbgs.is_empty()
+    }
+}
+
 struct ExpectCollector {
-    expects: VecMap<Region, Vec<(Symbol, Variable)>>,
+    lookups: ExpectLookups,
 }
 
 impl crate::traverse::Visitor for ExpectCollector {
@@ -2824,9 +3008,20 @@ impl crate::traverse::Visitor for ExpectCollector {
                 loc_condition,
                 ..
             } => {
-                self.expects
+                self.lookups
+                    .expects
                     .insert(loc_condition.region, lookups_in_cond.to_vec());
             }
+            Expr::Dbg {
+                loc_message,
+                variable,
+                symbol,
+                ..
+            } => {
+                self.lookups
+                    .dbgs
+                    .insert(loc_message.region, (*symbol, *variable));
+            }
             _ => (),
         }
 
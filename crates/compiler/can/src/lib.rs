@@ -19,4 +19,5 @@ pub mod pattern;
 pub mod procedure;
 pub mod scope;
 pub mod string;
+pub mod symbol_index;
 pub mod traverse;
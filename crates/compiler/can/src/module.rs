@@ -3,7 +3,7 @@ use crate::annotation::canonicalize_annotation;
 use crate::def::{canonicalize_defs, Def};
 use crate::effect_module::HostedGeneratedFunctions;
 use crate::env::Env;
-use crate::expr::{ClosureData, Declarations, Expr, Output, PendingDerives};
+use crate::expr::{ClosureData, Declarations, ExpectLookups, Expr, Output, PendingDerives};
 use crate::pattern::{BindingsFromPattern, Pattern};
 use crate::scope::Scope;
 use bumpalo::Bump;
@@ -130,7 +130,7 @@ pub struct Module {
     pub aliases: MutMap<Symbol, (bool, Alias)>,
     pub rigid_variables: RigidVariables,
     pub abilities_store: PendingAbilitiesStore,
-    pub loc_expects: VecMap<Region, Vec<(Symbol, Variable)>>,
+    pub loc_expects: ExpectLookups,
 }
 
 #[derive(Debug, Default)]
@@ -153,7 +153,7 @@ pub struct ModuleOutput {
     pub symbols_from_requires: Vec<(Loc<Symbol>, Loc<Type>)>,
     pub pending_derives: PendingDerives,
     pub scope: Scope,
-    pub loc_expects: VecMap<Region, Vec<(Symbol, Variable)>>,
+    pub loc_expects: ExpectLookups,
 }
 
 fn validate_generate_with<'a>(
@@ -905,6 +905,24 @@ fn fix_values_captured_in_closure_pattern(
                 }
             }
         }
+        TupleDestructure { destructs, .. } => {
+            for loc_destruct in destructs.iter_mut() {
+                fix_values_captured_in_closure_pattern(
+                    &mut loc_destruct.value.typ.1.value,
+                    no_capture_symbols,
+                    closure_captures,
+                )
+            }
+        }
+        List { patterns, .. } => {
+            for loc_pat in patterns.patterns.iter_mut() {
+                fix_values_captured_in_closure_pattern(
+                    &mut loc_pat.value,
+                    no_capture_symbols,
+                    closure_captures,
+                );
+            }
+        }
         Identifier(_)
         | NumLiteral(..)
         | IntLiteral(..)
@@ -981,6 +999,24 @@ fn fix_values_captured_in_closure_expr(
             );
         }
 
+        Dbg {
+            loc_message,
+            loc_continuation,
+            variable: _,
+            symbol: _,
+        } => {
+            fix_values_captured_in_closure_expr(
+                &mut loc_message.value,
+                no_capture_symbols,
+                closure_captures,
+            );
+            fix_values_captured_in_closure_expr(
+                &mut loc_continuation.value,
+                no_capture_symbols,
+                closure_captures,
+            );
+        }
+
         Closure(ClosureData {
             captured_symbols,
             name,
@@ -1162,7 +1198,17 @@ fn fix_values_captured_in_closure_expr(
             }
         }
 
-        Access { loc_expr, .. } => {
+        Tuple { elems, .. } => {
+            for (_, elem) in elems.iter_mut() {
+                fix_values_captured_in_closure_expr(
+                    &mut elem.value,
+                    no_capture_symbols,
+                    closure_captures,
+                );
+            }
+        }
+
+        Access { loc_expr, .. } | TupleAccess { loc_expr, .. } => {
             fix_values_captured_in_closure_expr(
                 &mut loc_expr.value,
                 no_capture_symbols,
@@ -6,9 +6,10 @@ use crate::num::{
     ParsedNumResult,
 };
 use crate::scope::{PendingAbilitiesInScope, Scope};
+use roc_exhaustive::ListArity;
 use roc_module::ident::{Ident, Lowercase, TagName};
 use roc_module::symbol::Symbol;
-use roc_parse::ast::{self, StrLiteral, StrSegment};
+use roc_parse::ast::{self, ExtractSpaces, StrLiteral, StrSegment};
 use roc_parse::pattern::PatternType;
 use roc_problem::can::{MalformedPatternProblem, Problem, RuntimeError, ShadowKind};
 use roc_region::all::{Loc, Region};
@@ -55,6 +56,16 @@ pub enum Pattern {
         ext_var: Variable,
         destructs: Vec<Loc<RecordDestruct>>,
     },
+    TupleDestructure {
+        whole_var: Variable,
+        ext_var: Variable,
+        destructs: Vec<Loc<TupleDestruct>>,
+    },
+    List {
+        list_var: Variable,
+        elem_var: Variable,
+        patterns: ListPatterns,
+    },
     NumLiteral(Variable, Box<str>, IntValue, NumBound),
     IntLiteral(Variable, Variable, Box<str>, IntValue, IntBound),
     FloatLiteral(Variable, Variable, Box<str>, f64, FloatBound),
@@ -91,6 +102,8 @@ impl Pattern {
             AppliedTag { whole_var, .. } => Some(*whole_var),
             UnwrappedOpaque { whole_var, .. } => Some(*whole_var),
             RecordDestructure { whole_var, .. } => Some(*whole_var),
+            TupleDestructure { whole_var, .. } => Some(*whole_var),
+            List { list_var, .. } => Some(*list_var),
             NumLiteral(var, ..) => Some(*var),
             IntLiteral(var, ..) => Some(*var),
             FloatLiteral(var, ..) => Some(*var),
@@ -118,6 +131,10 @@ impl Pattern {
             | MalformedPattern(..)
             | AbilityMemberSpecialization { .. } => true,
             RecordDestructure { destructs, .. } => destructs.is_empty(),
+            TupleDestructure { destructs, .. } => destructs
+                .iter()
+                .all(|d| d.value.typ.1.value.surely_exhaustive()),
+            List { patterns, .. } => patterns.surely_exhaustive(),
             AppliedTag { .. }
             | NumLiteral(..)
             | IntLiteral(..)
@@ -144,6 +161,8 @@ impl Pattern {
             UnwrappedOpaque { opaque, .. } => C::Opaque(*opaque),
             RecordDestructure { destructs, .. } if destructs.is_empty() => C::EmptyRecord,
             RecordDestructure { .. } => C::Record,
+            TupleDestructure { .. } => C::Tuple,
+            List { .. } => C::List,
             NumLiteral(..) => C::Num,
             IntLiteral(..) => C::Int,
             FloatLiteral(..) => C::Float,
@@ -160,6 +179,32 @@ impl Pattern {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct ListPatterns {
+    pub patterns: Vec<Loc<Pattern>>,
+    /// Where a rest pattern splits patterns before and after it, if there is one,
+    /// along with the symbol the rest is bound to, e.g. `rest` in `[x, .. as rest]`.
+    /// Patterns at indices >= the split come after the rest pattern. For example:
+    ///   [..]          - patterns = [], opt_rest = Some((0, None))
+    ///   [A, .., B]    - patterns = [A, B], opt_rest = Some((1, None))
+    ///   [A, .. as r]  - patterns = [A], opt_rest = Some((1, Some(r)))
+    pub opt_rest: Option<(usize, Option<Loc<Symbol>>)>,
+}
+
+impl ListPatterns {
+    /// Is this list pattern the trivially-exhaustive pattern `[..]`?
+    fn surely_exhaustive(&self) -> bool {
+        self.patterns.is_empty() && matches!(self.opt_rest, Some((0, _)))
+    }
+
+    pub fn arity(&self) -> ListArity {
+        match self.opt_rest {
+            Some((i, _)) => ListArity::Slice(i, self.patterns.len() - i),
+            None => ListArity::Exact(self.patterns.len()),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct RecordDestruct {
     pub var: Variable,
@@ -168,6 +213,13 @@ pub struct RecordDestruct {
     pub typ: DestructType,
 }
 
+#[derive(Clone, Debug)]
+pub struct TupleDestruct {
+    pub var: Variable,
+    pub destruct_index: usize,
+    pub typ: (Variable, Loc<Pattern>),
+}
+
 #[derive(Clone, Debug)]
 pub enum DestructType {
     Required,
@@ -608,6 +660,128 @@ pub fn canonicalize_pattern<'a>(
             })
         }
 
+        Tuple(patterns) => {
+            let ext_var = var_store.fresh();
+            let whole_var = var_store.fresh();
+            let mut destructs = Vec::with_capacity(patterns.len());
+
+            for (i, loc_pattern) in patterns.iter().enumerate() {
+                let can_guard = canonicalize_pattern(
+                    env,
+                    var_store,
+                    scope,
+                    output,
+                    pattern_type,
+                    &loc_pattern.value,
+                    loc_pattern.region,
+                    permit_shadows,
+                );
+
+                destructs.push(Loc {
+                    region: loc_pattern.region,
+                    value: TupleDestruct {
+                        var: var_store.fresh(),
+                        destruct_index: i,
+                        typ: (var_store.fresh(), can_guard),
+                    },
+                });
+            }
+
+            Pattern::TupleDestructure {
+                whole_var,
+                ext_var,
+                destructs,
+            }
+        }
+
+        List(patterns) => {
+            let list_var = var_store.fresh();
+            let elem_var = var_store.fresh();
+            let mut can_patterns = Vec::with_capacity(patterns.len());
+            let mut opt_rest = None;
+            let mut opt_erroneous = None;
+
+            for loc_pattern in patterns.iter() {
+                match loc_pattern.value.extract_spaces().item {
+                    ListRest(opt_loc_name) => {
+                        if opt_rest.is_some() {
+                            let problem = MalformedPatternProblem::DuplicateListRestPattern;
+                            opt_erroneous =
+                                Some(malformed_pattern(env, problem, loc_pattern.region));
+                            continue;
+                        }
+
+                        let opt_rest_symbol = match opt_loc_name {
+                            None => None,
+                            Some(loc_name) => {
+                                match scope.introduce_str(loc_name.value, loc_name.region) {
+                                    Ok(symbol) => {
+                                        output.references.insert_bound(symbol);
+
+                                        Some(Loc::at(loc_name.region, symbol))
+                                    }
+                                    Err((shadowed_symbol, _, _)) if permit_shadows.0 => {
+                                        output.references.insert_bound(shadowed_symbol.value);
+
+                                        Some(Loc::at(loc_name.region, shadowed_symbol.value))
+                                    }
+                                    Err((shadowed_symbol, shadow, new_symbol)) => {
+                                        env.problem(Problem::RuntimeError(
+                                            RuntimeError::Shadowing {
+                                                original_region: shadowed_symbol.region,
+                                                shadow: shadow.clone(),
+                                                kind: ShadowKind::Variable,
+                                            },
+                                        ));
+                                        output.references.insert_bound(new_symbol);
+
+                                        opt_erroneous = Some(Pattern::Shadowed(
+                                            shadowed_symbol.region,
+                                            shadow,
+                                            new_symbol,
+                                        ));
+
+                                        None
+                                    }
+                                }
+                            }
+                        };
+
+                        opt_rest = Some((can_patterns.len(), opt_rest_symbol));
+                    }
+                    _ => {
+                        let can_pattern = canonicalize_pattern(
+                            env,
+                            var_store,
+                            scope,
+                            output,
+                            pattern_type,
+                            &loc_pattern.value,
+                            loc_pattern.region,
+                            permit_shadows,
+                        );
+
+                        can_patterns.push(can_pattern);
+                    }
+                }
+            }
+
+            // If we encountered an erroneous pattern (e.g. a second rest pattern),
+            // use the resulting RuntimeError. Otherwise, return a successful list pattern.
+            opt_erroneous.unwrap_or(Pattern::List {
+                list_var,
+                elem_var,
+                patterns: ListPatterns {
+                    patterns: can_patterns,
+                    opt_rest,
+                },
+            })
+        }
+
+        ListRest(_) => {
+            unreachable!("should have been handled in List");
+        }
+
         RequiredField(_name, _loc_pattern) => {
             unreachable!("should have been handled in RecordDestructure");
         }
@@ -671,6 +845,7 @@ pub enum BindingsFromPattern<'a> {
 pub enum BindingsFromPatternWork<'a> {
     Pattern(&'a Loc<Pattern>),
     Destruct(&'a Loc<RecordDestruct>),
+    ListRest(&'a Loc<Symbol>),
 }
 
 impl<'a> BindingsFromPattern<'a> {
@@ -720,6 +895,19 @@ impl<'a> BindingsFromPattern<'a> {
                             let it = destructs.iter().rev().map(Destruct);
                             stack.extend(it);
                         }
+                        TupleDestructure { destructs, .. } => {
+                            let it = destructs.iter().rev().map(|d| Pattern(&d.value.typ.1));
+                            stack.extend(it);
+                        }
+                        List { patterns, .. } => {
+                            let ListPatterns { patterns, opt_rest } = patterns;
+
+                            if let Some((_, Some(rest_sym))) = opt_rest {
+                                stack.push(ListRest(rest_sym));
+                            }
+
+                            stack.extend(patterns.iter().rev().map(Pattern));
+                        }
                         NumLiteral(..)
                         | IntLiteral(..)
                         | FloatLiteral(..)
@@ -732,6 +920,9 @@ impl<'a> BindingsFromPattern<'a> {
                         | OpaqueNotInScope(..) => (),
                     }
                 }
+                BindingsFromPatternWork::ListRest(loc_symbol) => {
+                    return Some((loc_symbol.value, loc_symbol.region));
+                }
                 BindingsFromPatternWork::Destruct(loc_destruct) => {
                     match &loc_destruct.value.typ {
                         DestructType::Required | DestructType::Optional(_, _) => {
@@ -0,0 +1,164 @@
+//! Where value symbols are defined and referenced, for editor tooling like go-to-definition and
+//! find-references.
+
+use std::cmp::Reverse;
+
+use roc_collections::all::MutMap;
+use roc_module::symbol::{ModuleId, Symbol};
+use roc_region::all::{Loc, Position, Region};
+use roc_types::subs::Variable;
+
+use crate::{
+    expr::{Declarations, Expr},
+    pattern::{DestructType, Pattern, RecordDestruct},
+    traverse::{walk_expr, walk_pattern, walk_record_destruct, Visitor},
+};
+
+/// A place a symbol occurs in some module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Occurrence {
+    pub module_id: ModuleId,
+    pub region: Region,
+}
+
+/// The value symbols defined and referenced in a single module.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModuleSymbolIndex {
+    pub definitions: Vec<Loc<Symbol>>,
+    pub references: Vec<Loc<Symbol>>,
+}
+
+impl ModuleSymbolIndex {
+    pub fn from_declarations(decls: &Declarations) -> Self {
+        let mut collector = Collector {
+            index: ModuleSymbolIndex::default(),
+        };
+        collector.visit_decls(decls);
+
+        let mut index = collector.index;
+        index.definitions.sort_by_key(|loc| loc.region);
+        index.references.sort_by_key(|loc| loc.region);
+
+        index
+    }
+}
+
+struct Collector {
+    index: ModuleSymbolIndex,
+}
+
+impl Visitor for Collector {
+    fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+        match expr {
+            Expr::Var(symbol) | Expr::AbilityMember(symbol, _, _) => {
+                self.index.references.push(Loc::at(region, *symbol));
+            }
+            _ => walk_expr(self, expr, var),
+        }
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern, region: Region, _opt_var: Option<Variable>) {
+        match pattern {
+            Pattern::Identifier(symbol)
+            | Pattern::AbilityMemberSpecialization { ident: symbol, .. } => {
+                self.index.definitions.push(Loc::at(region, *symbol));
+            }
+            Pattern::Shadowed(_, loc_ident, symbol) => {
+                self.index
+                    .definitions
+                    .push(Loc::at(loc_ident.region, *symbol));
+            }
+            _ => walk_pattern(self, pattern),
+        }
+    }
+
+    fn visit_record_destruct(&mut self, destruct: &RecordDestruct, region: Region) {
+        // `{ x: Just y }` introduces `y` but not `x`; `{ x }` and `{ x ? 0 }` introduce `x`.
+        if !matches!(destruct.typ, DestructType::Guard(..)) {
+            self.index
+                .definitions
+                .push(Loc::at(region, destruct.symbol));
+        }
+
+        walk_record_destruct(self, destruct);
+    }
+}
+
+/// Definition and reference sites of value symbols, across every module that was loaded.
+///
+/// Symbols from one module can be referenced in others (e.g. `Foo.bar`, or `bar` when
+/// `Foo.{ bar }` was imported), so looking up a symbol's references gives sites in every module
+/// that was indexed.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolIndex {
+    definitions: MutMap<Symbol, Occurrence>,
+    references: MutMap<Symbol, Vec<Occurrence>>,
+    /// Every definition and reference in each module, sorted by region.
+    by_module: MutMap<ModuleId, Vec<Loc<Symbol>>>,
+}
+
+impl SymbolIndex {
+    pub fn insert_module(&mut self, module_id: ModuleId, index: ModuleSymbolIndex) {
+        let ModuleSymbolIndex {
+            definitions,
+            references,
+        } = index;
+
+        let mut occurrences = Vec::with_capacity(definitions.len() + references.len());
+
+        for loc_symbol in definitions {
+            let occurrence = Occurrence {
+                module_id,
+                region: loc_symbol.region,
+            };
+
+            // a symbol is defined once; keep the first site if canonicalization disagrees
+            self.definitions
+                .entry(loc_symbol.value)
+                .or_insert(occurrence);
+            occurrences.push(loc_symbol);
+        }
+
+        for loc_symbol in references {
+            let occurrence = Occurrence {
+                module_id,
+                region: loc_symbol.region,
+            };
+
+            self.references
+                .entry(loc_symbol.value)
+                .or_default()
+                .push(occurrence);
+            occurrences.push(loc_symbol);
+        }
+
+        occurrences.sort_by_key(|loc| loc.region);
+        self.by_module.insert(module_id, occurrences);
+    }
+
+    /// Where `symbol` is defined, if it was defined in an indexed module.
+    pub fn definition(&self, symbol: Symbol) -> Option<Occurrence> {
+        self.definitions.get(&symbol).copied()
+    }
+
+    /// Every place `symbol` is used, not including its definition.
+    pub fn references(&self, symbol: Symbol) -> &[Occurrence] {
+        self.references
+            .get(&symbol)
+            .map(|references| references.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// The symbol defined or referenced at `position` in the given module, if any.
+    /// When occurrences are nested, the innermost one wins.
+    pub fn symbol_at(&self, module_id: ModuleId, position: Position) -> Option<Symbol> {
+        let target = Region::new(position, position);
+
+        self.by_module
+            .get(&module_id)?
+            .iter()
+            .filter(|loc| loc.region.contains(&target))
+            .max_by_key(|loc| (loc.region.start(), Reverse(loc.region.end())))
+            .map(|loc| loc.value)
+    }
+}
@@ -1,7 +1,7 @@
 //! Traversals over the can ast.
 
 use roc_module::{ident::Lowercase, symbol::Symbol};
-use roc_region::all::{Loc, Region};
+use roc_region::all::{Loc, Position, Region};
 use roc_types::{subs::Variable, types::MemberImpl};
 
 use crate::{
@@ -233,6 +233,19 @@ pub fn walk_expr<V: Visitor>(visitor: &mut V, expr: &Expr, var: Variable) {
             record_var: _,
             ext_var: _,
         } => visitor.visit_expr(&loc_expr.value, loc_expr.region, *field_var),
+        Expr::Tuple {
+            tuple_var: _,
+            elems,
+        } => elems
+            .iter()
+            .for_each(|(v, le)| visitor.visit_expr(&le.value, le.region, *v)),
+        Expr::TupleAccess {
+            tuple_var,
+            loc_expr,
+            index: _,
+            elem_var: _,
+            ext_var: _,
+        } => visitor.visit_expr(&loc_expr.value, loc_expr.region, *tuple_var),
         Expr::Accessor(AccessorData { .. }) => { /* terminal */ }
         Expr::OpaqueWrapFunction(OpaqueWrapFunctionData { .. }) => { /* terminal */ }
         Expr::Update {
@@ -289,6 +302,19 @@ pub fn walk_expr<V: Visitor>(visitor: &mut V, expr: &Expr, var: Variable) {
                 Variable::NULL,
             );
         }
+        Expr::Dbg {
+            loc_message,
+            loc_continuation,
+            variable,
+            symbol: _,
+        } => {
+            visitor.visit_expr(&loc_message.value, loc_message.region, *variable);
+            visitor.visit_expr(
+                &loc_continuation.value,
+                loc_continuation.region,
+                Variable::NULL,
+            );
+        }
         Expr::TypedHole(_) => { /* terminal */ }
         Expr::RuntimeError(..) => { /* terminal */ }
     }
@@ -472,6 +498,16 @@ pub fn walk_pattern<V: Visitor>(visitor: &mut V, pattern: &Pattern) {
         RecordDestructure { destructs, .. } => destructs
             .iter()
             .for_each(|d| visitor.visit_record_destruct(&d.value, d.region)),
+        TupleDestructure { destructs, .. } => destructs.iter().for_each(|d| {
+            let (v, lp) = &d.value.typ;
+            visitor.visit_pattern(&lp.value, lp.region, Some(*v))
+        }),
+        List {
+            patterns, elem_var, ..
+        } => patterns
+            .patterns
+            .iter()
+            .for_each(|p| visitor.visit_pattern(&p.value, p.region, Some(*elem_var))),
         NumLiteral(..) => { /* terminal */ }
         IntLiteral(..) => { /* terminal */ }
         FloatLiteral(..) => { /* terminal */ }
@@ -533,6 +569,49 @@ pub fn find_type_at(region: Region, decls: &Declarations) -> Option<Variable> {
     visitor.typ
 }
 
+struct ClosestTypeVisitor {
+    position: Region,
+    found: Option<(Region, Variable)>,
+}
+
+impl Visitor for ClosestTypeVisitor {
+    fn should_visit(&mut self, region: Region) -> bool {
+        region.contains(&self.position)
+    }
+
+    fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+        if self.should_visit(region) {
+            self.found = Some((region, var));
+
+            walk_expr(self, expr, var);
+        }
+    }
+
+    fn visit_pattern(&mut self, pat: &Pattern, region: Region, opt_var: Option<Variable>) {
+        if self.should_visit(region) {
+            if let Some(var) = opt_var {
+                self.found = Some((region, var));
+            }
+
+            walk_pattern(self, pat);
+        }
+    }
+}
+
+/// Finds the innermost expression or pattern whose region contains `position`, returning its
+/// region and type variable. Useful for editor tooling, where we only know the cursor position.
+pub fn find_closest_type_at(
+    position: Position,
+    decls: &Declarations,
+) -> Option<(Region, Variable)> {
+    let mut visitor = ClosestTypeVisitor {
+        position: Region::new(position, position),
+        found: None,
+    };
+    visitor.visit_decls(decls);
+    visitor.found
+}
+
 /// Given an ability Foo has foo : ..., returns (T, foo1) if the symbol at the given region is a
 /// symbol foo1 that specializes foo for T. Otherwise if the symbol is foo but the specialization
 /// is unknown, (Foo, foo) is returned. Otherwise [None] is returned.
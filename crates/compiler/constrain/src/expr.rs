@@ -567,6 +567,38 @@ pub fn constrain_expr(
             constraints.exists_many(vars, all_constraints)
         }
 
+        Dbg {
+            loc_message,
+            loc_continuation,
+            variable,
+            symbol,
+        } => {
+            let dbg_type = Type::Variable(*variable);
+
+            let message_con = constrain_expr(
+                constraints,
+                env,
+                loc_message.region,
+                &loc_message.value,
+                NoExpectation(dbg_type.clone()),
+            );
+
+            let continuation_con = constrain_expr(
+                constraints,
+                env,
+                loc_continuation.region,
+                &loc_continuation.value,
+                expected,
+            );
+
+            // The message is bound to `symbol`; a `dbg` without a continuation looks it up.
+            let def_types = [(*symbol, Loc::at(loc_message.region, dbg_type))];
+            let dbg_con =
+                constraints.let_constraint([], [], def_types, message_con, continuation_con);
+
+            constraints.exists([*variable], dbg_con)
+        }
+
         If {
             cond_var,
             branch_var,
@@ -937,6 +969,72 @@ pub fn constrain_expr(
                 [constraint, eq, record_con],
             )
         }
+        Expr::Tuple { tuple_var, elems } => {
+            let mut elem_types = VecMap::with_capacity(elems.len());
+            let mut elem_vars = Vec::with_capacity(elems.len());
+
+            // Constraints need capacity for each elem
+            // + 1 for the tuple itself + 1 for tuple var
+            let mut tuple_constraints = Vec::with_capacity(2 + elems.len());
+
+            for (index, (elem_var, loc_expr)) in elems.iter().enumerate() {
+                let (elem_type, elem_con) = constrain_field(constraints, env, *elem_var, loc_expr);
+
+                elem_vars.push(*elem_var);
+                elem_types.insert(index, elem_type);
+
+                tuple_constraints.push(elem_con);
+            }
+
+            let tuple_type = Type::Tuple(elem_types, TypeExtension::Closed);
+
+            let tuple_con = constraints.equal_types_with_storage(
+                tuple_type,
+                expected,
+                Category::Tuple,
+                region,
+                *tuple_var,
+            );
+
+            tuple_constraints.push(tuple_con);
+            elem_vars.push(*tuple_var);
+
+            let and_constraint = constraints.and_constraint(tuple_constraints);
+            constraints.exists(elem_vars, and_constraint)
+        }
+        TupleAccess {
+            tuple_var,
+            ext_var,
+            elem_var,
+            loc_expr,
+            index,
+        } => {
+            let ext_var = *ext_var;
+            let ext_type = Type::Variable(ext_var);
+            let elem_var = *elem_var;
+            let elem_type = Type::Variable(elem_var);
+
+            let mut tup_elem_types = VecMap::with_capacity(1);
+            tup_elem_types.insert(*index, elem_type);
+
+            let tuple_type = Type::Tuple(tup_elem_types, TypeExtension::from_type(ext_type));
+            let tuple_expected = Expected::NoExpectation(tuple_type);
+
+            let category = Category::TupleAccess(*index);
+
+            let tuple_con = constraints.equal_types_var(
+                *tuple_var,
+                tuple_expected.clone(),
+                category.clone(),
+                region,
+            );
+
+            let constraint =
+                constrain_expr(constraints, env, region, &loc_expr.value, tuple_expected);
+
+            let eq = constraints.equal_types_var(elem_var, expected, category, region);
+            constraints.exists_many([*tuple_var, elem_var, ext_var], [constraint, eq, tuple_con])
+        }
         Accessor(AccessorData {
             name: closure_name,
             function_var,
@@ -3,7 +3,7 @@ use crate::expr::{constrain_expr, Env};
 use roc_can::constraint::{Constraint, Constraints};
 use roc_can::expected::{Expected, PExpected};
 use roc_can::pattern::Pattern::{self, *};
-use roc_can::pattern::{DestructType, RecordDestruct};
+use roc_can::pattern::{DestructType, RecordDestruct, TupleDestruct};
 use roc_collections::all::{HumanIndex, SendMap};
 use roc_collections::VecMap;
 use roc_module::ident::Lowercase;
@@ -101,6 +101,46 @@ fn headers_from_annotation_help(
             _ => false,
         },
 
+        TupleDestructure { destructs, .. } => match annotation.value.shallow_dealias() {
+            Type::Tuple(elems, _) => destructs.iter().all(|loc_destruct| {
+                let destruct = &loc_destruct.value;
+                let (_, loc_pattern) = &destruct.typ;
+
+                match elems.get(&destruct.destruct_index) {
+                    Some(elem_type) => headers_from_annotation_help(
+                        &loc_pattern.value,
+                        &Loc::at(annotation.region, elem_type),
+                        headers,
+                    ),
+                    None => false,
+                }
+            }),
+            _ => false,
+        },
+
+        List { patterns, .. } => match annotation.value.shallow_dealias() {
+            Type::Apply(Symbol::LIST_LIST, type_arguments, _) => {
+                let elem_type = match type_arguments.as_slice() {
+                    [elem_type] => elem_type,
+                    _ => return false,
+                };
+
+                if let Some((_, Some(rest_sym))) = &patterns.opt_rest {
+                    let typ = Loc::at(annotation.region, annotation.value.clone());
+                    headers.insert(rest_sym.value, typ);
+                }
+
+                patterns.patterns.iter().all(|loc_pattern| {
+                    headers_from_annotation_help(
+                        &loc_pattern.value,
+                        &Loc::at(annotation.region, elem_type),
+                        headers,
+                    )
+                })
+            }
+            _ => false,
+        },
+
         AppliedTag {
             tag_name,
             arguments,
@@ -446,6 +486,119 @@ pub fn constrain_pattern(
             state.constraints.push(whole_con);
             state.constraints.push(record_con);
         }
+        TupleDestructure {
+            whole_var,
+            ext_var,
+            destructs,
+        } => {
+            state.vars.push(*whole_var);
+            state.vars.push(*ext_var);
+            let ext_type = Type::Variable(*ext_var);
+
+            let mut elem_types: VecMap<usize, Type> = VecMap::default();
+
+            for Loc {
+                value:
+                    TupleDestruct {
+                        var,
+                        destruct_index: index,
+                        typ,
+                    },
+                ..
+            } in destructs
+            {
+                let pat_type = Type::Variable(*var);
+                let expected = PExpected::NoExpectation(pat_type.clone());
+
+                let (guard_var, loc_guard) = typ;
+                state.constraints.push(constraints.pattern_presence(
+                    Type::Variable(*guard_var),
+                    PExpected::ForReason(PReason::PatternGuard, pat_type.clone(), loc_guard.region),
+                    PatternCategory::PatternGuard,
+                    region,
+                ));
+                state.vars.push(*guard_var);
+
+                constrain_pattern(
+                    constraints,
+                    env,
+                    &loc_guard.value,
+                    loc_guard.region,
+                    expected,
+                    state,
+                );
+
+                elem_types.insert(*index, pat_type);
+
+                state.vars.push(*var);
+            }
+
+            let tuple_type = Type::Tuple(elem_types, TypeExtension::from_type(ext_type));
+
+            let whole_con = constraints.equal_types(
+                Type::Variable(*whole_var),
+                Expected::NoExpectation(tuple_type),
+                Category::Storage(std::file!(), std::line!()),
+                region,
+            );
+
+            let tuple_con = constraints.pattern_presence(
+                Type::Variable(*whole_var),
+                expected,
+                PatternCategory::Tuple,
+                region,
+            );
+
+            state.constraints.push(whole_con);
+            state.constraints.push(tuple_con);
+        }
+        List {
+            list_var,
+            elem_var,
+            patterns,
+        } => {
+            let elem_type = Type::Variable(*elem_var);
+
+            for loc_pat in patterns.patterns.iter() {
+                let expected = PExpected::ForReason(PReason::ListElem, elem_type.clone(), region);
+
+                constrain_pattern(
+                    constraints,
+                    env,
+                    &loc_pat.value,
+                    loc_pat.region,
+                    expected,
+                    state,
+                );
+            }
+
+            let list_type = builtins::list_type(elem_type);
+
+            if let Some((_, Some(rest_sym))) = &patterns.opt_rest {
+                state
+                    .headers
+                    .insert(rest_sym.value, Loc::at(rest_sym.region, list_type.clone()));
+            }
+
+            let whole_con = constraints.equal_types(
+                Type::Variable(*list_var),
+                Expected::NoExpectation(list_type),
+                Category::Storage(std::file!(), std::line!()),
+                region,
+            );
+
+            let list_con = constraints.pattern_presence(
+                Type::Variable(*list_var),
+                expected,
+                PatternCategory::List,
+                region,
+            );
+
+            state.vars.push(*list_var);
+            state.vars.push(*elem_var);
+            state.constraints.push(whole_con);
+            state.constraints.push(list_con);
+        }
         AppliedTag {
             whole_var,
             ext_var,
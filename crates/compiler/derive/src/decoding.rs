@@ -1,16 +1,17 @@
 //! Derivers for the `Decoding` ability.
 
 use roc_can::expr::{
-    AnnotatedMark, ClosureData, Expr, Field, Recursive, WhenBranch, WhenBranchPattern,
+    AnnotatedMark, ClosureData, Expr, Field, IntValue, Recursive, WhenBranch, WhenBranchPattern,
 };
 use roc_can::pattern::Pattern;
 use roc_collections::SendMap;
 use roc_derive_key::decoding::FlatDecodableKey;
 use roc_error_macros::internal_error;
 use roc_module::called_via::CalledVia;
-use roc_module::ident::Lowercase;
+use roc_module::ident::{Lowercase, TagName};
 use roc_module::symbol::Symbol;
 use roc_region::all::{Loc, Region};
+use roc_types::num::{IntBound, IntLitWidth};
 use roc_types::subs::{
     Content, ExhaustiveMark, FlatType, GetSubsSlice, LambdaSet, OptVariable, RecordFields,
     RedundantMark, SubsSlice, UnionLambdas, UnionTags, Variable,
@@ -28,6 +29,7 @@ pub(crate) fn derive_decoder(
     let (body, body_type) = match key {
         FlatDecodableKey::List() => decoder_list(env, def_symbol),
         FlatDecodableKey::Record(fields) => decoder_record(env, def_symbol, fields),
+        FlatDecodableKey::TagUnion(tags) => decoder_tag_union(env, def_symbol, tags),
     };
 
     let specialization_lambda_sets =
@@ -70,8 +72,11 @@ pub(crate) fn derive_decoder(
 //             Ok first ->
 //                 when f1 is
 //                     Ok second -> Ok {first, second}
-//                     Err NoField -> Err TooShort
-//             Err NoField -> Err TooShort
+//                     Err NoField -> Err (Decode.missing (Field "second"))
+//             Err NoField -> Err (Decode.missing (Field "first"))
+//
+// Errors from decoding the value of a field are wrapped with the path to that field, e.g.
+// `Decode.prependPath err (Field "first")`.
 //
 //     Decode.custom \bytes, fmt -> Decode.decodeWith bytes (Decode.record initialState stepField finalizer) fmt
 fn decoder_record(env: &mut Env, _def_symbol: Symbol, fields: Vec<Lowercase>) -> (Expr, Variable) {
@@ -103,37 +108,54 @@ fn decoder_record(env: &mut Env, _def_symbol: Symbol, fields: Vec<Lowercase>) ->
         decode_err_var,
     );
 
-    // Build up the type of `Decode.record` we expect
-    let record_decoder_var = env.subs.fresh_unnamed_flex_var();
-    let decode_record_lambda_set = env.subs.fresh_unnamed_flex_var();
-    let decode_record_var = env.import_builtin_symbol_var(Symbol::DECODE_RECORD);
-    let this_decode_record_var = {
+    // Decode.custom \bytes, fmt -> Decode.decodeWith bytes (Decode.record initialState stepField finalizer) fmt
+    decoder_from_steps(
+        env,
+        Symbol::DECODE_RECORD,
+        (initial_state, initial_state_var),
+        (step_field, step_var),
+        (finalizer, finalizer_var),
+    )
+}
+
+// Calls a `DecoderFormatting` member that drives decoding with a state machine, such as
+// `Decode.record`, and wraps the resulting decoder in `Decode.custom`:
+//
+//   Decode.custom \bytes, fmt -> Decode.decodeWith bytes (member initialState step finalizer) fmt
+fn decoder_from_steps(
+    env: &mut Env,
+    member: Symbol,
+    (initial_state, initial_state_var): (Expr, Variable),
+    (step, step_var): (Expr, Variable),
+    (finalizer, finalizer_var): (Expr, Variable),
+) -> (Expr, Variable) {
+    // Build up the type of the member we expect
+    let member_decoder_var = env.subs.fresh_unnamed_flex_var();
+    let member_lambda_set = env.subs.fresh_unnamed_flex_var();
+    let member_var = env.import_builtin_symbol_var(member);
+    let this_member_var = {
         let flat_type = FlatType::Func(
             SubsSlice::insert_into_subs(env.subs, [initial_state_var, step_var, finalizer_var]),
-            decode_record_lambda_set,
-            record_decoder_var,
+            member_lambda_set,
+            member_decoder_var,
         );
 
         synth_var(env.subs, Content::Structure(flat_type))
     };
 
-    env.unify(decode_record_var, this_decode_record_var);
+    env.unify(member_var, this_member_var);
 
-    // Decode.record initialState stepField finalizer
-    let call_decode_record = Expr::Call(
+    // member initialState step finalizer
+    let call_member = Expr::Call(
         Box::new((
-            this_decode_record_var,
-            Loc::at_zero(Expr::AbilityMember(
-                Symbol::DECODE_RECORD,
-                None,
-                this_decode_record_var,
-            )),
-            decode_record_lambda_set,
-            record_decoder_var,
+            this_member_var,
+            Loc::at_zero(Expr::AbilityMember(member, None, this_member_var)),
+            member_lambda_set,
+            member_decoder_var,
         )),
         vec![
             (initial_state_var, Loc::at_zero(initial_state)),
-            (step_var, Loc::at_zero(step_field)),
+            (step_var, Loc::at_zero(step)),
             (finalizer_var, Loc::at_zero(finalizer)),
         ],
         CalledVia::Space,
@@ -149,7 +171,7 @@ fn decoder_record(env: &mut Env, _def_symbol: Symbol, fields: Vec<Lowercase>) ->
             bytes_sym,
             (fmt_sym, fmt_var),
             vec![],
-            (call_decode_record, record_decoder_var),
+            (call_member, member_decoder_var),
         );
 
         (decode_custom, decode_custom_var)
@@ -171,7 +193,7 @@ fn decoder_record(env: &mut Env, _def_symbol: Symbol, fields: Vec<Lowercase>) ->
 //                             rest: rec.rest,
 //                             result: when rec.result is
 //                                 Ok val -> Ok {state & first: Ok val},
-//                                 Err err -> Err err
+//                                 Err err -> Err (Decode.prependPath err (Field "first"))
 //                         })
 //
 //         "second" ->
@@ -182,7 +204,7 @@ fn decoder_record(env: &mut Env, _def_symbol: Symbol, fields: Vec<Lowercase>) ->
 //                             rest: rec.rest,
 //                             result: when rec.result is
 //                                 Ok val -> Ok {state & second: Ok val},
-//                                 Err err -> Err err
+//                                 Err err -> Err (Decode.prependPath err (Field "second"))
 //                         })
 //
 //         _ -> Skip
@@ -199,436 +221,25 @@ fn decoder_record_step_field(
 
     // +1 because of the default branch.
     let mut branches = Vec::with_capacity(fields.len() + 1);
-    let keep_payload_var = env.subs.fresh_unnamed_flex_var();
-    let keep_or_skip_var = {
-        let keep_payload_subs_slice = SubsSlice::insert_into_subs(env.subs, [keep_payload_var]);
-        let flat_type = FlatType::TagUnion(
-            UnionTags::insert_slices_into_subs(
-                env.subs,
-                [
-                    ("Keep".into(), keep_payload_subs_slice),
-                    ("Skip".into(), Default::default()),
-                ],
-            ),
-            Variable::EMPTY_TAG_UNION,
-        );
-
-        synth_var(env.subs, Content::Structure(flat_type))
-    };
+    let (keep_or_skip_var, keep_payload_var) = keep_or_skip_var(env);
 
     for ((field_name, &field_var), &result_field_var) in fields
         .into_iter()
         .zip(field_vars.iter())
         .zip(result_field_vars.iter())
     {
-        // Example:
-        // "first" ->
-        //     Keep (Decode.custom \bytes, fmt ->
-        //         # Uses a single-branch `when` because `let` is more expensive to monomorphize
-        //         # due to checks for polymorphic expressions, and `rec` would be polymorphic.
-        //         when Decode.decodeWith bytes Decode.decoder fmt is
-        //             rec ->
-        //                 {
-        //                     rest: rec.rest,
-        //                     result: when rec.result is
-        //                         Ok val -> Ok {state & first: Ok val},
-        //                         Err err -> Err err
-        //                 }
-        //     )
-
-        let this_custom_callback_var;
-        let custom_callback_ret_var;
-        let custom_callback = {
-            // \bytes, fmt ->
-            //     when Decode.decodeWith bytes Decode.decoder fmt is
-            //         rec ->
-            //             {
-            //                 rest: rec.rest,
-            //                 result: when rec.result is
-            //                     Ok val -> Ok {state & first: Ok val},
-            //                     Err err -> Err err
-            //             }
-            let bytes_arg_symbol = env.new_symbol("bytes");
-            let fmt_arg_symbol = env.new_symbol("fmt");
-            let bytes_arg_var = env.subs.fresh_unnamed_flex_var();
-            let fmt_arg_var = env.subs.fresh_unnamed_flex_var();
-
-            // rec.result : [Ok field_var, Err DecodeError]
-            let rec_dot_result = {
-                let tag_union = FlatType::TagUnion(
-                    UnionTags::for_result(env.subs, field_var, decode_err_var),
-                    Variable::EMPTY_TAG_UNION,
-                );
-
-                synth_var(env.subs, Content::Structure(tag_union))
-            };
-
-            // rec : { rest: List U8, result: (typeof rec.result) }
-            let rec_var = {
-                let fields = RecordFields::insert_into_subs(
-                    env.subs,
-                    [
-                        ("rest".into(), RecordField::Required(Variable::LIST_U8)),
-                        ("result".into(), RecordField::Required(rec_dot_result)),
-                    ],
-                );
-                let record = FlatType::Record(fields, Variable::EMPTY_RECORD);
-
-                synth_var(env.subs, Content::Structure(record))
-            };
-
-            // `Decode.decoder` for the field's value
-            let decoder_var = env.import_builtin_symbol_var(Symbol::DECODE_DECODER);
-            let decode_with_var = env.import_builtin_symbol_var(Symbol::DECODE_DECODE_WITH);
-            let lambda_set_var = env.subs.fresh_unnamed_flex_var();
-            let this_decode_with_var = {
-                let subs_slice = SubsSlice::insert_into_subs(
-                    env.subs,
-                    [bytes_arg_var, decoder_var, fmt_arg_var],
-                );
-                let this_decode_with_var = synth_var(
-                    env.subs,
-                    Content::Structure(FlatType::Func(subs_slice, lambda_set_var, rec_var)),
-                );
-
-                env.unify(decode_with_var, this_decode_with_var);
-
-                this_decode_with_var
-            };
-
-            // The result of decoding this field's value - either the updated state, or a decoding error.
-            let when_expr_var = {
-                let flat_type = FlatType::TagUnion(
-                    UnionTags::for_result(env.subs, state_record_var, decode_err_var),
-                    Variable::EMPTY_TAG_UNION,
-                );
-
-                synth_var(env.subs, Content::Structure(flat_type))
-            };
-
-            // What our decoder passed to `Decode.custom` returns - the result of decoding the
-            // field's value, and the remaining bytes.
-            custom_callback_ret_var = {
-                let rest_field = RecordField::Required(Variable::LIST_U8);
-                let result_field = RecordField::Required(when_expr_var);
-                let flat_type = FlatType::Record(
-                    RecordFields::insert_into_subs(
-                        env.subs,
-                        [("rest".into(), rest_field), ("result".into(), result_field)],
-                    ),
-                    Variable::EMPTY_RECORD,
-                );
-
-                synth_var(env.subs, Content::Structure(flat_type))
-            };
-
-            let custom_callback_body = {
-                let rec_symbol = env.new_symbol("rec");
-
-                // # Uses a single-branch `when` because `let` is more expensive to monomorphize
-                // # due to checks for polymorphic expressions, and `rec` would be polymorphic.
-                // when Decode.decodeWith bytes Decode.decoder fmt is
-                //     rec ->
-                //         {
-                //             rest: rec.rest,
-                //             result: when rec.result is
-                //                 Ok val -> Ok {state & first: Ok val},
-                //                 Err err -> Err err
-                //         }
-                let branch_body = {
-                    let result_val = {
-                        // result: when rec.result is
-                        //     Ok val -> Ok {state & first: Ok val},
-                        //     Err err -> Err err
-                        let ok_val_symbol = env.new_symbol("val");
-                        let err_val_symbol = env.new_symbol("err");
-                        let ok_branch_expr = {
-                            // Ok {state & first: Ok val},
-                            let mut updates = SendMap::default();
-
-                            updates.insert(
-                                field_name.clone(),
-                                Field {
-                                    var: result_field_var,
-                                    region: Region::zero(),
-                                    loc_expr: Box::new(Loc::at_zero(Expr::Tag {
-                                        tag_union_var: result_field_var,
-                                        ext_var: env.new_ext_var(ExtensionKind::TagUnion),
-                                        name: "Ok".into(),
-                                        arguments: vec![(
-                                            field_var,
-                                            Loc::at_zero(Expr::Var(ok_val_symbol)),
-                                        )],
-                                    })),
-                                },
-                            );
-
-                            let updated_record = Expr::Update {
-                                record_var: state_record_var,
-                                ext_var: env.new_ext_var(ExtensionKind::Record),
-                                symbol: state_arg_symbol,
-                                updates,
-                            };
-
-                            Expr::Tag {
-                                tag_union_var: when_expr_var,
-                                ext_var: env.new_ext_var(ExtensionKind::TagUnion),
-                                name: "Ok".into(),
-                                arguments: vec![(state_record_var, Loc::at_zero(updated_record))],
-                            }
-                        };
-
-                        let branches = vec![
-                            // Ok val -> Ok {state & first: Ok val},
-                            WhenBranch {
-                                patterns: vec![WhenBranchPattern {
-                                    pattern: Loc::at_zero(Pattern::AppliedTag {
-                                        whole_var: rec_dot_result,
-                                        ext_var: Variable::EMPTY_TAG_UNION,
-                                        tag_name: "Ok".into(),
-                                        arguments: vec![(
-                                            field_var,
-                                            Loc::at_zero(Pattern::Identifier(ok_val_symbol)),
-                                        )],
-                                    }),
-                                    degenerate: false,
-                                }],
-                                value: Loc::at_zero(ok_branch_expr),
-                                guard: None,
-                                redundant: RedundantMark::known_non_redundant(),
-                            },
-                            // Err err -> Err err
-                            WhenBranch {
-                                patterns: vec![WhenBranchPattern {
-                                    pattern: Loc::at_zero(Pattern::AppliedTag {
-                                        whole_var: rec_dot_result,
-                                        ext_var: Variable::EMPTY_TAG_UNION,
-                                        tag_name: "Err".into(),
-                                        arguments: vec![(
-                                            decode_err_var,
-                                            Loc::at_zero(Pattern::Identifier(err_val_symbol)),
-                                        )],
-                                    }),
-                                    degenerate: false,
-                                }],
-                                value: Loc::at_zero(Expr::Tag {
-                                    tag_union_var: when_expr_var,
-                                    ext_var: env.new_ext_var(ExtensionKind::TagUnion),
-                                    name: "Err".into(),
-                                    arguments: vec![(
-                                        decode_err_var,
-                                        Loc::at_zero(Expr::Var(err_val_symbol)),
-                                    )],
-                                }),
-                                guard: None,
-                                redundant: RedundantMark::known_non_redundant(),
-                            },
-                        ];
-
-                        // when rec.result is
-                        //     Ok val -> Ok {state & first: Ok val},
-                        //     Err err -> Err err
-                        Expr::When {
-                            loc_cond: Box::new(Loc::at_zero(Expr::Access {
-                                record_var: rec_var,
-                                ext_var: env.new_ext_var(ExtensionKind::Record),
-                                field_var: rec_dot_result,
-                                loc_expr: Box::new(Loc::at_zero(Expr::Var(rec_symbol))),
-                                field: "result".into(),
-                            })),
-                            cond_var: rec_dot_result,
-                            expr_var: when_expr_var,
-                            region: Region::zero(),
-                            branches,
-                            branches_cond_var: rec_dot_result,
-                            exhaustive: ExhaustiveMark::known_exhaustive(),
-                        }
-                    };
-
-                    // {
-                    //     rest: rec.rest,
-                    //     result: when rec.result is
-                    //         Ok val -> Ok {state & first: Ok val},
-                    //         Err err -> Err err
-                    // }
-                    let mut fields_map = SendMap::default();
-
-                    fields_map.insert(
-                        "rest".into(),
-                        Field {
-                            var: Variable::LIST_U8,
-                            region: Region::zero(),
-                            loc_expr: Box::new(Loc::at_zero(Expr::Access {
-                                record_var: rec_var,
-                                ext_var: env.new_ext_var(ExtensionKind::Record),
-                                field_var: Variable::LIST_U8,
-                                loc_expr: Box::new(Loc::at_zero(Expr::Var(rec_symbol))),
-                                field: "rest".into(),
-                            })),
-                        },
-                    );
-
-                    // result: when rec.result is
-                    //     Ok val -> Ok {state & first: Ok val},
-                    //     Err err -> Err err
-                    fields_map.insert(
-                        "result".into(),
-                        Field {
-                            var: when_expr_var,
-                            region: Region::zero(),
-                            loc_expr: Box::new(Loc::at_zero(result_val)),
-                        },
-                    );
-
-                    Expr::Record {
-                        record_var: custom_callback_ret_var,
-                        fields: fields_map,
-                    }
-                };
-
-                let branch = WhenBranch {
-                    patterns: vec![WhenBranchPattern {
-                        pattern: Loc::at_zero(Pattern::Identifier(rec_symbol)),
-                        degenerate: false,
-                    }],
-                    value: Loc::at_zero(branch_body),
-                    guard: None,
-                    redundant: RedundantMark::known_non_redundant(),
-                };
-
-                let condition_expr = Expr::Call(
-                    Box::new((
-                        this_decode_with_var,
-                        Loc::at_zero(Expr::Var(Symbol::DECODE_DECODE_WITH)),
-                        lambda_set_var,
-                        rec_var,
-                    )),
-                    vec![
-                        (Variable::LIST_U8, Loc::at_zero(Expr::Var(bytes_arg_symbol))),
-                        (
-                            decoder_var,
-                            Loc::at_zero(Expr::AbilityMember(
-                                Symbol::DECODE_DECODER,
-                                None,
-                                decoder_var,
-                            )),
-                        ),
-                        (fmt_arg_var, Loc::at_zero(Expr::Var(fmt_arg_symbol))),
-                    ],
-                    CalledVia::Space,
-                );
-
-                // when Decode.decodeWith bytes Decode.decoder fmt is
-                Expr::When {
-                    loc_cond: Box::new(Loc::at_zero(condition_expr)),
-                    cond_var: rec_var,
-                    expr_var: custom_callback_ret_var,
-                    region: Region::zero(),
-                    branches: vec![branch],
-                    branches_cond_var: rec_var,
-                    exhaustive: ExhaustiveMark::known_exhaustive(),
-                }
-            };
-
-            let custom_closure_symbol = env.new_symbol("customCallback");
-            this_custom_callback_var = env.subs.fresh_unnamed_flex_var();
-            let custom_callback_lambda_set_var = {
-                let content = Content::LambdaSet(LambdaSet {
-                    solved: UnionLambdas::insert_into_subs(
-                        env.subs,
-                        [(custom_closure_symbol, [state_record_var])],
-                    ),
-                    recursion_var: OptVariable::NONE,
-                    unspecialized: Default::default(),
-                    ambient_function: this_custom_callback_var,
-                });
-                let custom_callback_lambda_set_var = synth_var(env.subs, content);
-                let subs_slice =
-                    SubsSlice::insert_into_subs(env.subs, [bytes_arg_var, fmt_arg_var]);
-
-                env.subs.set_content(
-                    this_custom_callback_var,
-                    Content::Structure(FlatType::Func(
-                        subs_slice,
-                        custom_callback_lambda_set_var,
-                        custom_callback_ret_var,
-                    )),
-                );
-
-                custom_callback_lambda_set_var
-            };
-
-            // \bytes, fmt -> …
-            Expr::Closure(ClosureData {
-                function_type: this_custom_callback_var,
-                closure_type: custom_callback_lambda_set_var,
-                return_type: custom_callback_ret_var,
-                name: custom_closure_symbol,
-                captured_symbols: vec![(state_arg_symbol, state_record_var)],
-                recursive: Recursive::NotRecursive,
-                arguments: vec![
-                    (
-                        bytes_arg_var,
-                        AnnotatedMark::known_exhaustive(),
-                        Loc::at_zero(Pattern::Identifier(bytes_arg_symbol)),
-                    ),
-                    (
-                        fmt_arg_var,
-                        AnnotatedMark::known_exhaustive(),
-                        Loc::at_zero(Pattern::Identifier(fmt_arg_symbol)),
-                    ),
-                ],
-                loc_body: Box::new(Loc::at_zero(custom_callback_body)),
-            })
-        };
-
-        let decode_custom_ret_var = env.subs.fresh_unnamed_flex_var();
-        let decode_custom = {
-            let decode_custom_var = env.import_builtin_symbol_var(Symbol::DECODE_CUSTOM);
-            let decode_custom_closure_var = env.subs.fresh_unnamed_flex_var();
-            let this_decode_custom_var = {
-                let subs_slice = SubsSlice::insert_into_subs(env.subs, [this_custom_callback_var]);
-                let flat_type =
-                    FlatType::Func(subs_slice, decode_custom_closure_var, decode_custom_ret_var);
-
-                synth_var(env.subs, Content::Structure(flat_type))
-            };
-
-            env.unify(decode_custom_var, this_decode_custom_var);
-
-            // Decode.custom \bytes, fmt -> …
-            Expr::Call(
-                Box::new((
-                    this_decode_custom_var,
-                    Loc::at_zero(Expr::Var(Symbol::DECODE_CUSTOM)),
-                    decode_custom_closure_var,
-                    decode_custom_ret_var,
-                )),
-                vec![(this_custom_callback_var, Loc::at_zero(custom_callback))],
-                CalledVia::Space,
-            )
-        };
-
-        env.unify(keep_payload_var, decode_custom_ret_var);
-
-        let keep = {
-            // Keep (Decode.custom \bytes, fmt ->
-            //     when Decode.decodeWith bytes Decode.decoder fmt is
-            //         rec ->
-            //             {
-            //                 rest: rec.rest,
-            //                 result: when rec.result is
-            //                     Ok val -> Ok {state & first: Ok val},
-            //                     Err err -> Err err
-            //             }
-            // )
-            Expr::Tag {
-                tag_union_var: keep_or_skip_var,
-                ext_var: env.new_ext_var(ExtensionKind::TagUnion),
-                name: "Keep".into(),
-                arguments: vec![(decode_custom_ret_var, Loc::at_zero(decode_custom))],
-            }
-        };
+        let keep = decoder_step_field_keep(
+            env,
+            &field_name,
+            &[PathSegment::Field(&field_name)],
+            field_var,
+            result_field_var,
+            state_arg_symbol,
+            state_record_var,
+            decode_err_var,
+            keep_or_skip_var,
+            keep_payload_var,
+        );
 
         let branch = {
             // "first" ->
@@ -639,7 +250,7 @@ fn decoder_record_step_field(
             //                     rest: rec.rest,
             //                     result: when rec.result is
             //                         Ok val -> Ok {state & first: Ok val},
-            //                         Err err -> Err err
+            //                         Err err -> Err (Decode.prependPath err (Field "first"))
             //                 }
             //     )
             WhenBranch {
@@ -732,14 +343,452 @@ fn decoder_record_step_field(
     (expr, function_type)
 }
 
+// The type `[Keep payload, Skip]` returned by steppers, along with the type of `payload`.
+fn keep_or_skip_var(env: &mut Env) -> (Variable, Variable) {
+    let keep_payload_var = env.subs.fresh_unnamed_flex_var();
+    let keep_payload_subs_slice = SubsSlice::insert_into_subs(env.subs, [keep_payload_var]);
+    let flat_type = FlatType::TagUnion(
+        UnionTags::insert_slices_into_subs(
+            env.subs,
+            [
+                ("Keep".into(), keep_payload_subs_slice),
+                ("Skip".into(), Default::default()),
+            ],
+        ),
+        Variable::EMPTY_TAG_UNION,
+    );
+
+    (
+        synth_var(env.subs, Content::Structure(flat_type)),
+        keep_payload_var,
+    )
+}
+
+// Decodes the next value and stores it in the field `first` of the decoding state. Decoding
+// errors are wrapped with `path`, the location of the value within the decoded type. Example:
+//
+// Keep (Decode.custom \bytes, fmt ->
+//     # Uses a single-branch `when` because `let` is more expensive to monomorphize
+//     # due to checks for polymorphic expressions, and `rec` would be polymorphic.
+//     when Decode.decodeWith bytes Decode.decoder fmt is
+//         rec ->
+//             {
+//                 rest: rec.rest,
+//                 result: when rec.result is
+//                     Ok val -> Ok {state & first: Ok val},
+//                     Err err -> Err (Decode.prependPath err (Field "first"))
+//             }
+// )
+#[allow(clippy::too_many_arguments)]
+fn decoder_step_field_keep(
+    env: &mut Env,
+    field_name: &Lowercase,
+    path: &[PathSegment],
+    field_var: Variable,
+    result_field_var: Variable,
+    state_arg_symbol: Symbol,
+    state_record_var: Variable,
+    decode_err_var: Variable,
+    keep_or_skip_var: Variable,
+    keep_payload_var: Variable,
+) -> Expr {
+    let this_custom_callback_var;
+    let custom_callback_ret_var;
+    let custom_callback = {
+        // \bytes, fmt ->
+        //     when Decode.decodeWith bytes Decode.decoder fmt is
+        //         rec ->
+        //             {
+        //                 rest: rec.rest,
+        //                 result: when rec.result is
+        //                     Ok val -> Ok {state & first: Ok val},
+        //                     Err err -> Err (Decode.prependPath err (Field "first"))
+        //             }
+        let bytes_arg_symbol = env.new_symbol("bytes");
+        let fmt_arg_symbol = env.new_symbol("fmt");
+        let bytes_arg_var = env.subs.fresh_unnamed_flex_var();
+        let fmt_arg_var = env.subs.fresh_unnamed_flex_var();
+
+        // rec.result : [Ok field_var, Err DecodeError]
+        let rec_dot_result = {
+            let tag_union = FlatType::TagUnion(
+                UnionTags::for_result(env.subs, field_var, decode_err_var),
+                Variable::EMPTY_TAG_UNION,
+            );
+
+            synth_var(env.subs, Content::Structure(tag_union))
+        };
+
+        // rec : { rest: List U8, result: (typeof rec.result) }
+        let rec_var = {
+            let fields = RecordFields::insert_into_subs(
+                env.subs,
+                [
+                    ("rest".into(), RecordField::Required(Variable::LIST_U8)),
+                    ("result".into(), RecordField::Required(rec_dot_result)),
+                ],
+            );
+            let record = FlatType::Record(fields, Variable::EMPTY_RECORD);
+
+            synth_var(env.subs, Content::Structure(record))
+        };
+
+        // `Decode.decoder` for the field's value
+        let decoder_var = env.import_builtin_symbol_var(Symbol::DECODE_DECODER);
+        let decode_with_var = env.import_builtin_symbol_var(Symbol::DECODE_DECODE_WITH);
+        let lambda_set_var = env.subs.fresh_unnamed_flex_var();
+        let this_decode_with_var = {
+            let subs_slice =
+                SubsSlice::insert_into_subs(env.subs, [bytes_arg_var, decoder_var, fmt_arg_var]);
+            let this_decode_with_var = synth_var(
+                env.subs,
+                Content::Structure(FlatType::Func(subs_slice, lambda_set_var, rec_var)),
+            );
+
+            env.unify(decode_with_var, this_decode_with_var);
+
+            this_decode_with_var
+        };
+
+        // The result of decoding this field's value - either the updated state, or a decoding error.
+        let when_expr_var = {
+            let flat_type = FlatType::TagUnion(
+                UnionTags::for_result(env.subs, state_record_var, decode_err_var),
+                Variable::EMPTY_TAG_UNION,
+            );
+
+            synth_var(env.subs, Content::Structure(flat_type))
+        };
+
+        // What our decoder passed to `Decode.custom` returns - the result of decoding the
+        // field's value, and the remaining bytes.
+        custom_callback_ret_var = {
+            let rest_field = RecordField::Required(Variable::LIST_U8);
+            let result_field = RecordField::Required(when_expr_var);
+            let flat_type = FlatType::Record(
+                RecordFields::insert_into_subs(
+                    env.subs,
+                    [("rest".into(), rest_field), ("result".into(), result_field)],
+                ),
+                Variable::EMPTY_RECORD,
+            );
+
+            synth_var(env.subs, Content::Structure(flat_type))
+        };
+
+        let custom_callback_body = {
+            let rec_symbol = env.new_symbol("rec");
+
+            // # Uses a single-branch `when` because `let` is more expensive to monomorphize
+            // # due to checks for polymorphic expressions, and `rec` would be polymorphic.
+            // when Decode.decodeWith bytes Decode.decoder fmt is
+            //     rec ->
+            //         {
+            //             rest: rec.rest,
+            //             result: when rec.result is
+            //                 Ok val -> Ok {state & first: Ok val},
+            //                 Err err -> Err (Decode.prependPath err (Field "first"))
+            //         }
+            let branch_body = {
+                let result_val = {
+                    // result: when rec.result is
+                    //     Ok val -> Ok {state & first: Ok val},
+                    //     Err err -> Err (Decode.prependPath err (Field "first"))
+                    let ok_val_symbol = env.new_symbol("val");
+                    let err_val_symbol = env.new_symbol("err");
+                    let err_with_path =
+                        prepend_path(env, Expr::Var(err_val_symbol), path, decode_err_var);
+                    let ok_branch_expr = {
+                        // Ok {state & first: Ok val},
+                        let mut updates = SendMap::default();
+
+                        updates.insert(
+                            field_name.clone(),
+                            Field {
+                                var: result_field_var,
+                                region: Region::zero(),
+                                loc_expr: Box::new(Loc::at_zero(Expr::Tag {
+                                    tag_union_var: result_field_var,
+                                    ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                                    name: "Ok".into(),
+                                    arguments: vec![(
+                                        field_var,
+                                        Loc::at_zero(Expr::Var(ok_val_symbol)),
+                                    )],
+                                })),
+                            },
+                        );
+
+                        let updated_record = Expr::Update {
+                            record_var: state_record_var,
+                            ext_var: env.new_ext_var(ExtensionKind::Record),
+                            symbol: state_arg_symbol,
+                            updates,
+                        };
+
+                        Expr::Tag {
+                            tag_union_var: when_expr_var,
+                            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                            name: "Ok".into(),
+                            arguments: vec![(state_record_var, Loc::at_zero(updated_record))],
+                        }
+                    };
+
+                    let branches = vec![
+                        // Ok val -> Ok {state & first: Ok val},
+                        WhenBranch {
+                            patterns: vec![WhenBranchPattern {
+                                pattern: Loc::at_zero(Pattern::AppliedTag {
+                                    whole_var: rec_dot_result,
+                                    ext_var: Variable::EMPTY_TAG_UNION,
+                                    tag_name: "Ok".into(),
+                                    arguments: vec![(
+                                        field_var,
+                                        Loc::at_zero(Pattern::Identifier(ok_val_symbol)),
+                                    )],
+                                }),
+                                degenerate: false,
+                            }],
+                            value: Loc::at_zero(ok_branch_expr),
+                            guard: None,
+                            redundant: RedundantMark::known_non_redundant(),
+                        },
+                        // Err err -> Err (Decode.prependPath err (Field "first"))
+                        WhenBranch {
+                            patterns: vec![WhenBranchPattern {
+                                pattern: Loc::at_zero(Pattern::AppliedTag {
+                                    whole_var: rec_dot_result,
+                                    ext_var: Variable::EMPTY_TAG_UNION,
+                                    tag_name: "Err".into(),
+                                    arguments: vec![(
+                                        decode_err_var,
+                                        Loc::at_zero(Pattern::Identifier(err_val_symbol)),
+                                    )],
+                                }),
+                                degenerate: false,
+                            }],
+                            value: Loc::at_zero(Expr::Tag {
+                                tag_union_var: when_expr_var,
+                                ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                                name: "Err".into(),
+                                arguments: vec![(decode_err_var, Loc::at_zero(err_with_path))],
+                            }),
+                            guard: None,
+                            redundant: RedundantMark::known_non_redundant(),
+                        },
+                    ];
+
+                    // when rec.result is
+                    //     Ok val -> Ok {state & first: Ok val},
+                    //     Err err -> Err (Decode.prependPath err (Field "first"))
+                    Expr::When {
+                        loc_cond: Box::new(Loc::at_zero(Expr::Access {
+                            record_var: rec_var,
+                            ext_var: env.new_ext_var(ExtensionKind::Record),
+                            field_var: rec_dot_result,
+                            loc_expr: Box::new(Loc::at_zero(Expr::Var(rec_symbol))),
+                            field: "result".into(),
+                        })),
+                        cond_var: rec_dot_result,
+                        expr_var: when_expr_var,
+                        region: Region::zero(),
+                        branches,
+                        branches_cond_var: rec_dot_result,
+                        exhaustive: ExhaustiveMark::known_exhaustive(),
+                    }
+                };
+
+                // {
+                //     rest: rec.rest,
+                //     result: when rec.result is
+                //         Ok val -> Ok {state & first: Ok val},
+                //         Err err -> Err (Decode.prependPath err (Field "first"))
+                // }
+                let mut fields_map = SendMap::default();
+
+                fields_map.insert(
+                    "rest".into(),
+                    Field {
+                        var: Variable::LIST_U8,
+                        region: Region::zero(),
+                        loc_expr: Box::new(Loc::at_zero(Expr::Access {
+                            record_var: rec_var,
+                            ext_var: env.new_ext_var(ExtensionKind::Record),
+                            field_var: Variable::LIST_U8,
+                            loc_expr: Box::new(Loc::at_zero(Expr::Var(rec_symbol))),
+                            field: "rest".into(),
+                        })),
+                    },
+                );
+
+                // result: when rec.result is
+                //     Ok val -> Ok {state & first: Ok val},
+                //     Err err -> Err (Decode.prependPath err (Field "first"))
+                fields_map.insert(
+                    "result".into(),
+                    Field {
+                        var: when_expr_var,
+                        region: Region::zero(),
+                        loc_expr: Box::new(Loc::at_zero(result_val)),
+                    },
+                );
+
+                Expr::Record {
+                    record_var: custom_callback_ret_var,
+                    fields: fields_map,
+                }
+            };
+
+            let branch = WhenBranch {
+                patterns: vec![WhenBranchPattern {
+                    pattern: Loc::at_zero(Pattern::Identifier(rec_symbol)),
+                    degenerate: false,
+                }],
+                value: Loc::at_zero(branch_body),
+                guard: None,
+                redundant: RedundantMark::known_non_redundant(),
+            };
+
+            let condition_expr = Expr::Call(
+                Box::new((
+                    this_decode_with_var,
+                    Loc::at_zero(Expr::Var(Symbol::DECODE_DECODE_WITH)),
+                    lambda_set_var,
+                    rec_var,
+                )),
+                vec![
+                    (Variable::LIST_U8, Loc::at_zero(Expr::Var(bytes_arg_symbol))),
+                    (
+                        decoder_var,
+                        Loc::at_zero(Expr::AbilityMember(
+                            Symbol::DECODE_DECODER,
+                            None,
+                            decoder_var,
+                        )),
+                    ),
+                    (fmt_arg_var, Loc::at_zero(Expr::Var(fmt_arg_symbol))),
+                ],
+                CalledVia::Space,
+            );
+
+            // when Decode.decodeWith bytes Decode.decoder fmt is
+            Expr::When {
+                loc_cond: Box::new(Loc::at_zero(condition_expr)),
+                cond_var: rec_var,
+                expr_var: custom_callback_ret_var,
+                region: Region::zero(),
+                branches: vec![branch],
+                branches_cond_var: rec_var,
+                exhaustive: ExhaustiveMark::known_exhaustive(),
+            }
+        };
+
+        let custom_closure_symbol = env.new_symbol("customCallback");
+        this_custom_callback_var = env.subs.fresh_unnamed_flex_var();
+        let custom_callback_lambda_set_var = {
+            let content = Content::LambdaSet(LambdaSet {
+                solved: UnionLambdas::insert_into_subs(
+                    env.subs,
+                    [(custom_closure_symbol, [state_record_var])],
+                ),
+                recursion_var: OptVariable::NONE,
+                unspecialized: Default::default(),
+                ambient_function: this_custom_callback_var,
+            });
+            let custom_callback_lambda_set_var = synth_var(env.subs, content);
+            let subs_slice = SubsSlice::insert_into_subs(env.subs, [bytes_arg_var, fmt_arg_var]);
+
+            env.subs.set_content(
+                this_custom_callback_var,
+                Content::Structure(FlatType::Func(
+                    subs_slice,
+                    custom_callback_lambda_set_var,
+                    custom_callback_ret_var,
+                )),
+            );
+
+            custom_callback_lambda_set_var
+        };
+
+        // \bytes, fmt -> …
+        Expr::Closure(ClosureData {
+            function_type: this_custom_callback_var,
+            closure_type: custom_callback_lambda_set_var,
+            return_type: custom_callback_ret_var,
+            name: custom_closure_symbol,
+            captured_symbols: vec![(state_arg_symbol, state_record_var)],
+            recursive: Recursive::NotRecursive,
+            arguments: vec![
+                (
+                    bytes_arg_var,
+                    AnnotatedMark::known_exhaustive(),
+                    Loc::at_zero(Pattern::Identifier(bytes_arg_symbol)),
+                ),
+                (
+                    fmt_arg_var,
+                    AnnotatedMark::known_exhaustive(),
+                    Loc::at_zero(Pattern::Identifier(fmt_arg_symbol)),
+                ),
+            ],
+            loc_body: Box::new(Loc::at_zero(custom_callback_body)),
+        })
+    };
+
+    let decode_custom_ret_var = env.subs.fresh_unnamed_flex_var();
+    let decode_custom = {
+        let decode_custom_var = env.import_builtin_symbol_var(Symbol::DECODE_CUSTOM);
+        let decode_custom_closure_var = env.subs.fresh_unnamed_flex_var();
+        let this_decode_custom_var = {
+            let subs_slice = SubsSlice::insert_into_subs(env.subs, [this_custom_callback_var]);
+            let flat_type =
+                FlatType::Func(subs_slice, decode_custom_closure_var, decode_custom_ret_var);
+
+            synth_var(env.subs, Content::Structure(flat_type))
+        };
+
+        env.unify(decode_custom_var, this_decode_custom_var);
+
+        // Decode.custom \bytes, fmt -> …
+        Expr::Call(
+            Box::new((
+                this_decode_custom_var,
+                Loc::at_zero(Expr::Var(Symbol::DECODE_CUSTOM)),
+                decode_custom_closure_var,
+                decode_custom_ret_var,
+            )),
+            vec![(this_custom_callback_var, Loc::at_zero(custom_callback))],
+            CalledVia::Space,
+        )
+    };
+
+    env.unify(keep_payload_var, decode_custom_ret_var);
+
+    // Keep (Decode.custom \bytes, fmt ->
+    //     when Decode.decodeWith bytes Decode.decoder fmt is
+    //         rec ->
+    //             {
+    //                 rest: rec.rest,
+    //                 result: when rec.result is
+    //                     Ok val -> Ok {state & first: Ok val},
+    //                     Err err -> Err (Decode.prependPath err (Field "first"))
+    //             }
+    // )
+    Expr::Tag {
+        tag_union_var: keep_or_skip_var,
+        ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+        name: "Keep".into(),
+        arguments: vec![(decode_custom_ret_var, Loc::at_zero(decode_custom))],
+    }
+}
+
 // Example:
 // finalizer = \rec ->
 //     when rec.first is
 //         Ok first ->
 //             when rec.second is
 //                 Ok second -> Ok {first, second}
-//                 Err NoField -> Err TooShort
-//         Err NoField -> Err TooShort
+//                 _ -> Err (Decode.missing (Field "second"))
+//         _ -> Err (Decode.missing (Field "first"))
 fn decoder_record_finalizer(
     env: &mut Env,
     state_record_var: Variable,
@@ -750,14 +799,7 @@ fn decoder_record_finalizer(
     let state_arg_symbol = env.new_symbol("stateRecord");
     let mut fields_map = SendMap::default();
     let mut pattern_symbols = Vec::with_capacity(fields.len());
-    let decode_err_var = {
-        let flat_type = FlatType::TagUnion(
-            UnionTags::tag_without_arguments(env.subs, "TooShort".into()),
-            Variable::EMPTY_TAG_UNION,
-        );
-
-        synth_var(env.subs, Content::Structure(flat_type))
-    };
+    let decode_err_var = env.subs.fresh_unnamed_flex_var();
 
     for (field_name, &field_var) in fields.iter().zip(field_vars.iter()) {
         let symbol = env.new_symbol(field_name.as_str());
@@ -777,7 +819,7 @@ fn decoder_record_finalizer(
     // The bottom of the happy path - return the decoded record {first: a, second: b} wrapped with
     // "Ok".
     let return_type_var;
-    let mut body = {
+    let body = {
         let subs = &mut env.subs;
         let record_field_iter = fields
             .iter()
@@ -814,24 +856,99 @@ fn decoder_record_finalizer(
     //
     // when rec.first is
     //     Ok first -> ...happy path...
-    //     Err NoField -> Err TooShort
-    for (((symbol, field_name), &field_var), &result_field_var) in pattern_symbols
+    //     _ -> Err (Decode.missing (Field "first"))
+    let missing_paths: Vec<_> = fields
         .iter()
-        .rev()
-        .zip(fields.iter().rev())
-        .zip(field_vars.iter().rev())
-        .zip(result_field_vars.iter().rev())
-    {
-        // when rec.first is
-        let cond_expr = Expr::Access {
-            record_var: state_record_var,
-            ext_var: env.new_ext_var(ExtensionKind::Record),
-            field_var: result_field_var,
-            loc_expr: Box::new(Loc::at_zero(Expr::Var(state_arg_symbol))),
-            field: field_name.clone(),
-        };
-
-        // Example: `Ok x -> expr`
+        .map(|field_name| vec![PathSegment::Field(field_name)])
+        .collect();
+    let body = decoder_unwrap_state_fields(
+        env,
+        body,
+        (state_arg_symbol, state_record_var),
+        fields,
+        &missing_paths,
+        &pattern_symbols,
+        field_vars,
+        result_field_vars,
+        (return_type_var, decode_err_var),
+    );
+
+    let function_var = synth_var(env.subs, Content::Error); // We'll fix this up in subs later.
+    let function_symbol = env.new_symbol("finalizer");
+    let lambda_set = LambdaSet {
+        solved: UnionLambdas::tag_without_arguments(env.subs, function_symbol),
+        recursion_var: OptVariable::NONE,
+        unspecialized: Default::default(),
+        ambient_function: function_var,
+    };
+    let closure_type = synth_var(env.subs, Content::LambdaSet(lambda_set));
+    let flat_type = FlatType::Func(
+        SubsSlice::insert_into_subs(env.subs, [state_record_var]),
+        closure_type,
+        return_type_var,
+    );
+
+    // Fix up function_var so it's not Content::Error anymore
+    env.subs
+        .set_content(function_var, Content::Structure(flat_type));
+
+    let finalizer = Expr::Closure(ClosureData {
+        function_type: function_var,
+        closure_type,
+        return_type: return_type_var,
+        name: function_symbol,
+        captured_symbols: Vec::new(),
+        recursive: Recursive::NotRecursive,
+        arguments: vec![(
+            state_record_var,
+            AnnotatedMark::known_exhaustive(),
+            Loc::at_zero(Pattern::Identifier(state_arg_symbol)),
+        )],
+        loc_body: Box::new(Loc::at_zero(body)),
+    });
+
+    (finalizer, function_var, decode_err_var)
+}
+
+// Wraps `body` in `when`s which unwrap each field of the decoding state, returning a `Missing`
+// error at the field's path in `missing_paths` if it was never decoded. Example:
+//
+// when rec.first is
+//     Ok first ->
+//         when rec.second is
+//             Ok second -> body
+//             _ -> Err (Decode.missing (Field "second"))
+//     _ -> Err (Decode.missing (Field "first"))
+#[allow(clippy::too_many_arguments)]
+fn decoder_unwrap_state_fields(
+    env: &mut Env,
+    mut body: Expr,
+    (state_arg_symbol, state_record_var): (Symbol, Variable),
+    fields: &[Lowercase],
+    missing_paths: &[Vec<PathSegment>],
+    pattern_symbols: &[Symbol],
+    field_vars: &[Variable],
+    result_field_vars: &[Variable],
+    (return_type_var, decode_err_var): (Variable, Variable),
+) -> Expr {
+    for ((((symbol, field_name), missing_path), &field_var), &result_field_var) in pattern_symbols
+        .iter()
+        .rev()
+        .zip(fields.iter().rev())
+        .zip(missing_paths.iter().rev())
+        .zip(field_vars.iter().rev())
+        .zip(result_field_vars.iter().rev())
+    {
+        // when rec.first is
+        let cond_expr = Expr::Access {
+            record_var: state_record_var,
+            ext_var: env.new_ext_var(ExtensionKind::Record),
+            field_var: result_field_var,
+            loc_expr: Box::new(Loc::at_zero(Expr::Var(state_arg_symbol))),
+            field: field_name.clone(),
+        };
+
+        // Example: `Ok x -> expr`
         let ok_branch = WhenBranch {
             patterns: vec![WhenBranchPattern {
                 pattern: Loc::at_zero(Pattern::AppliedTag {
@@ -847,26 +964,14 @@ fn decoder_record_finalizer(
             redundant: RedundantMark::known_non_redundant(),
         };
 
-        // Example: `_ -> Err TooShort`
+        // Example: `_ -> Err (Decode.missing (Field "first"))`
+        let missing = decode_err_missing(env, missing_path, decode_err_var);
         let err_branch = WhenBranch {
             patterns: vec![WhenBranchPattern {
                 pattern: Loc::at_zero(Pattern::Underscore),
                 degenerate: false,
             }],
-            value: Loc::at_zero(Expr::Tag {
-                tag_union_var: return_type_var,
-                ext_var: env.new_ext_var(ExtensionKind::TagUnion),
-                name: "Err".into(),
-                arguments: vec![(
-                    decode_err_var,
-                    Loc::at_zero(Expr::Tag {
-                        tag_union_var: decode_err_var,
-                        ext_var: Variable::EMPTY_TAG_UNION,
-                        name: "TooShort".into(),
-                        arguments: Vec::new(),
-                    }),
-                )],
-            }),
+            value: Loc::at_zero(err_tag(env, return_type_var, decode_err_var, missing)),
             guard: None,
             redundant: RedundantMark::known_non_redundant(),
         };
@@ -882,41 +987,139 @@ fn decoder_record_finalizer(
         };
     }
 
-    let function_var = synth_var(env.subs, Content::Error); // We'll fix this up in subs later.
-    let function_symbol = env.new_symbol("finalizer");
-    let lambda_set = LambdaSet {
-        solved: UnionLambdas::tag_without_arguments(env.subs, function_symbol),
-        recursion_var: OptVariable::NONE,
-        unspecialized: Default::default(),
-        ambient_function: function_var,
+    body
+}
+
+// A step into the value being decoded, mirroring `Decode.DecodePathSegment`.
+enum PathSegment<'a> {
+    Field(&'a Lowercase),
+    Index(usize),
+    Tag(&'a TagName),
+}
+
+// Example: `Field "first"`
+fn path_segment(env: &mut Env, segment: &PathSegment) -> (Variable, Expr) {
+    let (name, argument) = match segment {
+        PathSegment::Field(field_name) => (
+            "Field",
+            (Variable::STR, Expr::Str(field_name.as_str().into())),
+        ),
+        PathSegment::Index(index) => (
+            "Index",
+            (
+                Variable::NAT,
+                Expr::Int(
+                    Variable::NAT,
+                    Variable::NATURAL,
+                    index.to_string().into_boxed_str(),
+                    IntValue::I128((*index as i128).to_ne_bytes()),
+                    IntBound::Exact(IntLitWidth::Nat),
+                ),
+            ),
+        ),
+        PathSegment::Tag(tag_name) => (
+            "Tag",
+            (Variable::STR, Expr::Str(tag_name.0.as_str().into())),
+        ),
     };
-    let closure_type = synth_var(env.subs, Content::LambdaSet(lambda_set));
-    let flat_type = FlatType::Func(
-        SubsSlice::insert_into_subs(env.subs, [state_record_var]),
-        closure_type,
-        return_type_var,
+
+    // The segment is one of several tags, so both its type and extension are fixed to
+    // `DecodePathSegment` by the `Decode` function it is passed to.
+    let segment_var = env.subs.fresh_unnamed_flex_var();
+    let (argument_var, argument_expr) = argument;
+    let segment_expr = Expr::Tag {
+        tag_union_var: segment_var,
+        ext_var: env.subs.fresh_unnamed_flex_var(),
+        name: name.into(),
+        arguments: vec![(argument_var, Loc::at_zero(argument_expr))],
+    };
+
+    (segment_var, segment_expr)
+}
+
+// Calls a builtin function such as `Decode.prependPath`, returning the call and its return type.
+fn call_builtin(
+    env: &mut Env,
+    symbol: Symbol,
+    arguments: Vec<(Variable, Expr)>,
+) -> (Expr, Variable) {
+    let function_var = env.import_builtin_symbol_var(symbol);
+    let lambda_set_var = env.subs.fresh_unnamed_flex_var();
+    let ret_var = env.subs.fresh_unnamed_flex_var();
+    let this_function_var = {
+        let subs_slice =
+            SubsSlice::insert_into_subs(env.subs, arguments.iter().map(|(var, _)| *var));
+        let flat_type = FlatType::Func(subs_slice, lambda_set_var, ret_var);
+
+        synth_var(env.subs, Content::Structure(flat_type))
+    };
+
+    env.unify(function_var, this_function_var);
+
+    let call = Expr::Call(
+        Box::new((
+            this_function_var,
+            Loc::at_zero(Expr::Var(symbol)),
+            lambda_set_var,
+            ret_var,
+        )),
+        arguments
+            .into_iter()
+            .map(|(var, expr)| (var, Loc::at_zero(expr)))
+            .collect(),
+        CalledVia::Space,
     );
 
-    // Fix up function_var so it's not Content::Error anymore
-    env.subs
-        .set_content(function_var, Content::Structure(flat_type));
+    (call, ret_var)
+}
 
-    let finalizer = Expr::Closure(ClosureData {
-        function_type: function_var,
-        closure_type,
-        return_type: return_type_var,
-        name: function_symbol,
-        captured_symbols: Vec::new(),
-        recursive: Recursive::NotRecursive,
-        arguments: vec![(
-            state_record_var,
-            AnnotatedMark::known_exhaustive(),
-            Loc::at_zero(Pattern::Identifier(state_arg_symbol)),
-        )],
-        loc_body: Box::new(Loc::at_zero(body)),
-    });
+// Wraps the decoding error `err` with each segment of `path`. Example, for `[Tag "A", Index 0]`:
+//
+//   Decode.prependPath (Decode.prependPath err (Index 0)) (Tag "A")
+fn prepend_path(
+    env: &mut Env,
+    mut err: Expr,
+    path: &[PathSegment],
+    decode_err_var: Variable,
+) -> Expr {
+    for segment in path.iter().rev() {
+        let segment = path_segment(env, segment);
+        let (call, ret_var) = call_builtin(
+            env,
+            Symbol::DECODE_PREPEND_PATH,
+            vec![(decode_err_var, err), segment],
+        );
 
-    (finalizer, function_var, decode_err_var)
+        env.unify(ret_var, decode_err_var);
+        err = call;
+    }
+
+    err
+}
+
+// The error for a value at `path` that was never decoded. Example, for `[Tag "A", Index 1]`:
+//
+//   Decode.prependPath (Decode.missing (Index 1)) (Tag "A")
+fn decode_err_missing(env: &mut Env, path: &[PathSegment], decode_err_var: Variable) -> Expr {
+    let (last, outer) = path
+        .split_last()
+        .expect("a missing value always has a path");
+    let segment = path_segment(env, last);
+    let (missing, missing_var) = call_builtin(env, Symbol::DECODE_MISSING, vec![segment]);
+
+    env.unify(missing_var, decode_err_var);
+
+    prepend_path(env, missing, outer, decode_err_var)
+}
+
+// Example: `Err (Decode.missing (Field "first"))`
+fn err_tag(env: &mut Env, return_type_var: Variable, decode_err_var: Variable, err: Expr) -> Expr {
+    Expr::Tag {
+        tag_union_var: return_type_var,
+        ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+        name: "Err".into(),
+        arguments: vec![(decode_err_var, Loc::at_zero(err))],
+    }
 }
 
 // Example:
@@ -991,6 +1194,486 @@ fn decoder_record_initial_state(
     )
 }
 
+// Implements decoding of a tag union. For example, for
+//
+//   [A a, B b c]
+//
+// we'd like to generate an impl like
+//
+// decoder : Decoder [A a, B b c] fmt | a has Decoding, b has Decoding, c has Decoding, fmt has DecoderFormatting
+// decoder =
+//     initialState : {t0p0: Result a [NoField], t1p0: Result b [NoField], t1p1: Result c [NoField]}
+//     initialState = {t0p0: Err NoField, t1p0: Err NoField, t1p1: Err NoField}
+//
+//     stepPayload = \state, tag, index ->
+//         when tag is
+//             "A" ->
+//                 when index is
+//                     0 -> Keep (Decode.custom \bytes, fmt -> ...decodes {state & t0p0: Ok val}...)
+//                     _ -> Skip
+//             "B" ->
+//                 when index is
+//                     0 -> Keep (Decode.custom \bytes, fmt -> ...decodes {state & t1p0: Ok val}...)
+//                     1 -> Keep (Decode.custom \bytes, fmt -> ...decodes {state & t1p1: Ok val}...)
+//                     _ -> Skip
+//             _ -> Skip
+//
+//     finalizer = \state, tag ->
+//         when tag is
+//             "A" ->
+//                 when state.t0p0 is
+//                     Ok t0p0 -> Ok (A t0p0)
+//                     _ -> Err (Decode.prependPath (Decode.missing (Index 0)) (Tag "A"))
+//             "B" ->
+//                 when state.t1p0 is
+//                     Ok t1p0 ->
+//                         when state.t1p1 is
+//                             Ok t1p1 -> Ok (B t1p0 t1p1)
+//                             _ -> Err (Decode.prependPath (Decode.missing (Index 1)) (Tag "B"))
+//                     _ -> Err (Decode.prependPath (Decode.missing (Index 0)) (Tag "B"))
+//             _ -> Err (Decode.expected "one of the tags A, B" tag)
+//
+//     Decode.custom \bytes, fmt -> Decode.decodeWith bytes (Decode.tag initialState stepPayload finalizer) fmt
+//
+// The decoding state holds one field per payload value of every tag, so that each payload value
+// can be decoded on its own, just like the fields of a record. Errors from decoding a payload
+// value are wrapped with its path, e.g. `[Tag "B", Index 1]`.
+fn decoder_tag_union(
+    env: &mut Env,
+    _def_symbol: Symbol,
+    tags: Vec<(TagName, u16)>,
+) -> (Expr, Variable) {
+    // The field of the decoding state for each payload value of each tag, e.g. [[t0p0], [t1p0, t1p1]].
+    let payload_fields: Vec<Vec<Lowercase>> = tags
+        .iter()
+        .enumerate()
+        .map(|(tag_index, (_, arity))| {
+            (0..*arity)
+                .map(|payload_index| format!("t{}p{}", tag_index, payload_index).into())
+                .collect()
+        })
+        .collect();
+    let state_fields: Vec<Lowercase> = payload_fields.iter().flatten().cloned().collect();
+
+    // The decoded type of each payload value, e.g. a, b, c.
+    let mut field_vars = Vec::with_capacity(state_fields.len());
+    // The type of each field in the decoding state, e.g. Result a [NoField].
+    let mut result_field_vars = Vec::with_capacity(state_fields.len());
+
+    // initialState = ...
+    let (initial_state_var, initial_state) =
+        decoder_record_initial_state(env, &state_fields, &mut field_vars, &mut result_field_vars);
+
+    // finalizer = ...
+    let (finalizer, finalizer_var, decode_err_var) = decoder_tag_union_finalizer(
+        env,
+        initial_state_var,
+        &tags,
+        &payload_fields,
+        &field_vars,
+        &result_field_vars,
+    );
+
+    // stepPayload = ...
+    let (step_payload, step_var) = decoder_tag_union_step_payload(
+        env,
+        &tags,
+        &payload_fields,
+        &field_vars,
+        &result_field_vars,
+        initial_state_var,
+        decode_err_var,
+    );
+
+    // Decode.custom \bytes, fmt -> Decode.decodeWith bytes (Decode.tag initialState stepPayload finalizer) fmt
+    decoder_from_steps(
+        env,
+        Symbol::DECODE_TAG,
+        (initial_state, initial_state_var),
+        (step_payload, step_var),
+        (finalizer, finalizer_var),
+    )
+}
+
+// Example:
+// stepPayload = \state, tag, index ->
+//     when tag is
+//         "B" ->
+//             when index is
+//                 0 -> Keep (Decode.custom \bytes, fmt -> ...decodes {state & t1p0: Ok val}...)
+//                 1 -> Keep (Decode.custom \bytes, fmt -> ...decodes {state & t1p1: Ok val}...)
+//                 _ -> Skip
+//         _ -> Skip
+fn decoder_tag_union_step_payload(
+    env: &mut Env,
+    tags: &[(TagName, u16)],
+    payload_fields: &[Vec<Lowercase>],
+    field_vars: &[Variable],
+    result_field_vars: &[Variable],
+    state_record_var: Variable,
+    decode_err_var: Variable,
+) -> (Expr, Variable) {
+    let state_arg_symbol = env.new_symbol("stateRecord");
+    let tag_arg_symbol = env.new_symbol("tag");
+    let index_arg_symbol = env.new_symbol("index");
+
+    let (keep_or_skip_var, keep_payload_var) = keep_or_skip_var(env);
+
+    // Example: `_ -> Skip`
+    let skip_branch = |env: &mut Env| WhenBranch {
+        patterns: vec![WhenBranchPattern {
+            pattern: Loc::at_zero(Pattern::Underscore),
+            degenerate: false,
+        }],
+        value: Loc::at_zero(Expr::Tag {
+            tag_union_var: keep_or_skip_var,
+            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+            name: "Skip".into(),
+            arguments: Vec::new(),
+        }),
+        guard: None,
+        redundant: RedundantMark::known_non_redundant(),
+    };
+
+    // +1 because of the default branch.
+    let mut tag_branches = Vec::with_capacity(tags.len() + 1);
+    let mut field_vars = field_vars.iter().zip(result_field_vars.iter());
+
+    for ((tag_name, _), fields) in tags.iter().zip(payload_fields.iter()) {
+        // Tags without a payload never ask for a payload decoder.
+        if fields.is_empty() {
+            continue;
+        }
+
+        // +1 because of the default branch.
+        let mut index_branches = Vec::with_capacity(fields.len() + 1);
+
+        for (index, field_name) in fields.iter().enumerate() {
+            let (&field_var, &result_field_var) = field_vars.next().unwrap();
+
+            // Example: `0 -> Keep (Decode.custom \bytes, fmt -> ...)`
+            let keep = decoder_step_field_keep(
+                env,
+                field_name,
+                &[PathSegment::Tag(tag_name), PathSegment::Index(index)],
+                field_var,
+                result_field_var,
+                state_arg_symbol,
+                state_record_var,
+                decode_err_var,
+                keep_or_skip_var,
+                keep_payload_var,
+            );
+
+            index_branches.push(WhenBranch {
+                patterns: vec![WhenBranchPattern {
+                    pattern: Loc::at_zero(Pattern::IntLiteral(
+                        Variable::NAT,
+                        Variable::NATURAL,
+                        index.to_string().into_boxed_str(),
+                        IntValue::I128((index as i128).to_ne_bytes()),
+                        IntBound::Exact(IntLitWidth::Nat),
+                    )),
+                    degenerate: false,
+                }],
+                value: Loc::at_zero(keep),
+                guard: None,
+                redundant: RedundantMark::known_non_redundant(),
+            });
+        }
+
+        index_branches.push(skip_branch(env));
+
+        // when index is
+        let index_when = Expr::When {
+            loc_cond: Box::new(Loc::at_zero(Expr::Var(index_arg_symbol))),
+            cond_var: Variable::NAT,
+            expr_var: keep_or_skip_var,
+            region: Region::zero(),
+            branches: index_branches,
+            branches_cond_var: Variable::NAT,
+            exhaustive: ExhaustiveMark::known_exhaustive(),
+        };
+
+        tag_branches.push(WhenBranch {
+            patterns: vec![WhenBranchPattern {
+                pattern: Loc::at_zero(Pattern::StrLiteral(tag_name.0.as_str().into())),
+                degenerate: false,
+            }],
+            value: Loc::at_zero(index_when),
+            guard: None,
+            redundant: RedundantMark::known_non_redundant(),
+        });
+    }
+
+    tag_branches.push(skip_branch(env));
+
+    // when tag is
+    let body = Expr::When {
+        loc_cond: Box::new(Loc::at_zero(Expr::Var(tag_arg_symbol))),
+        cond_var: Variable::STR,
+        expr_var: keep_or_skip_var,
+        region: Region::zero(),
+        branches: tag_branches,
+        branches_cond_var: Variable::STR,
+        exhaustive: ExhaustiveMark::known_exhaustive(),
+    };
+
+    let step_payload_closure = env.new_symbol("stepPayload");
+    let function_type = env.subs.fresh_unnamed_flex_var();
+    let closure_type = {
+        let lambda_set = LambdaSet {
+            solved: UnionLambdas::tag_without_arguments(env.subs, step_payload_closure),
+            recursion_var: OptVariable::NONE,
+            unspecialized: Default::default(),
+            ambient_function: function_type,
+        };
+
+        synth_var(env.subs, Content::LambdaSet(lambda_set))
+    };
+
+    {
+        let args_slice =
+            SubsSlice::insert_into_subs(env.subs, [state_record_var, Variable::STR, Variable::NAT]);
+
+        env.subs.set_content(
+            function_type,
+            Content::Structure(FlatType::Func(args_slice, closure_type, keep_or_skip_var)),
+        )
+    };
+
+    let expr = Expr::Closure(ClosureData {
+        function_type,
+        closure_type,
+        return_type: keep_or_skip_var,
+        name: step_payload_closure,
+        captured_symbols: Vec::new(),
+        recursive: Recursive::NotRecursive,
+        arguments: vec![
+            (
+                state_record_var,
+                AnnotatedMark::known_exhaustive(),
+                Loc::at_zero(Pattern::Identifier(state_arg_symbol)),
+            ),
+            (
+                Variable::STR,
+                AnnotatedMark::known_exhaustive(),
+                Loc::at_zero(Pattern::Identifier(tag_arg_symbol)),
+            ),
+            (
+                Variable::NAT,
+                AnnotatedMark::known_exhaustive(),
+                Loc::at_zero(Pattern::Identifier(index_arg_symbol)),
+            ),
+        ],
+        loc_body: Box::new(Loc::at_zero(body)),
+    });
+
+    (expr, function_type)
+}
+
+// Example:
+// finalizer = \state, tag ->
+//     when tag is
+//         "B" ->
+//             when state.t1p0 is
+//                 Ok t1p0 ->
+//                     when state.t1p1 is
+//                         Ok t1p1 -> Ok (B t1p0 t1p1)
+//                         _ -> Err (Decode.prependPath (Decode.missing (Index 1)) (Tag "B"))
+//                 _ -> Err (Decode.prependPath (Decode.missing (Index 0)) (Tag "B"))
+//         _ -> Err (Decode.expected "one of the tags B" tag)
+fn decoder_tag_union_finalizer(
+    env: &mut Env,
+    state_record_var: Variable,
+    tags: &[(TagName, u16)],
+    payload_fields: &[Vec<Lowercase>],
+    field_vars: &[Variable],
+    result_field_vars: &[Variable],
+) -> (Expr, Variable, Variable) {
+    let state_arg_symbol = env.new_symbol("stateRecord");
+    let tag_arg_symbol = env.new_symbol("tag");
+    let decode_err_var = env.subs.fresh_unnamed_flex_var();
+
+    // The decoded tag union, e.g. [A a, B b c]
+    let tag_union_var = {
+        let mut payload_start = 0;
+        let union_tags = tags
+            .iter()
+            .zip(payload_fields.iter())
+            .map(|((tag_name, _), fields)| {
+                let payload_vars = &field_vars[payload_start..][..fields.len()];
+                payload_start += fields.len();
+
+                (
+                    tag_name.clone(),
+                    SubsSlice::insert_into_subs(env.subs, payload_vars.iter().copied()),
+                )
+            })
+            .collect::<Vec<_>>();
+        let flat_type = FlatType::TagUnion(
+            UnionTags::insert_slices_into_subs(env.subs, union_tags),
+            Variable::EMPTY_TAG_UNION,
+        );
+
+        synth_var(env.subs, Content::Structure(flat_type))
+    };
+
+    let return_type_var = {
+        let flat_type = FlatType::TagUnion(
+            UnionTags::for_result(env.subs, tag_union_var, decode_err_var),
+            Variable::EMPTY_TAG_UNION,
+        );
+
+        synth_var(env.subs, Content::Structure(flat_type))
+    };
+
+    // +1 because of the default branch.
+    let mut branches = Vec::with_capacity(tags.len() + 1);
+    let mut payload_start = 0;
+
+    for ((tag_name, _), fields) in tags.iter().zip(payload_fields.iter()) {
+        let payload_range = payload_start..payload_start + fields.len();
+        payload_start += fields.len();
+
+        let pattern_symbols: Vec<_> = fields
+            .iter()
+            .map(|field_name| env.new_symbol(field_name.as_str()))
+            .collect();
+
+        // The bottom of the happy path - return the decoded tag, e.g. `Ok (B t1p0 t1p1)`.
+        let done_tag = Expr::Tag {
+            tag_union_var,
+            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+            name: tag_name.clone(),
+            arguments: field_vars[payload_range.clone()]
+                .iter()
+                .zip(pattern_symbols.iter())
+                .map(|(&field_var, &symbol)| (field_var, Loc::at_zero(Expr::Var(symbol))))
+                .collect(),
+        };
+        let ok_done_tag = Expr::Tag {
+            tag_union_var: return_type_var,
+            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+            name: "Ok".into(),
+            arguments: vec![(tag_union_var, Loc::at_zero(done_tag))],
+        };
+
+        // Unwrap each payload value of this tag in the decoded state
+        let missing_paths: Vec<_> = (0..fields.len())
+            .map(|index| vec![PathSegment::Tag(tag_name), PathSegment::Index(index)])
+            .collect();
+        let body = decoder_unwrap_state_fields(
+            env,
+            ok_done_tag,
+            (state_arg_symbol, state_record_var),
+            fields,
+            &missing_paths,
+            &pattern_symbols,
+            &field_vars[payload_range.clone()],
+            &result_field_vars[payload_range],
+            (return_type_var, decode_err_var),
+        );
+
+        branches.push(WhenBranch {
+            patterns: vec![WhenBranchPattern {
+                pattern: Loc::at_zero(Pattern::StrLiteral(tag_name.0.as_str().into())),
+                degenerate: false,
+            }],
+            value: Loc::at_zero(body),
+            guard: None,
+            redundant: RedundantMark::known_non_redundant(),
+        });
+    }
+
+    // Example: `_ -> Err (Decode.expected "one of the tags A, B" tag)`
+    let expected_tags = match tags {
+        [] => "no tag".to_string(),
+        _ => {
+            let tag_names: Vec<_> = tags
+                .iter()
+                .map(|(tag_name, _)| tag_name.0.as_str())
+                .collect();
+
+            format!("one of the tags {}", tag_names.join(", "))
+        }
+    };
+    let (unknown_tag, unknown_tag_var) = call_builtin(
+        env,
+        Symbol::DECODE_EXPECTED,
+        vec![
+            (Variable::STR, Expr::Str(expected_tags.into())),
+            (Variable::STR, Expr::Var(tag_arg_symbol)),
+        ],
+    );
+
+    env.unify(unknown_tag_var, decode_err_var);
+
+    branches.push(WhenBranch {
+        patterns: vec![WhenBranchPattern {
+            pattern: Loc::at_zero(Pattern::Underscore),
+            degenerate: false,
+        }],
+        value: Loc::at_zero(err_tag(env, return_type_var, decode_err_var, unknown_tag)),
+        guard: None,
+        redundant: RedundantMark::known_non_redundant(),
+    });
+
+    // when tag is
+    let body = Expr::When {
+        loc_cond: Box::new(Loc::at_zero(Expr::Var(tag_arg_symbol))),
+        cond_var: Variable::STR,
+        expr_var: return_type_var,
+        region: Region::zero(),
+        branches,
+        branches_cond_var: Variable::STR,
+        exhaustive: ExhaustiveMark::known_exhaustive(),
+    };
+
+    let function_var = synth_var(env.subs, Content::Error); // We'll fix this up in subs later.
+    let function_symbol = env.new_symbol("finalizer");
+    let lambda_set = LambdaSet {
+        solved: UnionLambdas::tag_without_arguments(env.subs, function_symbol),
+        recursion_var: OptVariable::NONE,
+        unspecialized: Default::default(),
+        ambient_function: function_var,
+    };
+    let closure_type = synth_var(env.subs, Content::LambdaSet(lambda_set));
+    let flat_type = FlatType::Func(
+        SubsSlice::insert_into_subs(env.subs, [state_record_var, Variable::STR]),
+        closure_type,
+        return_type_var,
+    );
+
+    // Fix up function_var so it's not Content::Error anymore
+    env.subs
+        .set_content(function_var, Content::Structure(flat_type));
+
+    let finalizer = Expr::Closure(ClosureData {
+        function_type: function_var,
+        closure_type,
+        return_type: return_type_var,
+        name: function_symbol,
+        captured_symbols: Vec::new(),
+        recursive: Recursive::NotRecursive,
+        arguments: vec![
+            (
+                state_record_var,
+                AnnotatedMark::known_exhaustive(),
+                Loc::at_zero(Pattern::Identifier(state_arg_symbol)),
+            ),
+            (
+                Variable::STR,
+                AnnotatedMark::known_exhaustive(),
+                Loc::at_zero(Pattern::Identifier(tag_arg_symbol)),
+            ),
+        ],
+        loc_body: Box::new(Loc::at_zero(body)),
+    });
+
+    (finalizer, function_var, decode_err_var)
+}
+
 fn decoder_list(env: &mut Env<'_>, _def_symbol: Symbol) -> (Expr, Variable) {
     // Build
     //
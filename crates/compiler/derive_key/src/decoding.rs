@@ -1,8 +1,11 @@
-use roc_module::{ident::Lowercase, symbol::Symbol};
+use roc_module::{
+    ident::{Lowercase, TagName},
+    symbol::Symbol,
+};
 use roc_types::subs::{Content, FlatType, Subs, Variable};
 
 use crate::{
-    util::{check_derivable_ext_var, debug_name_record},
+    util::{check_derivable_ext_var, debug_name_record, debug_name_tag},
     DeriveError,
 };
 
@@ -18,6 +21,7 @@ pub enum FlatDecodableKey {
 
     // Unfortunate that we must allocate here, c'est la vie
     Record(Vec<Lowercase>),
+    TagUnion(Vec<(TagName, u16)>),
 }
 
 impl FlatDecodableKey {
@@ -25,6 +29,7 @@ impl FlatDecodableKey {
         match self {
             FlatDecodableKey::List() => "list".to_string(),
             FlatDecodableKey::Record(fields) => debug_name_record(fields),
+            FlatDecodableKey::TagUnion(tags) => debug_name_tag(tags),
         }
     }
 }
@@ -61,17 +66,38 @@ impl FlatDecodable {
 
                     Ok(Key(FlatDecodableKey::Record(field_names)))
                 }
-                FlatType::TagUnion(_tags, _ext) | FlatType::RecursiveTagUnion(_, _tags, _ext) => {
-                    Err(Underivable) // yet
-                }
-                FlatType::FunctionOrTagUnion(_name_index, _, _) => {
-                    Err(Underivable) // yet
+                FlatType::TagUnion(tags, ext) | FlatType::RecursiveTagUnion(_, tags, ext) => {
+                    // As with encoding, the recursion var doesn't matter: the derived decoder only
+                    // looks at the surface of the tag union, and leaves the payload types generic
+                    // for the monomorphizer to fill in.
+                    let (tags_iter, ext) = tags.unsorted_tags_and_ext(subs, ext);
+
+                    check_derivable_ext_var(subs, ext, |ext| {
+                        matches!(ext, Content::Structure(FlatType::EmptyTagUnion))
+                    })?;
+
+                    let mut tag_names_and_payload_sizes: Vec<_> = tags_iter
+                        .tags
+                        .into_iter()
+                        .map(|(name, payload_slice)| {
+                            let payload_size = payload_slice.len();
+                            (name.clone(), payload_size as _)
+                        })
+                        .collect();
+
+                    tag_names_and_payload_sizes.sort_by(|(t1, _), (t2, _)| t1.cmp(t2));
+
+                    Ok(Key(FlatDecodableKey::TagUnion(tag_names_and_payload_sizes)))
                 }
+                FlatType::FunctionOrTagUnion(name_index, _, _) => Ok(Key(
+                    FlatDecodableKey::TagUnion(vec![(subs[name_index].clone(), 0)]),
+                )),
                 FlatType::EmptyRecord => Ok(Key(FlatDecodableKey::Record(vec![]))),
-                FlatType::EmptyTagUnion => {
+                FlatType::EmptyTagUnion => Ok(Key(FlatDecodableKey::TagUnion(vec![]))),
+                //
+                FlatType::Tuple(..) | FlatType::EmptyTuple => {
                     Err(Underivable) // yet
                 }
-                //
                 FlatType::Erroneous(_) => Err(Underivable),
                 FlatType::Func(..) => Err(Underivable),
             },
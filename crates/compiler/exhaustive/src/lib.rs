@@ -36,6 +36,7 @@ pub enum RenderAs {
     Tag,
     Opaque,
     Record(Vec<Lowercase>),
+    Tuple,
     Guard,
 }
 
@@ -64,11 +65,39 @@ pub struct Ctor {
     pub arity: usize,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ListArity {
+    /// A list pattern of an exact length, e.g. `[a, b]`.
+    Exact(usize),
+    /// A list pattern with a rest, e.g. `[a, .., z]`.
+    /// Stores the number of patterns before and after the rest.
+    Slice(usize, usize),
+}
+
+impl ListArity {
+    /// The shortest list this arity can match.
+    pub fn min_len(&self) -> usize {
+        match self {
+            ListArity::Exact(n) => *n,
+            ListArity::Slice(before, after) => before + after,
+        }
+    }
+
+    /// Can a list pattern of this arity match a list of the given length?
+    pub fn covers_length(&self, length: usize) -> bool {
+        match self {
+            ListArity::Exact(n) => *n == length,
+            ListArity::Slice(before, after) => before + after <= length,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum Pattern {
     Anything,
     Literal(Literal),
     Ctor(Union, TagId, std::vec::Vec<Pattern>),
+    List(ListArity, std::vec::Vec<Pattern>),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -144,7 +173,30 @@ fn is_exhaustive(matrix: &RefPatternMatrix, n: usize) -> PatternMatrix {
         let ctors = collect_ctors(matrix);
         let num_seen = ctors.len();
 
-        if num_seen == 0 {
+        if let Some(list_ctors) = collect_list_ctors(matrix, ListArity::Slice(0, 0)) {
+            let is_list_exhaustive = |list_arity: ListArity| {
+                let arity = list_arity.min_len();
+                let new_matrix: Vec<_> = matrix
+                    .iter()
+                    .filter_map(|r| specialize_row_by_list(list_arity, r))
+                    .collect();
+                let rest: Vec<Vec<Pattern>> = is_exhaustive(&new_matrix, arity + n - 1);
+
+                let mut result = Vec::with_capacity(rest.len());
+                for mut row in rest {
+                    let mut rest = row.split_off(arity);
+                    rest.push(List(list_arity, row));
+                    result.push(rest);
+                }
+
+                result
+            };
+
+            list_ctors
+                .into_iter()
+                .flat_map(is_list_exhaustive)
+                .collect()
+        } else if num_seen == 0 {
             let new_matrix: Vec<_> = matrix
                 .iter()
                 .filter_map(|row| specialize_row_by_anything(row))
@@ -264,6 +316,34 @@ pub fn is_useful(mut old_matrix: PatternMatrix, mut vector: Row) -> bool {
                         vector.extend(args);
                     }
 
+                    List(arity, args) => {
+                        let list_ctors =
+                            collect_list_ctors(&old_matrix, arity).unwrap_or_else(|| vec![arity]);
+
+                        // The vector is useful if it is useful for any of the list lengths it
+                        // covers; the matrix is split into one group per length.
+                        for list_ctor in list_ctors {
+                            let matrix: Vec<_> = old_matrix
+                                .iter()
+                                .filter_map(|r| specialize_row_by_list_rev(list_ctor, r))
+                                .collect();
+
+                            let mut vector = vector.clone();
+                            vector.extend(pad_list_args(arity, list_ctor, args.clone()));
+
+                            if is_useful(matrix, vector) {
+                                break 'outer true;
+                            }
+                        }
+
+                        break false;
+                    }
+
+                    Anything if has_list_head(&old_matrix) => {
+                        // An Anything over lists covers lists of every length.
+                        vector.push(List(ListArity::Slice(0, 0), vec![]));
+                    }
+
                     Anything => {
                         // check if all alternatives appear in matrix
                         match is_complete(&old_matrix) {
@@ -325,7 +405,7 @@ pub fn is_useful(mut old_matrix: PatternMatrix, mut vector: Row) -> bool {
                                 }
                                 Some(Anything) => matrix.push(patterns),
 
-                                Some(Ctor(_, _, _)) => panic!(
+                                Some(Ctor(_, _, _) | List(_, _)) => panic!(
                                     r#"Compiler bug! After type checking, constructors and literals should never align in pattern match exhaustiveness checks."#
                                 ),
 
@@ -366,7 +446,7 @@ fn specialize_row_by_ctor2(
             patterns.extend(std::iter::repeat(Anything).take(arity));
             matrix.push(patterns);
             }
-        Some(Literal(_)) => panic!( "Compiler bug! After type checking, constructors and literal should never align in pattern match exhaustiveness checks."),
+        Some(Literal(_) | List(_, _)) => panic!( "Compiler bug! After type checking, constructors and literal should never align in pattern match exhaustiveness checks."),
         None => panic!("Compiler error! Empty matrices should not get specialized."),
     }
     }
@@ -399,7 +479,7 @@ fn specialize_row_by_ctor(tag_id: TagId, arity: usize, row: &RefRow) -> Option<R
                 .collect();
             Some(new_patterns)
         }
-        Some(Literal(_)) => unreachable!(
+        Some(Literal(_) | List(_, _)) => unreachable!(
             r#"Compiler bug! After type checking, a constructor can never align with a literal: that should be a type error!"#
         ),
         None => panic!("Compiler error! Empty matrices should not get specialized."),
@@ -416,6 +496,147 @@ fn specialize_row_by_anything(row: &RefRow) -> Option<Row> {
     }
 }
 
+/// LIST PATTERNS
+
+/// Lists have no fixed set of constructors, so we invent one per column: every exact length
+/// up to the longest list pattern in the column, and one variable-length constructor that
+/// stands for all longer lists. Returns `None` if no row in the matrix starts with a list.
+///
+/// Only the constructors that `covering` can match are returned.
+fn collect_list_ctors(matrix: &RefPatternMatrix, covering: ListArity) -> Option<Vec<ListArity>> {
+    if !has_list_head(matrix) {
+        return None;
+    }
+
+    let (before, after) = match covering {
+        ListArity::Exact(_) => return Some(vec![covering]),
+        ListArity::Slice(before, after) => (before, after),
+    };
+
+    let mut max_before = before;
+    let mut max_after = after;
+    let mut max_exact = None;
+
+    for row in matrix {
+        match row.last() {
+            Some(List(ListArity::Slice(before, after), _)) => {
+                max_before = max_before.max(*before);
+                max_after = max_after.max(*after);
+            }
+            Some(List(ListArity::Exact(n), _)) => {
+                max_exact = max_exact.max(Some(*n));
+            }
+            _ => {}
+        }
+    }
+
+    // The variable-length constructor must be longer than every exact-length pattern.
+    if let Some(max_exact) = max_exact {
+        max_before = max_before.max((max_exact + 1).saturating_sub(max_after));
+    }
+
+    let var_len = ListArity::Slice(max_before, max_after);
+
+    let mut ctors: Vec<_> = (before + after..var_len.min_len())
+        .map(ListArity::Exact)
+        .collect();
+    ctors.push(var_len);
+
+    Some(ctors)
+}
+
+fn has_list_head(matrix: &RefPatternMatrix) -> bool {
+    matrix
+        .iter()
+        .any(|row| matches!(row.last(), Some(List(..))))
+}
+
+/// Line up the patterns of a list pattern with the given arity against the positions of the
+/// list constructor `ctor`, filling the positions it does not constrain with Anything.
+///
+/// `ctor` must be covered by `arity`.
+fn pad_list_args(arity: ListArity, ctor: ListArity, mut args: Vec<Pattern>) -> Vec<Pattern> {
+    match arity {
+        ListArity::Exact(_) => args,
+        ListArity::Slice(before, after) => {
+            let gap = ctor.min_len() - (before + after);
+            let suffix = args.split_off(before);
+
+            args.extend(std::iter::repeat(Anything).take(gap));
+            args.extend(suffix);
+
+            args
+        }
+    }
+}
+
+/// Specialize a row by a list constructor; the constructor's patterns come first.
+///
+/// INVARIANT: (length row == N) ==> (length result == ctor.min_len() + N - 1)
+fn specialize_row_by_list(ctor: ListArity, row: &RefRow) -> Option<Row> {
+    let mut row = row.to_vec();
+
+    let head = row.pop();
+    let patterns = row;
+
+    let mut new_patterns = match head {
+        Some(List(arity, args)) => {
+            if list_arity_matches(arity, ctor) {
+                pad_list_args(arity, ctor, args)
+            } else {
+                return None;
+            }
+        }
+        Some(Anything) => std::iter::repeat(Anything).take(ctor.min_len()).collect(),
+        Some(Ctor(..) | Literal(_)) => unreachable!(
+            r#"Compiler bug! After type checking, a list can never align with a constructor or literal: that should be a type error!"#
+        ),
+        None => panic!("Compiler error! Empty matrices should not get specialized."),
+    };
+
+    new_patterns.extend(patterns);
+
+    Some(new_patterns)
+}
+
+/// Specialize a row by a list constructor; the constructor's patterns come last.
+///
+/// INVARIANT: (length row == N) ==> (length result == ctor.min_len() + N - 1)
+fn specialize_row_by_list_rev(ctor: ListArity, row: &RefRow) -> Option<Row> {
+    let mut patterns = row.to_vec();
+
+    match patterns.pop() {
+        Some(List(arity, args)) => {
+            if list_arity_matches(arity, ctor) {
+                patterns.extend(pad_list_args(arity, ctor, args));
+                Some(patterns)
+            } else {
+                None
+            }
+        }
+        Some(Anything) => {
+            patterns.extend(std::iter::repeat(Anything).take(ctor.min_len()));
+            Some(patterns)
+        }
+        Some(Ctor(..) | Literal(_)) => unreachable!(
+            r#"Compiler bug! After type checking, a list can never align with a constructor or literal: that should be a type error!"#
+        ),
+        None => panic!("Compiler error! Empty matrices should not get specialized."),
+    }
+}
+
+/// Does a list pattern with the given arity match every list the constructor stands for?
+fn list_arity_matches(arity: ListArity, ctor: ListArity) -> bool {
+    match ctor {
+        ListArity::Exact(n) => arity.covers_length(n),
+        // The variable-length constructor is longer than every exact-length pattern.
+        ListArity::Slice(..) => match arity {
+            ListArity::Exact(_) => false,
+            ListArity::Slice(..) => arity.min_len() <= ctor.min_len(),
+        },
+    }
+}
+
 /// ALL CONSTRUCTORS ARE PRESENT?
 
 pub enum Complete {
@@ -35,6 +35,7 @@ impl<'a> Formattable for Expr<'a> {
             | NonBase10Int { .. }
             | SingleQuote(_)
             | Access(_, _)
+            | TupleAccess(_, _)
             | AccessorFunction(_)
             | Var { .. }
             | Underscore { .. }
@@ -46,7 +47,10 @@ impl<'a> Formattable for Expr<'a> {
             // These expressions always have newlines
             Defs(_, _) | When(_, _) => true,
 
+            Malformed(source) => source.contains('\n'),
+
             List(items) => items.iter().any(|loc_expr| loc_expr.is_multiline()),
+            Tuple(items) => items.iter().any(|loc_expr| loc_expr.is_multiline()),
 
             Str(literal) => {
                 use roc_parse::ast::StrLiteral::*;
@@ -70,6 +74,9 @@ impl<'a> Formattable for Expr<'a> {
                 condition.is_multiline() || continuation.is_multiline()
             }
 
+            // the continuation of a `dbg` always goes on a later line
+            Dbg(value, continuation) => continuation.is_some() || value.is_multiline(),
+
             If(branches, final_else) => {
                 final_else.is_multiline()
                     || branches
@@ -367,11 +374,15 @@ impl<'a> Formattable for Expr<'a> {
             Expect(condition, continuation) => {
                 fmt_expect(buf, condition, continuation, self.is_multiline(), indent);
             }
+            Dbg(value, continuation) => {
+                fmt_dbg(buf, value, *continuation, indent);
+            }
             If(branches, final_else) => {
                 fmt_if(buf, branches, final_else, self.is_multiline(), indent);
             }
             When(loc_condition, branches) => fmt_when(buf, loc_condition, branches, indent),
             List(items) => fmt_collection(buf, indent, Braces::Square, *items, Newlines::No),
+            Tuple(items) => fmt_collection(buf, indent, Braces::Round, *items, Newlines::No),
             BinOps(lefts, right) => fmt_binops(buf, lefts, right, false, parens, indent),
             UnaryOp(sub_expr, unary_op) => {
                 buf.indent(indent);
@@ -391,13 +402,14 @@ impl<'a> Formattable for Expr<'a> {
                 buf.push('.');
                 buf.push_str(key);
             }
-            Access(expr, key) => {
+            Access(expr, key) | TupleAccess(expr, key) => {
                 expr.format_with_options(buf, Parens::InApply, Newlines::Yes, indent);
                 buf.push('.');
                 buf.push_str(key);
             }
             MalformedIdent(_, _) => {}
             MalformedClosure => {}
+            Malformed(source) => buf.push_str(source),
             PrecedenceConflict { .. } => {}
         }
     }
@@ -839,6 +851,33 @@ fn fmt_expect<'a, 'buf>(
     continuation.format(buf, indent);
 }
 
+fn fmt_dbg<'a, 'buf>(
+    buf: &mut Buf<'buf>,
+    value: &'a Loc<Expr<'a>>,
+    continuation: Option<&'a Loc<Expr<'a>>>,
+    indent: u16,
+) {
+    buf.indent(indent);
+    buf.push_str("dbg");
+
+    let return_indent = if value.is_multiline() {
+        buf.newline();
+        indent + INDENT
+    } else {
+        buf.spaces(1);
+        indent
+    };
+
+    value.format(buf, return_indent);
+
+    if let Some(continuation) = continuation {
+        // Always put a blank line after the `dbg` line(s)
+        buf.ensure_ends_with_blank_line();
+
+        continuation.format(buf, indent);
+    }
+}
+
 fn fmt_if<'a, 'buf>(
     buf: &mut Buf<'buf>,
     branches: &'a [(Loc<Expr<'a>>, Loc<Expr<'a>>)],
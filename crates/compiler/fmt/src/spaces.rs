@@ -641,6 +641,7 @@ impl<'a> RemoveSpaces<'a> for Expr<'a> {
             },
             Expr::Str(a) => Expr::Str(a.remove_spaces(arena)),
             Expr::Access(a, b) => Expr::Access(arena.alloc(a.remove_spaces(arena)), b),
+            Expr::TupleAccess(a, b) => Expr::TupleAccess(arena.alloc(a.remove_spaces(arena)), b),
             Expr::AccessorFunction(a) => Expr::AccessorFunction(a),
             Expr::List(a) => Expr::List(a.remove_spaces(arena)),
             Expr::RecordUpdate { update, fields } => Expr::RecordUpdate {
@@ -648,6 +649,7 @@ impl<'a> RemoveSpaces<'a> for Expr<'a> {
                 fields: fields.remove_spaces(arena),
             },
             Expr::Record(a) => Expr::Record(a.remove_spaces(arena)),
+            Expr::Tuple(a) => Expr::Tuple(a.remove_spaces(arena)),
             Expr::Var { module_name, ident } => Expr::Var { module_name, ident },
             Expr::Underscore(a) => Expr::Underscore(a),
             Expr::Tag(a) => Expr::Tag(a),
@@ -682,6 +684,10 @@ impl<'a> RemoveSpaces<'a> for Expr<'a> {
                 arena.alloc(a.remove_spaces(arena)),
                 arena.alloc(b.remove_spaces(arena)),
             ),
+            Expr::Dbg(a, b) => Expr::Dbg(
+                arena.alloc(a.remove_spaces(arena)),
+                b.map(|b| &*arena.alloc(b.remove_spaces(arena))),
+            ),
             Expr::Apply(a, b, c) => Expr::Apply(
                 arena.alloc(a.remove_spaces(arena)),
                 b.remove_spaces(arena),
@@ -703,6 +709,7 @@ impl<'a> RemoveSpaces<'a> for Expr<'a> {
             }
             Expr::MalformedIdent(a, b) => Expr::MalformedIdent(a, b),
             Expr::MalformedClosure => Expr::MalformedClosure,
+            Expr::Malformed(a) => Expr::Malformed(a),
             Expr::PrecedenceConflict(a) => Expr::PrecedenceConflict(a),
             Expr::SpaceBefore(a, _) => a.remove_spaces(arena),
             Expr::SpaceAfter(a, _) => a.remove_spaces(arena),
@@ -722,6 +729,9 @@ impl<'a> RemoveSpaces<'a> for Pattern<'a> {
                 arena.alloc(b.remove_spaces(arena)),
             ),
             Pattern::RecordDestructure(a) => Pattern::RecordDestructure(a.remove_spaces(arena)),
+            Pattern::Tuple(a) => Pattern::Tuple(a.remove_spaces(arena)),
+            Pattern::List(a) => Pattern::List(a.remove_spaces(arena)),
+            Pattern::ListRest(a) => Pattern::ListRest(a.map(|a| Loc::at(Region::zero(), a.value))),
             Pattern::RequiredField(a, b) => {
                 Pattern::RequiredField(a, arena.alloc(b.remove_spaces(arena)))
             }
@@ -774,6 +784,10 @@ impl<'a> RemoveSpaces<'a> for TypeAnnotation<'a> {
                 fields: fields.remove_spaces(arena),
                 ext: ext.remove_spaces(arena),
             },
+            TypeAnnotation::Tuple { elems, ext } => TypeAnnotation::Tuple {
+                elems: elems.remove_spaces(arena),
+                ext: ext.remove_spaces(arena),
+            },
             TypeAnnotation::TagUnion { ext, tags } => TypeAnnotation::TagUnion {
                 ext: ext.remove_spaces(arena),
                 tags: tags.remove_spaces(arena),
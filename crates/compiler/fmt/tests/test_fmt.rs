@@ -3091,6 +3091,186 @@ mod test_fmt {
         ));
     }
 
+    // TUPLES
+
+    #[test]
+    fn two_item_tuple() {
+        expr_formats_same("(7, 8)");
+        expr_formats_to("(   7  ,   8  )", "(7, 8)");
+    }
+
+    #[test]
+    fn multi_line_tuple() {
+        expr_formats_same(indoc!(
+            r#"
+            (
+                7,
+                8,
+                9,
+            )
+            "#
+        ));
+
+        expr_formats_to(
+            indoc!(
+                r#"
+                ( 17
+                , 18
+                )
+                "#
+            ),
+            indoc!(
+                r#"
+                (
+                    17,
+                    18,
+                )
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn tuple_access() {
+        expr_formats_same(indoc!(
+            r#"
+            pair = (1, "one")
+
+            (pair.0, (3, 4).1)
+            "#
+        ));
+    }
+
+    #[test]
+    fn tuple_destructuring() {
+        expr_formats_same(indoc!(
+            r#"
+            (x, y) = (1, 2)
+
+            when (x, y) is
+                (1, b) -> b
+                (_, b) -> b
+            "#
+        ));
+
+        expr_formats_to(
+            indoc!(
+                r#"
+                ( x,y ) = (1, 2)
+
+                x
+                "#
+            ),
+            indoc!(
+                r#"
+                (x, y) = (1, 2)
+
+                x
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn tuple_type() {
+        expr_formats_same(indoc!(
+            r#"
+            swap : (a, b) -> (b, a)
+            swap = \(x, y) -> (y, x)
+
+            swap
+            "#
+        ));
+
+        expr_formats_same(indoc!(
+            r#"
+            f : (Str, Str)a -> (Str, Str)a
+            f = \x -> x
+
+            f
+            "#
+        ));
+    }
+
+    #[test]
+    fn list_patterns() {
+        expr_formats_same(indoc!(
+            r#"
+            when l is
+                [] -> 0
+                [x] -> x
+                [first, .. as rest] -> first + List.len rest
+                [.., last] -> last
+                [_, .., _] -> 1
+            "#
+        ));
+
+        expr_formats_to(
+            indoc!(
+                r#"
+                when l is
+                    [ ] -> 0
+                    [ first , ..  as  rest ] -> first
+                    [ .. , last ] -> last
+                "#
+            ),
+            indoc!(
+                r#"
+                when l is
+                    [] -> 0
+                    [first, .. as rest] -> first
+                    [.., last] -> last
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn dbg() {
+        expr_formats_same(indoc!(
+            r#"
+            dbg 1 == 1
+
+            4
+            "#
+        ));
+
+        expr_formats_same(indoc!(
+            r#"
+            x = dbg List.len list
+
+            x + 1
+            "#
+        ));
+
+        expr_formats_same(indoc!(
+            r#"
+            f = \x ->
+                dbg x
+
+                x + 1
+
+            f 2
+            "#
+        ));
+
+        expr_formats_to(
+            indoc!(
+                r#"
+                dbg    x
+                4
+                "#
+            ),
+            indoc!(
+                r#"
+                dbg x
+
+                4
+                "#
+            ),
+        );
+    }
+
     // IF
 
     #[test]
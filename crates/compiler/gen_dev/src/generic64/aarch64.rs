@@ -1,10 +1,17 @@
 use crate::generic64::{storage::StorageManager, Assembler, CallConv, RegTrait};
-use crate::Relocation;
+use crate::{
+    single_register_floats, single_register_int_builtins, single_register_integers,
+    single_register_layouts, Relocation,
+};
 use bumpalo::collections::Vec;
 use packed_struct::prelude::*;
+use roc_builtins::bitcode::{FloatWidth, IntWidth};
 use roc_error_macros::internal_error;
 use roc_module::symbol::Symbol;
-use roc_mono::layout::Layout;
+use roc_mono::layout::{Builtin, Layout, STLayoutInterner};
+use roc_target::TargetInfo;
+
+const TARGET_INFO: TargetInfo = TargetInfo::default_aarch64();
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
 #[allow(dead_code)]
@@ -99,9 +106,44 @@ impl AArch64GeneralReg {
     }
 }
 
+/// The SIMD and floating point registers.
+/// The backend only ever uses their low 64 bits, accessed as `Dn` or `Sn`.
 #[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
 #[allow(dead_code)]
-pub enum AArch64FloatReg {}
+pub enum AArch64FloatReg {
+    V0 = 0,
+    V1 = 1,
+    V2 = 2,
+    V3 = 3,
+    V4 = 4,
+    V5 = 5,
+    V6 = 6,
+    V7 = 7,
+    V8 = 8,
+    V9 = 9,
+    V10 = 10,
+    V11 = 11,
+    V12 = 12,
+    V13 = 13,
+    V14 = 14,
+    V15 = 15,
+    V16 = 16,
+    V17 = 17,
+    V18 = 18,
+    V19 = 19,
+    V20 = 20,
+    V21 = 21,
+    V22 = 22,
+    V23 = 23,
+    V24 = 24,
+    V25 = 25,
+    V26 = 26,
+    V27 = 27,
+    V28 = 28,
+    V29 = 29,
+    V30 = 30,
+    V31 = 31,
+}
 impl RegTrait for AArch64FloatReg {
     fn value(&self) -> u8 {
         *self as u8
@@ -109,7 +151,14 @@ impl RegTrait for AArch64FloatReg {
 }
 impl std::fmt::Display for AArch64FloatReg {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "TODO",)
+        write!(f, "v{}", self.id())
+    }
+}
+
+impl AArch64FloatReg {
+    #[inline(always)]
+    fn id(&self) -> u8 {
+        *self as u8
     }
 }
 
@@ -147,6 +196,8 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         // Don't use platform register: AArch64GeneralReg::PR,
         // Don't use link register: AArch64GeneralReg::LR,
         // Don't use zero register/stack pointer: AArch64GeneralReg::ZRSP,
+        // Don't use intra-procedure-call scratch registers, the assembler needs them:
+        // AArch64GeneralReg::IP0, AArch64GeneralReg::IP1,
 
         // Use callee saved regs last.
         AArch64GeneralReg::X19,
@@ -175,12 +226,57 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         AArch64GeneralReg::X13,
         AArch64GeneralReg::X14,
         AArch64GeneralReg::X15,
-        AArch64GeneralReg::IP0,
-        AArch64GeneralReg::IP1,
     ];
-    const FLOAT_PARAM_REGS: &'static [AArch64FloatReg] = &[];
+    const FLOAT_PARAM_REGS: &'static [AArch64FloatReg] = &[
+        AArch64FloatReg::V0,
+        AArch64FloatReg::V1,
+        AArch64FloatReg::V2,
+        AArch64FloatReg::V3,
+        AArch64FloatReg::V4,
+        AArch64FloatReg::V5,
+        AArch64FloatReg::V6,
+        AArch64FloatReg::V7,
+    ];
     const FLOAT_RETURN_REGS: &'static [AArch64FloatReg] = Self::FLOAT_PARAM_REGS;
-    const FLOAT_DEFAULT_FREE_REGS: &'static [AArch64FloatReg] = &[];
+    const FLOAT_DEFAULT_FREE_REGS: &'static [AArch64FloatReg] = &[
+        // The regs we want to use first should be at the end of this vec.
+        // We will use pop to get which reg to use next
+
+        // Use callee saved regs last.
+        AArch64FloatReg::V8,
+        AArch64FloatReg::V9,
+        AArch64FloatReg::V10,
+        AArch64FloatReg::V11,
+        AArch64FloatReg::V12,
+        AArch64FloatReg::V13,
+        AArch64FloatReg::V14,
+        AArch64FloatReg::V15,
+        // Use caller saved regs first.
+        AArch64FloatReg::V0,
+        AArch64FloatReg::V1,
+        AArch64FloatReg::V2,
+        AArch64FloatReg::V3,
+        AArch64FloatReg::V4,
+        AArch64FloatReg::V5,
+        AArch64FloatReg::V6,
+        AArch64FloatReg::V7,
+        AArch64FloatReg::V16,
+        AArch64FloatReg::V17,
+        AArch64FloatReg::V18,
+        AArch64FloatReg::V19,
+        AArch64FloatReg::V20,
+        AArch64FloatReg::V21,
+        AArch64FloatReg::V22,
+        AArch64FloatReg::V23,
+        AArch64FloatReg::V24,
+        AArch64FloatReg::V25,
+        AArch64FloatReg::V26,
+        AArch64FloatReg::V27,
+        AArch64FloatReg::V28,
+        AArch64FloatReg::V29,
+        AArch64FloatReg::V30,
+        AArch64FloatReg::V31,
+    ];
 
     const SHADOW_SPACE_SIZE: u8 = 0;
 
@@ -201,8 +297,19 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         )
     }
     #[inline(always)]
-    fn float_callee_saved(_reg: &AArch64FloatReg) -> bool {
-        todo!("AArch64 FloatRegs");
+    fn float_callee_saved(reg: &AArch64FloatReg) -> bool {
+        // Only the bottom 64 bits of these are callee saved, but that is all we use.
+        matches!(
+            reg,
+            AArch64FloatReg::V8
+                | AArch64FloatReg::V9
+                | AArch64FloatReg::V10
+                | AArch64FloatReg::V11
+                | AArch64FloatReg::V12
+                | AArch64FloatReg::V13
+                | AArch64FloatReg::V14
+                | AArch64FloatReg::V15
+        )
     }
 
     #[inline(always)]
@@ -213,9 +320,18 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         requested_stack_size: i32,
         fn_call_stack_size: i32,
     ) -> i32 {
-        // Full size is upcast to i64 to make sure we don't overflow here.
+        // Push the frame pointer and link register as a pair, then point the frame pointer at them.
+        stp_reg64_reg64_reg64_preindex(
+            buf,
+            AArch64GeneralReg::FP,
+            AArch64GeneralReg::LR,
+            AArch64GeneralReg::ZRSP,
+            -2,
+        );
+        add_reg64_reg64_imm12(buf, AArch64GeneralReg::FP, AArch64GeneralReg::ZRSP, 0);
+
         let full_stack_size = match requested_stack_size
-            .checked_add(8 * (saved_general_regs.len() + saved_float_regs.len()) as i32 + 8) // The extra 8 is space to store the frame pointer.
+            .checked_add(8 * (saved_general_regs.len() + saved_float_regs.len()) as i32)
             .and_then(|size| size.checked_add(fn_call_stack_size))
         {
             Some(size) => size,
@@ -233,11 +349,6 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         };
         if let Some(aligned_stack_size) = full_stack_size.checked_add(offset as i32) {
             if aligned_stack_size > 0 {
-                AArch64Assembler::mov_reg64_reg64(
-                    buf,
-                    AArch64GeneralReg::FP,
-                    AArch64GeneralReg::ZRSP,
-                );
                 AArch64Assembler::sub_reg64_reg64_imm32(
                     buf,
                     AArch64GeneralReg::ZRSP,
@@ -245,21 +356,15 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
                     aligned_stack_size,
                 );
 
-                // All the following stores could be optimized by using `STP` to store pairs.
-                let mut offset = aligned_stack_size;
-                offset -= 8;
-                AArch64Assembler::mov_stack32_reg64(buf, offset, AArch64GeneralReg::LR);
-                offset -= 8;
-                AArch64Assembler::mov_stack32_reg64(buf, offset, AArch64GeneralReg::FP);
-
-                offset = aligned_stack_size - fn_call_stack_size;
+                // Put values at the top of the stack to avoid conflicts with previously saved variables.
+                let mut offset = aligned_stack_size - fn_call_stack_size;
                 for reg in saved_general_regs {
+                    AArch64Assembler::mov_base32_reg64(buf, -offset, *reg);
                     offset -= 8;
-                    AArch64Assembler::mov_base32_reg64(buf, offset, *reg);
                 }
                 for reg in saved_float_regs {
+                    AArch64Assembler::mov_base32_freg64(buf, -offset, *reg);
                     offset -= 8;
-                    AArch64Assembler::mov_base32_freg64(buf, offset, *reg);
                 }
                 aligned_stack_size
             } else {
@@ -279,21 +384,14 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         fn_call_stack_size: i32,
     ) {
         if aligned_stack_size > 0 {
-            // All the following stores could be optimized by using `STP` to store pairs.
-            let mut offset = aligned_stack_size;
-            offset -= 8;
-            AArch64Assembler::mov_reg64_stack32(buf, AArch64GeneralReg::LR, offset);
-            offset -= 8;
-            AArch64Assembler::mov_reg64_stack32(buf, AArch64GeneralReg::FP, offset);
-
-            offset = aligned_stack_size - fn_call_stack_size;
+            let mut offset = aligned_stack_size - fn_call_stack_size;
             for reg in saved_general_regs {
+                AArch64Assembler::mov_reg64_base32(buf, *reg, -offset);
                 offset -= 8;
-                AArch64Assembler::mov_reg64_base32(buf, *reg, offset);
             }
             for reg in saved_float_regs {
+                AArch64Assembler::mov_freg64_base32(buf, *reg, -offset);
                 offset -= 8;
-                AArch64Assembler::mov_freg64_base32(buf, *reg, offset);
             }
             AArch64Assembler::add_reg64_reg64_imm32(
                 buf,
@@ -302,87 +400,410 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
                 aligned_stack_size,
             );
         }
+        ldp_reg64_reg64_reg64_postindex(
+            buf,
+            AArch64GeneralReg::FP,
+            AArch64GeneralReg::LR,
+            AArch64GeneralReg::ZRSP,
+            2,
+        );
     }
 
     #[inline(always)]
     fn load_args<'a>(
-        _buf: &mut Vec<'a, u8>,
-        _storage_manager: &mut StorageManager<
+        buf: &mut Vec<'a, u8>,
+        storage_manager: &mut StorageManager<
             'a,
             AArch64GeneralReg,
             AArch64FloatReg,
             AArch64Assembler,
             AArch64Call,
         >,
-        _args: &'a [(Layout<'a>, Symbol)],
-        _ret_layout: &Layout<'a>,
+        args: &'a [(Layout<'a>, Symbol)],
+        ret_layout: &Layout<'a>,
     ) {
-        todo!("Loading args for AArch64");
+        let arg_offset = 16; // 16 is the size of the pushed frame pointer and link register.
+        if Self::returns_via_arg_pointer(storage_manager.env.layout_interner, ret_layout) {
+            // The result address comes in the indirect result register, not a param reg.
+            storage_manager.ret_pointer_arg(AArch64GeneralReg::XR);
+        }
+        let mut locations = ArgLocations::default();
+        for (layout, sym) in args.iter() {
+            let stack_size = layout.stack_size(storage_manager.env.layout_interner, TARGET_INFO);
+            match locations.next(storage_manager.env.layout_interner, layout) {
+                ArgLocation::NoData => {
+                    storage_manager.no_data_arg(sym);
+                }
+                ArgLocation::GeneralReg(reg) => {
+                    storage_manager.general_reg_arg(sym, reg);
+                }
+                ArgLocation::FloatReg(reg) => {
+                    storage_manager.float_reg_arg(sym, reg);
+                }
+                ArgLocation::GeneralRegs(regs) => {
+                    let base_offset = storage_manager.claim_stack_area(sym, stack_size);
+                    for (i, reg) in regs.iter().enumerate() {
+                        AArch64Assembler::mov_base32_reg64(buf, base_offset + 8 * i as i32, *reg);
+                    }
+                }
+                ArgLocation::Stack(offset) => match layout {
+                    single_register_layouts!() => {
+                        storage_manager.primitive_stack_arg(sym, arg_offset + offset);
+                    }
+                    _ => {
+                        storage_manager.complex_stack_arg(sym, arg_offset + offset, stack_size);
+                    }
+                },
+                location @ (ArgLocation::IndirectReg(_) | ArgLocation::IndirectStack(_)) => {
+                    // The caller passed a pointer to its copy of the value.
+                    // Copy it into this frame so it can be treated like any other local.
+                    let ptr_reg = match location {
+                        ArgLocation::IndirectReg(reg) => reg,
+                        ArgLocation::IndirectStack(offset) => {
+                            AArch64Assembler::mov_reg64_base32(
+                                buf,
+                                AArch64GeneralReg::IP1,
+                                arg_offset + offset,
+                            );
+                            AArch64GeneralReg::IP1
+                        }
+                        _ => unreachable!(),
+                    };
+                    let base_offset = storage_manager.claim_stack_area(sym, stack_size);
+                    storage_manager.with_tmp_general_reg(buf, |_storage_manager, buf, tmp_reg| {
+                        for i in (0..stack_size as i32).step_by(8) {
+                            AArch64Assembler::mov_reg64_mem64_offset32(buf, tmp_reg, ptr_reg, i);
+                            AArch64Assembler::mov_base32_reg64(buf, base_offset + i, tmp_reg);
+                        }
+                    });
+                }
+            }
+        }
     }
 
     #[inline(always)]
     fn store_args<'a>(
-        _buf: &mut Vec<'a, u8>,
-        _storage_manager: &mut StorageManager<
+        buf: &mut Vec<'a, u8>,
+        storage_manager: &mut StorageManager<
             'a,
             AArch64GeneralReg,
             AArch64FloatReg,
             AArch64Assembler,
             AArch64Call,
         >,
-        _dst: &Symbol,
-        _args: &[Symbol],
-        _arg_layouts: &[Layout<'a>],
-        _ret_layout: &Layout<'a>,
+        dst: &Symbol,
+        args: &[Symbol],
+        arg_layouts: &[Layout<'a>],
+        ret_layout: &Layout<'a>,
     ) {
-        todo!("Storing args for AArch64");
+        let interner = storage_manager.env.layout_interner;
+        let mut locations = ArgLocations::default();
+        let arg_locations = Vec::from_iter_in(
+            arg_layouts
+                .iter()
+                .map(|layout| locations.next(interner, layout)),
+            storage_manager.env.arena,
+        );
+        // Copies of args passed by reference go after the args passed on the stack.
+        let mut copy_stack_offset = locations.stack_offset;
+
+        for ((sym, layout), location) in args.iter().zip(arg_layouts.iter()).zip(arg_locations) {
+            match location {
+                ArgLocation::NoData => {}
+                ArgLocation::GeneralReg(reg) => {
+                    storage_manager.load_to_specified_general_reg(buf, sym, reg);
+                }
+                ArgLocation::FloatReg(reg) => {
+                    storage_manager.load_to_specified_float_reg(buf, sym, reg);
+                }
+                ArgLocation::GeneralRegs(regs) => {
+                    let (base_offset, _) = storage_manager.stack_offset_and_size(sym);
+                    for (i, reg) in regs.iter().enumerate() {
+                        AArch64Assembler::mov_reg64_base32(buf, *reg, base_offset + 8 * i as i32);
+                    }
+                }
+                ArgLocation::Stack(offset) => match layout {
+                    single_register_integers!() => {
+                        // IP1 is never allocated, so it is safe to use as a buffer.
+                        storage_manager.load_to_specified_general_reg(
+                            buf,
+                            sym,
+                            AArch64GeneralReg::IP1,
+                        );
+                        AArch64Assembler::mov_stack32_reg64(buf, offset, AArch64GeneralReg::IP1);
+                    }
+                    single_register_floats!() => {
+                        storage_manager.with_tmp_float_reg(buf, |storage_manager, buf, reg| {
+                            storage_manager.load_to_specified_float_reg(buf, sym, reg);
+                            AArch64Assembler::mov_stack32_freg64(buf, offset, reg);
+                        });
+                    }
+                    _ => {
+                        let (base_offset, size) = storage_manager.stack_offset_and_size(sym);
+                        Self::copy_base_to_stack(buf, base_offset, offset, size);
+                    }
+                },
+                ArgLocation::IndirectReg(reg) => {
+                    let (base_offset, size) = storage_manager.stack_offset_and_size(sym);
+                    Self::copy_base_to_stack(buf, base_offset, copy_stack_offset, size);
+                    AArch64Assembler::add_reg64_reg64_imm32(
+                        buf,
+                        reg,
+                        AArch64GeneralReg::ZRSP,
+                        copy_stack_offset,
+                    );
+                    copy_stack_offset += round_up_to_8(size) as i32;
+                }
+                ArgLocation::IndirectStack(offset) => {
+                    let (base_offset, size) = storage_manager.stack_offset_and_size(sym);
+                    Self::copy_base_to_stack(buf, base_offset, copy_stack_offset, size);
+                    AArch64Assembler::add_reg64_reg64_imm32(
+                        buf,
+                        AArch64GeneralReg::IP1,
+                        AArch64GeneralReg::ZRSP,
+                        copy_stack_offset,
+                    );
+                    AArch64Assembler::mov_stack32_reg64(buf, offset, AArch64GeneralReg::IP1);
+                    copy_stack_offset += round_up_to_8(size) as i32;
+                }
+            }
+        }
+        if Self::returns_via_arg_pointer(interner, ret_layout) {
+            // Save space on the stack for the result we will be return.
+            let base_offset = storage_manager.claim_stack_area(
+                dst,
+                ret_layout.stack_size(storage_manager.env.layout_interner, TARGET_INFO),
+            );
+            // Set the indirect result register to the address base + offset.
+            // This happens last so that no temporary register can clobber it.
+            AArch64Assembler::add_reg64_reg64_imm32(
+                buf,
+                AArch64GeneralReg::XR,
+                AArch64GeneralReg::FP,
+                base_offset,
+            );
+        }
+        storage_manager.update_fn_call_stack_size(copy_stack_offset as u32);
     }
 
     fn return_complex_symbol<'a>(
-        _buf: &mut Vec<'a, u8>,
-        _storage_manager: &mut StorageManager<
+        buf: &mut Vec<'a, u8>,
+        storage_manager: &mut StorageManager<
             'a,
             AArch64GeneralReg,
             AArch64FloatReg,
             AArch64Assembler,
             AArch64Call,
         >,
-        _sym: &Symbol,
-        _layout: &Layout<'a>,
+        sym: &Symbol,
+        layout: &Layout<'a>,
     ) {
-        todo!("Returning complex symbols for AArch64");
+        match layout {
+            single_register_layouts!() => {
+                internal_error!("single register layouts are not complex symbols");
+            }
+            x if x.stack_size(storage_manager.env.layout_interner, TARGET_INFO) == 0 => {}
+            x if !Self::returns_via_arg_pointer(storage_manager.env.layout_interner, x) => {
+                let (base_offset, size) = storage_manager.stack_offset_and_size(sym);
+                debug_assert_eq!(base_offset % 8, 0);
+                if size <= 8 {
+                    AArch64Assembler::mov_reg64_base32(
+                        buf,
+                        Self::GENERAL_RETURN_REGS[0],
+                        base_offset,
+                    );
+                } else if size <= 16 {
+                    AArch64Assembler::mov_reg64_base32(
+                        buf,
+                        Self::GENERAL_RETURN_REGS[0],
+                        base_offset,
+                    );
+                    AArch64Assembler::mov_reg64_base32(
+                        buf,
+                        Self::GENERAL_RETURN_REGS[1],
+                        base_offset + 8,
+                    );
+                } else {
+                    internal_error!(
+                        "types that don't return via arg pointer must be less than 16 bytes"
+                    );
+                }
+            }
+            _ => {
+                // This is a large type returned via the arg pointer.
+                // Unlike x86, the pointer does not need to be returned as well.
+                storage_manager.copy_symbol_to_arg_pointer(buf, sym, layout);
+            }
+        }
     }
 
     fn load_returned_complex_symbol<'a>(
-        _buf: &mut Vec<'a, u8>,
-        _storage_manager: &mut StorageManager<
+        buf: &mut Vec<'a, u8>,
+        storage_manager: &mut StorageManager<
             'a,
             AArch64GeneralReg,
             AArch64FloatReg,
             AArch64Assembler,
             AArch64Call,
         >,
-        _sym: &Symbol,
-        _layout: &Layout<'a>,
+        sym: &Symbol,
+        layout: &Layout<'a>,
     ) {
-        todo!("Loading returned complex symbols for AArch64");
+        match layout {
+            single_register_layouts!() => {
+                internal_error!("single register layouts are not complex symbols");
+            }
+            x if x.stack_size(storage_manager.env.layout_interner, TARGET_INFO) == 0 => {}
+            x if !Self::returns_via_arg_pointer(storage_manager.env.layout_interner, x) => {
+                let size = layout.stack_size(storage_manager.env.layout_interner, TARGET_INFO);
+                let offset = storage_manager.claim_stack_area(sym, size);
+                if size <= 8 {
+                    AArch64Assembler::mov_base32_reg64(buf, offset, Self::GENERAL_RETURN_REGS[0]);
+                } else if size <= 16 {
+                    AArch64Assembler::mov_base32_reg64(buf, offset, Self::GENERAL_RETURN_REGS[0]);
+                    AArch64Assembler::mov_base32_reg64(
+                        buf,
+                        offset + 8,
+                        Self::GENERAL_RETURN_REGS[1],
+                    );
+                } else {
+                    internal_error!(
+                        "types that don't return via arg pointer must be less than 16 bytes"
+                    );
+                }
+            }
+            _ => {
+                // This should have been recieved via an arg pointer.
+                // That means the value is already loaded onto the stack area we allocated before the call.
+                // Nothing to do.
+            }
+        }
     }
 }
 
+impl AArch64Call {
+    fn returns_via_arg_pointer<'a>(
+        interner: &STLayoutInterner<'a>,
+        ret_layout: &Layout<'a>,
+    ) -> bool {
+        // Composites over 16 bytes are returned in memory pointed to by XR.
+        // TODO: homogeneous floating point aggregates should be returned in float registers.
+        // details here: https://github.com/ARM-software/abi-aa/blob/main/aapcs64/aapcs64.rst
+        ret_layout.stack_size(interner, TARGET_INFO) > 16
+    }
+
+    /// Copies `size` bytes from the base offset to the stack offset, 8 bytes at a time.
+    /// IP1 is never allocated, so it is safe to use as a buffer.
+    fn copy_base_to_stack(buf: &mut Vec<'_, u8>, base_offset: i32, stack_offset: i32, size: u32) {
+        debug_assert_eq!(base_offset % 8, 0);
+        for i in (0..size as i32).step_by(8) {
+            AArch64Assembler::mov_reg64_base32(buf, AArch64GeneralReg::IP1, base_offset + i);
+            AArch64Assembler::mov_stack32_reg64(buf, stack_offset + i, AArch64GeneralReg::IP1);
+        }
+    }
+}
+
+/// Where a single argument is passed.
+#[derive(Clone, Copy, Debug)]
+enum ArgLocation {
+    NoData,
+    GeneralReg(AArch64GeneralReg),
+    FloatReg(AArch64FloatReg),
+    /// A composite of 16 bytes or less, split across consecutive general registers.
+    GeneralRegs(&'static [AArch64GeneralReg]),
+    /// An offset into the argument area on the stack.
+    Stack(i32),
+    /// A composite over 16 bytes, passed as a pointer to a copy in a general register.
+    IndirectReg(AArch64GeneralReg),
+    /// A composite over 16 bytes, passed as a pointer to a copy on the stack.
+    IndirectStack(i32),
+}
+
+/// Hands out argument locations in order, following AAPCS64.
+/// Both sides of a call use this, so they always agree on where each argument lives.
+#[derive(Default)]
+struct ArgLocations {
+    general_i: usize,
+    float_i: usize,
+    stack_offset: i32,
+}
+
+impl ArgLocations {
+    fn next<'a>(&mut self, interner: &STLayoutInterner<'a>, layout: &Layout<'a>) -> ArgLocation {
+        let stack_size = layout.stack_size(interner, TARGET_INFO);
+        match layout {
+            single_register_integers!() => match self.general_regs(1) {
+                Some(regs) => ArgLocation::GeneralReg(regs[0]),
+                None => ArgLocation::Stack(self.stack_slot(8, 8)),
+            },
+            single_register_floats!() => {
+                if self.float_i < AArch64Call::FLOAT_PARAM_REGS.len() {
+                    self.float_i += 1;
+                    ArgLocation::FloatReg(AArch64Call::FLOAT_PARAM_REGS[self.float_i - 1])
+                } else {
+                    ArgLocation::Stack(self.stack_slot(8, 8))
+                }
+            }
+            _ if stack_size == 0 => ArgLocation::NoData,
+            _ if stack_size > 16 => match self.general_regs(1) {
+                Some(regs) => ArgLocation::IndirectReg(regs[0]),
+                None => ArgLocation::IndirectStack(self.stack_slot(8, 8)),
+            },
+            _ => {
+                // TODO: homogeneous floating point aggregates should be passed in float registers.
+                let alignment = layout.alignment_bytes(interner, TARGET_INFO);
+                if alignment == 16 {
+                    // 16 byte aligned values, like 128-bit integers, start at an even register.
+                    self.general_i += self.general_i % 2;
+                }
+                let size = round_up_to_8(stack_size);
+                match self.general_regs(size as usize / 8) {
+                    Some(regs) => ArgLocation::GeneralRegs(regs),
+                    None => {
+                        // Once a composite goes on the stack, no later ones may use registers.
+                        self.general_i = AArch64Call::GENERAL_PARAM_REGS.len();
+                        ArgLocation::Stack(self.stack_slot(size, alignment.max(8)))
+                    }
+                }
+            }
+        }
+    }
+
+    fn general_regs(&mut self, count: usize) -> Option<&'static [AArch64GeneralReg]> {
+        let regs = AArch64Call::GENERAL_PARAM_REGS.get(self.general_i..self.general_i + count)?;
+        self.general_i += count;
+        Some(regs)
+    }
+
+    // TODO: Apple platforms pack stack arguments to their natural alignment instead of 8 bytes.
+    fn stack_slot(&mut self, size: u32, alignment: u32) -> i32 {
+        let alignment = alignment as i32;
+        let offset = (self.stack_offset + alignment - 1) / alignment * alignment;
+        self.stack_offset = offset + size as i32;
+        offset
+    }
+}
+
+#[inline(always)]
+fn round_up_to_8(size: u32) -> u32 {
+    (size + 7) & !7
+}
+
 impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
     #[inline(always)]
-    fn abs_reg64_reg64(_buf: &mut Vec<'_, u8>, _dst: AArch64GeneralReg, _src: AArch64GeneralReg) {
-        todo!("abs_reg64_reg64 for AArch64");
+    fn abs_reg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64GeneralReg) {
+        cmp_reg64_imm12(buf, src, 0);
+        cneg_reg64_reg64_cond(buf, dst, src, ConditionCode::MI);
     }
 
     #[inline(always)]
     fn abs_freg64_freg64(
-        _buf: &mut Vec<'_, u8>,
+        buf: &mut Vec<'_, u8>,
         _relocs: &mut Vec<'_, Relocation>,
-        _dst: AArch64FloatReg,
-        _src: AArch64FloatReg,
+        dst: AArch64FloatReg,
+        src: AArch64FloatReg,
     ) {
-        todo!("abs_reg64_reg64 for AArch64");
+        fabs_freg64_freg64(buf, dst, src);
     }
 
     #[inline(always)]
@@ -392,12 +813,23 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         src: AArch64GeneralReg,
         imm32: i32,
     ) {
-        if imm32 < 0 {
-            todo!("immediate addition with values less than 0");
-        } else if imm32 < 0xFFF {
-            add_reg64_reg64_imm12(buf, dst, src, imm32 as u16);
-        } else {
-            todo!("immediate additions with values greater than 12bits");
+        match imm32 {
+            0..=0xFFF => {
+                add_reg64_reg64_imm12(buf, dst, src, imm32 as u16);
+            }
+            0x1000..=0xFF_FFFF => {
+                add_reg64_reg64_imm12_lsl12(buf, dst, src, (imm32 >> 12) as u16);
+                if imm32 & 0xFFF != 0 {
+                    add_reg64_reg64_imm12(buf, dst, dst, (imm32 & 0xFFF) as u16);
+                }
+            }
+            -0xFF_FFFF..=-1 => {
+                Self::sub_reg64_reg64_imm32(buf, dst, src, -imm32);
+            }
+            _ => {
+                Self::mov_reg64_imm64(buf, AArch64GeneralReg::IP0, imm32 as i64);
+                add_reg64_reg64_reg64_uxtx(buf, dst, src, AArch64GeneralReg::IP0);
+            }
         }
     }
     #[inline(always)]
@@ -407,157 +839,179 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         src1: AArch64GeneralReg,
         src2: AArch64GeneralReg,
     ) {
-        add_reg64_reg64_reg64(buf, dst, src1, src2);
+        // Set the flags like on x86, so that set_if_overflow can follow this.
+        adds_reg64_reg64_reg64(buf, dst, src1, src2);
     }
     #[inline(always)]
     fn add_freg32_freg32_freg32(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src1: AArch64FloatReg,
-        _src2: AArch64FloatReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src1: AArch64FloatReg,
+        src2: AArch64FloatReg,
     ) {
-        todo!("adding floats for AArch64");
+        fadd_freg32_freg32_freg32(buf, dst, src1, src2);
     }
     #[inline(always)]
     fn add_freg64_freg64_freg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src1: AArch64FloatReg,
-        _src2: AArch64FloatReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src1: AArch64FloatReg,
+        src2: AArch64FloatReg,
     ) {
-        todo!("adding floats for AArch64");
+        fadd_freg64_freg64_freg64(buf, dst, src1, src2);
     }
 
     #[inline(always)]
-    fn call(_buf: &mut Vec<'_, u8>, _relocs: &mut Vec<'_, Relocation>, _fn_name: String) {
-        todo!("calling functions literal for AArch64");
+    fn call(buf: &mut Vec<'_, u8>, relocs: &mut Vec<'_, Relocation>, fn_name: String) {
+        // The relocation patches the whole instruction rather than a trailing immediate.
+        relocs.push(Relocation::LinkedFunction {
+            offset: buf.len() as u64,
+            name: fn_name,
+        });
+        bl_imm26(buf, 0);
     }
 
     #[inline(always)]
     fn imul_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("register signed multiplication for AArch64");
+        mul_reg64_reg64_reg64(buf, dst, src1, src2);
     }
 
     fn umul_reg64_reg64_reg64<'a, ASM, CC>(
-        _buf: &mut Vec<'a, u8>,
+        buf: &mut Vec<'a, u8>,
         _storage_manager: &mut StorageManager<'a, AArch64GeneralReg, AArch64FloatReg, ASM, CC>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) where
         ASM: Assembler<AArch64GeneralReg, AArch64FloatReg>,
         CC: CallConv<AArch64GeneralReg, AArch64FloatReg, ASM>,
     {
-        todo!("register unsigned multiplication for AArch64");
+        // The low 64 bits of the product are the same for signed and unsigned values.
+        mul_reg64_reg64_reg64(buf, dst, src1, src2);
     }
 
     fn idiv_reg64_reg64_reg64<'a, ASM, CC>(
-        _buf: &mut Vec<'a, u8>,
+        buf: &mut Vec<'a, u8>,
         _storage_manager: &mut StorageManager<'a, AArch64GeneralReg, AArch64FloatReg, ASM, CC>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) where
         ASM: Assembler<AArch64GeneralReg, AArch64FloatReg>,
         CC: CallConv<AArch64GeneralReg, AArch64FloatReg, ASM>,
     {
-        todo!("register signed division for AArch64");
+        sdiv_reg64_reg64_reg64(buf, dst, src1, src2);
     }
 
     fn udiv_reg64_reg64_reg64<'a, ASM, CC>(
-        _buf: &mut Vec<'a, u8>,
+        buf: &mut Vec<'a, u8>,
         _storage_manager: &mut StorageManager<'a, AArch64GeneralReg, AArch64FloatReg, ASM, CC>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) where
         ASM: Assembler<AArch64GeneralReg, AArch64FloatReg>,
         CC: CallConv<AArch64GeneralReg, AArch64FloatReg, ASM>,
     {
-        todo!("register unsigned division for AArch64");
+        udiv_reg64_reg64_reg64(buf, dst, src1, src2);
     }
 
     #[inline(always)]
     fn mul_freg32_freg32_freg32(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src1: AArch64FloatReg,
-        _src2: AArch64FloatReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src1: AArch64FloatReg,
+        src2: AArch64FloatReg,
     ) {
-        todo!("multiplication for floats for AArch64");
+        fmul_freg32_freg32_freg32(buf, dst, src1, src2);
     }
     #[inline(always)]
     fn mul_freg64_freg64_freg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src1: AArch64FloatReg,
-        _src2: AArch64FloatReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src1: AArch64FloatReg,
+        src2: AArch64FloatReg,
     ) {
-        todo!("multiplication for floats for AArch64");
+        fmul_freg64_freg64_freg64(buf, dst, src1, src2);
     }
 
     #[inline(always)]
     fn div_freg32_freg32_freg32(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src1: AArch64FloatReg,
-        _src2: AArch64FloatReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src1: AArch64FloatReg,
+        src2: AArch64FloatReg,
     ) {
-        todo!("division for floats for AArch64");
+        fdiv_freg32_freg32_freg32(buf, dst, src1, src2);
     }
     #[inline(always)]
     fn div_freg64_freg64_freg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src1: AArch64FloatReg,
-        _src2: AArch64FloatReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src1: AArch64FloatReg,
+        src2: AArch64FloatReg,
     ) {
-        todo!("division for floats for AArch64");
+        fdiv_freg64_freg64_freg64(buf, dst, src1, src2);
     }
 
     #[inline(always)]
-    fn jmp_imm32(_buf: &mut Vec<'_, u8>, _offset: i32) -> usize {
-        todo!("jump instructions for AArch64");
+    fn jmp_imm32(buf: &mut Vec<'_, u8>, offset: i32) -> usize {
+        // Branch offsets are relative to the start of the instruction, so that is the base offset.
+        let base_offset = buf.len();
+        b_imm26(buf, offset);
+        base_offset
     }
 
     #[inline(always)]
     fn tail_call(buf: &mut Vec<'_, u8>) -> u64 {
         Self::jmp_imm32(buf, 0);
-        buf.len() as u64 - 4 // TODO is 4 the correct offset in ARM?
+        buf.len() as u64 - 4
     }
 
     #[inline(always)]
     fn jne_reg64_imm64_imm32(
-        _buf: &mut Vec<'_, u8>,
-        _reg: AArch64GeneralReg,
-        _imm: u64,
-        _offset: i32,
+        buf: &mut Vec<'_, u8>,
+        reg: AArch64GeneralReg,
+        imm: u64,
+        offset: i32,
     ) -> usize {
-        todo!("jump not equal instructions for AArch64");
+        if imm <= 0xFFF {
+            cmp_reg64_imm12(buf, reg, imm as u16);
+        } else {
+            Self::mov_reg64_imm64(buf, AArch64GeneralReg::IP0, imm as i64);
+            cmp_reg64_reg64(buf, reg, AArch64GeneralReg::IP0);
+        }
+        let base_offset = buf.len();
+        b_cond_imm19(buf, ConditionCode::NE, offset);
+        base_offset
     }
 
     #[inline(always)]
     fn mov_freg32_imm32(
-        _buf: &mut Vec<'_, u8>,
+        buf: &mut Vec<'_, u8>,
         _relocs: &mut Vec<'_, Relocation>,
-        _dst: AArch64FloatReg,
-        _imm: f32,
+        dst: AArch64FloatReg,
+        imm: f32,
     ) {
-        todo!("loading f32 literal for AArch64");
+        // Build the bits in a general register rather than loading them from a data section.
+        Self::mov_reg64_imm64(buf, AArch64GeneralReg::IP0, imm.to_bits() as i64);
+        fmov_freg32_reg32(buf, dst, AArch64GeneralReg::IP0);
     }
     #[inline(always)]
     fn mov_freg64_imm64(
-        _buf: &mut Vec<'_, u8>,
+        buf: &mut Vec<'_, u8>,
         _relocs: &mut Vec<'_, Relocation>,
-        _dst: AArch64FloatReg,
-        _imm: f64,
+        dst: AArch64FloatReg,
+        imm: f64,
     ) {
-        todo!("loading f64 literal for AArch64");
+        // Build the bits in a general register rather than loading them from a data section.
+        Self::mov_reg64_imm64(buf, AArch64GeneralReg::IP0, imm.to_bits() as i64);
+        fmov_freg64_reg64(buf, dst, AArch64GeneralReg::IP0);
     }
     #[inline(always)]
     fn mov_reg64_imm64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, imm: i64) {
@@ -577,8 +1031,10 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         }
     }
     #[inline(always)]
-    fn mov_freg64_freg64(_buf: &mut Vec<'_, u8>, _dst: AArch64FloatReg, _src: AArch64FloatReg) {
-        todo!("moving data between float registers for AArch64");
+    fn mov_freg64_freg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+        if dst != src {
+            fmov_freg64_freg64(buf, dst, src);
+        }
     }
     #[inline(always)]
     fn mov_reg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64GeneralReg) {
@@ -586,34 +1042,20 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
     }
 
     #[inline(always)]
-    fn mov_freg64_base32(_buf: &mut Vec<'_, u8>, _dst: AArch64FloatReg, _offset: i32) {
-        todo!("loading floating point reg from base offset for AArch64");
+    fn mov_freg64_base32(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, offset: i32) {
+        Self::mov_freg64_mem64_offset32(buf, dst, AArch64GeneralReg::FP, offset);
     }
     #[inline(always)]
     fn mov_reg64_base32(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, offset: i32) {
-        if offset < 0 {
-            todo!("negative base offsets for AArch64");
-        } else if offset < (0xFFF << 8) {
-            debug_assert!(offset % 8 == 0);
-            ldr_reg64_reg64_imm12(buf, dst, AArch64GeneralReg::FP, (offset as u16) >> 3);
-        } else {
-            todo!("base offsets over 32k for AArch64");
-        }
+        Self::mov_reg64_mem64_offset32(buf, dst, AArch64GeneralReg::FP, offset);
     }
     #[inline(always)]
-    fn mov_base32_freg64(_buf: &mut Vec<'_, u8>, _offset: i32, _src: AArch64FloatReg) {
-        todo!("saving floating point reg to base offset for AArch64");
+    fn mov_base32_freg64(buf: &mut Vec<'_, u8>, offset: i32, src: AArch64FloatReg) {
+        Self::mov_mem64_offset32_freg64(buf, AArch64GeneralReg::FP, offset, src);
     }
     #[inline(always)]
     fn mov_base32_reg64(buf: &mut Vec<'_, u8>, offset: i32, src: AArch64GeneralReg) {
-        if offset < 0 {
-            todo!("negative base offsets for AArch64");
-        } else if offset < (0xFFF << 8) {
-            debug_assert!(offset % 8 == 0);
-            str_reg64_reg64_imm12(buf, src, AArch64GeneralReg::FP, (offset as u16) >> 3);
-        } else {
-            todo!("base offsets over 32k for AArch64");
-        }
+        Self::mov_mem64_offset32_reg64(buf, AArch64GeneralReg::FP, offset, src);
     }
 
     #[inline(always)]
@@ -623,13 +1065,11 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         src: AArch64GeneralReg,
         offset: i32,
     ) {
-        if offset < 0 {
-            todo!("negative mem offsets for AArch64");
-        } else if offset < (0xFFF << 8) {
-            debug_assert!(offset % 8 == 0);
-            ldr_reg64_reg64_imm12(buf, dst, src, (offset as u16) >> 3);
+        let (src, offset) = Self::encodable_offset(buf, src, offset);
+        if Self::fits_scaled_imm12(offset) {
+            ldr_reg64_reg64_imm12(buf, dst, src, (offset >> 3) as u16);
         } else {
-            todo!("mem offsets over 32k for AArch64");
+            ldur_reg64_reg64_imm9(buf, dst, src, offset as i16);
         }
     }
     #[inline(always)]
@@ -639,13 +1079,11 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         offset: i32,
         src: AArch64GeneralReg,
     ) {
-        if offset < 0 {
-            todo!("negative mem offsets for AArch64");
-        } else if offset < (0xFFF << 8) {
-            debug_assert!(offset % 8 == 0);
-            str_reg64_reg64_imm12(buf, src, dst, (offset as u16) >> 3);
+        let (dst, offset) = Self::encodable_offset(buf, dst, offset);
+        if Self::fits_scaled_imm12(offset) {
+            str_reg64_reg64_imm12(buf, src, dst, (offset >> 3) as u16);
         } else {
-            todo!("mem offsets over 32k for AArch64");
+            stur_reg64_reg64_imm9(buf, src, dst, offset as i16);
         }
     }
 
@@ -654,12 +1092,9 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         debug_assert!(size <= 8);
         if size == 8 {
             Self::mov_reg64_base32(buf, dst, offset);
-        } else if size == 4 {
-            todo!("sign extending 4 byte values");
-        } else if size == 2 {
-            todo!("sign extending 2 byte values");
-        } else if size == 1 {
-            todo!("sign extending 1 byte values");
+        } else if size == 4 || size == 2 || size == 1 {
+            let (base, offset) = Self::unscaled_offset(buf, AArch64GeneralReg::FP, offset);
+            ldursx_reg64_reg64_imm9(buf, size, dst, base, offset as i16);
         } else {
             internal_error!("Invalid size for sign extension: {}", size);
         }
@@ -669,50 +1104,33 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         debug_assert!(size <= 8);
         if size == 8 {
             Self::mov_reg64_base32(buf, dst, offset);
-        } else if size == 4 {
-            todo!("zero extending 4 byte values");
-        } else if size == 2 {
-            todo!("zero extending 2 byte values");
-        } else if size == 1 {
-            todo!("zero extending 1 byte values");
+        } else if size == 4 || size == 2 || size == 1 {
+            let (base, offset) = Self::unscaled_offset(buf, AArch64GeneralReg::FP, offset);
+            ldurzx_reg64_reg64_imm9(buf, size, dst, base, offset as i16);
         } else {
             internal_error!("Invalid size for zero extension: {}", size);
         }
     }
 
     #[inline(always)]
-    fn mov_freg64_stack32(_buf: &mut Vec<'_, u8>, _dst: AArch64FloatReg, _offset: i32) {
-        todo!("loading floating point reg from stack for AArch64");
+    fn mov_freg64_stack32(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, offset: i32) {
+        Self::mov_freg64_mem64_offset32(buf, dst, AArch64GeneralReg::ZRSP, offset);
     }
     #[inline(always)]
     fn mov_reg64_stack32(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, offset: i32) {
-        if offset < 0 {
-            todo!("negative stack offsets for AArch64");
-        } else if offset < (0xFFF << 8) {
-            debug_assert!(offset % 8 == 0);
-            ldr_reg64_reg64_imm12(buf, dst, AArch64GeneralReg::ZRSP, (offset as u16) >> 3);
-        } else {
-            todo!("stack offsets over 32k for AArch64");
-        }
+        Self::mov_reg64_mem64_offset32(buf, dst, AArch64GeneralReg::ZRSP, offset);
     }
     #[inline(always)]
-    fn mov_stack32_freg64(_buf: &mut Vec<'_, u8>, _offset: i32, _src: AArch64FloatReg) {
-        todo!("saving floating point reg to stack for AArch64");
+    fn mov_stack32_freg64(buf: &mut Vec<'_, u8>, offset: i32, src: AArch64FloatReg) {
+        Self::mov_mem64_offset32_freg64(buf, AArch64GeneralReg::ZRSP, offset, src);
     }
     #[inline(always)]
     fn mov_stack32_reg64(buf: &mut Vec<'_, u8>, offset: i32, src: AArch64GeneralReg) {
-        if offset < 0 {
-            todo!("negative stack offsets for AArch64");
-        } else if offset < (0xFFF << 8) {
-            debug_assert!(offset % 8 == 0);
-            str_reg64_reg64_imm12(buf, src, AArch64GeneralReg::ZRSP, (offset as u16) >> 3);
-        } else {
-            todo!("stack offsets over 32k for AArch64");
-        }
+        Self::mov_mem64_offset32_reg64(buf, AArch64GeneralReg::ZRSP, offset, src);
     }
     #[inline(always)]
-    fn neg_reg64_reg64(_buf: &mut Vec<'_, u8>, _dst: AArch64GeneralReg, _src: AArch64GeneralReg) {
-        todo!("neg for AArch64");
+    fn neg_reg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64GeneralReg) {
+        neg_reg64_reg64(buf, dst, src);
     }
 
     #[inline(always)]
@@ -722,112 +1140,112 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         src: AArch64GeneralReg,
         imm32: i32,
     ) {
-        if imm32 < 0 {
-            todo!("immediate subtractions with values less than 0");
-        } else if imm32 < 0xFFF {
-            sub_reg64_reg64_imm12(buf, dst, src, imm32 as u16);
-        } else {
-            todo!("immediate subtractions with values greater than 12bits");
+        match imm32 {
+            0..=0xFFF => {
+                sub_reg64_reg64_imm12(buf, dst, src, imm32 as u16);
+            }
+            0x1000..=0xFF_FFFF => {
+                sub_reg64_reg64_imm12_lsl12(buf, dst, src, (imm32 >> 12) as u16);
+                if imm32 & 0xFFF != 0 {
+                    sub_reg64_reg64_imm12(buf, dst, dst, (imm32 & 0xFFF) as u16);
+                }
+            }
+            -0xFF_FFFF..=-1 => {
+                Self::add_reg64_reg64_imm32(buf, dst, src, -imm32);
+            }
+            _ => {
+                Self::mov_reg64_imm64(buf, AArch64GeneralReg::IP0, -(imm32 as i64));
+                add_reg64_reg64_reg64_uxtx(buf, dst, src, AArch64GeneralReg::IP0);
+            }
         }
     }
     #[inline(always)]
     fn sub_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("registers subtractions for AArch64");
+        sub_reg64_reg64_reg64(buf, dst, src1, src2);
     }
 
     #[inline(always)]
     fn eq_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("registers equality for AArch64");
+        cmp_reg64_reg64(buf, src1, src2);
+        cset_reg64_cond(buf, dst, ConditionCode::EQ);
     }
 
     #[inline(always)]
     fn neq_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("registers non-equality for AArch64");
+        cmp_reg64_reg64(buf, src1, src2);
+        cset_reg64_cond(buf, dst, ConditionCode::NE);
     }
 
     #[inline(always)]
     fn lt_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("registers less than for AArch64");
+        cmp_reg64_reg64(buf, src1, src2);
+        cset_reg64_cond(buf, dst, ConditionCode::LT);
     }
 
     #[inline(always)]
-    fn to_float_freg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src: AArch64GeneralReg,
-    ) {
-        todo!("registers to float for AArch64");
+    fn to_float_freg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64GeneralReg) {
+        scvtf_freg64_reg64(buf, dst, src);
     }
 
     #[inline(always)]
-    fn to_float_freg32_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src: AArch64GeneralReg,
-    ) {
-        todo!("registers to float for AArch64");
+    fn to_float_freg32_reg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64GeneralReg) {
+        scvtf_freg32_reg64(buf, dst, src);
     }
 
     #[inline(always)]
-    fn to_float_freg32_freg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src: AArch64FloatReg,
-    ) {
-        todo!("registers to float for AArch64");
+    fn to_float_freg32_freg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+        fcvt_freg32_freg64(buf, dst, src);
     }
 
     #[inline(always)]
-    fn to_float_freg64_freg32(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src: AArch64FloatReg,
-    ) {
-        todo!("registers to float for AArch64");
+    fn to_float_freg64_freg32(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+        fcvt_freg64_freg32(buf, dst, src);
     }
 
     #[inline(always)]
     fn lte_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("registers less than or equal for AArch64");
+        cmp_reg64_reg64(buf, src1, src2);
+        cset_reg64_cond(buf, dst, ConditionCode::LE);
     }
 
     #[inline(always)]
     fn gte_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("registers greater than or equal for AArch64");
+        cmp_reg64_reg64(buf, src1, src2);
+        cset_reg64_cond(buf, dst, ConditionCode::GE);
     }
 
-    fn set_if_overflow(_buf: &mut Vec<'_, u8>, _dst: AArch64GeneralReg) {
-        todo!("set if overflow for AArch64");
+    fn set_if_overflow(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg) {
+        cset_reg64_cond(buf, dst, ConditionCode::VS);
     }
 
     #[inline(always)]
@@ -836,35 +1254,100 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
     }
 
     fn and_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("bitwise and for AArch64")
+        and_reg64_reg64_reg64(buf, dst, src1, src2);
     }
 
     fn or_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("bitwise or for AArch64")
+        orr_reg64_reg64_reg64(buf, dst, src1, src2);
     }
 
     fn xor_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("bitwise xor for AArch64")
+        eor_reg64_reg64_reg64(buf, dst, src1, src2);
     }
 }
 
-impl AArch64Assembler {}
+impl AArch64Assembler {
+    #[inline(always)]
+    fn mov_freg64_mem64_offset32(
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src: AArch64GeneralReg,
+        offset: i32,
+    ) {
+        let (src, offset) = Self::encodable_offset(buf, src, offset);
+        if Self::fits_scaled_imm12(offset) {
+            ldr_freg64_reg64_imm12(buf, dst, src, (offset >> 3) as u16);
+        } else {
+            ldur_freg64_reg64_imm9(buf, dst, src, offset as i16);
+        }
+    }
+    #[inline(always)]
+    fn mov_mem64_offset32_freg64(
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        offset: i32,
+        src: AArch64FloatReg,
+    ) {
+        let (dst, offset) = Self::encodable_offset(buf, dst, offset);
+        if Self::fits_scaled_imm12(offset) {
+            str_freg64_reg64_imm12(buf, src, dst, (offset >> 3) as u16);
+        } else {
+            stur_freg64_reg64_imm9(buf, src, dst, offset as i16);
+        }
+    }
+
+    /// 8 byte loads and stores encode either an unsigned 12 bit offset scaled by 8,
+    /// or an unscaled signed 9 bit offset.
+    /// If `offset` fits neither, `base + offset` is computed into IP0, which the assembler reserves as scratch.
+    /// Returns the base and offset to actually encode.
+    #[inline(always)]
+    fn encodable_offset(
+        buf: &mut Vec<'_, u8>,
+        base: AArch64GeneralReg,
+        offset: i32,
+    ) -> (AArch64GeneralReg, i32) {
+        if Self::fits_scaled_imm12(offset) {
+            (base, offset)
+        } else {
+            Self::unscaled_offset(buf, base, offset)
+        }
+    }
+
+    /// Like `encodable_offset`, but for loads that only have the unscaled form.
+    #[inline(always)]
+    fn unscaled_offset(
+        buf: &mut Vec<'_, u8>,
+        base: AArch64GeneralReg,
+        offset: i32,
+    ) -> (AArch64GeneralReg, i32) {
+        if (-256..256).contains(&offset) {
+            (base, offset)
+        } else {
+            Self::add_reg64_reg64_imm32(buf, AArch64GeneralReg::IP0, base, offset);
+            (AArch64GeneralReg::IP0, 0)
+        }
+    }
 
+    #[inline(always)]
+    fn fits_scaled_imm12(offset: i32) -> bool {
+        offset >= 0 && offset % 8 == 0 && offset >> 3 <= 0xFFF
+    }
+}
 // Instructions
 // ARM manual section C3
 // https://developer.arm.com/documentation/ddi0487/ga
@@ -1128,12 +1611,13 @@ impl UnconditionalBranchRegister {
 // Uses unsigned Offset
 // opc = 0b01 means load
 // opc = 0b00 means store
+// v = true means a SIMD & FP register
 #[derive(PackedStruct, Debug)]
 #[packed_struct(endian = "msb")]
 pub struct LoadStoreRegisterImmediate {
     size: Integer<u8, packed_bits::Bits<2>>,
     fixed: Integer<u8, packed_bits::Bits<3>>, // = 0b111,
-    fixed2: bool,
+    v: bool,
     fixed3: Integer<u8, packed_bits::Bits<2>>,
     opc: Integer<u8, packed_bits::Bits<2>>,
     imm12: Integer<u16, packed_bits::Bits<12>>,
@@ -1145,17 +1629,17 @@ impl Aarch64Bytes for LoadStoreRegisterImmediate {}
 
 impl LoadStoreRegisterImmediate {
     #[inline(always)]
-    fn new(size: u8, opc: u8, imm12: u16, rn: AArch64GeneralReg, rt: AArch64GeneralReg) -> Self {
+    fn new(size: u8, v: bool, opc: u8, imm12: u16, rn: AArch64GeneralReg, rt: u8) -> Self {
         debug_assert!(size <= 0b11);
         debug_assert!(imm12 <= 0xFFF);
 
         Self {
-            rt: rt.id().into(),
+            rt: rt.into(),
             rn: rn.id().into(),
             imm12: imm12.into(),
             opc: opc.into(),
             fixed3: 0b01.into(),
-            fixed2: false,
+            v,
             fixed: 0b111.into(),
             size: size.into(),
         }
@@ -1163,330 +1647,2119 @@ impl LoadStoreRegisterImmediate {
 
     #[inline(always)]
     fn new_load(size: u8, imm12: u16, rn: AArch64GeneralReg, rt: AArch64GeneralReg) -> Self {
-        Self::new(size, 0b01, imm12, rn, rt)
+        Self::new(size, false, 0b01, imm12, rn, rt.id())
     }
 
     #[inline(always)]
     fn new_store(size: u8, imm12: u16, rn: AArch64GeneralReg, rt: AArch64GeneralReg) -> Self {
-        Self::new(size, 0b00, imm12, rn, rt)
+        Self::new(size, false, 0b00, imm12, rn, rt.id())
     }
-}
 
-// Below here are the functions for all of the assembly instructions.
-// Their names are based on the instruction and operators combined.
-// You should call `buf.reserve()` if you push or extend more than once.
-// Unit tests are added at the bottom of the file to ensure correct asm generation.
-// Please keep these in alphanumeric order.
+    #[inline(always)]
+    fn new_float_load(imm12: u16, rn: AArch64GeneralReg, rt: AArch64FloatReg) -> Self {
+        Self::new(0b11, true, 0b01, imm12, rn, rt.id())
+    }
 
-/// `ADD Xd, Xn, imm12` -> Add Xn and imm12 and place the result into Xd.
-#[inline(always)]
-fn add_reg64_reg64_imm12(
-    buf: &mut Vec<'_, u8>,
-    dst: AArch64GeneralReg,
-    src: AArch64GeneralReg,
-    imm12: u16,
-) {
-    let inst = ArithmeticImmediate::new(false, false, dst, src, imm12, false);
+    #[inline(always)]
+    fn new_float_store(imm12: u16, rn: AArch64GeneralReg, rt: AArch64FloatReg) -> Self {
+        Self::new(0b11, true, 0b00, imm12, rn, rt.id())
+    }
+}
 
-    buf.extend(inst.bytes());
+// Uses a signed offset that is not scaled by the size.
+// opc = 0b00 means store
+// opc = 0b01 means load (zero extending)
+// opc = 0b10 means load sign extending to 64 bits
+#[derive(PackedStruct, Debug)]
+#[packed_struct(endian = "msb")]
+pub struct LoadStoreRegisterUnscaledImmediate {
+    size: Integer<u8, packed_bits::Bits<2>>,
+    fixed: Integer<u8, packed_bits::Bits<3>>, // = 0b111,
+    v: bool,
+    fixed2: Integer<u8, packed_bits::Bits<2>>, // = 0b00,
+    opc: Integer<u8, packed_bits::Bits<2>>,
+    fixed3: bool, // = 0b0,
+    imm9: Integer<u16, packed_bits::Bits<9>>,
+    fixed4: Integer<u8, packed_bits::Bits<2>>, // = 0b00,
+    rn: Integer<u8, packed_bits::Bits<5>>,
+    rt: Integer<u8, packed_bits::Bits<5>>,
 }
 
-/// `ADD Xd, Xm, Xn` -> Add Xm and Xn and place the result into Xd.
-#[inline(always)]
-fn add_reg64_reg64_reg64(
-    buf: &mut Vec<'_, u8>,
-    dst: AArch64GeneralReg,
-    src1: AArch64GeneralReg,
-    src2: AArch64GeneralReg,
-) {
-    let inst = ArithmeticShifted::new(false, false, ShiftType::LSL, 0, src2, src1, dst);
+impl Aarch64Bytes for LoadStoreRegisterUnscaledImmediate {}
 
-    buf.extend(inst.bytes());
-}
+impl LoadStoreRegisterUnscaledImmediate {
+    #[inline(always)]
+    fn new(size: u8, v: bool, opc: u8, imm9: i16, rn: AArch64GeneralReg, rt: u8) -> Self {
+        debug_assert!(size <= 0b11);
+        debug_assert!((-256..256).contains(&imm9));
 
-/// `LDR Xt, [Xn, #offset]` -> Load Xn + Offset Xt. ZRSP is SP.
-/// Note: imm12 is the offest divided by 8.
-#[inline(always)]
-fn ldr_reg64_reg64_imm12(
-    buf: &mut Vec<'_, u8>,
-    dst: AArch64GeneralReg,
-    base: AArch64GeneralReg,
-    imm12: u16,
-) {
-    let inst = LoadStoreRegisterImmediate::new_load(0b11, imm12, base, dst);
+        Self {
+            rt: rt.into(),
+            rn: rn.id().into(),
+            fixed4: 0b00.into(),
+            imm9: ((imm9 as u16) & 0x1FF).into(),
+            fixed3: false,
+            opc: opc.into(),
+            fixed2: 0b00.into(),
+            v,
+            fixed: 0b111.into(),
+            size: size.into(),
+        }
+    }
+}
 
-    buf.extend(inst.bytes());
+// Pushes and pops pairs of registers.
+// index = 0b001 means post-index, 0b011 means pre-index.
+#[derive(PackedStruct, Debug)]
+#[packed_struct(endian = "msb")]
+pub struct LoadStoreRegisterPair {
+    opc: Integer<u8, packed_bits::Bits<2>>,
+    fixed: Integer<u8, packed_bits::Bits<3>>, // = 0b101,
+    v: bool,
+    index: Integer<u8, packed_bits::Bits<3>>,
+    l: bool, // load
+    imm7: Integer<u8, packed_bits::Bits<7>>,
+    rt2: Integer<u8, packed_bits::Bits<5>>,
+    rn: Integer<u8, packed_bits::Bits<5>>,
+    rt: Integer<u8, packed_bits::Bits<5>>,
 }
 
-/// `MOV Xd, Xm` -> Move Xm to Xd.
-#[inline(always)]
-fn mov_reg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64GeneralReg) {
-    // MOV is equvalent to `ORR Xd, XZR, XM` in AARCH64.
-    let inst = LogicalShiftedRegister::new(
-        LogicalOp::ORR,
-        ShiftType::LSL,
-        0,
-        src,
-        AArch64GeneralReg::ZRSP,
-        dst,
-    );
+impl Aarch64Bytes for LoadStoreRegisterPair {}
 
-    buf.extend(inst.bytes());
+impl LoadStoreRegisterPair {
+    #[inline(always)]
+    fn new(
+        index: u8,
+        l: bool,
+        imm7: i8,
+        rt2: AArch64GeneralReg,
+        rn: AArch64GeneralReg,
+        rt: AArch64GeneralReg,
+    ) -> Self {
+        debug_assert!((-64..64).contains(&imm7));
+
+        Self {
+            rt: rt.id().into(),
+            rn: rn.id().into(),
+            rt2: rt2.id().into(),
+            imm7: ((imm7 as u8) & 0x7F).into(),
+            l,
+            index: index.into(),
+            v: false,
+            fixed: 0b101.into(),
+            // 64 bit registers
+            opc: 0b10.into(),
+        }
+    }
 }
 
-/// `MOVK Xd, imm16` -> Keeps Xd and moves an optionally shifted imm16 to Xd.
-#[inline(always)]
-fn movk_reg64_imm16(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, imm16: u16, hw: u8) {
-    let inst = MoveWideImmediate::new(0b11, dst, imm16, hw, true);
+#[derive(PackedStruct, Debug)]
+#[packed_struct(endian = "msb")]
+pub struct ArithmeticExtended {
+    sf: bool,
+    op: bool, // add or subtract
+    s: bool,
+    fixed: Integer<u8, packed_bits::Bits<5>>, // = 0b01011,
+    opt: Integer<u8, packed_bits::Bits<2>>,   // = 0b00,
+    fixed2: bool,                             // = 0b1,
+    reg_m: Integer<u8, packed_bits::Bits<5>>,
+    option: Integer<u8, packed_bits::Bits<3>>,
+    imm3: Integer<u8, packed_bits::Bits<3>>,
+    reg_n: Integer<u8, packed_bits::Bits<5>>,
+    reg_d: Integer<u8, packed_bits::Bits<5>>,
+}
 
-    buf.extend(inst.bytes());
+impl Aarch64Bytes for ArithmeticExtended {}
+
+impl ArithmeticExtended {
+    #[inline(always)]
+    fn new(
+        op: bool,
+        option: u8,
+        rm: AArch64GeneralReg,
+        rn: AArch64GeneralReg,
+        rd: AArch64GeneralReg,
+    ) -> Self {
+        debug_assert!(option <= 0b111);
+
+        Self {
+            reg_d: rd.id().into(),
+            reg_n: rn.id().into(),
+            imm3: 0.into(),
+            option: option.into(),
+            reg_m: rm.id().into(),
+            fixed2: true,
+            opt: 0b00.into(),
+            fixed: 0b01011.into(),
+            s: false,
+            op,
+            sf: true,
+        }
+    }
 }
 
-/// `MOVZ Xd, imm16` -> Zeros Xd and moves an optionally shifted imm16 to Xd.
-#[inline(always)]
-fn movz_reg64_imm16(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, imm16: u16, hw: u8) {
-    let inst = MoveWideImmediate::new(0b10, dst, imm16, hw, true);
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+enum ConditionCode {
+    EQ = 0,
+    NE = 1,
+    HS = 2,
+    LO = 3,
+    MI = 4,
+    PL = 5,
+    VS = 6,
+    VC = 7,
+    HI = 8,
+    LS = 9,
+    GE = 10,
+    LT = 11,
+    GT = 12,
+    LE = 13,
+    AL = 14,
+}
 
-    buf.extend(inst.bytes());
+impl ConditionCode {
+    #[inline(always)]
+    fn id(&self) -> u8 {
+        *self as u8
+    }
+
+    /// The condition that holds exactly when this one does not.
+    #[inline(always)]
+    fn invert(&self) -> u8 {
+        debug_assert!(!matches!(self, ConditionCode::AL));
+        self.id() ^ 1
+    }
 }
 
-/// `STR Xt, [Xn, #offset]` -> Store Xt to Xn + Offset. ZRSP is SP.
-/// Note: imm12 is the offest divided by 8.
-#[inline(always)]
-fn str_reg64_reg64_imm12(
-    buf: &mut Vec<'_, u8>,
-    src: AArch64GeneralReg,
-    base: AArch64GeneralReg,
-    imm12: u16,
-) {
-    let inst = LoadStoreRegisterImmediate::new_store(0b11, imm12, base, src);
+#[derive(PackedStruct, Debug)]
+#[packed_struct(endian = "msb")]
+pub struct ConditionalSelect {
+    sf: bool,
+    op: bool,
+    s: bool,
+    fixed: Integer<u8, packed_bits::Bits<8>>, // = 0b11010100,
+    reg_m: Integer<u8, packed_bits::Bits<5>>,
+    cond: Integer<u8, packed_bits::Bits<4>>,
+    fixed2: bool, // = 0b0,
+    o2: bool,
+    reg_n: Integer<u8, packed_bits::Bits<5>>,
+    reg_d: Integer<u8, packed_bits::Bits<5>>,
+}
 
-    buf.extend(inst.bytes());
+impl Aarch64Bytes for ConditionalSelect {}
+
+impl ConditionalSelect {
+    #[inline(always)]
+    fn new(
+        op: bool,
+        o2: bool,
+        cond: u8,
+        rm: AArch64GeneralReg,
+        rn: AArch64GeneralReg,
+        rd: AArch64GeneralReg,
+    ) -> Self {
+        debug_assert!(cond <= 0b1111);
+
+        Self {
+            reg_d: rd.id().into(),
+            reg_n: rn.id().into(),
+            o2,
+            fixed2: false,
+            cond: cond.into(),
+            reg_m: rm.id().into(),
+            fixed: 0b11010100.into(),
+            s: false,
+            op,
+            sf: true,
+        }
+    }
 }
 
-/// `SUB Xd, Xn, imm12` -> Subtract Xn and imm12 and place the result into Xd.
-#[inline(always)]
-fn sub_reg64_reg64_imm12(
-    buf: &mut Vec<'_, u8>,
-    dst: AArch64GeneralReg,
-    src: AArch64GeneralReg,
-    imm12: u16,
-) {
-    let inst = ArithmeticImmediate::new(true, false, dst, src, imm12, false);
+#[derive(PackedStruct, Debug)]
+#[packed_struct(endian = "msb")]
+pub struct DataProcessingTwoSource {
+    sf: bool,
+    fixed: bool, // = 0b0,
+    s: bool,
+    fixed2: Integer<u8, packed_bits::Bits<8>>, // = 0b11010110,
+    reg_m: Integer<u8, packed_bits::Bits<5>>,
+    opcode: Integer<u8, packed_bits::Bits<6>>,
+    reg_n: Integer<u8, packed_bits::Bits<5>>,
+    reg_d: Integer<u8, packed_bits::Bits<5>>,
+}
 
-    buf.extend(inst.bytes());
+impl Aarch64Bytes for DataProcessingTwoSource {}
+
+impl DataProcessingTwoSource {
+    #[inline(always)]
+    fn new(
+        opcode: u8,
+        rm: AArch64GeneralReg,
+        rn: AArch64GeneralReg,
+        rd: AArch64GeneralReg,
+    ) -> Self {
+        debug_assert!(opcode <= 0b111111);
+
+        Self {
+            reg_d: rd.id().into(),
+            reg_n: rn.id().into(),
+            opcode: opcode.into(),
+            reg_m: rm.id().into(),
+            fixed2: 0b11010110.into(),
+            s: false,
+            fixed: false,
+            sf: true,
+        }
+    }
 }
 
-/// `RET Xn` -> Return to the address stored in Xn.
-#[inline(always)]
-fn ret_reg64(buf: &mut Vec<'_, u8>, xn: AArch64GeneralReg) {
-    let inst = UnconditionalBranchRegister::new(0b10, xn);
+#[derive(PackedStruct, Debug)]
+#[packed_struct(endian = "msb")]
+pub struct DataProcessingThreeSource {
+    sf: bool,
+    op54: Integer<u8, packed_bits::Bits<2>>,
+    fixed: Integer<u8, packed_bits::Bits<5>>, // = 0b11011,
+    op31: Integer<u8, packed_bits::Bits<3>>,
+    reg_m: Integer<u8, packed_bits::Bits<5>>,
+    o0: bool,
+    reg_a: Integer<u8, packed_bits::Bits<5>>,
+    reg_n: Integer<u8, packed_bits::Bits<5>>,
+    reg_d: Integer<u8, packed_bits::Bits<5>>,
+}
 
-    buf.extend(inst.bytes());
+impl Aarch64Bytes for DataProcessingThreeSource {}
+
+impl DataProcessingThreeSource {
+    #[inline(always)]
+    fn new(
+        op31: u8,
+        o0: bool,
+        rm: AArch64GeneralReg,
+        ra: AArch64GeneralReg,
+        rn: AArch64GeneralReg,
+        rd: AArch64GeneralReg,
+    ) -> Self {
+        debug_assert!(op31 <= 0b111);
+
+        Self {
+            reg_d: rd.id().into(),
+            reg_n: rn.id().into(),
+            reg_a: ra.id().into(),
+            o0,
+            reg_m: rm.id().into(),
+            op31: op31.into(),
+            fixed: 0b11011.into(),
+            op54: 0b00.into(),
+            sf: true,
+        }
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::disassembler_test;
-    use capstone::prelude::*;
+#[derive(PackedStruct, Debug)]
+#[packed_struct(endian = "msb")]
+pub struct UnconditionalBranchImmediate {
+    op: bool,                                 // with link
+    fixed: Integer<u8, packed_bits::Bits<5>>, // = 0b00101,
+    imm26: Integer<u32, packed_bits::Bits<26>>,
+}
+
+impl Aarch64Bytes for UnconditionalBranchImmediate {}
+
+impl UnconditionalBranchImmediate {
+    #[inline(always)]
+    fn new(op: bool, offset: i32) -> Self {
+        debug_assert!(offset % 4 == 0);
+
+        Self {
+            // Out of range offsets are only ever placeholders that get overwritten.
+            imm26: (((offset >> 2) as u32) & 0x3FF_FFFF).into(),
+            fixed: 0b00101.into(),
+            op,
+        }
+    }
+}
+
+#[derive(PackedStruct, Debug)]
+#[packed_struct(endian = "msb")]
+pub struct ConditionalBranchImmediate {
+    fixed: Integer<u8, packed_bits::Bits<7>>, // = 0b0101010,
+    o1: bool,
+    imm19: Integer<u32, packed_bits::Bits<19>>,
+    o0: bool,
+    cond: Integer<u8, packed_bits::Bits<4>>,
+}
+
+impl Aarch64Bytes for ConditionalBranchImmediate {}
+
+impl ConditionalBranchImmediate {
+    #[inline(always)]
+    fn new(cond: u8, offset: i32) -> Self {
+        debug_assert!(offset % 4 == 0);
+        if !(-(1 << 20)..(1 << 20)).contains(&offset) {
+            internal_error!("Conditional branch offset out of range: {}", offset);
+        }
+
+        Self {
+            cond: cond.into(),
+            o0: false,
+            imm19: (((offset >> 2) as u32) & 0x7_FFFF).into(),
+            o1: false,
+            fixed: 0b0101010.into(),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum FloatType {
+    Single = 0b00,
+    Double = 0b01,
+}
+
+impl FloatType {
+    #[inline(always)]
+    fn id(&self) -> u8 {
+        *self as u8
+    }
+}
+
+#[derive(PackedStruct, Debug)]
+#[packed_struct(endian = "msb")]
+pub struct FloatingPointDataProcessingOneSource {
+    m: bool,
+    fixed: bool,
+    s: bool,
+    fixed2: Integer<u8, packed_bits::Bits<5>>, // = 0b11110,
+    ptype: Integer<u8, packed_bits::Bits<2>>,
+    fixed3: bool, // = 0b1,
+    opcode: Integer<u8, packed_bits::Bits<6>>,
+    fixed4: Integer<u8, packed_bits::Bits<5>>, // = 0b10000,
+    reg_n: Integer<u8, packed_bits::Bits<5>>,
+    reg_d: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for FloatingPointDataProcessingOneSource {}
+
+impl FloatingPointDataProcessingOneSource {
+    #[inline(always)]
+    fn new(ptype: FloatType, opcode: u8, rn: AArch64FloatReg, rd: AArch64FloatReg) -> Self {
+        debug_assert!(opcode <= 0b111111);
+
+        Self {
+            reg_d: rd.id().into(),
+            reg_n: rn.id().into(),
+            fixed4: 0b10000.into(),
+            opcode: opcode.into(),
+            fixed3: true,
+            ptype: ptype.id().into(),
+            fixed2: 0b11110.into(),
+            s: false,
+            fixed: false,
+            m: false,
+        }
+    }
+}
+
+#[derive(PackedStruct, Debug)]
+#[packed_struct(endian = "msb")]
+pub struct FloatingPointDataProcessingTwoSource {
+    m: bool,
+    fixed: bool,
+    s: bool,
+    fixed2: Integer<u8, packed_bits::Bits<5>>, // = 0b11110,
+    ptype: Integer<u8, packed_bits::Bits<2>>,
+    fixed3: bool, // = 0b1,
+    reg_m: Integer<u8, packed_bits::Bits<5>>,
+    opcode: Integer<u8, packed_bits::Bits<4>>,
+    fixed4: Integer<u8, packed_bits::Bits<2>>, // = 0b10,
+    reg_n: Integer<u8, packed_bits::Bits<5>>,
+    reg_d: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for FloatingPointDataProcessingTwoSource {}
+
+impl FloatingPointDataProcessingTwoSource {
+    #[inline(always)]
+    fn new(
+        ptype: FloatType,
+        opcode: u8,
+        rm: AArch64FloatReg,
+        rn: AArch64FloatReg,
+        rd: AArch64FloatReg,
+    ) -> Self {
+        debug_assert!(opcode <= 0b1111);
+
+        Self {
+            reg_d: rd.id().into(),
+            reg_n: rn.id().into(),
+            fixed4: 0b10.into(),
+            opcode: opcode.into(),
+            reg_m: rm.id().into(),
+            fixed3: true,
+            ptype: ptype.id().into(),
+            fixed2: 0b11110.into(),
+            s: false,
+            fixed: false,
+            m: false,
+        }
+    }
+}
+
+#[derive(PackedStruct, Debug)]
+#[packed_struct(endian = "msb")]
+pub struct ConversionBetweenFloatingPointAndInteger {
+    sf: bool,
+    fixed: bool,
+    s: bool,
+    fixed2: Integer<u8, packed_bits::Bits<5>>, // = 0b11110,
+    ptype: Integer<u8, packed_bits::Bits<2>>,
+    fixed3: bool, // = 0b1,
+    rmode: Integer<u8, packed_bits::Bits<2>>,
+    opcode: Integer<u8, packed_bits::Bits<3>>,
+    fixed4: Integer<u8, packed_bits::Bits<6>>, // = 0b000000,
+    reg_n: Integer<u8, packed_bits::Bits<5>>,
+    reg_d: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for ConversionBetweenFloatingPointAndInteger {}
+
+impl ConversionBetweenFloatingPointAndInteger {
+    #[inline(always)]
+    fn new(
+        sf: bool,
+        ptype: FloatType,
+        opcode: u8,
+        rn: AArch64GeneralReg,
+        rd: AArch64FloatReg,
+    ) -> Self {
+        debug_assert!(opcode <= 0b111);
+
+        Self {
+            reg_d: rd.id().into(),
+            reg_n: rn.id().into(),
+            fixed4: 0b000000.into(),
+            opcode: opcode.into(),
+            rmode: 0b00.into(),
+            fixed3: true,
+            ptype: ptype.id().into(),
+            fixed2: 0b11110.into(),
+            s: false,
+            fixed: false,
+            sf,
+        }
+    }
+}
+// Below here are the functions for all of the assembly instructions.
+// Their names are based on the instruction and operators combined.
+// You should call `buf.reserve()` if you push or extend more than once.
+// Unit tests are added at the bottom of the file to ensure correct asm generation.
+// Please keep these in alphanumeric order.
+
+/// `ADD Xd, Xn, imm12` -> Add Xn and imm12 and place the result into Xd.
+#[inline(always)]
+fn add_reg64_reg64_imm12(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src: AArch64GeneralReg,
+    imm12: u16,
+) {
+    let inst = ArithmeticImmediate::new(false, false, dst, src, imm12, false);
+
+    buf.extend(inst.bytes());
+}
+
+/// `ADD Xd, Xn, imm12, LSL 12` -> Add Xn and imm12 shifted left by 12 and place the result into Xd.
+#[inline(always)]
+fn add_reg64_reg64_imm12_lsl12(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src: AArch64GeneralReg,
+    imm12: u16,
+) {
+    let inst = ArithmeticImmediate::new(false, false, dst, src, imm12, true);
+
+    buf.extend(inst.bytes());
+}
+
+/// `ADD Xd, Xm, Xn` -> Add Xm and Xn and place the result into Xd.
+#[inline(always)]
+#[allow(dead_code)]
+fn add_reg64_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = ArithmeticShifted::new(false, false, ShiftType::LSL, 0, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `ADD Xd, Xn, Xm, UXTX` -> Add Xn and Xm and place the result into Xd. ZRSP is SP for Xd and Xn.
+#[inline(always)]
+fn add_reg64_reg64_reg64_uxtx(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = ArithmeticExtended::new(false, 0b011, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `ADDS Xd, Xm, Xn` -> Add Xm and Xn, place the result into Xd, and set the flags.
+#[inline(always)]
+fn adds_reg64_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = ArithmeticShifted::new(false, true, ShiftType::LSL, 0, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `AND Xd, Xn, Xm` -> Bitwise and Xn and Xm and place the result into Xd.
+#[inline(always)]
+fn and_reg64_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = LogicalShiftedRegister::new(LogicalOp::AND, ShiftType::LSL, 0, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `B imm26` -> Branch to the instruction offset bytes from this one.
+#[inline(always)]
+fn b_imm26(buf: &mut Vec<'_, u8>, offset: i32) {
+    let inst = UnconditionalBranchImmediate::new(false, offset);
+
+    buf.extend(inst.bytes());
+}
+
+/// `B.cond imm19` -> Branch to the instruction offset bytes from this one if the condition holds.
+#[inline(always)]
+fn b_cond_imm19(buf: &mut Vec<'_, u8>, cond: ConditionCode, offset: i32) {
+    let inst = ConditionalBranchImmediate::new(cond.id(), offset);
+
+    buf.extend(inst.bytes());
+}
+
+/// `BL imm26` -> Branch to the instruction offset bytes from this one and put the return address in LR.
+#[inline(always)]
+fn bl_imm26(buf: &mut Vec<'_, u8>, offset: i32) {
+    let inst = UnconditionalBranchImmediate::new(true, offset);
+
+    buf.extend(inst.bytes());
+}
+
+/// `CMP Xn, imm12` -> Compare Xn and imm12, setting the flags.
+#[inline(always)]
+fn cmp_reg64_imm12(buf: &mut Vec<'_, u8>, src: AArch64GeneralReg, imm12: u16) {
+    // CMP is equivalent to `SUBS XZR, Xn, imm12` in AARCH64.
+    let inst = ArithmeticImmediate::new(true, true, AArch64GeneralReg::ZRSP, src, imm12, false);
+
+    buf.extend(inst.bytes());
+}
+
+/// `CMP Xn, Xm` -> Compare Xn and Xm, setting the flags.
+#[inline(always)]
+fn cmp_reg64_reg64(buf: &mut Vec<'_, u8>, src1: AArch64GeneralReg, src2: AArch64GeneralReg) {
+    // CMP is equivalent to `SUBS XZR, Xn, Xm` in AARCH64.
+    let inst = ArithmeticShifted::new(
+        true,
+        true,
+        ShiftType::LSL,
+        0,
+        src2,
+        src1,
+        AArch64GeneralReg::ZRSP,
+    );
+
+    buf.extend(inst.bytes());
+}
+
+/// `CNEG Xd, Xn, cond` -> Place the negation of Xn into Xd if the condition holds, otherwise Xn.
+#[inline(always)]
+fn cneg_reg64_reg64_cond(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src: AArch64GeneralReg,
+    cond: ConditionCode,
+) {
+    // CNEG is equivalent to `CSNEG Xd, Xn, Xn, invert(cond)` in AARCH64.
+    let inst = ConditionalSelect::new(true, true, cond.invert(), src, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `CSET Xd, cond` -> Place 1 into Xd if the condition holds, otherwise 0.
+#[inline(always)]
+fn cset_reg64_cond(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, cond: ConditionCode) {
+    // CSET is equivalent to `CSINC Xd, XZR, XZR, invert(cond)` in AARCH64.
+    let inst = ConditionalSelect::new(
+        false,
+        true,
+        cond.invert(),
+        AArch64GeneralReg::ZRSP,
+        AArch64GeneralReg::ZRSP,
+        dst,
+    );
+
+    buf.extend(inst.bytes());
+}
+
+/// `EOR Xd, Xn, Xm` -> Bitwise exclusive or Xn and Xm and place the result into Xd.
+#[inline(always)]
+fn eor_reg64_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = LogicalShiftedRegister::new(LogicalOp::EOR, ShiftType::LSL, 0, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FABS Dd, Dn` -> Place the absolute value of Dn into Dd.
+#[inline(always)]
+fn fabs_freg64_freg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+    let inst = FloatingPointDataProcessingOneSource::new(FloatType::Double, 0b000001, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FADD Sd, Sn, Sm` -> Add Sn and Sm and place the result into Sd.
+#[inline(always)]
+fn fadd_freg32_freg32_freg32(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    src1: AArch64FloatReg,
+    src2: AArch64FloatReg,
+) {
+    let inst =
+        FloatingPointDataProcessingTwoSource::new(FloatType::Single, 0b0010, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FADD Dd, Dn, Dm` -> Add Dn and Dm and place the result into Dd.
+#[inline(always)]
+fn fadd_freg64_freg64_freg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    src1: AArch64FloatReg,
+    src2: AArch64FloatReg,
+) {
+    let inst =
+        FloatingPointDataProcessingTwoSource::new(FloatType::Double, 0b0010, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FCVT Sd, Dn` -> Convert the double Dn to a single and place the result into Sd.
+#[inline(always)]
+fn fcvt_freg32_freg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+    let inst = FloatingPointDataProcessingOneSource::new(FloatType::Double, 0b000100, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FCVT Dd, Sn` -> Convert the single Sn to a double and place the result into Dd.
+#[inline(always)]
+fn fcvt_freg64_freg32(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+    let inst = FloatingPointDataProcessingOneSource::new(FloatType::Single, 0b000101, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FDIV Sd, Sn, Sm` -> Divide Sn by Sm and place the result into Sd.
+#[inline(always)]
+fn fdiv_freg32_freg32_freg32(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    src1: AArch64FloatReg,
+    src2: AArch64FloatReg,
+) {
+    let inst =
+        FloatingPointDataProcessingTwoSource::new(FloatType::Single, 0b0001, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FDIV Dd, Dn, Dm` -> Divide Dn by Dm and place the result into Dd.
+#[inline(always)]
+fn fdiv_freg64_freg64_freg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    src1: AArch64FloatReg,
+    src2: AArch64FloatReg,
+) {
+    let inst =
+        FloatingPointDataProcessingTwoSource::new(FloatType::Double, 0b0001, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FMOV Sd, Wn` -> Move the low 32 bits of Xn to Sd.
+#[inline(always)]
+fn fmov_freg32_reg32(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64GeneralReg) {
+    let inst =
+        ConversionBetweenFloatingPointAndInteger::new(false, FloatType::Single, 0b111, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FMOV Dd, Dn` -> Move Dn to Dd.
+#[inline(always)]
+fn fmov_freg64_freg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+    let inst = FloatingPointDataProcessingOneSource::new(FloatType::Double, 0b000000, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FMOV Dd, Xn` -> Move the bits of Xn to Dd.
+#[inline(always)]
+fn fmov_freg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64GeneralReg) {
+    let inst =
+        ConversionBetweenFloatingPointAndInteger::new(true, FloatType::Double, 0b111, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FMUL Sd, Sn, Sm` -> Multiply Sn and Sm and place the result into Sd.
+#[inline(always)]
+fn fmul_freg32_freg32_freg32(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    src1: AArch64FloatReg,
+    src2: AArch64FloatReg,
+) {
+    let inst =
+        FloatingPointDataProcessingTwoSource::new(FloatType::Single, 0b0000, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FMUL Dd, Dn, Dm` -> Multiply Dn and Dm and place the result into Dd.
+#[inline(always)]
+fn fmul_freg64_freg64_freg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    src1: AArch64FloatReg,
+    src2: AArch64FloatReg,
+) {
+    let inst =
+        FloatingPointDataProcessingTwoSource::new(FloatType::Double, 0b0000, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDP Xt1, Xt2, [Xn], #offset` -> Load Xt1 and Xt2 from Xn, then add offset to Xn. ZRSP is SP.
+/// Note: imm7 is the offset divided by 8.
+#[inline(always)]
+fn ldp_reg64_reg64_reg64_postindex(
+    buf: &mut Vec<'_, u8>,
+    dst1: AArch64GeneralReg,
+    dst2: AArch64GeneralReg,
+    base: AArch64GeneralReg,
+    imm7: i8,
+) {
+    let inst = LoadStoreRegisterPair::new(0b001, true, imm7, dst2, base, dst1);
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDR Dt, [Xn, #offset]` -> Load Xn + Offset Dt. ZRSP is SP.
+/// Note: imm12 is the offest divided by 8.
+#[inline(always)]
+fn ldr_freg64_reg64_imm12(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    base: AArch64GeneralReg,
+    imm12: u16,
+) {
+    let inst = LoadStoreRegisterImmediate::new_float_load(imm12, base, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDR Xt, [Xn, #offset]` -> Load Xn + Offset Xt. ZRSP is SP.
+/// Note: imm12 is the offest divided by 8.
+#[inline(always)]
+fn ldr_reg64_reg64_imm12(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    base: AArch64GeneralReg,
+    imm12: u16,
+) {
+    let inst = LoadStoreRegisterImmediate::new_load(0b11, imm12, base, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDUR Dt, [Xn, #offset]` -> Load Xn + Offset Dt. ZRSP is SP.
+#[inline(always)]
+fn ldur_freg64_reg64_imm9(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    base: AArch64GeneralReg,
+    imm9: i16,
+) {
+    let inst = LoadStoreRegisterUnscaledImmediate::new(0b11, true, 0b01, imm9, base, dst.id());
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDUR Xt, [Xn, #offset]` -> Load Xn + Offset Xt. ZRSP is SP.
+#[inline(always)]
+fn ldur_reg64_reg64_imm9(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    base: AArch64GeneralReg,
+    imm9: i16,
+) {
+    let inst = LoadStoreRegisterUnscaledImmediate::new(0b11, false, 0b01, imm9, base, dst.id());
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDURSB/LDURSH/LDURSW Xt, [Xn, #offset]` -> Load `size` bytes from Xn + Offset and sign extend them into Xt. ZRSP is SP.
+#[inline(always)]
+fn ldursx_reg64_reg64_imm9(
+    buf: &mut Vec<'_, u8>,
+    size: u8,
+    dst: AArch64GeneralReg,
+    base: AArch64GeneralReg,
+    imm9: i16,
+) {
+    let inst =
+        LoadStoreRegisterUnscaledImmediate::new(log2_size(size), false, 0b10, imm9, base, dst.id());
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDURB/LDURH/LDUR Wt, [Xn, #offset]` -> Load `size` bytes from Xn + Offset and zero extend them into Xt. ZRSP is SP.
+#[inline(always)]
+fn ldurzx_reg64_reg64_imm9(
+    buf: &mut Vec<'_, u8>,
+    size: u8,
+    dst: AArch64GeneralReg,
+    base: AArch64GeneralReg,
+    imm9: i16,
+) {
+    let inst =
+        LoadStoreRegisterUnscaledImmediate::new(log2_size(size), false, 0b01, imm9, base, dst.id());
+
+    buf.extend(inst.bytes());
+}
+
+/// The size field of loads and stores is the log2 of the number of bytes.
+#[inline(always)]
+fn log2_size(size: u8) -> u8 {
+    match size {
+        1 => 0b00,
+        2 => 0b01,
+        4 => 0b10,
+        8 => 0b11,
+        _ => internal_error!("Invalid size for a load or store: {}", size),
+    }
+}
+
+/// `MOV Xd, Xm` -> Move Xm to Xd.
+#[inline(always)]
+fn mov_reg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64GeneralReg) {
+    // MOV is equvalent to `ORR Xd, XZR, XM` in AARCH64.
+    let inst = LogicalShiftedRegister::new(
+        LogicalOp::ORR,
+        ShiftType::LSL,
+        0,
+        src,
+        AArch64GeneralReg::ZRSP,
+        dst,
+    );
+
+    buf.extend(inst.bytes());
+}
+
+/// `MOVK Xd, imm16` -> Keeps Xd and moves an optionally shifted imm16 to Xd.
+#[inline(always)]
+fn movk_reg64_imm16(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, imm16: u16, hw: u8) {
+    let inst = MoveWideImmediate::new(0b11, dst, imm16, hw, true);
+
+    buf.extend(inst.bytes());
+}
+
+/// `MOVZ Xd, imm16` -> Zeros Xd and moves an optionally shifted imm16 to Xd.
+#[inline(always)]
+fn movz_reg64_imm16(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, imm16: u16, hw: u8) {
+    let inst = MoveWideImmediate::new(0b10, dst, imm16, hw, true);
+
+    buf.extend(inst.bytes());
+}
+
+/// `MUL Xd, Xn, Xm` -> Multiply Xn and Xm and place the low 64 bits of the result into Xd.
+#[inline(always)]
+fn mul_reg64_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    // MUL is equivalent to `MADD Xd, Xn, Xm, XZR` in AARCH64.
+    let inst =
+        DataProcessingThreeSource::new(0b000, false, src2, AArch64GeneralReg::ZRSP, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `NEG Xd, Xm` -> Place the negation of Xm into Xd.
+#[inline(always)]
+fn neg_reg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64GeneralReg) {
+    // NEG is equivalent to `SUB Xd, XZR, Xm` in AARCH64.
+    let inst = ArithmeticShifted::new(
+        true,
+        false,
+        ShiftType::LSL,
+        0,
+        src,
+        AArch64GeneralReg::ZRSP,
+        dst,
+    );
+
+    buf.extend(inst.bytes());
+}
+
+/// `ORR Xd, Xn, Xm` -> Bitwise or Xn and Xm and place the result into Xd.
+#[inline(always)]
+fn orr_reg64_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = LogicalShiftedRegister::new(LogicalOp::ORR, ShiftType::LSL, 0, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `SCVTF Sd, Xn` -> Convert the signed integer Xn to a single and place the result into Sd.
+#[inline(always)]
+fn scvtf_freg32_reg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64GeneralReg) {
+    let inst =
+        ConversionBetweenFloatingPointAndInteger::new(true, FloatType::Single, 0b010, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `SCVTF Dd, Xn` -> Convert the signed integer Xn to a double and place the result into Dd.
+#[inline(always)]
+fn scvtf_freg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64GeneralReg) {
+    let inst =
+        ConversionBetweenFloatingPointAndInteger::new(true, FloatType::Double, 0b010, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `SDIV Xd, Xn, Xm` -> Divide the signed Xn by Xm and place the result into Xd.
+#[inline(always)]
+fn sdiv_reg64_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = DataProcessingTwoSource::new(0b000011, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `STP Xt1, Xt2, [Xn, #offset]!` -> Add offset to Xn, then store Xt1 and Xt2 to Xn. ZRSP is SP.
+/// Note: imm7 is the offset divided by 8.
+#[inline(always)]
+fn stp_reg64_reg64_reg64_preindex(
+    buf: &mut Vec<'_, u8>,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+    base: AArch64GeneralReg,
+    imm7: i8,
+) {
+    let inst = LoadStoreRegisterPair::new(0b011, false, imm7, src2, base, src1);
+
+    buf.extend(inst.bytes());
+}
+
+/// `STR Dt, [Xn, #offset]` -> Store Dt to Xn + Offset. ZRSP is SP.
+/// Note: imm12 is the offest divided by 8.
+#[inline(always)]
+fn str_freg64_reg64_imm12(
+    buf: &mut Vec<'_, u8>,
+    src: AArch64FloatReg,
+    base: AArch64GeneralReg,
+    imm12: u16,
+) {
+    let inst = LoadStoreRegisterImmediate::new_float_store(imm12, base, src);
+
+    buf.extend(inst.bytes());
+}
+
+/// `STR Xt, [Xn, #offset]` -> Store Xt to Xn + Offset. ZRSP is SP.
+/// Note: imm12 is the offest divided by 8.
+#[inline(always)]
+fn str_reg64_reg64_imm12(
+    buf: &mut Vec<'_, u8>,
+    src: AArch64GeneralReg,
+    base: AArch64GeneralReg,
+    imm12: u16,
+) {
+    let inst = LoadStoreRegisterImmediate::new_store(0b11, imm12, base, src);
+
+    buf.extend(inst.bytes());
+}
+
+/// `STUR Dt, [Xn, #offset]` -> Store Dt to Xn + Offset. ZRSP is SP.
+#[inline(always)]
+fn stur_freg64_reg64_imm9(
+    buf: &mut Vec<'_, u8>,
+    src: AArch64FloatReg,
+    base: AArch64GeneralReg,
+    imm9: i16,
+) {
+    let inst = LoadStoreRegisterUnscaledImmediate::new(0b11, true, 0b00, imm9, base, src.id());
+
+    buf.extend(inst.bytes());
+}
+
+/// `STUR Xt, [Xn, #offset]` -> Store Xt to Xn + Offset. ZRSP is SP.
+#[inline(always)]
+fn stur_reg64_reg64_imm9(
+    buf: &mut Vec<'_, u8>,
+    src: AArch64GeneralReg,
+    base: AArch64GeneralReg,
+    imm9: i16,
+) {
+    let inst = LoadStoreRegisterUnscaledImmediate::new(0b11, false, 0b00, imm9, base, src.id());
+
+    buf.extend(inst.bytes());
+}
+
+/// `SUB Xd, Xn, imm12` -> Subtract Xn and imm12 and place the result into Xd.
+#[inline(always)]
+fn sub_reg64_reg64_imm12(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src: AArch64GeneralReg,
+    imm12: u16,
+) {
+    let inst = ArithmeticImmediate::new(true, false, dst, src, imm12, false);
+
+    buf.extend(inst.bytes());
+}
+
+/// `SUB Xd, Xn, imm12, LSL 12` -> Subtract Xn and imm12 shifted left by 12 and place the result into Xd.
+#[inline(always)]
+fn sub_reg64_reg64_imm12_lsl12(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src: AArch64GeneralReg,
+    imm12: u16,
+) {
+    let inst = ArithmeticImmediate::new(true, false, dst, src, imm12, true);
+
+    buf.extend(inst.bytes());
+}
+
+/// `SUB Xd, Xn, Xm` -> Subtract Xm from Xn and place the result into Xd.
+#[inline(always)]
+fn sub_reg64_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = ArithmeticShifted::new(true, false, ShiftType::LSL, 0, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `RET Xn` -> Return to the address stored in Xn.
+#[inline(always)]
+fn ret_reg64(buf: &mut Vec<'_, u8>, xn: AArch64GeneralReg) {
+    let inst = UnconditionalBranchRegister::new(0b10, xn);
+
+    buf.extend(inst.bytes());
+}
+
+/// `UDIV Xd, Xn, Xm` -> Divide the unsigned Xn by Xm and place the result into Xd.
+#[inline(always)]
+fn udiv_reg64_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = DataProcessingTwoSource::new(0b000010, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disassembler_test;
+    use capstone::prelude::*;
+
+    enum ZRSPKind {
+        UsesZR,
+        UsesSP,
+    }
+    use ZRSPKind::*;
+
+    impl AArch64GeneralReg {
+        fn capstone_string(&self, zrsp_kind: ZRSPKind) -> String {
+            match self {
+                AArch64GeneralReg::XR => "x8".to_owned(),
+                AArch64GeneralReg::IP0 => "x16".to_owned(),
+                AArch64GeneralReg::IP1 => "x17".to_owned(),
+                AArch64GeneralReg::PR => "x18".to_owned(),
+                AArch64GeneralReg::FP => "x29".to_owned(),
+                AArch64GeneralReg::LR => "x30".to_owned(),
+                AArch64GeneralReg::ZRSP => match zrsp_kind {
+                    UsesZR => "xzr".to_owned(),
+                    UsesSP => "sp".to_owned(),
+                },
+                _ => format!("{}", self),
+            }
+        }
+    }
+
+    impl AArch64FloatReg {
+        fn capstone_string(&self, float_type: FloatType) -> String {
+            match float_type {
+                FloatType::Single => format!("s{}", self.id()),
+                FloatType::Double => format!("d{}", self.id()),
+            }
+        }
+    }
+
+    impl ConditionCode {
+        fn capstone_string(&self) -> &'static str {
+            match self {
+                ConditionCode::EQ => "eq",
+                ConditionCode::NE => "ne",
+                ConditionCode::HS => "hs",
+                ConditionCode::LO => "lo",
+                ConditionCode::MI => "mi",
+                ConditionCode::PL => "pl",
+                ConditionCode::VS => "vs",
+                ConditionCode::VC => "vc",
+                ConditionCode::HI => "hi",
+                ConditionCode::LS => "ls",
+                ConditionCode::GE => "ge",
+                ConditionCode::LT => "lt",
+                ConditionCode::GT => "gt",
+                ConditionCode::LE => "le",
+                ConditionCode::AL => "al",
+            }
+        }
+    }
+
+    const TEST_U16: u16 = 0x1234;
+    //const TEST_I32: i32 = 0x12345678;
+    //const TEST_I64: i64 = 0x12345678_9ABCDEF0;
+
+    const ALL_GENERAL_REGS: &[AArch64GeneralReg] = &[
+        AArch64GeneralReg::X0,
+        AArch64GeneralReg::X1,
+        AArch64GeneralReg::X2,
+        AArch64GeneralReg::X3,
+        AArch64GeneralReg::X4,
+        AArch64GeneralReg::X5,
+        AArch64GeneralReg::X6,
+        AArch64GeneralReg::X7,
+        AArch64GeneralReg::XR,
+        AArch64GeneralReg::X9,
+        AArch64GeneralReg::X10,
+        AArch64GeneralReg::X11,
+        AArch64GeneralReg::X12,
+        AArch64GeneralReg::X13,
+        AArch64GeneralReg::X14,
+        AArch64GeneralReg::X15,
+        AArch64GeneralReg::IP0,
+        AArch64GeneralReg::IP1,
+        AArch64GeneralReg::PR,
+        AArch64GeneralReg::X19,
+        AArch64GeneralReg::X20,
+        AArch64GeneralReg::X21,
+        AArch64GeneralReg::X22,
+        AArch64GeneralReg::X23,
+        AArch64GeneralReg::X24,
+        AArch64GeneralReg::X25,
+        AArch64GeneralReg::X26,
+        AArch64GeneralReg::X27,
+        AArch64GeneralReg::X28,
+        AArch64GeneralReg::FP,
+        AArch64GeneralReg::LR,
+        AArch64GeneralReg::ZRSP,
+    ];
+
+    const TEST_IMM9S: &[i16] = &[-0x100, -0x10, 0x10, 0xFF];
+
+    const ALL_FLOAT_REGS: &[AArch64FloatReg] = &[
+        AArch64FloatReg::V0,
+        AArch64FloatReg::V1,
+        AArch64FloatReg::V2,
+        AArch64FloatReg::V3,
+        AArch64FloatReg::V4,
+        AArch64FloatReg::V5,
+        AArch64FloatReg::V6,
+        AArch64FloatReg::V7,
+        AArch64FloatReg::V8,
+        AArch64FloatReg::V9,
+        AArch64FloatReg::V10,
+        AArch64FloatReg::V11,
+        AArch64FloatReg::V12,
+        AArch64FloatReg::V13,
+        AArch64FloatReg::V14,
+        AArch64FloatReg::V15,
+        AArch64FloatReg::V16,
+        AArch64FloatReg::V17,
+        AArch64FloatReg::V18,
+        AArch64FloatReg::V19,
+        AArch64FloatReg::V20,
+        AArch64FloatReg::V21,
+        AArch64FloatReg::V22,
+        AArch64FloatReg::V23,
+        AArch64FloatReg::V24,
+        AArch64FloatReg::V25,
+        AArch64FloatReg::V26,
+        AArch64FloatReg::V27,
+        AArch64FloatReg::V28,
+        AArch64FloatReg::V29,
+        AArch64FloatReg::V30,
+        AArch64FloatReg::V31,
+    ];
+
+    // AL is left out since inverting it is meaningless for CSET and CNEG.
+    const ALL_CONDITIONS: &[ConditionCode] = &[
+        ConditionCode::EQ,
+        ConditionCode::NE,
+        ConditionCode::HS,
+        ConditionCode::LO,
+        ConditionCode::MI,
+        ConditionCode::PL,
+        ConditionCode::VS,
+        ConditionCode::VC,
+        ConditionCode::HI,
+        ConditionCode::LS,
+        ConditionCode::GE,
+        ConditionCode::LT,
+        ConditionCode::GT,
+        ConditionCode::LE,
+    ];
+
+    fn setup_capstone_and_arena<T>(
+        arena: &bumpalo::Bump,
+    ) -> (bumpalo::collections::Vec<T>, Capstone) {
+        let buf = bumpalo::vec![in arena];
+        let cs = Capstone::new()
+            .arm64()
+            .mode(arch::arm64::ArchMode::Arm)
+            .detail(true)
+            .build()
+            .expect("Failed to create Capstone object");
+        (buf, cs)
+    }
+
+    #[test]
+    fn test_add_reg64_reg64_reg64() {
+        disassembler_test!(
+            add_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| format!(
+                "add {}, {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR),
+                reg3.capstone_string(UsesZR)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_add_reg64_reg64_imm12() {
+        disassembler_test!(
+            add_reg64_reg64_imm12,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
+                "add {}, {}, #0x{:x}",
+                reg1.capstone_string(UsesSP),
+                reg2.capstone_string(UsesSP),
+                imm
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [0x123]
+        );
+    }
+
+    #[test]
+    fn test_ldr_reg64_reg64_imm12() {
+        disassembler_test!(
+            ldr_reg64_reg64_imm12,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
+                "ldr {}, [{}, #0x{:x}]",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesSP),
+                imm << 3
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [0x123]
+        );
+    }
+
+    #[test]
+    fn test_mov_reg64_reg64() {
+        disassembler_test!(
+            mov_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg| format!(
+                "mov {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR),
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_movk_reg64_imm16() {
+        disassembler_test!(
+            movk_reg64_imm16,
+            |reg1: AArch64GeneralReg, imm, hw| format!(
+                "movk {}, #0x{:x}{}",
+                reg1.capstone_string(UsesZR),
+                imm,
+                if hw > 0 {
+                    format!(", lsl #{}", hw * 16)
+                } else {
+                    "".to_owned()
+                }
+            ),
+            ALL_GENERAL_REGS,
+            [TEST_U16],
+            [0, 1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn test_movz_reg64_imm16() {
+        disassembler_test!(
+            movz_reg64_imm16,
+            |reg1: AArch64GeneralReg, imm, hw| format!(
+                "mov {}, #0x{:x}{}",
+                reg1.capstone_string(UsesZR),
+                imm,
+                "0000".repeat(hw as usize)
+            ),
+            ALL_GENERAL_REGS,
+            [TEST_U16],
+            [0, 1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn test_str_reg64_reg64_imm12() {
+        disassembler_test!(
+            str_reg64_reg64_imm12,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
+                "str {}, [{}, #0x{:x}]",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesSP),
+                imm << 3
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [0x123]
+        );
+    }
+
+    #[test]
+    fn test_sub_reg64_reg64_imm12() {
+        disassembler_test!(
+            sub_reg64_reg64_imm12,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
+                "sub {}, {}, #0x{:x}",
+                reg1.capstone_string(UsesSP),
+                reg2.capstone_string(UsesSP),
+                imm
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [0x123]
+        );
+    }
+
+    #[test]
+    fn test_add_reg64_reg64_imm12_lsl12() {
+        disassembler_test!(
+            add_reg64_reg64_imm12_lsl12,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
+                "add {}, {}, #0x{:x}, lsl #12",
+                reg1.capstone_string(UsesSP),
+                reg2.capstone_string(UsesSP),
+                imm
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [0x123]
+        );
+    }
+
+    #[test]
+    fn test_add_reg64_reg64_reg64_uxtx() {
+        disassembler_test!(
+            add_reg64_reg64_reg64_uxtx,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| {
+                if reg1 == AArch64GeneralReg::ZRSP || reg2 == AArch64GeneralReg::ZRSP {
+                    format!(
+                        "add {}, {}, {}",
+                        reg1.capstone_string(UsesSP),
+                        reg2.capstone_string(UsesSP),
+                        reg3.capstone_string(UsesZR)
+                    )
+                } else {
+                    format!(
+                        "add {}, {}, {}, uxtx",
+                        reg1.capstone_string(UsesSP),
+                        reg2.capstone_string(UsesSP),
+                        reg3.capstone_string(UsesZR)
+                    )
+                }
+            },
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_adds_reg64_reg64_reg64() {
+        disassembler_test!(
+            adds_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| {
+                if reg1 == AArch64GeneralReg::ZRSP {
+                    format!(
+                        "cmn {}, {}",
+                        reg2.capstone_string(UsesZR),
+                        reg3.capstone_string(UsesZR)
+                    )
+                } else {
+                    format!(
+                        "adds {}, {}, {}",
+                        reg1.capstone_string(UsesZR),
+                        reg2.capstone_string(UsesZR),
+                        reg3.capstone_string(UsesZR)
+                    )
+                }
+            },
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_and_reg64_reg64_reg64() {
+        disassembler_test!(
+            and_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| format!(
+                "and {}, {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR),
+                reg3.capstone_string(UsesZR)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_b_imm26() {
+        disassembler_test!(
+            b_imm26,
+            |offset: i32| format!("b #0x{:x}", offset),
+            [0x10, 0x120, 0x7FF_FFFC]
+        );
+    }
+
+    #[test]
+    fn test_b_cond_imm19() {
+        disassembler_test!(
+            b_cond_imm19,
+            |cond: ConditionCode, offset: i32| format!(
+                "b.{} #0x{:x}",
+                cond.capstone_string(),
+                offset
+            ),
+            ALL_CONDITIONS,
+            [0x10, 0x120, 0xF_FFFC]
+        );
+    }
+
+    #[test]
+    fn test_bl_imm26() {
+        disassembler_test!(
+            bl_imm26,
+            |offset: i32| format!("bl #0x{:x}", offset),
+            [0x10, 0x120, 0x7FF_FFFC]
+        );
+    }
+
+    #[test]
+    fn test_cmp_reg64_imm12() {
+        disassembler_test!(
+            cmp_reg64_imm12,
+            |reg1: AArch64GeneralReg, imm| format!(
+                "cmp {}, #0x{:x}",
+                reg1.capstone_string(UsesSP),
+                imm
+            ),
+            ALL_GENERAL_REGS,
+            [0x123]
+        );
+    }
+
+    #[test]
+    fn test_cmp_reg64_reg64() {
+        disassembler_test!(
+            cmp_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg| format!(
+                "cmp {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_cneg_reg64_reg64_cond() {
+        disassembler_test!(
+            cneg_reg64_reg64_cond,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, cond: ConditionCode| format!(
+                "cneg {}, {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR),
+                cond.capstone_string()
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_CONDITIONS
+        );
+    }
+
+    #[test]
+    fn test_cset_reg64_cond() {
+        disassembler_test!(
+            cset_reg64_cond,
+            |reg1: AArch64GeneralReg, cond: ConditionCode| format!(
+                "cset {}, {}",
+                reg1.capstone_string(UsesZR),
+                cond.capstone_string()
+            ),
+            ALL_GENERAL_REGS,
+            ALL_CONDITIONS
+        );
+    }
+
+    #[test]
+    fn test_eor_reg64_reg64_reg64() {
+        disassembler_test!(
+            eor_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| format!(
+                "eor {}, {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR),
+                reg3.capstone_string(UsesZR)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_fabs_freg64_freg64() {
+        disassembler_test!(
+            fabs_freg64_freg64,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg| format!(
+                "fabs {}, {}",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(FloatType::Double)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fadd_freg32_freg32_freg32() {
+        disassembler_test!(
+            fadd_freg32_freg32_freg32,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg, reg3: AArch64FloatReg| format!(
+                "fadd {}, {}, {}",
+                reg1.capstone_string(FloatType::Single),
+                reg2.capstone_string(FloatType::Single),
+                reg3.capstone_string(FloatType::Single)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fadd_freg64_freg64_freg64() {
+        disassembler_test!(
+            fadd_freg64_freg64_freg64,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg, reg3: AArch64FloatReg| format!(
+                "fadd {}, {}, {}",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(FloatType::Double),
+                reg3.capstone_string(FloatType::Double)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fcvt_freg32_freg64() {
+        disassembler_test!(
+            fcvt_freg32_freg64,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg| format!(
+                "fcvt {}, {}",
+                reg1.capstone_string(FloatType::Single),
+                reg2.capstone_string(FloatType::Double)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fcvt_freg64_freg32() {
+        disassembler_test!(
+            fcvt_freg64_freg32,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg| format!(
+                "fcvt {}, {}",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(FloatType::Single)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fdiv_freg32_freg32_freg32() {
+        disassembler_test!(
+            fdiv_freg32_freg32_freg32,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg, reg3: AArch64FloatReg| format!(
+                "fdiv {}, {}, {}",
+                reg1.capstone_string(FloatType::Single),
+                reg2.capstone_string(FloatType::Single),
+                reg3.capstone_string(FloatType::Single)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fdiv_freg64_freg64_freg64() {
+        disassembler_test!(
+            fdiv_freg64_freg64_freg64,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg, reg3: AArch64FloatReg| format!(
+                "fdiv {}, {}, {}",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(FloatType::Double),
+                reg3.capstone_string(FloatType::Double)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
 
-    enum ZRSPKind {
-        UsesZR,
-        UsesSP,
+    #[test]
+    fn test_fmov_freg32_reg32() {
+        disassembler_test!(
+            fmov_freg32_reg32,
+            |reg1: AArch64FloatReg, reg2: AArch64GeneralReg| format!(
+                "fmov {}, {}",
+                reg1.capstone_string(FloatType::Single),
+                reg2.capstone_string(UsesZR).replacen('x', "w", 1)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS
+        );
     }
-    use ZRSPKind::*;
 
-    impl AArch64GeneralReg {
-        fn capstone_string(&self, zrsp_kind: ZRSPKind) -> String {
-            match self {
-                AArch64GeneralReg::XR => "x8".to_owned(),
-                AArch64GeneralReg::IP0 => "x16".to_owned(),
-                AArch64GeneralReg::IP1 => "x17".to_owned(),
-                AArch64GeneralReg::PR => "x18".to_owned(),
-                AArch64GeneralReg::FP => "x29".to_owned(),
-                AArch64GeneralReg::LR => "x30".to_owned(),
-                AArch64GeneralReg::ZRSP => match zrsp_kind {
-                    UsesZR => "xzr".to_owned(),
-                    UsesSP => "sp".to_owned(),
-                },
-                _ => format!("{}", self),
-            }
-        }
+    #[test]
+    fn test_fmov_freg64_freg64() {
+        disassembler_test!(
+            fmov_freg64_freg64,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg| format!(
+                "fmov {}, {}",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(FloatType::Double)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
     }
 
-    const TEST_U16: u16 = 0x1234;
-    //const TEST_I32: i32 = 0x12345678;
-    //const TEST_I64: i64 = 0x12345678_9ABCDEF0;
+    #[test]
+    fn test_fmov_freg64_reg64() {
+        disassembler_test!(
+            fmov_freg64_reg64,
+            |reg1: AArch64FloatReg, reg2: AArch64GeneralReg| format!(
+                "fmov {}, {}",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(UsesZR)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
 
-    const ALL_GENERAL_REGS: &[AArch64GeneralReg] = &[
-        AArch64GeneralReg::X0,
-        AArch64GeneralReg::X1,
-        AArch64GeneralReg::X2,
-        AArch64GeneralReg::X3,
-        AArch64GeneralReg::X4,
-        AArch64GeneralReg::X5,
-        AArch64GeneralReg::X6,
-        AArch64GeneralReg::X7,
-        AArch64GeneralReg::XR,
-        AArch64GeneralReg::X9,
-        AArch64GeneralReg::X10,
-        AArch64GeneralReg::X11,
-        AArch64GeneralReg::X12,
-        AArch64GeneralReg::X13,
-        AArch64GeneralReg::X14,
-        AArch64GeneralReg::X15,
-        AArch64GeneralReg::IP0,
-        AArch64GeneralReg::IP1,
-        AArch64GeneralReg::PR,
-        AArch64GeneralReg::X19,
-        AArch64GeneralReg::X20,
-        AArch64GeneralReg::X21,
-        AArch64GeneralReg::X22,
-        AArch64GeneralReg::X23,
-        AArch64GeneralReg::X24,
-        AArch64GeneralReg::X25,
-        AArch64GeneralReg::X26,
-        AArch64GeneralReg::X27,
-        AArch64GeneralReg::X28,
-        AArch64GeneralReg::FP,
-        AArch64GeneralReg::LR,
-        AArch64GeneralReg::ZRSP,
-    ];
+    #[test]
+    fn test_fmul_freg32_freg32_freg32() {
+        disassembler_test!(
+            fmul_freg32_freg32_freg32,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg, reg3: AArch64FloatReg| format!(
+                "fmul {}, {}, {}",
+                reg1.capstone_string(FloatType::Single),
+                reg2.capstone_string(FloatType::Single),
+                reg3.capstone_string(FloatType::Single)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
 
-    fn setup_capstone_and_arena<T>(
-        arena: &bumpalo::Bump,
-    ) -> (bumpalo::collections::Vec<T>, Capstone) {
-        let buf = bumpalo::vec![in arena];
-        let cs = Capstone::new()
-            .arm64()
-            .mode(arch::arm64::ArchMode::Arm)
-            .detail(true)
-            .build()
-            .expect("Failed to create Capstone object");
-        (buf, cs)
+    #[test]
+    fn test_fmul_freg64_freg64_freg64() {
+        disassembler_test!(
+            fmul_freg64_freg64_freg64,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg, reg3: AArch64FloatReg| format!(
+                "fmul {}, {}, {}",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(FloatType::Double),
+                reg3.capstone_string(FloatType::Double)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
     }
 
     #[test]
-    fn test_add_reg64_reg64_reg64() {
+    fn test_ldp_reg64_reg64_reg64_postindex() {
+        // Pairs that overlap each other or the written back base are unpredictable,
+        // and capstone will not decode them.
         disassembler_test!(
-            add_reg64_reg64_reg64,
+            |buf, reg1, reg2, reg3| ldp_reg64_reg64_reg64_postindex(buf, reg1, reg2, reg3, 2),
             |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| format!(
-                "add {}, {}, {}",
+                "ldp {}, {}, [{}], #0x10",
                 reg1.capstone_string(UsesZR),
                 reg2.capstone_string(UsesZR),
-                reg3.capstone_string(UsesZR)
+                reg3.capstone_string(UsesSP)
+            ),
+            &ALL_GENERAL_REGS[..15],
+            &ALL_GENERAL_REGS[15..],
+            [AArch64GeneralReg::ZRSP]
+        );
+    }
+
+    #[test]
+    fn test_ldr_freg64_reg64_imm12() {
+        disassembler_test!(
+            ldr_freg64_reg64_imm12,
+            |reg1: AArch64FloatReg, reg2: AArch64GeneralReg, imm| format!(
+                "ldr {}, [{}, #0x{:x}]",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(UsesSP),
+                imm << 3
             ),
+            ALL_FLOAT_REGS,
             ALL_GENERAL_REGS,
+            [0x123]
+        );
+    }
+
+    #[test]
+    fn test_ldur_freg64_reg64_imm9() {
+        disassembler_test!(
+            ldur_freg64_reg64_imm9,
+            |reg1: AArch64FloatReg, reg2: AArch64GeneralReg, imm: i16| format!(
+                "ldur {}, [{}, #{}0x{:x}]",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(UsesSP),
+                if imm < 0 { "-" } else { "" },
+                imm.abs()
+            ),
+            ALL_FLOAT_REGS,
             ALL_GENERAL_REGS,
-            ALL_GENERAL_REGS
+            TEST_IMM9S
         );
     }
 
     #[test]
-    fn test_add_reg64_reg64_imm12() {
+    fn test_ldur_reg64_reg64_imm9() {
         disassembler_test!(
-            add_reg64_reg64_imm12,
-            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
-                "add {}, {}, #0x{:x}",
-                reg1.capstone_string(UsesSP),
+            ldur_reg64_reg64_imm9,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm: i16| format!(
+                "ldur {}, [{}, #{}0x{:x}]",
+                reg1.capstone_string(UsesZR),
                 reg2.capstone_string(UsesSP),
-                imm
+                if imm < 0 { "-" } else { "" },
+                imm.abs()
             ),
             ALL_GENERAL_REGS,
             ALL_GENERAL_REGS,
-            [0x123]
+            TEST_IMM9S
         );
     }
 
     #[test]
-    fn test_ldr_reg64_reg64_imm12() {
+    fn test_ldursx_reg64_reg64_imm9() {
         disassembler_test!(
-            ldr_reg64_reg64_imm12,
-            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
-                "ldr {}, [{}, #0x{:x}]",
+            |buf, size, reg1, reg2| ldursx_reg64_reg64_imm9(buf, size, reg1, reg2, -0x10),
+            |size: u8, reg1: AArch64GeneralReg, reg2: AArch64GeneralReg| format!(
+                "{} {}, [{}, #-0x10]",
+                match size {
+                    1 => "ldursb",
+                    2 => "ldursh",
+                    _ => "ldursw",
+                },
                 reg1.capstone_string(UsesZR),
-                reg2.capstone_string(UsesSP),
-                imm << 3
+                reg2.capstone_string(UsesSP)
             ),
+            [1, 2, 4],
             ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_ldurzx_reg64_reg64_imm9() {
+        disassembler_test!(
+            |buf, size, reg1, reg2| ldurzx_reg64_reg64_imm9(buf, size, reg1, reg2, -0x10),
+            |size: u8, reg1: AArch64GeneralReg, reg2: AArch64GeneralReg| format!(
+                "{} {}, [{}, #-0x10]",
+                match size {
+                    1 => "ldurb",
+                    2 => "ldurh",
+                    _ => "ldur",
+                },
+                reg1.capstone_string(UsesZR).replacen('x', "w", 1),
+                reg2.capstone_string(UsesSP)
+            ),
+            [1, 2, 4],
             ALL_GENERAL_REGS,
-            [0x123]
+            ALL_GENERAL_REGS
         );
     }
 
     #[test]
-    fn test_mov_reg64_reg64() {
+    fn test_mul_reg64_reg64_reg64() {
         disassembler_test!(
-            mov_reg64_reg64,
-            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg| format!(
-                "mov {}, {}",
+            mul_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| format!(
+                "mul {}, {}, {}",
                 reg1.capstone_string(UsesZR),
                 reg2.capstone_string(UsesZR),
+                reg3.capstone_string(UsesZR)
             ),
             ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
             ALL_GENERAL_REGS
         );
     }
 
     #[test]
-    fn test_movk_reg64_imm16() {
+    fn test_neg_reg64_reg64() {
         disassembler_test!(
-            movk_reg64_imm16,
-            |reg1: AArch64GeneralReg, imm, hw| format!(
-                "movk {}, #0x{:x}{}",
+            neg_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg| format!(
+                "neg {}, {}",
                 reg1.capstone_string(UsesZR),
-                imm,
-                if hw > 0 {
-                    format!(", lsl #{}", hw * 16)
+                reg2.capstone_string(UsesZR)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_orr_reg64_reg64_reg64() {
+        disassembler_test!(
+            orr_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| {
+                if reg2 == AArch64GeneralReg::ZRSP {
+                    format!(
+                        "mov {}, {}",
+                        reg1.capstone_string(UsesZR),
+                        reg3.capstone_string(UsesZR)
+                    )
                 } else {
-                    "".to_owned()
+                    format!(
+                        "orr {}, {}, {}",
+                        reg1.capstone_string(UsesZR),
+                        reg2.capstone_string(UsesZR),
+                        reg3.capstone_string(UsesZR)
+                    )
                 }
-            ),
+            },
             ALL_GENERAL_REGS,
-            [TEST_U16],
-            [0, 1, 2, 3]
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
         );
     }
 
     #[test]
-    fn test_movz_reg64_imm16() {
+    fn test_scvtf_freg32_reg64() {
         disassembler_test!(
-            movz_reg64_imm16,
-            |reg1: AArch64GeneralReg, imm, hw| format!(
-                "mov {}, #0x{:x}{}",
+            scvtf_freg32_reg64,
+            |reg1: AArch64FloatReg, reg2: AArch64GeneralReg| format!(
+                "scvtf {}, {}",
+                reg1.capstone_string(FloatType::Single),
+                reg2.capstone_string(UsesZR)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_scvtf_freg64_reg64() {
+        disassembler_test!(
+            scvtf_freg64_reg64,
+            |reg1: AArch64FloatReg, reg2: AArch64GeneralReg| format!(
+                "scvtf {}, {}",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(UsesZR)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_sdiv_reg64_reg64_reg64() {
+        disassembler_test!(
+            sdiv_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| format!(
+                "sdiv {}, {}, {}",
                 reg1.capstone_string(UsesZR),
-                imm,
-                "0000".repeat(hw as usize)
+                reg2.capstone_string(UsesZR),
+                reg3.capstone_string(UsesZR)
             ),
             ALL_GENERAL_REGS,
-            [TEST_U16],
-            [0, 1, 2, 3]
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
         );
     }
 
     #[test]
-    fn test_str_reg64_reg64_imm12() {
+    fn test_stp_reg64_reg64_reg64_preindex() {
+        // Pairs that overlap each other or the written back base are unpredictable,
+        // and capstone will not decode them.
         disassembler_test!(
-            str_reg64_reg64_imm12,
-            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
-                "str {}, [{}, #0x{:x}]",
+            |buf, reg1, reg2, reg3| stp_reg64_reg64_reg64_preindex(buf, reg1, reg2, reg3, -2),
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| format!(
+                "stp {}, {}, [{}, #-0x10]!",
                 reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR),
+                reg3.capstone_string(UsesSP)
+            ),
+            &ALL_GENERAL_REGS[..15],
+            &ALL_GENERAL_REGS[15..],
+            [AArch64GeneralReg::ZRSP]
+        );
+    }
+
+    #[test]
+    fn test_str_freg64_reg64_imm12() {
+        disassembler_test!(
+            str_freg64_reg64_imm12,
+            |reg1: AArch64FloatReg, reg2: AArch64GeneralReg, imm| format!(
+                "str {}, [{}, #0x{:x}]",
+                reg1.capstone_string(FloatType::Double),
                 reg2.capstone_string(UsesSP),
                 imm << 3
             ),
-            ALL_GENERAL_REGS,
+            ALL_FLOAT_REGS,
             ALL_GENERAL_REGS,
             [0x123]
         );
     }
 
     #[test]
-    fn test_sub_reg64_reg64_imm12() {
+    fn test_stur_freg64_reg64_imm9() {
         disassembler_test!(
-            sub_reg64_reg64_imm12,
+            stur_freg64_reg64_imm9,
+            |reg1: AArch64FloatReg, reg2: AArch64GeneralReg, imm: i16| format!(
+                "stur {}, [{}, #{}0x{:x}]",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(UsesSP),
+                if imm < 0 { "-" } else { "" },
+                imm.abs()
+            ),
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS,
+            TEST_IMM9S
+        );
+    }
+
+    #[test]
+    fn test_stur_reg64_reg64_imm9() {
+        disassembler_test!(
+            stur_reg64_reg64_imm9,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm: i16| format!(
+                "stur {}, [{}, #{}0x{:x}]",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesSP),
+                if imm < 0 { "-" } else { "" },
+                imm.abs()
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            TEST_IMM9S
+        );
+    }
+
+    #[test]
+    fn test_sub_reg64_reg64_imm12_lsl12() {
+        disassembler_test!(
+            sub_reg64_reg64_imm12_lsl12,
             |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
-                "sub {}, {}, #0x{:x}",
+                "sub {}, {}, #0x{:x}, lsl #12",
                 reg1.capstone_string(UsesSP),
                 reg2.capstone_string(UsesSP),
                 imm
@@ -1497,6 +3770,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sub_reg64_reg64_reg64() {
+        disassembler_test!(
+            sub_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| {
+                if reg2 == AArch64GeneralReg::ZRSP {
+                    format!(
+                        "neg {}, {}",
+                        reg1.capstone_string(UsesZR),
+                        reg3.capstone_string(UsesZR)
+                    )
+                } else {
+                    format!(
+                        "sub {}, {}, {}",
+                        reg1.capstone_string(UsesZR),
+                        reg2.capstone_string(UsesZR),
+                        reg3.capstone_string(UsesZR)
+                    )
+                }
+            },
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_udiv_reg64_reg64_reg64() {
+        disassembler_test!(
+            udiv_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| format!(
+                "udiv {}, {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR),
+                reg3.capstone_string(UsesZR)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
     #[test]
     fn test_ret_reg64() {
         disassembler_test!(
@@ -12,6 +12,7 @@ use roc_mono::ir::{
     BranchInfo, JoinPointId, ListLiteralElement, Literal, Param, ProcLayout, SelfRecursive, Stmt,
 };
 use roc_mono::layout::{Builtin, Layout, TagIdIntType, UnionLayout};
+use roc_region::all::Region;
 use roc_target::TargetInfo;
 use std::marker::PhantomData;
 
@@ -1332,6 +1333,96 @@ impl<
         ASM::add_reg64_reg64_imm32(&mut self.buf, dst_reg, CC::BASE_PTR_REG, offset);
     }
 
+    fn build_dbg(&mut self, sym: &Symbol, layout: &Layout<'a>, region: Region) {
+        // The zig builtins write the value into the buffer of `roc`, in the same frame the llvm
+        // backend uses. They all start with the region and module of the dbg.
+        let u32_layout = Layout::Builtin(Builtin::Int(IntWidth::U32));
+        let u64_layout = Layout::Builtin(Builtin::Int(IntWidth::U64));
+        let module_id: u32 = unsafe { std::mem::transmute(sym.module_id()) };
+        self.load_literal(
+            &Symbol::DEV_TMP,
+            &u32_layout,
+            &Literal::Int((region.start().offset as i128).to_ne_bytes()),
+        );
+        self.load_literal(
+            &Symbol::DEV_TMP2,
+            &u32_layout,
+            &Literal::Int((region.end().offset as i128).to_ne_bytes()),
+        );
+        self.load_literal(
+            &Symbol::DEV_TMP3,
+            &u32_layout,
+            &Literal::Int((module_id as i128).to_ne_bytes()),
+        );
+
+        let target_info = self.storage_manager.target_info();
+        match layout {
+            Layout::Builtin(Builtin::Str) => {
+                self.build_fn_call(
+                    &Symbol::DEV_TMP4,
+                    bitcode::UTILS_DBG_STR.to_string(),
+                    &[Symbol::DEV_TMP, Symbol::DEV_TMP2, Symbol::DEV_TMP3, *sym],
+                    &[u32_layout, u32_layout, u32_layout, *layout],
+                    &Layout::UNIT,
+                );
+            }
+            Layout::Builtin(Builtin::List(elem_layout))
+                if elem_layout.safe_to_memcpy(self.env.layout_interner) =>
+            {
+                let elem_stack_size = elem_layout.stack_size(self.env.layout_interner, target_info);
+                self.load_literal(
+                    &Symbol::DEV_TMP4,
+                    &u64_layout,
+                    &Literal::Int((elem_stack_size as i128).to_ne_bytes()),
+                );
+                self.build_fn_call(
+                    &Symbol::DEV_TMP5,
+                    bitcode::UTILS_DBG_LIST.to_string(),
+                    &[
+                        Symbol::DEV_TMP,
+                        Symbol::DEV_TMP2,
+                        Symbol::DEV_TMP3,
+                        *sym,
+                        Symbol::DEV_TMP4,
+                    ],
+                    &[u32_layout, u32_layout, u32_layout, *layout, u64_layout],
+                    &Layout::UNIT,
+                );
+            }
+            _ if layout.safe_to_memcpy(self.env.layout_interner) => {
+                // Have to pass the value by pointer, so put it on the stack and load its address.
+                self.build_ptr_cast(&Symbol::DEV_TMP4, sym);
+                let stack_size = layout.stack_size(self.env.layout_interner, target_info);
+                self.load_literal(
+                    &Symbol::DEV_TMP5,
+                    &u64_layout,
+                    &Literal::Int((stack_size as i128).to_ne_bytes()),
+                );
+                self.build_fn_call(
+                    &Symbol::DEV_TMP6,
+                    bitcode::UTILS_DBG_BYTES.to_string(),
+                    &[
+                        Symbol::DEV_TMP,
+                        Symbol::DEV_TMP2,
+                        Symbol::DEV_TMP3,
+                        Symbol::DEV_TMP4,
+                        Symbol::DEV_TMP5,
+                    ],
+                    &[u32_layout, u32_layout, u32_layout, u64_layout, u64_layout],
+                    &Layout::UNIT,
+                );
+            }
+            x => todo!("dbg of a value with layout, {:?}", x),
+        }
+
+        self.free_symbol(&Symbol::DEV_TMP);
+        self.free_symbol(&Symbol::DEV_TMP2);
+        self.free_symbol(&Symbol::DEV_TMP3);
+        self.free_symbol(&Symbol::DEV_TMP4);
+        self.free_symbol(&Symbol::DEV_TMP5);
+        self.free_symbol(&Symbol::DEV_TMP6);
+    }
+
     fn create_empty_array(&mut self, sym: &Symbol) {
         let base_offset = self.storage_manager.claim_stack_area(sym, 24);
         self.storage_manager
@@ -17,6 +17,7 @@ use roc_mono::ir::{
 use roc_mono::layout::{
     Builtin, Layout, LayoutId, LayoutIds, STLayoutInterner, TagIdIntType, UnionLayout,
 };
+use roc_region::all::Region;
 
 mod generic64;
 mod object_builder;
@@ -209,6 +210,21 @@ trait Backend<'a> {
                 self.build_jump(id, args, arg_layouts.into_bump_slice(), ret_layout);
                 self.free_symbols(stmt);
             }
+            Stmt::Dbg {
+                symbol,
+                region,
+                remainder,
+            } => {
+                let layout = match self.layout_map().get(symbol) {
+                    Some(layout) => *layout,
+                    None => internal_error!("the dbg symbol, {:?}, has no know layout", symbol),
+                };
+                // Now that the value is needed, load it if it is a literal.
+                self.load_literal_symbols(&[*symbol]);
+                self.build_dbg(symbol, &layout, *region);
+                self.free_symbols(stmt);
+                self.build_stmt(remainder, ret_layout);
+            }
             x => todo!("the statement, {:?}", x),
         }
     }
@@ -655,6 +671,20 @@ trait Backend<'a> {
                 arg_layouts,
                 ret_layout,
             ),
+            LowLevel::Hash => self.build_fn_call(
+                sym,
+                bitcode::DICT_HASH_BYTES.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
+            LowLevel::HashU64 => self.build_fn_call(
+                sym,
+                bitcode::DICT_HASH_U64.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
             LowLevel::PtrCast => {
                 debug_assert_eq!(
                     1,
@@ -858,6 +888,9 @@ trait Backend<'a> {
     /// build_refcount_getptr loads the pointer to the reference count of src into dst.
     fn build_ptr_cast(&mut self, dst: &Symbol, src: &Symbol);
 
+    /// build_dbg sends the value of sym to the `roc` process that started the program, if any.
+    fn build_dbg(&mut self, sym: &Symbol, layout: &Layout<'a>, region: Region);
+
     /// literal_map gets the map from symbol to literal and layout, used for lazy loading and literal folding.
     fn literal_map(&mut self) -> &mut MutMap<Symbol, (*const Literal<'a>, *const Layout<'a>)>;
 
@@ -1097,6 +1130,13 @@ trait Backend<'a> {
             Stmt::Expect { .. } => todo!("expect is not implemented in the dev backend"),
             Stmt::ExpectFx { .. } => todo!("expect-fx is not implemented in the dev backend"),
 
+            Stmt::Dbg {
+                symbol, remainder, ..
+            } => {
+                self.set_last_seen(*symbol, stmt);
+                self.scan_ast(remainder);
+            }
+
             Stmt::RuntimeError(_) => {}
         }
     }
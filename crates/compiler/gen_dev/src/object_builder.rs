@@ -26,8 +26,17 @@ pub fn build_module<'a>(
     env: &'a Env,
     interns: &'a mut Interns,
     target: &Triple,
-    procedures: MutMap<(symbol::Symbol, ProcLayout<'a>), Proc<'a>>,
+    mut procedures: MutMap<(symbol::Symbol, ProcLayout<'a>), Proc<'a>>,
 ) -> Object<'a> {
+    // LLVM inlines on its own, but here the IR is turned into machine code as it is
+    Proc::inline_and_fold_constants(
+        env.arena,
+        env.module_id,
+        interns.all_ident_ids.get_mut(&env.module_id).unwrap(),
+        &env.exposed_to_host,
+        &mut procedures,
+    );
+
     match target {
         Triple {
             architecture: TargetArch::X86_64,
@@ -144,14 +153,7 @@ fn generate_wrapper<'a, B: Backend<'a>>(
     };
     output.add_symbol(symbol);
     if let Some(sym_id) = output.symbol_id(name) {
-        let reloc = write::Relocation {
-            offset: offset + proc_offset,
-            size: 32,
-            kind: RelocationKind::PltRelative,
-            encoding: RelocationEncoding::X86Branch,
-            symbol: sym_id,
-            addend: -4,
-        };
+        let reloc = function_call_relocation(output, offset + proc_offset, sym_id);
 
         match output.add_relocation(text_section, reloc) {
             Ok(obj) => obj,
@@ -162,6 +164,40 @@ fn generate_wrapper<'a, B: Backend<'a>>(
     }
 }
 
+/// The relocation for a call or tail call to `symbol` whose instruction is patched at `offset`.
+fn function_call_relocation(output: &Object, offset: u64, symbol: SymbolId) -> write::Relocation {
+    match (output.architecture(), output.format()) {
+        // The whole `BL`/`B` instruction is relocated, so there is no addend.
+        (Architecture::Aarch64, BinaryFormat::MachO) => write::Relocation {
+            offset,
+            size: 32,
+            kind: RelocationKind::MachO {
+                value: object::macho::ARM64_RELOC_BRANCH26,
+                relative: true,
+            },
+            encoding: RelocationEncoding::Generic,
+            symbol,
+            addend: 0,
+        },
+        (Architecture::Aarch64, _) => write::Relocation {
+            offset,
+            size: 26,
+            kind: RelocationKind::PltRelative,
+            encoding: RelocationEncoding::AArch64Call,
+            symbol,
+            addend: 0,
+        },
+        _ => write::Relocation {
+            offset,
+            size: 32,
+            kind: RelocationKind::PltRelative,
+            encoding: RelocationEncoding::X86Branch,
+            symbol,
+            addend: -4,
+        },
+    }
+}
+
 fn build_object<'a, B: Backend<'a>>(
     procedures: MutMap<(symbol::Symbol, ProcLayout<'a>), Proc<'a>>,
     mut backend: B,
@@ -459,14 +495,7 @@ fn build_proc<'a, B: Backend<'a>>(
                     }
                 }
                 if let Some(sym_id) = output.symbol_id(name.as_bytes()) {
-                    write::Relocation {
-                        offset: offset + proc_offset,
-                        size: 32,
-                        kind: RelocationKind::PltRelative,
-                        encoding: RelocationEncoding::X86Branch,
-                        symbol: sym_id,
-                        addend: -4,
-                    }
+                    function_call_relocation(output, offset + proc_offset, sym_id)
                 } else {
                     internal_error!("failed to find fn symbol for {:?}", name);
                 }
@@ -12,7 +12,7 @@ use crate::llvm::compare::{generic_eq, generic_neq};
 use crate::llvm::convert::{
     self, argument_type_from_layout, basic_type_from_builtin, basic_type_from_layout, zig_str_type,
 };
-use crate::llvm::expect::clone_to_shared_memory;
+use crate::llvm::expect::{clone_to_shared_memory, send_dbg_to_parent};
 use crate::llvm::refcounting::{
     build_reset, decrement_refcount_layout, increment_refcount_layout, PointerToRefcount,
 };
@@ -23,7 +23,8 @@ use inkwell::basic_block::BasicBlock;
 use inkwell::builder::Builder;
 use inkwell::context::Context;
 use inkwell::debug_info::{
-    AsDIScope, DICompileUnit, DIFlagsConstants, DISubprogram, DebugInfoBuilder,
+    AsDIScope, DICompileUnit, DIFile, DIFlags, DIFlagsConstants, DISubprogram, DISubroutineType,
+    DIType, DebugInfoBuilder,
 };
 use inkwell::memory_buffer::MemoryBuffer;
 use inkwell::module::{Linkage, Module};
@@ -58,10 +59,11 @@ use roc_mono::layout::{
     Builtin, CapturesNiche, LambdaName, LambdaSet, Layout, LayoutIds, RawFunctionLayout,
     STLayoutInterner, TagIdIntType, UnionLayout,
 };
+use roc_region::all::{LineInfo, Region};
 use roc_std::RocDec;
 use roc_target::{PtrWidth, TargetInfo};
 use std::convert::TryInto;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use target_lexicon::{Architecture, OperatingSystem, Triple};
 
 use super::convert::{zig_dec_type, zig_with_overflow_roc_dec, RocUnion};
@@ -131,6 +133,10 @@ pub struct Scope<'a, 'ctx> {
     symbols: ImMap<Symbol, (Layout<'a>, BasicValueEnum<'ctx>)>,
     pub top_level_thunks: ImMap<Symbol, (ProcLayout<'a>, FunctionValue<'ctx>)>,
     join_points: ImMap<JoinPointId, (BasicBlock<'ctx>, &'a [PhiValue<'ctx>])>,
+    /// Source regions of the named values in the current proc, see [`roc_mono::ir::Proc::locals`]
+    debug_locals: ImMap<Symbol, Region>,
+    /// Source regions of the lets in the current proc, see [`roc_mono::ir::Proc::let_regions`]
+    debug_lets: ImMap<Symbol, Region>,
 }
 
 impl<'a, 'ctx> Scope<'a, 'ctx> {
@@ -157,12 +163,19 @@ impl<'a, 'ctx> Scope<'a, 'ctx> {
         self.top_level_thunks
             .retain(|s, _| s.module_id() == module_id);
     }
+
+    pub fn set_debug_regions(&mut self, locals: &[(Symbol, Region)], lets: &[(Symbol, Region)]) {
+        self.debug_locals = locals.iter().copied().collect();
+        self.debug_lets = lets.iter().copied().collect();
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
 pub enum LlvmBackendMode {
     /// Assumes primitives (roc_alloc, roc_panic, etc) are provided by the host
     Binary,
+    /// Like `Binary`, but a `dbg` sends its value to the `roc` process that started the program
+    BinaryDev,
     /// Creates a test wrapper around the main roc function to catch and report panics.
     /// Provides a testing implementation of primitives (roc_alloc, roc_panic, etc)
     GenTest,
@@ -174,6 +187,7 @@ impl LlvmBackendMode {
     pub(crate) fn has_host(self) -> bool {
         match self {
             LlvmBackendMode::Binary => true,
+            LlvmBackendMode::BinaryDev => true,
             LlvmBackendMode::GenTest => false,
             LlvmBackendMode::WasmGenTest => true,
             LlvmBackendMode::CliTest => false,
@@ -184,6 +198,7 @@ impl LlvmBackendMode {
     fn returns_roc_result(self) -> bool {
         match self {
             LlvmBackendMode::Binary => false,
+            LlvmBackendMode::BinaryDev => false,
             LlvmBackendMode::GenTest => true,
             LlvmBackendMode::WasmGenTest => true,
             LlvmBackendMode::CliTest => true,
@@ -193,6 +208,7 @@ impl LlvmBackendMode {
     fn runs_expects(self) -> bool {
         match self {
             LlvmBackendMode::Binary => false,
+            LlvmBackendMode::BinaryDev => false,
             LlvmBackendMode::GenTest => false,
             LlvmBackendMode::WasmGenTest => false,
             LlvmBackendMode::CliTest => true,
@@ -212,8 +228,23 @@ pub struct Env<'a, 'ctx, 'env> {
     pub target_info: TargetInfo,
     pub mode: LlvmBackendMode,
     pub exposed_to_host: MutSet<Symbol>,
+    /// The Roc source files debug info points into. When a module has no entry here, its code
+    /// gets placeholder locations.
+    pub debug_sources: MutMap<ModuleId, DebugSource<'ctx>>,
 }
 
+/// A Roc source file, registered with the `DebugInfoBuilder`
+pub struct DebugSource<'ctx> {
+    pub file: DIFile<'ctx>,
+    pub line_info: LineInfo,
+}
+
+// DWARF base type encodings
+const DW_ATE_BOOLEAN: u32 = 0x02;
+const DW_ATE_FLOAT: u32 = 0x04;
+const DW_ATE_SIGNED: u32 = 0x05;
+const DW_ATE_UNSIGNED: u32 = 0x08;
+
 #[repr(u32)]
 pub enum PanicTagId {
     NullTerminatedString = 0,
@@ -409,11 +440,45 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
         )
     }
 
-    pub fn new_subprogram(&self, function_name: &str) -> DISubprogram<'ctx> {
-        let dibuilder = self.dibuilder;
-        let compile_unit = self.compile_unit;
+    /// Register the source file of every loaded module with the `DebugInfoBuilder`
+    pub fn new_debug_sources(
+        dibuilder: &DebugInfoBuilder<'ctx>,
+        sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    ) -> MutMap<ModuleId, DebugSource<'ctx>> {
+        sources
+            .iter()
+            .map(|(module_id, (path, src))| {
+                let filename = path.file_name().and_then(|name| name.to_str());
+                let directory = path
+                    .parent()
+                    .filter(|dir| !dir.as_os_str().is_empty())
+                    .and_then(|dir| dir.to_str());
+
+                let source = DebugSource {
+                    file: dibuilder.create_file(filename.unwrap_or(""), directory.unwrap_or(".")),
+                    line_info: LineInfo::new(src),
+                };
 
-        let ditype = dibuilder
+                (*module_id, source)
+            })
+            .collect()
+    }
+
+    /// The file, and the 1-based line and column (as DWARF expects them) of the start of `region`
+    fn debug_position(
+        &self,
+        module_id: ModuleId,
+        region: Region,
+    ) -> Option<(DIFile<'ctx>, u32, u32)> {
+        let source = self.debug_sources.get(&module_id)?;
+        let position = source.line_info.convert_pos(region.start());
+
+        Some((source.file, position.line + 1, position.column + 1))
+    }
+
+    fn new_subroutine_type(&self) -> DISubroutineType<'ctx> {
+        let ditype = self
+            .dibuilder
             .create_basic_type(
                 "type_name",
                 0_u64,
@@ -422,12 +487,17 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
             )
             .unwrap();
 
-        let subroutine_type = dibuilder.create_subroutine_type(
-            compile_unit.get_file(),
+        self.dibuilder.create_subroutine_type(
+            self.compile_unit.get_file(),
             /* return type */ Some(ditype.as_type()),
             /* parameter types */ &[],
             inkwell::debug_info::DIFlags::PUBLIC,
-        );
+        )
+    }
+
+    pub fn new_subprogram(&self, function_name: &str) -> DISubprogram<'ctx> {
+        let dibuilder = self.dibuilder;
+        let compile_unit = self.compile_unit;
 
         dibuilder.create_function(
             /* scope */ compile_unit.get_file().as_debug_info_scope(),
@@ -435,7 +505,7 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
             /* linkage_name */ None,
             /* file */ compile_unit.get_file(),
             /* line_no */ 0,
-            /* DIType */ subroutine_type,
+            /* DIType */ self.new_subroutine_type(),
             /* is_local_to_unit */ true,
             /* is_definition */ true,
             /* scope_line */ 0,
@@ -443,6 +513,205 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
             /* is_optimized */ false,
         )
     }
+
+    /// A subprogram for a Roc proc: it is named after the Roc symbol, and points at the
+    /// definition of the proc when the source of its module is known.
+    pub fn new_proc_subprogram(
+        &self,
+        symbol: Symbol,
+        linkage_name: &str,
+        region: Region,
+    ) -> DISubprogram<'ctx> {
+        let (file, line, _) = match self.debug_position(symbol.module_id(), region) {
+            Some(position) => position,
+            None => return self.new_subprogram(linkage_name),
+        };
+
+        self.dibuilder.create_function(
+            /* scope */ file.as_debug_info_scope(),
+            /* func name */ symbol.as_str(&self.interns),
+            /* linkage_name */ Some(linkage_name),
+            /* file */ file,
+            /* line_no */ line,
+            /* DIType */ self.new_subroutine_type(),
+            /* is_local_to_unit */ true,
+            /* is_definition */ true,
+            /* scope_line */ line,
+            /* flags */ inkwell::debug_info::DIFlags::PUBLIC,
+            /* is_optimized */ false,
+        )
+    }
+
+    /// Point the instructions built from now on at `region` in the source of `module_id`.
+    /// Returns the file and line, or `None` if the source of the module is unknown.
+    fn set_debug_location(
+        &self,
+        parent: FunctionValue<'ctx>,
+        module_id: ModuleId,
+        region: Region,
+    ) -> Option<(DIFile<'ctx>, u32)> {
+        let (file, line, column) = self.debug_position(module_id, region)?;
+        let subprogram = parent.get_subprogram()?;
+
+        let loc = self.dibuilder.create_debug_location(
+            self.context,
+            line,
+            column,
+            subprogram.as_debug_info_scope(),
+            /* inlined_at */ None,
+        );
+        self.builder.set_current_debug_location(self.context, loc);
+
+        Some((file, line))
+    }
+
+    fn debug_type_from_layout(&self, layout: &Layout<'a>) -> DIType<'ctx> {
+        let (name, encoding) = match layout {
+            Layout::Builtin(Builtin::Int(width)) if width.is_signed() => {
+                (format!("{:?}", width), DW_ATE_SIGNED)
+            }
+            Layout::Builtin(Builtin::Int(width)) => (format!("{:?}", width), DW_ATE_UNSIGNED),
+            Layout::Builtin(Builtin::Float(width)) => (format!("{:?}", width), DW_ATE_FLOAT),
+            Layout::Builtin(Builtin::Bool) => ("Bool".to_string(), DW_ATE_BOOLEAN),
+            Layout::Builtin(Builtin::Decimal) => ("Dec".to_string(), DW_ATE_SIGNED),
+            Layout::Builtin(Builtin::Str) => {
+                let byte = self.debug_basic_type("U8", 8, DW_ATE_UNSIGNED);
+                return self.debug_list_type("Str", byte);
+            }
+            Layout::Builtin(Builtin::List(element_layout)) => {
+                let element = self.debug_type_from_layout(element_layout);
+                return self.debug_list_type("List", element);
+            }
+            // debuggers only get to see the bytes of other values for now
+            _ => ("Value".to_string(), DW_ATE_UNSIGNED),
+        };
+
+        let size_in_bits = layout.stack_size(self.layout_interner, self.target_info) as u64 * 8;
+
+        self.debug_basic_type(&name, size_in_bits, encoding)
+    }
+
+    fn debug_basic_type(&self, name: &str, size_in_bits: u64, encoding: u32) -> DIType<'ctx> {
+        self.dibuilder
+            .create_basic_type(name, size_in_bits, encoding, DIFlags::PUBLIC)
+            .unwrap()
+            .as_type()
+    }
+
+    /// A `Str` or `List` is a pointer to its elements, followed by its length and capacity.
+    fn debug_list_type(&self, name: &str, element: DIType<'ctx>) -> DIType<'ctx> {
+        let ptr_bits = self.target_info.ptr_width() as u64 * 8;
+        let scope = self.compile_unit.as_debug_info_scope();
+        let file = self.compile_unit.get_file();
+
+        let ptr = self
+            .dibuilder
+            .create_pointer_type(
+                "",
+                element,
+                ptr_bits,
+                ptr_bits as u32,
+                AddressSpace::Generic,
+            )
+            .as_type();
+        let nat = self.debug_basic_type("Nat", ptr_bits, DW_ATE_UNSIGNED);
+
+        let members: Vec<DIType<'ctx>> = [("ptr", ptr), ("len", nat), ("cap", nat)]
+            .into_iter()
+            .enumerate()
+            .map(|(index, (member_name, member_type))| {
+                self.dibuilder
+                    .create_member_type(
+                        scope,
+                        member_name,
+                        file,
+                        /* line_no */ 0,
+                        ptr_bits,
+                        ptr_bits as u32,
+                        /* offset_in_bits */ index as u64 * ptr_bits,
+                        DIFlags::PUBLIC,
+                        member_type,
+                    )
+                    .as_type()
+            })
+            .collect();
+
+        self.dibuilder
+            .create_struct_type(
+                scope,
+                name,
+                file,
+                /* line_number */ 0,
+                3 * ptr_bits,
+                ptr_bits as u32,
+                DIFlags::PUBLIC,
+                /* derived_from */ None,
+                &members,
+                /* runtime_language */ 0,
+                /* vtable_holder */ None,
+                // lists of different elements share the name, so don't let LLVM merge them
+                /* unique_id */
+                "",
+            )
+            .as_type()
+    }
+
+    /// Describe a named Roc value to the debugger. Values are spilled to the stack so that
+    /// `llvm.dbg.declare` can refer to them; mem2reg turns this into `llvm.dbg.value` again.
+    fn declare_debug_variable(
+        &self,
+        parent: FunctionValue<'ctx>,
+        symbol: Symbol,
+        argument_number: Option<u32>,
+        (file, line): (DIFile<'ctx>, u32),
+        layout: &Layout<'a>,
+        value: BasicValueEnum<'ctx>,
+    ) {
+        let (subprogram, block) = match (parent.get_subprogram(), self.builder.get_insert_block()) {
+            (Some(subprogram), Some(block)) => (subprogram, block),
+            _ => return,
+        };
+
+        let scope = subprogram.as_debug_info_scope();
+        let name = symbol.as_str(&self.interns);
+        let ditype = self.debug_type_from_layout(layout);
+
+        let variable = match argument_number {
+            Some(arg_no) => self.dibuilder.create_parameter_variable(
+                scope,
+                name,
+                arg_no,
+                file,
+                line,
+                ditype,
+                /* always_preserve */ true,
+                DIFlags::ZERO,
+            ),
+            None => self.dibuilder.create_auto_variable(
+                scope,
+                name,
+                file,
+                line,
+                ditype,
+                /* always_preserve */ true,
+                DIFlags::ZERO,
+                /* align_in_bits */ 0,
+            ),
+        };
+
+        let storage = create_entry_block_alloca(self, parent, value.get_type(), name);
+        self.builder.build_store(storage, value);
+
+        let loc = self.dibuilder.create_debug_location(
+            self.context,
+            line,
+            /* column */ 0,
+            scope,
+            /* inlined_at */ None,
+        );
+        self.dibuilder
+            .insert_declare_at_end(storage, Some(variable), None, loc, block);
+    }
 }
 
 pub fn module_from_builtins<'ctx>(
@@ -2472,6 +2741,17 @@ pub fn build_exp_stmt<'a, 'ctx, 'env>(
             for (symbol, expr, layout) in queue {
                 debug_assert!(layout != &Layout::RecursivePointer);
 
+                // lets without a region of their own belong with the statement before them
+                if let Some(region) = scope.debug_lets.get(symbol) {
+                    env.set_debug_location(parent, symbol.module_id(), *region);
+                }
+
+                let debug_position = scope
+                    .debug_locals
+                    .get(symbol)
+                    .and_then(|region| env.debug_position(symbol.module_id(), *region))
+                    .map(|(file, line, _column)| (file, line));
+
                 let val = build_exp_expr(
                     env,
                     layout_ids,
@@ -2489,6 +2769,10 @@ pub fn build_exp_stmt<'a, 'ctx, 'env>(
                 // access itself!
                 // scope = scope.clone();
 
+                if let Some(position) = debug_position {
+                    env.declare_debug_variable(parent, *symbol, None, position, layout, val);
+                }
+
                 scope.insert(*symbol, (*layout, val));
                 stack.push(*symbol);
             }
@@ -2917,6 +3201,45 @@ pub fn build_exp_stmt<'a, 'ctx, 'env>(
             )
         }
 
+        Dbg {
+            symbol,
+            region,
+            remainder,
+        } => {
+            if let roc_target::PtrWidth::Bytes8 = env.target_info.ptr_width() {
+                match env.mode {
+                    // read back by `roc test`, just like the lookups of a failed `expect`
+                    LlvmBackendMode::CliTest => {
+                        clone_to_shared_memory(
+                            env,
+                            scope,
+                            layout_ids,
+                            *symbol,
+                            *region,
+                            &[*symbol],
+                        );
+                    }
+                    // shown by `roc run` while the program keeps running
+                    LlvmBackendMode::BinaryDev => {
+                        send_dbg_to_parent(env, parent, scope, layout_ids, *symbol, *region);
+                    }
+                    // `--optimize` builds drop the `dbg`; `roc build` warns about that
+                    LlvmBackendMode::Binary
+                    | LlvmBackendMode::GenTest
+                    | LlvmBackendMode::WasmGenTest => {}
+                }
+            }
+
+            build_exp_stmt(
+                env,
+                layout_ids,
+                func_spec_solutions,
+                scope,
+                parent,
+                remainder,
+            )
+        }
+
         RuntimeError(error_msg) => {
             throw_exception(env, error_msg);
 
@@ -3935,7 +4258,7 @@ fn expose_function_to_host_help_c_abi<'a, 'ctx, 'env>(
             )
         }
 
-        LlvmBackendMode::Binary => {}
+        LlvmBackendMode::Binary | LlvmBackendMode::BinaryDev => {}
     }
 
     // a generic version that writes the result into a passed *u8 pointer
@@ -3986,7 +4309,9 @@ fn expose_function_to_host_help_c_abi<'a, 'ctx, 'env>(
             roc_result_type(env, roc_function.get_type().get_return_type().unwrap()).into()
         }
 
-        LlvmBackendMode::Binary => basic_type_from_layout(env, &return_layout),
+        LlvmBackendMode::Binary | LlvmBackendMode::BinaryDev => {
+            basic_type_from_layout(env, &return_layout)
+        }
     };
 
     let size: BasicValueEnum = return_type.size_of().unwrap().into();
@@ -4630,7 +4955,7 @@ fn build_proc_header<'a, 'ctx, 'env>(
         Linkage::Internal,
     );
 
-    let subprogram = env.new_subprogram(&fn_name);
+    let subprogram = env.new_proc_subprogram(symbol, &fn_name, proc.region);
     fn_val.set_subprogram(subprogram);
 
     if env.exposed_to_host.contains(&symbol) {
@@ -4958,7 +5283,7 @@ pub fn build_proc<'a, 'ctx, 'env>(
                 GenTest | WasmGenTest | CliTest => {
                     /* no host, or exposing types is not supported */
                 }
-                Binary => {
+                Binary | BinaryDev => {
                     for (alias_name, (generated_function, top_level, layout)) in aliases.iter() {
                         expose_alias_to_host(
                             env,
@@ -4986,10 +5311,25 @@ pub fn build_proc<'a, 'ctx, 'env>(
 
     debug_info_init!(env, fn_val);
 
+    let proc_position = env.set_debug_location(fn_val, proc.name.name().module_id(), proc.region);
+    scope.set_debug_regions(proc.locals, proc.let_regions);
+
     // Add args to scope
-    for (arg_val, (layout, arg_symbol)) in fn_val.get_param_iter().zip(args) {
+    for (index, (arg_val, (layout, arg_symbol))) in fn_val.get_param_iter().zip(args).enumerate() {
         arg_val.set_name(arg_symbol.as_str(&env.interns));
         scope.insert(*arg_symbol, (*layout, arg_val));
+
+        if let Some(position) = proc_position {
+            let argument_number = index as u32 + 1;
+            env.declare_debug_variable(
+                fn_val,
+                *arg_symbol,
+                Some(argument_number),
+                position,
+                layout,
+                arg_val,
+            );
+        }
     }
 
     let body = build_exp_stmt(
@@ -6509,6 +6849,27 @@ fn run_low_level<'a, 'ctx, 'env>(
                 }
             }
         }
+        NumToBits => {
+            debug_assert_eq!(args.len(), 1);
+
+            let (arg, arg_layout) = load_symbol_and_layout(scope, &args[0]);
+
+            match arg_layout {
+                Layout::Builtin(Builtin::Float(_)) => {
+                    let dest = basic_type_from_layout(env, layout).into_int_type();
+
+                    env.builder.build_bitcast(arg, dest, "float_to_bits")
+                }
+                // a Dec is already represented as an i128
+                Layout::Builtin(Builtin::Decimal) => arg,
+                other => {
+                    unreachable!(
+                        "Tried to get the bits of a non-fractional layout {:?}",
+                        other
+                    );
+                }
+            }
+        }
         NumToFloatChecked => {
             // NOTE: There's a NumToIntChecked implementation above,
             // which could be useful to look at when implementing this.
@@ -6568,7 +6929,24 @@ fn run_low_level<'a, 'ctx, 'env>(
             BasicValueEnum::IntValue(bool_val)
         }
         Hash => {
-            unimplemented!()
+            // Dict.hashBytesLowlevel : List U8, U64 -> U64
+            debug_assert_eq!(args.len(), 2);
+            let list = load_symbol(scope, &args[0]).into_struct_value();
+            let seed = load_symbol(scope, &args[1]);
+            call_list_bitcode_fn(
+                env,
+                &[list],
+                &[seed],
+                BitcodeReturns::Basic,
+                bitcode::DICT_HASH_BYTES,
+            )
+        }
+        HashU64 => {
+            // Dict.hashU64Lowlevel : U64, U64 -> U64
+            debug_assert_eq!(args.len(), 2);
+            let value = load_symbol(scope, &args[0]);
+            let seed = load_symbol(scope, &args[1]);
+            call_bitcode_fn(env, &[value, seed], bitcode::DICT_HASH_U64)
         }
 
         ListMap | ListMap2 | ListMap3 | ListMap4 | ListSortWith => {
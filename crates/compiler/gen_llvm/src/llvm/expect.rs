@@ -116,10 +116,93 @@ pub(crate) fn clone_to_shared_memory<'a, 'ctx, 'env>(
         .unwrap()
         .into_pointer_value();
 
+    write_frame(
+        env,
+        scope,
+        layout_ids,
+        original_ptr,
+        condition,
+        region,
+        lookups,
+    )
+}
+
+/// Writes the value of a `dbg` into the buffer of the `roc` process that started this program, and
+/// waits until `roc` has shown it. Does nothing when the program was started some other way.
+pub(crate) fn send_dbg_to_parent<'a, 'ctx, 'env>(
+    env: &Env<'a, 'ctx, 'env>,
+    parent: FunctionValue<'ctx>,
+    scope: &Scope<'a, 'ctx>,
+    layout_ids: &mut LayoutIds<'a>,
+    symbol: Symbol,
+    region: Region,
+) {
+    let bd = env.builder;
+
+    let func = env
+        .module
+        .get_function(bitcode::UTILS_EXPECT_FAILED_START_SHARED_FILE)
+        .unwrap();
+
+    let parent_ptr = bd
+        .build_call(func, &[], "call_expect_start_shared_file")
+        .try_as_basic_value()
+        .left()
+        .unwrap()
+        .into_pointer_value();
+
+    let send_block = env.context.append_basic_block(parent, "send_dbg");
+    let after_block = env.context.append_basic_block(parent, "after_dbg");
+
+    let no_parent = bd.build_is_null(parent_ptr, "no_parent");
+    bd.build_conditional_branch(no_parent, after_block, send_block);
+
+    bd.position_at_end(send_block);
+
+    write_frame(
+        env,
+        scope,
+        layout_ids,
+        parent_ptr,
+        symbol,
+        region,
+        &[symbol],
+    );
+
+    let finalize = env
+        .module
+        .get_function(bitcode::UTILS_EXPECT_FAILED_FINALIZE)
+        .unwrap();
+
+    bd.build_call(finalize, &[], "call_expect_finalize");
+    bd.build_unconditional_branch(after_block);
+
+    bd.position_at_end(after_block);
+}
+
+fn write_frame<'a, 'ctx, 'env>(
+    env: &Env<'a, 'ctx, 'env>,
+    scope: &Scope<'a, 'ctx>,
+    layout_ids: &mut LayoutIds<'a>,
+    original_ptr: PointerValue<'ctx>,
+    condition: Symbol,
+    region: Region,
+    lookups: &[Symbol],
+) {
     let (count, mut offset) = read_state(env, original_ptr);
 
     offset = write_header(env, original_ptr, offset, condition, region);
 
+    // the offset at which this frame ends is only known after cloning the lookups; it lets the
+    // reader skip over this frame to the next one
+    let frame_end_slot = offset;
+
+    let ptr_width = env
+        .ptr_int()
+        .const_int(env.target_info.ptr_size() as _, false);
+
+    offset = env.builder.build_int_add(offset, ptr_width, "offset");
+
     let after_header = offset;
 
     let space_for_offsets = env
@@ -168,14 +251,12 @@ pub(crate) fn clone_to_shared_memory<'a, 'ctx, 'env>(
         for lookup_start in lookup_starts {
             build_copy(env, original_ptr, offset, lookup_start.into());
 
-            let ptr_width = env
-                .ptr_int()
-                .const_int(env.target_info.ptr_size() as _, false);
-
             offset = env.builder.build_int_add(offset, ptr_width, "offset")
         }
     }
 
+    build_copy(env, original_ptr, frame_end_slot, offset.into());
+
     let one = env.ptr_int().const_int(1, false);
     let new_count = env.builder.build_int_add(count, one, "inc");
     write_state(env, original_ptr, new_count, offset)
@@ -714,6 +714,10 @@ impl<'a> WasmBackend<'a> {
             Stmt::Expect { .. } => todo!("expect is not implemented in the wasm backend"),
             Stmt::ExpectFx { .. } => todo!("expect-fx is not implemented in the wasm backend"),
 
+            // there is no way to inspect values from wasm yet, so `dbg` is skipped;
+            // `roc build` reports every skipped `dbg` as a warning
+            Stmt::Dbg { remainder, .. } => self.stmt(remainder),
+
             Stmt::RuntimeError(msg) => self.stmt_runtime_error(msg),
         }
     }
@@ -88,8 +88,17 @@ pub fn build_app_module<'a>(
     env: &'a Env<'a>,
     interns: &'a mut Interns,
     host_module: WasmModule<'a>,
-    procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    mut procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
 ) -> (WasmModule<'a>, BitVec<usize>, u32) {
+    // LLVM inlines on its own, but here the IR is turned into Wasm as it is
+    Proc::inline_and_fold_constants(
+        env.arena,
+        env.module_id,
+        interns.all_ident_ids.get_mut(&env.module_id).unwrap(),
+        &env.exposed_to_host,
+        &mut procedures,
+    );
+
     let mut layout_ids = LayoutIds::default();
     let mut procs = Vec::with_capacity_in(procedures.len(), env.arena);
     let mut proc_lookup = Vec::with_capacity_in(procedures.len() * 2, env.arena);
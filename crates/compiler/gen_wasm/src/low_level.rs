@@ -1797,6 +1797,30 @@ impl<'a> LowLevelCall<'a> {
                     _ => todo!("{:?}: {:?} -> {:?}", self.lowlevel, arg_type, ret_type),
                 }
             }
+            NumToBits => {
+                let arg_layout = backend.storage.symbol_layouts[&self.arguments[0]];
+                match arg_layout {
+                    Layout::Builtin(Builtin::Float(FloatWidth::F32)) => {
+                        self.load_args(backend);
+                        backend.code_builder.i32_reinterpret_f32();
+                    }
+                    Layout::Builtin(Builtin::Float(FloatWidth::F64)) => {
+                        self.load_args(backend);
+                        backend.code_builder.i64_reinterpret_f64();
+                    }
+                    Layout::Builtin(Builtin::Decimal) => {
+                        // A Dec is stored as an I128, so its bits can be copied as they are
+                        let from = backend.storage.get(&self.arguments[0]).to_owned();
+                        backend.storage.clone_value(
+                            &mut backend.code_builder,
+                            &self.ret_storage,
+                            &from,
+                            self.arguments[0],
+                        );
+                    }
+                    x => internal_error!("NumToBits is not defined for {:?}", x),
+                }
+            }
             NumToIntChecked => {
                 let arg_layout = backend.storage.symbol_layouts[&self.arguments[0]];
 
@@ -1852,7 +1876,8 @@ impl<'a> LowLevelCall<'a> {
                 backend.storage.load_symbols(code_builder, self.arguments);
             }
 
-            Hash => todo!("{:?}", self.lowlevel),
+            Hash => self.load_args_and_call_zig(backend, bitcode::DICT_HASH_BYTES),
+            HashU64 => self.load_args_and_call_zig(backend, bitcode::DICT_HASH_U64),
 
             Eq | NotEq => self.eq_or_neq(backend),
 
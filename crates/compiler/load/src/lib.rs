@@ -14,6 +14,7 @@ const SKIP_SUBS_CACHE: bool = {
     }
 };
 
+pub use roc_load_internal::cache::default_cache_dir;
 pub use roc_load_internal::docs;
 pub use roc_load_internal::file::{
     EntryPoint, ExecutionMode, Expectations, LoadConfig, LoadResult, LoadStart, LoadedModule,
@@ -49,6 +50,7 @@ pub fn load_single_threaded<'a>(
         exposed_types,
         target_info,
         cached_subs,
+        None,
         render,
         exec_mode,
     )
@@ -96,6 +98,24 @@ pub fn load_and_monomorphize_from_str<'a>(
     }
 }
 
+pub fn load_and_typecheck_from_str<'a>(
+    arena: &'a Bump,
+    filename: PathBuf,
+    src: &'a str,
+    src_dir: PathBuf,
+    exposed_types: ExposedByModule,
+    load_config: LoadConfig,
+) -> Result<LoadedModule, LoadingProblem<'a>> {
+    use LoadResult::*;
+
+    let load_start = LoadStart::from_str(arena, filename, src, src_dir)?;
+
+    match load(arena, load_start, exposed_types, load_config)? {
+        Monomorphized(_) => unreachable!(""),
+        TypeChecked(module) => Ok(module),
+    }
+}
+
 pub fn load_and_monomorphize(
     arena: &Bump,
     filename: PathBuf,
@@ -157,12 +177,9 @@ pub fn load_and_typecheck_str<'a>(
     }
 }
 
-const BOOL: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/Bool.dat")) as &[_];
 const RESULT: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/Result.dat")) as &[_];
 const LIST: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/List.dat")) as &[_];
 const STR: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/Str.dat")) as &[_];
-const DICT: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/Dict.dat")) as &[_];
-const SET: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/Set.dat")) as &[_];
 const BOX: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/Box.dat")) as &[_];
 const NUM: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/Num.dat")) as &[_];
 
@@ -178,16 +195,15 @@ fn read_cached_subs() -> MutMap<ModuleId, (Subs, Vec<(Symbol, Variable)>)> {
     // Wasm seems to re-order definitions between build time and runtime, but only in release mode.
     // That is very strange, but we can solve it separately
     if !cfg!(target_family = "wasm") && !cfg!(windows) && !SKIP_SUBS_CACHE {
-        output.insert(ModuleId::BOOL, deserialize_help(BOOL));
         output.insert(ModuleId::RESULT, deserialize_help(RESULT));
         output.insert(ModuleId::NUM, deserialize_help(NUM));
 
         output.insert(ModuleId::LIST, deserialize_help(LIST));
         output.insert(ModuleId::STR, deserialize_help(STR));
-        output.insert(ModuleId::DICT, deserialize_help(DICT));
-
-        output.insert(ModuleId::SET, deserialize_help(SET));
         output.insert(ModuleId::BOX, deserialize_help(BOX));
+
+        // Bool, Dict and Set are not cached, because they declare or implement abilities, and
+        // abilities are not cached yet.
     }
 
     output
@@ -0,0 +1,429 @@
+//! An on-disk cache of the solved types of interface modules.
+//!
+//! Solving is the most expensive part of checking a module. When neither an interface module nor
+//! anything it (transitively) imports changed since an earlier build, we read its solved [Subs],
+//! exposed types and abilities back from disk instead of solving it again.
+//!
+//! Only solving is skipped: the module is still parsed and canonicalized, which hands out the
+//! same variables as it did when the cache entry was written, so the cached types line up with
+//! the fresh declarations.
+//!
+//! Every module file gets a directory, which only keeps the entry that was written last. Once
+//! the whole cache grows past [MAX_CACHE_BYTES], the modules that were written longest ago are
+//! removed.
+use roc_can::abilities::{
+    deserialize_resolved_implementations, serialize_resolved_implementations, AbilitiesStore,
+    ResolvedImpl,
+};
+use roc_can::module::ResolvedImplementations;
+use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+use roc_types::subs::{Subs, Variable};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Every cache file starts with this, followed by a hash of the rest of the file
+const MAGIC: &[u8; 8] = b"rocsolv1";
+const HEADER_LENGTH: usize = MAGIC.len() + 32;
+
+/// How large the cache can get before the oldest modules are removed from it
+const MAX_CACHE_BYTES: u64 = 512 * 1024 * 1024;
+
+/// The directory `roc` keeps solved modules in, unless told otherwise
+pub fn default_cache_dir() -> Option<PathBuf> {
+    let cache_home = match std::env::var_os("XDG_CACHE_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ if cfg!(windows) => PathBuf::from(std::env::var_os("LOCALAPPDATA")?),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".cache"),
+    };
+
+    Some(cache_home.join("roc").join("modules"))
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct DiskCache {
+    dir: PathBuf,
+    /// Cache entries are raw memory, so they can only be read by the compiler that wrote them
+    compiler: [u8; 32],
+}
+
+impl DiskCache {
+    /// Returns `None` when we can't tell which compiler is running, so caching is unsafe.
+    pub fn new(dir: PathBuf) -> Option<Self> {
+        let exe = std::env::current_exe().ok()?;
+        let metadata = std::fs::metadata(&exe).ok()?;
+        let modified = metadata
+            .modified()
+            .ok()?
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?;
+
+        let mut hasher = Sha256::new();
+        hasher.update(exe.to_string_lossy().as_bytes());
+        hasher.update(metadata.len().to_le_bytes());
+        hasher.update(modified.as_nanos().to_le_bytes());
+
+        Some(Self {
+            dir,
+            compiler: hasher.finalize().into(),
+        })
+    }
+
+    /// The key of a module, given the keys of the (non-builtin) modules it imports
+    pub fn key<'k>(
+        &self,
+        module_id: ModuleId,
+        name: String,
+        src: &str,
+        imports: impl IntoIterator<Item = &'k CacheKey>,
+    ) -> CacheKey {
+        let mut imports: Vec<&CacheKey> = imports.into_iter().collect();
+        imports.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut hasher = Sha256::new();
+        hasher.update(self.compiler);
+        hasher.update(name.as_bytes());
+        hasher.update([0]);
+        hasher.update(src.as_bytes());
+
+        let mut modules = vec![(name.clone(), module_id)];
+
+        for import in imports {
+            hasher.update(import.hash);
+            modules.extend(import.modules.iter().cloned());
+        }
+
+        // every module has its own name
+        modules.sort_by(|(a, _), (b, _)| a.cmp(b));
+        modules.dedup_by(|(a, _), (b, _)| a == b);
+
+        CacheKey {
+            hash: hasher.finalize().into(),
+            name,
+            modules,
+        }
+    }
+
+    /// Where a module is cached. `module_path` is the file the module is in, because modules in
+    /// different projects can have the same name.
+    pub fn slot(&self, key: &CacheKey, module_path: &Path) -> CacheSlot {
+        let path_hash = Sha256::digest(module_path.to_string_lossy().as_bytes());
+        let module_dir = format!("{}-{}", key.name, hex(&path_hash[..8]));
+
+        CacheSlot {
+            dir: self.dir.clone(),
+            path: self.dir.join(module_dir).join(hex(&key.hash)),
+            module_ids: key.modules.iter().map(|(_, id)| *id).collect(),
+        }
+    }
+}
+
+/// Identifies the solved types of a module: it hashes the module's source, and the sources of
+/// everything it imports.
+#[derive(Debug, Clone)]
+pub(crate) struct CacheKey {
+    hash: [u8; 32],
+    name: String,
+    /// The module itself and everything it transitively imports, sorted by name.
+    ///
+    /// Solved types refer to these modules by id, and ids are handed out in the order modules are
+    /// discovered, which can differ between builds. A cached module can only be reused when all of
+    /// these modules got the same id as when it was written.
+    modules: Vec<(String, ModuleId)>,
+}
+
+/// Where the solved types of one module are cached
+#[derive(Debug, Clone)]
+pub(crate) struct CacheSlot {
+    /// The directory of the whole cache
+    dir: PathBuf,
+    path: PathBuf,
+    module_ids: Vec<ModuleId>,
+}
+
+pub(crate) struct CachedModule {
+    pub subs: Subs,
+    pub exposed_vars_by_symbol: Vec<(Symbol, Variable)>,
+    pub solved_implementations: ResolvedImplementations,
+    pub abilities_store: AbilitiesStore,
+}
+
+impl CacheSlot {
+    /// Reads the module back, if it was cached by an earlier build in which canonicalizing it
+    /// produced the same `ident_ids`.
+    pub fn read(&self, ident_ids: &IdentIds) -> Option<CachedModule> {
+        let file = std::fs::read(&self.path).ok()?;
+
+        if file.len() < HEADER_LENGTH || &file[..MAGIC.len()] != MAGIC {
+            return None;
+        }
+
+        let payload = &file[HEADER_LENGTH..];
+
+        // a cache file that got cut short or mangled must not be read as raw memory
+        if Sha256::digest(payload).as_slice() != &file[MAGIC.len()..HEADER_LENGTH] {
+            return None;
+        }
+
+        // copy into 8-byte aligned memory, so the slices in it can be read in place
+        let mut words = vec![0u64; (payload.len() + 7) / 8];
+        let bytes =
+            unsafe { std::slice::from_raw_parts_mut(words.as_mut_ptr() as *mut u8, payload.len()) };
+        bytes.copy_from_slice(payload);
+
+        let mut sections = Sections { bytes, offset: 0 };
+
+        let module_ids = sections.next()?;
+        if module_ids.len() != self.module_ids.len() * std::mem::size_of::<ModuleId>() {
+            return None;
+        }
+        let (module_ids, _) =
+            Subs::deserialize_slice::<ModuleId>(module_ids, self.module_ids.len(), 0);
+
+        if module_ids != self.module_ids.as_slice() {
+            return None;
+        }
+
+        if sections.next()? != ident_names(ident_ids).as_slice() {
+            return None;
+        }
+
+        let (subs, exposed_vars_by_symbol) = Subs::deserialize(sections.next()?);
+        let exposed_vars_by_symbol = exposed_vars_by_symbol.to_vec();
+        let (solved_implementations, _) = deserialize_resolved_implementations(sections.next()?, 0);
+        let (abilities_store, _) = AbilitiesStore::deserialize(sections.next()?, 0);
+
+        Some(CachedModule {
+            subs,
+            exposed_vars_by_symbol,
+            solved_implementations,
+            abilities_store,
+        })
+    }
+
+    /// Caches a solved module. This is best-effort: if anything goes wrong, the module is just
+    /// solved again next time.
+    pub fn write(
+        &self,
+        ident_ids: &IdentIds,
+        subs: &Subs,
+        exposed_vars_by_symbol: &[(Symbol, Variable)],
+        solved_implementations: &ResolvedImplementations,
+        abilities_store: &AbilitiesStore,
+    ) {
+        // Types that were solved with errors are not complete, and derived implementations are
+        // made on demand in every build, so the symbols for them can't be reused.
+        if !subs.problems.is_empty()
+            || mentions_derived_module(subs, solved_implementations, abilities_store)
+        {
+            return;
+        }
+
+        let _ = self.write_help(
+            ident_ids,
+            subs,
+            exposed_vars_by_symbol,
+            solved_implementations,
+            abilities_store,
+        );
+    }
+
+    fn write_help(
+        &self,
+        ident_ids: &IdentIds,
+        subs: &Subs,
+        exposed_vars_by_symbol: &[(Symbol, Variable)],
+        solved_implementations: &ResolvedImplementations,
+        abilities_store: &AbilitiesStore,
+    ) -> std::io::Result<()> {
+        let mut payload = Vec::new();
+
+        let mut section = Vec::new();
+        Subs::serialize_slice(&self.module_ids, &mut section, 0)?;
+        push_section(&mut payload, &section);
+
+        push_section(&mut payload, &ident_names(ident_ids));
+
+        let mut section = Vec::new();
+        subs.serialize(exposed_vars_by_symbol, &mut section)?;
+        push_section(&mut payload, &section);
+
+        let mut section = Vec::new();
+        serialize_resolved_implementations(solved_implementations, &mut section, 0)?;
+        push_section(&mut payload, &section);
+
+        let mut section = Vec::new();
+        abilities_store.serialize(&mut section, 0)?;
+        push_section(&mut payload, &section);
+
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        // Other builds may be reading or writing this entry at the same time, so write it to a
+        // file of our own, then move that into place.
+        let temporary = self
+            .path
+            .with_extension(format!("{}.tmp", std::process::id()));
+
+        let mut file = std::fs::File::create(&temporary)?;
+        file.write_all(MAGIC)?;
+        file.write_all(&Sha256::digest(&payload))?;
+        file.write_all(&payload)?;
+        drop(file);
+
+        let result = std::fs::rename(&temporary, &self.path);
+        if result.is_err() {
+            let _ = std::fs::remove_file(&temporary);
+            return result;
+        }
+
+        self.prune()
+    }
+
+    /// Removes the entries this one replaces, and then the oldest modules while the cache is too
+    /// large.
+    fn prune(&self) -> std::io::Result<()> {
+        let module_dir = match self.path.parent() {
+            Some(dir) => dir,
+            None => return Ok(()),
+        };
+
+        for entry in std::fs::read_dir(module_dir)? {
+            let path = entry?.path();
+
+            // entries that other builds are still writing have an extension
+            if path != self.path && path.extension().is_none() {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+
+        let mut modules = Vec::new();
+        let mut total_bytes = 0;
+
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+
+            if path == module_dir {
+                continue;
+            }
+
+            if let Some((bytes, written)) = size_and_age(&path) {
+                total_bytes += bytes;
+                modules.push((written, bytes, path));
+            }
+        }
+
+        total_bytes += size_and_age(module_dir).map_or(0, |(bytes, _)| bytes);
+
+        // oldest first
+        modules.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (_, bytes, path) in modules {
+            if total_bytes <= MAX_CACHE_BYTES {
+                break;
+            }
+
+            let removed = if path.is_dir() {
+                std::fs::remove_dir_all(&path)
+            } else {
+                std::fs::remove_file(&path)
+            };
+
+            if removed.is_ok() {
+                total_bytes -= bytes;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The size of a module's directory in the cache, and when the last entry in it was written.
+/// Files directly in the cache directory are counted too, so leftovers get removed eventually.
+fn size_and_age(path: &Path) -> Option<(u64, SystemTime)> {
+    let metadata = std::fs::metadata(path).ok()?;
+
+    if !metadata.is_dir() {
+        return Some((metadata.len(), metadata.modified().ok()?));
+    }
+
+    let mut bytes = 0;
+    let mut written = metadata.modified().ok()?;
+
+    for entry in std::fs::read_dir(path).ok()? {
+        let metadata = entry.ok()?.metadata().ok()?;
+
+        bytes += metadata.len();
+        written = written.max(metadata.modified().ok()?);
+    }
+
+    Some((bytes, written))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The names of all identifiers, in order of their ids
+fn ident_names(ident_ids: &IdentIds) -> Vec<u8> {
+    let mut names = Vec::new();
+
+    for (_, name) in ident_ids.ident_strs() {
+        names.extend_from_slice(name.as_bytes());
+        names.push(b'\n');
+    }
+
+    names
+}
+
+fn mentions_derived_module(
+    subs: &Subs,
+    solved_implementations: &ResolvedImplementations,
+    abilities_store: &AbilitiesStore,
+) -> bool {
+    let is_derived = |symbol: &Symbol| {
+        let module_id = symbol.module_id();
+        module_id == ModuleId::DERIVED_SYNTH || module_id == ModuleId::DERIVED_GEN
+    };
+
+    subs.closure_names.iter().any(is_derived)
+        || abilities_store.mentions_symbol(is_derived)
+        || solved_implementations
+            .iter()
+            .any(|(_, resolved)| match resolved {
+                ResolvedImpl::Impl(info) => is_derived(&info.symbol),
+                ResolvedImpl::Derived | ResolvedImpl::Error => false,
+            })
+}
+
+/// Every section is prefixed with its length, and padded to a multiple of 8 bytes
+fn push_section(payload: &mut Vec<u8>, section: &[u8]) {
+    payload.extend_from_slice(&(section.len() as u64).to_le_bytes());
+    payload.extend_from_slice(section);
+
+    while payload.len() % 8 != 0 {
+        payload.push(0);
+    }
+}
+
+struct Sections<'b> {
+    bytes: &'b [u8],
+    offset: usize,
+}
+
+impl<'b> Sections<'b> {
+    fn next(&mut self) -> Option<&'b [u8]> {
+        let length = self.bytes.get(self.offset..self.offset + 8)?;
+        let length = u64::from_le_bytes(length.try_into().unwrap()) as usize;
+
+        let start = self.offset + 8;
+        let section = self.bytes.get(start..start.checked_add(length)?)?;
+
+        self.offset = start + length;
+        self.offset += (8 - self.offset % 8) % 8;
+
+        Some(section)
+    }
+}
@@ -1,3 +1,4 @@
+use crate::cache::{CacheKey, CacheSlot, DiskCache};
 use crate::docs::ModuleDocumentation;
 use bumpalo::Bump;
 use crossbeam::channel::{bounded, Sender};
@@ -7,12 +8,13 @@ use parking_lot::Mutex;
 use roc_builtins::roc::module_source;
 use roc_can::abilities::{AbilitiesStore, PendingAbilitiesStore, ResolvedImpl};
 use roc_can::constraint::{Constraint as ConstraintSoa, Constraints};
-use roc_can::expr::Declarations;
 use roc_can::expr::PendingDerives;
+use roc_can::expr::{Declarations, ExpectLookups};
 use roc_can::module::{
     canonicalize_module_defs, ExposedByModule, ExposedForModule, ExposedModuleTypes, Module,
     ResolvedImplementations,
 };
+use roc_can::symbol_index::{ModuleSymbolIndex, SymbolIndex};
 use roc_collections::{default_hasher, BumpMap, MutMap, MutSet, VecMap, VecSet};
 use roc_constrain::module::constrain_module;
 use roc_debug_flags::dbg_do;
@@ -41,7 +43,7 @@ use roc_parse::ast::{self, Defs, ExtractSpaces, Spaced, StrLiteral, TypeAnnotati
 use roc_parse::header::{ExposedName, ImportsEntry, PackageEntry, PlatformHeader, To, TypedIdent};
 use roc_parse::header::{HeaderFor, ModuleNameEnum, PackageName};
 use roc_parse::ident::UppercaseIdent;
-use roc_parse::module::module_defs;
+use roc_parse::module::module_defs_with_recovery;
 use roc_parse::parser::{FileError, Parser, SyntaxError};
 use roc_region::all::{LineInfo, Loc, Region};
 use roc_reporting::report::RenderTarget;
@@ -126,6 +128,9 @@ pub struct LoadConfig {
     pub render: RenderTarget,
     pub threading: Threading,
     pub exec_mode: ExecutionMode,
+    /// Where to keep the solved types of interface modules between builds,
+    /// or `None` to always solve them from scratch.
+    pub cache_dir: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -167,16 +172,28 @@ struct ModuleCache<'a> {
     imports: MutMap<ModuleId, MutSet<ModuleId>>,
     top_level_thunks: MutMap<ModuleId, MutSet<Symbol>>,
     documentation: MutMap<ModuleId, ModuleDocumentation>,
+    symbol_indices: MutMap<ModuleId, ModuleSymbolIndex>,
+    /// Syntax errors the parser recovered from. Like the other problems, they are only rendered
+    /// into reports once the idents of every module are known.
+    syntax_problems: MutMap<ModuleId, Vec<FileError<'a, SyntaxError<'a>>>>,
     can_problems: MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     type_problems: MutMap<ModuleId, Vec<TypeError>>,
 
     sources: MutMap<ModuleId, (PathBuf, &'a str)>,
+
+    /// For the on-disk cache of solved modules
+    cache_keys: MutMap<ModuleId, CacheKey>,
+    cache_slots: MutMap<ModuleId, CacheSlot>,
 }
 
 impl<'a> ModuleCache<'a> {
     pub fn total_problems(&self) -> usize {
         let mut total = 0;
 
+        for problems in self.syntax_problems.values() {
+            total += problems.len();
+        }
+
         for problems in self.can_problems.values() {
             total += problems.len();
         }
@@ -214,6 +231,7 @@ impl Default for ModuleCache<'_> {
             ENCODE,
             DECODE,
             JSON,
+            HASH,
         }
 
         Self {
@@ -230,16 +248,108 @@ impl Default for ModuleCache<'_> {
             imports: Default::default(),
             top_level_thunks: Default::default(),
             documentation: Default::default(),
+            symbol_indices: Default::default(),
+            syntax_problems: Default::default(),
             can_problems: Default::default(),
             type_problems: Default::default(),
             sources: Default::default(),
             expectations: Default::default(),
+            cache_keys: Default::default(),
+            cache_slots: Default::default(),
         }
     }
 }
 
 type SharedIdentIdsByModule = Arc<Mutex<roc_module::symbol::IdentIdsByModule>>;
 
+/// Works out where the solved types of a module would be cached, from its source and the keys of
+/// the modules it imports. Only interface modules are cached, but any module can be imported.
+fn add_cache_key(state: &mut State, parsed: &ParsedModule) {
+    let disk_cache = match &state.disk_cache {
+        Some(disk_cache) if !parsed.module_id.is_builtin() => disk_cache,
+        _ => return,
+    };
+
+    let mut imports = Vec::with_capacity(parsed.imported_modules.len());
+
+    for imported in parsed.imported_modules.keys() {
+        if imported.is_builtin() {
+            // builtins are part of the compiler, which is already part of every key
+            continue;
+        }
+
+        match state.module_cache.cache_keys.get(imported) {
+            Some(key) => imports.push(key),
+            None => return,
+        }
+    }
+
+    let name = match state.module_cache.module_names.get(&parsed.module_id) {
+        Some(PQModuleName::Unqualified(name)) => name.as_str().to_string(),
+        Some(PQModuleName::Qualified(shorthand, name)) => {
+            format!("{}.{}", shorthand, name.as_str())
+        }
+        // the root module is not imported by anything, so it was never given a name
+        None => match &parsed.module_name {
+            ModuleNameEnum::Interface(name) | ModuleNameEnum::Hosted(name) => {
+                name.as_str().to_string()
+            }
+            ModuleNameEnum::App(_) | ModuleNameEnum::Platform => {
+                parsed.module_path.to_string_lossy().into_owned()
+            }
+        },
+    };
+
+    let key = disk_cache.key(parsed.module_id, name, parsed.src, imports);
+
+    if let HeaderFor::Interface = parsed.header_for {
+        let slot = disk_cache.slot(&key, &parsed.module_path);
+        state
+            .module_cache
+            .cache_slots
+            .insert(parsed.module_id, slot);
+    }
+
+    state.module_cache.cache_keys.insert(parsed.module_id, key);
+}
+
+/// Top-level `expect`s are usually written right below the def they test, so that is the def we
+/// associate each of them with.
+fn toplevel_expect_defs(decls: &Declarations) -> VecMap<Symbol, Symbol> {
+    use roc_can::expr::DeclarationTag::*;
+
+    let mut defs = Vec::new();
+    let mut expects = Vec::new();
+
+    for (index, tag) in decls.declarations.iter().enumerate() {
+        let symbol = decls.symbols[index];
+
+        match tag {
+            Value | Function(_) | Recursive(_) | TailRecursive(_) => {
+                defs.push((symbol.region.start(), symbol.value));
+            }
+            Expectation | ExpectationFx => {
+                let region = decls.expressions[index].region;
+                expects.push((region.start(), symbol.value));
+            }
+            Destructure(_) | MutualRecursion { .. } => {}
+        }
+    }
+
+    // declarations are sorted by their dependencies, not by where they are in the source
+    defs.sort_by_key(|(start, _)| *start);
+
+    expects
+        .into_iter()
+        .filter_map(|(start, expect)| {
+            let following = defs.partition_point(|(def_start, _)| *def_start < start);
+            let (_, def) = defs.get(following.checked_sub(1)?)?;
+
+            Some((expect, *def))
+        })
+        .collect()
+}
+
 fn start_phase<'a>(
     module_id: ModuleId,
     phase: Phase,
@@ -391,6 +501,8 @@ fn start_phase<'a>(
                     state.cached_subs.lock().contains_key(&module_id)
                 };
 
+                add_cache_key(state, &parsed);
+
                 BuildTask::CanonicalizeAndConstrain {
                     parsed,
                     dep_idents,
@@ -420,6 +532,7 @@ fn start_phase<'a>(
                 } = constrained;
 
                 let derived_module = SharedDerivedModule::clone(&state.derived_module);
+                let cache_slot = state.module_cache.cache_slots.remove(&module_id);
 
                 BuildTask::solve_module(
                     module,
@@ -434,6 +547,7 @@ fn start_phase<'a>(
                     dep_idents,
                     declarations,
                     state.cached_subs.clone(),
+                    cache_slot,
                     derived_module,
                 )
             }
@@ -605,6 +719,8 @@ pub struct LoadedModule {
     pub module_id: ModuleId,
     pub interns: Interns,
     pub solved: Solved<Subs>,
+    /// Rendered reports of the syntax errors the parser recovered from
+    pub syntax_problems: MutMap<ModuleId, Vec<String>>,
     pub can_problems: MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     pub type_problems: MutMap<ModuleId, Vec<TypeError>>,
     pub declarations_by_id: MutMap<ModuleId, Declarations>,
@@ -618,12 +734,17 @@ pub struct LoadedModule {
     pub timings: MutMap<ModuleId, ModuleTiming>,
     pub documentation: MutMap<ModuleId, ModuleDocumentation>,
     pub abilities_store: AbilitiesStore,
+    pub symbol_index: SymbolIndex,
 }
 
 impl LoadedModule {
     pub fn total_problems(&self) -> usize {
         let mut total = 0;
 
+        for problems in self.syntax_problems.values() {
+            total += problems.len();
+        }
+
         for problems in self.can_problems.values() {
             total += problems.len();
         }
@@ -726,6 +847,8 @@ pub struct MonomorphizedModule<'a> {
     pub subs: Subs,
     pub layout_interner: SingleThreadedInterner<'a, Layout<'a>>,
     pub output_path: Box<Path>,
+    /// Rendered reports of the syntax errors the parser recovered from
+    pub syntax_problems: MutMap<ModuleId, Vec<String>>,
     pub can_problems: MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     pub type_problems: MutMap<ModuleId, Vec<TypeError>>,
     pub procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
@@ -752,6 +875,10 @@ pub struct Expectations {
     pub subs: roc_types::subs::Subs,
     pub path: PathBuf,
     pub expectations: VecMap<Region, Vec<(Symbol, Variable)>>,
+    pub dbgs: VecMap<Region, (Symbol, Variable)>,
+    /// For each top-level `expect`, the top-level def written above it. An `expect` has no name
+    /// of its own, so this is the name we can refer to it by.
+    pub toplevel_defs: VecMap<Symbol, Symbol>,
     pub ident_ids: IdentIds,
 }
 
@@ -767,6 +894,10 @@ impl<'a> MonomorphizedModule<'a> {
     pub fn total_problems(&self) -> usize {
         let mut total = 0;
 
+        for problems in self.syntax_problems.values() {
+            total += problems.len();
+        }
+
         for problems in self.can_problems.values() {
             total += problems.len();
         }
@@ -790,13 +921,13 @@ struct ParsedModule<'a> {
     exposed_ident_ids: IdentIds,
     exposed_imports: MutMap<Ident, (Symbol, Region)>,
     parsed_defs: Defs<'a>,
+    /// Syntax errors in defs, which were replaced by malformed placeholders
+    syntax_problems: Vec<FileError<'a, SyntaxError<'a>>>,
     module_name: ModuleNameEnum<'a>,
     symbols_from_requires: Vec<(Loc<Symbol>, Loc<TypeAnnotation<'a>>)>,
     header_for: HeaderFor<'a>,
 }
 
-type LocExpects = VecMap<Region, Vec<(Symbol, Variable)>>;
-
 /// A message sent out _from_ a worker thread,
 /// representing a result of work done, or a request for further work
 #[derive(Debug)]
@@ -814,7 +945,7 @@ enum Msg<'a> {
         dep_idents: IdentIdsByModule,
         module_timing: ModuleTiming,
         abilities_store: AbilitiesStore,
-        loc_expects: LocExpects,
+        loc_expects: ExpectLookups,
     },
     FinishedAllTypeChecking {
         solved_subs: Solved<Subs>,
@@ -870,6 +1001,7 @@ struct CanAndCon {
     constrained_module: ConstrainedModule,
     canonicalization_problems: Vec<roc_problem::can::Problem>,
     module_docs: Option<ModuleDocumentation>,
+    symbol_index: ModuleSymbolIndex,
 }
 
 #[derive(Debug)]
@@ -963,6 +1095,9 @@ struct State<'a> {
     // cached subs (used for builtin modules, could include packages in the future too)
     cached_subs: CachedSubs,
 
+    /// Solved interface modules from earlier builds
+    disk_cache: Option<DiskCache>,
+
     layout_interner: Arc<GlobalInterner<'a, Layout<'a>>>,
 }
 
@@ -981,6 +1116,7 @@ impl<'a> State<'a> {
         arc_modules: Arc<Mutex<PackageModuleIds<'a>>>,
         ident_ids_by_module: SharedIdentIdsByModule,
         cached_subs: MutMap<ModuleId, (Subs, Vec<(Symbol, Variable)>)>,
+        cache_dir: Option<PathBuf>,
         render: RenderTarget,
         number_of_workers: usize,
         exec_mode: ExecutionMode,
@@ -1012,6 +1148,7 @@ impl<'a> State<'a> {
             timings: MutMap::default(),
             layout_caches: std::vec::Vec::with_capacity(number_of_workers),
             cached_subs: Arc::new(Mutex::new(cached_subs)),
+            disk_cache: cache_dir.and_then(DiskCache::new),
             render,
             exec_mode,
             make_specializations_pass: MakeSpecializationsPass::Pass(1),
@@ -1125,6 +1262,7 @@ enum BuildTask<'a> {
         declarations: Declarations,
         dep_idents: IdentIdsByModule,
         cached_subs: CachedSubs,
+        cache_slot: Option<CacheSlot>,
         derived_module: SharedDerivedModule,
     },
     BuildPendingSpecializations {
@@ -1227,6 +1365,7 @@ pub fn load_and_typecheck_str<'a>(
         render,
         threading,
         exec_mode: ExecutionMode::Check,
+        cache_dir: None,
     };
 
     match load(arena, load_start, exposed_types, cached_subs, load_config)? {
@@ -1321,7 +1460,7 @@ impl<'a> LoadStart<'a> {
                     return Err(LoadingProblem::FormattedReport(buf));
                 }
                 Err(LoadingProblem::FileProblem { filename, error }) => {
-                    let buf = to_file_problem_report(&filename, error);
+                    let buf = to_file_problem_report(&filename, error, render);
                     return Err(LoadingProblem::FormattedReport(buf));
                 }
                 Err(e) => return Err(e),
@@ -1472,6 +1611,7 @@ pub fn load<'a>(
             exposed_types,
             load_config.target_info,
             cached_subs,
+            load_config.cache_dir,
             load_config.render,
             load_config.exec_mode,
         ),
@@ -1481,6 +1621,7 @@ pub fn load<'a>(
             exposed_types,
             load_config.target_info,
             cached_subs,
+            load_config.cache_dir,
             load_config.render,
             threads,
             load_config.exec_mode,
@@ -1496,6 +1637,7 @@ pub fn load_single_threaded<'a>(
     exposed_types: ExposedByModule,
     target_info: TargetInfo,
     cached_subs: MutMap<ModuleId, (Subs, Vec<(Symbol, Variable)>)>,
+    cache_dir: Option<PathBuf>,
     render: RenderTarget,
     exec_mode: ExecutionMode,
 ) -> Result<LoadResult<'a>, LoadingProblem<'a>> {
@@ -1522,6 +1664,7 @@ pub fn load_single_threaded<'a>(
         arc_modules,
         ident_ids_by_module,
         cached_subs,
+        cache_dir,
         render,
         number_of_workers,
         exec_mode,
@@ -1627,7 +1770,7 @@ fn state_thread_step<'a>(
                     Ok(ControlFlow::Break(LoadResult::Monomorphized(monomorphized)))
                 }
                 Msg::FailedToReadFile { filename, error } => {
-                    let buf = to_file_problem_report(&filename, error);
+                    let buf = to_file_problem_report(&filename, error, state.render);
                     Err(LoadingProblem::FormattedReport(buf))
                 }
 
@@ -1699,6 +1842,7 @@ fn load_multi_threaded<'a>(
     exposed_types: ExposedByModule,
     target_info: TargetInfo,
     cached_subs: MutMap<ModuleId, (Subs, Vec<(Symbol, Variable)>)>,
+    cache_dir: Option<PathBuf>,
     render: RenderTarget,
     available_threads: usize,
     exec_mode: ExecutionMode,
@@ -1741,6 +1885,7 @@ fn load_multi_threaded<'a>(
         arc_modules,
         ident_ids_by_module,
         cached_subs,
+        cache_dir,
         render,
         num_workers,
         exec_mode,
@@ -2184,6 +2329,10 @@ fn update<'a>(
                 header
                     .exposed_imports
                     .insert(Ident::from("Bool"), (Symbol::BOOL_BOOL, Region::zero()));
+
+                header
+                    .exposed_imports
+                    .insert(Ident::from("Eq"), (Symbol::BOOL_EQ_ABILITY, Region::zero()));
             }
 
             if header.module_id == ModuleId::NUM {
@@ -2248,6 +2397,14 @@ fn update<'a>(
                 header
                     .imported_modules
                     .insert(ModuleId::ENCODE, Region::zero());
+
+                header
+                    .package_qualified_imported_modules
+                    .insert(PackageQualified::Unqualified(ModuleId::HASH));
+
+                header
+                    .imported_modules
+                    .insert(ModuleId::HASH, Region::zero());
             }
 
             state
@@ -2278,7 +2435,7 @@ fn update<'a>(
 
             Ok(state)
         }
-        Parsed(parsed) => {
+        Parsed(mut parsed) => {
             state
                 .module_cache
                 .sources
@@ -2304,6 +2461,15 @@ fn update<'a>(
 
             let module_id = parsed.module_id;
 
+            if !parsed.syntax_problems.is_empty() {
+                let syntax_problems = std::mem::take(&mut parsed.syntax_problems);
+
+                state
+                    .module_cache
+                    .syntax_problems
+                    .insert(module_id, syntax_problems);
+            }
+
             state.module_cache.parsed.insert(parsed.module_id, parsed);
 
             let work = state.dependencies.notify(module_id, Phase::Parse);
@@ -2317,6 +2483,7 @@ fn update<'a>(
             constrained_module,
             canonicalization_problems,
             module_docs,
+            symbol_index,
         }) => {
             let module_id = constrained_module.module.module_id;
             log!("generated constraints for {:?}", module_id);
@@ -2329,6 +2496,11 @@ fn update<'a>(
                 state.module_cache.documentation.insert(module_id, docs);
             }
 
+            state
+                .module_cache
+                .symbol_indices
+                .insert(module_id, symbol_index);
+
             report_unused_imported_modules(&mut state, module_id, &constrained_module);
 
             state
@@ -2384,7 +2556,9 @@ fn update<'a>(
                 let (path, _) = state.module_cache.sources.get(&module_id).unwrap();
 
                 let expectations = Expectations {
-                    expectations: loc_expects,
+                    expectations: loc_expects.expects,
+                    dbgs: loc_expects.dbgs,
+                    toplevel_defs: toplevel_expect_defs(&decls),
                     subs: solved_subs.clone().into_inner(),
                     path: path.to_owned(),
                     ident_ids: ident_ids.clone(),
@@ -2924,10 +3098,13 @@ fn finish_specialization<'a>(
         expectations,
         type_problems,
         can_problems,
+        syntax_problems,
         sources,
         ..
     } = module_cache;
 
+    let syntax_problems = render_syntax_problems(syntax_problems, &sources, &interns, state.render);
+
     let sources: MutMap<ModuleId, (PathBuf, Box<str>)> = sources
         .into_iter()
         .map(|(id, (path, src))| (id, (path, src.into())))
@@ -2948,7 +3125,8 @@ fn finish_specialization<'a>(
                         }
                         Valid(To::NewPackage(p_or_p)) => p_or_p,
                         other => {
-                            let buf = to_missing_platform_report(state.root_id, other);
+                            let buf =
+                                to_missing_platform_report(state.root_id, other, state.render);
                             return Err(LoadingProblem::FormattedReport(buf));
                         }
                     };
@@ -2996,6 +3174,7 @@ fn finish_specialization<'a>(
     };
 
     Ok(MonomorphizedModule {
+        syntax_problems,
         can_problems,
         type_problems,
         output_path,
@@ -3046,6 +3225,13 @@ fn finish(
         all_ident_ids: state.constrained_ident_ids,
     };
 
+    let syntax_problems = render_syntax_problems(
+        state.module_cache.syntax_problems,
+        &state.module_cache.sources,
+        &interns,
+        state.render,
+    );
+
     let sources = state
         .module_cache
         .sources
@@ -3055,10 +3241,17 @@ fn finish(
 
     let exposed_values = exposed_vars_by_symbol.iter().map(|x| x.0).collect();
 
+    let mut symbol_index = SymbolIndex::default();
+
+    for (module_id, module_index) in state.module_cache.symbol_indices {
+        symbol_index.insert_module(module_id, module_index);
+    }
+
     LoadedModule {
         module_id: state.root_id,
         interns,
         solved,
+        syntax_problems,
         can_problems: state.module_cache.can_problems,
         type_problems: state.module_cache.type_problems,
         declarations_by_id: state.declarations_by_id,
@@ -3072,6 +3265,7 @@ fn finish(
         timings: state.timings,
         documentation,
         abilities_store,
+        symbol_index,
     }
 }
 
@@ -3266,6 +3460,7 @@ fn load_module<'a>(
         "Encode", ModuleId::ENCODE
         "Decode", ModuleId::DECODE
         "Json", ModuleId::JSON
+        "Hash", ModuleId::HASH
     }
 
     let (filename, opt_shorthand) = module_name_to_path(src_dir, module_name, arc_shorthands);
@@ -4085,6 +4280,7 @@ impl<'a> BuildTask<'a> {
         dep_idents: IdentIdsByModule,
         declarations: Declarations,
         cached_subs: CachedSubs,
+        cache_slot: Option<CacheSlot>,
         derived_module: SharedDerivedModule,
     ) -> Self {
         let exposed_by_module = exposed_types.retain_modules(imported_modules.keys());
@@ -4105,6 +4301,7 @@ impl<'a> BuildTask<'a> {
             dep_idents,
             module_timing,
             cached_subs,
+            cache_slot,
             derived_module,
         }
     }
@@ -4392,6 +4589,7 @@ fn run_solve<'a>(
     decls: Declarations,
     dep_idents: IdentIdsByModule,
     cached_subs: CachedSubs,
+    cache_slot: Option<CacheSlot>,
     derived_module: SharedDerivedModule,
 ) -> Msg<'a> {
     let solve_start = Instant::now();
@@ -4429,8 +4627,16 @@ fn run_solve<'a>(
                     )
                 }
             }
+        } else if let Some(cached) = cache_slot.as_ref().and_then(|slot| slot.read(&ident_ids)) {
+            (
+                Solved(cached.subs),
+                cached.solved_implementations,
+                cached.exposed_vars_by_symbol,
+                vec![],
+                cached.abilities_store,
+            )
         } else {
-            run_solve_solve(
+            let solved = run_solve_solve(
                 exposed_for_module,
                 constraints,
                 constraint,
@@ -4438,7 +4644,29 @@ fn run_solve<'a>(
                 var_store,
                 module,
                 derived_module,
-            )
+            );
+
+            if let Some(slot) = &cache_slot {
+                let (
+                    solved_subs,
+                    solved_implementations,
+                    exposed_vars_by_symbol,
+                    problems,
+                    abilities_store,
+                ) = &solved;
+
+                if problems.is_empty() {
+                    slot.write(
+                        &ident_ids,
+                        solved_subs.inner(),
+                        exposed_vars_by_symbol,
+                        solved_implementations,
+                        abilities_store,
+                    );
+                }
+            }
+
+            solved
         }
     };
 
@@ -4654,7 +4882,11 @@ fn canonicalize_and_constrain<'a>(
             Vacant(vacant) => {
                 let should_include_builtin = matches!(
                     name.module_id(),
-                    ModuleId::ENCODE | ModuleId::DECODE | ModuleId::DICT | ModuleId::SET
+                    ModuleId::ENCODE
+                        | ModuleId::DECODE
+                        | ModuleId::DICT
+                        | ModuleId::SET
+                        | ModuleId::HASH
                 );
 
                 if !name.is_builtin() || should_include_builtin {
@@ -4664,6 +4896,8 @@ fn canonicalize_and_constrain<'a>(
         }
     }
 
+    let symbol_index = ModuleSymbolIndex::from_declarations(&module_output.declarations);
+
     let module = Module {
         module_id,
         exposed_imports: module_output.exposed_imports,
@@ -4693,6 +4927,7 @@ fn canonicalize_and_constrain<'a>(
         constrained_module,
         canonicalization_problems: module_output.problems,
         module_docs,
+        symbol_index,
     }
 }
 
@@ -4701,14 +4936,15 @@ fn parse<'a>(arena: &'a Bump, header: ModuleHeader<'a>) -> Result<Msg<'a>, Loadi
     let parse_start = Instant::now();
     let source = header.parse_state.original_bytes();
     let parse_state = header.parse_state;
-    let parsed_defs = match module_defs().parse(arena, parse_state) {
-        Ok((_, success, _state)) => success,
-        Err((_, fail, state)) => {
-            return Err(LoadingProblem::ParsingFailed(
-                fail.into_file_error(header.module_path, &state),
-            ));
-        }
-    };
+
+    // Syntax errors in one def should not hide the problems in all the others, so instead of
+    // failing, keep going with a malformed placeholder for each def that did not parse.
+    let (parsed_defs, syntax_errors) = module_defs_with_recovery(arena, parse_state.clone());
+
+    let syntax_problems = syntax_errors
+        .into_iter()
+        .map(|fail| fail.into_file_error(header.module_path.clone(), &parse_state))
+        .collect();
 
     // Record the parse end time once, to avoid checking the time a second time
     // immediately afterward (for the beginning of canonicalization).
@@ -4746,6 +4982,7 @@ fn parse<'a>(arena: &'a Bump, header: ModuleHeader<'a>) -> Result<Msg<'a>, Loadi
         exposed_ident_ids,
         exposed_imports,
         parsed_defs,
+        syntax_problems,
         symbols_from_requires,
         header_for,
     };
@@ -4985,6 +5222,7 @@ fn build_pending_specializations<'a>(
                     body_var: expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
+                    region: body.region,
                 };
 
                 procs_base.partial_procs.insert(symbol, proc);
@@ -5123,6 +5361,7 @@ fn build_pending_specializations<'a>(
                     body_var: expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
+                    region: body.region,
                 };
 
                 procs_base.partial_procs.insert(symbol, proc);
@@ -5192,6 +5431,7 @@ fn build_pending_specializations<'a>(
                     body_var: expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
+                    region: body.region,
                 };
 
                 // extend the region of the expect expression with the region of the preceding
@@ -5265,6 +5505,7 @@ fn build_pending_specializations<'a>(
                     body_var: expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
+                    region: body.region,
                 };
 
                 // extend the region of the expect expression with the region of the preceding
@@ -5385,6 +5626,8 @@ fn load_derived_partial_procs<'a>(
                     body_var: derived_expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
+                    // Derived code has no source to point to
+                    region: Region::zero(),
                 }
             }
         };
@@ -5464,6 +5707,7 @@ fn run_task<'a>(
             declarations,
             dep_idents,
             cached_subs,
+            cache_slot,
             derived_module,
         } => Ok(run_solve(
             module,
@@ -5477,6 +5721,7 @@ fn run_task<'a>(
             declarations,
             dep_idents,
             cached_subs,
+            cache_slot,
             derived_module,
         )),
         BuildPendingSpecializations {
@@ -5542,7 +5787,7 @@ fn run_task<'a>(
     Ok(())
 }
 
-fn to_file_problem_report(filename: &Path, error: io::ErrorKind) -> String {
+fn to_file_problem_report(filename: &Path, error: io::ErrorKind, render: RenderTarget) -> String {
     use roc_reporting::report::{Report, RocDocAllocator, Severity, DEFAULT_PALETTE};
     use ven_pretty::DocAllocator;
 
@@ -5614,7 +5859,7 @@ fn to_file_problem_report(filename: &Path, error: io::ErrorKind) -> String {
 
     let mut buf = String::new();
     let palette = DEFAULT_PALETTE;
-    report.render_color_terminal(&mut buf, &alloc, &palette);
+    report.render(render, &mut buf, &alloc, &palette);
 
     buf
 }
@@ -5663,7 +5908,47 @@ fn to_parse_problem_report<'a>(
     buf
 }
 
-fn to_missing_platform_report(module_id: ModuleId, other: PlatformPath) -> String {
+/// Render the syntax errors the parser recovered from, like a failed parse would be
+fn render_syntax_problems<'a>(
+    syntax_problems: MutMap<ModuleId, Vec<FileError<'a, SyntaxError<'a>>>>,
+    sources: &MutMap<ModuleId, (PathBuf, &'a str)>,
+    interns: &Interns,
+    render: RenderTarget,
+) -> MutMap<ModuleId, Vec<String>> {
+    use roc_reporting::report::{parse_problem, RocDocAllocator, DEFAULT_PALETTE};
+
+    let mut rendered = MutMap::default();
+
+    for (module_id, problems) in syntax_problems {
+        let (_, src) = sources[&module_id];
+        let src_lines: Vec<&str> = src.lines().collect();
+        let lines = LineInfo::new(src);
+
+        let alloc = RocDocAllocator::new(&src_lines, module_id, interns);
+
+        let reports = problems
+            .into_iter()
+            .map(|problem| {
+                let report = parse_problem(&alloc, &lines, problem.filename.clone(), 0, problem);
+
+                let mut buf = String::new();
+                report.render(render, &mut buf, &alloc, &DEFAULT_PALETTE);
+
+                buf
+            })
+            .collect();
+
+        rendered.insert(module_id, reports);
+    }
+
+    rendered
+}
+
+fn to_missing_platform_report(
+    module_id: ModuleId,
+    other: PlatformPath,
+    render: RenderTarget,
+) -> String {
     use roc_reporting::report::{Report, RocDocAllocator, Severity, DEFAULT_PALETTE};
     use ven_pretty::DocAllocator;
     use PlatformPath::*;
@@ -5742,7 +6027,7 @@ fn to_missing_platform_report(module_id: ModuleId, other: PlatformPath) -> Strin
 
     let palette = DEFAULT_PALETTE;
     let mut buf = String::new();
-    report.render_color_terminal(&mut buf, &alloc, &palette);
+    report.render(render, &mut buf, &alloc, &palette);
 
     buf
 }
@@ -17,12 +17,13 @@ mod helpers;
 use crate::helpers::fixtures_dir;
 use bumpalo::Bump;
 use roc_can::module::ExposedByModule;
+use roc_can::symbol_index::Occurrence;
 use roc_load_internal::file::{ExecutionMode, LoadConfig, Threading};
 use roc_load_internal::file::{LoadResult, LoadStart, LoadedModule, LoadingProblem};
 use roc_module::ident::ModuleName;
 use roc_module::symbol::{Interns, ModuleId};
 use roc_problem::can::Problem;
-use roc_region::all::LineInfo;
+use roc_region::all::{LineColumn, LineInfo};
 use roc_reporting::report::can_problem;
 use roc_reporting::report::RenderTarget;
 use roc_reporting::report::RocDocAllocator;
@@ -37,6 +38,7 @@ fn load_and_typecheck(
     filename: PathBuf,
     exposed_types: ExposedByModule,
     target_info: TargetInfo,
+    cache_dir: Option<PathBuf>,
 ) -> Result<LoadedModule, LoadingProblem> {
     use LoadResult::*;
 
@@ -46,6 +48,7 @@ fn load_and_typecheck(
         render: RenderTarget::Generic,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Check,
+        cache_dir,
     };
 
     match roc_load_internal::file::load(
@@ -102,6 +105,16 @@ fn multiple_modules(subdir: &str, files: Vec<(&str, &str)>) -> Result<LoadedModu
             let home = loaded_module.module_id;
             let (filepath, src) = loaded_module.sources.get(&home).unwrap();
 
+            // A syntax error in any module (e.g. the platform) fails the load here.
+            let syntax_reports: Vec<String> = loaded_module
+                .syntax_problems
+                .drain()
+                .flat_map(|(_, reports)| reports)
+                .collect();
+            if !syntax_reports.is_empty() {
+                return Err(syntax_reports.concat());
+            }
+
             let can_problems = loaded_module.can_problems.remove(&home).unwrap_or_default();
             if !can_problems.is_empty() {
                 return Err(format_can_problems(
@@ -165,7 +178,7 @@ fn multiple_modules_help<'a>(
         writeln!(file, "{}", source)?;
         file_handles.push(file);
 
-        load_and_typecheck(arena, full_file_path, Default::default(), TARGET_INFO)
+        load_and_typecheck(arena, full_file_path, Default::default(), TARGET_INFO, None)
     };
 
     Ok(result)
@@ -179,7 +192,7 @@ fn load_fixture(
     let src_dir = fixtures_dir().join(dir_name);
     let filename = src_dir.join(format!("{}.roc", module_name));
     let arena = Bump::new();
-    let loaded = load_and_typecheck(&arena, filename, subs_by_module, TARGET_INFO);
+    let loaded = load_and_typecheck(&arena, filename, subs_by_module, TARGET_INFO, None);
     let mut loaded_module = match loaded {
         Ok(x) => x,
         Err(roc_load_internal::file::LoadingProblem::FormattedReport(report)) => {
@@ -339,7 +352,7 @@ fn interface_with_deps() {
     let src_dir = fixtures_dir().join("interface_with_deps");
     let filename = src_dir.join("Primary.roc");
     let arena = Bump::new();
-    let loaded = load_and_typecheck(&arena, filename, subs_by_module, TARGET_INFO);
+    let loaded = load_and_typecheck(&arena, filename, subs_by_module, TARGET_INFO, None);
 
     let mut loaded_module = loaded.expect("Test module failed to load");
     let home = loaded_module.module_id;
@@ -381,6 +394,99 @@ fn interface_with_deps() {
     assert_eq!(def_count, 10);
 }
 
+#[test]
+fn solved_modules_are_cached() {
+    let dir = roc_test_utils::TmpDir::new("tmp/solved_modules_are_cached");
+    let cache_dir = dir.path().join("cache");
+
+    let write = |name: &str, source: &str| std::fs::write(dir.path().join(name), source).unwrap();
+    let load = || {
+        let arena = Bump::new();
+        let filename = dir.path().join("Main.roc");
+
+        load_and_typecheck(
+            &arena,
+            filename,
+            Default::default(),
+            TARGET_INFO,
+            Some(cache_dir.clone()),
+        )
+        .unwrap()
+    };
+    let cache_entries = || {
+        let mut entries = Vec::new();
+
+        for module_dir in std::fs::read_dir(&cache_dir).unwrap() {
+            for entry in std::fs::read_dir(module_dir.unwrap().path()).unwrap() {
+                entries.push(entry.unwrap().path());
+            }
+        }
+
+        entries.sort();
+        entries
+    };
+
+    write(
+        "Dep.roc",
+        indoc!(
+            r#"
+            interface Dep exposes [description] imports []
+
+            Describe has describe : a -> Str | a has Describe
+
+            Id := U64 has [Describe {describe: describeId}]
+
+            describeId = \@Id _ -> "an id"
+
+            description = describe (@Id 1)
+            "#
+        ),
+    );
+    write(
+        "Main.roc",
+        indoc!(
+            r#"
+            interface Main exposes [main] imports [Dep]
+
+            main = Dep.description
+            "#
+        ),
+    );
+
+    expect_types(load(), hashmap! { "main" => "Str" });
+    let first_entries = cache_entries();
+    assert_eq!(first_entries.len(), 2);
+
+    // now both modules come from the cache
+    expect_types(load(), hashmap! { "main" => "Str" });
+    assert_eq!(cache_entries(), first_entries);
+
+    // Main did not change, but what it imports did, so it has to be solved again
+    write(
+        "Dep.roc",
+        indoc!(
+            r#"
+            interface Dep exposes [description] imports []
+
+            Describe has describe : a -> U64 | a has Describe
+
+            Id := U64 has [Describe {describe: describeId}]
+
+            describeId = \@Id n -> n
+
+            description = describe (@Id 1)
+            "#
+        ),
+    );
+
+    expect_types(load(), hashmap! { "main" => "U64" });
+
+    // the new entries replace the old ones
+    let entries = cache_entries();
+    assert_eq!(entries.len(), 2);
+    assert!(entries.iter().all(|entry| !first_entries.contains(entry)));
+}
+
 #[test]
 fn load_unit() {
     let subs_by_module = Default::default();
@@ -481,12 +587,12 @@ fn load_astar() {
     expect_types(
         loaded_module,
         hashmap! {
-            "findPath" => "{ costFunction : position, position -> F64, end : position, moveFunction : position -> Set position, start : position } -> Result (List position) [KeyNotFound]*",
-            "initialModel" => "position -> Model position",
-            "reconstructPath" => "Dict position position, position -> List position",
-            "updateCost" => "position, position, Model position -> Model position",
-            "cheapestOpen" => "(position -> F64), Model position -> Result position [KeyNotFound]*",
-            "astar" => "(position, position -> F64), (position -> Set position), position, Model position -> [Err [KeyNotFound]*, Ok (List position)]*",
+            "findPath" => "{ costFunction : position, position -> F64, end : position, moveFunction : position -> Set position, start : position } -> Result (List position) [KeyNotFound]* | position has Hash",
+            "initialModel" => "position -> Model position | position has Hash",
+            "reconstructPath" => "Dict position position, position -> List position | position has Hash",
+            "updateCost" => "position, position, Model position -> Model position | position has Hash",
+            "cheapestOpen" => "(position -> F64), Model position -> Result position [KeyNotFound]* | position has Hash",
+            "astar" => "(position, position -> F64), (position -> Set position), position, Model position -> [Err [KeyNotFound]*, Ok (List position)]* | position has Hash",
         },
     );
 }
@@ -872,3 +978,81 @@ fn issue_2863_module_type_does_not_exist() {
         Ok(_) => unreachable!("we expect failure here"),
     }
 }
+
+#[test]
+fn symbol_index_across_modules() {
+    let modules = vec![
+        (
+            "Dep",
+            indoc!(
+                r#"
+                    interface Dep exposes [double] imports []
+
+                    double = \n -> n * 2
+                    "#
+            ),
+        ),
+        (
+            "Main",
+            indoc!(
+                r#"
+                    interface Main exposes [four, eight] imports [Dep.{ double }]
+
+                    four = double 2
+
+                    eight = Dep.double four
+                    "#
+            ),
+        ),
+    ];
+
+    let loaded_module = multiple_modules("symbol_index_across_modules", modules).unwrap();
+    let home = loaded_module.module_id;
+    let index = &loaded_module.symbol_index;
+
+    // (module, line, start column, end column), all zero-based
+    let describe = |occurrence: Occurrence| {
+        let (_, src) = &loaded_module.sources[&occurrence.module_id];
+        let region = LineInfo::new(src).convert_region(occurrence.region);
+        let module_name = occurrence.module_id.to_ident_str(&loaded_module.interns);
+
+        (
+            module_name.as_str().to_string(),
+            region.start.line,
+            region.start.column,
+            region.end.column,
+        )
+    };
+
+    let (_, home_src) = &loaded_module.sources[&home];
+    let position = LineInfo::new(home_src).convert_line_column(LineColumn { line: 2, column: 8 });
+    let double = index.symbol_at(home, position).unwrap();
+
+    assert_eq!(
+        index.definition(double).map(describe),
+        Some(("Dep".to_string(), 2, 0, 6))
+    );
+    assert_eq!(
+        index
+            .references(double)
+            .iter()
+            .copied()
+            .map(describe)
+            .collect::<Vec<_>>(),
+        vec![
+            ("Main".to_string(), 2, 7, 13),
+            ("Main".to_string(), 4, 8, 18),
+        ]
+    );
+
+    let position = LineInfo::new(home_src).convert_line_column(LineColumn {
+        line: 4,
+        column: 20,
+    });
+    let four = index.symbol_at(home, position).unwrap();
+
+    assert_eq!(
+        index.definition(four).map(describe),
+        Some(("Main".to_string(), 2, 0, 4))
+    );
+}
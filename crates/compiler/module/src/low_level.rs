@@ -98,6 +98,7 @@ pub enum LowLevel {
     NumToFloatCast,
     NumToIntChecked,
     NumToFloatChecked,
+    NumToBits,
     NumToStr,
     Eq,
     NotEq,
@@ -105,6 +106,7 @@ pub enum LowLevel {
     Or,
     Not,
     Hash,
+    HashU64,
     PtrCast,
     RefCountInc,
     RefCountDec,
@@ -205,9 +207,9 @@ macro_rules! map_symbol_to_lowlevel {
                 LowLevel::NumToFloatCast => unreachable!(),
                 LowLevel::NumToIntChecked => unreachable!(),
                 LowLevel::NumToFloatChecked => unreachable!(),
+                LowLevel::NumToBits => unreachable!(),
 
                 // these are used internally and not tied to a symbol
-                LowLevel::Hash => unimplemented!(),
                 LowLevel::PtrCast => unimplemented!(),
                 LowLevel::RefCountInc => unimplemented!(),
                 LowLevel::RefCountDec => unimplemented!(),
@@ -308,10 +310,12 @@ map_symbol_to_lowlevel! {
     NumShiftRightBy <= NUM_SHIFT_RIGHT,
     NumShiftRightZfBy <= NUM_SHIFT_RIGHT_ZERO_FILL,
     NumToStr <= NUM_TO_STR,
-    Eq <= BOOL_EQ,
-    NotEq <= BOOL_NEQ,
+    Eq <= BOOL_STRUCTURAL_EQ,
+    NotEq <= BOOL_STRUCTURAL_NOT_EQ,
     And <= BOOL_AND,
     Or <= BOOL_OR,
     Not <= BOOL_NOT,
+    Hash <= DICT_HASH_BYTES_LOWLEVEL,
+    HashU64 <= DICT_HASH_U64_LOWLEVEL,
     Unreachable <= LIST_UNREACHABLE,
 }
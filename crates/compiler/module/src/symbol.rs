@@ -50,8 +50,16 @@ const PRETTY_PRINT_DEBUG_SYMBOLS: bool = true;
 pub const DERIVABLE_ABILITIES: &[(Symbol, &[Symbol])] = &[
     (Symbol::ENCODE_ENCODING, &[Symbol::ENCODE_TO_ENCODER]),
     (Symbol::DECODE_DECODING, &[Symbol::DECODE_DECODER]),
+    (Symbol::HASH_HASH_ABILITY, &[Symbol::HASH_HASH]),
 ];
 
+/// Pairs of abilities where every type that has the first ability has the second one as well.
+///
+/// A type variable can only be bound to one ability, so this is how the keys of a `Dict`, which
+/// are bound to `Hash`, can also be compared with `Eq`.
+pub const IMPLIED_ABILITIES: &[(Symbol, Symbol)] =
+    &[(Symbol::HASH_HASH_ABILITY, Symbol::BOOL_EQ_ABILITY)];
+
 /// In Debug builds only, Symbol has a name() method that lets
 /// you look up its name in a global intern table. This table is
 /// behind a mutex, so it is neither populated nor available in release builds.
@@ -97,6 +105,12 @@ impl Symbol {
         DERIVABLE_ABILITIES.iter().find(|(name, _)| *name == self)
     }
 
+    /// Whether a type that has this ability also has the `other` ability. Every ability implies
+    /// itself.
+    pub fn implies_ability(self, other: Symbol) -> bool {
+        self == other || IMPLIED_ABILITIES.contains(&(self, other))
+    }
+
     pub fn module_string<'a>(&self, interns: &'a Interns) -> &'a ModuleName {
         interns
             .module_ids
@@ -1020,10 +1034,11 @@ define_builtins! {
         28 DEV_TMP3: "#dev_tmp3"
         29 DEV_TMP4: "#dev_tmp4"
         30 DEV_TMP5: "#dev_tmp5"
+        31 DEV_TMP6: "#dev_tmp6"
 
-        31 ATTR_INVALID: "#attr_invalid"
+        32 ATTR_INVALID: "#attr_invalid"
 
-        32 CLONE: "#clone" // internal function that clones a value into a buffer
+        33 CLONE: "#clone" // internal function that clones a value into a buffer
     }
     // Fake module for synthesizing and storing derived implementations
     1 DERIVED_SYNTH: "#Derived" => {
@@ -1191,6 +1206,9 @@ define_builtins! {
         6 BOOL_XOR: "xor"
         7 BOOL_EQ: "isEq"
         8 BOOL_NEQ: "isNotEq"
+        9 BOOL_EQ_ABILITY: "Eq"
+        10 BOOL_STRUCTURAL_EQ: "structuralEq"
+        11 BOOL_STRUCTURAL_NOT_EQ: "structuralNotEq"
     }
     5 STR: "Str" => {
         0 STR_STR: "Str" imported // the Str.Str type alias
@@ -1359,6 +1377,9 @@ define_builtins! {
 
         15 DICT_WITH_CAPACITY: "withCapacity"
         16 DICT_CAPACITY: "capacity"
+        17 DICT_HASH_BYTES_LOWLEVEL: "hashBytesLowlevel"
+        18 DICT_IS_EQ: "isEq"
+        19 DICT_HASH_U64_LOWLEVEL: "hashU64Lowlevel"
     }
     9 SET: "Set" => {
         0 SET_SET: "Set" // the Set.Set type alias
@@ -1377,6 +1398,7 @@ define_builtins! {
         13 SET_CONTAINS: "contains"
         14 SET_TO_DICT: "toDict"
         15 SET_CAPACITY: "capacity"
+        16 SET_IS_EQ: "isEq"
     }
     10 BOX: "Box" => {
         0 BOX_BOX_TYPE: "Box" imported // the Box.Box opaque type
@@ -1435,14 +1457,46 @@ define_builtins! {
         20 DECODE_STRING: "string"
         21 DECODE_LIST: "list"
         22 DECODE_RECORD: "record"
-        23 DECODE_CUSTOM: "custom"
-        24 DECODE_DECODE_WITH: "decodeWith"
-        25 DECODE_FROM_BYTES_PARTIAL: "fromBytesPartial"
-        26 DECODE_FROM_BYTES: "fromBytes"
+        23 DECODE_TAG: "tag"
+        24 DECODE_CUSTOM: "custom"
+        25 DECODE_DECODE_WITH: "decodeWith"
+        26 DECODE_FROM_BYTES_PARTIAL: "fromBytesPartial"
+        27 DECODE_FROM_BYTES: "fromBytes"
+        28 DECODE_DECODE_PATH_SEGMENT: "DecodePathSegment"
+        29 DECODE_EXPECTED: "expected"
+        30 DECODE_MISSING: "missing"
+        31 DECODE_PREPEND_PATH: "prependPath"
+        32 DECODE_ERROR_TO_STR: "errorToStr"
     }
     13 JSON: "Json" => {
         0 JSON_JSON: "Json"
     }
-
-    num_modules: 14 // Keep this count up to date by hand! (TODO: see the mut_map! macro for how we could determine this count correctly in the macro)
+    14 HASH: "Hash" => {
+        0 HASH_HASH_ABILITY: "Hash"
+        1 HASH_HASH: "hash"
+        2 HASH_HASHER: "Hasher"
+        3 HASH_ADD_BYTES: "addBytes"
+        4 HASH_ADD_U8: "addU8"
+        5 HASH_ADD_U16: "addU16"
+        6 HASH_ADD_U32: "addU32"
+        7 HASH_ADD_U64: "addU64"
+        8 HASH_ADD_U128: "addU128"
+        9 HASH_COMPLETE: "complete"
+        10 HASH_HASH_I8: "hashI8"
+        11 HASH_HASH_I16: "hashI16"
+        12 HASH_HASH_I32: "hashI32"
+        13 HASH_HASH_I64: "hashI64"
+        14 HASH_HASH_I128: "hashI128"
+        15 HASH_HASH_NAT: "hashNat"
+        16 HASH_HASH_STR_BYTES: "hashStrBytes"
+        17 HASH_HASH_LIST: "hashList"
+        18 HASH_HASH_F32: "hashF32"
+        19 HASH_HASH_F64: "hashF64"
+        20 HASH_HASH_DEC: "hashDec"
+        21 HASH_F32_TO_BITS_LOWLEVEL: "f32ToBitsLowlevel"
+        22 HASH_F64_TO_BITS_LOWLEVEL: "f64ToBitsLowlevel"
+        23 HASH_DEC_TO_BITS_LOWLEVEL: "decToBitsLowlevel"
+    }
+
+    num_modules: 15 // Keep this count up to date by hand! (TODO: see the mut_map! macro for how we could determine this count correctly in the macro)
 }
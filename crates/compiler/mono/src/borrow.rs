@@ -310,6 +310,7 @@ impl<'a> ParamMap<'a> {
 
                 Expect { remainder, .. } => stack.push(remainder),
                 ExpectFx { remainder, .. } => stack.push(remainder),
+                Dbg { remainder, .. } => stack.push(remainder),
 
                 Switch {
                     branches,
@@ -825,6 +826,10 @@ impl<'a> BorrowInfState<'a> {
                 self.collect_stmt(param_map, remainder);
             }
 
+            Dbg { remainder, .. } => {
+                self.collect_stmt(param_map, remainder);
+            }
+
             Refcounting(_, _) => unreachable!("these have not been introduced yet"),
 
             Ret(_) | RuntimeError(_) => {
@@ -920,9 +925,8 @@ pub fn lowlevel_borrow_signature(arena: &Bump, op: LowLevel) -> &[bool] {
 
         NumToStr | NumAbs | NumNeg | NumSin | NumCos | NumSqrtUnchecked | NumLogUnchecked
         | NumRound | NumCeiling | NumFloor | NumToFrac | Not | NumIsFinite | NumAtan | NumAcos
-        | NumAsin | NumIntCast | NumToIntChecked | NumToFloatCast | NumToFloatChecked => {
-            arena.alloc_slice_copy(&[irrelevant])
-        }
+        | NumAsin | NumIntCast | NumToIntChecked | NumToFloatCast | NumToFloatChecked
+        | NumToBits => arena.alloc_slice_copy(&[irrelevant]),
         NumBytesToU16 => arena.alloc_slice_copy(&[borrowed, irrelevant]),
         NumBytesToU32 => arena.alloc_slice_copy(&[borrowed, irrelevant]),
         StrStartsWith | StrEndsWith => arena.alloc_slice_copy(&[borrowed, borrowed]),
@@ -932,6 +936,7 @@ pub fn lowlevel_borrow_signature(arena: &Bump, op: LowLevel) -> &[bool] {
         StrRepeat => arena.alloc_slice_copy(&[borrowed, irrelevant]),
         StrFromInt | StrFromFloat => arena.alloc_slice_copy(&[irrelevant]),
         Hash => arena.alloc_slice_copy(&[borrowed, irrelevant]),
+        HashU64 => arena.alloc_slice_copy(&[irrelevant, irrelevant]),
 
         ListIsUnique => arena.alloc_slice_copy(&[borrowed]),
 
@@ -994,6 +999,7 @@ fn call_info_stmt<'a>(arena: &'a Bump, stmt: &Stmt<'a>, info: &mut CallInfo<'a>)
 
             Expect { remainder, .. } => stack.push(remainder),
             ExpectFx { remainder, .. } => stack.push(remainder),
+            Dbg { remainder, .. } => stack.push(remainder),
 
             Refcounting(_, _) => unreachable!("these have not been introduced yet"),
 
@@ -1,10 +1,11 @@
 use crate::ir::{
-    BranchInfo, DestructType, Env, Expr, JoinPointId, Literal, Param, Pattern, Procs, Stmt,
+    BranchInfo, DestructType, Env, Expr, JoinPointId, ListIndex, Literal, Param, Pattern, Procs,
+    Stmt,
 };
 use crate::layout::{Builtin, Layout, LayoutCache, TagIdIntType, UnionLayout};
 use roc_builtins::bitcode::{FloatWidth, IntWidth};
 use roc_collections::all::{MutMap, MutSet};
-use roc_exhaustive::{Ctor, CtorName, RenderAs, TagId, Union};
+use roc_exhaustive::{Ctor, CtorName, ListArity, RenderAs, TagId, Union};
 use roc_module::ident::TagName;
 use roc_module::low_level::LowLevel;
 use roc_module::symbol::Symbol;
@@ -13,6 +14,7 @@ use roc_module::symbol::Symbol;
 
 type Label = u64;
 const RECORD_TAG_NAME: &str = "#Record";
+const TUPLE_TAG_NAME: &str = "#Tuple";
 
 /// Users of this module will mainly interact with this function. It takes
 /// some normal branches and gives out a decision tree that has "labels" at all
@@ -94,6 +96,18 @@ enum Test<'a> {
         tag_id: TagIdIntType,
         num_alts: usize,
     },
+    IsListLen {
+        bound: ListLenBound,
+        len: u64,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum ListLenBound {
+    /// The list has exactly this length
+    Exact,
+    /// The list has at least this length
+    AtLeast,
 }
 
 impl<'a> Test<'a> {
@@ -109,6 +123,7 @@ impl<'a> Test<'a> {
             Test::IsStr(_) => false,
             Test::IsBit(_) => true,
             Test::IsByte { .. } => true,
+            Test::IsListLen { .. } => false,
         }
     }
 }
@@ -152,6 +167,11 @@ impl<'a> Hash for Test<'a> {
                 state.write_u8(6);
                 v.hash(state);
             }
+            IsListLen { bound, len } => {
+                state.write_u8(7);
+                bound.hash(state);
+                len.hash(state);
+            }
         }
     }
 }
@@ -330,6 +350,7 @@ fn tests_are_complete_help(last_test: &Test, number_of_tests: usize) -> bool {
         Test::IsFloat(_, _) => false,
         Test::IsDecimal(_) => false,
         Test::IsStr(_) => false,
+        Test::IsListLen { bound, len } => *bound == ListLenBound::AtLeast && *len == 0,
     }
 }
 
@@ -436,8 +457,19 @@ fn gather_edges<'a>(
     let check = guarded_tests_are_complete(&relevant_tests);
 
     let all_edges = relevant_tests
-        .into_iter()
-        .map(|t| edges_for(path, &branches, t))
+        .iter()
+        .enumerate()
+        .map(|(index, test)| {
+            let (test, mut edge_branches) = edges_for(path, &branches, test.clone());
+
+            // edges are tried in order, so a branch whose test failed on an earlier edge can
+            // not match on this one. List length tests do not exclude each other, so they
+            // can leave such branches behind.
+            let earlier_tests = &relevant_tests[..index];
+            edge_branches.retain(|branch| !failed_earlier_test(path, branch, earlier_tests));
+
+            (test, edge_branches)
+        })
         .collect();
 
     let fallbacks = if check {
@@ -452,6 +484,23 @@ fn gather_edges<'a>(
     (all_edges, fallbacks)
 }
 
+fn failed_earlier_test(
+    path: &[PathInstruction],
+    branch: &Branch,
+    earlier_tests: &[GuardedTest],
+) -> bool {
+    let has_list_pattern = branch
+        .patterns
+        .iter()
+        .any(|(p, pattern)| p == path && matches!(pattern, Pattern::List { .. }));
+
+    has_list_pattern
+        && match test_at_path(path, branch) {
+            Some(test) => earlier_tests.contains(&test),
+            None => false,
+        }
+}
+
 /// FIND RELEVANT TESTS
 
 fn tests_at_path<'a>(
@@ -488,9 +537,44 @@ fn tests_at_path<'a>(
         }
     }
 
+    sort_list_len_tests(&mut unique);
+
     unique
 }
 
+/// Within every run of list length tests, test exact lengths first, and then the lower bounds
+/// from largest to smallest. A list then takes the edge of the most specific test it passes.
+///
+/// Reordering is fine because list length tests never consume patterns that a later test could
+/// still match (see [to_relevant_branch_help]); the order only avoids redundant tests.
+fn sort_list_len_tests(tests: &mut [GuardedTest]) {
+    fn sort_key(test: &GuardedTest) -> (u8, std::cmp::Reverse<u64>) {
+        match test {
+            GuardedTest::TestNotGuarded {
+                test: Test::IsListLen { bound, len },
+            } => match bound {
+                ListLenBound::Exact => (0, std::cmp::Reverse(0)),
+                ListLenBound::AtLeast => (1, std::cmp::Reverse(*len)),
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    let is_list_len_test = |test: &GuardedTest| {
+        matches!(
+            test,
+            GuardedTest::TestNotGuarded {
+                test: Test::IsListLen { .. }
+            }
+        )
+    };
+
+    for run in tests.split_mut(|test| !is_list_len_test(test)) {
+        // a stable sort, so exact tests keep their relative order
+        run.sort_by_key(sort_key);
+    }
+}
+
 fn test_at_path<'a>(
     selected_path: &[PathInstruction],
     branch: &Branch<'a>,
@@ -548,6 +632,31 @@ fn test_at_path<'a>(
                     }
                 }
 
+                TupleDestructure(destructs, _) => {
+                    // not rendered, so pick the easiest
+                    let union = Union {
+                        render_as: RenderAs::Tag,
+                        alternatives: vec![Ctor {
+                            tag_id: TagId(0),
+                            name: CtorName::Tag(TagName(TUPLE_TAG_NAME.into())),
+                            arity: destructs.len(),
+                        }],
+                    };
+
+                    let mut arguments = std::vec::Vec::new();
+
+                    for destruct in destructs {
+                        arguments.push((destruct.pat.clone(), destruct.layout));
+                    }
+
+                    IsCtor {
+                        tag_id: 0,
+                        ctor_name: CtorName::Tag(TagName(TUPLE_TAG_NAME.into())),
+                        union,
+                        arguments,
+                    }
+                }
+
                 NewtypeDestructure {
                     tag_name,
                     arguments,
@@ -604,6 +713,14 @@ fn test_at_path<'a>(
                 FloatLiteral(v, precision) => IsFloat(*v, *precision),
                 DecimalLiteral(v) => IsDecimal(*v),
                 StrLiteral(v) => IsStr(v.clone()),
+
+                List { arity, .. } => IsListLen {
+                    bound: match arity {
+                        ListArity::Exact(_) => ListLenBound::Exact,
+                        ListArity::Slice(_, _) => ListLenBound::AtLeast,
+                    },
+                    len: arity.min_len() as u64,
+                },
             };
 
             let guarded_test = GuardedTest::TestNotGuarded { test };
@@ -727,6 +844,42 @@ fn to_relevant_branch_help<'a>(
             _ => None,
         },
 
+        TupleDestructure(destructs, _) => match test {
+            IsCtor {
+                ctor_name: test_name,
+                tag_id,
+                ..
+            } => {
+                debug_assert!(test_name == &CtorName::Tag(TagName(TUPLE_TAG_NAME.into())));
+                let destructs_len = destructs.len();
+                let sub_positions = destructs.into_iter().enumerate().map(|(index, destruct)| {
+                    let pattern = destruct.pat.clone();
+
+                    let mut new_path = path.to_vec();
+                    let next_instr = if destructs_len == 1 {
+                        PathInstruction::NewType
+                    } else {
+                        PathInstruction::TagIndex {
+                            index: index as u64,
+                            tag_id: *tag_id,
+                        }
+                    };
+                    new_path.push(next_instr);
+
+                    (new_path, pattern)
+                });
+                start.extend(sub_positions);
+                start.extend(end);
+
+                Some(Branch {
+                    goal: branch.goal,
+                    guard: branch.guard.clone(),
+                    patterns: start,
+                })
+            }
+            _ => None,
+        },
+
         OpaqueUnwrap { opaque, argument } => match test {
             IsCtor {
                 ctor_name: test_opaque_tag_name,
@@ -951,6 +1104,56 @@ fn to_relevant_branch_help<'a>(
 
             _ => None,
         },
+
+        List {
+            arity, elements, ..
+        } => match test {
+            IsListLen { bound, len } => {
+                let len = *len as usize;
+
+                let is_consumed = match bound {
+                    ListLenBound::Exact => arity.covers_length(len),
+                    // a list that is at least `len` long surely matches a rest pattern that needs
+                    // at most `len` elements. Any other length has not been ruled out yet.
+                    ListLenBound::AtLeast => {
+                        matches!(arity, ListArity::Slice(..)) && arity.min_len() <= len
+                    }
+                };
+
+                let can_still_match = match bound {
+                    ListLenBound::Exact => false,
+                    ListLenBound::AtLeast => match arity {
+                        ListArity::Exact(n) => n >= len,
+                        ListArity::Slice(..) => true,
+                    },
+                };
+
+                if is_consumed {
+                    let sub_positions = elements.into_iter().enumerate().map(|(index, pattern)| {
+                        let mut new_path = path.to_vec();
+                        new_path.push(PathInstruction::ListIndex(ListIndex::from_pattern_index(
+                            index, arity,
+                        )));
+                        (new_path, pattern)
+                    });
+                    start.extend(sub_positions);
+                    start.extend(end);
+
+                    Some(Branch {
+                        goal: branch.goal,
+                        guard: branch.guard.clone(),
+                        patterns: start,
+                    })
+                } else if can_still_match {
+                    // keep testing this pattern's length further down the tree
+                    Some(branch.clone())
+                } else {
+                    None
+                }
+            }
+
+            _ => None,
+        },
     }
 }
 
@@ -1010,6 +1213,7 @@ fn needs_tests(pattern: &Pattern) -> bool {
 
         NewtypeDestructure { .. }
         | RecordDestructure(..)
+        | TupleDestructure(..)
         | AppliedTag { .. }
         | OpaqueUnwrap { .. }
         | BitLiteral { .. }
@@ -1017,7 +1221,8 @@ fn needs_tests(pattern: &Pattern) -> bool {
         | IntLiteral(_, _)
         | FloatLiteral(_, _)
         | DecimalLiteral(_)
-        | StrLiteral(_) => true,
+        | StrLiteral(_)
+        | List { .. } => true,
     }
 }
 
@@ -1262,6 +1467,7 @@ pub fn optimize_when<'a>(
 enum PathInstruction {
     NewType,
     TagIndex { index: u64, tag_id: TagIdIntType },
+    ListIndex(ListIndex),
 }
 
 fn path_to_expr_help<'a>(
@@ -1331,19 +1537,46 @@ fn path_to_expr_help<'a>(
                     }
                 }
             }
+
+            PathInstruction::ListIndex(list_index) => {
+                let element_layout = match layout {
+                    Layout::Builtin(Builtin::List(element_layout)) => *element_layout,
+                    _ => unreachable!("list index into a non-list layout {:?}", layout),
+                };
+
+                let elem_symbol = env.unique_symbol();
+                stores.extend(crate::ir::list_index_stores(
+                    env,
+                    symbol,
+                    element_layout,
+                    *list_index,
+                    elem_symbol,
+                ));
+
+                symbol = elem_symbol;
+                layout = element_layout;
+            }
         }
     }
 
     (symbol, stores, layout)
 }
 
-fn test_to_equality<'a>(
+#[derive(Debug, Clone, Copy)]
+enum Comparator {
+    Eq,
+    Geq,
+}
+
+type Comparison = (Symbol, Comparator, Symbol);
+
+fn test_to_comparison<'a>(
     env: &mut Env<'a, '_>,
     cond_symbol: Symbol,
     cond_layout: &Layout<'a>,
     path: &[PathInstruction],
     test: Test<'a>,
-) -> (StoresVec<'a>, Symbol, Symbol, Option<ConstructorKnown<'a>>) {
+) -> (StoresVec<'a>, Comparison, Option<ConstructorKnown<'a>>) {
     let (rhs_symbol, mut stores, test_layout) =
         path_to_expr_help(env, cond_symbol, path, *cond_layout);
 
@@ -1373,8 +1606,7 @@ fn test_to_equality<'a>(
 
                     (
                         stores,
-                        lhs_symbol,
-                        rhs_symbol,
+                        (lhs_symbol, Comparator::Eq, rhs_symbol),
                         Some(ConstructorKnown::OnlyPass {
                             scrutinee: path_symbol,
                             layout: *cond_layout,
@@ -1391,7 +1623,7 @@ fn test_to_equality<'a>(
             let lhs_symbol = env.unique_symbol();
             stores.push((lhs_symbol, Layout::int_width(precision), lhs));
 
-            (stores, lhs_symbol, rhs_symbol, None)
+            (stores, (lhs_symbol, Comparator::Eq, rhs_symbol), None)
         }
 
         Test::IsFloat(test_int, precision) => {
@@ -1401,7 +1633,7 @@ fn test_to_equality<'a>(
             let lhs_symbol = env.unique_symbol();
             stores.push((lhs_symbol, Layout::float_width(precision), lhs));
 
-            (stores, lhs_symbol, rhs_symbol, None)
+            (stores, (lhs_symbol, Comparator::Eq, rhs_symbol), None)
         }
 
         Test::IsDecimal(test_dec) => {
@@ -1409,7 +1641,7 @@ fn test_to_equality<'a>(
             let lhs_symbol = env.unique_symbol();
             stores.push((lhs_symbol, *cond_layout, lhs));
 
-            (stores, lhs_symbol, rhs_symbol, None)
+            (stores, (lhs_symbol, Comparator::Eq, rhs_symbol), None)
         }
 
         Test::IsByte {
@@ -1421,7 +1653,7 @@ fn test_to_equality<'a>(
             let lhs_symbol = env.unique_symbol();
             stores.push((lhs_symbol, Layout::u8(), lhs));
 
-            (stores, lhs_symbol, rhs_symbol, None)
+            (stores, (lhs_symbol, Comparator::Eq, rhs_symbol), None)
         }
 
         Test::IsBit(test_bit) => {
@@ -1429,7 +1661,7 @@ fn test_to_equality<'a>(
             let lhs_symbol = env.unique_symbol();
             stores.push((lhs_symbol, Layout::Builtin(Builtin::Bool), lhs));
 
-            (stores, lhs_symbol, rhs_symbol, None)
+            (stores, (lhs_symbol, Comparator::Eq, rhs_symbol), None)
         }
 
         Test::IsStr(test_str) => {
@@ -1438,15 +1670,42 @@ fn test_to_equality<'a>(
 
             stores.push((lhs_symbol, Layout::Builtin(Builtin::Str), lhs));
 
-            (stores, lhs_symbol, rhs_symbol, None)
+            (stores, (lhs_symbol, Comparator::Eq, rhs_symbol), None)
+        }
+
+        Test::IsListLen { bound, len } => {
+            let list_sym = rhs_symbol;
+
+            let real_len_expr = Expr::Call(crate::ir::Call {
+                call_type: crate::ir::CallType::LowLevel {
+                    op: LowLevel::ListLen,
+                    update_mode: env.next_update_mode_id(),
+                },
+                arguments: env.arena.alloc([list_sym]),
+            });
+            let test_len_expr = Expr::Literal(Literal::Int((len as i128).to_ne_bytes()));
+
+            let real_len = env.unique_symbol();
+            let test_len = env.unique_symbol();
+
+            let usize_layout = Layout::usize(env.target_info);
+
+            stores.push((real_len, usize_layout, real_len_expr));
+            stores.push((test_len, usize_layout, test_len_expr));
+
+            let comparison = match bound {
+                ListLenBound::Exact => (real_len, Comparator::Eq, test_len),
+                ListLenBound::AtLeast => (real_len, Comparator::Geq, test_len),
+            };
+
+            (stores, comparison, None)
         }
     }
 }
 
 type Tests<'a> = std::vec::Vec<(
     bumpalo::collections::Vec<'a, (Symbol, Layout<'a>, Expr<'a>)>,
-    Symbol,
-    Symbol,
+    Comparison,
     Option<ConstructorKnown<'a>>,
 )>;
 
@@ -1460,7 +1719,13 @@ fn stores_and_condition<'a>(
 
     // Assumption: there is at most 1 guard, and it is the outer layer.
     for (path, test) in test_chain {
-        tests.push(test_to_equality(env, cond_symbol, cond_layout, &path, test))
+        tests.push(test_to_comparison(
+            env,
+            cond_symbol,
+            cond_layout,
+            &path,
+            test,
+        ))
     }
 
     tests
@@ -1470,8 +1735,7 @@ fn compile_test<'a>(
     env: &mut Env<'a, '_>,
     ret_layout: Layout<'a>,
     stores: bumpalo::collections::Vec<'a, (Symbol, Layout<'a>, Expr<'a>)>,
-    lhs: Symbol,
-    rhs: Symbol,
+    comparison: Comparison,
     fail: &'a Stmt<'a>,
     cond: Stmt<'a>,
 ) -> Stmt<'a> {
@@ -1480,8 +1744,7 @@ fn compile_test<'a>(
         ConstructorKnown::Neither,
         ret_layout,
         stores,
-        lhs,
-        rhs,
+        comparison,
         fail,
         cond,
     )
@@ -1493,8 +1756,7 @@ fn compile_test_help<'a>(
     branch_info: ConstructorKnown<'a>,
     ret_layout: Layout<'a>,
     stores: bumpalo::collections::Vec<'a, (Symbol, Layout<'a>, Expr<'a>)>,
-    lhs: Symbol,
-    rhs: Symbol,
+    (lhs, cmp, rhs): Comparison,
     fail: &'a Stmt<'a>,
     mut cond: Stmt<'a>,
 ) -> Stmt<'a> {
@@ -1554,7 +1816,10 @@ fn compile_test_help<'a>(
         default_branch,
     };
 
-    let op = LowLevel::Eq;
+    let op = match cmp {
+        Comparator::Eq => LowLevel::Eq,
+        Comparator::Geq => LowLevel::NumGte,
+    };
     let test = Expr::Call(crate::ir::Call {
         call_type: crate::ir::CallType::LowLevel {
             op,
@@ -1586,13 +1851,14 @@ fn compile_tests<'a>(
     fail: &'a Stmt<'a>,
     mut cond: Stmt<'a>,
 ) -> Stmt<'a> {
-    for (new_stores, lhs, rhs, opt_constructor_info) in tests.into_iter() {
+    for (new_stores, comparison, opt_constructor_info) in tests.into_iter() {
         match opt_constructor_info {
             None => {
-                cond = compile_test(env, ret_layout, new_stores, lhs, rhs, fail, cond);
+                cond = compile_test(env, ret_layout, new_stores, comparison, fail, cond);
             }
             Some(cinfo) => {
-                cond = compile_test_help(env, cinfo, ret_layout, new_stores, lhs, rhs, fail, cond);
+                cond =
+                    compile_test_help(env, cinfo, ret_layout, new_stores, comparison, fail, cond);
             }
         }
     }
@@ -1775,15 +2041,14 @@ fn decide_to_branching<'a>(
             if number_of_tests == 1 {
                 // if there is just one test, compile to a simple if-then-else
 
-                let (new_stores, lhs, rhs, _cinfo) = tests.into_iter().next().unwrap();
+                let (new_stores, comparison, _cinfo) = tests.into_iter().next().unwrap();
 
                 compile_test_help(
                     env,
                     chain_branch_info,
                     ret_layout,
                     new_stores,
-                    lhs,
-                    rhs,
+                    comparison,
                     fail,
                     pass_expr,
                 )
@@ -1850,6 +2115,7 @@ fn decide_to_branching<'a>(
                     Test::IsCtor { tag_id, .. } => tag_id as u64,
                     Test::IsDecimal(_) => unreachable!("decimals cannot be switched on"),
                     Test::IsStr(_) => unreachable!("strings cannot be switched on"),
+                    Test::IsListLen { .. } => unreachable!("list lengths cannot be switched on"),
                 };
 
                 // branch info is only useful for refcounted values
@@ -130,6 +130,13 @@ pub fn occurring_variables(stmt: &Stmt<'_>) -> (MutSet<Symbol>, MutSet<Symbol>)
                 stack.push(remainder);
             }
 
+            Dbg {
+                symbol, remainder, ..
+            } => {
+                result.insert(*symbol);
+                stack.push(remainder);
+            }
+
             Jump(_, arguments) => {
                 result.extend(arguments.iter().copied());
             }
@@ -560,7 +567,11 @@ impl<'a, 'i> Context<'a, 'i> {
                     arguments,
                 });
 
-                &*self.arena.alloc(Stmt::Let(z, v, l, b))
+                let b = self.arena.alloc(Stmt::Let(z, v, l, b));
+
+                // an owned argument that is still used afterwards must be incremented first
+                let pred = |i: usize| !ps[i];
+                self.add_inc_before_help(arguments, pred, b, b_live_vars)
             }
 
             HigherOrder(lowlevel) => {
@@ -1240,6 +1251,27 @@ impl<'a, 'i> Context<'a, 'i> {
                 (expect, b_live_vars)
             }
 
+            Dbg {
+                symbol,
+                region,
+                remainder,
+            } => {
+                let (b, mut b_live_vars) = self.visit_stmt(codegen, remainder);
+
+                // printing only reads the value, so `dbg` borrows its symbol
+                let b = self.add_dec_if_needed(*symbol, b, &b_live_vars);
+
+                let dbg = self.arena.alloc(Stmt::Dbg {
+                    symbol: *symbol,
+                    region: *region,
+                    remainder: b,
+                });
+
+                b_live_vars.insert(*symbol);
+
+                (dbg, b_live_vars)
+            }
+
             RuntimeError(_) | Refcounting(_, _) => (stmt, MutSet::default()),
         }
     }
@@ -1365,6 +1397,13 @@ pub fn collect_stmt(
             collect_stmt(remainder, jp_live_vars, vars)
         }
 
+        Dbg {
+            symbol, remainder, ..
+        } => {
+            vars.insert(*symbol);
+            collect_stmt(remainder, jp_live_vars, vars)
+        }
+
         Join {
             id: j,
             parameters,
@@ -0,0 +1,1066 @@
+//! Inline small procs into their callers, and fold the constants that this exposes.
+//!
+//! Many procs are tiny wrappers, e.g. `Num.add` is just a call to the `NumAdd` lowlevel. LLVM
+//! inlines those (and does much more), but the dev and wasm backends generate code for the mono IR
+//! as it is. This pass runs on the final IR, after refcounting operations have been inserted:
+//! inlining a body together with its `inc`s and `dec`s does exactly what the call would have done.
+//!
+//! e.g.
+//!
+//! ```text
+//! procedure Num.19 (#Attr.2, #Attr.3):
+//!     let Num.257 : I64 = lowlevel NumAdd #Attr.2 #Attr.3;
+//!     ret Num.257;
+//!
+//! procedure Test.0 ():
+//!     let Test.2 : I64 = 1i64;
+//!     let Test.3 : I64 = 2i64;
+//!     let Test.1 : I64 = CallByName Num.19 Test.2 Test.3;
+//!     ret Test.1;
+//! ```
+//!
+//! becomes
+//!
+//! ```text
+//! procedure Test.0 ():
+//!     let Test.1 : I64 = 3i64;
+//!     ret Test.1;
+//! ```
+
+use crate::inc_dec::occurring_variables_expr;
+use crate::ir::{
+    BranchInfo, Call, CallType, Expr, HostExposedLayouts, JoinPointId, ListLiteralElement, Literal,
+    ModifyRc, Param, Proc, ProcLayout, SelfRecursive, Stmt,
+};
+use crate::layout::{Builtin, Layout};
+use bumpalo::collections::Vec;
+use bumpalo::Bump;
+use roc_builtins::bitcode::IntWidth;
+use roc_collections::all::{MutMap, MutSet};
+use roc_module::low_level::LowLevel;
+use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+
+/// Procs with more statements than this are not inlined
+const INLINE_SIZE_LIMIT: usize = 12;
+
+pub fn inline_procs<'a, 'i>(
+    arena: &'a Bump,
+    home: ModuleId,
+    ident_ids: &'i mut IdentIds,
+    exposed_to_host: &MutSet<Symbol>,
+    procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) {
+    // Inline the bodies as they were before this pass; calls in those bodies are inlined
+    // recursively, as long as that does not recurse forever.
+    let candidates: MutMap<_, _> = procs
+        .iter()
+        .filter(|(_, proc)| is_inline_candidate(proc))
+        .map(|(key, proc)| (*key, proc.clone()))
+        .collect();
+
+    let mut env = Env {
+        arena,
+        home,
+        ident_ids,
+        candidates: &candidates,
+        inlining: std::vec::Vec::new(),
+        ret_layout: Layout::UNIT,
+    };
+
+    for proc in procs.values_mut() {
+        env.ret_layout = proc.ret_layout;
+        let body = inline_stmt(&mut env, arena.alloc(proc.body.clone()));
+        let body = fold_constants(arena, &mut MutMap::default(), body);
+
+        let mut used = MutSet::default();
+        symbol_uses(body, &mut used);
+
+        proc.body = remove_unused_literals(arena, &used, body).clone();
+    }
+
+    // The procs that were inlined everywhere they were called can be dropped
+    let mut called = MutSet::default();
+
+    for proc in procs.values() {
+        called_procs(&proc.body, &mut called);
+    }
+
+    let inlined: MutSet<Symbol> = candidates.keys().map(|(symbol, _)| *symbol).collect();
+
+    procs.retain(|(symbol, _), _| {
+        !inlined.contains(symbol) || called.contains(symbol) || exposed_to_host.contains(symbol)
+    });
+}
+
+struct Env<'a, 'i> {
+    arena: &'a Bump,
+
+    /// required for creating new `Symbol`s
+    home: ModuleId,
+    ident_ids: &'i mut IdentIds,
+
+    candidates: &'i MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+
+    /// The procs whose bodies are being inlined right now. (Mutually) recursive calls to these
+    /// stay calls.
+    inlining: std::vec::Vec<Symbol>,
+
+    /// The return layout of the proc that calls are inlined into. Inlined bodies that branch
+    /// now return from that proc.
+    ret_layout: Layout<'a>,
+}
+
+impl<'a, 'i> Env<'a, 'i> {
+    fn unique_symbol(&mut self) -> Symbol {
+        let ident_id = self.ident_ids.gen_unique();
+
+        Symbol::new(self.home, ident_id)
+    }
+
+    fn inlinable_callee(&self, call: &Call<'a>) -> Option<&'i Proc<'a>> {
+        let candidates: &'i MutMap<_, _> = self.candidates;
+
+        match &call.call_type {
+            CallType::ByName {
+                name,
+                ret_layout,
+                arg_layouts,
+                ..
+            } if !self.inlining.contains(&name.name()) => {
+                let proc_layout = ProcLayout {
+                    arguments: arg_layouts,
+                    result: **ret_layout,
+                    captures_niche: name.captures_niche(),
+                };
+
+                candidates.get(&(name.name(), proc_layout))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn is_inline_candidate(proc: &Proc) -> bool {
+    matches!(proc.is_self_recursive, SelfRecursive::NotSelfRecursive)
+        && matches!(
+            proc.host_exposed_layouts,
+            HostExposedLayouts::NotHostExposed
+        )
+        && inline_size(proc.name.name(), &proc.body).map_or(false, |size| size <= INLINE_SIZE_LIMIT)
+}
+
+/// The number of statements in the body of `name`, or `None` if it cannot be inlined. Join points
+/// and the statements that mention regions are never inlined, to keep renaming simple; neither are
+/// reset/reuse, whose update modes must be unique.
+fn inline_size(name: Symbol, stmt: &Stmt) -> Option<usize> {
+    use Stmt::*;
+
+    match stmt {
+        Let(_, expr, _, cont) => {
+            let inlinable = match expr {
+                Expr::Call(Call {
+                    call_type: CallType::ByName { name: callee, .. },
+                    ..
+                }) => callee.name() != name,
+                Expr::Call(Call {
+                    call_type: CallType::HigherOrder(_),
+                    ..
+                })
+                | Expr::Reset { .. }
+                | Expr::Reuse { .. } => false,
+                _ => true,
+            };
+
+            if inlinable {
+                Some(1 + inline_size(name, cont)?)
+            } else {
+                None
+            }
+        }
+        Refcounting(_, cont) => Some(1 + inline_size(name, cont)?),
+        Switch {
+            branches,
+            default_branch,
+            ..
+        } => {
+            let mut size = 1 + inline_size(name, default_branch.1)?;
+
+            for (_, _, branch) in branches.iter() {
+                size += inline_size(name, branch)?;
+            }
+
+            Some(size)
+        }
+        Ret(_) | RuntimeError(_) => Some(1),
+        Expect { .. } | ExpectFx { .. } | Dbg { .. } | Join { .. } | Jump(_, _) => None,
+    }
+}
+
+fn inline_stmt<'a>(env: &mut Env<'a, '_>, stmt: &'a Stmt<'a>) -> &'a Stmt<'a> {
+    use Stmt::*;
+
+    let arena = env.arena;
+
+    match stmt {
+        Let(symbol, expr, layout, cont) => {
+            let cont = inline_stmt(env, cont);
+
+            if let Expr::Call(call) = expr {
+                if let Some(callee) = env.inlinable_callee(call) {
+                    return inline_call(env, callee, call.arguments, *symbol, *layout, cont);
+                }
+            }
+
+            arena.alloc(Let(*symbol, expr.clone(), *layout, cont))
+        }
+        Switch {
+            cond_symbol,
+            cond_layout,
+            branches,
+            default_branch,
+            ret_layout,
+        } => {
+            let mut new_branches = Vec::with_capacity_in(branches.len(), arena);
+
+            for (label, info, branch) in branches.iter() {
+                new_branches.push((*label, info.clone(), inline_stmt(env, branch).clone()));
+            }
+
+            let default_branch = (default_branch.0.clone(), inline_stmt(env, default_branch.1));
+
+            arena.alloc(Switch {
+                cond_symbol: *cond_symbol,
+                cond_layout: *cond_layout,
+                branches: new_branches.into_bump_slice(),
+                default_branch,
+                ret_layout: *ret_layout,
+            })
+        }
+        Refcounting(modify_rc, cont) => {
+            arena.alloc(Refcounting(*modify_rc, inline_stmt(env, cont)))
+        }
+        Expect {
+            condition,
+            region,
+            lookups,
+            layouts,
+            remainder,
+        } => arena.alloc(Expect {
+            condition: *condition,
+            region: *region,
+            lookups,
+            layouts,
+            remainder: inline_stmt(env, remainder),
+        }),
+        ExpectFx {
+            condition,
+            region,
+            lookups,
+            layouts,
+            remainder,
+        } => arena.alloc(ExpectFx {
+            condition: *condition,
+            region: *region,
+            lookups,
+            layouts,
+            remainder: inline_stmt(env, remainder),
+        }),
+        Dbg {
+            symbol,
+            region,
+            remainder,
+        } => arena.alloc(Dbg {
+            symbol: *symbol,
+            region: *region,
+            remainder: inline_stmt(env, remainder),
+        }),
+        Join {
+            id,
+            parameters,
+            body,
+            remainder,
+        } => {
+            let body = inline_stmt(env, body);
+            let remainder = inline_stmt(env, remainder);
+
+            arena.alloc(Join {
+                id: *id,
+                parameters,
+                body,
+                remainder,
+            })
+        }
+        Ret(_) | Jump(_, _) | RuntimeError(_) => stmt,
+    }
+}
+
+/// Replace `let result = callee arguments` followed by `cont` with the body of `callee`
+fn inline_call<'a>(
+    env: &mut Env<'a, '_>,
+    callee: &Proc<'a>,
+    arguments: &'a [Symbol],
+    result: Symbol,
+    result_layout: Layout<'a>,
+    cont: &'a Stmt<'a>,
+) -> &'a Stmt<'a> {
+    let arena = env.arena;
+
+    let mut renames: MutMap<Symbol, Symbol> = callee
+        .args
+        .iter()
+        .map(|(_, param)| *param)
+        .zip(arguments.iter().copied())
+        .collect();
+
+    // A body that returns in just one place can flow right into the continuation, if it defines
+    // the value that it returns. Then that value can be defined as `result` right away.
+    let returns_directly = match single_return(&callee.body) {
+        Some(returned) if !renames.contains_key(&returned) => {
+            renames.insert(returned, result);
+            true
+        }
+        _ => false,
+    };
+
+    let body = rename_stmt(env, &mut renames, &callee.body);
+
+    env.inlining.push(callee.name.name());
+    let body = inline_stmt(env, body);
+    env.inlining.pop();
+
+    let ret_layout = env.ret_layout;
+
+    if returns_directly {
+        replace_returns(arena, ret_layout, body, &mut |_| cont)
+    } else {
+        let id = JoinPointId(env.unique_symbol());
+        let remainder = replace_returns(arena, ret_layout, body, &mut |returned| {
+            arena.alloc(Stmt::Jump(id, arena.alloc([returned])))
+        });
+
+        arena.alloc(Stmt::Join {
+            id,
+            parameters: arena.alloc([Param {
+                symbol: result,
+                borrow: false,
+                layout: result_layout,
+            }]),
+            body: cont,
+            remainder,
+        })
+    }
+}
+
+/// The symbol that is returned, if the statement returns in exactly one place
+fn single_return(stmt: &Stmt) -> Option<Symbol> {
+    let mut returned = None;
+    let mut stack = std::vec![stmt];
+
+    while let Some(stmt) = stack.pop() {
+        match stmt {
+            Stmt::Ret(symbol) if returned.is_none() => returned = Some(*symbol),
+            Stmt::Ret(_) => return None,
+            Stmt::Let(_, _, _, cont) | Stmt::Refcounting(_, cont) => stack.push(cont),
+            Stmt::Switch {
+                branches,
+                default_branch,
+                ..
+            } => {
+                stack.extend(branches.iter().map(|(_, _, branch)| branch));
+                stack.push(default_branch.1);
+            }
+            _ => {}
+        }
+    }
+
+    returned
+}
+
+/// Copy the body of an inline candidate (see [`inline_size`]), giving every symbol it defines a
+/// new name so that it stays unique in the proc it is inlined into
+fn rename_stmt<'a>(
+    env: &mut Env<'a, '_>,
+    renames: &mut MutMap<Symbol, Symbol>,
+    stmt: &Stmt<'a>,
+) -> &'a Stmt<'a> {
+    use Stmt::*;
+
+    let arena = env.arena;
+
+    match stmt {
+        Let(symbol, expr, layout, cont) => {
+            let expr = rename_expr(arena, renames, expr);
+
+            let new_symbol = match renames.get(symbol) {
+                Some(new_symbol) => *new_symbol,
+                None => {
+                    let new_symbol = env.unique_symbol();
+                    renames.insert(*symbol, new_symbol);
+                    new_symbol
+                }
+            };
+
+            arena.alloc(Let(
+                new_symbol,
+                expr,
+                *layout,
+                rename_stmt(env, renames, cont),
+            ))
+        }
+        Switch {
+            cond_symbol,
+            cond_layout,
+            branches,
+            default_branch,
+            ret_layout,
+        } => {
+            let mut new_branches = Vec::with_capacity_in(branches.len(), arena);
+
+            for (label, info, branch) in branches.iter() {
+                let info = rename_branch_info(renames, info);
+                let branch = rename_stmt(env, renames, branch).clone();
+
+                new_branches.push((*label, info, branch));
+            }
+
+            let default_branch = (
+                rename_branch_info(renames, &default_branch.0),
+                rename_stmt(env, renames, default_branch.1),
+            );
+
+            arena.alloc(Switch {
+                cond_symbol: rename(renames, *cond_symbol),
+                cond_layout: *cond_layout,
+                branches: new_branches.into_bump_slice(),
+                default_branch,
+                ret_layout: *ret_layout,
+            })
+        }
+        Refcounting(modify_rc, cont) => {
+            let modify_rc = match *modify_rc {
+                ModifyRc::Inc(symbol, n) => ModifyRc::Inc(rename(renames, symbol), n),
+                ModifyRc::Dec(symbol) => ModifyRc::Dec(rename(renames, symbol)),
+                ModifyRc::DecRef(symbol) => ModifyRc::DecRef(rename(renames, symbol)),
+            };
+
+            arena.alloc(Refcounting(modify_rc, rename_stmt(env, renames, cont)))
+        }
+        Ret(symbol) => arena.alloc(Ret(rename(renames, *symbol))),
+        RuntimeError(message) => arena.alloc(RuntimeError(message)),
+        Expect { .. } | ExpectFx { .. } | Dbg { .. } | Join { .. } | Jump(_, _) => {
+            unreachable!("procs with these statements are not inlined")
+        }
+    }
+}
+
+fn rename(renames: &MutMap<Symbol, Symbol>, symbol: Symbol) -> Symbol {
+    renames.get(&symbol).copied().unwrap_or(symbol)
+}
+
+fn rename_all<'a>(
+    arena: &'a Bump,
+    renames: &MutMap<Symbol, Symbol>,
+    symbols: &[Symbol],
+) -> &'a [Symbol] {
+    Vec::from_iter_in(symbols.iter().map(|s| rename(renames, *s)), arena).into_bump_slice()
+}
+
+fn rename_branch_info<'a>(
+    renames: &MutMap<Symbol, Symbol>,
+    info: &BranchInfo<'a>,
+) -> BranchInfo<'a> {
+    match info {
+        BranchInfo::None => BranchInfo::None,
+        BranchInfo::Constructor {
+            scrutinee,
+            layout,
+            tag_id,
+        } => BranchInfo::Constructor {
+            scrutinee: rename(renames, *scrutinee),
+            layout: *layout,
+            tag_id: *tag_id,
+        },
+    }
+}
+
+fn rename_expr<'a>(arena: &'a Bump, renames: &MutMap<Symbol, Symbol>, expr: &Expr<'a>) -> Expr<'a> {
+    use Expr::*;
+
+    match expr {
+        Literal(_) | EmptyArray | RuntimeErrorFunction(_) => expr.clone(),
+        Call(call) => Call(crate::ir::Call {
+            call_type: call.call_type.clone(),
+            arguments: rename_all(arena, renames, call.arguments),
+        }),
+        Tag {
+            tag_layout,
+            tag_id,
+            arguments,
+        } => Tag {
+            tag_layout: *tag_layout,
+            tag_id: *tag_id,
+            arguments: rename_all(arena, renames, arguments),
+        },
+        Struct(arguments) => Struct(rename_all(arena, renames, arguments)),
+        StructAtIndex {
+            index,
+            field_layouts,
+            structure,
+        } => StructAtIndex {
+            index: *index,
+            field_layouts,
+            structure: rename(renames, *structure),
+        },
+        GetTagId {
+            structure,
+            union_layout,
+        } => GetTagId {
+            structure: rename(renames, *structure),
+            union_layout: *union_layout,
+        },
+        UnionAtIndex {
+            structure,
+            tag_id,
+            union_layout,
+            index,
+        } => UnionAtIndex {
+            structure: rename(renames, *structure),
+            tag_id: *tag_id,
+            union_layout: *union_layout,
+            index: *index,
+        },
+        Array { elem_layout, elems } => {
+            let elems = elems.iter().map(|elem| match elem {
+                ListLiteralElement::Symbol(symbol) => {
+                    ListLiteralElement::Symbol(rename(renames, *symbol))
+                }
+                ListLiteralElement::Literal(_) => *elem,
+            });
+
+            Array {
+                elem_layout: *elem_layout,
+                elems: Vec::from_iter_in(elems, arena).into_bump_slice(),
+            }
+        }
+        ExprBox { symbol } => ExprBox {
+            symbol: rename(renames, *symbol),
+        },
+        ExprUnbox { symbol } => ExprUnbox {
+            symbol: rename(renames, *symbol),
+        },
+        Reuse { .. } | Reset { .. } => unreachable!("procs with reset/reuse are not inlined"),
+    }
+}
+
+/// Replace every `ret` in `stmt`, which will be part of a proc that returns `ret_layout`
+fn replace_returns<'a>(
+    arena: &'a Bump,
+    ret_layout: Layout<'a>,
+    stmt: &'a Stmt<'a>,
+    replacement: &mut dyn FnMut(Symbol) -> &'a Stmt<'a>,
+) -> &'a Stmt<'a> {
+    use Stmt::*;
+
+    match stmt {
+        Ret(symbol) => replacement(*symbol),
+        Let(symbol, expr, layout, cont) => arena.alloc(Let(
+            *symbol,
+            expr.clone(),
+            *layout,
+            replace_returns(arena, ret_layout, cont, replacement),
+        )),
+        Refcounting(modify_rc, cont) => arena.alloc(Refcounting(
+            *modify_rc,
+            replace_returns(arena, ret_layout, cont, replacement),
+        )),
+        Switch {
+            cond_symbol,
+            cond_layout,
+            branches,
+            default_branch,
+            ret_layout: _,
+        } => {
+            let mut new_branches = Vec::with_capacity_in(branches.len(), arena);
+
+            for (label, info, branch) in branches.iter() {
+                let branch = replace_returns(arena, ret_layout, branch, replacement).clone();
+
+                new_branches.push((*label, info.clone(), branch));
+            }
+
+            let default_branch = (
+                default_branch.0.clone(),
+                replace_returns(arena, ret_layout, default_branch.1, replacement),
+            );
+
+            arena.alloc(Switch {
+                cond_symbol: *cond_symbol,
+                cond_layout: *cond_layout,
+                branches: new_branches.into_bump_slice(),
+                default_branch,
+                ret_layout,
+            })
+        }
+        // the join points come from calls that were inlined into the body; returning from
+        // their continuation returns from the body
+        Join {
+            id,
+            parameters,
+            body,
+            remainder,
+        } => {
+            let body = replace_returns(arena, ret_layout, body, replacement);
+            let remainder = replace_returns(arena, ret_layout, remainder, replacement);
+
+            arena.alloc(Join {
+                id: *id,
+                parameters,
+                body,
+                remainder,
+            })
+        }
+        Jump(_, _) | RuntimeError(_) => stmt,
+        Expect { .. } | ExpectFx { .. } | Dbg { .. } => {
+            unreachable!("procs with these statements are not inlined")
+        }
+    }
+}
+
+/// Evaluate operations on literals, and pick the branch of a `Switch` on a literal.
+/// `constants` holds the literal value of the symbols defined so far.
+fn fold_constants<'a>(
+    arena: &'a Bump,
+    constants: &mut MutMap<Symbol, Literal<'a>>,
+    stmt: &'a Stmt<'a>,
+) -> &'a Stmt<'a> {
+    use Stmt::*;
+
+    match stmt {
+        Let(symbol, expr, layout, cont) => {
+            let folded = match expr {
+                Expr::Literal(literal) => Some(*literal),
+                Expr::Call(Call {
+                    call_type: CallType::LowLevel { op, .. },
+                    arguments,
+                }) => {
+                    let literals: Option<std::vec::Vec<_>> = arguments
+                        .iter()
+                        .map(|argument| constants.get(argument).copied())
+                        .collect();
+
+                    literals.and_then(|literals| fold_lowlevel(*op, &literals, layout))
+                }
+                _ => None,
+            };
+
+            let expr = match folded {
+                Some(literal) => {
+                    constants.insert(*symbol, literal);
+                    Expr::Literal(literal)
+                }
+                None => expr.clone(),
+            };
+
+            let cont = fold_constants(arena, constants, cont);
+
+            arena.alloc(Let(*symbol, expr, *layout, cont))
+        }
+        Switch {
+            cond_symbol,
+            cond_layout,
+            branches,
+            default_branch,
+            ret_layout,
+        } => {
+            let known = match constants.get(cond_symbol) {
+                // this is how the decision tree turns values into branch labels
+                Some(Literal::Int(bytes)) => Some(i128::from_ne_bytes(*bytes) as u64),
+                Some(Literal::Bool(value)) => Some(*value as u64),
+                Some(Literal::Byte(value)) => Some(*value as u64),
+                _ => None,
+            };
+
+            if let Some(value) = known {
+                let taken = branches
+                    .iter()
+                    .find(|(label, _, _)| *label == value)
+                    .map(|(_, _, branch)| branch)
+                    .unwrap_or(default_branch.1);
+
+                return fold_constants(arena, constants, taken);
+            }
+
+            let mut new_branches = Vec::with_capacity_in(branches.len(), arena);
+
+            for (label, info, branch) in branches.iter() {
+                let branch = fold_constants(arena, constants, branch).clone();
+
+                new_branches.push((*label, info.clone(), branch));
+            }
+
+            let default_branch = (
+                default_branch.0.clone(),
+                fold_constants(arena, constants, default_branch.1),
+            );
+
+            arena.alloc(Switch {
+                cond_symbol: *cond_symbol,
+                cond_layout: *cond_layout,
+                branches: new_branches.into_bump_slice(),
+                default_branch,
+                ret_layout: *ret_layout,
+            })
+        }
+        Refcounting(modify_rc, cont) => arena.alloc(Refcounting(
+            *modify_rc,
+            fold_constants(arena, constants, cont),
+        )),
+        Expect {
+            condition,
+            region,
+            lookups,
+            layouts,
+            remainder,
+        } => arena.alloc(Expect {
+            condition: *condition,
+            region: *region,
+            lookups,
+            layouts,
+            remainder: fold_constants(arena, constants, remainder),
+        }),
+        ExpectFx {
+            condition,
+            region,
+            lookups,
+            layouts,
+            remainder,
+        } => arena.alloc(ExpectFx {
+            condition: *condition,
+            region: *region,
+            lookups,
+            layouts,
+            remainder: fold_constants(arena, constants, remainder),
+        }),
+        Dbg {
+            symbol,
+            region,
+            remainder,
+        } => arena.alloc(Dbg {
+            symbol: *symbol,
+            region: *region,
+            remainder: fold_constants(arena, constants, remainder),
+        }),
+        Join {
+            id,
+            parameters,
+            body,
+            remainder,
+        } => {
+            let body = fold_constants(arena, constants, body);
+            let remainder = fold_constants(arena, constants, remainder);
+
+            arena.alloc(Join {
+                id: *id,
+                parameters,
+                body,
+                remainder,
+            })
+        }
+        Ret(_) | Jump(_, _) | RuntimeError(_) => stmt,
+    }
+}
+
+/// The result of a lowlevel operation on literals, if it can be computed at compile time.
+/// Integer arithmetic that overflows is left alone, so that it still crashes at runtime.
+fn fold_lowlevel<'a>(
+    op: LowLevel,
+    arguments: &[Literal<'a>],
+    layout: &Layout,
+) -> Option<Literal<'a>> {
+    use Literal::{Bool, Byte, Int};
+    use LowLevel::*;
+
+    let int = |bytes: &[u8; 16]| i128::from_ne_bytes(*bytes);
+
+    match (op, arguments) {
+        (NumAdd | NumSub | NumMul, [Int(a), Int(b)]) => {
+            let width = match layout {
+                Layout::Builtin(Builtin::Int(width)) => *width,
+                _ => return None,
+            };
+
+            let result = match op {
+                NumAdd => int(a).checked_add(int(b))?,
+                NumSub => int(a).checked_sub(int(b))?,
+                _ => int(a).checked_mul(int(b))?,
+            };
+
+            if fits_in(width, result) {
+                Some(Int(result.to_ne_bytes()))
+            } else {
+                None
+            }
+        }
+        (NumGt, [Int(a), Int(b)]) => Some(Bool(int(a) > int(b))),
+        (NumGte, [Int(a), Int(b)]) => Some(Bool(int(a) >= int(b))),
+        (NumLt, [Int(a), Int(b)]) => Some(Bool(int(a) < int(b))),
+        (NumLte, [Int(a), Int(b)]) => Some(Bool(int(a) <= int(b))),
+        (Eq | NotEq, [a, b]) => {
+            let equal = match (a, b) {
+                (Int(a), Int(b)) => int(a) == int(b),
+                (Bool(a), Bool(b)) => a == b,
+                (Byte(a), Byte(b)) => a == b,
+                _ => return None,
+            };
+
+            Some(Bool(equal == matches!(op, Eq)))
+        }
+        (And, [Bool(a), Bool(b)]) => Some(Bool(*a && *b)),
+        (Or, [Bool(a), Bool(b)]) => Some(Bool(*a || *b)),
+        (Not, [Bool(a)]) => Some(Bool(!a)),
+        _ => None,
+    }
+}
+
+/// Whether `value` is in range for integers of this width. 128-bit unsigned integers can be
+/// beyond what an `i128` holds, so those are never folded.
+fn fits_in(width: IntWidth, value: i128) -> bool {
+    use IntWidth::*;
+
+    match width {
+        U8 => u8::try_from(value).is_ok(),
+        U16 => u16::try_from(value).is_ok(),
+        U32 => u32::try_from(value).is_ok(),
+        U64 => u64::try_from(value).is_ok(),
+        I8 => i8::try_from(value).is_ok(),
+        I16 => i16::try_from(value).is_ok(),
+        I32 => i32::try_from(value).is_ok(),
+        I64 => i64::try_from(value).is_ok(),
+        I128 => true,
+        U128 => false,
+    }
+}
+
+/// The symbols that are used (as opposed to defined) in `stmt`
+fn symbol_uses(stmt: &Stmt, result: &mut MutSet<Symbol>) {
+    let mut stack = std::vec![stmt];
+
+    while let Some(stmt) = stack.pop() {
+        use Stmt::*;
+
+        match stmt {
+            Let(_, expr, _, cont) => {
+                occurring_variables_expr(expr, result);
+                stack.push(cont);
+            }
+            Switch {
+                cond_symbol,
+                branches,
+                default_branch,
+                ..
+            } => {
+                result.insert(*cond_symbol);
+
+                stack.extend(branches.iter().map(|(_, _, branch)| branch));
+                stack.push(default_branch.1);
+            }
+            Ret(symbol) => {
+                result.insert(*symbol);
+            }
+            Refcounting(modify_rc, cont) => {
+                result.insert(modify_rc.get_symbol());
+                stack.push(cont);
+            }
+            Expect {
+                condition,
+                lookups,
+                remainder,
+                ..
+            }
+            | ExpectFx {
+                condition,
+                lookups,
+                remainder,
+                ..
+            } => {
+                result.insert(*condition);
+                result.extend(lookups.iter().copied());
+                stack.push(remainder);
+            }
+            Dbg {
+                symbol, remainder, ..
+            } => {
+                result.insert(*symbol);
+                stack.push(remainder);
+            }
+            Join {
+                body, remainder, ..
+            } => {
+                stack.push(body);
+                stack.push(remainder);
+            }
+            Jump(_, arguments) => {
+                result.extend(arguments.iter().copied());
+            }
+            RuntimeError(_) => {}
+        }
+    }
+}
+
+/// Remove the definitions of literals that are no longer used, e.g. because the operation they
+/// were arguments to was folded. String literals are left alone.
+fn remove_unused_literals<'a>(
+    arena: &'a Bump,
+    used: &MutSet<Symbol>,
+    stmt: &'a Stmt<'a>,
+) -> &'a Stmt<'a> {
+    use Stmt::*;
+
+    match stmt {
+        Let(symbol, Expr::Literal(literal), _, cont)
+            if !used.contains(symbol) && !matches!(literal, Literal::Str(_)) =>
+        {
+            remove_unused_literals(arena, used, cont)
+        }
+        Let(symbol, expr, layout, cont) => arena.alloc(Let(
+            *symbol,
+            expr.clone(),
+            *layout,
+            remove_unused_literals(arena, used, cont),
+        )),
+        Switch {
+            cond_symbol,
+            cond_layout,
+            branches,
+            default_branch,
+            ret_layout,
+        } => {
+            let mut new_branches = Vec::with_capacity_in(branches.len(), arena);
+
+            for (label, info, branch) in branches.iter() {
+                let branch = remove_unused_literals(arena, used, branch).clone();
+
+                new_branches.push((*label, info.clone(), branch));
+            }
+
+            let default_branch = (
+                default_branch.0.clone(),
+                remove_unused_literals(arena, used, default_branch.1),
+            );
+
+            arena.alloc(Switch {
+                cond_symbol: *cond_symbol,
+                cond_layout: *cond_layout,
+                branches: new_branches.into_bump_slice(),
+                default_branch,
+                ret_layout: *ret_layout,
+            })
+        }
+        Refcounting(modify_rc, cont) => arena.alloc(Refcounting(
+            *modify_rc,
+            remove_unused_literals(arena, used, cont),
+        )),
+        Expect {
+            condition,
+            region,
+            lookups,
+            layouts,
+            remainder,
+        } => arena.alloc(Expect {
+            condition: *condition,
+            region: *region,
+            lookups,
+            layouts,
+            remainder: remove_unused_literals(arena, used, remainder),
+        }),
+        ExpectFx {
+            condition,
+            region,
+            lookups,
+            layouts,
+            remainder,
+        } => arena.alloc(ExpectFx {
+            condition: *condition,
+            region: *region,
+            lookups,
+            layouts,
+            remainder: remove_unused_literals(arena, used, remainder),
+        }),
+        Dbg {
+            symbol,
+            region,
+            remainder,
+        } => arena.alloc(Dbg {
+            symbol: *symbol,
+            region: *region,
+            remainder: remove_unused_literals(arena, used, remainder),
+        }),
+        Join {
+            id,
+            parameters,
+            body,
+            remainder,
+        } => {
+            let body = remove_unused_literals(arena, used, body);
+            let remainder = remove_unused_literals(arena, used, remainder);
+
+            arena.alloc(Join {
+                id: *id,
+                parameters,
+                body,
+                remainder,
+            })
+        }
+        Ret(_) | Jump(_, _) | RuntimeError(_) => stmt,
+    }
+}
+
+/// The procs that `stmt` calls, or passes to a higher-order lowlevel
+fn called_procs(stmt: &Stmt, result: &mut MutSet<Symbol>) {
+    let mut stack = std::vec![stmt];
+
+    while let Some(stmt) = stack.pop() {
+        use Stmt::*;
+
+        match stmt {
+            Let(_, expr, _, cont) => {
+                match expr {
+                    Expr::Call(Call {
+                        call_type: CallType::ByName { name, .. },
+                        ..
+                    }) => {
+                        result.insert(name.name());
+                    }
+                    Expr::Call(Call {
+                        call_type: CallType::HigherOrder(higher_order),
+                        ..
+                    }) => {
+                        result.insert(higher_order.passed_function.name.name());
+                    }
+                    _ => {}
+                }
+
+                stack.push(cont);
+            }
+            Switch {
+                branches,
+                default_branch,
+                ..
+            } => {
+                stack.extend(branches.iter().map(|(_, _, branch)| branch));
+                stack.push(default_branch.1);
+            }
+            Refcounting(_, cont) => stack.push(cont),
+            Expect { remainder, .. } | ExpectFx { remainder, .. } | Dbg { remainder, .. } => {
+                stack.push(remainder)
+            }
+            Join {
+                body, remainder, ..
+            } => {
+                stack.push(body);
+                stack.push(remainder);
+            }
+            Ret(_) | Jump(_, _) | RuntimeError(_) => {}
+        }
+    }
+}
@@ -11,7 +11,7 @@ use roc_builtins::bitcode::{FloatWidth, IntWidth};
 use roc_can::abilities::SpecializationId;
 use roc_can::expr::{AnnotatedMark, ClosureData, IntValue};
 use roc_can::module::ExposedByModule;
-use roc_collections::all::{default_hasher, BumpMap, BumpMapDefault, MutMap};
+use roc_collections::all::{default_hasher, BumpMap, BumpMapDefault, MutMap, MutSet};
 use roc_collections::VecMap;
 use roc_debug_flags::dbg_do;
 #[cfg(debug_assertions)]
@@ -21,7 +21,7 @@ use roc_debug_flags::{
 };
 use roc_derive::SharedDerivedModule;
 use roc_error_macros::{internal_error, todo_abilities};
-use roc_exhaustive::{Ctor, CtorName, RenderAs, TagId};
+use roc_exhaustive::{Ctor, CtorName, ListArity, RenderAs, TagId};
 use roc_intern::Interner;
 use roc_late_solve::storage::{ExternalModuleStorage, ExternalModuleStorageSnapshot};
 use roc_late_solve::{resolve_ability_specialization, AbilitiesView, Resolved, UnificationFailed};
@@ -211,6 +211,8 @@ pub struct PartialProc<'a> {
     pub body: roc_can::expr::Expr,
     pub body_var: Variable,
     pub is_self_recursive: bool,
+    /// Where this function is defined in the source, used for debug info.
+    pub region: Region,
 }
 
 impl<'a> PartialProc<'a> {
@@ -225,6 +227,7 @@ impl<'a> PartialProc<'a> {
         ret_var: Variable,
     ) -> PartialProc<'a> {
         let number_of_arguments = loc_args.len();
+        let region = loc_body.region;
 
         match patterns_to_when(env, loc_args, ret_var, loc_body) {
             Ok((_, pattern_symbols, body)) => {
@@ -240,6 +243,7 @@ impl<'a> PartialProc<'a> {
                     body: body.value,
                     body_var: ret_var,
                     is_self_recursive,
+                    region,
                 }
             }
 
@@ -257,6 +261,7 @@ impl<'a> PartialProc<'a> {
                     body: roc_can::expr::Expr::RuntimeError(error.value),
                     body_var: ret_var,
                     is_self_recursive: false,
+                    region,
                 }
             }
         }
@@ -315,6 +320,16 @@ pub struct Proc<'a> {
     pub is_self_recursive: SelfRecursive,
     pub must_own_arguments: bool,
     pub host_exposed_layouts: HostExposedLayouts<'a>,
+    /// Where this function is defined in the source; `Region::zero()` for generated procs.
+    pub region: Region,
+    /// The named values bound in the body, with the regions they were defined at.
+    /// Backends use these to emit source locations and variable info for debuggers.
+    pub locals: &'a [(Symbol, Region)],
+    /// The source expressions the `Stmt::Let`s in the body compute, by the symbol they bind.
+    /// Backends point the code of each such statement at its expression, so that debuggers can
+    /// step through the proc. Lets that were added later, like refcounting, have no entry and
+    /// belong with the statement before them.
+    pub let_regions: &'a [(Symbol, Region)],
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -445,6 +460,16 @@ impl<'a> Proc<'a> {
         }
     }
 
+    pub fn inline_and_fold_constants<'i>(
+        arena: &'a Bump,
+        home: ModuleId,
+        ident_ids: &'i mut IdentIds,
+        exposed_to_host: &MutSet<Symbol>,
+        procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    ) {
+        crate::inline::inline_procs(arena, home, ident_ids, exposed_to_host, procs);
+    }
+
     fn make_tail_recursive(&mut self, env: &mut Env<'a, '_>) {
         let mut args = Vec::with_capacity_in(self.args.len(), env.arena);
         let mut proc_args = Vec::with_capacity_in(self.args.len(), env.arena);
@@ -1000,6 +1025,10 @@ pub struct Procs<'a> {
     symbol_specializations: SymbolSpecializations<'a>,
     /// The current set of functions under specialization.
     pub specialization_stack: Vec<'a, Symbol>,
+    /// Named values bound in the body currently being specialized, with their source regions.
+    local_regions: Vec<'a, (Symbol, Region)>,
+    /// The source expressions of the lets in the body currently being specialized
+    let_regions: Vec<'a, (Symbol, Region)>,
 }
 
 impl<'a> Procs<'a> {
@@ -1015,6 +1044,8 @@ impl<'a> Procs<'a> {
             externals_we_need: BumpMap::new_in(arena),
             symbol_specializations: Default::default(),
             specialization_stack: Vec::with_capacity_in(16, arena),
+            local_regions: Vec::new_in(arena),
+            let_regions: Vec::new_in(arena),
         }
     }
 
@@ -1177,6 +1208,7 @@ impl<'a> Procs<'a> {
                                         annotation,
                                         pattern_symbols,
                                         captured_symbols,
+                                        region: body.region,
                                         body: body.value,
                                         body_var: ret_var,
                                         is_self_recursive,
@@ -1213,6 +1245,7 @@ impl<'a> Procs<'a> {
                                     annotation,
                                     pattern_symbols,
                                     captured_symbols,
+                                    region: body.region,
                                     body: body.value,
                                     body_var: ret_var,
                                     is_self_recursive,
@@ -1571,6 +1604,14 @@ pub enum Stmt<'a> {
         /// what happens after the expect
         remainder: &'a Stmt<'a>,
     },
+    Dbg {
+        /// the value to print
+        symbol: Symbol,
+        /// the region of the value in the source, used to report where it was printed
+        region: Region,
+        /// what happens after the dbg
+        remainder: &'a Stmt<'a>,
+    },
     /// a join point `join f <params> = <continuation> in remainder`
     Join {
         id: JoinPointId,
@@ -2170,6 +2211,15 @@ impl<'a> Stmt<'a> {
                 .append(alloc.hardline())
                 .append(remainder.to_doc(alloc, interner)),
 
+            Dbg {
+                symbol, remainder, ..
+            } => alloc
+                .text("dbg ")
+                .append(symbol_to_doc(alloc, *symbol))
+                .append(";")
+                .append(alloc.hardline())
+                .append(remainder.to_doc(alloc, interner)),
+
             Ret(symbol) => alloc
                 .text("ret ")
                 .append(symbol_to_doc(alloc, *symbol))
@@ -2479,6 +2529,47 @@ fn from_can_let<'a>(
 
                 lower_rest!(variable, new_outer)
             }
+            Dbg {
+                loc_message,
+                loc_continuation,
+                variable: dbg_var,
+                symbol: dbg_symbol,
+            } => {
+                // We must transform
+                //
+                //      let answer =
+                //              dbg message
+                //              continuation
+                //      in
+                //          answer
+                //
+                // into
+                //
+                //      dbg message
+                //      let answer = continuation
+                //      in
+                //          answer
+
+                let new_def = roc_can::def::Def {
+                    loc_pattern: def.loc_pattern,
+                    loc_expr: *loc_continuation,
+                    pattern_vars: def.pattern_vars,
+                    annotation: def.annotation,
+                    expr_var: def.expr_var,
+                };
+
+                let rest = lower_rest!(variable, LetNonRec(Box::new(new_def), cont));
+
+                compile_dbg(
+                    env,
+                    procs,
+                    layout_cache,
+                    dbg_symbol,
+                    dbg_var,
+                    *loc_message,
+                    rest,
+                )
+            }
             _ => {
                 let rest = lower_rest!(variable, cont.value);
 
@@ -2490,9 +2581,11 @@ fn from_can_let<'a>(
                     0 => {
                         // We don't need any specializations, that means this symbol is never
                         // referenced.
-                        with_hole(
+                        procs.local_regions.push((*symbol, def.loc_pattern.region));
+
+                        with_hole_at(
                             env,
-                            def.loc_expr.value,
+                            def.loc_expr,
                             def.expr_var,
                             procs,
                             layout_cache,
@@ -2506,6 +2599,10 @@ fn from_can_let<'a>(
                         let (_specialization_mark, (var, specialized_symbol)) =
                             needed_specializations.next().unwrap();
 
+                        procs
+                            .local_regions
+                            .push((specialized_symbol, def.loc_pattern.region));
+
                         // Make sure rigid variables in the annotation are converted to flex variables.
                         instantiate_rigids(env.subs, def.expr_var);
                         // Unify the expr_var with the requested specialization once.
@@ -2516,9 +2613,9 @@ fn from_can_let<'a>(
                             def.expr_var,
                         );
 
-                        with_hole(
+                        with_hole_at(
                             env,
-                            def.loc_expr.value,
+                            def.loc_expr,
                             def.expr_var,
                             procs,
                             layout_cache,
@@ -2555,6 +2652,13 @@ fn from_can_let<'a>(
                                 new_def_expr_var,
                             );
 
+                            procs
+                                .local_regions
+                                .push((specialized_symbol, def.loc_pattern.region));
+                            procs
+                                .let_regions
+                                .push((specialized_symbol, def.loc_expr.region));
+
                             stmt = with_hole(
                                 env,
                                 specialized_expr,
@@ -2580,6 +2684,12 @@ fn from_can_let<'a>(
             Err(_) => todo!(),
         };
 
+    procs.local_regions.extend(
+        def.pattern_vars
+            .keys()
+            .map(|symbol| (*symbol, def.loc_pattern.region)),
+    );
+
     // convert the continuation
     let mut stmt = lower_rest!(variable, cont.value);
 
@@ -2611,9 +2721,9 @@ fn from_can_let<'a>(
         stmt = store_pattern(env, procs, layout_cache, &mono_pattern, outer_symbol, stmt);
 
         // convert the def body, store in outer_symbol
-        with_hole(
+        with_hole_at(
             env,
-            def.loc_expr.value,
+            def.loc_expr,
             def.expr_var,
             procs,
             layout_cache,
@@ -2737,7 +2847,11 @@ fn pattern_to_when<'a>(
             (env.unique_symbol(), Loc::at_zero(RuntimeError(error)))
         }
 
-        AppliedTag { .. } | RecordDestructure { .. } | UnwrappedOpaque { .. } => {
+        AppliedTag { .. }
+        | RecordDestructure { .. }
+        | TupleDestructure { .. }
+        | roc_can::pattern::Pattern::List { .. }
+        | UnwrappedOpaque { .. } => {
             let symbol = env.unique_symbol();
 
             let wrapped_body = When {
@@ -3108,6 +3222,9 @@ fn generate_runtime_error_function<'a>(
         is_self_recursive: SelfRecursive::NotSelfRecursive,
         must_own_arguments: false,
         host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+        region: Region::zero(),
+        locals: &[],
+        let_regions: &[],
     }
 }
 
@@ -3215,6 +3332,7 @@ fn specialize_proc_help<'a>(
 
     let body = partial_proc.body.clone();
     let body_var = partial_proc.body_var;
+    let partial_proc_region = partial_proc.region;
 
     // determine the layout of aliases/rigids exposed to the host
     let host_exposed_layouts = if host_exposed_variables.is_empty() {
@@ -3280,6 +3398,9 @@ fn specialize_proc_help<'a>(
                         is_self_recursive: SelfRecursive::NotSelfRecursive,
                         must_own_arguments: false,
                         host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+                        region: partial_proc_region,
+                        locals: &[],
+                        let_regions: &[],
                     };
 
                     let top_level = ProcLayout::new(
@@ -3307,6 +3428,9 @@ fn specialize_proc_help<'a>(
                         is_self_recursive: SelfRecursive::NotSelfRecursive,
                         must_own_arguments: false,
                         host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+                        region: partial_proc_region,
+                        locals: &[],
+                        let_regions: &[],
                     };
 
                     let top_level = ProcLayout::from_raw(
@@ -3336,7 +3460,13 @@ fn specialize_proc_help<'a>(
         }
     };
 
+    // nested specializations collect their own locals and lets, so only the ones bound in this
+    // body are left in `procs` once it has been lowered
+    let outer_locals = std::mem::replace(&mut procs.local_regions, Vec::new_in(env.arena));
+    let outer_lets = std::mem::replace(&mut procs.let_regions, Vec::new_in(env.arena));
     let mut specialized_body = from_can(env, body_var, body, procs, layout_cache);
+    let locals = std::mem::replace(&mut procs.local_regions, outer_locals).into_bump_slice();
+    let let_regions = std::mem::replace(&mut procs.let_regions, outer_lets).into_bump_slice();
 
     let specialized_proc = match specialized {
         SpecializedLayout::FunctionPointerBody {
@@ -3368,6 +3498,9 @@ fn specialize_proc_help<'a>(
                 is_self_recursive: recursivity,
                 must_own_arguments: false,
                 host_exposed_layouts,
+                region: partial_proc_region,
+                locals,
+                let_regions,
             }
         }
         SpecializedLayout::FunctionBody {
@@ -3546,6 +3679,9 @@ fn specialize_proc_help<'a>(
                 is_self_recursive: recursivity,
                 must_own_arguments: false,
                 host_exposed_layouts,
+                region: partial_proc_region,
+                locals,
+                let_regions,
             }
         }
     };
@@ -4289,9 +4425,9 @@ pub fn with_hole<'a>(
                         );
                     }
                     Field::Field(field) => {
-                        stmt = with_hole(
+                        stmt = with_hole_at(
                             env,
-                            field.loc_expr.value,
+                            *field.loc_expr,
                             field.var,
                             procs,
                             layout_cache,
@@ -4307,9 +4443,161 @@ pub fn with_hole<'a>(
 
         EmptyRecord => let_empty_struct(assigned, hole),
 
+        Tuple {
+            tuple_var, elems, ..
+        } => {
+            let sorted_elems_result = {
+                let mut layout_env = layout::Env::from_components(
+                    layout_cache,
+                    env.subs,
+                    env.arena,
+                    env.target_info,
+                );
+                layout::sort_tuple_elems(&mut layout_env, tuple_var)
+            };
+            let sorted_elems = match sorted_elems_result {
+                Ok(elems) => elems,
+                Err(_) => return Stmt::RuntimeError("Can't create tuple with improper layout"),
+            };
+
+            let mut elem_symbols = Vec::with_capacity_in(elems.len(), env.arena);
+            let mut can_elems = Vec::with_capacity_in(elems.len(), env.arena);
+
+            enum Elem {
+                FunctionOrUnspecialized(Symbol, Variable),
+                ValueSymbol,
+                Expr(Variable, roc_can::expr::Expr),
+            }
+
+            let mut elems: std::vec::Vec<_> = elems.into_iter().map(Some).collect();
+
+            for (index, variable, _) in sorted_elems.into_iter() {
+                // TODO how should function pointers be handled here?
+                use ReuseSymbol::*;
+                let (var, loc_expr) = elems[index]
+                    .take()
+                    .expect("tuple element not in its own type");
+
+                match can_reuse_symbol(env, procs, &loc_expr.value, var) {
+                    Imported(symbol) | LocalFunction(symbol) | UnspecializedExpr(symbol) => {
+                        elem_symbols.push(symbol);
+                        can_elems.push(Elem::FunctionOrUnspecialized(symbol, variable));
+                    }
+                    Value(symbol) => {
+                        let reusable = procs.symbol_specializations.get_or_insert(
+                            env,
+                            layout_cache,
+                            symbol,
+                            var,
+                        );
+                        elem_symbols.push(reusable);
+                        can_elems.push(Elem::ValueSymbol);
+                    }
+                    NotASymbol => {
+                        elem_symbols.push(env.unique_symbol());
+                        can_elems.push(Elem::Expr(var, loc_expr.value));
+                    }
+                }
+            }
+
+            // creating a tuple from the var will unpack it if it's just a single element.
+            let layout = match layout_cache.from_var(env.arena, tuple_var, env.subs) {
+                Ok(layout) => layout,
+                Err(_) => return Stmt::RuntimeError("Can't create tuple with improper layout"),
+            };
+
+            let elem_symbols = elem_symbols.into_bump_slice();
+
+            let mut stmt = if let [only_elem] = elem_symbols {
+                let mut hole = hole.clone();
+                substitute_in_exprs(env.arena, &mut hole, assigned, *only_elem);
+                hole
+            } else {
+                Stmt::Let(assigned, Expr::Struct(elem_symbols), layout, hole)
+            };
+
+            for (opt_elem, symbol) in can_elems.into_iter().rev().zip(elem_symbols.iter().rev()) {
+                match opt_elem {
+                    Elem::ValueSymbol => {
+                        // this symbol is already defined; nothing to do
+                    }
+                    Elem::FunctionOrUnspecialized(symbol, variable) => {
+                        stmt = specialize_symbol(
+                            env,
+                            procs,
+                            layout_cache,
+                            Some(variable),
+                            symbol,
+                            stmt,
+                            symbol,
+                        );
+                    }
+                    Elem::Expr(var, expr) => {
+                        stmt = with_hole(
+                            env,
+                            expr,
+                            var,
+                            procs,
+                            layout_cache,
+                            *symbol,
+                            env.arena.alloc(stmt),
+                        );
+                    }
+                }
+            }
+
+            stmt
+        }
+
         Expect { .. } => unreachable!("I think this is unreachable"),
         ExpectFx { .. } => unreachable!("I think this is unreachable"),
 
+        Dbg {
+            loc_message,
+            loc_continuation,
+            variable: dbg_var,
+            symbol: dbg_symbol,
+        } => {
+            if matches!(loc_continuation.value, Var(symbol) if symbol == dbg_symbol) {
+                // without a continuation, the message is the value of the whole expression
+                let stmt = Stmt::Dbg {
+                    symbol: assigned,
+                    region: loc_message.region,
+                    remainder: hole,
+                };
+
+                return with_hole_at(
+                    env,
+                    *loc_message,
+                    dbg_var,
+                    procs,
+                    layout_cache,
+                    assigned,
+                    env.arena.alloc(stmt),
+                );
+            }
+
+            let rest = with_hole(
+                env,
+                loc_continuation.value,
+                variable,
+                procs,
+                layout_cache,
+                assigned,
+                hole,
+            );
+
+            compile_dbg(
+                env,
+                procs,
+                layout_cache,
+                dbg_symbol,
+                dbg_var,
+                *loc_message,
+                rest,
+            )
+        }
+
         If {
             cond_var,
             branch_var,
@@ -4354,9 +4642,9 @@ pub fn with_hole<'a>(
                             stmt = cond(env, branching_symbol, cond_layout, then, stmt, ret_layout);
 
                             // add condition
-                            stmt = with_hole(
+                            stmt = with_hole_at(
                                 env,
-                                loc_cond.value,
+                                loc_cond,
                                 cond_var,
                                 procs,
                                 layout_cache,
@@ -4673,6 +4961,83 @@ pub fn with_hole<'a>(
             stmt
         }
 
+        TupleAccess {
+            tuple_var,
+            elem_var,
+            index: accessed_index,
+            loc_expr,
+            ..
+        } => {
+            let sorted_elems_result = {
+                let mut layout_env = layout::Env::from_components(
+                    layout_cache,
+                    env.subs,
+                    env.arena,
+                    env.target_info,
+                );
+                layout::sort_tuple_elems(&mut layout_env, tuple_var)
+            };
+            let sorted_elems = match sorted_elems_result {
+                Ok(elems) => elems,
+                Err(_) => return Stmt::RuntimeError("Can't access tuple with improper layout"),
+            };
+
+            let mut index = None;
+            let mut elem_layouts = Vec::with_capacity_in(sorted_elems.len(), env.arena);
+
+            for (current, (elem_index, _, elem_layout)) in sorted_elems.into_iter().enumerate() {
+                elem_layouts.push(elem_layout);
+
+                if elem_index == accessed_index {
+                    index = Some(current);
+                }
+            }
+
+            let tuple_symbol = possible_reuse_symbol_or_specialize(
+                env,
+                procs,
+                layout_cache,
+                &loc_expr.value,
+                tuple_var,
+            );
+
+            let mut stmt = match elem_layouts.as_slice() {
+                [_] => {
+                    let mut hole = hole.clone();
+                    substitute_in_exprs(env.arena, &mut hole, assigned, tuple_symbol);
+
+                    hole
+                }
+                _ => {
+                    let expr = Expr::StructAtIndex {
+                        index: index.expect("elem not in its own type") as u64,
+                        field_layouts: elem_layouts.into_bump_slice(),
+                        structure: tuple_symbol,
+                    };
+
+                    let layout = layout_cache
+                        .from_var(env.arena, elem_var, env.subs)
+                        .unwrap_or_else(|err| {
+                            panic!("TODO turn fn_var into a RuntimeError {:?}", err)
+                        });
+
+                    Stmt::Let(assigned, expr, layout, hole)
+                }
+            };
+
+            stmt = assign_to_symbol(
+                env,
+                procs,
+                layout_cache,
+                tuple_var,
+                *loc_expr,
+                tuple_symbol,
+                stmt,
+            );
+
+            stmt
+        }
+
         Accessor(accessor_data) => {
             let field_var = accessor_data.field_var;
             let fresh_record_symbol = env.unique_symbol();
@@ -5526,6 +5891,10 @@ fn late_resolve_ability_specialization<'a>(
                         // The immediate is an ability member itself, so it must be resolved!
                         late_resolve_ability_specialization(env, imm, None, specialization_var)
                     }
+                    roc_derive_key::Derived::SingleLambdaSetImmediate(imm) => {
+                        // The immediate is a plain function, so it is its own specialization.
+                        imm
+                    }
                     roc_derive_key::Derived::Key(derive_key) => {
                         let mut derived_module = env
                             .derived_module
@@ -6318,9 +6687,9 @@ pub fn from_can<'a>(
                 remainder: env.arena.alloc(rest),
             };
 
-            stmt = with_hole(
+            stmt = with_hole_at(
                 env,
-                loc_condition.value,
+                *loc_condition,
                 variable,
                 procs,
                 layout_cache,
@@ -6357,9 +6726,9 @@ pub fn from_can<'a>(
                 remainder: env.arena.alloc(rest),
             };
 
-            stmt = with_hole(
+            stmt = with_hole_at(
                 env,
-                loc_condition.value,
+                *loc_condition,
                 variable,
                 procs,
                 layout_cache,
@@ -6370,6 +6739,25 @@ pub fn from_can<'a>(
             stmt
         }
 
+        Dbg {
+            loc_message,
+            loc_continuation,
+            variable: dbg_var,
+            symbol: dbg_symbol,
+        } => {
+            let rest = from_can(env, variable, loc_continuation.value, procs, layout_cache);
+
+            compile_dbg(
+                env,
+                procs,
+                layout_cache,
+                dbg_symbol,
+                dbg_var,
+                *loc_message,
+                rest,
+            )
+        }
+
         LetRec(defs, cont, _cycle_mark) => {
             // because Roc is strict, only functions can be recursive!
             for def in defs.into_iter() {
@@ -6538,9 +6926,9 @@ fn from_can_when<'a>(
                 let symbol = env.unique_symbol();
                 let jump = env.arena.alloc(Stmt::Jump(id, env.arena.alloc([symbol])));
 
-                let guard_stmt = with_hole(
+                let guard_stmt = with_hole_at(
                     env,
-                    loc_expr.value,
+                    loc_expr,
                     Variable::BOOL,
                     procs,
                     layout_cache,
@@ -6760,6 +7148,23 @@ fn substitute_in_stmt_help<'a>(
             Some(arena.alloc(expect))
         }
 
+        Dbg {
+            symbol,
+            region,
+            remainder,
+        } => {
+            let new_remainder =
+                substitute_in_stmt_help(arena, remainder, subs).unwrap_or(remainder);
+
+            let dbg = Dbg {
+                symbol: substitute(subs, *symbol).unwrap_or(*symbol),
+                region: *region,
+                remainder: new_remainder,
+            };
+
+            Some(arena.alloc(dbg))
+        }
+
         Jump(id, args) => {
             let mut did_change = false;
             let new_args = Vec::from_iter_in(
@@ -7090,6 +7495,27 @@ fn store_pattern_help<'a>(
             return store_pattern_help(env, procs, layout_cache, pattern, outer_symbol, stmt);
         }
 
+        List {
+            arity,
+            list_layout,
+            element_layout,
+            elements,
+            opt_rest,
+        } => {
+            return store_list_pattern(
+                env,
+                procs,
+                layout_cache,
+                outer_symbol,
+                *arity,
+                *list_layout,
+                *element_layout,
+                elements,
+                opt_rest,
+                stmt,
+            );
+        }
+
         RecordDestructure(destructs, [_single_field]) => {
             for destruct in destructs {
                 match &destruct.typ {
@@ -7121,6 +7547,45 @@ fn store_pattern_help<'a>(
                 }
             }
         }
+        TupleDestructure(destructs, [_single_elem]) => {
+            // a tuple with one element has the same layout as that element
+            debug_assert_eq!(destructs.len(), 1);
+            return store_pattern_help(
+                env,
+                procs,
+                layout_cache,
+                &destructs[0].pat,
+                outer_symbol,
+                stmt,
+            );
+        }
+        TupleDestructure(destructs, sorted_elems) => {
+            let mut is_productive = false;
+            for (index, destruct) in destructs.iter().enumerate().rev() {
+                match store_tuple_destruct(
+                    env,
+                    procs,
+                    layout_cache,
+                    destruct,
+                    index as u64,
+                    outer_symbol,
+                    sorted_elems,
+                    stmt,
+                ) {
+                    StorePattern::Productive(new) => {
+                        is_productive = true;
+                        stmt = new;
+                    }
+                    StorePattern::NotProductive(new) => {
+                        stmt = new;
+                    }
+                }
+            }
+
+            if !is_productive {
+                return StorePattern::NotProductive(stmt);
+            }
+        }
         RecordDestructure(destructs, sorted_fields) => {
             let mut is_productive = false;
             for (index, destruct) in destructs.iter().enumerate().rev() {
@@ -7232,6 +7697,127 @@ fn store_tag_pattern<'a>(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
+fn store_list_pattern<'a>(
+    env: &mut Env<'a, '_>,
+    procs: &mut Procs<'a>,
+    layout_cache: &mut LayoutCache<'a>,
+    list_sym: Symbol,
+    list_arity: ListArity,
+    list_layout: Layout<'a>,
+    element_layout: Layout<'a>,
+    elements: &[Pattern<'a>],
+    opt_rest: &Option<(usize, Option<Symbol>)>,
+    mut stmt: Stmt<'a>,
+) -> StorePattern<'a> {
+    use Pattern::*;
+
+    let mut is_productive = false;
+
+    for (index, element) in elements.iter().enumerate().rev() {
+        let elem_sym = match element {
+            Identifier(symbol) => {
+                // Pattern can define only one specialization
+                procs
+                    .symbol_specializations
+                    .remove_single(*symbol)
+                    .unwrap_or(*symbol)
+            }
+            Underscore
+            | IntLiteral(_, _)
+            | FloatLiteral(_, _)
+            | DecimalLiteral(_)
+            | EnumLiteral { .. }
+            | BitLiteral { .. }
+            | StrLiteral(_) => {
+                // nothing is bound, and literals were already tested
+                continue;
+            }
+            _ => {
+                // store the element in a symbol, and continue matching on it
+                let symbol = env.unique_symbol();
+
+                // only if we bind one of its (sub)fields to a used name should we
+                // load the element
+                match store_pattern_help(env, procs, layout_cache, element, symbol, stmt) {
+                    StorePattern::Productive(new) => {
+                        stmt = new;
+                        symbol
+                    }
+                    StorePattern::NotProductive(new) => {
+                        stmt = new;
+                        continue;
+                    }
+                }
+            }
+        };
+
+        let list_index = ListIndex::from_pattern_index(index, list_arity);
+        let stores = list_index_stores(env, list_sym, element_layout, list_index, elem_sym);
+
+        for (symbol, layout, expr) in stores.into_iter().rev() {
+            stmt = Stmt::Let(symbol, expr, layout, env.arena.alloc(stmt));
+        }
+
+        is_productive = true;
+    }
+
+    if let Some((_, Some(rest_sym))) = opt_rest {
+        // the rest is the sublist between the patterns before and after it
+        let (before, after) = match list_arity {
+            ListArity::Slice(before, after) => (before, after),
+            ListArity::Exact(_) => internal_error!("list pattern with a rest has an exact arity"),
+        };
+
+        let rest_sym = procs
+            .symbol_specializations
+            .remove_single(*rest_sym)
+            .unwrap_or(*rest_sym);
+
+        let usize_layout = Layout::usize(env.target_info);
+        let len_sym = env.unique_symbol();
+        let start_sym = env.unique_symbol();
+        let dropped_sym = env.unique_symbol();
+        let rest_len_sym = env.unique_symbol();
+
+        let len_expr = low_level_call(env, LowLevel::ListLen, env.arena.alloc([list_sym]));
+        let rest_len_expr = low_level_call(
+            env,
+            LowLevel::NumSubWrap,
+            env.arena.alloc([len_sym, dropped_sym]),
+        );
+        let rest_expr = low_level_call(
+            env,
+            LowLevel::ListSublist,
+            env.arena.alloc([list_sym, start_sym, rest_len_sym]),
+        );
+
+        let stores = [
+            (len_sym, usize_layout, len_expr),
+            (start_sym, usize_layout, usize_literal(before as u64)),
+            (
+                dropped_sym,
+                usize_layout,
+                usize_literal((before + after) as u64),
+            ),
+            (rest_len_sym, usize_layout, rest_len_expr),
+            (rest_sym, list_layout, rest_expr),
+        ];
+
+        for (symbol, layout, expr) in stores.into_iter().rev() {
+            stmt = Stmt::Let(symbol, expr, layout, env.arena.alloc(stmt));
+        }
+
+        is_productive = true;
+    }
+
+    if is_productive {
+        StorePattern::Productive(stmt)
+    } else {
+        StorePattern::NotProductive(stmt)
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn store_newtype_pattern<'a>(
     env: &mut Env<'a, '_>,
@@ -7413,6 +7999,71 @@ fn store_record_destruct<'a>(
     StorePattern::Productive(stmt)
 }
 
+#[allow(clippy::too_many_arguments)]
+fn store_tuple_destruct<'a>(
+    env: &mut Env<'a, '_>,
+    procs: &mut Procs<'a>,
+    layout_cache: &mut LayoutCache<'a>,
+    destruct: &TupleDestruct<'a>,
+    index: u64,
+    outer_symbol: Symbol,
+    sorted_elems: &'a [Layout<'a>],
+    mut stmt: Stmt<'a>,
+) -> StorePattern<'a> {
+    use Pattern::*;
+
+    let load = Expr::StructAtIndex {
+        index,
+        field_layouts: sorted_elems,
+        structure: outer_symbol,
+    };
+
+    match &destruct.pat {
+        Identifier(symbol) => {
+            let specialization_symbol = procs
+                .symbol_specializations
+                .remove_single(*symbol)
+                // Can happen when the symbol was never used under this body, and hence has no
+                // requested specialization.
+                .unwrap_or(*symbol);
+
+            stmt = Stmt::Let(
+                specialization_symbol,
+                load,
+                destruct.layout,
+                env.arena.alloc(stmt),
+            );
+        }
+        Underscore => {
+            // Elements not bound in the source are guarded with the underscore pattern; see the
+            // note in `store_record_destruct`.
+            return StorePattern::NotProductive(stmt);
+        }
+        IntLiteral(_, _)
+        | FloatLiteral(_, _)
+        | DecimalLiteral(_)
+        | EnumLiteral { .. }
+        | BitLiteral { .. }
+        | StrLiteral(_) => {
+            return StorePattern::NotProductive(stmt);
+        }
+
+        _ => {
+            let symbol = env.unique_symbol();
+
+            match store_pattern_help(env, procs, layout_cache, &destruct.pat, symbol, stmt) {
+                StorePattern::Productive(new) => {
+                    stmt = new;
+                    stmt = Stmt::Let(symbol, load, destruct.layout, env.arena.alloc(stmt));
+                }
+                StorePattern::NotProductive(stmt) => return StorePattern::NotProductive(stmt),
+            }
+        }
+    }
+
+    StorePattern::Productive(stmt)
+}
+
 /// We want to re-use symbols that are not function symbols
 /// for any other expression, we create a new symbol, and will
 /// later make sure it gets assigned the correct value.
@@ -7590,6 +8241,41 @@ where
     }
 }
 
+/// Binds the `dbg` message to `dbg_symbol` and prints it before running `remainder`.
+fn compile_dbg<'a>(
+    env: &mut Env<'a, '_>,
+    procs: &mut Procs<'a>,
+    layout_cache: &mut LayoutCache<'a>,
+    dbg_symbol: Symbol,
+    dbg_var: Variable,
+    loc_message: Loc<roc_can::expr::Expr>,
+    remainder: Stmt<'a>,
+) -> Stmt<'a> {
+    // A `dbg` without a continuation evaluates to its message, so the remainder may have
+    // requested a specialization of the message symbol. The message is never generalized,
+    // so there is at most one.
+    let dbg_symbol = procs
+        .symbol_specializations
+        .remove_single(dbg_symbol)
+        .unwrap_or(dbg_symbol);
+
+    let stmt = Stmt::Dbg {
+        symbol: dbg_symbol,
+        region: loc_message.region,
+        remainder: env.arena.alloc(remainder),
+    };
+
+    with_hole_at(
+        env,
+        loc_message,
+        dbg_var,
+        procs,
+        layout_cache,
+        dbg_symbol,
+        env.arena.alloc(stmt),
+    )
+}
+
 fn force_thunk<'a>(
     env: &mut Env<'a, '_>,
     thunk_name: Symbol,
@@ -7855,9 +8541,9 @@ fn assign_to_symbol<'a>(
             )
         }
         Value(_symbol) => result,
-        NotASymbol => with_hole(
+        NotASymbol => with_hole_at(
             env,
-            loc_arg.value,
+            loc_arg,
             arg_var,
             procs,
             layout_cache,
@@ -7867,6 +8553,29 @@ fn assign_to_symbol<'a>(
     }
 }
 
+/// Like [`with_hole`], but also records where `loc_expr` is in the source, see [`Proc::let_regions`]
+fn with_hole_at<'a>(
+    env: &mut Env<'a, '_>,
+    loc_expr: Loc<roc_can::expr::Expr>,
+    variable: Variable,
+    procs: &mut Procs<'a>,
+    layout_cache: &mut LayoutCache<'a>,
+    assigned: Symbol,
+    hole: &'a Stmt<'a>,
+) -> Stmt<'a> {
+    procs.let_regions.push((assigned, loc_expr.region));
+
+    with_hole(
+        env,
+        loc_expr.value,
+        variable,
+        procs,
+        layout_cache,
+        assigned,
+        hole,
+    )
+}
+
 fn assign_to_symbols<'a, I>(
     env: &mut Env<'a, '_>,
     procs: &mut Procs<'a>,
@@ -8665,6 +9374,7 @@ pub enum Pattern<'a> {
     StrLiteral(Box<str>),
 
     RecordDestructure(Vec<'a, RecordDestruct<'a>>, &'a [Layout<'a>]),
+    TupleDestructure(Vec<'a, TupleDestruct<'a>>, &'a [Layout<'a>]),
     NewtypeDestructure {
         tag_name: TagName,
         arguments: Vec<'a, (Pattern<'a>, Layout<'a>)>,
@@ -8680,6 +9390,98 @@ pub enum Pattern<'a> {
         opaque: Symbol,
         argument: Box<(Pattern<'a>, Layout<'a>)>,
     },
+    List {
+        arity: ListArity,
+        list_layout: Layout<'a>,
+        element_layout: Layout<'a>,
+        elements: Vec<'a, Pattern<'a>>,
+        opt_rest: Option<(usize, Option<Symbol>)>,
+    },
+}
+
+/// Where an element matched by a list pattern lives in the list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ListIndex {
+    /// Counted from the start of the list; `0` is the first element.
+    FromStart(u64),
+    /// Counted from the end of the list; `0` is the last element.
+    FromEnd(u64),
+}
+
+impl ListIndex {
+    /// The position of the `index`th element pattern of a list pattern with the given arity.
+    pub fn from_pattern_index(index: usize, arity: ListArity) -> Self {
+        match arity {
+            ListArity::Exact(_) => ListIndex::FromStart(index as u64),
+            ListArity::Slice(before, _) if index < before => ListIndex::FromStart(index as u64),
+            ListArity::Slice(before, after) => {
+                ListIndex::FromEnd((before + after - 1 - index) as u64)
+            }
+        }
+    }
+}
+
+/// Stores that load the element at `index` of the list `list_sym` into `elem_sym`, in
+/// top-to-bottom order. The caller must have checked that the list is long enough.
+pub(crate) fn list_index_stores<'a>(
+    env: &mut Env<'a, '_>,
+    list_sym: Symbol,
+    element_layout: Layout<'a>,
+    index: ListIndex,
+    elem_sym: Symbol,
+) -> Vec<'a, (Symbol, Layout<'a>, Expr<'a>)> {
+    let usize_layout = Layout::usize(env.target_info);
+    let mut stores = Vec::with_capacity_in(4, env.arena);
+
+    let index_sym = match index {
+        ListIndex::FromStart(index) => {
+            let index_sym = env.unique_symbol();
+            stores.push((index_sym, usize_layout, usize_literal(index)));
+
+            index_sym
+        }
+        ListIndex::FromEnd(index) => {
+            let len_sym = env.unique_symbol();
+            let offset_sym = env.unique_symbol();
+            let index_sym = env.unique_symbol();
+
+            let len_expr = low_level_call(env, LowLevel::ListLen, env.arena.alloc([list_sym]));
+            let index_expr = low_level_call(
+                env,
+                LowLevel::NumSubWrap,
+                env.arena.alloc([len_sym, offset_sym]),
+            );
+
+            stores.push((len_sym, usize_layout, len_expr));
+            stores.push((offset_sym, usize_layout, usize_literal(index + 1)));
+            stores.push((index_sym, usize_layout, index_expr));
+
+            index_sym
+        }
+    };
+
+    let elem_expr = low_level_call(
+        env,
+        LowLevel::ListGetUnsafe,
+        env.arena.alloc([list_sym, index_sym]),
+    );
+    stores.push((elem_sym, element_layout, elem_expr));
+
+    stores
+}
+
+fn usize_literal<'a>(n: u64) -> Expr<'a> {
+    Expr::Literal(Literal::Int((n as i128).to_ne_bytes()))
+}
+
+fn low_level_call<'a>(env: &mut Env<'a, '_>, op: LowLevel, arguments: &'a [Symbol]) -> Expr<'a> {
+    Expr::Call(Call {
+        call_type: CallType::LowLevel {
+            op,
+            update_mode: env.next_update_mode_id(),
+        },
+        arguments,
+    })
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -8690,6 +9492,14 @@ pub struct RecordDestruct<'a> {
     pub typ: DestructType<'a>,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct TupleDestruct<'a> {
+    pub index: usize,
+    pub variable: Variable,
+    pub layout: Layout<'a>,
+    pub pat: Pattern<'a>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum DestructType<'a> {
     Required(Symbol),
@@ -9372,6 +10182,99 @@ fn from_can_pattern_help<'a>(
                 field_layouts.into_bump_slice(),
             ))
         }
+
+        TupleDestructure {
+            whole_var,
+            destructs,
+            ..
+        } => {
+            // sorted elements based on the type
+            let sorted_elems = {
+                let mut layout_env = layout::Env::from_components(
+                    layout_cache,
+                    env.subs,
+                    env.arena,
+                    env.target_info,
+                );
+                crate::layout::sort_tuple_elems(&mut layout_env, *whole_var)
+                    .map_err(RuntimeError::from)?
+            };
+
+            // sorted elements based on the destruct
+            let mut mono_destructs = Vec::with_capacity_in(destructs.len(), env.arena);
+            let mut destructs_by_index = BumpMap::with_capacity_in(destructs.len(), env.arena);
+            destructs_by_index.extend(destructs.iter().map(|x| (x.value.destruct_index, x)));
+
+            let mut elem_layouts = Vec::with_capacity_in(sorted_elems.len(), env.arena);
+
+            // As with records, step through the elements in the order of the type; elements that
+            // the pattern does not mention are destructured with an Underscore.
+            for (index, variable, elem_layout) in sorted_elems.into_iter() {
+                let pat = match destructs_by_index.remove(&index) {
+                    Some(destruct) => from_can_pattern_help(
+                        env,
+                        procs,
+                        layout_cache,
+                        &destruct.value.typ.1.value,
+                        assignments,
+                    )?,
+                    None => Pattern::Underscore,
+                };
+
+                mono_destructs.push(TupleDestruct {
+                    index,
+                    variable,
+                    layout: elem_layout,
+                    pat,
+                });
+
+                elem_layouts.push(elem_layout);
+            }
+
+            Ok(Pattern::TupleDestructure(
+                mono_destructs,
+                elem_layouts.into_bump_slice(),
+            ))
+        }
+
+        List {
+            list_var,
+            elem_var: _,
+            patterns,
+        } => {
+            let list_layout = layout_cache
+                .from_var(env.arena, *list_var, env.subs)
+                .map_err(RuntimeError::from)?;
+
+            let element_layout = match list_layout {
+                Layout::Builtin(Builtin::List(element_layout)) => *element_layout,
+                _ => internal_error!("list pattern has non-list layout {:?}", list_layout),
+            };
+
+            let mut elements = Vec::with_capacity_in(patterns.patterns.len(), env.arena);
+            for loc_pattern in patterns.patterns.iter() {
+                let mono_pattern = from_can_pattern_help(
+                    env,
+                    procs,
+                    layout_cache,
+                    &loc_pattern.value,
+                    assignments,
+                )?;
+                elements.push(mono_pattern);
+            }
+
+            let opt_rest = patterns
+                .opt_rest
+                .map(|(index, opt_rest_sym)| (index, opt_rest_sym.map(|loc_sym| loc_sym.value)));
+
+            Ok(Pattern::List {
+                arity: patterns.arity(),
+                list_layout,
+                element_layout,
+                elements,
+                opt_rest,
+            })
+        }
     }
 }
 
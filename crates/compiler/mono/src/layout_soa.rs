@@ -1,4 +1,4 @@
-use crate::layout::{ext_var_is_empty_record, ext_var_is_empty_tag_union};
+use crate::layout::{ext_var_is_empty_record, ext_var_is_empty_tag_union, ext_var_is_empty_tuple};
 use roc_builtins::bitcode::{FloatWidth, IntWidth};
 use roc_collections::all::MutMap;
 use roc_module::symbol::Symbol;
@@ -825,6 +825,13 @@ impl Layout {
 
                 Ok(Layout::Struct(slice))
             }
+            FlatType::Tuple(elems, ext) => {
+                debug_assert!(ext_var_is_empty_tuple(subs, *ext));
+
+                let slice = Layout::from_variable_slice(layouts, subs, elems.variables())?;
+
+                Ok(Layout::Struct(slice))
+            }
             FlatType::TagUnion(union_tags, ext) => {
                 debug_assert!(ext_var_is_empty_tag_union(subs, *ext));
 
@@ -867,6 +874,7 @@ impl Layout {
             }
             FlatType::Erroneous(_) => Err(TypeError(())),
             FlatType::EmptyRecord => Ok(Layout::UNIT),
+            FlatType::EmptyTuple => Ok(Layout::UNIT),
             FlatType::EmptyTagUnion => Ok(Layout::VOID),
         }
     }
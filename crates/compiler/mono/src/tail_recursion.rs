@@ -297,6 +297,26 @@ fn insert_jumps<'a>(
             None => None,
         },
 
+        Dbg {
+            symbol,
+            region,
+            remainder,
+        } => match insert_jumps(
+            arena,
+            remainder,
+            goal_id,
+            needle,
+            needle_arguments,
+            needle_result,
+        ) {
+            Some(cont) => Some(arena.alloc(Dbg {
+                symbol: *symbol,
+                region: *region,
+                remainder: cont,
+            })),
+            None => None,
+        },
+
         Ret(_) => None,
         Jump(_, _) => None,
         RuntimeError(_) => None,
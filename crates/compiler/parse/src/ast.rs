@@ -165,6 +165,8 @@ pub enum Expr<'a> {
     Str(StrLiteral<'a>), // string without escapes in it
     /// Look up exactly one field on a record, e.g. (expr).foo.
     Access(&'a Expr<'a>, &'a str),
+    /// Look up exactly one element of a tuple, e.g. (expr).1.
+    TupleAccess(&'a Expr<'a>, &'a str),
     /// e.g. `.foo`
     AccessorFunction(&'a str),
     /// eg 'b'
@@ -180,6 +182,9 @@ pub enum Expr<'a> {
 
     Record(Collection<'a, Loc<AssignedField<'a, Expr<'a>>>>),
 
+    /// A tuple with at least two elements, e.g. (1, "foo")
+    Tuple(Collection<'a, &'a Loc<Expr<'a>>>),
+
     // Lookups
     Var {
         module_name: &'a str, // module_name will only be filled if the original Roc code stated something like `5 + SomeModule.myVar`, module_name will be blank if it was `5 + myVar`
@@ -200,6 +205,8 @@ pub enum Expr<'a> {
     Defs(&'a Defs<'a>, &'a Loc<Expr<'a>>),
     Backpassing(&'a [Loc<Pattern<'a>>], &'a Loc<Expr<'a>>, &'a Loc<Expr<'a>>),
     Expect(&'a Loc<Expr<'a>>, &'a Loc<Expr<'a>>),
+    /// `dbg value` followed by an optional continuation; without one, evaluates to `value`
+    Dbg(&'a Loc<Expr<'a>>, Option<&'a Loc<Expr<'a>>>),
 
     // Application
     /// To apply by name, do Apply(Var(...), ...)
@@ -230,6 +237,9 @@ pub enum Expr<'a> {
     // Problems
     MalformedIdent(&'a str, crate::ident::BadIdent),
     MalformedClosure,
+    /// The source of a def that failed to parse. When the parser recovers from a syntax error,
+    /// this stands in for the body of the def.
+    Malformed(&'a str),
     // Both operators were non-associative, e.g. (True == False == False).
     // We should tell the author to disambiguate by grouping them with parens.
     PrecedenceConflict(&'a PrecedenceConflict<'a>),
@@ -519,6 +529,13 @@ pub enum TypeAnnotation<'a> {
         ext: Option<&'a Loc<TypeAnnotation<'a>>>,
     },
 
+    Tuple {
+        elems: Collection<'a, Loc<TypeAnnotation<'a>>>,
+        /// The row type variable in an open tuple, e.g. the `r` in `( Str, Str )r`.
+        /// This is None if it's a closed tuple annotation like `( Str, Str )`.
+        ext: Option<&'a Loc<TypeAnnotation<'a>>>,
+    },
+
     /// A tag union, e.g. `[
     TagUnion {
         /// The row type variable in an open tag union, e.g. the `a` in `[Foo, Bar]a`.
@@ -633,6 +650,16 @@ pub enum Pattern<'a> {
     /// In practice, these patterns will always be Identifier
     RecordDestructure(Collection<'a, Loc<Pattern<'a>>>),
 
+    /// A tuple destructure with at least two elements, e.g. (x, _, Ok y)
+    Tuple(Collection<'a, Loc<Pattern<'a>>>),
+
+    /// A list pattern, e.g. [first, .. as rest] or [.., last]
+    List(Collection<'a, Loc<Pattern<'a>>>),
+
+    /// The rest of a list, e.g. `..` or `.. as rest`
+    /// Can only occur inside of a List
+    ListRest(Option<Loc<&'a str>>),
+
     /// A required field pattern, e.g. { x: Just 0 } -> ...
     /// Can only occur inside of a RecordDestructure
     RequiredField(&'a str, &'a Loc<Pattern<'a>>),
@@ -739,6 +766,21 @@ impl<'a> Pattern<'a> {
                 .iter()
                 .zip(fields_y.iter())
                 .all(|(p, q)| p.value.equivalent(&q.value)),
+            (Tuple(elems_x), Tuple(elems_y)) => {
+                elems_x.len() == elems_y.len()
+                    && elems_x
+                        .iter()
+                        .zip(elems_y.iter())
+                        .all(|(p, q)| p.value.equivalent(&q.value))
+            }
+            (List(elems_x), List(elems_y)) => {
+                elems_x.len() == elems_y.len()
+                    && elems_x
+                        .iter()
+                        .zip(elems_y.iter())
+                        .all(|(p, q)| p.value.equivalent(&q.value))
+            }
+            (ListRest(x), ListRest(y)) => x.map(|x| x.value) == y.map(|y| y.value),
             (RequiredField(x, inner_x), RequiredField(y, inner_y)) => {
                 x == y && inner_x.value.equivalent(&inner_y.value)
             }
@@ -5,12 +5,12 @@ use crate::ast::{
 use crate::blankspace::{
     space0_after_e, space0_around_ee, space0_before_e, space0_before_optional_after, space0_e,
 };
-use crate::ident::{lowercase_ident, parse_ident, Ident};
+use crate::ident::{is_tuple_index, lowercase_ident, parse_ident, tuple_index, Ident};
 use crate::keyword;
 use crate::parser::{
     self, backtrackable, optional, sep_by1, sep_by1_e, specialize, specialize_ref, then,
     trailing_sep_by0, word1, word2, EExpect, EExpr, EIf, EInParens, ELambda, EList, ENumber,
-    EPattern, ERecord, EString, EType, EWhen, Either, ParseResult, Parser,
+    EPattern, ERecord, EString, EType, EWhen, Either, ParseResult, Parser, SyntaxError,
 };
 use crate::pattern::{loc_closure_param, loc_has_parser};
 use crate::state::State;
@@ -66,6 +66,13 @@ pub struct ExprParseOptions {
     ///
     /// > Just foo if foo == 2 -> ...
     check_for_arrow: bool,
+
+    /// End the expression at a comma that does not start multi-backpassing
+    /// This is usually false, but true for elements of a parenthesized expression,
+    /// where the comma may instead separate tuple elements
+    ///
+    /// > (a, b <- foo ...) vs. (a, b)
+    comma_may_end_expr: bool,
 }
 
 impl Default for ExprParseOptions {
@@ -73,6 +80,7 @@ impl Default for ExprParseOptions {
         ExprParseOptions {
             accept_multi_backpassing: true,
             check_for_arrow: true,
+            comma_may_end_expr: false,
         }
     }
 }
@@ -84,35 +92,50 @@ pub fn expr_help<'a>(min_indent: u32) -> impl Parser<'a, Expr<'a>, EExpr<'a>> {
 }
 
 fn loc_expr_in_parens_help<'a>(min_indent: u32) -> impl Parser<'a, Loc<Expr<'a>>, EInParens<'a>> {
-    move |arena, state| {
-        let (_, loc_expr, state) = loc_expr_in_parens_help_help(min_indent).parse(arena, state)?;
-
-        Ok((
-            MadeProgress,
-            Loc {
-                region: loc_expr.region,
-                value: Expr::ParensAround(arena.alloc(loc_expr.value)),
-            },
-            state,
-        ))
-    }
-}
+    move |arena, state: State<'a>| {
+        let start = state.pos();
 
-fn loc_expr_in_parens_help_help<'a>(
-    min_indent: u32,
-) -> impl Parser<'a, Loc<Expr<'a>>, EInParens<'a>> {
-    between!(
-        word1(b'(', EInParens::Open),
-        space0_around_ee(
-            specialize_ref(EInParens::Expr, move |arena, state| parse_loc_expr(
-                min_indent, arena, state
-            )),
+        let (_, elems, state) = parens_or_tuple_e!(
+            specialize_ref(EInParens::Expr, move |arena, state| {
+                parse_loc_expr_with_options(
+                    min_indent,
+                    ExprParseOptions {
+                        comma_may_end_expr: true,
+                        ..Default::default()
+                    },
+                    arena,
+                    state,
+                )
+            }),
             min_indent,
+            EInParens::Open,
+            EInParens::Empty,
+            EInParens::End,
+            EInParens::End,
             EInParens::IndentOpen,
-            EInParens::IndentEnd,
-        ),
-        word1(b')', EInParens::End)
-    )
+            EInParens::IndentEnd
+        )
+        .parse(arena, state)?;
+
+        match elems.items {
+            [loc_expr] => Ok((
+                MadeProgress,
+                Loc {
+                    region: loc_expr.region,
+                    value: Expr::ParensAround(arena.alloc(loc_expr.value)),
+                },
+                state,
+            )),
+            _ => Ok((
+                MadeProgress,
+                Loc {
+                    region: Region::new(start, state.pos()),
+                    value: Expr::Tuple(elems.ptrify_items(arena)),
+                },
+                state,
+            )),
+        }
+    }
 }
 
 fn loc_expr_in_parens_etc_help<'a>(min_indent: u32) -> impl Parser<'a, Loc<Expr<'a>>, EExpr<'a>> {
@@ -146,7 +169,7 @@ fn loc_expr_in_parens_etc_help<'a>(min_indent: u32) -> impl Parser<'a, Loc<Expr<
                 // Wrap the previous answer in the new one, so we end up
                 // with a nested Expr. That way, `foo.bar.baz` gets represented
                 // in the AST as if it had been written (foo.bar).baz all along.
-                value = Expr::Access(arena.alloc(value), field);
+                value = field_or_tuple_access(arena, value, field);
             }
         }
 
@@ -183,10 +206,22 @@ fn record_field_access_chain<'a>() -> impl Parser<'a, Vec<'a, &'a str>, EExpr<'a
 fn record_field_access<'a>() -> impl Parser<'a, &'a str, EExpr<'a>> {
     skip_first!(
         word1(b'.', EExpr::Access),
-        specialize(|_, pos| EExpr::Access(pos), lowercase_ident())
+        specialize(
+            |_, pos| EExpr::Access(pos),
+            one_of!(lowercase_ident(), tuple_index())
+        )
     )
 }
 
+/// Access a record field like `foo` or a tuple element like `0` on an expression.
+fn field_or_tuple_access<'a>(arena: &'a Bump, value: Expr<'a>, field: &'a str) -> Expr<'a> {
+    if is_tuple_index(field) {
+        Expr::TupleAccess(arena.alloc(value), field)
+    } else {
+        Expr::Access(arena.alloc(value), field)
+    }
+}
+
 /// In some contexts we want to parse the `_` as an expression, so it can then be turned into a
 /// pattern later
 fn parse_loc_term_or_underscore_or_conditional<'a>(
@@ -355,6 +390,7 @@ fn parse_expr_start<'a>(
             when::expr_help(min_indent, options)
         )),
         loc!(specialize(EExpr::Expect, expect_help(min_indent, options))),
+        loc!(specialize(EExpr::Dbg, dbg_help(min_indent, options))),
         loc!(specialize(EExpr::Lambda, closure_help(min_indent, options))),
         loc!(move |a, s| parse_expr_operator_chain(min_indent, options, start_column, a, s)),
         fail_expr_start_e()
@@ -605,10 +641,10 @@ fn numeric_negate_expression<'a, T>(
 fn parse_defs_end<'a>(
     _options: ExprParseOptions,
     start_column: u32,
-    mut defs: Defs<'a>,
+    defs: &mut Defs<'a>,
     arena: &'a Bump,
     state: State<'a>,
-) -> ParseResult<'a, Defs<'a>, EExpr<'a>> {
+) -> ParseResult<'a, (), EExpr<'a>> {
     let min_indent = start_column;
 
     let mut global_state = state;
@@ -649,7 +685,7 @@ fn parse_defs_end<'a>(
                 match parse_expect.parse(arena, state) {
                     Err((_, _, _)) => {
                         // a hacky way to get expression-based error messages. TODO fix this
-                        return Ok((NoProgress, defs, initial));
+                        return Ok((NoProgress, (), initial));
                     }
 
                     Ok((_, expect_flavor, state)) => {
@@ -701,7 +737,7 @@ fn parse_defs_end<'a>(
             }
             Err((MadeProgress, _, _)) => {
                 // a hacky way to get expression-based error messages. TODO fix this
-                return Ok((NoProgress, defs, initial));
+                return Ok((NoProgress, (), initial));
             }
             Ok((_, loc_pattern, state)) => {
                 // First let's check whether this is an ability definition.
@@ -958,7 +994,7 @@ fn parse_defs_end<'a>(
                         continue;
                     }
 
-                    _ => return Ok((MadeProgress, defs, initial)),
+                    _ => return Ok((MadeProgress, (), initial)),
                 }
             }
         }
@@ -968,15 +1004,15 @@ fn parse_defs_end<'a>(
 fn parse_defs_expr<'a>(
     options: ExprParseOptions,
     start_column: u32,
-    defs: Defs<'a>,
+    mut defs: Defs<'a>,
     arena: &'a Bump,
     state: State<'a>,
 ) -> ParseResult<'a, Expr<'a>, EExpr<'a>> {
     let min_indent = start_column;
 
-    match parse_defs_end(options, start_column, defs, arena, state) {
+    match parse_defs_end(options, start_column, &mut defs, arena, state) {
         Err(bad) => Err(bad),
-        Ok((_, def_state, state)) => {
+        Ok((_, (), state)) => {
             // this is no def, because there is no `=` or `:`; parse as an expr
             let parse_final_expr = space0_before_e(
                 move |a, s| parse_loc_expr(min_indent, a, s),
@@ -995,7 +1031,7 @@ fn parse_defs_expr<'a>(
                 Ok((_, loc_ret, state)) => {
                     return Ok((
                         MadeProgress,
-                        Expr::Defs(arena.alloc(def_state), arena.alloc(loc_ret)),
+                        Expr::Defs(arena.alloc(defs), arena.alloc(loc_ret)),
                         state,
                     ));
                 }
@@ -1547,9 +1583,8 @@ fn parse_expr_operator<'a>(
                     }
                 }
             }
-            Err((NoProgress, expr, e)) => {
-                todo!("{:?} {:?}", expr, e)
-            }
+            // the operator was consumed, so a missing operand is still progress
+            Err((NoProgress, fail, state)) => Err((MadeProgress, fail, state)),
         },
     }
 }
@@ -1675,22 +1710,15 @@ fn parse_expr_end<'a>(
                 }
                 Err((NoProgress, _, mut state)) => {
                     // try multi-backpassing
-                    if options.accept_multi_backpassing && state.bytes().starts_with(b",") {
+                    if options.accept_multi_backpassing
+                        && state.bytes().starts_with(b",")
+                        && (!options.comma_may_end_expr
+                            || is_multi_backpassing_ahead(min_indent, arena, state.clone()))
+                    {
                         state = state.advance(1);
 
-                        let (_, mut patterns, state) = specialize_ref(
-                            EExpr::Pattern,
-                            crate::parser::sep_by0(
-                                word1(b',', EPattern::Start),
-                                space0_around_ee(
-                                    crate::pattern::loc_pattern_help(min_indent),
-                                    min_indent,
-                                    EPattern::Start,
-                                    EPattern::IndentEnd,
-                                ),
-                            ),
-                        )
-                        .parse(arena, state)?;
+                        let (_, mut patterns, state) =
+                            backpassing_patterns(min_indent).parse(arena, state)?;
 
                         expr_state.consume_spaces(arena);
                         let call = to_call(arena, expr_state.arguments, expr_state.expr);
@@ -1746,6 +1774,34 @@ fn parse_expr_end<'a>(
     }
 }
 
+fn backpassing_patterns<'a>(
+    min_indent: u32,
+) -> impl Parser<'a, Vec<'a, Loc<Pattern<'a>>>, EExpr<'a>> {
+    specialize_ref(
+        EExpr::Pattern,
+        crate::parser::sep_by0(
+            word1(b',', EPattern::Start),
+            space0_around_ee(
+                crate::pattern::loc_pattern_help(min_indent),
+                min_indent,
+                EPattern::Start,
+                EPattern::IndentEnd,
+            ),
+        ),
+    )
+}
+
+/// Whether the comma at the start of `state` is followed by more backpassing patterns and
+/// a `<-`, rather than by e.g. the next element of a tuple.
+fn is_multi_backpassing_ahead<'a>(min_indent: u32, arena: &'a Bump, state: State<'a>) -> bool {
+    let state = state.advance(1);
+
+    match backpassing_patterns(min_indent).parse(arena, state) {
+        Ok((_, _, state)) => state.bytes().starts_with(b"<-"),
+        Err(_) => false,
+    }
+}
+
 pub fn parse_loc_expr<'a>(
     min_indent: u32,
     arena: &'a Bump,
@@ -1842,6 +1898,16 @@ fn expr_to_pattern_help<'a>(arena: &'a Bump, expr: &Expr<'a>) -> Result<Pattern<
             Ok(Pattern::RecordDestructure(patterns))
         }
 
+        Expr::Tuple(elems) => {
+            let patterns = elems.map_items_result(arena, |loc_elem| {
+                let region = loc_elem.region;
+                let value = expr_to_pattern_help(arena, &loc_elem.value)?;
+                Ok(Loc { region, value })
+            })?;
+
+            Ok(Pattern::Tuple(patterns))
+        }
+
         &Expr::Float(string) => Ok(Pattern::FloatLiteral(string)),
         &Expr::Num(string) => Ok(Pattern::NumLiteral(string)),
         Expr::NonBase10Int {
@@ -1856,6 +1922,7 @@ fn expr_to_pattern_help<'a>(arena: &'a Bump, expr: &Expr<'a>) -> Result<Pattern<
         // These would not have parsed as patterns
         Expr::AccessorFunction(_)
         | Expr::Access(_, _)
+        | Expr::TupleAccess(_, _)
         | Expr::List { .. }
         | Expr::Closure(_, _)
         | Expr::Backpassing(_, _, _)
@@ -1864,7 +1931,9 @@ fn expr_to_pattern_help<'a>(arena: &'a Bump, expr: &Expr<'a>) -> Result<Pattern<
         | Expr::If(_, _)
         | Expr::When(_, _)
         | Expr::Expect(_, _)
+        | Expr::Dbg(_, _)
         | Expr::MalformedClosure
+        | Expr::Malformed(_)
         | Expr::PrecedenceConflict { .. }
         | Expr::RecordUpdate { .. }
         | Expr::UnaryOp(_, _) => Err(()),
@@ -1933,12 +2002,13 @@ pub fn toplevel_defs<'a>(min_indent: u32) -> impl Parser<'a, Defs<'a>, EExpr<'a>
         let options = ExprParseOptions {
             accept_multi_backpassing: false,
             check_for_arrow: true,
+            comma_may_end_expr: false,
         };
 
         let mut output = Defs::default();
         let before = Slice::extend_new(&mut output.spaces, initial_space.iter().copied());
 
-        let (_, mut output, state) = parse_defs_end(options, start_column, output, arena, state)?;
+        let (_, (), state) = parse_defs_end(options, start_column, &mut output, arena, state)?;
 
         let (_, final_space, state) =
             space0_e(start_column, EExpr::IndentEnd).parse(arena, state)?;
@@ -1959,6 +2029,192 @@ pub fn toplevel_defs<'a>(min_indent: u32) -> impl Parser<'a, Defs<'a>, EExpr<'a>
     }
 }
 
+/// Parse the defs of a module like [`toplevel_defs`], but recover from syntax errors at top-level
+/// def boundaries. Each def that fails to parse is replaced by a `Body` whose expression is
+/// [`Expr::Malformed`], and its error is returned alongside the defs.
+pub fn toplevel_defs_with_recovery<'a>(
+    arena: &'a Bump,
+    state: State<'a>,
+) -> (Defs<'a>, std::vec::Vec<SyntaxError<'a>>) {
+    let options = ExprParseOptions {
+        accept_multi_backpassing: false,
+        check_for_arrow: true,
+        comma_may_end_expr: false,
+    };
+
+    let mut defs = Defs::default();
+    let mut errors = std::vec::Vec::new();
+
+    let mut state = match space0_e(0, EExpr::IndentEnd).parse(arena, state) {
+        Ok((_, _, state)) => state,
+        Err((_, fail, state)) => {
+            errors.push(SyntaxError::Expr(fail, state.pos()));
+            return (defs, errors);
+        }
+    };
+
+    let start_column = state.column();
+
+    loop {
+        let chunk_start = state.pos();
+        let defs_before = defs.len();
+
+        let (error, def_start) =
+            match parse_defs_end(options, start_column, &mut defs, arena, state.clone()) {
+                Ok((_, (), rest)) => {
+                    // parse_defs_end stops in front of the whitespace before the first thing
+                    // that is not a def
+                    match space0_e(start_column, EExpr::IndentEnd).parse(arena, rest) {
+                        Ok((_, _, rest)) if rest.has_reached_end() => break,
+                        Ok((_, _, rest)) => (SyntaxError::NotEndOfFile(rest.pos()), rest.pos()),
+                        Err((_, fail, rest)) => (SyntaxError::Expr(fail, rest.pos()), rest.pos()),
+                    }
+                }
+                Err((_, fail, _)) => {
+                    // the def that failed starts after the last one that was parsed successfully
+                    let after_last_def = if defs.len() > defs_before {
+                        defs.regions[defs.len() - 1].end()
+                    } else {
+                        chunk_start
+                    };
+                    let def_start = skip_blank(state.original_bytes(), after_last_def);
+
+                    (SyntaxError::Expr(fail, def_start), def_start)
+                }
+            };
+
+        errors.push(error);
+
+        let bytes = state.original_bytes();
+        let resume = next_toplevel_line(bytes, def_start, start_column);
+
+        push_malformed_def(
+            arena,
+            &mut defs,
+            state.clone().with_offset(def_start.offset as usize),
+            resume,
+        );
+
+        if resume == bytes.len() {
+            break;
+        }
+
+        state = state.with_offset(resume);
+    }
+
+    (defs, errors)
+}
+
+/// Skip over whitespace and comments
+fn skip_blank(bytes: &[u8], position: Position) -> Position {
+    let mut offset = position.offset as usize;
+
+    while let Some(byte) = bytes.get(offset) {
+        match byte {
+            b' ' | b'\t' | b'\r' | b'\n' => offset += 1,
+            b'#' => {
+                offset = match bytes[offset..].iter().position(|byte| *byte == b'\n') {
+                    Some(newline) => offset + newline,
+                    None => bytes.len(),
+                }
+            }
+            _ => break,
+        }
+    }
+
+    Position::new(offset as u32)
+}
+
+/// The offset of the first line after the one containing `position` that starts a new def at
+/// `column`, or the end of the input if there is none. A line that starts with a closing bracket
+/// still belongs to the broken def, like the `]` that ends a list written over several lines.
+fn next_toplevel_line(bytes: &[u8], position: Position, column: u32) -> usize {
+    let column = column as usize;
+    let mut offset = position.offset as usize;
+
+    while let Some(newline) = bytes[offset..].iter().position(|byte| *byte == b'\n') {
+        offset += newline + 1;
+
+        let line = &bytes[offset..];
+        let indented = line.iter().take(column).all(|byte| *byte == b' ');
+
+        match line.get(column) {
+            Some(b' ' | b'\t' | b'\r' | b'\n' | b']' | b'}' | b')') | None => {}
+            Some(_) if indented => return offset,
+            Some(_) => {}
+        }
+    }
+
+    bytes.len()
+}
+
+/// Stand in for a def that failed to parse, which spans from `state` to `end`. If the def
+/// at least starts with `name =`, the placeholder still defines `name`, so that references to it
+/// do not produce further errors.
+fn push_malformed_def<'a>(arena: &'a Bump, defs: &mut Defs<'a>, state: State<'a>, end: usize) {
+    let start = state.pos();
+    let source = &state.original_bytes()[start.offset as usize..end];
+
+    // SAFETY: the input is valid UTF-8, and both ends of the slice are at character boundaries
+    let source = unsafe { std::str::from_utf8_unchecked(source) }.trim_end();
+
+    if source.is_empty() {
+        // the error was at the very end of the input
+        return;
+    }
+
+    let region = Region::new(start, start.bump_column(source.len() as u32));
+
+    let malformed_expr = arena.alloc(Loc::at(region, Expr::Malformed(source)));
+
+    let header = space0_after_e(crate::pattern::loc_pattern_help(0), 0, EPattern::IndentEnd);
+
+    let loc_pattern = match header.parse(arena, state) {
+        Ok((
+            _,
+            loc_pattern @ Loc {
+                value: Pattern::Identifier(_),
+                ..
+            },
+            state,
+        )) => match operator().parse(arena, state) {
+            Ok((_, BinOp::Assignment, _)) => Some(loc_pattern),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    match (loc_pattern, defs.last()) {
+        (Some(body_pattern), Some(Err(ValueDef::Annotation(ann_pattern, ann_type))))
+            if ann_pattern.value.equivalent(&body_pattern.value) =>
+        {
+            // join this body with the preceding annotation, like a def that parsed would be
+            let region = Region::span_across(&ann_pattern.region, &region);
+
+            let value_def = ValueDef::AnnotatedBody {
+                ann_pattern: arena.alloc(*ann_pattern),
+                ann_type: arena.alloc(*ann_type),
+                comment: None,
+                body_pattern: arena.alloc(body_pattern),
+                body_expr: malformed_expr,
+            };
+
+            defs.replace_with_value_def(defs.tags.len() - 1, value_def, region);
+        }
+        (Some(body_pattern), _) => {
+            let value_def = ValueDef::Body(arena.alloc(body_pattern), malformed_expr);
+
+            defs.push_value_def(value_def, region, &[], &[]);
+        }
+        (None, _) => {
+            let pattern = arena.alloc(Loc::at(region, Pattern::Malformed(source)));
+            let value_def = ValueDef::Body(pattern, malformed_expr);
+
+            defs.push_value_def(value_def, region, &[], &[]);
+        }
+    }
+}
+
 // PARSER HELPERS
 
 fn closure_help<'a>(
@@ -2361,6 +2617,59 @@ fn expect_help<'a>(
     }
 }
 
+fn dbg_help<'a>(
+    min_indent: u32,
+    options: ExprParseOptions,
+) -> impl Parser<'a, Expr<'a>, EExpect<'a>> {
+    move |arena: &'a Bump, state: State<'a>| {
+        let start_column = state.column();
+
+        let (_, _, state) = parser::keyword_e(keyword::DBG, EExpect::Dbg).parse(arena, state)?;
+
+        let (_, value, state) = space0_before_e(
+            specialize_ref(EExpect::Condition, move |arena, state| {
+                parse_loc_expr_with_options(start_column + 1, options, arena, state)
+            }),
+            start_column + 1,
+            EExpect::IndentCondition,
+        )
+        .parse(arena, state)
+        .map_err(|(_, f, s)| (MadeProgress, f, s))?;
+
+        // Unlike `expect`, the continuation is optional: a `dbg` that is not
+        // followed by anything evaluates to the value it prints.
+        let (_, spaces, cont_state) =
+            match space0_e(min_indent, EExpect::IndentCondition).parse(arena, state.clone()) {
+                Ok(parsed) => parsed,
+                Err(_) => return Ok((MadeProgress, Expr::Dbg(arena.alloc(value), None), state)),
+            };
+
+        let parse_cont = specialize_ref(EExpect::Continuation, move |a, s| {
+            parse_loc_expr(min_indent, a, s)
+        });
+
+        match parse_cont.parse(arena, cont_state) {
+            Ok((_, loc_cont, state)) => {
+                let loc_cont = if spaces.is_empty() {
+                    loc_cont
+                } else {
+                    arena
+                        .alloc(loc_cont.value)
+                        .with_spaces_before(spaces, loc_cont.region)
+                };
+
+                let expr = Expr::Dbg(arena.alloc(value), Some(arena.alloc(loc_cont)));
+
+                Ok((MadeProgress, expr, state))
+            }
+            Err((NoProgress, _, _)) => {
+                Ok((MadeProgress, Expr::Dbg(arena.alloc(value), None), state))
+            }
+            Err((MadeProgress, fail, state)) => Err((MadeProgress, fail, state)),
+        }
+    }
+}
+
 fn if_expr_help<'a>(
     min_indent: u32,
     options: ExprParseOptions,
@@ -2467,12 +2776,13 @@ fn ident_to_expr<'a>(arena: &'a Bump, src: Ident<'a>) -> Expr<'a> {
             };
 
             // The remaining items in the iterator are record field accesses,
-            // e.g. `bar` in `foo.bar.baz`, followed by `baz`
+            // e.g. `bar` in `foo.bar.baz`, followed by `baz`, or tuple element
+            // accesses, e.g. `0` in `foo.0`
             for field in iter {
                 // Wrap the previous answer in the new one, so we end up
                 // with a nested Expr. That way, `foo.bar.baz` gets represented
                 // in the AST as if it had been written (foo.bar).baz all along.
-                answer = Expr::Access(arena.alloc(answer), field);
+                answer = field_or_tuple_access(arena, answer, field);
             }
 
             answer
@@ -2656,7 +2966,7 @@ fn record_literal_help<'a>(min_indent: u32) -> impl Parser<'a, Expr<'a>, EExpr<'
                     // Wrap the previous answer in the new one, so we end up
                     // with a nested Expr. That way, `foo.bar.baz` gets represented
                     // in the AST as if it had been written (foo.bar).baz all along.
-                    value = Expr::Access(arena.alloc(value), field);
+                    value = field_or_tuple_access(arena, value, field);
                 }
             }
 
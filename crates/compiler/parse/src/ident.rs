@@ -38,7 +38,7 @@ pub enum Ident<'a> {
     Tag(&'a str),
     /// @Foo or @Bar
     OpaqueRef(&'a str),
-    /// foo or foo.bar or Foo.Bar.baz.qux
+    /// foo or foo.bar or Foo.Bar.baz.qux or foo.0
     Access {
         module_name: &'a str,
         parts: &'a [&'a str],
@@ -97,6 +97,17 @@ pub fn lowercase_ident<'a>() -> impl Parser<'a, &'a str, ()> {
     }
 }
 
+/// A tuple index, e.g. the `1` in `pair.1`
+pub fn tuple_index<'a>() -> impl Parser<'a, &'a str, ()> {
+    move |_, state: State<'a>| match chomp_tuple_index(state.bytes()) {
+        Err(progress) => Err((progress, (), state)),
+        Ok(index) => {
+            let width = index.len();
+            Ok((MadeProgress, index, state.advance(width)))
+        }
+    }
+}
+
 pub fn tag_name<'a>() -> impl Parser<'a, &'a str, ()> {
     move |arena, state: State<'a>| uppercase_ident().parse(arena, state)
 }
@@ -268,6 +279,26 @@ where
     }
 }
 
+/// a tuple index like the `1` in `pair.1`
+///
+/// A tuple index is a single digit, so only the first ten elements of a tuple can be accessed
+/// this way. Anything longer, like the `100` in `foo.100`, is a malformed field access.
+fn chomp_tuple_index(buffer: &[u8]) -> Result<&str, Progress> {
+    let chomped = buffer.iter().take_while(|b| b.is_ascii_digit()).count();
+
+    if chomped != 1 {
+        Err(NoProgress)
+    } else {
+        let name = unsafe { std::str::from_utf8_unchecked(&buffer[..chomped]) };
+
+        Ok(name)
+    }
+}
+
+pub(crate) fn is_tuple_index(part: &str) -> bool {
+    part.starts_with(|c: char| c.is_ascii_digit())
+}
+
 /// a `.foo` accessor function
 fn chomp_accessor(buffer: &[u8], pos: Position) -> Result<&str, BadIdent> {
     // assumes the leading `.` has been chomped already
@@ -386,6 +417,13 @@ fn chomp_identifier_chain<'a>(
         }
 
         match chomp_access_chain(&buffer[chomped..], &mut parts) {
+            Ok(_) if first_is_uppercase && is_tuple_index(parts[0]) => {
+                // e.g. `Foo.0`; a module name can't be followed by a tuple index
+                Err((
+                    chomped as u32 + 1,
+                    BadIdent::WeirdDotQualified(pos.bump_column(chomped as u32 + 1)),
+                ))
+            }
             Ok(width) => {
                 chomped += width as usize;
 
@@ -504,19 +542,21 @@ fn chomp_access_chain<'a>(buffer: &'a [u8], parts: &mut Vec<'a, &'a str>) -> Res
 
     while let Some(b'.') = buffer.get(chomped) {
         match &buffer.get(chomped + 1..) {
-            Some(slice) => match chomp_lowercase_part(slice) {
-                Ok(name) => {
-                    let value = unsafe {
-                        std::str::from_utf8_unchecked(
-                            &buffer[chomped + 1..chomped + 1 + name.len()],
-                        )
-                    };
-                    parts.push(value);
-
-                    chomped += name.len() + 1;
+            Some(slice) => {
+                match chomp_lowercase_part(slice).or_else(|_| chomp_tuple_index(slice)) {
+                    Ok(name) => {
+                        let value = unsafe {
+                            std::str::from_utf8_unchecked(
+                                &buffer[chomped + 1..chomped + 1 + name.len()],
+                            )
+                        };
+                        parts.push(value);
+
+                        chomped += name.len() + 1;
+                    }
+                    Err(_) => return Err(chomped as u32 + 1),
                 }
-                Err(_) => return Err(chomped as u32 + 1),
-            },
+            }
             None => return Err(chomped as u32 + 1),
         }
     }
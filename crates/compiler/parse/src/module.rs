@@ -38,6 +38,16 @@ pub fn module_defs<'a>() -> impl Parser<'a, Defs<'a>, SyntaxError<'a>> {
     )
 }
 
+/// Like [`module_defs`], but instead of stopping at the first syntax error, recover at the next
+/// top-level def. Returns the defs, with placeholders for the ones that failed to parse, and
+/// every syntax error that was found.
+pub fn module_defs_with_recovery<'a>(
+    arena: &'a bumpalo::Bump,
+    state: State<'a>,
+) -> (Defs<'a>, Vec<SyntaxError<'a>>) {
+    crate::expr::toplevel_defs_with_recovery(arena, state)
+}
+
 pub fn parse_header<'a>(
     arena: &'a bumpalo::Bump,
     state: State<'a>,
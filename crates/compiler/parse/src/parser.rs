@@ -107,6 +107,7 @@ impl_space_problem! {
     EWhen<'a>,
     EAbility<'a>,
     PInParens<'a>,
+    PList<'a>,
     PRecord<'a>
 }
 
@@ -351,6 +352,7 @@ pub enum EExpr<'a> {
     If(EIf<'a>, Position),
 
     Expect(EExpect<'a>, Position),
+    Dbg(EExpect<'a>, Position),
 
     Lambda(ELambda<'a>, Position),
     Underscore(Position),
@@ -366,6 +368,56 @@ pub enum EExpr<'a> {
     IndentEnd(Position),
 }
 
+impl<'a> EExpr<'a> {
+    /// The position this problem was reported at.
+    pub fn get_position(&self) -> Position {
+        use EExpr::*;
+
+        match self {
+            Start(pos)
+            | End(pos)
+            | BadExprEnd(pos)
+            | Space(_, pos)
+            | Dot(pos)
+            | Access(pos)
+            | UnaryNot(pos)
+            | UnaryNegate(pos)
+            | BadOperator(_, pos)
+            | DefMissingFinalExpr(pos)
+            | DefMissingFinalExpr2(_, pos)
+            | Type(_, pos)
+            | Pattern(_, pos)
+            | Ability(_, pos)
+            | IndentDefBody(pos)
+            | IndentEquals(pos)
+            | IndentAnnotation(pos)
+            | Equals(pos)
+            | Colon(pos)
+            | DoubleColon(pos)
+            | Ident(pos)
+            | ElmStyleFunction(_, pos)
+            | MalformedPattern(pos)
+            | QualifiedTag(pos)
+            | BackpassComma(pos)
+            | BackpassArrow(pos)
+            | When(_, pos)
+            | If(_, pos)
+            | Expect(_, pos)
+            | Dbg(_, pos)
+            | Lambda(_, pos)
+            | Underscore(pos)
+            | InParens(_, pos)
+            | Record(_, pos)
+            | Str(_, pos)
+            | SingleQuote(_, pos)
+            | Number(_, pos)
+            | List(_, pos)
+            | IndentStart(pos)
+            | IndentEnd(pos) => *pos,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ENumber {
     End,
@@ -415,6 +467,8 @@ pub enum ERecord<'a> {
 pub enum EInParens<'a> {
     End(Position),
     Open(Position),
+    /// Empty parens, e.g. () is not allowed
+    Empty(Position),
     ///
     Expr(&'a EExpr<'a>, Position),
 
@@ -509,6 +563,7 @@ pub enum EIf<'a> {
 pub enum EExpect<'a> {
     Space(BadInputError, Position),
     Expect(Position),
+    Dbg(Position),
     Condition(&'a EExpr<'a>, Position),
     Continuation(&'a EExpr<'a>, Position),
     IndentCondition(Position),
@@ -524,6 +579,7 @@ pub enum EPattern<'a> {
     Space(BadInputError, Position),
 
     PInParens(PInParens<'a>, Position),
+    List(PList<'a>, Position),
     NumLiteral(ENumber, Position),
 
     IndentStart(Position),
@@ -551,8 +607,21 @@ pub enum PRecord<'a> {
     IndentEnd(Position),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PList<'a> {
+    End(Position),
+    Open(Position),
+    Rest(Position),
+    Pattern(&'a EPattern<'a>, Position),
+
+    Space(BadInputError, Position),
+    IndentOpen(Position),
+    IndentEnd(Position),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PInParens<'a> {
+    Empty(Position),
     End(Position),
     Open(Position),
     Pattern(&'a EPattern<'a>, Position),
@@ -620,6 +689,8 @@ pub enum ETypeTagUnion<'a> {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ETypeInParens<'a> {
+    /// e.g. (), which isn't a valid type
+    Empty(Position),
     End(Position),
     Open(Position),
     ///
@@ -1313,6 +1384,101 @@ macro_rules! collection_trailing_sep_e {
     };
 }
 
+/// Parses either a single element in parentheses, like `(a)`, or a tuple, like `(a, b)`, into a
+/// collection of its elements.
+///
+/// Up to the first comma this is `between!(open, space0_around_ee(elem, ..), close)`, so that
+/// unfinished parentheses are reported the same way whether or not they would have held a tuple.
+#[macro_export]
+macro_rules! parens_or_tuple_e {
+    ($elem:expr, $min_indent:expr, $open_problem:expr, $empty_problem:expr, $end_problem:expr, $close_problem:expr, $indent_open_problem:expr, $indent_end_problem:expr) => {
+        move |arena: &'a bumpalo::Bump, state: $crate::state::State<'a>| {
+            use $crate::parser::Progress::MadeProgress;
+
+            let original_state = state.clone();
+
+            if let Some((b'(', rest)) = state.bytes().split_first() {
+                if rest.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b')') {
+                    return Err((MadeProgress, $empty_problem(state.pos()), original_state));
+                }
+            }
+
+            let (_, first, state) = $crate::skip_first!(
+                $crate::parser::word1(b'(', $open_problem),
+                $crate::blankspace::space0_around_ee(
+                    $elem,
+                    $min_indent,
+                    $indent_open_problem,
+                    $indent_end_problem
+                )
+            )
+            .parse(arena, state)?;
+
+            if !state.bytes().starts_with(b",") {
+                return match $crate::parser::word1(b')', $close_problem).parse(arena, state) {
+                    Ok((_, (), state)) => {
+                        let items = arena.alloc([first]);
+
+                        Ok((
+                            MadeProgress,
+                            $crate::ast::Collection::with_items(items),
+                            state,
+                        ))
+                    }
+                    Err((_, fail, _)) => Err((MadeProgress, fail, original_state)),
+                };
+            }
+
+            // This is a tuple. Like in other collections, a trailing comma is allowed, and the
+            // closing parenthesis may be outdented.
+            let mut items = bumpalo::collections::Vec::with_capacity_in(2, arena);
+            items.push(first);
+
+            let mut state = state;
+            while let Ok((_, (), after_comma)) =
+                $crate::parser::word1(b',', $end_problem).parse(arena, state.clone())
+            {
+                let elem = $crate::blankspace::space0_before_optional_after(
+                    $elem,
+                    $min_indent,
+                    $indent_end_problem,
+                    $indent_end_problem,
+                );
+
+                match elem.parse(arena, after_comma.clone()) {
+                    Ok((_, item, next_state)) => {
+                        items.push(item);
+                        state = next_state;
+                    }
+                    Err(_) => {
+                        state = after_comma;
+                        break;
+                    }
+                }
+            }
+
+            let (_, final_comments, state) =
+                match $crate::blankspace::space0_e(0, $indent_end_problem).parse(arena, state) {
+                    Ok(ok) => ok,
+                    Err((_, fail, _)) => return Err((MadeProgress, fail, original_state)),
+                };
+
+            match $crate::parser::word1(b')', $end_problem).parse(arena, state) {
+                Ok((_, (), state)) => {
+                    let collection = $crate::ast::Collection::with_items_and_comments(
+                        arena,
+                        items.into_bump_slice(),
+                        final_comments,
+                    );
+
+                    Ok((MadeProgress, collection, state))
+                }
+                Err((_, fail, _)) => Err((MadeProgress, fail, original_state)),
+            }
+        }
+    };
+}
+
 #[macro_export]
 macro_rules! succeed {
     ($value:expr) => {
@@ -1,10 +1,11 @@
 use crate::ast::{Has, Pattern};
-use crate::blankspace::{space0_around_ee, space0_before_e, space0_e};
+use crate::blankspace::{space0_before_e, space0_e};
 use crate::ident::{lowercase_ident, parse_ident, Ident};
+use crate::keyword;
 use crate::parser::Progress::{self, *};
 use crate::parser::{
-    backtrackable, optional, specialize, specialize_ref, then, word1, EPattern, PInParens, PRecord,
-    ParseResult, Parser,
+    backtrackable, optional, specialize, specialize_ref, then, word1, word2, EPattern, PInParens,
+    PList, PRecord, ParseResult, Parser,
 };
 use crate::state::State;
 use bumpalo::collections::string::String;
@@ -59,6 +60,7 @@ pub fn loc_pattern_help<'a>(min_indent: u32) -> impl Parser<'a, Loc<Pattern<'a>>
             EPattern::Record,
             crate::pattern::record_pattern_help(min_indent)
         )),
+        loc!(specialize(EPattern::List, list_pattern_help(min_indent))),
         loc!(number_pattern_help()),
         loc!(string_pattern_help()),
         loc!(single_quote_pattern_help()),
@@ -139,6 +141,7 @@ fn loc_parse_tag_pattern_arg<'a>(
             EPattern::Record,
             crate::pattern::record_pattern_help(min_indent)
         )),
+        loc!(specialize(EPattern::List, list_pattern_help(min_indent))),
         loc!(string_pattern_help()),
         loc!(single_quote_pattern_help()),
         loc!(number_pattern_help())
@@ -149,19 +152,89 @@ fn loc_parse_tag_pattern_arg<'a>(
 fn loc_pattern_in_parens_help<'a>(
     min_indent: u32,
 ) -> impl Parser<'a, Loc<Pattern<'a>>, PInParens<'a>> {
-    between!(
-        word1(b'(', PInParens::Open),
-        space0_around_ee(
-            move |arena, state| specialize_ref(PInParens::Pattern, loc_pattern_help(min_indent))
-                .parse(arena, state),
+    move |arena, state: State<'a>| {
+        let start = state.pos();
+
+        let (_, pats, state) = parens_or_tuple_e!(
+            specialize_ref(PInParens::Pattern, loc_pattern_help(min_indent)),
             min_indent,
+            PInParens::Open,
+            PInParens::Empty,
+            PInParens::End,
+            PInParens::End,
             PInParens::IndentOpen,
-            PInParens::IndentEnd,
+            PInParens::IndentEnd
+        )
+        .parse(arena, state)?;
+
+        match pats.items {
+            [loc_pattern] => Ok((MadeProgress, *loc_pattern, state)),
+            _ => Ok((
+                MadeProgress,
+                Loc::at(Region::new(start, state.pos()), Pattern::Tuple(pats)),
+                state,
+            )),
+        }
+    }
+}
+
+fn list_pattern_help<'a>(min_indent: u32) -> impl Parser<'a, Pattern<'a>, PList<'a>> {
+    map!(
+        collection_trailing_sep_e!(
+            word1(b'[', PList::Open),
+            list_element_pattern(min_indent),
+            word1(b',', PList::End),
+            word1(b']', PList::End),
+            min_indent,
+            PList::Open,
+            PList::IndentEnd,
+            Pattern::SpaceBefore
         ),
-        word1(b')', PInParens::End)
+        Pattern::List
     )
 }
 
+fn list_element_pattern<'a>(min_indent: u32) -> impl Parser<'a, Loc<Pattern<'a>>, PList<'a>> {
+    one_of!(
+        list_rest_pattern(min_indent),
+        specialize_ref(PList::Pattern, loc_pattern_help(min_indent)),
+    )
+}
+
+/// The rest of a list pattern, e.g. the `..` in `[first, ..]`, or the
+/// `.. as rest` in `[first, .. as rest]`
+fn list_rest_pattern<'a>(min_indent: u32) -> impl Parser<'a, Loc<Pattern<'a>>, PList<'a>> {
+    move |arena: &'a Bump, state: State<'a>| {
+        let start = state.pos();
+
+        let (_, (), state) = word2(b'.', b'.', PList::Open).parse(arena, state)?;
+
+        let dots_region = Region::new(start, state.pos());
+
+        let (_, opt_as, state) = optional(skip_first!(
+            and!(
+                backtrackable(space0_e(min_indent, PList::IndentEnd)),
+                crate::parser::keyword_e(keyword::AS, PList::End)
+            ),
+            skip_first!(
+                space0_e(min_indent, PList::IndentEnd),
+                loc!(specialize(|_, pos| PList::Rest(pos), lowercase_ident()))
+            )
+        ))
+        .parse(arena, state)?;
+
+        let loc_pattern = match opt_as {
+            Some(loc_ident) => Loc::at(
+                Region::span_across(&dots_region, &loc_ident.region),
+                Pattern::ListRest(Some(loc_ident)),
+            ),
+            None => Loc::at(dots_region, Pattern::ListRest(None)),
+        };
+
+        Ok((MadeProgress, loc_pattern, state))
+    }
+}
+
 fn number_pattern_help<'a>() -> impl Parser<'a, Pattern<'a>, EPattern<'a>> {
     specialize(
         EPattern::NumLiteral,
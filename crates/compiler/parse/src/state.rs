@@ -71,6 +71,19 @@ impl<'a> State<'a> {
         self
     }
 
+    /// The state at `offset` in the same input, e.g. to resume parsing after a syntax error
+    #[must_use]
+    pub(crate) fn with_offset(mut self, offset: usize) -> State<'a> {
+        let line_start = self.original_bytes[..offset]
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .map_or(0, |newline| newline + 1);
+
+        self.offset = offset;
+        self.line_start = Position::new(line_start as u32);
+        self
+    }
+
     /// Returns the current position
     pub const fn pos(&self) -> Position {
         Position::new(self.offset as u32)
@@ -123,7 +123,10 @@ fn term<'a>(
             one_of!(
                 loc_wildcard(),
                 loc_inferred(),
-                specialize(EType::TInParens, loc_type_in_parens(min_indent)),
+                specialize(
+                    EType::TInParens,
+                    loc_type_in_parens(min_indent, stop_at_surface_has)
+                ),
                 loc!(specialize(
                     EType::TRecord,
                     record_type(min_indent, stop_at_surface_has)
@@ -196,7 +199,10 @@ fn loc_applied_arg<'a>(
             one_of!(
                 loc_wildcard(),
                 loc_inferred(),
-                specialize(EType::TInParens, loc_type_in_parens(min_indent)),
+                specialize(
+                    EType::TInParens,
+                    loc_type_in_parens(min_indent, stop_at_surface_has)
+                ),
                 loc!(specialize(
                     EType::TRecord,
                     record_type(min_indent, stop_at_surface_has)
@@ -222,21 +228,40 @@ fn loc_applied_arg<'a>(
 
 fn loc_type_in_parens<'a>(
     min_indent: u32,
+    stop_at_surface_has: bool,
 ) -> impl Parser<'a, Loc<TypeAnnotation<'a>>, ETypeInParens<'a>> {
-    between!(
-        word1(b'(', ETypeInParens::Open),
-        space0_around_ee(
-            move |arena, state| specialize_ref(
-                ETypeInParens::Type,
-                expression(min_indent, true, false)
-            )
-            .parse(arena, state),
+    move |arena, state: State<'a>| {
+        let start = state.pos();
+
+        let (_, elems, state) = parens_or_tuple_e!(
+            specialize_ref(ETypeInParens::Type, expression(min_indent, true, false)),
             min_indent,
-            ETypeInParens::IndentOpen,
+            ETypeInParens::Open,
+            ETypeInParens::Empty,
+            ETypeInParens::End,
             ETypeInParens::IndentEnd,
-        ),
-        word1(b')', ETypeInParens::IndentEnd)
-    )
+            ETypeInParens::IndentOpen,
+            ETypeInParens::IndentEnd
+        )
+        .parse(arena, state)?;
+
+        match elems.items {
+            [loc_type] => Ok((MadeProgress, *loc_type, state)),
+            _ => {
+                // This could be an open tuple, e.g. `(Str, Str)a`
+                let (_, ext, state) = optional(allocated(specialize_ref(
+                    ETypeInParens::Type,
+                    term(min_indent, stop_at_surface_has),
+                )))
+                .parse(arena, state)?;
+
+                let region = Region::new(start, state.pos());
+                let result = TypeAnnotation::Tuple { elems, ext };
+
+                Ok((MadeProgress, Loc::at(region, result), state))
+            }
+        }
+    }
 }
 
 #[inline(always)]
@@ -0,0 +1 @@
+(dbg 42) + 1
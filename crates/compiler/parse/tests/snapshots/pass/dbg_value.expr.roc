@@ -0,0 +1,3 @@
+x = dbg 42
+
+x + 1
@@ -0,0 +1,9 @@
+when [] is
+    [] -> {}
+    [..] -> {}
+    [_, .., _, ..] -> {}
+    [a, b, c, d] -> {}
+    [a, b, ..] -> {}
+    [.., c, d] -> {}
+    [[A], [..], [a]] -> {}
+    [[[], []], [[], x]] -> {}
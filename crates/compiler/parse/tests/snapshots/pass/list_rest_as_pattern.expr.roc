@@ -0,0 +1,4 @@
+when [] is
+    [first, .. as rest] -> {}
+    [.., last] -> {}
+    [  ..  as   rest  ] -> {}
@@ -0,0 +1,3 @@
+pair = (1, "one")
+
+(pair.0, (3, 4).1)
@@ -0,0 +1,4 @@
+swap : (a, b) -> (b, a)
+swap = \(x, y) -> (y, x)
+
+swap
@@ -0,0 +1,4 @@
+f : (Str, Str)a -> (Str, Str)a
+f = \x -> x
+
+f
@@ -0,0 +1,3 @@
+when (1, 2) is
+    (1, x) -> x
+    (_, y) -> y
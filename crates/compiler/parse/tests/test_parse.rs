@@ -19,11 +19,11 @@ mod test_parse {
     use roc_parse::ast::StrLiteral::*;
     use roc_parse::ast::StrSegment::*;
     use roc_parse::ast::{self, EscapedChar};
-    use roc_parse::module::module_defs;
+    use roc_parse::module::{module_defs, module_defs_with_recovery};
     use roc_parse::parser::{Parser, SyntaxError};
     use roc_parse::state::State;
     use roc_parse::test_helpers::parse_expr_with;
-    use roc_region::all::{Loc, Region};
+    use roc_region::all::{Loc, Position, Region};
     use roc_test_utils::assert_multiline_str_eq;
     use std::{f64, i64};
 
@@ -147,6 +147,10 @@ mod test_parse {
         pass/comment_before_op.expr,
         pass/comment_inside_empty_list.expr,
         pass/comment_with_non_ascii.expr,
+        pass/dbg.expr,
+        pass/dbg_in_parens.expr,
+        pass/dbg_value.expr,
+        pass/dbg_without_continuation.expr,
         pass/destructure_tag_assignment.expr,
         pass/empty_app_header.header,
         pass/empty_hosted_header.header,
@@ -170,6 +174,8 @@ mod test_parse {
         pass/list_closing_indent_not_enough.expr,
         pass/list_closing_same_indent_no_trailing_comma.expr,
         pass/list_closing_same_indent_with_trailing_comma.expr,
+        pass/list_patterns.expr,
+        pass/list_rest_as_pattern.expr,
         pass/lowest_float.expr,
         pass/lowest_int.expr,
         pass/malformed_ident_due_to_underscore.expr,
@@ -258,6 +264,11 @@ mod test_parse {
         pass/tag_pattern.expr,
         pass/ten_times_eleven.expr,
         pass/three_arg_closure.expr,
+        pass/tuple_access.expr,
+        pass/tuple_destructure_def.expr,
+        pass/tuple_type.expr,
+        pass/tuple_type_ext.expr,
+        pass/tuple_when_pattern.expr,
         pass/two_arg_closure.expr,
         pass/two_backpassing.expr,
         pass/two_branch_when.expr,
@@ -833,6 +844,135 @@ mod test_parse {
         }
     }
 
+    #[test]
+    fn recover_at_next_toplevel_def() {
+        let arena = &Bump::new();
+        let src = indoc!(
+            r#"
+            a = 1
+
+            b = ]
+
+            c = 3
+            "#
+        );
+
+        let (defs, errors) = module_defs_with_recovery(arena, State::new(src.as_bytes()));
+
+        match errors.as_slice() {
+            [SyntaxError::Expr(_, start)] => assert_eq!(*start, Position::new(7)),
+            other => panic!("unexpected errors: {:?}", other),
+        }
+
+        assert_eq!(defs.len(), 3);
+
+        match &defs.value_defs[1] {
+            ast::ValueDef::Body(pattern, expr) => {
+                assert_eq!(pattern.value, ast::Pattern::Identifier("b"));
+                assert_eq!(expr.value, Malformed("b = ]"));
+            }
+            other => panic!("expected a malformed body, but got {:?}", other),
+        }
+
+        match &defs.value_defs[2] {
+            ast::ValueDef::Body(pattern, _) => {
+                assert_eq!(pattern.value, ast::Pattern::Identifier("c"))
+            }
+            other => panic!("expected c to parse, but got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn recover_from_several_syntax_errors() {
+        let arena = &Bump::new();
+        let src = indoc!(
+            r#"
+            a = ]
+
+            b : Str
+            b = "b"
+
+            c : Str
+            c = (
+                "c"
+            ]
+
+            = oops
+            "#
+        );
+
+        let (defs, errors) = module_defs_with_recovery(arena, State::new(src.as_bytes()));
+
+        assert_eq!(errors.len(), 3, "{:?}", errors);
+        assert_eq!(defs.len(), 4);
+
+        let value_defs: std::vec::Vec<_> = defs.defs().filter_map(|def| def.err()).collect();
+
+        // the placeholder for `c` keeps its annotation
+        match value_defs[2] {
+            ast::ValueDef::AnnotatedBody {
+                body_pattern,
+                body_expr,
+                ..
+            } => {
+                assert_eq!(body_pattern.value, ast::Pattern::Identifier("c"));
+                assert_eq!(body_expr.value, Malformed("c = (\n    \"c\"\n]"));
+            }
+            other => panic!("expected a malformed annotated body, but got {:?}", other),
+        }
+
+        // a def that does not even have a name is still kept, so it can be formatted
+        match value_defs[3] {
+            ast::ValueDef::Body(pattern, expr) => {
+                assert_eq!(pattern.value, ast::Pattern::Malformed("= oops"));
+                assert_eq!(expr.value, Malformed("= oops"));
+            }
+            other => panic!("expected a malformed body, but got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn recover_after_closing_bracket_of_broken_def() {
+        let arena = &Bump::new();
+        let src = indoc!(
+            r#"
+            list = [
+                1,
+                2 +,
+                3,
+            ]
+
+            d = 4
+            "#
+        );
+
+        let (defs, errors) = module_defs_with_recovery(arena, State::new(src.as_bytes()));
+
+        // the `]` is part of the broken list, not the start of another broken def
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert_eq!(defs.len(), 2);
+
+        let value_defs: std::vec::Vec<_> = defs.defs().filter_map(|def| def.err()).collect();
+
+        match value_defs[0] {
+            ast::ValueDef::Body(pattern, expr) => {
+                assert_eq!(pattern.value, ast::Pattern::Identifier("list"));
+                assert_eq!(
+                    expr.value,
+                    Malformed("list = [\n    1,\n    2 +,\n    3,\n]")
+                );
+            }
+            other => panic!("expected a malformed body, but got {:?}", other),
+        }
+
+        match value_defs[1] {
+            ast::ValueDef::Body(pattern, _) => {
+                assert_eq!(pattern.value, ast::Pattern::Identifier("d"));
+            }
+            other => panic!("expected the body of d, but got {:?}", other),
+        }
+    }
+
     #[test]
     fn parse_expr_size() {
         assert_eq!(std::mem::size_of::<roc_parse::ast::Expr>(), 40);
@@ -177,12 +177,115 @@ pub enum Problem {
         original_opaque: Symbol,
         ability_member: Symbol,
     },
+    /// Reported by the build, not by canonicalization: this `dbg` will not show anything when the
+    /// program runs.
+    DbgIgnored {
+        region: Region,
+        reason: DbgIgnoredReason,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DbgIgnoredReason {
+    /// `--optimize` builds leave every `dbg` out
+    Optimize,
+    /// The target of this build cannot send values to `roc` yet
+    Unsupported,
+}
+
+impl Problem {
+    /// The region of source code this problem should be reported at, if it has one.
+    pub fn region(&self) -> Option<Region> {
+        match self {
+            Problem::UnusedDef(_, region)
+            | Problem::UnusedImport(_, region)
+            | Problem::UnusedArgument(_, _, _, region)
+            | Problem::UnusedBranchDef(_, region)
+            | Problem::PrecedenceProblem(PrecedenceProblem::BothNonAssociative(region, _, _))
+            | Problem::UnsupportedPattern(_, region)
+            | Problem::CyclicAlias(_, region, _, _)
+            | Problem::PhantomTypeArgument {
+                variable_region: region,
+                ..
+            }
+            | Problem::UnboundTypeVariable {
+                one_occurrence: region,
+                ..
+            }
+            | Problem::DuplicateRecordFieldValue {
+                field_region: region,
+                ..
+            }
+            | Problem::DuplicateRecordFieldType {
+                field_region: region,
+                ..
+            }
+            | Problem::InvalidOptionalValue {
+                field_region: region,
+                ..
+            }
+            | Problem::DuplicateTag {
+                tag_region: region, ..
+            }
+            | Problem::SignatureDefMismatch {
+                def_pattern: region,
+                ..
+            }
+            | Problem::InvalidAliasRigid { region, .. }
+            | Problem::InvalidInterpolation(region)
+            | Problem::InvalidHexadecimal(region)
+            | Problem::InvalidUnicodeCodePt(region)
+            | Problem::NestedDatatype {
+                differing_recursion_region: region,
+                ..
+            }
+            | Problem::InvalidExtensionType { region, .. }
+            | Problem::AbilityHasTypeVariables {
+                variables_region: region,
+                ..
+            }
+            | Problem::HasClauseIsNotAbility { region }
+            | Problem::IllegalHasClause { region }
+            | Problem::AbilityMemberMissingHasClause { region, .. }
+            | Problem::AbilityMemberMultipleBoundVars {
+                span_has_clauses: region,
+                ..
+            }
+            | Problem::AbilityNotOnToplevel { region }
+            | Problem::AbilityUsedAsType(_, _, region)
+            | Problem::NestedSpecialization(_, region)
+            | Problem::IllegalDerivedAbility(region)
+            | Problem::ImplementationNotFound { region, .. }
+            | Problem::NotAnAbilityMember { region, .. }
+            | Problem::OptionalAbilityImpl { region, .. }
+            | Problem::QualifiedAbilityImpl { region }
+            | Problem::AbilityImplNotIdent { region }
+            | Problem::DuplicateImpl {
+                duplicate: region, ..
+            }
+            | Problem::NotAnAbility(region)
+            | Problem::ImplementsNonRequired { region, .. }
+            | Problem::DoesNotImplementAbility { region, .. }
+            | Problem::NotBoundInAllPatterns { region, .. }
+            | Problem::NoIdentifiersIntroduced(region)
+            | Problem::OverloadedSpecialization {
+                overload: region, ..
+            }
+            | Problem::DbgIgnored { region, .. } => Some(*region),
+            Problem::UnknownGeneratesWith(loc_ident) => Some(loc_ident.region),
+            Problem::Shadowing { shadow, .. } => Some(shadow.region),
+            Problem::BadRecursion(cycle) => cycle.first().map(|entry| entry.symbol_region),
+            Problem::RuntimeError(runtime_error) => runtime_error.region(),
+            Problem::ExposedButNotDefined(_) => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum ExtensionTypeKind {
     Record,
     TagUnion,
+    Tuple,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -309,6 +412,8 @@ pub enum RuntimeError {
     MalformedIdentifier(Box<str>, roc_parse::ident::BadIdent, Region),
     MalformedTypeName(Box<str>, Region),
     MalformedClosure(Region),
+    /// A def that failed to parse
+    MalformedExpr(Region),
     InvalidRecordUpdate {
         region: Region,
     },
@@ -342,6 +447,49 @@ pub enum RuntimeError {
 }
 
 impl RuntimeError {
+    /// The region of source code this error should be reported at, if it has one.
+    pub fn region(&self) -> Option<Region> {
+        use RuntimeError::*;
+
+        match self {
+            Shadowing { shadow, .. } => Some(shadow.region),
+            InvalidOptionalValue { field_region, .. } => Some(*field_region),
+            UnsupportedPattern(region)
+            | MalformedPattern(_, region)
+            | OpaqueOutsideScope {
+                referenced_region: region,
+                ..
+            }
+            | OpaqueAppliedToMultipleArgs(region)
+            | ValueNotExposed { region, .. }
+            | ModuleNotImported { region, .. }
+            | InvalidPrecedence(_, region)
+            | MalformedIdentifier(_, _, region)
+            | MalformedTypeName(_, region)
+            | MalformedClosure(region)
+            | MalformedExpr(region)
+            | InvalidRecordUpdate { region }
+            | InvalidFloat(_, region, _)
+            | InvalidInt(_, _, region, _)
+            | InvalidInterpolation(region)
+            | InvalidHexadecimal(region)
+            | InvalidUnicodeCodePt(region)
+            | EmptySingleQuote(region)
+            | MultipleCharsInSingleQuote(region)
+            | DegenerateBranch(region) => Some(*region),
+            LookupNotInScope(loc_ident, _) | OpaqueNotApplied(loc_ident) => Some(loc_ident.region),
+            OpaqueNotDefined { usage, .. } => Some(usage.region),
+            CircularDef(cycle) => cycle.first().map(|entry| entry.symbol_region),
+            UnresolvedTypeVar
+            | ErroneousType
+            | NonExhaustivePattern
+            | NoImplementationNamed { .. }
+            | NoImplementation
+            | VoidValue
+            | ExposedButNotDefined(_) => None,
+        }
+    }
+
     pub fn runtime_message(self) -> String {
         use RuntimeError::*;
 
@@ -367,4 +515,5 @@ pub enum MalformedPatternProblem {
     BadIdent(roc_parse::ident::BadIdent),
     EmptySingleQuote,
     MultipleCharsInSingleQuote,
+    DuplicateListRestPattern,
 }
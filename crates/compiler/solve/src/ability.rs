@@ -272,6 +272,14 @@ impl ObligationCache {
                 var,
             )),
 
+            Symbol::HASH_HASH_ABILITY => {
+                Some(DeriveHash::is_derivable(self, abilities_store, subs, var))
+            }
+
+            Symbol::BOOL_EQ_ABILITY => {
+                Some(DeriveEq::is_derivable(self, abilities_store, subs, var))
+            }
+
             _ => None,
         };
 
@@ -336,7 +344,8 @@ impl ObligationCache {
         let ImplKey { opaque, ability } = impl_key;
         let has_declared_impl = abilities_store.has_declared_implementation(opaque, ability);
 
-        let obligation_result = if !has_declared_impl {
+        // Opaque types that do not implement `Eq` themselves are compared structurally.
+        let obligation_result = if !has_declared_impl && ability != Symbol::BOOL_EQ_ABILITY {
             Err(Unfulfilled::OpaqueDoesNotImplement {
                 typ: opaque,
                 ability,
@@ -510,6 +519,14 @@ trait DerivableVisitor {
         })
     }
 
+    #[inline(always)]
+    fn visit_tuple(var: Variable) -> Result<Descend, NotDerivable> {
+        Err(NotDerivable {
+            var,
+            context: NotDerivableContext::NoContext,
+        })
+    }
+
     #[inline(always)]
     fn visit_tag_union(var: Variable) -> Result<Descend, NotDerivable> {
         Err(NotDerivable {
@@ -542,6 +559,14 @@ trait DerivableVisitor {
         })
     }
 
+    #[inline(always)]
+    fn visit_empty_tuple(var: Variable) -> Result<(), NotDerivable> {
+        Err(NotDerivable {
+            var,
+            context: NotDerivableContext::NoContext,
+        })
+    }
+
     #[inline(always)]
     fn visit_empty_tag_union(var: Variable) -> Result<(), NotDerivable> {
         Err(NotDerivable {
@@ -566,6 +591,13 @@ trait DerivableVisitor {
         })
     }
 
+    /// Visits a builtin number type, like `Num a` or `Int a`. By default, the type behind the
+    /// number is visited too, until a concrete number type is found.
+    #[inline(always)]
+    fn visit_number(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
     #[inline(always)]
     fn is_derivable(
         obligation_cache: &mut ObligationCache,
@@ -643,6 +675,13 @@ trait DerivableVisitor {
                             }
                         }
                     }
+                    Tuple(elems, ext) => {
+                        let descend = Self::visit_tuple(var)?;
+                        if descend.0 {
+                            push_var_slice!(elems.variables());
+                            stack.push(ext);
+                        }
+                    }
                     TagUnion(tags, ext) => {
                         let descend = Self::visit_tag_union(var)?;
                         if descend.0 {
@@ -669,6 +708,7 @@ trait DerivableVisitor {
                         }
                     }
                     EmptyRecord => Self::visit_empty_record(var)?,
+                    EmptyTuple => Self::visit_empty_tuple(var)?,
                     EmptyTagUnion => Self::visit_empty_tag_union(var)?,
 
                     Erroneous(_) => {
@@ -684,8 +724,11 @@ trait DerivableVisitor {
                     real_var,
                     AliasKind::Opaque,
                 ) => {
-                    // Numbers: always decay until a ground is hit.
-                    stack.push(real_var);
+                    // Numbers: decay until a ground is hit, unless the visitor knows enough.
+                    let descend = Self::visit_number(var)?;
+                    if descend.0 {
+                        stack.push(real_var);
+                    }
                 }
                 Alias(opaque, _alias_variables, _real_var, AliasKind::Opaque) => {
                     if obligation_cache
@@ -893,6 +936,201 @@ impl DerivableVisitor for DeriveDecoding {
     }
 }
 
+struct DeriveHash;
+impl DerivableVisitor for DeriveHash {
+    const ABILITY: Symbol = Symbol::HASH_HASH_ABILITY;
+
+    #[inline(always)]
+    fn is_derivable_builtin_opaque(symbol: Symbol) -> bool {
+        is_builtin_number_alias(symbol)
+    }
+
+    #[inline(always)]
+    fn visit_recursion(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_apply(var: Variable, symbol: Symbol) -> Result<Descend, NotDerivable> {
+        if matches!(symbol, Symbol::LIST_LIST | Symbol::STR_STR) {
+            Ok(Descend(true))
+        } else {
+            Err(NotDerivable {
+                var,
+                context: NotDerivableContext::NoContext,
+            })
+        }
+    }
+
+    #[inline(always)]
+    fn visit_record(
+        _subs: &Subs,
+        _var: Variable,
+        _fields: RecordFields,
+    ) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_recursive_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_function_or_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_empty_record(_var: Variable) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn visit_empty_tag_union(_var: Variable) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn visit_alias(_var: Variable, symbol: Symbol) -> Result<Descend, NotDerivable> {
+        if is_builtin_number_alias(symbol) {
+            Ok(Descend(false))
+        } else {
+            Ok(Descend(true))
+        }
+    }
+
+    #[inline(always)]
+    fn visit_ranged_number(_var: Variable, _range: NumericRange) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+}
+
+struct DeriveEq;
+impl DerivableVisitor for DeriveEq {
+    const ABILITY: Symbol = Symbol::BOOL_EQ_ABILITY;
+
+    #[inline(always)]
+    fn is_derivable_builtin_opaque(symbol: Symbol) -> bool {
+        is_builtin_number_alias(symbol)
+    }
+
+    #[inline(always)]
+    fn visit_flex_able(var: Variable, ability: Symbol) -> Result<(), NotDerivable> {
+        if ability.implies_ability(Self::ABILITY) {
+            Ok(())
+        } else {
+            Err(NotDerivable {
+                var,
+                context: NotDerivableContext::NoContext,
+            })
+        }
+    }
+
+    #[inline(always)]
+    fn visit_rigid_able(var: Variable, ability: Symbol) -> Result<(), NotDerivable> {
+        if ability.implies_ability(Self::ABILITY) {
+            Ok(())
+        } else {
+            Err(NotDerivable {
+                var,
+                context: NotDerivableContext::NoContext,
+            })
+        }
+    }
+
+    #[inline(always)]
+    fn visit_recursion(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_apply(_var: Variable, _symbol: Symbol) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_record(
+        _subs: &Subs,
+        _var: Variable,
+        _fields: RecordFields,
+    ) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_tuple(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_recursive_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_function_or_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_empty_record(_var: Variable) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn visit_empty_tuple(_var: Variable) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn visit_empty_tag_union(_var: Variable) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn visit_alias(_var: Variable, symbol: Symbol) -> Result<Descend, NotDerivable> {
+        if is_builtin_number_alias(symbol) {
+            Ok(Descend(false))
+        } else {
+            Ok(Descend(true))
+        }
+    }
+
+    #[inline(always)]
+    fn visit_ranged_number(_var: Variable, _range: NumericRange) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn visit_number(_var: Variable) -> Result<Descend, NotDerivable> {
+        // Every number can be compared, whatever its precision.
+        Ok(Descend(false))
+    }
+}
+
+/// The function `==` uses for an opaque type that does not implement `Eq` itself.
+///
+/// Dictionaries and sets are compared by their entries, regardless of their order; every other
+/// opaque type is compared structurally.
+pub(crate) fn opaque_is_eq(opaque: Symbol) -> Symbol {
+    match opaque {
+        Symbol::DICT_DICT => Symbol::DICT_IS_EQ,
+        Symbol::SET_SET => Symbol::SET_IS_EQ,
+        _ => Symbol::BOOL_STRUCTURAL_EQ,
+    }
+}
+
 /// Determines what type implements an ability member of a specialized signature, given the
 /// [MustImplementAbility] constraints of the signature.
 pub fn type_implementing_specialization(
@@ -1003,7 +1241,15 @@ pub fn resolve_ability_specialization<R: AbilityResolver>(
                 ability_member,
             };
 
-            match resolver.get_implementation(impl_key)? {
+            let member_impl = match resolver.get_implementation(impl_key) {
+                Some(member_impl) => member_impl,
+                None if ability_member == Symbol::BOOL_EQ => {
+                    return Some(Resolved::Specialization(opaque_is_eq(symbol)));
+                }
+                None => return None,
+            };
+
+            match member_impl {
                 roc_types::types::MemberImpl::Impl(spec_symbol) => {
                     Resolved::Specialization(spec_symbol)
                 }
@@ -26,13 +26,13 @@ use roc_region::all::Loc;
 use roc_solve_problem::TypeError;
 use roc_types::subs::{
     self, AliasVariables, Content, Descriptor, FlatType, GetSubsSlice, LambdaSet, Mark,
-    OptVariable, Rank, RecordFields, Subs, SubsIndex, SubsSlice, UlsOfVar, UnionLabels,
+    OptVariable, Rank, RecordFields, Subs, SubsIndex, SubsSlice, TupleElems, UlsOfVar, UnionLabels,
     UnionLambdas, UnionTags, Variable, VariableSubsSlice,
 };
 use roc_types::types::Type::{self, *};
 use roc_types::types::{
-    gather_fields_unsorted_iter, AliasCommon, AliasKind, Category, OptAbleType, OptAbleVar, Reason,
-    RecordField, TypeExtension, Uls,
+    gather_fields_unsorted_iter, gather_tuple_elems_unsorted_iter, AliasCommon, AliasKind,
+    Category, OptAbleType, OptAbleVar, Reason, RecordField, TypeExtension, Uls,
 };
 use roc_unify::unify::{
     unify, unify_introduced_ability_specialization, Env as UEnv, Mode, Obligated,
@@ -2270,6 +2270,32 @@ fn type_to_variable<'a>(
                 register_with_known_var(subs, destination, rank, pools, content)
             }
 
+            Tuple(elems, ext) => {
+                let mut elem_vars = Vec::with_capacity_in(elems.len(), arena);
+
+                for (index, elem_type) in elems.iter() {
+                    elem_vars.push((*index, helper!(elem_type)));
+                }
+
+                let temp_ext_var = match ext {
+                    TypeExtension::Open(ext) => helper!(ext),
+                    TypeExtension::Closed => Variable::EMPTY_TUPLE,
+                };
+
+                let (it, new_ext_var) =
+                    gather_tuple_elems_unsorted_iter(subs, TupleElems::empty(), temp_ext_var)
+                        .expect("Something ended up weird in this tuple type");
+
+                elem_vars.extend(it);
+                insertion_sort_by(&mut elem_vars, |(a, _), (b, _)| a.cmp(b));
+
+                let tuple_elems = TupleElems::insert_into_subs(subs, elem_vars);
+
+                let content = Content::Structure(FlatType::Tuple(tuple_elems, new_ext_var));
+
+                register_with_known_var(subs, destination, rank, pools, content)
+            }
+
             TagUnion(tags, ext) => {
                 // An empty tags is inefficient (but would be correct)
                 // If hit, try to turn the value into an EmptyTagUnion in canonicalization
@@ -3118,6 +3144,9 @@ fn adjust_rank_content(
                     group_rank
                 }
 
+                // Same as for the empty record above.
+                EmptyTuple => group_rank,
+
                 // THEORY: an empty tag never needs to get generalized
                 EmptyTagUnion => Rank::toplevel(),
 
@@ -3139,6 +3168,17 @@ fn adjust_rank_content(
                     rank
                 }
 
+                Tuple(elems, ext_var) => {
+                    let mut rank = adjust_rank(subs, young_mark, visit_mark, group_rank, *ext_var);
+
+                    for var_index in elems.iter_variables() {
+                        let var = subs[var_index];
+                        rank = rank.max(adjust_rank(subs, young_mark, visit_mark, group_rank, var));
+                    }
+
+                    rank
+                }
+
                 TagUnion(tags, ext_var) => {
                     let mut rank = adjust_rank(subs, young_mark, visit_mark, group_rank, *ext_var);
                     // For performance reasons, we only keep one representation of empty tag unions
@@ -3487,7 +3527,10 @@ fn deep_copy_var_help(
                         Func(new_arguments, new_closure_var, new_ret_var)
                     }
 
-                    same @ EmptyRecord | same @ EmptyTagUnion | same @ Erroneous(_) => same,
+                    same @ EmptyRecord
+                    | same @ EmptyTuple
+                    | same @ EmptyTagUnion
+                    | same @ Erroneous(_) => same,
 
                     Record(fields, ext_var) => {
                         let record_fields = {
@@ -3529,6 +3572,20 @@ fn deep_copy_var_help(
                         Record(record_fields, work!(ext_var))
                     }
 
+                    Tuple(elems, ext_var) => {
+                        let tuple_elems = {
+                            let new_variables = copy_sequence!(elems.len(), elems.iter_variables());
+
+                            TupleElems {
+                                length: elems.length,
+                                elem_index_start: elems.elem_index_start,
+                                variables_start: new_variables.start,
+                            }
+                        };
+
+                        Tuple(tuple_elems, work!(ext_var))
+                    }
+
                     TagUnion(tags, ext_var) => {
                         let union_tags = copy_union!(tags);
 
@@ -17,13 +17,14 @@ use roc_error_macros::{internal_error, todo_abilities};
 use roc_module::symbol::{ModuleId, Symbol};
 use roc_types::{
     subs::{
-        get_member_lambda_sets_at_region, Content, Descriptor, GetSubsSlice, LambdaSet, Mark,
-        OptVariable, Rank, Subs, SubsSlice, UlsOfVar, Variable,
+        get_member_lambda_sets_at_region, instantiate_rigids, Content, Descriptor, FlatType,
+        GetSubsSlice, LambdaSet, Mark, OptVariable, Rank, Subs, SubsSlice, UlsOfVar, Variable,
     },
     types::{AliasKind, MemberImpl, Uls},
 };
 use roc_unify::unify::{unify, Env as UEnv, Mode, MustImplementConstraints};
 
+use crate::ability::opaque_is_eq;
 use crate::solve::{deep_copy_var_in, introduce, Pools};
 
 /// What phase in the compiler is reaching out to specialize lambda sets?
@@ -601,6 +602,7 @@ enum SpecializationTypeKey {
     Opaque(Symbol),
     Derived(DeriveKey),
     Immediate(Symbol),
+    SingleLambdaSetImmediate(Symbol),
 }
 
 enum SpecializeDecision {
@@ -623,7 +625,14 @@ fn make_specialization_decision<P: Phase>(
     use SpecializationTypeKey::*;
     match subs.get_content_without_compacting(var) {
         Alias(opaque, _, _, AliasKind::Opaque) if opaque.module_id() != ModuleId::NUM => {
-            if P::IS_LATE {
+            if ability_member == Symbol::BOOL_EQ
+                && !phase.with_module_abilities_store(opaque.module_id(), |abilities_store| {
+                    abilities_store.has_declared_implementation(*opaque, Symbol::BOOL_EQ_ABILITY)
+                })
+            {
+                // Opaque types that do not implement `Eq` themselves use a builtin equality.
+                SpecializeDecision::Specialize(SingleLambdaSetImmediate(opaque_is_eq(*opaque)))
+            } else if P::IS_LATE {
                 SpecializeDecision::Specialize(Opaque(*opaque))
             } else {
                 // Solving within a module.
@@ -666,6 +675,9 @@ fn make_specialization_decision<P: Phase>(
                     roc_derive_key::Derived::Immediate(imm) => {
                         SpecializeDecision::Specialize(Immediate(imm))
                     }
+                    roc_derive_key::Derived::SingleLambdaSetImmediate(imm) => {
+                        SpecializeDecision::Specialize(SingleLambdaSetImmediate(imm))
+                    }
                     roc_derive_key::Derived::Key(derive_key) => {
                         SpecializeDecision::Specialize(Derived(derive_key))
                     }
@@ -785,5 +797,39 @@ fn get_specialization_lambda_set_ambient_function<P: Phase>(
 
             Ok(immediate_lambda_set_at_region)
         }
+
+        SpecializationTypeKey::SingleLambdaSetImmediate(imm) => {
+            // A single-lambda-set immediate is a plain function of a builtin module, whose only
+            // lambda set is the one we are specializing. So, we can import the function's type and
+            // take the ambient function of that lambda set.
+            debug_assert_eq!(lset_region, 1);
+
+            let module_id = imm.module_id();
+            debug_assert!(module_id.is_builtin());
+
+            let module_types = match derived_env.exposed_types.get(&module_id) {
+                Some(exposed_types) => &exposed_types.exposed_types_storage_subs,
+                None => {
+                    // We are solving the module that defines the function, e.g. `Set` comparing
+                    // sets in its own `expect`s. Its type is not exposed yet, and the expects of
+                    // builtin modules are never compiled, so we don't need the lambda.
+                    return Err(());
+                }
+            };
+            let storage_var = module_types.stored_vars_by_symbol.get(&imm).unwrap();
+            let imported = module_types
+                .storage_subs
+                .export_variable_to(subs, *storage_var);
+
+            let lambda_set = match subs.get_content_without_compacting(imported.variable) {
+                Content::Structure(FlatType::Func(_, lambda_set, _)) => *lambda_set,
+                _ => internal_error!("{:?} is not a function", imm),
+            };
+            let ambient_function = subs.get_lambda_set(lambda_set).ambient_function;
+
+            instantiate_rigids(subs, ambient_function);
+
+            Ok(ambient_function)
+        }
     }
 }
@@ -1515,6 +1515,96 @@ mod solve_expr {
         );
     }
 
+    #[test]
+    fn two_elem_tuple() {
+        infer_eq("(5, \"foo\")", "(Num *, Str)");
+    }
+
+    #[test]
+    fn tuple_access() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                tup = (1, "foo", 3.14)
+
+                tup.1
+                "#
+            ),
+            "Str",
+        );
+    }
+
+    #[test]
+    fn tuple_access_function() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                \tup -> tup.0
+                "#
+            ),
+            "(a)* -> a",
+        );
+    }
+
+    #[test]
+    fn tuple_destructure_def() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                (x, y) = (1, "foo")
+
+                y
+                "#
+            ),
+            "Str",
+        );
+    }
+
+    #[test]
+    fn tuple_when_pattern() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                \tup ->
+                    when tup is
+                        (Ok a, _) -> a
+                        (Err _, b) -> b
+                "#
+            ),
+            "([Err *, Ok a], a)* -> a",
+        );
+    }
+
+    #[test]
+    fn list_pattern_first_and_rest() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                \l ->
+                    when l is
+                        [] -> []
+                        [first, .. as rest] -> List.prepend rest first
+                "#
+            ),
+            "List a -> List a",
+        );
+    }
+
+    #[test]
+    fn list_pattern_nested_elements() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                \l ->
+                    when l is
+                        [.., [Ok x]] -> x
+                        _ -> ""
+                "#
+            ),
+            "List (List [Ok Str]) -> Str",
+        );
+    }
+
     #[test]
     fn empty_record_pattern() {
         infer_eq(
@@ -3469,7 +3559,7 @@ mod solve_expr {
                 Dict.insert
                 "#
             ),
-            "Dict k v, k, v -> Dict k v",
+            "Dict k v, k, v -> Dict k v | k has Hash",
         );
     }
 
@@ -3730,7 +3820,7 @@ mod solve_expr {
         infer_eq_without_problem(
             indoc!(
                 r#"
-                reconstructPath : Dict position position, position -> List position
+                reconstructPath : Dict position position, position -> List position | position has Hash.Hash
                 reconstructPath = \cameFrom, goal ->
                     when Dict.get cameFrom goal is
                         Err KeyNotFound ->
@@ -3742,7 +3832,7 @@ mod solve_expr {
                 reconstructPath
                 "#
             ),
-            "Dict position position, position -> List position",
+            "Dict position position, position -> List position | position has Hash",
         );
     }
 
@@ -3777,7 +3867,7 @@ mod solve_expr {
 
                 Model position : { openSet : Set position }
 
-                cheapestOpen : Model position -> Result position [KeyNotFound]*
+                cheapestOpen : Model position -> Result position [KeyNotFound]* | position has Eq
                 cheapestOpen = \model ->
 
                     folder = \resSmallestSoFar, position ->
@@ -3792,14 +3882,14 @@ mod solve_expr {
                     Set.walk model.openSet (Ok { position: boom {}, cost: 0.0 }) folder
                         |> Result.map (\x -> x.position)
 
-                astar : Model position -> Result position [KeyNotFound]*
+                astar : Model position -> Result position [KeyNotFound]* | position has Eq
                 astar = \model -> cheapestOpen model
 
                 main =
                     astar
                 "#
             ),
-            "Model position -> Result position [KeyNotFound]*",
+            "Model position -> Result position [KeyNotFound]* | position has Eq",
         );
     }
 
@@ -6551,6 +6641,63 @@ mod solve_expr {
         )
     }
 
+    #[test]
+    fn hash_record() {
+        infer_queries!(
+            indoc!(
+                r#"
+                app "test"
+                    imports [Hash.{ hash }]
+                    provides [main] to "./platform"
+
+                main = \h -> hash h { a: "" }
+                           # ^^^^
+                "#
+            ),
+            @"Hash#hash(1) : hasher, { a : Str } -[[#Derived.hash_{a}(0)]]-> hasher | hasher has Hasher"
+        )
+    }
+
+    #[test]
+    fn hash_str() {
+        infer_queries!(
+            indoc!(
+                r#"
+                app "test"
+                    imports [Hash.{ hash }]
+                    provides [main] to "./platform"
+
+                main = \h -> hash h "abc"
+                           # ^^^^
+                "#
+            ),
+            @"Hash#hash(1) : a, Str -[[Hash.hashStrBytes(16)]]-> a | a has Hasher"
+        )
+    }
+
+    #[test]
+    fn rigid_able_var_unified_with_flex_keeps_ability() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                app "test" provides [f] to "./platform"
+
+                Ab has ab : a -> Nat | a has Ab
+
+                f : List k, k -> Nat | k has Ab
+                f = \data, key ->
+                    when List.get data 0 is
+                        Ok _ -> 0
+                        _ -> g key
+
+                g : k -> Nat | k has Ab
+                g = \k -> ab k
+                "#
+            ),
+            "List k, k -> Nat | k has Ab",
+        )
+    }
+
     #[test]
     fn resolve_lambda_set_generalized_ability_alias() {
         infer_queries!(
@@ -7775,4 +7922,33 @@ mod solve_expr {
             "{}",
         );
     }
+
+    #[test]
+    fn dbg_statement() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                \x ->
+                    dbg x
+
+                    x + 1
+                "#
+            ),
+            "Num a -> Num a",
+        );
+    }
+
+    #[test]
+    fn dbg_evaluates_to_its_argument() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                x = dbg "foo"
+
+                x
+                "#
+            ),
+            "Str",
+        );
+    }
 }
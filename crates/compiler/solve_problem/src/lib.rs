@@ -31,6 +31,33 @@ pub enum TypeError {
     },
 }
 
+impl TypeError {
+    /// The region of source code this error should be reported at, if it has one.
+    pub fn region(&self) -> Option<Region> {
+        use TypeError::*;
+
+        match self {
+            BadExpr(region, ..)
+            | BadPattern(region, ..)
+            | CircularType(region, ..)
+            | BadExprMissingAbility(region, ..)
+            | BadPatternMissingAbility(region, ..)
+            | StructuralSpecialization { region, .. }
+            | WrongSpecialization { region, .. } => Some(*region),
+            CircularDef(cycle) => cycle.first().map(|entry| entry.symbol_region),
+            BadType(problem) => problem.region(),
+            UnfulfilledAbility(Unfulfilled::OpaqueUnderivable { derive_region, .. }) => {
+                Some(*derive_region)
+            }
+            Exhaustive(roc_exhaustive::Error::Incomplete(region, ..)) => Some(*region),
+            Exhaustive(roc_exhaustive::Error::Redundant { branch_region, .. }) => {
+                Some(*branch_region)
+            }
+            UnexposedLookup(..) | UnfulfilledAbility(..) => None,
+        }
+    }
+}
+
 #[derive(PartialEq, Debug, Clone)]
 pub enum Unfulfilled {
     /// No claimed implementation of an ability for an opaque type.
@@ -28,6 +28,20 @@ test_key_eq! {
     explicit_empty_record_and_implicit_empty_record:
         v!(EMPTY_RECORD), v!({})
 
+    same_tag_union:
+        v!([ A v!(U8) v!(STR), B v!(STR) ]), v!([ A v!(U8) v!(STR), B v!(STR) ])
+    same_tag_union_tags_diff_types:
+        v!([ A v!(U8) v!(U8), B v!(U8) ]), v!([ A v!(STR) v!(STR), B v!(STR) ])
+    same_tag_union_tags_any_order:
+        v!([ A v!(U8) v!(U8), B v!(U8), C ]), v!([ C, B v!(STR), A v!(STR) v!(STR) ])
+    explicit_empty_tag_union_and_implicit_empty_tag_union:
+        v!(EMPTY_TAG_UNION), v!([])
+
+    same_recursive_tag_union:
+        v!([ Nil, Cons v!(^lst)] as lst), v!([ Nil, Cons v!(^lst)] as lst)
+    same_tag_union_and_recursive_tag_union_fields:
+        v!([ Nil, Cons v!(STR)]), v!([ Nil, Cons v!(^lst)] as lst)
+
     list_list_diff_types:
         v!(Symbol::LIST_LIST v!(STR)), v!(Symbol::LIST_LIST v!(U8))
     str_str:
@@ -41,6 +55,15 @@ test_key_neq! {
         v!({ a: v!(U8), }), v!({ b: v!(U8), })
     record_empty_vs_nonempty:
         v!(EMPTY_RECORD), v!({ a: v!(U8), })
+
+    different_tag_union_tags:
+        v!([ A v!(U8) ]), v!([ B v!(U8) ])
+    tag_union_empty_vs_nonempty:
+        v!(EMPTY_TAG_UNION), v!([ B v!(U8) ])
+    different_tag_union_payload_sizes:
+        v!([ A v!(U8) ]), v!([ A v!(U8) v!(U8) ])
+    different_recursive_tag_union_tags:
+        v!([ Nil, Cons v!(^lst) ] as lst), v!([ Nil, Next v!(^lst) ] as lst)
 }
 
 #[test]
@@ -93,13 +116,43 @@ fn derivable_record_with_record_ext() {
     );
 }
 
+#[test]
+fn derivable_tag_ext_flex_var() {
+    check_derivable(
+        Decoder,
+        v!([ A v!(STR) ]* ),
+        DeriveKey::Decoder(FlatDecodableKey::TagUnion(vec![("A".into(), 1)])),
+    );
+}
+
+#[test]
+fn derivable_tag_ext_flex_able_var() {
+    check_derivable(
+        Decoder,
+        v!([ A v!(STR) ]a has Symbol::DECODE_DECODER),
+        DeriveKey::Decoder(FlatDecodableKey::TagUnion(vec![("A".into(), 1)])),
+    );
+}
+
+#[test]
+fn derivable_tag_with_tag_ext() {
+    check_derivable(
+        Decoder,
+        v!([ B v!(STR) v!(U8) ][ A v!(STR) ]),
+        DeriveKey::Decoder(FlatDecodableKey::TagUnion(vec![
+            ("A".into(), 1),
+            ("B".into(), 2),
+        ])),
+    );
+}
+
 #[test]
 fn list() {
     derive_test(Decoder, v!(Symbol::LIST_LIST v!(STR)), |golden| {
         assert_snapshot!(golden, @r###"
         # derived for List Str
         # Decoder (List val) fmt | fmt has DecoderFormatting, val has Decoding
-        # List U8, fmt -[[custom(3)]]-> { rest : List U8, result : [Err [TooShort], Ok (List val)] } | fmt has DecoderFormatting, val has Decoding
+        # List U8, fmt -[[custom(3)]]-> { rest : List U8, result : [Err [Expected { expected : Str, found : Str, path : List [Field Str, Index Nat, Tag Str] }, Missing { path : List [Field Str, Index Nat, Tag Str] }], Ok (List val)] } | fmt has DecoderFormatting, val has Decoding
         # Specialization lambda sets:
         #   @<1>: [[custom(3)]]
         #Derived.decoder_list =
@@ -117,7 +170,7 @@ fn record_2_fields() {
         assert_snapshot!(golden, @r###"
         # derived for { first : Str, second : Str }
         # Decoder { first : val, second : val1 } fmt | fmt has DecoderFormatting, val has Decoding, val1 has Decoding
-        # List U8, fmt -[[custom(22)]]-> { rest : List U8, result : [Err [TooShort], Ok { first : val, second : val1 }] } | fmt has DecoderFormatting, val has Decoding, val1 has Decoding
+        # List U8, fmt -[[custom(22)]]-> { rest : List U8, result : [Err [Expected { expected : Str, found : Str, path : List [Field Str, Index Nat, Tag Str] }, Missing { path : List [Field Str, Index Nat, Tag Str] }], Ok { first : val, second : val1 }] } | fmt has DecoderFormatting, val has Decoding, val1 has Decoding
         # Specialization lambda sets:
         #   @<1>: [[custom(22)]]
         #Derived.decoder_{first,second} =
@@ -141,7 +194,10 @@ fn record_2_fields() {
                                   result: when #Derived.rec.result is
                                       Ok #Derived.val ->
                                         Ok { stateRecord2 & first: Ok #Derived.val }
-                                      Err #Derived.err -> Err #Derived.err,
+                                      Err #Derived.err ->
+                                        Err (Decode.prependPath
+                                          #Derived.err
+                                          (Field "first")),
                                   rest: #Derived.rec.rest
                                 })
                       "second" ->
@@ -156,7 +212,10 @@ fn record_2_fields() {
                                   result: when #Derived.rec2.result is
                                       Ok #Derived.val2 ->
                                         Ok { stateRecord2 & second: Ok #Derived.val2 }
-                                      Err #Derived.err2 -> Err #Derived.err2,
+                                      Err #Derived.err2 ->
+                                        Err (Decode.prependPath
+                                          #Derived.err2
+                                          (Field "second")),
                                   rest: #Derived.rec2.rest
                                 })
                       _ -> Skip
@@ -166,8 +225,236 @@ fn record_2_fields() {
                         when #Derived.stateRecord.second is
                           Ok #Derived.second ->
                             Ok { second: #Derived.second, first: #Derived.first }
-                          _ -> Err TooShort
-                      _ -> Err TooShort)
+                          _ -> Err (Decode.missing (Field "second"))
+                      _ -> Err (Decode.missing (Field "first")))
+                #Derived.fmt3
+        "###
+        )
+    })
+}
+
+#[test]
+fn tag_one_label_zero_args() {
+    derive_test(Decoder, v!([A]), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for [A]
+        # Decoder [A] fmt | fmt has DecoderFormatting
+        # List U8, fmt -[[custom(10)]]-> { rest : List U8, result : [Err [Expected { expected : Str, found : Str, path : List [Field Str, Index Nat, Tag Str] }, Missing { path : List [Field Str, Index Nat, Tag Str] }], Ok [A]] } | fmt has DecoderFormatting
+        # Specialization lambda sets:
+        #   @<1>: [[custom(10)]]
+        #Derived.decoder_[A 0] =
+          Decode.custom
+            \#Derived.bytes, #Derived.fmt ->
+              Decode.decodeWith
+                #Derived.bytes
+                (Decode.tag
+                  { }
+                  \#Derived.stateRecord2, #Derived.tag2, #Derived.index ->
+                    when #Derived.tag2 is _ -> Skip
+                  \#Derived.stateRecord, #Derived.tag ->
+                    when #Derived.tag is
+                      "A" -> Ok A
+                      _ -> Err (Decode.expected "one of the tags A" #Derived.tag))
+                #Derived.fmt
+        "###
+        )
+    })
+}
+
+#[test]
+fn tag_two_labels() {
+    derive_test(Decoder, v!([A v!(U8) v!(STR), B v!(STR)]), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for [A U8 Str, B Str]
+        # Decoder [A val val1, B val1] fmt | fmt has DecoderFormatting, val has Decoding, val1 has Decoding
+        # List U8, fmt -[[custom(31)]]-> { rest : List U8, result : [Err [Expected { expected : Str, found : Str, path : List [Field Str, Index Nat, Tag Str] }, Missing { path : List [Field Str, Index Nat, Tag Str] }], Ok [A val val1, B val1]] } | fmt has DecoderFormatting, val has Decoding, val1 has Decoding
+        # Specialization lambda sets:
+        #   @<1>: [[custom(31)]]
+        #Derived.decoder_[A 2,B 1] =
+          Decode.custom
+            \#Derived.bytes4, #Derived.fmt4 ->
+              Decode.decodeWith
+                #Derived.bytes4
+                (Decode.tag
+                  { t1p0: Err NoField, t0p1: Err NoField, t0p0: Err NoField }
+                  \#Derived.stateRecord2, #Derived.tag2, #Derived.index ->
+                    when #Derived.tag2 is
+                      "A" ->
+                        when #Derived.index is
+                          0 ->
+                            Keep (Decode.custom
+                              \#Derived.bytes, #Derived.fmt ->
+                                when Decode.decodeWith
+                                    #Derived.bytes
+                                    Decode.decoder
+                                    #Derived.fmt is
+                                  #Derived.rec ->
+                                    {
+                                      result: when #Derived.rec.result is
+                                          Ok #Derived.val ->
+                                            Ok { stateRecord2 & t0p0: Ok #Derived.val }
+                                          Err #Derived.err ->
+                                            Err (Decode.prependPath
+                                              (Decode.prependPath
+                                                #Derived.err
+                                                (Index 0))
+                                              (Tag "A")),
+                                      rest: #Derived.rec.rest
+                                    })
+                          1 ->
+                            Keep (Decode.custom
+                              \#Derived.bytes2, #Derived.fmt2 ->
+                                when Decode.decodeWith
+                                    #Derived.bytes2
+                                    Decode.decoder
+                                    #Derived.fmt2 is
+                                  #Derived.rec2 ->
+                                    {
+                                      result: when #Derived.rec2.result is
+                                          Ok #Derived.val2 ->
+                                            Ok { stateRecord2 & t0p1: Ok #Derived.val2 }
+                                          Err #Derived.err2 ->
+                                            Err (Decode.prependPath
+                                              (Decode.prependPath
+                                                #Derived.err2
+                                                (Index 1))
+                                              (Tag "A")),
+                                      rest: #Derived.rec2.rest
+                                    })
+                          _ -> Skip
+                      "B" ->
+                        when #Derived.index is
+                          0 ->
+                            Keep (Decode.custom
+                              \#Derived.bytes3, #Derived.fmt3 ->
+                                when Decode.decodeWith
+                                    #Derived.bytes3
+                                    Decode.decoder
+                                    #Derived.fmt3 is
+                                  #Derived.rec3 ->
+                                    {
+                                      result: when #Derived.rec3.result is
+                                          Ok #Derived.val3 ->
+                                            Ok { stateRecord2 & t1p0: Ok #Derived.val3 }
+                                          Err #Derived.err3 ->
+                                            Err (Decode.prependPath
+                                              (Decode.prependPath
+                                                #Derived.err3
+                                                (Index 0))
+                                              (Tag "B")),
+                                      rest: #Derived.rec3.rest
+                                    })
+                          _ -> Skip
+                      _ -> Skip
+                  \#Derived.stateRecord, #Derived.tag ->
+                    when #Derived.tag is
+                      "A" ->
+                        when #Derived.stateRecord.t0p0 is
+                          Ok #Derived.t0p0 ->
+                            when #Derived.stateRecord.t0p1 is
+                              Ok #Derived.t0p1 -> Ok (A #Derived.t0p0 #Derived.t0p1)
+                              _ ->
+                                Err (Decode.prependPath
+                                  (Decode.missing (Index 1))
+                                  (Tag "A"))
+                          _ ->
+                            Err (Decode.prependPath
+                              (Decode.missing (Index 0))
+                              (Tag "A"))
+                      "B" ->
+                        when #Derived.stateRecord.t1p0 is
+                          Ok #Derived.t1p0 -> Ok (B #Derived.t1p0)
+                          _ ->
+                            Err (Decode.prependPath
+                              (Decode.missing (Index 0))
+                              (Tag "B"))
+                      _ -> Err (Decode.expected "one of the tags A, B" #Derived.tag))
+                #Derived.fmt4
+        "###
+        )
+    })
+}
+
+#[test]
+fn recursive_tag_union() {
+    derive_test(Decoder, v!([Nil, Cons v!(U8) v!(^lst) ] as lst), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for [Cons U8 $rec, Nil] as $rec
+        # Decoder [Cons val val1, Nil] fmt | fmt has DecoderFormatting, val has Decoding, val1 has Decoding
+        # List U8, fmt -[[custom(24)]]-> { rest : List U8, result : [Err [Expected { expected : Str, found : Str, path : List [Field Str, Index Nat, Tag Str] }, Missing { path : List [Field Str, Index Nat, Tag Str] }], Ok [Cons val val1, Nil]] } | fmt has DecoderFormatting, val has Decoding, val1 has Decoding
+        # Specialization lambda sets:
+        #   @<1>: [[custom(24)]]
+        #Derived.decoder_[Cons 2,Nil 0] =
+          Decode.custom
+            \#Derived.bytes3, #Derived.fmt3 ->
+              Decode.decodeWith
+                #Derived.bytes3
+                (Decode.tag
+                  { t0p1: Err NoField, t0p0: Err NoField }
+                  \#Derived.stateRecord2, #Derived.tag2, #Derived.index ->
+                    when #Derived.tag2 is
+                      "Cons" ->
+                        when #Derived.index is
+                          0 ->
+                            Keep (Decode.custom
+                              \#Derived.bytes, #Derived.fmt ->
+                                when Decode.decodeWith
+                                    #Derived.bytes
+                                    Decode.decoder
+                                    #Derived.fmt is
+                                  #Derived.rec ->
+                                    {
+                                      result: when #Derived.rec.result is
+                                          Ok #Derived.val ->
+                                            Ok { stateRecord2 & t0p0: Ok #Derived.val }
+                                          Err #Derived.err ->
+                                            Err (Decode.prependPath
+                                              (Decode.prependPath
+                                                #Derived.err
+                                                (Index 0))
+                                              (Tag "Cons")),
+                                      rest: #Derived.rec.rest
+                                    })
+                          1 ->
+                            Keep (Decode.custom
+                              \#Derived.bytes2, #Derived.fmt2 ->
+                                when Decode.decodeWith
+                                    #Derived.bytes2
+                                    Decode.decoder
+                                    #Derived.fmt2 is
+                                  #Derived.rec2 ->
+                                    {
+                                      result: when #Derived.rec2.result is
+                                          Ok #Derived.val2 ->
+                                            Ok { stateRecord2 & t0p1: Ok #Derived.val2 }
+                                          Err #Derived.err2 ->
+                                            Err (Decode.prependPath
+                                              (Decode.prependPath
+                                                #Derived.err2
+                                                (Index 1))
+                                              (Tag "Cons")),
+                                      rest: #Derived.rec2.rest
+                                    })
+                          _ -> Skip
+                      _ -> Skip
+                  \#Derived.stateRecord, #Derived.tag ->
+                    when #Derived.tag is
+                      "Cons" ->
+                        when #Derived.stateRecord.t0p0 is
+                          Ok #Derived.t0p0 ->
+                            when #Derived.stateRecord.t0p1 is
+                              Ok #Derived.t0p1 -> Ok (Cons #Derived.t0p0 #Derived.t0p1)
+                              _ ->
+                                Err (Decode.prependPath
+                                  (Decode.missing (Index 1))
+                                  (Tag "Cons"))
+                          _ ->
+                            Err (Decode.prependPath
+                              (Decode.missing (Index 0))
+                              (Tag "Cons"))
+                      "Nil" -> Ok Nil
+                      _ ->
+                        Err (Decode.expected "one of the tags Cons, Nil" #Derived.tag))
                 #Derived.fmt3
         "###
         )
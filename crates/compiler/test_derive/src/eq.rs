@@ -0,0 +1,41 @@
+#![cfg(test)]
+// Even with #[allow(non_snake_case)] on individual idents, rust-analyzer issues diagnostics.
+// See https://github.com/rust-lang/rust-analyzer/issues/6541.
+// For the `v!` macro we use uppercase variables when constructing tag unions.
+#![allow(non_snake_case)]
+
+use crate::{util::check_single_lset_immediate, v};
+use roc_derive_key::DeriveBuiltin::IsEq;
+use roc_module::symbol::Symbol;
+use roc_types::subs::Variable;
+
+// {{{ deriver tests
+
+#[test]
+fn always_structural_eq() {
+    check_single_lset_immediate(IsEq, v!(U8), Symbol::BOOL_STRUCTURAL_EQ);
+    check_single_lset_immediate(IsEq, v!(DEC), Symbol::BOOL_STRUCTURAL_EQ);
+    check_single_lset_immediate(IsEq, v!(STR), Symbol::BOOL_STRUCTURAL_EQ);
+    check_single_lset_immediate(
+        IsEq,
+        v!(Symbol::LIST_LIST v!(U8)),
+        Symbol::BOOL_STRUCTURAL_EQ,
+    );
+    check_single_lset_immediate(
+        IsEq,
+        v!({ a: v!(U8), b: v!(STR), }),
+        Symbol::BOOL_STRUCTURAL_EQ,
+    );
+    check_single_lset_immediate(
+        IsEq,
+        v!([ A v!(U8) v!(STR), B v!(STR) ]),
+        Symbol::BOOL_STRUCTURAL_EQ,
+    );
+    check_single_lset_immediate(
+        IsEq,
+        v!([ Nil, Cons v!(^lst)] as lst),
+        Symbol::BOOL_STRUCTURAL_EQ,
+    );
+}
+
+// }}} deriver tests
@@ -1102,3 +1102,193 @@ fn decode_record_of_record() {
         RocStr
     )
 }
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_tag_zero_payloads() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Encode, Decode, Json] provides [main] to "./platform"
+
+            main =
+                when Str.toUtf8 "{\"B\":[]}" |> Decode.fromBytes Json.fromUtf8 is
+                    Ok A -> "A"
+                    Ok B -> "B"
+                    _ -> "something went wrong"
+            "#
+        ),
+        RocStr::from("B"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_tag_two_payloads_string_and_int() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Encode, Decode, Json] provides [main] to "./platform"
+
+            main =
+                when Str.toUtf8 "{\"A\":[\"ab\",10]}" |> Decode.fromBytes Json.fromUtf8 is
+                    Ok (A "ab" 10u8) -> "ab10"
+                    _ -> "something went wrong"
+            "#
+        ),
+        RocStr::from("ab10"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(feature = "gen-llvm-wasm"), // hits a wasm3 stack overflow
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_nested_tag_round_trip() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Encode.{ toEncoder }, Decode, Json] provides [main] to "./platform"
+
+            main =
+                value : [A [B Str Str, C], D]
+                value = A (B "foo" "bar")
+                when Encode.toBytes value Json.toUtf8 |> Decode.fromBytes Json.fromUtf8 is
+                    Ok decoded if decoded == value -> "round trip"
+                    _ -> "something went wrong"
+            "#
+        ),
+        RocStr::from("round trip"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(feature = "gen-llvm-wasm"), // hits a wasm3 stack overflow
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_tag_with_record_payload() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Encode, Decode, Json] provides [main] to "./platform"
+
+            main =
+                when Str.toUtf8 "{\"D\":[{\"c\":\"foo\"}]}" |> Decode.fromBytes Json.fromUtf8 is
+                    Ok (D {c: "foo"}) -> "foo"
+                    _ -> "something went wrong"
+            "#
+        ),
+        RocStr::from("foo"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_error_path_to_record_field() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Decode, Json] provides [main] to "./platform"
+
+            main =
+                decoded = Str.toUtf8 "{\"first\":\"ab\",\"second\":true}" |> Decode.fromBytesPartial Json.fromUtf8
+                when decoded.result is
+                    Ok { first, second } -> Str.concat first (Num.toStr (second + 1u8))
+                    Err err -> Decode.errorToStr err
+            "#
+        ),
+        RocStr::from("value.second: expected U8, found true}"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_error_missing_record_field() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Decode, Json] provides [main] to "./platform"
+
+            main =
+                decoded = Str.toUtf8 "{\"first\":\"ab\"}" |> Decode.fromBytesPartial Json.fromUtf8
+                when decoded.result is
+                    Ok { first, second } -> Str.concat first second
+                    Err err -> Decode.errorToStr err
+            "#
+        ),
+        RocStr::from("value.second: missing"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(feature = "gen-llvm-wasm"), // hits a wasm3 stack overflow
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_error_path_through_tag_and_list() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Decode, Json] provides [main] to "./platform"
+
+            main =
+                decoded : { result : Result [A (List U8), B] Decode.DecodeError, rest : List U8 }
+                decoded = Str.toUtf8 "{\"A\":[[1,\"x\"]]}" |> Decode.fromBytesPartial Json.fromUtf8
+                when decoded.result is
+                    Ok _ -> "something went wrong"
+                    Err err -> Decode.errorToStr err
+            "#
+        ),
+        RocStr::from(r#"value.A[0][1]: expected U8, found "x"]]}"#),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(feature = "gen-llvm-wasm"), // hits a wasm3 stack overflow
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_error_input_cut_between_chars() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Decode, Json] provides [main] to "./platform"
+
+            main =
+                decoded : { result : Result { n : U8 } Decode.DecodeError, rest : List U8 }
+                decoded = Str.toUtf8 "{\"n\":xéééééééééé}" |> Decode.fromBytesPartial Json.fromUtf8
+                when decoded.result is
+                    Ok _ -> "something went wrong"
+                    Err err -> Decode.errorToStr err
+            "#
+        ),
+        // 20 bytes in, the input is halfway through the tenth é
+        RocStr::from("value.n: expected U8, found xééééééééé"),
+        RocStr
+    )
+}
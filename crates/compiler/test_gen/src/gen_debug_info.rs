@@ -0,0 +1,254 @@
+//! Check the DWARF that the LLVM backend emits for `roc build --debug`, by reading it back
+//! from the object file.
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use indoc::indoc;
+use object::{Object, ObjectSection, ObjectSymbol, RelocationTarget};
+use roc_load::{ExecutionMode, LoadConfig, Threading};
+use roc_mono::ir::OptLevel;
+use roc_reporting::report::RenderTarget;
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct Entry {
+    tag: gimli::DwTag,
+    name: String,
+    line: Option<u64>,
+}
+
+struct DebugInfo {
+    /// The named debug info entries of the Roc compile unit
+    entries: HashSet<Entry>,
+    /// The lines of the Roc source that have machine code attributed to them
+    lines: HashSet<u64>,
+}
+
+fn compile_with_debug_info(src: &str) -> Vec<u8> {
+    let arena = bumpalo::Bump::new();
+    let target = target_lexicon::Triple::host();
+
+    let load_config = LoadConfig {
+        target_info: roc_target::TargetInfo::from(&target),
+        render: RenderTarget::ColorTerminal,
+        threading: Threading::Single,
+        exec_mode: ExecutionMode::Executable,
+        cache_dir: None,
+    };
+    let loaded = roc_load::load_and_monomorphize_from_str(
+        &arena,
+        PathBuf::from("Test.roc"),
+        src,
+        PathBuf::from("fake/test/path"),
+        Default::default(),
+        load_config,
+    )
+    .unwrap_or_else(|problem| panic!("{:?}", problem));
+
+    let dir = tempfile::tempdir().unwrap();
+    let app_o_file = dir.path().join("app.o");
+
+    roc_build::program::gen_from_mono_module_llvm(
+        &arena,
+        loaded,
+        &dir.path().join("Test.roc"),
+        &target,
+        &app_o_file,
+        OptLevel::Normal,
+        true,
+    );
+
+    std::fs::read(app_o_file).unwrap()
+}
+
+/// The contents of a section, with its relocations applied. Relocatable objects leave the
+/// references between debug sections (e.g. to names in `.debug_str`) to the linker.
+fn load_section<'data>(object: &object::File<'data>, name: &str) -> Cow<'data, [u8]> {
+    let section = match object.section_by_name(name) {
+        Some(section) => section,
+        None => return Cow::Borrowed(&[]),
+    };
+
+    let mut data = section.uncompressed_data().unwrap();
+
+    for (offset, relocation) in section.relocations() {
+        let symbol_address = match relocation.target() {
+            RelocationTarget::Symbol(index) => object.symbol_by_index(index).unwrap().address(),
+            _ => 0,
+        };
+        let value = (symbol_address as i64 + relocation.addend()) as u64;
+        let offset = offset as usize;
+
+        match relocation.size() {
+            32 => data.to_mut()[offset..][..4].copy_from_slice(&(value as u32).to_le_bytes()),
+            64 => data.to_mut()[offset..][..8].copy_from_slice(&value.to_le_bytes()),
+            size => panic!("unexpected relocation size {}", size),
+        }
+    }
+
+    data
+}
+
+fn read_debug_info(bytes: &[u8]) -> DebugInfo {
+    let object = object::File::parse(bytes).unwrap();
+
+    let dwarf = gimli::Dwarf::load(|id| -> Result<_, gimli::Error> {
+        Ok(load_section(&object, id.name()))
+    })
+    .unwrap();
+    let dwarf = dwarf.borrow(|section| gimli::EndianSlice::new(section, gimli::LittleEndian));
+
+    let mut entries = HashSet::new();
+    let mut lines = HashSet::new();
+
+    let mut units = dwarf.units();
+    while let Some(header) = units.next().unwrap() {
+        let unit = dwarf.unit(header).unwrap();
+
+        // skip the compile units of the builtins
+        if unit.name.map(|name| name.slice()) != Some(b"roc_app") {
+            continue;
+        }
+
+        let mut cursor = unit.entries();
+        while let Some((_, entry)) = cursor.next_dfs().unwrap() {
+            let name = match entry.attr_value(gimli::DW_AT_name).unwrap() {
+                Some(value) => dwarf.attr_string(&unit, value).unwrap(),
+                None => continue,
+            };
+            let line = entry
+                .attr_value(gimli::DW_AT_decl_line)
+                .unwrap()
+                .and_then(|value| value.udata_value());
+
+            entries.insert(Entry {
+                tag: entry.tag(),
+                name: name.to_string_lossy().into_owned(),
+                line,
+            });
+        }
+
+        let program = unit.line_program.clone().unwrap();
+        let mut rows = program.rows();
+        while let Some((header, row)) = rows.next_row().unwrap() {
+            let file = row.file(header).unwrap();
+            let path = dwarf.attr_string(&unit, file.path_name()).unwrap();
+
+            if path.slice().ends_with(b"Test.roc") {
+                lines.extend(row.line().map(u64::from));
+            }
+        }
+    }
+
+    DebugInfo { entries, lines }
+}
+
+fn entry(tag: gimli::DwTag, name: &str, line: u64) -> Entry {
+    Entry {
+        tag,
+        name: name.to_string(),
+        line: Some(line),
+    }
+}
+
+#[test]
+fn procs_and_locals_point_at_roc_source() {
+    let src = indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        addOne : I64 -> I64
+        addOne = \n ->
+            incremented = n + 1
+
+            doubled = incremented * 2
+
+            doubled
+
+        main = addOne 41
+        "#
+    );
+
+    let debug_info = read_debug_info(&compile_with_debug_info(src));
+
+    for expected in [
+        entry(gimli::DW_TAG_subprogram, "addOne", 5),
+        entry(gimli::DW_TAG_formal_parameter, "n", 5),
+        entry(gimli::DW_TAG_variable, "incremented", 5),
+        entry(gimli::DW_TAG_variable, "doubled", 7),
+        entry(gimli::DW_TAG_subprogram, "main", 11),
+    ] {
+        assert!(
+            debug_info.entries.contains(&expected),
+            "{:?} not found in {:#?}",
+            expected,
+            debug_info.entries
+        );
+    }
+
+    assert!(debug_info.lines.contains(&5), "{:?}", debug_info.lines);
+    assert!(debug_info.lines.contains(&7), "{:?}", debug_info.lines);
+}
+
+#[test]
+fn statements_point_at_their_lines() {
+    let src = indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        describe : I64 -> Str
+        describe = \n ->
+            Str.concat
+                (Num.toStr n)
+                "!"
+
+        main = describe 41
+        "#
+    );
+
+    let debug_info = read_debug_info(&compile_with_debug_info(src));
+
+    // the argument is computed on its own line, before the call it is passed to
+    assert!(debug_info.lines.contains(&5), "{:?}", debug_info.lines);
+    assert!(debug_info.lines.contains(&6), "{:?}", debug_info.lines);
+}
+
+#[test]
+fn str_and_list_are_structs() {
+    let src = indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        main =
+            numbers = List.repeat 1u8 (Num.toNat (Str.countUtf8Bytes "abc"))
+
+            greeting = Str.concat "Hi, " (Num.toStr (List.len numbers))
+
+            greeting
+        "#
+    );
+
+    let debug_info = read_debug_info(&compile_with_debug_info(src));
+
+    let unnamed = |tag, name: &str| Entry {
+        tag,
+        name: name.to_string(),
+        line: None,
+    };
+
+    for expected in [
+        unnamed(gimli::DW_TAG_structure_type, "Str"),
+        unnamed(gimli::DW_TAG_structure_type, "List"),
+        unnamed(gimli::DW_TAG_member, "ptr"),
+        unnamed(gimli::DW_TAG_member, "len"),
+        unnamed(gimli::DW_TAG_member, "cap"),
+    ] {
+        assert!(
+            debug_info.entries.contains(&expected),
+            "{:?} not found in {:#?}",
+            expected,
+            debug_info.entries
+        );
+    }
+}
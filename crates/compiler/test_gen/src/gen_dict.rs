@@ -549,3 +549,213 @@ fn walk_sum_keys() {
         i64
     );
 }
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn eq_ignores_how_dict_was_built() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            dict1 : Dict.Dict I64 I64
+            dict1 =
+                Dict.withCapacity 100
+                    |> Dict.insert 1 1
+                    |> Dict.insert 2 2
+
+            dict2 : Dict.Dict I64 I64
+            dict2 =
+                List.range 0 20
+                    |> List.walk Dict.empty (\d, k -> Dict.insert d k k)
+                    |> Dict.keepShared dict1
+
+            dict1 == dict2
+            "#
+        ),
+        true,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn eq_after_remove() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            dict1 : Dict.Dict I64 {}
+            dict1 =
+                Dict.empty
+                    |> Dict.insert 1 {}
+                    |> Dict.insert 2 {}
+                    |> Dict.insert 3 {}
+                    |> Dict.remove 3
+
+            dict2 : Dict.Dict I64 {}
+            dict2 =
+                Dict.empty
+                    |> Dict.insert 1 {}
+                    |> Dict.insert 2 {}
+
+            dict1 == dict2
+            "#
+        ),
+        true,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn eq_ignores_insertion_order() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            dict1 : Dict.Dict I64 Str
+            dict1 =
+                Dict.empty
+                    |> Dict.insert 1 "one"
+                    |> Dict.insert 2 "two"
+                    |> Dict.insert 3 "three"
+
+            dict2 : Dict.Dict I64 Str
+            dict2 =
+                Dict.empty
+                    |> Dict.insert 3 "three"
+                    |> Dict.insert 1 "one"
+                    |> Dict.insert 2 "two"
+
+            dict1 == dict2
+            "#
+        ),
+        true,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn eq_compares_values() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            dict1 : Dict.Dict I64 Str
+            dict1 =
+                Dict.empty
+                    |> Dict.insert 1 "one"
+                    |> Dict.insert 2 "two"
+
+            dict2 : Dict.Dict I64 Str
+            dict2 =
+                Dict.empty
+                    |> Dict.insert 2 "two"
+                    |> Dict.insert 1 "uno"
+
+            dict1 != dict2
+            "#
+        ),
+        true,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn grow_keeps_every_association() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            keys : List I64
+            keys = List.range 0 1000
+
+            dict = List.walk keys Dict.empty \d, k -> Dict.insert d k (k * 10)
+
+            found = List.all keys \k -> Dict.get dict k == Ok (k * 10)
+
+            if found && Dict.keys dict == keys then
+                Dict.len dict
+            else
+                0
+            "#
+        ),
+        1000,
+        usize
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn colliding_keys_interleaved_insert_remove() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Hash.{ Hash }] provides [main] to "./platform"
+
+            # Every key hashes the same way, so every lookup has to probe past the other keys.
+            Collide := I64 has [Hash { hash: hashCollide }]
+
+            hashCollide = \hasher, @Collide _ -> Hash.addU8 hasher 0
+
+            main =
+                keys : List I64
+                keys = List.range 0 40
+
+                full = List.walk keys Dict.empty \d, k -> Dict.insert d (@Collide k) k
+
+                dict =
+                    List.walk keys full \d, k ->
+                        if Num.isMultipleOf k 3 then
+                            Dict.remove d (@Collide k)
+                        else
+                            Dict.insert d (@Collide k) (k * 2)
+
+                consistent =
+                    List.all keys \k ->
+                        if Num.isMultipleOf k 3 then
+                            !(Dict.contains dict (@Collide k))
+                        else
+                            Dict.get dict (@Collide k) == Ok (k * 2)
+
+                keySum =
+                    Dict.keys dict
+                    |> List.map (\@Collide k -> k)
+                    |> List.sum
+
+                if consistent && keySum == 780 - 273 then
+                    Dict.len dict
+                else
+                    0
+            "#
+        ),
+        26,
+        usize
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn colliding_keys_remove_moves_last_key() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Hash.{ Hash }] provides [main] to "./platform"
+
+            Collide := I64 has [Hash { hash: hashCollide }]
+
+            hashCollide = \hasher, @Collide _ -> Hash.addU8 hasher 0
+
+            main =
+                dict =
+                    List.range 0 10
+                    |> List.walk Dict.empty (\d, k -> Dict.insert d (@Collide k) {})
+                    |> Dict.remove (@Collide 3)
+                    |> Dict.remove (@Collide 42)
+                    |> Dict.insert (@Collide 3) {}
+
+                Dict.keys dict
+                |> List.map (\@Collide k -> k)
+            "#
+        ),
+        RocList::from_slice(&[0, 1, 2, 9, 4, 5, 6, 7, 8, 3]),
+        RocList<i64>
+    );
+}
@@ -920,7 +920,7 @@ fn list_walk_implements_position() {
         r#"
         Option a : [Some a, None]
 
-        find : List a, a -> Option Nat
+        find : List a, a -> Option Nat | a has Eq
         find = \list, needle ->
             findHelp list needle
                 |> .v
@@ -3394,3 +3394,80 @@ fn list_let_generalization() {
         usize
     );
 }
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn list_pattern_exact_lengths() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            helper = \l ->
+                when l is
+                    [] -> 0u8
+                    [_] -> 1u8
+                    [_, _] -> 2u8
+                    _ -> 3u8
+
+            [helper [], helper [1u8], helper [1u8, 2], helper [1u8, 2, 3, 4]]
+            "#
+        ),
+        RocList::from_slice(&[0, 1, 2, 3]),
+        RocList<u8>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn list_pattern_first_and_last() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            helper = \l ->
+                when l is
+                    [] -> 0u64
+                    [x] -> x
+                    [first, .., last] -> first * 10 + last
+
+            [helper [], helper [7], helper [1, 2], helper [3, 4, 5, 6]]
+            "#
+        ),
+        RocList::from_slice(&[0, 7, 12, 36]),
+        RocList<u64>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn list_pattern_rest() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            sum = \l ->
+                when l is
+                    [] -> 0u64
+                    [x, .. as rest] -> x + sum rest
+
+            sum [1, 2, 3, 4]
+            "#
+        ),
+        10,
+        u64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn list_pattern_refcounted_elements() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            when ["a", "b", "c"] is
+                [.., "b", _] -> "middle"
+                [.. as rest, last] -> Str.concat last (Str.joinWith rest "")
+                _ -> "other"
+            "#
+        ),
+        RocStr::from("middle"),
+        RocStr
+    );
+}
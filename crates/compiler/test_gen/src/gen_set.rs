@@ -280,3 +280,23 @@ fn from_list_result() {
         i64
     );
 }
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn eq_ignores_insertion_order() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            set1 : Set.Set I64
+            set1 = Set.fromList [1, 2, 3]
+
+            set2 : Set.Set I64
+            set2 = Set.fromList [3, 1, 2, 1]
+
+            set1 == set2
+            "#
+        ),
+        true,
+        bool
+    );
+}
@@ -0,0 +1,233 @@
+#[cfg(feature = "gen-llvm")]
+use crate::helpers::llvm::assert_evals_to;
+
+#[cfg(feature = "gen-dev")]
+use crate::helpers::dev::assert_evals_to;
+
+#[cfg(feature = "gen-wasm")]
+use crate::helpers::wasm::assert_evals_to;
+
+use indoc::indoc;
+
+#[cfg(all(test, any(feature = "gen-llvm", feature = "gen-wasm")))]
+use roc_std::RocStr;
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn return_tuple() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                (3, 5, 4)
+                "#
+        ),
+        (3, 5, 4),
+        (i64, i64, i64)
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn tuple_access() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                pair = (15, 17)
+
+                pair.0
+                "#
+        ),
+        15,
+        i64
+    );
+
+    assert_evals_to!(
+        indoc!(
+            r#"
+                pair = (15, 17)
+
+                pair.1
+                "#
+        ),
+        17,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn tuple_access_on_literal() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                (3, 4).1
+                "#
+        ),
+        4,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn nested_tuple_access() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                nested = (1, (2, 3))
+
+                nested.1.0 + nested.0
+                "#
+        ),
+        3,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn tuple_let_destructure() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                (x, y) = (3, 5)
+
+                x + y
+                "#
+        ),
+        8,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn tuple_closure_param_destructure() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                swap = \(a, b) -> (b, a)
+
+                swap (1, 2)
+                "#
+        ),
+        (2, 1),
+        (i64, i64)
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn tuple_when_pattern() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                when (1, 2) is
+                    (1, x) -> x
+                    (_, y) -> y + 10
+                "#
+        ),
+        2,
+        i64
+    );
+
+    assert_evals_to!(
+        indoc!(
+            r#"
+                when (3, 2) is
+                    (1, x) -> x
+                    (_, y) -> y + 10
+                "#
+        ),
+        12,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn tuple_when_nested_tag_pattern() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                x : (Result I64 I64, I64)
+                x = (Err 4, 5)
+
+                when x is
+                    (Ok a, b) -> a + b
+                    (Err a, b) -> a * b
+                "#
+        ),
+        20,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn return_tuple_mixed_alignment() {
+    // Elements are laid out from the largest to the smallest alignment.
+    assert_evals_to!(
+        indoc!(
+            r#"
+                x : (U8, I64, U16)
+                x = (1, 2, 3)
+
+                x
+                "#
+        ),
+        (2, 3, 1),
+        (i64, u16, u8)
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn tuple_access_mixed_alignment() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                x : (U8, I64, U16)
+                x = (1, 2, 3)
+
+                Num.toI64 x.0 + x.1 * 10 + Num.toI64 x.2 * 100
+                "#
+        ),
+        321,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn tuple_destructure_mixed_alignment() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                x : (U8, F64, U16)
+                x = (1, 2.5, 3)
+
+                when x is
+                    (a, b, c) -> Num.toF64 a + b + Num.toF64 c
+                "#
+        ),
+        6.5,
+        f64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn tuple_with_str() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                pair = ("hello", 1.5)
+
+                pair.0
+                "#
+        ),
+        RocStr::from("hello"),
+        RocStr
+    );
+}
@@ -72,6 +72,7 @@ fn create_llvm_module<'a>(
         render: RenderTarget::ColorTerminal,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Executable,
+        cache_dir: None,
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -224,6 +225,7 @@ fn create_llvm_module<'a>(
         mode: config.mode,
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
+        debug_sources: Default::default(),
     };
 
     // strip Zig debug stuff
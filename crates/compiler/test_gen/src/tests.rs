@@ -6,6 +6,8 @@
 
 pub mod gen_abilities;
 pub mod gen_compare;
+#[cfg(all(feature = "gen-llvm", target_os = "linux"))]
+pub mod gen_debug_info;
 pub mod gen_dict;
 pub mod gen_list;
 pub mod gen_num;
@@ -16,6 +18,7 @@ pub mod gen_result;
 pub mod gen_set;
 pub mod gen_str;
 pub mod gen_tags;
+pub mod gen_tuples;
 mod helpers;
 pub mod wasm_str;
 
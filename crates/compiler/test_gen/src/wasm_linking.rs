@@ -19,6 +19,7 @@ use roc_mono::ir::{
     UpdateModeId,
 };
 use roc_mono::layout::{Builtin, CapturesNiche, LambdaName, Layout, STLayoutInterner};
+use roc_region::all::Region;
 use wasm3::{Environment, Module};
 
 const LINKING_TEST_HOST_WASM: &str = "build/wasm_linking_test_host.wasm";
@@ -119,6 +120,9 @@ fn build_app_mono<'a>(
         is_self_recursive: SelfRecursive::NotSelfRecursive,
         must_own_arguments: false,
         host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+        region: Region::zero(),
+        locals: &[],
+        let_regions: &[],
     };
 
     let proc_layout = ProcLayout {
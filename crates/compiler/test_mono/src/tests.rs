@@ -74,7 +74,7 @@ fn promote_expr_to_module(src: &str) -> String {
     buffer
 }
 
-fn compiles_to_ir(test_name: &str, src: &str) {
+fn compiles_to_ir(test_name: &str, src: &str, inline: bool) {
     use bumpalo::Bump;
     use std::path::PathBuf;
 
@@ -99,6 +99,7 @@ fn compiles_to_ir(test_name: &str, src: &str) {
         threading: Threading::Single,
         render: roc_reporting::report::RenderTarget::Generic,
         exec_mode: ExecutionMode::Executable,
+        cache_dir: None,
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -121,9 +122,10 @@ fn compiles_to_ir(test_name: &str, src: &str) {
     use roc_load::MonomorphizedModule;
     let MonomorphizedModule {
         module_id: home,
-        procedures,
+        mut procedures,
         exposed_to_host,
         layout_interner,
+        mut interns,
         ..
     } = loaded;
 
@@ -140,6 +142,18 @@ fn compiles_to_ir(test_name: &str, src: &str) {
 
     let main_fn_symbol = exposed_to_host.values.keys().copied().next().unwrap();
 
+    if inline {
+        let exposed = exposed_to_host.values.keys().copied().collect();
+
+        Proc::inline_and_fold_constants(
+            arena,
+            home,
+            interns.all_ident_ids.get_mut(&home).unwrap(),
+            &exposed,
+            &mut procedures,
+        );
+    }
+
     verify_procedures(test_name, layout_interner, procedures, main_fn_symbol);
 }
 
@@ -370,6 +384,34 @@ fn dict() {
     "#
 }
 
+#[mono_test]
+fn dict_insert_get_str_key() {
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        main =
+            Dict.empty
+            |> Dict.insert "a" 1u8
+            |> Dict.get "a"
+        "#
+    )
+}
+
+#[mono_test]
+fn hash_derived_record_and_tag() {
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        main =
+            Dict.empty
+            |> Dict.insert { a: A 1u8, b: [B "b", C] } 1u8
+            |> Dict.len
+        "#
+    )
+}
+
 #[mono_test]
 fn list_append_closure() {
     r#"
@@ -1936,3 +1978,113 @@ fn num_width_gt_u8_layout_as_float() {
         "#
     )
 }
+
+#[mono_test]
+fn let_with_tuple_pattern() {
+    indoc!(
+        r#"
+        (x, _) = (0x2, 3.14)
+
+        x
+        "#
+    )
+}
+
+#[mono_test]
+fn tuple_access() {
+    indoc!(
+        r#"
+        tup = (1u8, "foo", 3.14f64)
+
+        tup.1
+        "#
+    )
+}
+
+#[mono_test]
+fn ir_when_tuple() {
+    indoc!(
+        r#"
+        when (Ok 1u8, 2u8) is
+            (Ok x, y) -> x + y
+            (Err {}, y) -> y
+        "#
+    )
+}
+
+#[mono_test]
+fn list_pattern_first_and_rest() {
+    indoc!(
+        r#"
+        l : List U8
+        l = [1, 2, 3]
+
+        when l is
+            [] -> 0
+            [first, .. as rest] -> first + Num.toU8 (List.len rest)
+        "#
+    )
+}
+
+#[mono_test]
+fn list_pattern_exact_and_last() {
+    indoc!(
+        r#"
+        l : List U8
+        l = [1, 2, 3]
+
+        when l is
+            [a, b] -> a + b
+            [.., 5] -> 5
+            [.., last] -> last
+            [] -> 0
+        "#
+    )
+}
+
+#[mono_test]
+fn dbg_value_and_continuation() {
+    indoc!(
+        r#"
+        x = dbg Str.concat "a" "b"
+
+        dbg Str.countGraphemes x
+
+        x
+        "#
+    )
+}
+
+#[mono_test]
+fn dbg_in_call_argument() {
+    indoc!(
+        r#"
+        List.len (dbg [1u8, 2, 3])
+        "#
+    )
+}
+
+#[mono_test(inline)]
+fn inline_and_fold_int_add() {
+    r#"
+    x = [1,2]
+    5 + 4 + 3 + List.len x
+    "#
+}
+
+#[mono_test(inline)]
+fn inline_and_fold_plus() {
+    r#"
+    1 + 2
+    "#
+}
+
+#[mono_test(inline)]
+fn fold_if_on_literal() {
+    r#"
+    if True then
+        1
+    else
+        2
+    "#
+}
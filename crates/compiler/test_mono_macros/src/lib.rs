@@ -4,7 +4,19 @@ use proc_macro::TokenStream;
 use quote::quote;
 
 #[proc_macro_attribute]
-pub fn mono_test(_args: TokenStream, item: TokenStream) -> TokenStream {
+pub fn mono_test(args: TokenStream, item: TokenStream) -> TokenStream {
+    let mut inline = false;
+
+    // `#[mono_test(inline)]` snapshots the IR after the inlining pass of the dev backends
+    for arg in syn::parse_macro_input!(args as syn::AttributeArgs) {
+        match arg {
+            syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("inline") => {
+                inline = true;
+            }
+            _ => panic!("unknown argument to mono_test"),
+        }
+    }
+
     let task_fn = syn::parse_macro_input!(item as syn::ItemFn);
 
     let args = task_fn.sig.inputs.clone();
@@ -20,7 +32,7 @@ pub fn mono_test(_args: TokenStream, item: TokenStream) -> TokenStream {
         #[test]
         #(#attributes)*
         #visibility fn #name(#args) {
-            compiles_to_ir(#name_str, #body);
+            compiles_to_ir(#name_str, #body, #inline);
 
         }
     };
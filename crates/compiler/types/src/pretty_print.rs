@@ -10,6 +10,7 @@ use std::fmt::Write;
 
 pub static WILDCARD: &str = "*";
 static EMPTY_RECORD: &str = "{}";
+static EMPTY_TUPLE: &str = "()";
 static EMPTY_TAG_UNION: &str = "[]";
 
 /// Requirements for parentheses.
@@ -255,6 +256,28 @@ fn find_names_needed(
                 find_under_alias,
             );
         }
+        Structure(Tuple(elems, ext_var)) => {
+            for index in elems.iter_variables() {
+                let var = subs[index];
+                find_names_needed(
+                    var,
+                    subs,
+                    roots,
+                    root_appearances,
+                    names_taken,
+                    find_under_alias,
+                );
+            }
+
+            find_names_needed(
+                *ext_var,
+                subs,
+                roots,
+                root_appearances,
+                names_taken,
+                find_under_alias,
+            );
+        }
         Structure(TagUnion(tags, ext_var)) => {
             for slice_index in tags.variables() {
                 let slice = subs[slice_index];
@@ -402,7 +425,11 @@ fn find_names_needed(
                 find_under_alias,
             );
         }
-        Error | Structure(Erroneous(_)) | Structure(EmptyRecord) | Structure(EmptyTagUnion) => {
+        Error
+        | Structure(Erroneous(_))
+        | Structure(EmptyRecord)
+        | Structure(EmptyTuple)
+        | Structure(EmptyTagUnion) => {
             // Errors and empty records don't need names.
         }
     }
@@ -920,6 +947,7 @@ impl<'a> ExtContent<'a> {
         match content {
             Content::Structure(FlatType::EmptyTagUnion) => ExtContent::Empty,
             Content::Structure(FlatType::EmptyRecord) => ExtContent::Empty,
+            Content::Structure(FlatType::EmptyTuple) => ExtContent::Empty,
 
             Content::FlexVar(_)
             | Content::FlexAbleVar(..)
@@ -1062,6 +1090,7 @@ fn write_flat_type<'a>(
             parens,
         ),
         EmptyRecord => buf.push_str(EMPTY_RECORD),
+        EmptyTuple => buf.push_str(EMPTY_TUPLE),
         EmptyTagUnion => buf.push_str(EMPTY_TAG_UNION),
         Func(args, closure, ret) => write_fn(
             env,
@@ -1137,6 +1166,37 @@ fn write_flat_type<'a>(
                 }
             }
         }
+        Tuple(elems, ext_var) => {
+            use crate::types::{gather_tuple_elems, TupleStructure};
+
+            // If the `ext` has concrete elements, merge them
+            let TupleStructure {
+                elems: sorted_elems,
+                ext,
+            } = gather_tuple_elems(subs, *elems, *ext_var)
+                .expect("Something ended up weird in this tuple type");
+
+            buf.push('(');
+
+            for (i, (_, var)) in sorted_elems.into_iter().enumerate() {
+                if i > 0 {
+                    buf.push_str(", ");
+                }
+
+                write_content(
+                    env,
+                    ctx,
+                    subs.get_content_without_compacting(var),
+                    subs,
+                    buf,
+                    Parens::Unnecessary,
+                );
+            }
+
+            buf.push(')');
+
+            write_ext_content(env, ctx, subs, buf, ExtContent::from_var(subs, ext), parens)
+        }
         TagUnion(tags, ext_var) => {
             buf.push('[');
 
@@ -1,6 +1,7 @@
 #![deny(unsafe_op_in_unsafe_fn)]
 use crate::types::{
-    name_type_var, AliasKind, ErrorType, Problem, RecordField, RecordFieldsError, TypeExt, Uls,
+    name_type_var, AliasKind, ErrorType, Problem, RecordField, RecordFieldsError, TupleElemsError,
+    TypeExt, Uls,
 };
 use roc_collections::all::{FnvMap, ImMap, ImSet, MutSet, SendMap};
 use roc_collections::{VecMap, VecSet};
@@ -75,6 +76,7 @@ struct SubsHeader {
     closure_names: u64,
     field_names: u64,
     record_fields: u64,
+    tuple_elem_indices: u64,
     variable_slices: u64,
     unspecialized_lambda_sets: u64,
     exposed_vars_by_symbol: u64,
@@ -93,6 +95,7 @@ impl SubsHeader {
             closure_names: subs.closure_names.len() as u64,
             field_names: subs.field_names.len() as u64,
             record_fields: subs.record_fields.len() as u64,
+            tuple_elem_indices: subs.tuple_elem_indices.len() as u64,
             variable_slices: subs.variable_slices.len() as u64,
             unspecialized_lambda_sets: subs.unspecialized_lambda_sets.len() as u64,
             exposed_vars_by_symbol: exposed_vars_by_symbol as u64,
@@ -142,6 +145,7 @@ impl Subs {
         written = Self::serialize_slice(&self.closure_names, writer, written)?;
         written = Self::serialize_field_names(&self.field_names, writer, written)?;
         written = Self::serialize_slice(&self.record_fields, writer, written)?;
+        written = Self::serialize_slice(&self.tuple_elem_indices, writer, written)?;
         written = Self::serialize_slice(&self.variable_slices, writer, written)?;
         written = Self::serialize_slice(&self.unspecialized_lambda_sets, writer, written)?;
         written = Self::serialize_slice(exposed_vars_by_symbol, writer, written)?;
@@ -190,7 +194,7 @@ impl Subs {
         Self::serialize_slice(&buf, writer, written)
     }
 
-    pub(crate) fn serialize_slice<T>(
+    pub fn serialize_slice<T>(
         slice: &[T],
         writer: &mut impl std::io::Write,
         written: usize,
@@ -225,6 +229,8 @@ impl Subs {
             Self::deserialize_field_names(bytes, header.field_names as usize, offset);
         let (record_fields, offset) =
             Self::deserialize_slice(bytes, header.record_fields as usize, offset);
+        let (tuple_elem_indices, offset) =
+            Self::deserialize_slice(bytes, header.tuple_elem_indices as usize, offset);
         let (variable_slices, offset) =
             Self::deserialize_slice(bytes, header.variable_slices as usize, offset);
         let (unspecialized_lambda_sets, offset) =
@@ -240,6 +246,7 @@ impl Subs {
                 closure_names: closure_names.to_vec(),
                 field_names,
                 record_fields: record_fields.to_vec(),
+                tuple_elem_indices: tuple_elem_indices.to_vec(),
                 variable_slices: variable_slices.to_vec(),
                 unspecialized_lambda_sets: unspecialized_lambda_sets.to_vec(),
                 tag_name_cache: Default::default(),
@@ -291,11 +298,7 @@ impl Subs {
         (tag_names, offset)
     }
 
-    pub(crate) fn deserialize_slice<T>(
-        bytes: &[u8],
-        length: usize,
-        mut offset: usize,
-    ) -> (&[T], usize) {
+    pub fn deserialize_slice<T>(bytes: &[u8], length: usize, mut offset: usize) -> (&[T], usize) {
         let alignment = std::mem::align_of::<T>();
         let size = std::mem::size_of::<T>();
 
@@ -389,6 +392,7 @@ pub struct Subs {
     pub closure_names: Vec<Symbol>,
     pub field_names: Vec<Lowercase>,
     pub record_fields: Vec<RecordField<()>>,
+    pub tuple_elem_indices: Vec<usize>,
     pub variable_slices: Vec<VariableSubsSlice>,
     pub unspecialized_lambda_sets: Vec<Uls>,
     pub tag_name_cache: TagNameCache,
@@ -525,6 +529,20 @@ impl std::ops::IndexMut<SubsIndex<RecordField<()>>> for Subs {
     }
 }
 
+impl std::ops::Index<SubsIndex<usize>> for Subs {
+    type Output = usize;
+
+    fn index(&self, index: SubsIndex<usize>) -> &Self::Output {
+        &self.tuple_elem_indices[index.index as usize]
+    }
+}
+
+impl std::ops::IndexMut<SubsIndex<usize>> for Subs {
+    fn index_mut(&mut self, index: SubsIndex<usize>) -> &mut Self::Output {
+        &mut self.tuple_elem_indices[index.index as usize]
+    }
+}
+
 impl std::ops::Index<SubsIndex<VariableSubsSlice>> for Subs {
     type Output = VariableSubsSlice;
 
@@ -736,6 +754,12 @@ impl GetSubsSlice<RecordField<()>> for Subs {
     }
 }
 
+impl GetSubsSlice<usize> for Subs {
+    fn get_subs_slice(&self, subs_slice: SubsSlice<usize>) -> &[usize] {
+        subs_slice.get_slice(&self.tuple_elem_indices)
+    }
+}
+
 impl GetSubsSlice<Lowercase> for Subs {
     fn get_subs_slice(&self, subs_slice: SubsSlice<Lowercase>) -> &[Lowercase] {
         subs_slice.get_slice(&self.field_names)
@@ -943,6 +967,21 @@ fn subs_fmt_flat_type(this: &FlatType, subs: &Subs, f: &mut fmt::Formatter) -> f
 
             write!(f, "}}<{:?}>", new_ext)
         }
+        FlatType::Tuple(elems, ext) => {
+            write!(f, "( ")?;
+
+            let (it, new_ext) = elems.sorted_iterator_and_ext(subs, *ext);
+            for (index, var) in it {
+                write!(
+                    f,
+                    "{:?}: {:?}, ",
+                    index,
+                    SubsFmtContent(subs.get_content_without_compacting(var), subs)
+                )?;
+            }
+
+            write!(f, ")<{:?}>", new_ext)
+        }
         FlatType::TagUnion(tags, ext) => {
             write!(f, "[")?;
 
@@ -983,6 +1022,7 @@ fn subs_fmt_flat_type(this: &FlatType, subs: &Subs, f: &mut fmt::Formatter) -> f
         }
         FlatType::Erroneous(e) => write!(f, "Erroneous({:?})", e),
         FlatType::EmptyRecord => write!(f, "EmptyRecord"),
+        FlatType::EmptyTuple => write!(f, "EmptyTuple"),
         FlatType::EmptyTagUnion => write!(f, "EmptyTagUnion"),
     }
 }
@@ -1241,6 +1281,7 @@ define_const_var! {
 
     :pub EMPTY_RECORD,
     :pub EMPTY_TAG_UNION,
+    :pub EMPTY_TUPLE,
 
     BOOL_ENUM,
     :pub BOOL,
@@ -1706,6 +1747,7 @@ impl Subs {
             closure_names: Vec::new(),
             field_names: Vec::new(),
             record_fields: Vec::new(),
+            tuple_elem_indices: Vec::new(),
             // store an empty slice at the first position
             // used for "TagOrFunction"
             variable_slices: vec![VariableSubsSlice::default()],
@@ -1728,6 +1770,10 @@ impl Subs {
             Variable::EMPTY_TAG_UNION,
             Content::Structure(FlatType::EmptyTagUnion),
         );
+        subs.set_content(
+            Variable::EMPTY_TUPLE,
+            Content::Structure(FlatType::EmptyTuple),
+        );
 
         let bool_union_tags = UnionTags::insert_into_subs(
             &mut subs,
@@ -2418,11 +2464,13 @@ pub enum FlatType {
     Apply(Symbol, VariableSubsSlice),
     Func(VariableSubsSlice, Variable, Variable),
     Record(RecordFields, Variable),
+    Tuple(TupleElems, Variable),
     TagUnion(UnionTags, Variable),
     FunctionOrTagUnion(SubsIndex<TagName>, Symbol, Variable),
     RecursiveTagUnion(Variable, UnionTags, Variable),
     Erroneous(SubsIndex<Problem>),
     EmptyRecord,
+    EmptyTuple,
     EmptyTagUnion,
 }
 
@@ -3097,6 +3145,151 @@ impl RecordFields {
     }
 }
 
+#[derive(Clone, Copy, Debug)]
+pub struct TupleElems {
+    pub length: u16,
+    pub elem_index_start: u32,
+    pub variables_start: u32,
+}
+
+pub type SortedElemsIterator<'a> = Box<dyn Iterator<Item = (usize, Variable)> + 'a>;
+
+impl TupleElems {
+    pub const fn len(&self) -> usize {
+        self.length as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn empty() -> Self {
+        Self {
+            length: 0,
+            elem_index_start: 0,
+            variables_start: 0,
+        }
+    }
+
+    pub const fn variables(&self) -> SubsSlice<Variable> {
+        SubsSlice::new(self.variables_start, self.length)
+    }
+
+    pub const fn elem_indices(&self) -> SubsSlice<usize> {
+        SubsSlice::new(self.elem_index_start, self.length)
+    }
+
+    pub fn iter_variables(&self) -> impl Iterator<Item = SubsIndex<Variable>> {
+        let slice = SubsSlice::new(self.variables_start, self.length);
+        slice.into_iter()
+    }
+
+    pub fn insert_into_subs<I>(subs: &mut Subs, input: I) -> Self
+    where
+        I: IntoIterator<Item = (usize, Variable)>,
+    {
+        let elem_index_start = subs.tuple_elem_indices.len() as u32;
+        let variables_start = subs.variables.len() as u32;
+
+        let it = input.into_iter();
+        let size_hint = it.size_hint().0;
+
+        subs.tuple_elem_indices.reserve(size_hint);
+        subs.variables.reserve(size_hint);
+
+        let mut length = 0;
+        for (index, var) in it {
+            subs.tuple_elem_indices.push(index);
+            subs.variables.push(var);
+
+            length += 1;
+        }
+
+        TupleElems {
+            length,
+            elem_index_start,
+            variables_start,
+        }
+    }
+
+    #[inline(always)]
+    pub fn unsorted_iterator<'a>(
+        &'a self,
+        subs: &'a Subs,
+        ext: Variable,
+    ) -> Result<impl Iterator<Item = (usize, Variable)> + 'a, TupleElemsError> {
+        let (it, _) = crate::types::gather_tuple_elems_unsorted_iter(subs, *self, ext)?;
+
+        Ok(it)
+    }
+
+    /// get a sorted iterator over the elements of this tuple type
+    ///
+    /// Like [RecordFields::sorted_iterator], this reads the elements directly from Subs when the
+    /// `ext` variable is the empty tuple, and otherwise chases the ext var and sorts.
+    #[inline(always)]
+    pub fn sorted_iterator<'a>(&'_ self, subs: &'a Subs, ext: Variable) -> SortedElemsIterator<'a> {
+        self.sorted_iterator_and_ext(subs, ext).0
+    }
+
+    #[inline(always)]
+    pub fn sorted_iterator_and_ext<'a>(
+        &'_ self,
+        subs: &'a Subs,
+        ext: Variable,
+    ) -> (SortedElemsIterator<'a>, Variable) {
+        if is_empty_tuple(subs, ext) {
+            (
+                Box::new(self.iter_all().map(move |(i1, i2)| (subs[i1], subs[i2]))),
+                ext,
+            )
+        } else {
+            let tuple_structure = crate::types::gather_tuple_elems(subs, *self, ext)
+                .expect("Something ended up weird in this tuple type");
+
+            (
+                Box::new(tuple_structure.elems.into_iter()),
+                tuple_structure.ext,
+            )
+        }
+    }
+
+    pub fn iter_all(&self) -> impl Iterator<Item = (SubsIndex<usize>, SubsIndex<Variable>)> {
+        let helper = |start| start..(start + self.length as u32);
+
+        let range1 = helper(self.elem_index_start);
+        let range2 = helper(self.variables_start);
+
+        let it = range1.into_iter().zip(range2.into_iter());
+
+        it.map(|(i1, i2)| (SubsIndex::new(i1), SubsIndex::new(i2)))
+    }
+}
+
+fn is_empty_tuple(subs: &Subs, mut var: Variable) -> bool {
+    use crate::subs::Content::*;
+    use crate::subs::FlatType::*;
+
+    loop {
+        match subs.get_content_without_compacting(var) {
+            Structure(EmptyTuple) => return true,
+            Structure(Tuple(sub_elems, sub_ext)) => {
+                if !sub_elems.is_empty() {
+                    return false;
+                }
+
+                var = *sub_ext;
+            }
+
+            Alias(_, _, actual_var, _) => {
+                var = *actual_var;
+            }
+
+            _ => return false,
+        }
+    }
+}
+
 fn is_empty_record(subs: &Subs, mut var: Variable) -> bool {
     use crate::subs::Content::*;
     use crate::subs::FlatType::*;
@@ -3164,6 +3357,11 @@ fn occurs(
                         let it = once(ext_var).chain(subs.get_subs_slice(slice).iter());
                         short_circuit(subs, root_var, &new_seen, it)
                     }
+                    Tuple(vars_by_elem, ext_var) => {
+                        let slice = vars_by_elem.variables();
+                        let it = once(ext_var).chain(subs.get_subs_slice(slice).iter());
+                        short_circuit(subs, root_var, &new_seen, it)
+                    }
                     TagUnion(tags, ext_var) => {
                         occurs_union(subs, root_var, &new_seen, tags)?;
 
@@ -3178,7 +3376,7 @@ fn occurs(
 
                         short_circuit_help(subs, root_var, &new_seen, *ext_var)
                     }
-                    EmptyRecord | EmptyTagUnion | Erroneous(_) => Ok(()),
+                    EmptyRecord | EmptyTuple | EmptyTagUnion | Erroneous(_) => Ok(()),
                 }
             }
             Alias(_, args, _, _) => {
@@ -3348,8 +3546,19 @@ fn explicit_substitute(
 
                         subs.set_content(in_var, Structure(Record(vars_by_field, new_ext_var)));
                     }
+                    Tuple(vars_by_elem, ext_var) => {
+                        let new_ext_var = explicit_substitute(subs, from, to, ext_var, seen);
+
+                        for index in vars_by_elem.iter_variables() {
+                            let var = subs[index];
+                            let new_var = explicit_substitute(subs, from, to, var, seen);
+                            subs[index] = new_var;
+                        }
+
+                        subs.set_content(in_var, Structure(Tuple(vars_by_elem, new_ext_var)));
+                    }
 
-                    EmptyRecord | EmptyTagUnion | Erroneous(_) => {}
+                    EmptyRecord | EmptyTuple | EmptyTagUnion | Erroneous(_) => {}
                 }
 
                 in_var
@@ -3532,9 +3741,10 @@ fn get_var_names(
                     accum
                 }
 
-                FlatType::EmptyRecord | FlatType::EmptyTagUnion | FlatType::Erroneous(_) => {
-                    taken_names
-                }
+                FlatType::EmptyRecord
+                | FlatType::EmptyTuple
+                | FlatType::EmptyTagUnion
+                | FlatType::Erroneous(_) => taken_names,
 
                 FlatType::Record(vars_by_field, ext_var) => {
                     let mut accum = get_var_names(subs, ext_var, taken_names);
@@ -3547,6 +3757,17 @@ fn get_var_names(
 
                     accum
                 }
+                FlatType::Tuple(vars_by_elem, ext_var) => {
+                    let mut accum = get_var_names(subs, ext_var, taken_names);
+
+                    for var_index in vars_by_elem.iter_variables() {
+                        let arg_var = subs[var_index];
+
+                        accum = get_var_names(subs, arg_var, accum)
+                    }
+
+                    accum
+                }
                 FlatType::TagUnion(tags, ext_var) => {
                     let taken_names = get_var_names(subs, ext_var, taken_names);
                     get_var_names_union(subs, tags, taken_names)
@@ -3811,6 +4032,7 @@ fn flat_type_to_err_type(
         }
 
         EmptyRecord => ErrorType::Record(SendMap::default(), TypeExt::Closed),
+        EmptyTuple => ErrorType::Tuple(Vec::default(), TypeExt::Closed),
         EmptyTagUnion => ErrorType::TagUnion(SendMap::default(), TypeExt::Closed),
 
         Record(vars_by_field, ext_var) => {
@@ -3852,6 +4074,37 @@ fn flat_type_to_err_type(
             }
         }
 
+        Tuple(vars_by_elem, ext_var) => {
+            let mut err_elems = Vec::with_capacity(vars_by_elem.len());
+
+            for (i1, i2) in vars_by_elem.iter_all() {
+                let index = subs[i1];
+                let var = subs[i2];
+
+                err_elems.push((index, var_to_err_type(subs, state, var)));
+            }
+
+            match var_to_err_type(subs, state, ext_var).unwrap_structural_alias() {
+                ErrorType::Tuple(sub_elems, sub_ext) => {
+                    err_elems.extend(sub_elems);
+                    err_elems.sort_by_key(|(index, _)| *index);
+
+                    ErrorType::Tuple(err_elems, sub_ext)
+                }
+
+                ErrorType::FlexVar(var) => {
+                    ErrorType::Tuple(err_elems, TypeExt::FlexOpen(var))
+                }
+
+                ErrorType::RigidVar(var) => {
+                    ErrorType::Tuple(err_elems, TypeExt::RigidOpen(var))
+                }
+
+                other =>
+                    panic!("Tried to convert a tuple extension to an error, but the tuple extension had the ErrorType of {:?}", other)
+            }
+        }
+
         TagUnion(tags, ext_var) => {
             let err_tags = union_tags_to_err_tags(subs, state, tags);
 
@@ -4009,6 +4262,7 @@ struct StorageSubsOffsets {
     closure_names: u32,
     field_names: u32,
     record_fields: u32,
+    tuple_elem_indices: u32,
     variable_slices: u32,
     unspecialized_lambda_sets: u32,
     problems: u32,
@@ -4093,6 +4347,7 @@ impl StorageSubs {
             closure_names: self.subs.closure_names.len() as u32,
             field_names: self.subs.field_names.len() as u32,
             record_fields: self.subs.record_fields.len() as u32,
+            tuple_elem_indices: self.subs.tuple_elem_indices.len() as u32,
             variable_slices: self.subs.variable_slices.len() as u32,
             unspecialized_lambda_sets: self.subs.unspecialized_lambda_sets.len() as u32,
             problems: self.subs.problems.len() as u32,
@@ -4105,6 +4360,7 @@ impl StorageSubs {
             closure_names: target.closure_names.len() as u32,
             field_names: target.field_names.len() as u32,
             record_fields: target.record_fields.len() as u32,
+            tuple_elem_indices: target.tuple_elem_indices.len() as u32,
             variable_slices: target.variable_slices.len() as u32,
             unspecialized_lambda_sets: target.unspecialized_lambda_sets.len() as u32,
             problems: target.problems.len() as u32,
@@ -4153,6 +4409,9 @@ impl StorageSubs {
         target.closure_names.extend(self.subs.closure_names);
         target.field_names.extend(self.subs.field_names);
         target.record_fields.extend(self.subs.record_fields);
+        target
+            .tuple_elem_indices
+            .extend(self.subs.tuple_elem_indices);
         target
             .unspecialized_lambda_sets
             .extend(self.subs.unspecialized_lambda_sets);
@@ -4193,6 +4452,10 @@ impl StorageSubs {
                 Self::offset_record_fields(offsets, *record_fields),
                 Self::offset_variable(offsets, *ext),
             ),
+            FlatType::Tuple(tuple_elems, ext) => FlatType::Tuple(
+                Self::offset_tuple_elems(offsets, *tuple_elems),
+                Self::offset_variable(offsets, *ext),
+            ),
             FlatType::TagUnion(union_tags, ext) => FlatType::TagUnion(
                 Self::offset_tag_union(offsets, *union_tags),
                 Self::offset_variable(offsets, *ext),
@@ -4211,6 +4474,7 @@ impl StorageSubs {
                 FlatType::Erroneous(Self::offset_problem(offsets, *problem))
             }
             FlatType::EmptyRecord => FlatType::EmptyRecord,
+            FlatType::EmptyTuple => FlatType::EmptyTuple,
             FlatType::EmptyTagUnion => FlatType::EmptyTagUnion,
         }
     }
@@ -4290,6 +4554,13 @@ impl StorageSubs {
         record_fields
     }
 
+    fn offset_tuple_elems(offsets: &StorageSubsOffsets, mut tuple_elems: TupleElems) -> TupleElems {
+        tuple_elems.elem_index_start += offsets.tuple_elem_indices;
+        tuple_elems.variables_start += offsets.variables;
+
+        tuple_elems
+    }
+
     fn offset_tag_name_index(
         offsets: &StorageSubsOffsets,
         mut tag_name: SubsIndex<TagName>,
@@ -4493,7 +4764,10 @@ fn storage_copy_var_to_help(env: &mut StorageCopyVarToEnv<'_>, var: Variable) ->
                     Func(new_arguments, new_closure_var, new_ret_var)
                 }
 
-                same @ EmptyRecord | same @ EmptyTagUnion | same @ Erroneous(_) => same,
+                same @ EmptyRecord
+                | same @ EmptyTuple
+                | same @ EmptyTagUnion
+                | same @ Erroneous(_) => same,
 
                 Record(fields, ext_var) => {
                     let record_fields = {
@@ -4530,6 +4804,36 @@ fn storage_copy_var_to_help(env: &mut StorageCopyVarToEnv<'_>, var: Variable) ->
                     Record(record_fields, storage_copy_var_to_help(env, ext_var))
                 }
 
+                Tuple(elems, ext_var) => {
+                    let tuple_elems = {
+                        let new_variables =
+                            VariableSubsSlice::reserve_into_subs(env.target, elems.len());
+
+                        let it = (new_variables.indices()).zip(elems.iter_variables());
+                        for (target_index, var_index) in it {
+                            let var = env.source[var_index];
+                            let copy_var = storage_copy_var_to_help(env, var);
+                            env.target.variables[target_index] = copy_var;
+                        }
+
+                        let elem_index_start = env.target.tuple_elem_indices.len() as u32;
+
+                        let elem_indices =
+                            &env.source.tuple_elem_indices[elems.elem_indices().indices()];
+                        env.target
+                            .tuple_elem_indices
+                            .extend(elem_indices.iter().copied());
+
+                        TupleElems {
+                            length: elems.len() as _,
+                            elem_index_start,
+                            variables_start: new_variables.start,
+                        }
+                    };
+
+                    Tuple(tuple_elems, storage_copy_var_to_help(env, ext_var))
+                }
+
                 TagUnion(tags, ext_var) => {
                     let new_ext = storage_copy_var_to_help(env, ext_var);
                     let union_tags = storage_copy_union(env, tags);
@@ -4931,7 +5235,7 @@ fn copy_import_to_help(env: &mut CopyImportEnv<'_>, max_rank: Rank, var: Variabl
 
                 Erroneous(_) => internal_error!("I thought this was handled above"),
 
-                same @ EmptyRecord | same @ EmptyTagUnion => same,
+                same @ EmptyRecord | same @ EmptyTuple | same @ EmptyTagUnion => same,
 
                 Record(fields, ext_var) => {
                     let record_fields = {
@@ -4968,6 +5272,36 @@ fn copy_import_to_help(env: &mut CopyImportEnv<'_>, max_rank: Rank, var: Variabl
                     Record(record_fields, copy_import_to_help(env, max_rank, ext_var))
                 }
 
+                Tuple(elems, ext_var) => {
+                    let tuple_elems = {
+                        let new_variables =
+                            VariableSubsSlice::reserve_into_subs(env.target, elems.len());
+
+                        let it = (new_variables.indices()).zip(elems.iter_variables());
+                        for (target_index, var_index) in it {
+                            let var = env.source[var_index];
+                            let copy_var = copy_import_to_help(env, max_rank, var);
+                            env.target.variables[target_index] = copy_var;
+                        }
+
+                        let elem_index_start = env.target.tuple_elem_indices.len() as u32;
+
+                        let elem_indices =
+                            &env.source.tuple_elem_indices[elems.elem_indices().indices()];
+                        env.target
+                            .tuple_elem_indices
+                            .extend(elem_indices.iter().copied());
+
+                        TupleElems {
+                            length: elems.len() as _,
+                            elem_index_start,
+                            variables_start: new_variables.start,
+                        }
+                    };
+
+                    Tuple(tuple_elems, copy_import_to_help(env, max_rank, ext_var))
+                }
+
                 TagUnion(tags, ext_var) => {
                     let new_ext = copy_import_to_help(env, max_rank, ext_var);
 
@@ -5245,6 +5579,7 @@ fn instantiate_rigids_help(subs: &mut Subs, max_rank: Rank, initial: Variable) {
                 }
 
                 EmptyRecord => (),
+                EmptyTuple => (),
                 EmptyTagUnion => (),
 
                 Record(fields, ext_var) => {
@@ -5254,6 +5589,13 @@ fn instantiate_rigids_help(subs: &mut Subs, max_rank: Rank, initial: Variable) {
 
                     stack.push(ext_var);
                 }
+                Tuple(elems, ext_var) => {
+                    let elems = *elems;
+                    let ext_var = *ext_var;
+                    stack.extend(var_slice!(elems.variables()));
+
+                    stack.push(ext_var);
+                }
                 TagUnion(tags, ext_var) => {
                     let tags = *tags;
                     let ext_var = *ext_var;
@@ -5366,6 +5708,10 @@ pub fn get_member_lambda_sets_at_region(subs: &Subs, var: Variable, target_regio
                     stack.extend(subs.get_subs_slice(fields.variables()));
                     stack.push(*ext);
                 }
+                FlatType::Tuple(elems, ext) => {
+                    stack.extend(subs.get_subs_slice(elems.variables()));
+                    stack.push(*ext);
+                }
                 FlatType::TagUnion(tags, ext) => {
                     stack.extend(
                         subs.get_subs_slice(tags.variables())
@@ -5386,7 +5732,10 @@ pub fn get_member_lambda_sets_at_region(subs: &Subs, var: Variable, target_regio
                     );
                     stack.push(*ext);
                 }
-                FlatType::Erroneous(_) | FlatType::EmptyRecord | FlatType::EmptyTagUnion => {}
+                FlatType::Erroneous(_)
+                | FlatType::EmptyRecord
+                | FlatType::EmptyTuple
+                | FlatType::EmptyTagUnion => {}
             },
             Content::Alias(_, _, real_var, _) => {
                 stack.push(*real_var);
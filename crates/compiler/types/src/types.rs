@@ -1,9 +1,10 @@
 use crate::num::NumericRange;
 use crate::pretty_print::Parens;
 use crate::subs::{
-    GetSubsSlice, RecordFields, Subs, UnionTags, VarStore, Variable, VariableSubsSlice,
+    GetSubsSlice, RecordFields, Subs, TupleElems, UnionTags, VarStore, Variable, VariableSubsSlice,
 };
 use roc_collections::all::{HumanIndex, ImMap, ImSet, MutMap, MutSet, SendMap};
+use roc_collections::VecMap;
 use roc_error_macros::internal_error;
 use roc_module::called_via::CalledVia;
 use roc_module::ident::{ForeignSymbol, Ident, Lowercase, TagName};
@@ -250,6 +251,7 @@ pub enum Type {
     /// A function. The types of its arguments, size of its closure, then the type of its return value.
     Function(Vec<Type>, Box<Type>, Box<Type>),
     Record(SendMap<Lowercase, RecordField<Type>>, TypeExtension),
+    Tuple(VecMap<usize, Type>, TypeExtension),
     TagUnion(Vec<(TagName, Vec<Type>)>, TypeExtension),
     FunctionOrTagUnion(TagName, Symbol, TypeExtension),
     /// A function name that is used in our defunctionalization algorithm. For example in
@@ -331,6 +333,7 @@ impl Clone for Type {
                 Self::Function(arg0.clone(), arg1.clone(), arg2.clone())
             }
             Self::Record(arg0, arg1) => Self::Record(arg0.clone(), arg1.clone()),
+            Self::Tuple(arg0, arg1) => Self::Tuple(arg0.clone(), arg1.clone()),
             Self::TagUnion(arg0, arg1) => Self::TagUnion(arg0.clone(), arg1.clone()),
             Self::FunctionOrTagUnion(arg0, arg1, arg2) => {
                 Self::FunctionOrTagUnion(arg0.clone(), *arg1, arg2.clone())
@@ -617,6 +620,24 @@ impl fmt::Debug for Type {
                     }
                 }
             }
+            Type::Tuple(elems, ext) => {
+                write!(f, "(")?;
+
+                for (i, (_, elem_type)) in elems.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write!(f, "{:?}", elem_type)?;
+                }
+
+                write!(f, ")")?;
+
+                match ext {
+                    TypeExtension::Closed => Ok(()),
+                    TypeExtension::Open(other) => other.fmt(f),
+                }
+            }
             Type::TagUnion(tags, ext) => {
                 write_tags(f, tags.iter())?;
 
@@ -787,6 +808,15 @@ impl Type {
                         stack.push(ext);
                     }
                 }
+                Tuple(elems, ext) => {
+                    for (_, x) in elems.iter_mut() {
+                        stack.push(x);
+                    }
+
+                    if let TypeExtension::Open(ext) = ext {
+                        stack.push(ext);
+                    }
+                }
                 Type::DelayedAlias(AliasCommon {
                     type_arguments,
                     lambda_set_variables,
@@ -909,6 +939,14 @@ impl Type {
                         stack.push(ext);
                     }
                 }
+                Tuple(elems, ext) => {
+                    for (_, x) in elems.iter_mut() {
+                        stack.push(x);
+                    }
+                    if let TypeExtension::Open(ext) = ext {
+                        stack.push(ext);
+                    }
+                }
                 Type::DelayedAlias(AliasCommon {
                     type_arguments,
                     lambda_set_variables,
@@ -1015,6 +1053,16 @@ impl Type {
                     TypeExtension::Closed => Ok(()),
                 }
             }
+            Tuple(elems, ext) => {
+                for (_, x) in elems.iter_mut() {
+                    x.substitute_alias(rep_symbol, rep_args, actual)?;
+                }
+
+                match ext {
+                    TypeExtension::Open(ext) => ext.substitute_alias(rep_symbol, rep_args, actual),
+                    TypeExtension::Closed => Ok(()),
+                }
+            }
             DelayedAlias(AliasCommon {
                 type_arguments,
                 lambda_set_variables: _no_aliases_in_lambda_sets,
@@ -1096,6 +1144,10 @@ impl Type {
                 Self::contains_symbol_ext(ext, rep_symbol)
                     || fields.values().any(|arg| arg.contains_symbol(rep_symbol))
             }
+            Tuple(elems, ext) => {
+                Self::contains_symbol_ext(ext, rep_symbol)
+                    || elems.values().any(|arg| arg.contains_symbol(rep_symbol))
+            }
             DelayedAlias(AliasCommon {
                 symbol,
                 type_arguments,
@@ -1166,6 +1218,12 @@ impl Type {
                         .values()
                         .any(|arg| arg.contains_variable(rep_variable))
             }
+            Tuple(elems, ext) => {
+                Self::contains_variable_ext(ext, rep_variable)
+                    || elems
+                        .values()
+                        .any(|arg| arg.contains_variable(rep_variable))
+            }
             DelayedAlias(AliasCommon { .. }) => {
                 todo!()
             }
@@ -1250,6 +1308,15 @@ impl Type {
                     ext.instantiate_aliases(region, aliases, var_store, new_lambda_set_variables);
                 }
             }
+            Tuple(elems, ext) => {
+                for (_, x) in elems.iter_mut() {
+                    x.instantiate_aliases(region, aliases, var_store, new_lambda_set_variables);
+                }
+
+                if let TypeExtension::Open(ext) = ext {
+                    ext.instantiate_aliases(region, aliases, var_store, new_lambda_set_variables);
+                }
+            }
             DelayedAlias(AliasCommon {
                 type_arguments,
                 lambda_set_variables,
@@ -1499,6 +1566,12 @@ impl Type {
                 }
                 TypeExtension::Closed => fields.values().all(|field| field.as_inner().is_narrow()),
             },
+            Type::Tuple(elems, ext) => match ext {
+                TypeExtension::Open(ext) => {
+                    elems.values().all(|elem| elem.is_narrow()) && ext.is_narrow()
+                }
+                TypeExtension::Closed => elems.values().all(|elem| elem.is_narrow()),
+            },
             Type::Function(args, clos, ret) => {
                 args.iter().all(|a| a.is_narrow()) && clos.is_narrow() && ret.is_narrow()
             }
@@ -1546,6 +1619,10 @@ fn symbols_help(initial: &Type) -> Vec<Symbol> {
                 stack.extend(ext);
                 stack.extend(fields.values().map(|field| field.as_inner()));
             }
+            Tuple(elems, ext) => {
+                stack.extend(ext);
+                stack.extend(elems.values());
+            }
             DelayedAlias(AliasCommon {
                 symbol,
                 type_arguments,
@@ -1626,6 +1703,15 @@ fn variables_help(tipe: &Type, accum: &mut ImSet<Variable>) {
                 variables_help(ext, accum);
             }
         }
+        Tuple(elems, ext) => {
+            for (_, elem) in elems.iter() {
+                variables_help(elem, accum);
+            }
+
+            if let TypeExtension::Open(ext) = ext {
+                variables_help(ext, accum);
+            }
+        }
         ClosureTag {
             name: _,
             captures,
@@ -1768,6 +1854,15 @@ fn variables_help_detailed(tipe: &Type, accum: &mut VariableDetail) {
                 variables_help_detailed(ext, accum);
             }
         }
+        Tuple(elems, ext) => {
+            for (_, elem) in elems.iter() {
+                variables_help_detailed(elem, accum);
+            }
+
+            if let TypeExtension::Open(ext) = ext {
+                variables_help_detailed(ext, accum);
+            }
+        }
         ClosureTag {
             name: _,
             captures,
@@ -1870,6 +1965,13 @@ pub struct RecordStructure {
     pub ext: Variable,
 }
 
+#[derive(Debug)]
+pub struct TupleStructure {
+    /// Invariant: these should be sorted by index!
+    pub elems: Vec<(usize, Variable)>,
+    pub ext: Variable,
+}
+
 #[derive(Debug)]
 pub struct TagUnionStructure<'a> {
     /// Invariant: these should be sorted!
@@ -1893,6 +1995,7 @@ pub enum PReason {
     },
     PatternGuard,
     OptionalField,
+    ListElem,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -2018,6 +2121,10 @@ pub enum Category {
     Access(Lowercase),
     DefaultValue(Lowercase), // for setting optional fields
 
+    // tuples
+    Tuple,
+    TupleAccess(usize),
+
     AbilityMemberSpecialization(Symbol),
 
     Expect,
@@ -2028,6 +2135,8 @@ pub enum Category {
 pub enum PatternCategory {
     Record,
     EmptyRecord,
+    Tuple,
+    List,
     PatternGuard,
     PatternDefault,
     Set,
@@ -2146,6 +2255,23 @@ pub enum Problem {
     HasClauseIsNotAbility(Region),
 }
 
+impl Problem {
+    pub fn region(&self) -> Option<Region> {
+        use Problem::*;
+
+        match self {
+            CircularType(_, _, region)
+            | CyclicAlias(_, region, _)
+            | BadTypeArguments { region, .. }
+            | HasClauseIsNotAbility(region) => Some(*region),
+            Shadowed(_, shadow) => Some(shadow.region),
+            CanonicalizationProblem | UnrecognizedIdent(_) | InvalidModule | SolvedTypeError => {
+                None
+            }
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub enum Mismatch {
     TypeMismatch,
@@ -2168,6 +2294,7 @@ pub enum ErrorType {
     FlexAbleVar(Lowercase, Symbol),
     RigidAbleVar(Lowercase, Symbol),
     Record(SendMap<Lowercase, RecordField<ErrorType>>, TypeExt),
+    Tuple(Vec<(usize, ErrorType)>, TypeExt),
     TagUnion(SendMap<TagName, Vec<ErrorType>>, TypeExt),
     RecursiveTagUnion(Box<ErrorType>, SendMap<TagName, Vec<ErrorType>>, TypeExt),
     Function(Vec<ErrorType>, Box<ErrorType>, Box<ErrorType>),
@@ -2206,6 +2333,10 @@ impl ErrorType {
                     .for_each(|(_, t)| t.as_inner().add_names(taken));
                 ext.add_names(taken);
             }
+            Tuple(elems, ext) => {
+                elems.iter().for_each(|(_, t)| t.add_names(taken));
+                ext.add_names(taken);
+            }
             TagUnion(tags, ext) => {
                 tags.iter()
                     .for_each(|(_, ts)| ts.iter().for_each(|t| t.add_names(taken)));
@@ -2357,6 +2488,22 @@ fn write_error_type_help(
             buf.push('}');
             write_type_ext(ext, buf);
         }
+        Tuple(elems, ext) => {
+            buf.push('(');
+
+            let mut it = elems.into_iter().peekable();
+
+            while let Some((_, elem)) = it.next() {
+                write_error_type_help(home, interns, elem, buf, Parens::Unnecessary);
+
+                if it.peek().is_some() {
+                    buf.push_str(", ");
+                }
+            }
+
+            buf.push(')');
+            write_type_ext(ext, buf);
+        }
 
         other => todo!("cannot format {:?} yet", other),
     }
@@ -2511,6 +2658,22 @@ fn write_debug_error_type_help(error_type: ErrorType, buf: &mut String, parens:
             buf.push('}');
             write_type_ext(ext, buf);
         }
+        Tuple(elems, ext) => {
+            buf.push('(');
+
+            let mut it = elems.into_iter().peekable();
+
+            while let Some((_, elem)) = it.next() {
+                write_debug_error_type_help(elem, buf, Parens::Unnecessary);
+
+                if it.peek().is_some() {
+                    buf.push_str(", ");
+                }
+            }
+
+            buf.push(')');
+            write_type_ext(ext, buf);
+        }
         TagUnion(tags, ext) => {
             buf.push('[');
 
@@ -2737,6 +2900,68 @@ pub fn gather_fields(
     })
 }
 
+#[derive(Debug, Copy, Clone)]
+pub struct TupleElemsError;
+
+pub fn gather_tuple_elems_unsorted_iter(
+    subs: &Subs,
+    other_elems: TupleElems,
+    mut var: Variable,
+) -> Result<(impl Iterator<Item = (usize, Variable)> + '_, Variable), TupleElemsError> {
+    use crate::subs::Content::*;
+    use crate::subs::FlatType::*;
+
+    let mut stack = vec![other_elems];
+
+    loop {
+        match subs.get_content_without_compacting(var) {
+            Structure(Tuple(sub_elems, sub_ext)) => {
+                stack.push(*sub_elems);
+
+                if var == Variable::EMPTY_TUPLE {
+                    break;
+                } else {
+                    var = *sub_ext;
+                }
+            }
+
+            Alias(_, _, actual_var, _) => {
+                var = *actual_var;
+            }
+
+            Structure(EmptyTuple) => break,
+            FlexVar(_) | FlexAbleVar(..) => break,
+            RigidVar(_) | RigidAbleVar(..) => break,
+
+            // Stop on errors in the tuple
+            Error => break,
+
+            _ => return Err(TupleElemsError),
+        }
+    }
+
+    let it = stack
+        .into_iter()
+        .flat_map(|elems| elems.iter_all())
+        .map(move |(i1, i2)| (subs[i1], subs[i2]));
+
+    Ok((it, var))
+}
+
+pub fn gather_tuple_elems(
+    subs: &Subs,
+    other_elems: TupleElems,
+    var: Variable,
+) -> Result<TupleStructure, TupleElemsError> {
+    let (it, ext) = gather_tuple_elems_unsorted_iter(subs, other_elems, var)?;
+
+    let mut result: Vec<_> = it.collect();
+
+    result.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    Ok(TupleStructure { elems: result, ext })
+}
+
 #[derive(Debug)]
 pub enum GatherTagsError {
     NotATagUnion(Variable),
@@ -2898,6 +3123,12 @@ fn instantiate_lambda_sets_as_unspecialized(
                     stack.push(x.as_inner_mut());
                 }
             }
+            Type::Tuple(elems, ext) => {
+                stack.extend(ext.iter_mut());
+                for (_, x) in elems.iter_mut() {
+                    stack.push(x);
+                }
+            }
             Type::TagUnion(tags, ext) | Type::RecursiveTagUnion(_, tags, ext) => {
                 stack.extend(ext.iter_mut());
                 for (_, ts) in tags {
@@ -450,6 +450,7 @@ pub fn load_modules_for_files(filenames: Vec<PathBuf>) -> Vec<LoadedModule> {
             render: roc_reporting::report::RenderTarget::ColorTerminal,
             threading: Threading::AllAvailable,
             exec_mode: ExecutionMode::Check,
+            cache_dir: None,
         };
         match roc_load::load_and_typecheck(&arena, filename, Default::default(), load_config) {
             Ok(loaded) => modules.push(loaded),
@@ -0,0 +1,892 @@
+use crate::types::{RocNum, RocTagUnion, RocType, TypeId, Types};
+use indexmap::IndexMap;
+use roc_mono::layout::round_up_to_alignment;
+use roc_target::{Architecture, TargetInfo};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+pub static HEADER: &[u8] = include_bytes!("../templates/header.h");
+const INDENT: &str = "    ";
+
+/// The declarations for each type, keyed by the name of the type, along with the targets
+/// each version of the declaration applies to.
+///
+/// Unlike Rust, C requires a type to be declared before it gets used. Types are added in
+/// dependency order, so keeping the keys in insertion order takes care of that.
+type Decls = IndexMap<String, IndexMap<String, Vec<TargetInfo>>>;
+
+/// Add the given declaration body, along with the architecture, to the Decls.
+fn add_decl(decls: &mut Decls, key: String, target_info: TargetInfo, body: String) {
+    let targets = decls.entry(key).or_default().entry(body).or_default();
+
+    // The same type can come up more than once, e.g. two fields which are both `Result Str U8`
+    if !targets.contains(&target_info) {
+        targets.push(target_info);
+    }
+}
+
+pub fn emit(types_and_targets: &[(Types, TargetInfo)]) -> String {
+    let mut buf = String::new();
+    let mut decls: Decls = IndexMap::default();
+
+    // A recursive tag union is a pointer to its payloads, so it can be declared up front.
+    // That way, the payloads can refer to the tag union they're a part of. The union of
+    // the payloads can only be declared once they have all been declared, so that goes last.
+    for (types, target_info) in types_and_targets {
+        for id in types.sorted_ids() {
+            if let RocType::TagUnion(tag_union) = types.get_type(id) {
+                add_recursive_pointer(*target_info, tag_union, &mut decls);
+            }
+        }
+    }
+
+    for (types, target_info) in types_and_targets {
+        let mut added = HashSet::default();
+
+        for id in types.sorted_ids() {
+            add_type_after_deps(*target_info, id, types, &mut decls, &mut added);
+        }
+    }
+
+    for (types, target_info) in types_and_targets {
+        for id in types.sorted_ids() {
+            if is_recursive_union(types.get_type(id)) {
+                add_type(*target_info, id, types, &mut decls);
+            }
+        }
+    }
+
+    for (_, bodies) in decls {
+        for (body, targets) in bodies {
+            // We should never have a decl recorded with 0 targets!
+            debug_assert_ne!(targets.len(), 0);
+
+            let condition = targets
+                .iter()
+                .map(|target_info| arch_to_macro(target_info.architecture))
+                .collect::<Vec<_>>()
+                .join(" || ");
+
+            write!(buf, "\n#if {condition}\n\n{body}\n\n#endif\n").unwrap();
+        }
+    }
+
+    buf
+}
+
+/// Add the given type, after the types it needs declared before it. That excludes recursive
+/// tag unions, whose pointers are declared up front.
+fn add_type_after_deps(
+    target_info: TargetInfo,
+    id: TypeId,
+    types: &Types,
+    decls: &mut Decls,
+    added: &mut HashSet<TypeId>,
+) {
+    if is_recursive_union(types.get_type(id)) || !added.insert(id) {
+        return;
+    }
+
+    let deps: Vec<TypeId> = match types.get_type(id) {
+        RocType::Struct { fields, .. } => fields.iter().map(|(_, type_id)| *type_id).collect(),
+        RocType::TagUnionPayload { fields, .. } => {
+            fields.iter().map(|(_, type_id)| *type_id).collect()
+        }
+        RocType::TagUnion(RocTagUnion::NonRecursive { tags, .. }) => tags
+            .iter()
+            .filter_map(|(_, opt_payload)| *opt_payload)
+            .collect(),
+        RocType::TagUnion(RocTagUnion::SingleTagStruct { payload_fields, .. }) => {
+            payload_fields.clone()
+        }
+        RocType::RocResult(ok_id, err_id) => vec![*ok_id, *err_id],
+        _ => Vec::new(),
+    };
+
+    for dep in deps {
+        add_type_after_deps(target_info, dep, types, decls, added);
+    }
+
+    add_type(target_info, id, types, decls);
+}
+
+fn add_type(target_info: TargetInfo, id: TypeId, types: &Types, decls: &mut Decls) {
+    match types.get_type(id) {
+        RocType::Struct { name, fields } => {
+            let fields = fields
+                .iter()
+                .map(|(label, type_id)| (escape_kw(label.clone()), *type_id));
+
+            add_struct(name, target_info, fields, id, types, decls)
+        }
+        RocType::TagUnionPayload { name, fields } => {
+            // Tag union payloads have numbered fields, so we prefix them
+            // with an "f" because C doesn't allow struct fields to be numbers.
+            let fields = fields
+                .iter()
+                .map(|(index, type_id)| (format!("f{index}"), *type_id));
+
+            add_struct(name, target_info, fields, id, types, decls)
+        }
+        RocType::TagUnion(tag_union) => {
+            match tag_union {
+                RocTagUnion::Enumeration { tags, name, size } => {
+                    add_enumeration(name, target_info, tags.iter(), *size, decls)
+                }
+                RocTagUnion::NonRecursive {
+                    tags,
+                    name,
+                    discriminant_size,
+                    discriminant_offset,
+                } => {
+                    // Empty tag unions can never come up at runtime,
+                    // and so don't need declared types.
+                    if !tags.is_empty() {
+                        add_non_recursive(
+                            name,
+                            target_info,
+                            id,
+                            tags,
+                            *discriminant_size,
+                            *discriminant_offset,
+                            types,
+                            decls,
+                        );
+                    }
+                }
+                RocTagUnion::Recursive {
+                    tags,
+                    name,
+                    discriminant_size,
+                    discriminant_offset,
+                } => {
+                    // Empty tag unions can never come up at runtime,
+                    // and so don't need declared types.
+                    if !tags.is_empty() {
+                        add_recursive(
+                            name,
+                            target_info,
+                            id,
+                            tags,
+                            None,
+                            *discriminant_size,
+                            *discriminant_offset,
+                            types,
+                            decls,
+                        );
+                    }
+                }
+                RocTagUnion::NullableWrapped {
+                    name,
+                    index_of_null_tag,
+                    tags,
+                    discriminant_size,
+                    discriminant_offset,
+                } => add_recursive(
+                    name,
+                    target_info,
+                    id,
+                    tags,
+                    Some(*index_of_null_tag as usize),
+                    *discriminant_size,
+                    *discriminant_offset,
+                    types,
+                    decls,
+                ),
+                RocTagUnion::NullableUnwrapped {
+                    name,
+                    null_tag,
+                    non_null_tag,
+                    non_null_payload,
+                    null_represents_first_tag: _,
+                } => add_nullable_unwrapped(
+                    name,
+                    target_info,
+                    id,
+                    null_tag,
+                    non_null_tag,
+                    *non_null_payload,
+                    types,
+                    decls,
+                ),
+                RocTagUnion::SingleTagStruct {
+                    name,
+                    tag_name,
+                    payload_fields,
+                } => add_single_tag_struct(
+                    name,
+                    target_info,
+                    id,
+                    tag_name,
+                    payload_fields,
+                    types,
+                    decls,
+                ),
+                RocTagUnion::NonNullableUnwrapped {
+                    name,
+                    tag_name,
+                    payload,
+                } => add_recursive(
+                    name,
+                    target_info,
+                    id,
+                    &[(tag_name.clone(), Some(*payload))],
+                    None,
+                    0,
+                    0,
+                    types,
+                    decls,
+                ),
+            }
+        }
+        RocType::RocResult(ok_id, err_id) => {
+            add_result(target_info, id, *ok_id, *err_id, types, decls)
+        }
+        // These types are declared in the header, if they need declaring at all.
+        RocType::Unit
+        | RocType::EmptyTagUnion
+        | RocType::Num(_)
+        | RocType::Bool
+        | RocType::RocStr
+        | RocType::RocDict(_, _)
+        | RocType::RocSet(_)
+        | RocType::RocList(_)
+        | RocType::RocBox(_) => {}
+        RocType::RecursivePointer { .. } => {
+            // This is recursively pointing to a type that should already have been added,
+            // so no extra work needs to happen.
+        }
+        RocType::Function { .. } => {
+            // TODO actually generate glue functions!
+        }
+    }
+}
+
+fn is_recursive_union(roc_type: &RocType) -> bool {
+    matches!(
+        roc_type,
+        RocType::TagUnion(
+            RocTagUnion::Recursive { .. }
+                | RocTagUnion::NullableWrapped { .. }
+                | RocTagUnion::NullableUnwrapped { .. }
+                | RocTagUnion::NonNullableUnwrapped { .. }
+        )
+    )
+}
+
+/// The struct for a recursive tag union, which holds a pointer to its payloads
+fn add_recursive_pointer(target_info: TargetInfo, tag_union: &RocTagUnion, decls: &mut Decls) {
+    match tag_union {
+        RocTagUnion::Recursive { tags, .. } if tags.is_empty() => {}
+        RocTagUnion::Recursive { name, .. }
+        | RocTagUnion::NullableWrapped { name, .. }
+        | RocTagUnion::NullableUnwrapped { name, .. }
+        | RocTagUnion::NonNullableUnwrapped { name, .. } => {
+            let name = escape_kw(name.to_string());
+
+            add_decl(
+                decls,
+                format!("{name} pointer"),
+                target_info,
+                format!(
+                    r#"union payload_{name};
+
+typedef struct {name} {{
+    union payload_{name} *pointer;
+}} {name};"#
+                ),
+            );
+        }
+        RocTagUnion::Enumeration { .. }
+        | RocTagUnion::NonRecursive { .. }
+        | RocTagUnion::SingleTagStruct { .. } => {}
+    }
+}
+
+fn add_struct<I: Iterator<Item = (String, TypeId)>>(
+    name: &str,
+    target_info: TargetInfo,
+    fields: I,
+    struct_id: TypeId,
+    types: &Types,
+    decls: &mut Decls,
+) {
+    let name = escape_kw(name.to_string());
+    let fields: Vec<_> = fields
+        .filter(|(_, type_id)| !is_zero_sized(*type_id, types))
+        .collect();
+
+    // C doesn't allow empty structs, and this has no runtime representation anyway.
+    if fields.is_empty() {
+        return;
+    }
+
+    let mut buf = format!("typedef struct {name} {{\n");
+
+    for (label, type_id) in fields {
+        writeln!(buf, "{INDENT}{} {label};", type_name(type_id, types)).unwrap();
+    }
+
+    write!(buf, "}} {name};").unwrap();
+    write_layout_asserts(&name, struct_id, types, &mut buf);
+
+    add_decl(decls, name, target_info, buf);
+}
+
+fn add_enumeration<I: ExactSizeIterator<Item = S>, S: AsRef<str>>(
+    name: &str,
+    target_info: TargetInfo,
+    tags: I,
+    tag_bytes: u32,
+    decls: &mut Decls,
+) {
+    let name = escape_kw(name.to_string());
+    let repr_bits = tag_bytes * 8;
+
+    // A C enum has the size of an int, so the values are named by the enum, and the type
+    // which holds them has the size Roc uses, e.g.
+    //
+    // enum MyEnum {
+    //     MyEnum_Bar = 0,
+    //     MyEnum_Foo = 1,
+    // };
+    //
+    // typedef uint8_t MyEnum;
+    let mut buf = format!("enum {name} {{\n");
+
+    for (index, tag_name) in tags.enumerate() {
+        writeln!(buf, "{INDENT}{name}_{} = {index},", tag_name.as_ref()).unwrap();
+    }
+
+    write!(buf, "}};\n\ntypedef uint{repr_bits}_t {name};").unwrap();
+
+    add_decl(decls, name, target_info, buf);
+}
+
+fn add_discriminant<'a, I: ExactSizeIterator<Item = &'a str>>(
+    name: &str,
+    target_info: TargetInfo,
+    tag_names: I,
+    size: u32,
+    decls: &mut Decls,
+) -> String {
+    let discriminant_name = format!("discriminant_{name}");
+
+    add_enumeration(&discriminant_name, target_info, tag_names, size, decls);
+
+    discriminant_name
+}
+
+/// The members of the union of a tag union's payloads. Tags without a payload, or with a
+/// zero-sized one, don't need a member.
+fn payload_members(tags: &[(String, Option<TypeId>)], types: &Types) -> String {
+    let mut buf = String::new();
+
+    for (tag_name, opt_payload_id) in tags {
+        if let Some(payload_id) = opt_payload_id {
+            if !is_zero_sized(*payload_id, types) {
+                let payload_type = type_name(*payload_id, types);
+
+                writeln!(buf, "{INDENT}{payload_type} {tag_name};").unwrap();
+            }
+        }
+    }
+
+    buf
+}
+
+/// The parameter list and the value of the payload for a tag's constructor function
+fn constructor_args(opt_payload_id: Option<TypeId>, types: &Types) -> Option<String> {
+    match opt_payload_id {
+        Some(payload_id) if !is_zero_sized(payload_id, types) => {
+            Some(format!("{} payload", type_name(payload_id, types)))
+        }
+        _ => None,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn add_non_recursive(
+    name: &str,
+    target_info: TargetInfo,
+    type_id: TypeId,
+    tags: &[(String, Option<TypeId>)],
+    discriminant_size: u32,
+    discriminant_offset: u32,
+    types: &Types,
+    decls: &mut Decls,
+) {
+    let name = escape_kw(name.to_string());
+    let discriminant_name = add_discriminant(
+        &name,
+        target_info,
+        tags.iter().map(|(tag_name, _)| tag_name.as_str()),
+        discriminant_size,
+        decls,
+    );
+    let members = payload_members(tags, types);
+    let mut buf = String::new();
+
+    // The discriminant goes right after the union of the payloads, e.g.
+    //
+    // union payload_MyTagUnion {
+    //     RocStr Foo;
+    //     uint8_t Bar;
+    // };
+    //
+    // typedef struct MyTagUnion {
+    //     union payload_MyTagUnion payload;
+    //     discriminant_MyTagUnion discriminant;
+    // } MyTagUnion;
+    if members.is_empty() {
+        writeln!(buf, "typedef struct {name} {{").unwrap();
+    } else {
+        write!(
+            buf,
+            "union payload_{name} {{\n{members}}};\n\ntypedef struct {name} {{\n{INDENT}union payload_{name} payload;\n"
+        )
+        .unwrap();
+    }
+
+    write!(buf, "{INDENT}{discriminant_name} discriminant;\n}} {name};").unwrap();
+
+    write_layout_asserts(&name, type_id, types, &mut buf);
+
+    write!(
+        buf,
+        "\n_Static_assert(offsetof({name}, discriminant) == {discriminant_offset}, \"{name} should have its discriminant at offset {discriminant_offset}\");"
+    )
+    .unwrap();
+
+    write!(
+        buf,
+        r#"
+
+static inline {discriminant_name} {name}_discriminant({name} tag_union) {{
+    return tag_union.discriminant;
+}}"#
+    )
+    .unwrap();
+
+    for (tag_name, opt_payload_id) in tags {
+        // Add a convenience constructor function for each tag, e.g.
+        //
+        // static inline MyTagUnion MyTagUnion_new_Foo(RocStr payload) {
+        //     return (MyTagUnion){ .payload = { .Foo = payload }, .discriminant = discriminant_MyTagUnion_Foo };
+        // }
+        let (args, payload) = match constructor_args(*opt_payload_id, types) {
+            Some(args) => (args, format!(".payload = {{ .{tag_name} = payload }}, ")),
+            None => ("void".to_string(), String::new()),
+        };
+
+        write!(
+            buf,
+            r#"
+
+static inline {name} {name}_new_{tag_name}({args}) {{
+    return ({name}){{ {payload}.discriminant = {discriminant_name}_{tag_name} }};
+}}"#
+        )
+        .unwrap();
+    }
+
+    add_decl(decls, name, target_info, buf);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn add_recursive(
+    name: &str,
+    target_info: TargetInfo,
+    type_id: TypeId,
+    tags: &[(String, Option<TypeId>)],
+    null_tag_index: Option<usize>, // used only in the nullable-wrapped case
+    discriminant_size: u32,
+    discriminant_offset: u32,
+    types: &Types,
+    decls: &mut Decls,
+) {
+    let name = escape_kw(name.to_string());
+
+    // We should never be attempting to generate glue for empty tag unions;
+    // RocType should not have let this happen.
+    debug_assert_ne!(tags.len(), 0);
+
+    let discriminant_name = if discriminant_size > 0 {
+        add_discriminant(
+            &name,
+            target_info,
+            tags.iter().map(|(tag_name, _)| tag_name.as_str()),
+            discriminant_size,
+            decls,
+        )
+    } else {
+        // If there's no discriminant, use an empty string for the name.
+        String::new()
+    };
+
+    let union_name = format!("union payload_{name}");
+    let mut buf = format!("{union_name} {{\n{}}};", payload_members(tags, types));
+
+    write_layout_asserts(&name, type_id, types, &mut buf);
+
+    // When there are few enough tags, the discriminant is stored in the unused bits at the end
+    // of the pointer. Otherwise, it's stored after the payload in the allocation.
+    let pointer_tagged = tags.len() <= max_pointer_tagged_variants(target_info.architecture);
+    let bitmask = if pointer_tagged && discriminant_size > 0 {
+        tagged_pointer_bitmask(target_info.architecture)
+    } else {
+        0
+    };
+    let alloc_size = if pointer_tagged || discriminant_size == 0 {
+        format!("sizeof({union_name})")
+    } else {
+        format!("{discriminant_offset} + sizeof({discriminant_name})")
+    };
+
+    write!(
+        buf,
+        r#"
+
+static inline {union_name} *{name}_union_pointer({name} tag_union) {{
+    return ({union_name} *)((uintptr_t)tag_union.pointer & ~(uintptr_t){bitmask:#x});
+}}"#
+    )
+    .unwrap();
+
+    if discriminant_size > 0 {
+        let null_check = match null_tag_index {
+            Some(index) => format!(
+                "if (tag_union.pointer == NULL) {{\n{INDENT}{INDENT}return {discriminant_name}_{};\n{INDENT}}}\n\n{INDENT}",
+                tags[index].0
+            ),
+            None => String::new(),
+        };
+        let discriminant = if pointer_tagged {
+            format!("(uintptr_t)tag_union.pointer & {bitmask:#x}")
+        } else {
+            format!("*({discriminant_name} *)((char *){name}_union_pointer(tag_union) + {discriminant_offset})")
+        };
+
+        write!(
+            buf,
+            r#"
+
+static inline {discriminant_name} {name}_discriminant({name} tag_union) {{
+    {null_check}return {discriminant};
+}}"#
+        )
+        .unwrap();
+    }
+
+    for (tag_index, (tag_name, opt_payload_id)) in tags.iter().enumerate() {
+        if Some(tag_index) == null_tag_index {
+            // The null tag index only occurs for nullable-wrapped tag unions,
+            // and it always has no payload.
+            write!(
+                buf,
+                r#"
+
+static inline {name} {name}_new_{tag_name}(void) {{
+    return ({name}){{ NULL }};
+}}"#
+            )
+            .unwrap();
+
+            continue;
+        }
+
+        let (args, set_payload) = match constructor_args(*opt_payload_id, types) {
+            Some(args) => (args, format!("{INDENT}pointer->{tag_name} = payload;\n")),
+            None => ("void".to_string(), String::new()),
+        };
+        let (set_discriminant, pointer) = if discriminant_size == 0 {
+            (String::new(), "pointer".to_string())
+        } else if pointer_tagged {
+            (
+                String::new(),
+                format!("({union_name} *)((uintptr_t)pointer | {discriminant_name}_{tag_name})"),
+            )
+        } else {
+            (
+                format!("{INDENT}*({discriminant_name} *)((char *)pointer + {discriminant_offset}) = {discriminant_name}_{tag_name};\n"),
+                "pointer".to_string(),
+            )
+        };
+
+        write!(
+            buf,
+            r#"
+
+static inline {name} {name}_new_{tag_name}({args}) {{
+    {union_name} *pointer = roc_alloc_refcounted({alloc_size}, _Alignof({union_name}));
+
+{set_payload}{set_discriminant}
+    return ({name}){{ {pointer} }};
+}}"#
+        )
+        .unwrap();
+    }
+
+    add_decl(decls, name, target_info, buf);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn add_nullable_unwrapped(
+    name: &str,
+    target_info: TargetInfo,
+    type_id: TypeId,
+    null_tag: &str,
+    non_null_tag: &str,
+    non_null_payload: TypeId,
+    types: &Types,
+    decls: &mut Decls,
+) {
+    let name = escape_kw(name.to_string());
+    let mut tag_names = vec![null_tag, non_null_tag];
+
+    tag_names.sort_unstable();
+
+    let discriminant_name = add_discriminant(&name, target_info, tag_names.into_iter(), 1, decls);
+    let union_name = format!("union payload_{name}");
+    let payload_type = type_name(non_null_payload, types);
+    let mut buf = format!("{union_name} {{\n{INDENT}{payload_type} {non_null_tag};\n}};");
+
+    write_layout_asserts(&name, type_id, types, &mut buf);
+
+    write!(
+        buf,
+        r#"
+
+static inline {union_name} *{name}_union_pointer({name} tag_union) {{
+    return tag_union.pointer;
+}}
+
+static inline {discriminant_name} {name}_discriminant({name} tag_union) {{
+    if (tag_union.pointer == NULL) {{
+        return {discriminant_name}_{null_tag};
+    }} else {{
+        return {discriminant_name}_{non_null_tag};
+    }}
+}}
+
+static inline {name} {name}_new_{null_tag}(void) {{
+    return ({name}){{ NULL }};
+}}
+
+static inline {name} {name}_new_{non_null_tag}({payload_type} payload) {{
+    {union_name} *pointer = roc_alloc_refcounted(sizeof({union_name}), _Alignof({union_name}));
+
+    pointer->{non_null_tag} = payload;
+
+    return ({name}){{ pointer }};
+}}"#
+    )
+    .unwrap();
+
+    add_decl(decls, name, target_info, buf);
+}
+
+fn add_single_tag_struct(
+    name: &str,
+    target_info: TargetInfo,
+    type_id: TypeId,
+    tag_name: &str,
+    payload_fields: &[TypeId],
+    types: &Types,
+    decls: &mut Decls,
+) {
+    let name = escape_kw(name.to_string());
+    let fields: Vec<(String, String)> = payload_fields
+        .iter()
+        .enumerate()
+        .filter(|(_, type_id)| !is_zero_sized(**type_id, types))
+        .map(|(index, type_id)| (format!("f{index}"), type_name(*type_id, types)))
+        .collect();
+
+    // A single tag with no payload has no runtime representation,
+    // and C doesn't allow empty structs anyway.
+    if fields.is_empty() {
+        return;
+    }
+
+    // Store single-tag unions as structs rather than unions,
+    // because they have only one alternative.
+    let mut buf = format!("typedef struct {name} {{\n");
+
+    for (label, field_type) in fields.iter() {
+        writeln!(buf, "{INDENT}{field_type} {label};").unwrap();
+    }
+
+    write!(buf, "}} {name};").unwrap();
+    write_layout_asserts(&name, type_id, types, &mut buf);
+
+    let args = fields
+        .iter()
+        .map(|(label, field_type)| format!("{field_type} {label}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let labels = fields
+        .iter()
+        .map(|(label, _)| label.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    write!(
+        buf,
+        r#"
+
+static inline {name} {name}_new_{tag_name}({args}) {{
+    return ({name}){{ {labels} }};
+}}"#
+    )
+    .unwrap();
+
+    add_decl(decls, name, target_info, buf);
+}
+
+fn add_result(
+    target_info: TargetInfo,
+    type_id: TypeId,
+    ok_id: TypeId,
+    err_id: TypeId,
+    types: &Types,
+    decls: &mut Decls,
+) {
+    // The discriminant goes after the larger of the two payloads.
+    let payload_align = types.align(ok_id).max(types.align(err_id));
+    let payload_size = types
+        .size_rounded_to_alignment(ok_id)
+        .max(types.size_rounded_to_alignment(err_id));
+    let discriminant_offset = round_up_to_alignment(payload_size, payload_align);
+
+    // A Result is a non-recursive tag union, with the tags in alphabetical order.
+    add_non_recursive(
+        &result_name(ok_id, err_id, types),
+        target_info,
+        type_id,
+        &[
+            ("Err".to_string(), Some(err_id)),
+            ("Ok".to_string(), Some(ok_id)),
+        ],
+        1,
+        discriminant_offset,
+        types,
+        decls,
+    );
+}
+
+/// C has no generics, so each Result type needs a struct of its own, e.g. RocResult_RocStr_uint8_t
+fn result_name(ok_id: TypeId, err_id: TypeId, types: &Types) -> String {
+    let to_ident = |id| {
+        type_name(id, types)
+            .chars()
+            .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+            .collect::<String>()
+    };
+
+    format!("RocResult_{}_{}", to_ident(ok_id), to_ident(err_id))
+}
+
+/// Static assertions that the C compiler lays the type out the same way Roc does
+fn write_layout_asserts(name: &str, type_id: TypeId, types: &Types, buf: &mut String) {
+    let size = types.size_rounded_to_alignment(type_id);
+    let align = types.align(type_id);
+
+    write!(
+        buf,
+        r#"
+
+_Static_assert(sizeof({name}) == {size}, "{name} should have a size of {size}");
+_Static_assert(_Alignof({name}) == {align}, "{name} should have an alignment of {align}");"#
+    )
+    .unwrap();
+}
+
+fn is_zero_sized(type_id: TypeId, types: &Types) -> bool {
+    types.size_ignoring_alignment(type_id) == 0
+}
+
+fn type_name(id: TypeId, types: &Types) -> String {
+    match types.get_type(id) {
+        RocType::Unit | RocType::EmptyTagUnion => "void".to_string(),
+        RocType::RocStr => "RocStr".to_string(),
+        RocType::Bool => "bool".to_string(),
+        RocType::Num(RocNum::U8) => "uint8_t".to_string(),
+        RocType::Num(RocNum::U16) => "uint16_t".to_string(),
+        RocType::Num(RocNum::U32) => "uint32_t".to_string(),
+        RocType::Num(RocNum::U64) => "uint64_t".to_string(),
+        RocType::Num(RocNum::U128) => "RocU128".to_string(),
+        RocType::Num(RocNum::I8) => "int8_t".to_string(),
+        RocType::Num(RocNum::I16) => "int16_t".to_string(),
+        RocType::Num(RocNum::I32) => "int32_t".to_string(),
+        RocType::Num(RocNum::I64) => "int64_t".to_string(),
+        RocType::Num(RocNum::I128) => "RocI128".to_string(),
+        RocType::Num(RocNum::F32) => "float".to_string(),
+        RocType::Num(RocNum::F64) => "double".to_string(),
+        RocType::Num(RocNum::F128) => "long double".to_string(),
+        RocType::Num(RocNum::Dec) => "RocDec".to_string(),
+        // C has no generics, so the element types of these aren't part of the type.
+        RocType::RocDict(_, _) => "RocDict".to_string(),
+        RocType::RocSet(_) => "RocSet".to_string(),
+        RocType::RocList(_) => "RocList".to_string(),
+        RocType::RocBox(_) => "RocBox".to_string(),
+        RocType::RocResult(ok_id, err_id) => result_name(*ok_id, *err_id, types),
+        RocType::Struct { name, .. }
+        | RocType::TagUnionPayload { name, .. }
+        | RocType::TagUnion(RocTagUnion::NonRecursive { name, .. })
+        | RocType::TagUnion(RocTagUnion::Recursive { name, .. })
+        | RocType::TagUnion(RocTagUnion::Enumeration { name, .. })
+        | RocType::TagUnion(RocTagUnion::NullableWrapped { name, .. })
+        | RocType::TagUnion(RocTagUnion::NullableUnwrapped { name, .. })
+        | RocType::TagUnion(RocTagUnion::NonNullableUnwrapped { name, .. })
+        | RocType::TagUnion(RocTagUnion::SingleTagStruct { name, .. }) => escape_kw(name.clone()),
+        RocType::RecursivePointer(content) => type_name(*content, types),
+        RocType::Function { name, .. } => escape_kw(name.clone()),
+    }
+}
+
+/// The macro the header defines when compiling for the given architecture
+fn arch_to_macro(architecture: Architecture) -> &'static str {
+    match architecture {
+        Architecture::X86_64 => "ROC_ARCH_X86_64",
+        Architecture::X86_32 => "ROC_ARCH_X86",
+        Architecture::Aarch64 => "ROC_ARCH_AARCH64",
+        Architecture::Aarch32 => "ROC_ARCH_ARM",
+        Architecture::Wasm32 => "ROC_ARCH_WASM32",
+    }
+}
+
+fn max_pointer_tagged_variants(architecture: Architecture) -> usize {
+    match architecture {
+        // On a 64-bit system, pointers have 3 bits that are unused, so return 2^3 = 8
+        Architecture::X86_64 | Architecture::Aarch64 => 8,
+        // On a 32-bit system, pointers have 2 bits that are unused, so return 2^2 = 4
+        Architecture::X86_32 | Architecture::Aarch32 | Architecture::Wasm32 => 4,
+    }
+}
+
+fn tagged_pointer_bitmask(architecture: Architecture) -> u8 {
+    match architecture {
+        // On a 64-bit system, pointers have 3 bits that are unused
+        Architecture::X86_64 | Architecture::Aarch64 => 0b0000_0111,
+        // On a 32-bit system, pointers have 2 bits that are unused
+        Architecture::X86_32 | Architecture::Aarch32 | Architecture::Wasm32 => 0b0000_0011,
+    }
+}
+
+// Based on https://en.cppreference.com/w/c/keyword, plus the macros from stdbool.h
+const RESERVED_KEYWORDS: &[&str] = &[
+    "auto", "break", "case", "char", "const", "continue", "default", "do", "double", "else",
+    "enum", "extern", "float", "for", "goto", "if", "inline", "int", "long", "register",
+    "restrict", "return", "short", "signed", "sizeof", "static", "struct", "switch", "typedef",
+    "union", "unsigned", "void", "volatile", "while", "bool", "true", "false",
+];
+
+/// Escape a C reserved keyword, if necessary.
+fn escape_kw(input: String) -> String {
+    if RESERVED_KEYWORDS.contains(&input.as_str()) {
+        format!("{input}_")
+    } else {
+        input
+    }
+}
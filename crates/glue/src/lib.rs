@@ -1,10 +1,12 @@
+pub mod c_glue;
 pub mod enums;
 pub mod load;
 pub mod rust_glue;
 pub mod structs;
 pub mod types;
+pub mod zig_glue;
 
 #[rustfmt::skip]
 pub mod glue;
 
-pub use load::generate;
+pub use load::{generate, Lang};
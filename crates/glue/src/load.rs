@@ -1,10 +1,12 @@
-use crate::rust_glue;
 use crate::types::{Env, Types};
+use crate::{c_glue, rust_glue, zig_glue};
 use bumpalo::Bump;
 use roc_intern::GlobalInterner;
 use roc_load::{ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, Threading};
+use roc_module::symbol::Symbol;
 use roc_reporting::report::RenderTarget;
 use roc_target::{Architecture, OperatingSystem, TargetInfo};
+use roc_types::subs::Variable;
 use std::fs::File;
 use std::io::{self, ErrorKind, Write};
 use std::path::{Path, PathBuf};
@@ -12,7 +14,74 @@ use std::process;
 use strum::IntoEnumIterator;
 use target_lexicon::Triple;
 
-pub fn generate(input_path: &Path, output_path: &Path) -> io::Result<i32> {
+/// The language to generate glue in
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Lang {
+    Rust,
+    C,
+    Zig,
+}
+
+impl Lang {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Lang::Rust => "rust",
+            Lang::C => "c",
+            Lang::Zig => "zig",
+        }
+    }
+
+    /// NOTE keep up to date!
+    pub const OPTIONS: &'static [&'static str] =
+        &[Lang::Rust.as_str(), Lang::C.as_str(), Lang::Zig.as_str()];
+
+    /// The language a glue file is written in, based on its extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("rs") => Some(Lang::Rust),
+            Some("h" | "c") => Some(Lang::C),
+            Some("zig") => Some(Lang::Zig),
+            _ => None,
+        }
+    }
+
+    pub fn header(&self) -> &'static [u8] {
+        match self {
+            Lang::Rust => rust_glue::HEADER,
+            Lang::C => c_glue::HEADER,
+            Lang::Zig => zig_glue::HEADER,
+        }
+    }
+
+    pub fn emit(&self, types_and_targets: &[(Types, TargetInfo)]) -> String {
+        match self {
+            Lang::Rust => rust_glue::emit(types_and_targets),
+            Lang::C => c_glue::emit(types_and_targets),
+            Lang::Zig => zig_glue::emit(types_and_targets),
+        }
+    }
+}
+
+impl std::fmt::Display for Lang {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for Lang {
+    type Err = String;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        match string {
+            "rust" => Ok(Lang::Rust),
+            "c" => Ok(Lang::C),
+            "zig" => Ok(Lang::Zig),
+            _ => Err(format!("roc glue does not know how to generate {}", string)),
+        }
+    }
+}
+
+pub fn generate(input_path: &Path, output_path: &Path, lang: Lang) -> io::Result<i32> {
     match load_types(input_path.to_path_buf(), Threading::AllAvailable) {
         Ok(types_and_targets) => {
             let mut file = File::create(output_path).unwrap_or_else(|err| {
@@ -25,8 +94,8 @@ pub fn generate(input_path: &Path, output_path: &Path) -> io::Result<i32> {
                 process::exit(1);
             });
 
-            let mut buf = std::str::from_utf8(rust_glue::HEADER).unwrap().to_string();
-            let body = rust_glue::emit(&types_and_targets);
+            let mut buf = std::str::from_utf8(lang.header()).unwrap().to_string();
+            let body = lang.emit(&types_and_targets);
 
             buf.push_str(&body);
 
@@ -79,6 +148,7 @@ pub fn load_types(
         mut declarations_by_id,
         mut solved,
         interns,
+        exposed_to_host,
         ..
     } = roc_load::load_and_typecheck(
         arena,
@@ -89,6 +159,7 @@ pub fn load_types(
             render: RenderTarget::Generic,
             threading,
             exec_mode: ExecutionMode::Check,
+            cache_dir: None,
         },
     )
     .unwrap_or_else(|problem| match problem {
@@ -137,6 +208,11 @@ pub fn load_types(
         }
     });
 
+    let mut entry_points: Vec<(Symbol, Variable)> = exposed_to_host.into_iter().collect();
+
+    // Sort these, so the generated glue is the same every time.
+    entry_points.sort_by_key(|(symbol, _)| symbol.as_str(&interns));
+
     let layout_interner = GlobalInterner::with_capacity(128);
 
     let architectures = Architecture::iter();
@@ -150,7 +226,7 @@ pub fn load_types(
         let types = {
             let mut env = Env::new(arena, subs, &interns, layout_interner.fork(), target_info);
 
-            env.vars_to_types(variables.clone())
+            env.vars_to_types(variables.clone(), &entry_points)
         };
 
         types_and_targets.push((types, target_info));
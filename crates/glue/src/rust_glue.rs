@@ -65,6 +65,10 @@ pub fn emit(types_and_targets: &[(Types, TargetInfo)]) -> String {
         for id in types.sorted_ids() {
             add_type(*target_info, id, types, &mut impls);
         }
+
+        for (name, id) in types.entry_points() {
+            add_entry_point(*target_info, name, *id, types, &mut impls);
+        }
     }
 
     for (opt_impl, decls) in impls {
@@ -294,12 +298,301 @@ fn add_type(target_info: TargetInfo, id: TypeId, types: &Types, impls: &mut Impl
             // This is recursively pointing to a type that should already have been added,
             // so no extra work needs to happen.
         }
-        RocType::Function { .. } => {
-            // TODO actually generate glue functions!
+        RocType::Function {
+            name,
+            extern_name: Some(extern_name),
+            args,
+            ret,
+        } => add_function(target_info, name, extern_name, args, *ret, types, impls),
+        RocType::Function {
+            extern_name: None, ..
+        } => {
+            // The LLVM backend only exports callers for closures the platform gave a
+            // type alias, so there is no way for the host to call these.
         }
     }
 }
 
+/// A closure Roc returned to the host, which owns the values the closure captured.
+///
+/// The host can't know how big those are ahead of time, so it asks Roc (using the `_size`
+/// function the LLVM backend exports along with each closure's `_caller`), and allocates
+/// that much room for Roc to write them into.
+#[allow(clippy::too_many_arguments)]
+fn add_function(
+    target_info: TargetInfo,
+    name: &str,
+    extern_name: &str,
+    args: &[TypeId],
+    ret: TypeId,
+    types: &Types,
+    impls: &mut Impls,
+) {
+    let name = escape_kw(name.to_string());
+    let ret_is_closure = is_exported_closure(ret, types);
+    let ret_type = type_name(ret, types);
+    let output_type = if ret_is_closure {
+        "u8".to_string()
+    } else {
+        ret_type.clone()
+    };
+
+    // The caller takes each argument by pointer, followed by the closure data,
+    // and a pointer to write the return value to.
+    let mut params = Vec::with_capacity(args.len());
+    let mut caller_args = Vec::with_capacity(args.len());
+    let mut call_params = Vec::with_capacity(args.len());
+    let mut take_args = String::new();
+
+    for (index, arg_id) in args.iter().enumerate() {
+        let arg_type = type_name(*arg_id, types);
+
+        params.push(format!("arg{index}: *const {arg_type}"));
+
+        if let RocType::Unit = types.get_type(*arg_id) {
+            // Roc never reads these, so there's no need for the host to pass them.
+            caller_args.push("&()".to_string());
+        } else {
+            call_params.push(format!("arg{index}: {arg_type}"));
+            caller_args.push(format!("&*arg{index}"));
+
+            // Roc takes ownership of the arguments, so the host must not drop them.
+            write!(
+                take_args,
+                "\n{INDENT}{INDENT}let arg{index} = core::mem::ManuallyDrop::new(arg{index});"
+            )
+            .unwrap();
+        }
+    }
+
+    if !take_args.is_empty() {
+        take_args.push('\n');
+    }
+
+    params.push("closure_data: *const u8".to_string());
+    params.push(format!("output: *mut {output_type}"));
+    caller_args.push("self.closure_data.as_ptr()".to_string());
+
+    add_decl(
+        impls,
+        None,
+        target_info,
+        format!(
+            r#"extern "C" {{
+    fn {extern_name}_caller({});
+
+    fn {extern_name}_size() -> i64;
+
+    fn {extern_name}_result_size() -> i64;
+}}"#,
+            params.join(", ")
+        ),
+    );
+
+    add_decl(
+        impls,
+        None,
+        target_info,
+        format!(
+            r#"/// A Roc closure, along with the values it captured. Calling it hands those over
+/// to Roc; dropping it without calling it leaks them.
+pub struct {name} {{
+    closure_data: core::ptr::NonNull<u8>,
+    layout: core::alloc::Layout,
+}}"#
+        ),
+    );
+
+    let opt_impl = Some(format!("impl {name}"));
+
+    add_decl(
+        impls,
+        opt_impl.clone(),
+        target_info,
+        r#"/// Allocate room for Roc to write a closure's captured values into.
+    unsafe fn alloc_closure_data(size: i64) -> Self {
+        // Roc doesn't tell us the alignment of the captured values,
+        // so use the largest alignment a Roc value can have.
+        let layout = core::alloc::Layout::from_size_align((size as usize).max(1), 16).unwrap();
+
+        match core::ptr::NonNull::new(std::alloc::alloc(layout)) {
+            Some(closure_data) => Self {
+                closure_data,
+                layout,
+            },
+            None => std::alloc::handle_alloc_error(layout),
+        }
+    }"#
+        .to_string(),
+    );
+
+    let body = if ret_is_closure {
+        caller_args.push("output.closure_data.as_ptr()".to_string());
+
+        format!(
+            r#"unsafe {{
+            let output = {ret_type}::alloc_closure_data({extern_name}_result_size());
+
+            {extern_name}_caller({});
+
+            output
+        }}"#,
+            caller_args.join(", ")
+        )
+    } else {
+        caller_args.push("output.as_mut_ptr()".to_string());
+
+        format!(
+            r#"let mut output = core::mem::MaybeUninit::<{ret_type}>::uninit();
+
+        unsafe {{
+            debug_assert_eq!(
+                {extern_name}_result_size() as usize,
+                core::mem::size_of::<{ret_type}>()
+            );
+
+            {extern_name}_caller({});
+
+            output.assume_init()
+        }}"#,
+            caller_args.join(", ")
+        )
+    };
+
+    add_decl(
+        impls,
+        opt_impl,
+        target_info,
+        format!(
+            r#"/// Call this closure, giving Roc ownership of the arguments and the captured values.
+    pub fn call(self{}) -> {ret_type} {{{take_args}
+        {body}
+    }}"#,
+            call_params
+                .iter()
+                .map(|param| format!(", {param}"))
+                .collect::<String>(),
+        ),
+    );
+
+    add_decl(
+        impls,
+        Some(format!("impl Drop for {name}")),
+        target_info,
+        r#"fn drop(&mut self) {
+        unsafe { std::alloc::dealloc(self.closure_data.as_ptr(), self.layout) }
+    }"#
+        .to_string(),
+    );
+}
+
+/// The function Roc exposes for a value the platform provides, and a wrapper which calls it,
+/// for values that are (or return) closures the host can call. Roc writes the closure's
+/// captured values to the pointer passed as the first argument, and takes strings and lists
+/// by pointer.
+fn add_entry_point(
+    target_info: TargetInfo,
+    name: &str,
+    type_id: TypeId,
+    types: &Types,
+    impls: &mut Impls,
+) {
+    let (arg_ids, closure_id) = match types.get_type(type_id) {
+        RocType::Function {
+            extern_name: None,
+            args,
+            ret,
+            ..
+        } if is_exported_closure(*ret, types) => (args.as_slice(), *ret),
+        _ if is_exported_closure(type_id, types) => (&[][..], type_id),
+        _ => {
+            return;
+        }
+    };
+    let closure_type = type_name(closure_id, types);
+    let symbol = format!("roc__{name}_1_exposed_generic");
+    let mut params = vec!["output: *mut u8".to_string()];
+    let mut wrapper_params = Vec::with_capacity(arg_ids.len());
+    let mut wrapper_args = vec!["closure.closure_data.as_ptr()".to_string()];
+    let mut take_args = String::new();
+
+    for (index, arg_id) in arg_ids.iter().enumerate() {
+        let arg_type = type_name(*arg_id, types);
+
+        match types.get_type(*arg_id) {
+            RocType::Unit => {
+                continue;
+            }
+            RocType::RocStr | RocType::RocList(_) => {
+                params.push(format!("arg{index}: *const {arg_type}"));
+                wrapper_args.push(format!("&*arg{index}"));
+
+                // Roc takes ownership of the arguments, so the host must not drop them.
+                write!(
+                    take_args,
+                    "\n{INDENT}let arg{index} = core::mem::ManuallyDrop::new(arg{index});"
+                )
+                .unwrap();
+            }
+            _ => {
+                params.push(format!("arg{index}: {arg_type}"));
+                wrapper_args.push(format!("arg{index}"));
+            }
+        }
+
+        wrapper_params.push(format!("arg{index}: {arg_type}"));
+    }
+
+    if !take_args.is_empty() {
+        take_args.push('\n');
+    }
+
+    add_decl(
+        impls,
+        None,
+        target_info,
+        format!(
+            r#"extern "C" {{
+    fn {symbol}({});
+
+    fn roc__{name}_size() -> i64;
+}}"#,
+            params.join(", ")
+        ),
+    );
+
+    add_decl(
+        impls,
+        None,
+        target_info,
+        format!(
+            r#"pub fn {}({}) -> {closure_type} {{{take_args}
+    unsafe {{
+        let closure = {closure_type}::alloc_closure_data(roc__{name}_size());
+
+        {symbol}({});
+
+        closure
+    }}
+}}"#,
+            escape_kw(name.to_string()),
+            wrapper_params.join(", "),
+            wrapper_args.join(", "),
+        ),
+    );
+}
+
+/// Whether this is a closure which the LLVM backend exports a caller for
+fn is_exported_closure(type_id: TypeId, types: &Types) -> bool {
+    matches!(
+        types.get_type(type_id),
+        RocType::Function {
+            extern_name: Some(_),
+            ..
+        }
+    )
+}
+
 fn add_single_tag_struct(
     name: &str,
     tag_name: &str,
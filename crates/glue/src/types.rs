@@ -18,7 +18,7 @@ use roc_mono::layout::{
 use roc_target::TargetInfo;
 use roc_types::{
     subs::{Content, FlatType, GetSubsSlice, Subs, UnionLabels, UnionTags, Variable},
-    types::{AliasKind, RecordField},
+    types::RecordField,
 };
 use std::fmt::Display;
 
@@ -49,6 +49,8 @@ pub struct Types {
     /// This is important for declaration order in C; we need to output a
     /// type declaration earlier in the file than where it gets referenced by another type.
     deps: VecMap<TypeId, Vec<TypeId>>,
+    /// The values the platform provides to the host, along with their types
+    entry_points: Vec<(String, TypeId)>,
     target: TargetInfo,
 }
 
@@ -61,6 +63,7 @@ impl Types {
             sizes: Vec::new(),
             aligns: Vec::new(),
             deps: VecMap::with_capacity(cap),
+            entry_points: Vec::new(),
         }
     }
 
@@ -301,11 +304,13 @@ impl Types {
                     name: name_a,
                     args: args_a,
                     ret: ret_a,
+                    ..
                 },
                 Function {
                     name: name_b,
                     args: args_b,
                     ret: ret_b,
+                    ..
                 },
             ) => {
                 // for functions, the name is actually important because two functions
@@ -413,6 +418,10 @@ impl Types {
         id
     }
 
+    pub fn entry_points(&self) -> &[(String, TypeId)] {
+        &self.entry_points
+    }
+
     pub fn depends(&mut self, id: TypeId, depends_on: TypeId) {
         self.deps.get_or_insert(id, Vec::new).push(depends_on);
     }
@@ -523,6 +532,11 @@ pub enum RocType {
     RecursivePointer(TypeId),
     Function {
         name: String,
+        /// The prefix of the symbols the LLVM backend exports for calling this function,
+        /// e.g. `roc__mainForHost_1__Fx` for the `Fx` in `mainForHost : Str -> Task {} [] as Fx`.
+        /// Only functions which the platform gives a type alias get these exports, so this
+        /// is `None` for all other functions.
+        extern_name: Option<String>,
         args: Vec<TypeId>,
         ret: TypeId,
     },
@@ -668,6 +682,9 @@ pub struct Env<'a> {
     enum_names: Enums,
     pending_recursive_types: VecMap<TypeId, Variable>,
     known_recursive_types: VecMap<Variable, TypeId>,
+    /// The value the platform provides to the host whose type is being added, if any.
+    /// The symbols exported for calling the closures within it are named after it.
+    entry_point: Option<Symbol>,
     target: TargetInfo,
 }
 
@@ -687,17 +704,32 @@ impl<'a> Env<'a> {
             enum_names: Default::default(),
             pending_recursive_types: Default::default(),
             known_recursive_types: Default::default(),
+            entry_point: None,
             layout_cache: LayoutCache::new(layout_interner, target),
             target,
         }
     }
 
-    pub fn vars_to_types<I>(&mut self, variables: I) -> Types
+    pub fn vars_to_types<I>(&mut self, variables: I, entry_points: &[(Symbol, Variable)]) -> Types
     where
         I: Iterator<Item = Variable>,
     {
         let mut types = Types::with_capacity(variables.size_hint().0, self.target);
 
+        // Add the entry points first, so that the closures in them get named after
+        // the first entry point they appear in.
+        for (symbol, var) in entry_points {
+            self.entry_point = Some(*symbol);
+
+            let type_id = self.add_type(*var, &mut types);
+
+            types
+                .entry_points
+                .push((symbol.as_str(self.interns).to_string(), type_id));
+        }
+
+        self.entry_point = None;
+
         for var in variables {
             self.add_type(var, &mut types);
         }
@@ -787,6 +819,20 @@ fn add_type_help<'a>(
                 RocType::Struct { name, fields }
             })
         }
+        Content::Structure(FlatType::Tuple(elems, ext)) => {
+            let it = elems
+                .unsorted_iterator(subs, *ext)
+                .expect("something weird in content");
+
+            let name = match opt_name {
+                Some(sym) => sym.as_str(env.interns).to_string(),
+                None => env.struct_names.get_name(var),
+            };
+
+            add_struct(env, name, it, types, layout, |name, fields| {
+                RocType::TagUnionPayload { name, fields }
+            })
+        }
         Content::Structure(FlatType::TagUnion(tags, ext_var)) => {
             debug_assert!(ext_var_is_empty_tag_union(subs, *ext_var));
 
@@ -841,12 +887,33 @@ fn add_type_help<'a>(
                 add_type_help(env, ret_layout, *ret_var, None, types)
             };
 
-            let name = format!("TODO_roc_function_{:?}", closure_var);
+            let (name, extern_name) = match opt_name {
+                Some(sym) => {
+                    let name = sym.as_str(env.interns).to_string();
+                    // Only the platform's own aliases get exported. The names match the
+                    // closure callers in the LLVM backend, e.g. roc__mainForHost_1__Fx_caller
+                    let extern_name = env
+                        .entry_point
+                        .filter(|entry_point| entry_point.module_id() == sym.module_id())
+                        .map(|entry_point| {
+                            format!(
+                                "roc__{}_1_{}_{}",
+                                entry_point.as_str(env.interns),
+                                sym.module_string(env.interns),
+                                name
+                            )
+                        });
+
+                    (name, extern_name)
+                }
+                None => (format!("TODO_roc_function_{:?}", closure_var), None),
+            };
             let fn_type_id = types.add_named(
                 &env.layout_cache.interner,
                 name.clone(),
                 RocType::Function {
                     name,
+                    extern_name,
                     args: arg_type_ids.clone(),
                     ret: ret_type_id,
                 },
@@ -865,7 +932,7 @@ fn add_type_help<'a>(
             todo!()
         }
         Content::Structure(FlatType::Erroneous(_)) => todo!(),
-        Content::Structure(FlatType::EmptyRecord) => {
+        Content::Structure(FlatType::EmptyRecord | FlatType::EmptyTuple) => {
             types.add_anonymous(&env.layout_cache.interner, RocType::Unit, layout)
         }
         Content::Structure(FlatType::EmptyTagUnion) => {
@@ -932,14 +999,69 @@ fn add_type_help<'a>(
                             }
                         }
                     }
+                    Layout::Struct { .. } if *name == Symbol::DICT_DICT => {
+                        let type_vars = env.subs.get_subs_slice(alias_vars.type_variables());
+
+                        debug_assert_eq!(type_vars.len(), 2);
+
+                        let key_var = type_vars[0];
+                        let key_layout =
+                            env.layout_cache.from_var(env.arena, key_var, subs).unwrap();
+                        let key_id = add_type_help(env, key_layout, key_var, None, types);
+
+                        let val_var = type_vars[1];
+                        let val_layout =
+                            env.layout_cache.from_var(env.arena, val_var, subs).unwrap();
+                        let val_id = add_type_help(env, val_layout, val_var, None, types);
+
+                        let dict_id = types.add_anonymous(
+                            &env.layout_cache.interner,
+                            RocType::RocDict(key_id, val_id),
+                            layout,
+                        );
+
+                        types.depends(dict_id, key_id);
+                        types.depends(dict_id, val_id);
+
+                        dict_id
+                    }
+                    Layout::Struct { .. } if *name == Symbol::SET_SET => {
+                        let type_vars = env.subs.get_subs_slice(alias_vars.type_variables());
+
+                        debug_assert_eq!(type_vars.len(), 1);
+
+                        let elem_var = type_vars[0];
+                        let elem_layout = env
+                            .layout_cache
+                            .from_var(env.arena, elem_var, subs)
+                            .unwrap();
+                        let elem_id = add_type_help(env, elem_layout, elem_var, None, types);
+
+                        let set_id = types.add_anonymous(
+                            &env.layout_cache.interner,
+                            RocType::RocSet(elem_id),
+                            layout,
+                        );
+
+                        types.depends(set_id, elem_id);
+
+                        set_id
+                    }
                     _ => {
                         unreachable!()
                     }
                 }
             } else {
                 // If this was a non-builtin type alias, we can use that alias name
-                // in the generated bindings.
-                add_type_help(env, layout, *real_var, Some(*name), types)
+                // in the generated bindings. Functions keep their outermost alias
+                // (e.g. `Fx` in `Task {} [] as Fx`, rather than the `Effect` that `Task`
+                // wraps), since that is the one the LLVM backend exports a caller for.
+                let name = match opt_name {
+                    Some(outer_name) if is_function(subs, *real_var) => outer_name,
+                    _ => *name,
+                };
+
+                add_type_help(env, layout, *real_var, Some(name), types)
             }
         }
         Content::RangedNumber(_) => todo!(),
@@ -969,6 +1091,15 @@ fn add_type_help<'a>(
     }
 }
 
+/// Whether this is a function type, possibly behind some type aliases
+fn is_function(subs: &Subs, var: Variable) -> bool {
+    match subs.get_content_without_compacting(var) {
+        Content::Structure(FlatType::Func(_, _, _)) => true,
+        Content::Alias(_, _, real_var, _) => is_function(subs, *real_var),
+        _ => false,
+    }
+}
+
 fn add_builtin_type<'a>(
     env: &mut Env<'a>,
     builtin: Builtin<'a>,
@@ -1070,91 +1201,6 @@ fn add_builtin_type<'a>(
 
             list_id
         }
-        (
-            Builtin::List(elem_layout),
-            Alias(Symbol::DICT_DICT, _alias_variables, alias_var, AliasKind::Opaque),
-        ) => {
-            match (
-                elem_layout,
-                env.subs.get_content_without_compacting(*alias_var),
-            ) {
-                (
-                    Layout::Struct { field_layouts, .. },
-                    Content::Structure(FlatType::Apply(Symbol::LIST_LIST, args_subs_slice)),
-                ) => {
-                    let (key_var, val_var) = {
-                        let args_tuple = env.subs.get_subs_slice(*args_subs_slice);
-
-                        debug_assert_eq!(args_tuple.len(), 1);
-
-                        match env.subs.get_content_without_compacting(args_tuple[0]) {
-                            Content::Structure(FlatType::TagUnion(union_tags, ext_var)) => {
-                                let (mut iter, _) = union_tags.sorted_iterator_and_ext(env.subs, *ext_var);
-                                let payloads = iter.next().unwrap().1;
-
-                                debug_assert_eq!(iter.next(), None);
-
-                                (payloads[0], payloads[1])
-                            }
-                            _ => {
-                                unreachable!()
-                            }
-                        }
-                    };
-
-                    debug_assert_eq!(field_layouts.len(), 2);
-
-                    let key_id = add_type_help(env, field_layouts[0], key_var, opt_name, types);
-                    let val_id = add_type_help(env, field_layouts[1], val_var, opt_name, types);
-                    let dict_id = types.add_anonymous(&env.layout_cache.interner,RocType::RocDict(key_id, val_id), layout);
-
-                    types.depends(dict_id, key_id);
-                    types.depends(dict_id, val_id);
-
-                    dict_id
-                }
-                (elem_layout, alias_content) => unreachable!(
-                    "Unrecognized List element for Dict. Layout was: {:?} and alias_content was: {:?}",
-                    elem_layout,
-                    alias_content
-                ),
-            }
-        }
-        (
-            Builtin::List(elem_layout),
-            Alias(Symbol::SET_SET, _alias_vars, alias_var, AliasKind::Opaque),
-        ) => {
-            match (
-                elem_layout,
-                env.subs.get_content_without_compacting(*alias_var),
-            ) {
-                (
-                    Layout::Struct { field_layouts, .. },
-                    Alias(Symbol::DICT_DICT, alias_args, _alias_var, AliasKind::Opaque),
-                ) => {
-                    let dict_type_vars = env.subs.get_subs_slice(alias_args.type_variables());
-
-                    debug_assert_eq!(dict_type_vars.len(), 2);
-
-                    // Sets only use the key of the Dict they wrap, not the value
-                    let elem_var = dict_type_vars[0];
-
-                    debug_assert_eq!(field_layouts.len(), 2);
-
-                    let elem_id = add_type_help(env, field_layouts[0], elem_var, opt_name, types);
-                    let set_id = types.add_anonymous(&env.layout_cache.interner,RocType::RocSet(elem_id), layout);
-
-                    types.depends(set_id, elem_id);
-
-                    set_id
-                }
-                (elem_layout, alias_content) => unreachable!(
-                    "Unrecognized List element for Set. Layout was: {:?} and alias_content was: {:?}",
-                    elem_layout,
-                    alias_content
-                ),
-            }
-        }
         (Builtin::List(elem_layout), alias) => {
             unreachable!(
                 "The type alias {:?} was not an Apply(Symbol::LIST_LIST) as expected, given that its builtin was Builtin::List({:?})",
@@ -1267,8 +1313,7 @@ fn add_tag_union<'a>(
                 // A non-recursive tag union
                 // e.g. `Result ok err : [Ok ok, Err err]`
                 NonRecursive(_) => {
-                    let tags =
-                        union_tags_to_types(&name, union_tags, subs, env, types, layout, false);
+                    let tags = union_tags_to_types(&name, union_tags, subs, env, types, false);
                     // TODO deal with empty tag union
                     let discriminant_size = Discriminant::from_number_of_tags(tags.len())
                         .stack_size()
@@ -1287,8 +1332,7 @@ fn add_tag_union<'a>(
                 // A recursive tag union (general case)
                 // e.g. `Expr : [Sym Str, Add Expr Expr]`
                 Recursive(_) => {
-                    let tags =
-                        union_tags_to_types(&name, union_tags, subs, env, types, layout, true);
+                    let tags = union_tags_to_types(&name, union_tags, subs, env, types, true);
                     let discriminant_size =
                         Discriminant::from_number_of_tags(tags.len()).stack_size();
                     let discriminant_offset = union_layout
@@ -1305,7 +1349,7 @@ fn add_tag_union<'a>(
                 NonNullableUnwrapped(_) => {
                     let (tag_name, payload_vars) = single_tag_payload(union_tags, subs);
                     let (tag_name, opt_payload) =
-                        tag_to_type(&name, env, tag_name, payload_vars, types, layout, true);
+                        tag_to_type(&name, env, tag_name, payload_vars, types, true);
 
                     // A recursive tag union with just one constructor
                     // Optimization: No need to store a tag ID (the payload is "unwrapped")
@@ -1325,8 +1369,7 @@ fn add_tag_union<'a>(
                     nullable_id,
                     other_tags,
                 } => {
-                    let tags =
-                        union_tags_to_types(&name, union_tags, subs, env, types, layout, true);
+                    let tags = union_tags_to_types(&name, union_tags, subs, env, types, true);
                     let discriminant_size =
                         Discriminant::from_number_of_tags(other_tags.len()).stack_size();
                     let discriminant_offset = union_layout
@@ -1353,8 +1396,7 @@ fn add_tag_union<'a>(
                     nullable_id: null_represents_first_tag,
                     other_fields: _, // TODO use this!
                 } => {
-                    let mut tags =
-                        union_tags_to_types(&name, union_tags, subs, env, types, layout, true);
+                    let mut tags = union_tags_to_types(&name, union_tags, subs, env, types, true);
                     // NullableUnwrapped tag unions should always have exactly 2 tags.
                     debug_assert_eq!(tags.len(), 2);
 
@@ -1469,7 +1511,6 @@ fn union_tags_to_types<'a>(
     subs: &Subs,
     env: &mut Env<'a>,
     types: &mut Types,
-    layout: Layout<'a>,
     is_recursive: bool,
 ) -> Vec<(String, Option<TypeId>)> {
     let mut tags: Vec<(String, Vec<Variable>)> = union_tags
@@ -1486,15 +1527,7 @@ fn union_tags_to_types<'a>(
 
     tags.into_iter()
         .map(|(tag_name, payload_vars)| {
-            tag_to_type(
-                name,
-                env,
-                tag_name,
-                &payload_vars,
-                types,
-                layout,
-                is_recursive,
-            )
+            tag_to_type(name, env, tag_name, &payload_vars, types, is_recursive)
         })
         .collect()
 }
@@ -1535,7 +1568,6 @@ fn tag_to_type<'a, D: Display>(
     tag_name: D,
     payload_vars: &[Variable],
     types: &mut Types,
-    layout: Layout<'a>,
     is_recursive: bool,
 ) -> (D, Option<TypeId>) {
     match struct_fields_needed(env, payload_vars.iter().copied()) {
@@ -1560,9 +1592,23 @@ fn tag_to_type<'a, D: Display>(
             // create a RocType for the payload and save it
             let struct_name = format!("{}_{}", &name, tag_name); // e.g. "MyUnion_MyVariant"
             let fields = payload_vars.iter().copied().enumerate();
-            let struct_id = add_struct(env, struct_name, fields, types, layout, |name, fields| {
-                RocType::TagUnionPayload { name, fields }
+            // The payload is laid out as a struct of its fields, which is not the same as the
+            // layout of the tag union (that's a pointer, if the tag union is recursive).
+            let field_layouts = payload_vars.iter().map(|var| {
+                env.layout_cache
+                    .from_var(env.arena, *var, env.subs)
+                    .expect("Something weird ended up in the content")
             });
+            let payload_layout =
+                Layout::struct_no_name_order(env.arena.alloc_slice_fill_iter(field_layouts));
+            let struct_id = add_struct(
+                env,
+                struct_name,
+                fields,
+                types,
+                payload_layout,
+                |name, fields| RocType::TagUnionPayload { name, fields },
+            );
 
             (tag_name, Some(struct_id))
         }
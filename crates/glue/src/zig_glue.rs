@@ -0,0 +1,980 @@
+use crate::types::{RocNum, RocTagUnion, RocType, TypeId, Types};
+use indexmap::IndexMap;
+use roc_target::{Architecture, TargetInfo};
+use std::fmt::Write;
+
+pub static HEADER: &[u8] = include_bytes!("../templates/header.zig");
+const INDENT: &str = "    ";
+
+/// The declarations for each type, keyed by the name of the type, along with the targets
+/// each version of the declaration applies to. A declaration is the type expression that
+/// gets assigned to the name, e.g. `extern struct { ... }`.
+type Decls = IndexMap<String, IndexMap<String, Vec<TargetInfo>>>;
+
+/// The (size, alignment) checks for the types declared for each target
+type LayoutChecks = IndexMap<String, Vec<TargetInfo>>;
+
+/// Add the given declaration body, along with the architecture, to the Decls.
+fn add_decl(decls: &mut Decls, key: String, target_info: TargetInfo, body: String) {
+    let targets = decls.entry(key).or_default().entry(body).or_default();
+
+    // The same type can come up more than once, e.g. two fields which are both `Result Str U8`
+    if !targets.contains(&target_info) {
+        targets.push(target_info);
+    }
+}
+
+pub fn emit(types_and_targets: &[(Types, TargetInfo)]) -> String {
+    let mut buf = String::new();
+    let mut decls: Decls = IndexMap::default();
+    let mut layout_checks: LayoutChecks = IndexMap::default();
+
+    // Unlike C, Zig doesn't care what order top-level declarations are in.
+    for (types, target_info) in types_and_targets {
+        for id in types.sorted_ids() {
+            add_type(*target_info, id, types, &mut decls);
+        }
+    }
+
+    for (name, bodies) in decls.iter() {
+        buf.push('\n');
+
+        if bodies.len() == 1 {
+            let body = bodies.keys().next().unwrap();
+
+            writeln!(buf, "pub const {name} = {body};").unwrap();
+        } else {
+            // The type is laid out differently on different targets.
+            writeln!(buf, "pub const {name} = switch (builtin.cpu.arch) {{").unwrap();
+
+            for (body, targets) in bodies {
+                // We should never have a decl recorded with 0 targets!
+                debug_assert_ne!(targets.len(), 0);
+
+                writeln!(buf, "{INDENT}{} => {},", arch_list(targets), indent(body)).unwrap();
+            }
+
+            writeln!(
+                buf,
+                "{INDENT}else => @compileError(\"Roc does not support this target architecture\"),\n}};"
+            )
+            .unwrap();
+        }
+    }
+
+    for (types, target_info) in types_and_targets {
+        let mut checks = String::new();
+        let mut checked = Vec::new();
+
+        for id in types.sorted_ids() {
+            if let Some(name) = decl_name(id, types) {
+                if decls.contains_key(&name) && !checked.contains(&name) {
+                    let size = types.size_rounded_to_alignment(id);
+                    let align = types.align(id);
+
+                    writeln!(
+                        checks,
+                        "{INDENT}{INDENT}{INDENT}checkLayout({name}, {size}, {align});"
+                    )
+                    .unwrap();
+
+                    checked.push(name);
+                }
+            }
+        }
+
+        let targets = layout_checks.entry(checks).or_default();
+
+        if !targets.contains(target_info) {
+            targets.push(*target_info);
+        }
+    }
+
+    if !decls.is_empty() {
+        buf.push_str("\ncomptime {\n");
+        buf.push_str("    switch (builtin.cpu.arch) {\n");
+
+        for (checks, targets) in layout_checks {
+            writeln!(buf, "{INDENT}{INDENT}{} => {{", arch_list(&targets)).unwrap();
+            buf.push_str(&checks);
+            writeln!(buf, "{INDENT}{INDENT}}},").unwrap();
+        }
+
+        buf.push_str("        else => {},\n");
+        buf.push_str("    }\n");
+        buf.push_str("}\n");
+    }
+
+    // The entry points are the same on every target, so the first one will do.
+    if let Some((types, _)) = types_and_targets.first() {
+        for (name, type_id) in types.entry_points() {
+            add_entry_point(name, *type_id, types, &mut buf);
+        }
+    }
+
+    buf
+}
+
+/// The name a type gets declared with, if it gets declared at all
+fn decl_name(id: TypeId, types: &Types) -> Option<String> {
+    match types.get_type(id) {
+        RocType::Struct { .. }
+        | RocType::TagUnionPayload { .. }
+        | RocType::RocResult(_, _)
+        | RocType::TagUnion(_)
+            if !is_zero_sized(id, types) =>
+        {
+            Some(type_name(id, types))
+        }
+        _ => None,
+    }
+}
+
+fn add_type(target_info: TargetInfo, id: TypeId, types: &Types, decls: &mut Decls) {
+    // Zig doesn't allow zero-sized fields in extern types, so these get skipped.
+    if is_zero_sized(id, types) {
+        return;
+    }
+
+    match types.get_type(id) {
+        RocType::Struct { name, fields } => {
+            let fields = fields
+                .iter()
+                .map(|(label, type_id)| (escape_kw(label.clone()), *type_id));
+
+            add_struct(name, target_info, fields, types, decls)
+        }
+        RocType::TagUnionPayload { name, fields } => {
+            // Tag union payloads have numbered fields, so we prefix them
+            // with an "f" because Zig doesn't allow struct fields to be numbers.
+            let fields = fields
+                .iter()
+                .map(|(index, type_id)| (format!("f{index}"), *type_id));
+
+            add_struct(name, target_info, fields, types, decls)
+        }
+        RocType::TagUnion(tag_union) => match tag_union {
+            RocTagUnion::Enumeration { tags, name, size } => {
+                let body = enumeration(tags.iter(), *size);
+
+                add_decl(decls, escape_kw(name.clone()), target_info, body);
+            }
+            RocTagUnion::NonRecursive {
+                tags,
+                name,
+                discriminant_size,
+                discriminant_offset: _,
+            } => {
+                // Empty tag unions can never come up at runtime,
+                // and so don't need declared types.
+                if !tags.is_empty() {
+                    add_non_recursive(name, target_info, tags, *discriminant_size, types, decls);
+                }
+            }
+            RocTagUnion::Recursive {
+                tags,
+                name,
+                discriminant_size,
+                discriminant_offset: _,
+            } => {
+                // Empty tag unions can never come up at runtime,
+                // and so don't need declared types.
+                if !tags.is_empty() {
+                    add_recursive(
+                        name,
+                        target_info,
+                        tags,
+                        None,
+                        *discriminant_size,
+                        types,
+                        decls,
+                    );
+                }
+            }
+            RocTagUnion::NullableWrapped {
+                name,
+                index_of_null_tag,
+                tags,
+                discriminant_size,
+                discriminant_offset: _,
+            } => add_recursive(
+                name,
+                target_info,
+                tags,
+                Some(*index_of_null_tag as usize),
+                *discriminant_size,
+                types,
+                decls,
+            ),
+            RocTagUnion::NullableUnwrapped {
+                name,
+                null_tag,
+                non_null_tag,
+                non_null_payload,
+                null_represents_first_tag: _,
+            } => add_nullable_unwrapped(
+                name,
+                target_info,
+                null_tag,
+                non_null_tag,
+                *non_null_payload,
+                types,
+                decls,
+            ),
+            RocTagUnion::SingleTagStruct {
+                name,
+                tag_name,
+                payload_fields,
+            } => add_single_tag_struct(name, target_info, tag_name, payload_fields, types, decls),
+            RocTagUnion::NonNullableUnwrapped {
+                name,
+                tag_name,
+                payload,
+            } => add_recursive(
+                name,
+                target_info,
+                &[(tag_name.clone(), Some(*payload))],
+                None,
+                0,
+                types,
+                decls,
+            ),
+        },
+        RocType::RocResult(ok_id, err_id) => {
+            // A Result is a non-recursive tag union, with the tags in alphabetical order.
+            add_non_recursive(
+                &type_name(id, types),
+                target_info,
+                &[
+                    ("Err".to_string(), Some(*err_id)),
+                    ("Ok".to_string(), Some(*ok_id)),
+                ],
+                1,
+                types,
+                decls,
+            )
+        }
+        // These types are declared in the header, if they need declaring at all.
+        RocType::Unit
+        | RocType::EmptyTagUnion
+        | RocType::Num(_)
+        | RocType::Bool
+        | RocType::RocStr
+        | RocType::RocDict(_, _)
+        | RocType::RocSet(_)
+        | RocType::RocList(_)
+        | RocType::RocBox(_) => {}
+        RocType::RecursivePointer { .. } => {
+            // This is recursively pointing to a type that should already have been added,
+            // so no extra work needs to happen.
+        }
+        RocType::Function { .. } => {
+            // TODO actually generate glue functions!
+        }
+    }
+}
+
+fn add_struct<I: Iterator<Item = (String, TypeId)>>(
+    name: &str,
+    target_info: TargetInfo,
+    fields: I,
+    types: &Types,
+    decls: &mut Decls,
+) {
+    let mut buf = "extern struct {\n".to_string();
+
+    for (label, type_id) in fields.filter(|(_, type_id)| !is_zero_sized(*type_id, types)) {
+        writeln!(buf, "{INDENT}{label}: {},", type_name(type_id, types)).unwrap();
+    }
+
+    buf.push('}');
+
+    add_decl(decls, escape_kw(name.to_string()), target_info, buf);
+}
+
+fn enumeration<I: ExactSizeIterator<Item = S>, S: AsRef<str>>(tags: I, tag_bytes: u32) -> String {
+    let repr_bits = tag_bytes * 8;
+    let mut buf = format!("enum(u{repr_bits}) {{\n");
+
+    for (index, tag_name) in tags.enumerate() {
+        writeln!(
+            buf,
+            "{INDENT}{} = {index},",
+            escape_kw(tag_name.as_ref().to_string())
+        )
+        .unwrap();
+    }
+
+    buf.push('}');
+
+    buf
+}
+
+/// The declaration of the `Tag` enum nested in a tag union's wrapper struct
+fn tag_decl<'a, I: ExactSizeIterator<Item = &'a str>>(tag_names: I, size: u32) -> String {
+    format!(
+        "\n\n{INDENT}pub const Tag = {};",
+        indent(&enumeration(tag_names, size))
+    )
+}
+
+/// The members of the union of a tag union's payloads. Tags without a payload, or with a
+/// zero-sized one, don't need a member.
+fn payload_decl(tags: &[(String, Option<TypeId>)], types: &Types) -> Option<String> {
+    let mut buf = String::new();
+
+    for (tag_name, opt_payload_id) in tags {
+        if let Some(payload_type) = payload_type(*opt_payload_id, types) {
+            writeln!(buf, "{INDENT}{INDENT}{tag_name}: {payload_type},").unwrap();
+        }
+    }
+
+    if buf.is_empty() {
+        None
+    } else {
+        Some(format!(
+            "\n\n{INDENT}pub const Payload = extern union {{\n{buf}{INDENT}}};"
+        ))
+    }
+}
+
+/// The type of a tag's payload, unless it doesn't need to be stored
+fn payload_type(opt_payload_id: Option<TypeId>, types: &Types) -> Option<String> {
+    match opt_payload_id {
+        Some(payload_id) if !is_zero_sized(payload_id, types) => Some(type_name(payload_id, types)),
+        _ => None,
+    }
+}
+
+fn add_non_recursive(
+    name: &str,
+    target_info: TargetInfo,
+    tags: &[(String, Option<TypeId>)],
+    discriminant_size: u32,
+    types: &Types,
+    decls: &mut Decls,
+) {
+    let name = escape_kw(name.to_string());
+    let payload_decl = payload_decl(tags, types);
+
+    // The discriminant goes right after the union of the payloads, e.g.
+    //
+    // pub const MyTagUnion = extern struct {
+    //     payload: Payload,
+    //     tag: Tag,
+    //
+    //     pub const Payload = extern union {
+    //         Bar: u8,
+    //         Foo: RocStr,
+    //     };
+    //
+    //     ...
+    // };
+    let mut buf = "extern struct {\n".to_string();
+
+    if payload_decl.is_some() {
+        writeln!(buf, "{INDENT}payload: Payload,").unwrap();
+    }
+
+    writeln!(buf, "{INDENT}tag: Tag,\n\n{INDENT}const Self = @This();").unwrap();
+    buf.push_str(payload_decl.as_deref().unwrap_or_default());
+    buf.push_str(&tag_decl(
+        tags.iter().map(|(tag_name, _)| tag_name.as_str()),
+        discriminant_size,
+    ));
+
+    write!(
+        buf,
+        r#"
+
+    pub fn discriminant(self: Self) Tag {{
+        return self.tag;
+    }}"#
+    )
+    .unwrap();
+
+    for (tag_name, opt_payload_id) in tags {
+        match payload_type(*opt_payload_id, types) {
+            Some(payload_type) => {
+                write!(
+                    buf,
+                    r#"
+
+    pub fn init{tag_name}(payload: {payload_type}) Self {{
+        return .{{ .payload = .{{ .{tag_name} = payload }}, .tag = .{tag_name} }};
+    }}
+
+    pub fn get{tag_name}(self: Self) {payload_type} {{
+        std.debug.assert(self.tag == .{tag_name});
+
+        return self.payload.{tag_name};
+    }}"#
+                )
+                .unwrap();
+            }
+            None => {
+                let payload = if payload_decl.is_some() {
+                    ".payload = undefined, "
+                } else {
+                    ""
+                };
+
+                write!(
+                    buf,
+                    r#"
+
+    pub fn init{tag_name}() Self {{
+        return .{{ {payload}.tag = .{tag_name} }};
+    }}"#
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    buf.push_str("\n}");
+
+    add_decl(decls, name, target_info, buf);
+}
+
+fn add_recursive(
+    name: &str,
+    target_info: TargetInfo,
+    tags: &[(String, Option<TypeId>)],
+    null_tag_index: Option<usize>, // used only in the nullable-wrapped case
+    discriminant_size: u32,
+    types: &Types,
+    decls: &mut Decls,
+) {
+    let name = escape_kw(name.to_string());
+
+    // We should never be attempting to generate glue for empty tag unions;
+    // RocType should not have let this happen.
+    debug_assert_ne!(tags.len(), 0);
+
+    // A recursive tag union is a pointer to its payload, with the discriminant either in the
+    // unused bits at the end of the pointer, or (if there are too many tags for that) stored
+    // after the payload in the allocation.
+    let mut buf =
+        format!("extern struct {{\n{INDENT}pointer: ?*anyopaque,\n\n{INDENT}const Self = @This();");
+    let payload_decl = payload_decl(tags, types)
+        .unwrap_or_else(|| format!("\n\n{INDENT}pub const Payload = extern union {{}};"));
+
+    buf.push_str(&payload_decl);
+
+    let has_discriminant = discriminant_size > 0;
+    let pointer_tagged = tags.len() <= max_pointer_tagged_variants(target_info.architecture);
+
+    if has_discriminant {
+        let repr_bits = discriminant_size * 8;
+        let null_check = match null_tag_index {
+            Some(index) => format!(
+                "if (self.pointer == null) {{\n{INDENT}{INDENT}{INDENT}return .{};\n{INDENT}{INDENT}}}\n\n{INDENT}{INDENT}",
+                tags[index].0
+            ),
+            None => String::new(),
+        };
+
+        buf.push_str(&tag_decl(
+            tags.iter().map(|(tag_name, _)| tag_name.as_str()),
+            discriminant_size,
+        ));
+
+        if pointer_tagged {
+            write!(
+                buf,
+                r#"
+
+    // On a 64-bit system, pointers have 3 bits that are unused, and 2 on a 32-bit system.
+    const tag_mask: usize = if (@sizeOf(usize) == 8) 0b111 else 0b11;
+
+    pub fn discriminant(self: Self) Tag {{
+        {null_check}return @intToEnum(Tag, @intCast(u{repr_bits}, @ptrToInt(self.pointer) & tag_mask));
+    }}
+
+    fn payloadPointer(self: Self) *Payload {{
+        return @intToPtr(*Payload, @ptrToInt(self.pointer) & ~tag_mask);
+    }}"#
+            )
+            .unwrap();
+        } else {
+            write!(
+                buf,
+                r#"
+
+    const Storage = extern struct {{
+        payload: Payload,
+        tag: Tag,
+    }};
+
+    fn storage(self: Self) *Storage {{
+        return @ptrCast(*Storage, @alignCast(@alignOf(Storage), self.pointer.?));
+    }}
+
+    pub fn discriminant(self: Self) Tag {{
+        {null_check}return self.storage().tag;
+    }}
+
+    fn payloadPointer(self: Self) *Payload {{
+        return &self.storage().payload;
+    }}"#
+            )
+            .unwrap();
+        }
+    } else {
+        write!(
+            buf,
+            r#"
+
+    fn payloadPointer(self: Self) *Payload {{
+        return @ptrCast(*Payload, @alignCast(@alignOf(Payload), self.pointer.?));
+    }}"#
+        )
+        .unwrap();
+    }
+
+    for (tag_index, (tag_name, opt_payload_id)) in tags.iter().enumerate() {
+        if Some(tag_index) == null_tag_index {
+            // The null tag index only occurs for nullable-wrapped tag unions,
+            // and it always has no payload.
+            write!(
+                buf,
+                r#"
+
+    pub fn init{tag_name}() Self {{
+        return .{{ .pointer = null }};
+    }}"#
+            )
+            .unwrap();
+
+            continue;
+        }
+
+        let payload_type = payload_type(*opt_payload_id, types);
+        let (args, set_payload) = match &payload_type {
+            Some(payload_type) => (
+                format!("payload: {payload_type}"),
+                format!("\n{INDENT}{INDENT}payload_pointer.{tag_name} = payload;\n"),
+            ),
+            None => (String::new(), String::new()),
+        };
+        let body = if !has_discriminant {
+            format!(
+                r#"const payload_pointer = allocRefcounted(Payload);
+{set_payload}
+        return .{{ .pointer = payload_pointer }};"#
+            )
+        } else if pointer_tagged {
+            format!(
+                r#"const payload_pointer = allocRefcounted(Payload);
+{set_payload}
+        return .{{ .pointer = @intToPtr(?*anyopaque, @ptrToInt(payload_pointer) | @enumToInt(Tag.{tag_name})) }};"#
+            )
+        } else {
+            format!(
+                r#"const storage_pointer = allocRefcounted(Storage);
+        const payload_pointer = &storage_pointer.payload;
+{set_payload}
+        storage_pointer.tag = .{tag_name};
+
+        return .{{ .pointer = storage_pointer }};"#
+            )
+        };
+
+        write!(
+            buf,
+            r#"
+
+    pub fn init{tag_name}({args}) Self {{
+        {body}
+    }}"#
+        )
+        .unwrap();
+
+        if let Some(payload_type) = payload_type {
+            let assertion = if has_discriminant {
+                format!("std.debug.assert(self.discriminant() == .{tag_name});\n\n{INDENT}{INDENT}")
+            } else {
+                String::new()
+            };
+
+            write!(
+                buf,
+                r#"
+
+    pub fn get{tag_name}(self: Self) {payload_type} {{
+        {assertion}return self.payloadPointer().{tag_name};
+    }}"#
+            )
+            .unwrap();
+        }
+    }
+
+    buf.push_str("\n}");
+
+    add_decl(decls, name, target_info, buf);
+}
+
+fn add_nullable_unwrapped(
+    name: &str,
+    target_info: TargetInfo,
+    null_tag: &str,
+    non_null_tag: &str,
+    non_null_payload: TypeId,
+    types: &Types,
+    decls: &mut Decls,
+) {
+    let name = escape_kw(name.to_string());
+    let mut tag_names = vec![null_tag, non_null_tag];
+
+    tag_names.sort_unstable();
+
+    // The null tag is a null pointer, and the other tag is a pointer to its payload,
+    // so there's no need to store a discriminant.
+    let payload_type = type_name(non_null_payload, types);
+    let mut buf =
+        format!("extern struct {{\n{INDENT}pointer: ?*anyopaque,\n\n{INDENT}const Self = @This();");
+
+    buf.push_str(&tag_decl(tag_names.into_iter(), 1));
+
+    write!(
+        buf,
+        r#"
+
+    pub fn discriminant(self: Self) Tag {{
+        if (self.pointer == null) {{
+            return .{null_tag};
+        }} else {{
+            return .{non_null_tag};
+        }}
+    }}
+
+    pub fn init{null_tag}() Self {{
+        return .{{ .pointer = null }};
+    }}
+
+    pub fn init{non_null_tag}(payload: {payload_type}) Self {{
+        const payload_pointer = allocRefcounted({payload_type});
+
+        payload_pointer.* = payload;
+
+        return .{{ .pointer = payload_pointer }};
+    }}
+
+    pub fn get{non_null_tag}(self: Self) {payload_type} {{
+        std.debug.assert(self.pointer != null);
+
+        return @ptrCast(*{payload_type}, @alignCast(@alignOf({payload_type}), self.pointer.?)).*;
+    }}
+}}"#
+    )
+    .unwrap();
+
+    add_decl(decls, name, target_info, buf);
+}
+
+fn add_single_tag_struct(
+    name: &str,
+    target_info: TargetInfo,
+    tag_name: &str,
+    payload_fields: &[TypeId],
+    types: &Types,
+    decls: &mut Decls,
+) {
+    let fields: Vec<(String, String)> = payload_fields
+        .iter()
+        .enumerate()
+        .filter(|(_, type_id)| !is_zero_sized(**type_id, types))
+        .map(|(index, type_id)| (format!("f{index}"), type_name(*type_id, types)))
+        .collect();
+
+    // Store single-tag unions as structs rather than unions,
+    // because they have only one alternative.
+    let mut buf = "extern struct {\n".to_string();
+
+    for (label, field_type) in fields.iter() {
+        writeln!(buf, "{INDENT}{label}: {field_type},").unwrap();
+    }
+
+    let args = fields
+        .iter()
+        .map(|(label, field_type)| format!("{label}: {field_type}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let inits = fields
+        .iter()
+        .map(|(label, _)| format!(".{label} = {label}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    write!(
+        buf,
+        r#"
+    pub fn init{tag_name}({args}) @This() {{
+        return .{{ {inits} }};
+    }}
+}}"#
+    )
+    .unwrap();
+
+    add_decl(decls, escape_kw(name.to_string()), target_info, buf);
+}
+
+/// The declaration of the function Roc exposes for a value the platform provides,
+/// along with a wrapper which calls it.
+///
+/// Roc writes the value it returns to the pointer passed as the first argument, and takes
+/// strings and lists by pointer.
+fn add_entry_point(name: &str, type_id: TypeId, types: &Types, buf: &mut String) {
+    let symbol = format!("roc__{name}_1_exposed_generic");
+    let (arg_ids, ret_id) = match types.get_type(type_id) {
+        RocType::Function { args, ret, .. } => (args.as_slice(), *ret),
+        _ => (&[][..], type_id),
+    };
+    let arg_ids: Vec<TypeId> = arg_ids
+        .iter()
+        .copied()
+        .filter(|arg_id| !is_zero_sized(*arg_id, types))
+        .collect();
+
+    let mut params = Vec::with_capacity(arg_ids.len());
+    let mut wrapper_params = Vec::with_capacity(arg_ids.len());
+    let mut wrapper_args = Vec::with_capacity(arg_ids.len());
+
+    for (index, arg_id) in arg_ids.iter().enumerate() {
+        let arg_type = type_name(*arg_id, types);
+
+        if matches!(
+            types.get_type(*arg_id),
+            RocType::RocStr | RocType::RocList(_)
+        ) {
+            params.push(format!("arg{index}: *const {arg_type}"));
+            wrapper_args.push(format!("&arg{index}"));
+        } else {
+            params.push(format!("arg{index}: {arg_type}"));
+            wrapper_args.push(format!("arg{index}"));
+        }
+
+        wrapper_params.push(format!("arg{index}: {arg_type}"));
+    }
+
+    if let RocType::Function { .. } = types.get_type(ret_id) {
+        // The host can't know the size of a closure's captured environment ahead of time,
+        // so it has to ask for it, and allocate that much room for Roc to write into.
+        params.insert(0, "ret: [*]u8".to_string());
+
+        write!(
+            buf,
+            "\npub extern fn {symbol}({}) void;\npub extern fn roc__{name}_size() i64;\n",
+            params.join(", ")
+        )
+        .unwrap();
+
+        return;
+    }
+
+    let (ret_type, ret_param_type) = if is_zero_sized(ret_id, types) {
+        // Nothing gets written, but Roc still expects a pointer.
+        ("void".to_string(), "u8".to_string())
+    } else {
+        let ret_type = type_name(ret_id, types);
+
+        (ret_type.clone(), ret_type)
+    };
+
+    params.insert(0, format!("ret: *{ret_param_type}"));
+    wrapper_args.insert(0, "&ret".to_string());
+
+    let return_ret = if ret_type == "void" {
+        ""
+    } else {
+        "\n\n    return ret;"
+    };
+
+    write!(
+        buf,
+        r#"
+pub extern fn {symbol}({}) void;
+
+pub fn {}({}) {ret_type} {{
+    var ret: {ret_param_type} = undefined;
+
+    {symbol}({});{return_ret}
+}}
+"#,
+        params.join(", "),
+        escape_kw(name.to_string()),
+        wrapper_params.join(", "),
+        wrapper_args.join(", "),
+    )
+    .unwrap();
+}
+
+fn is_zero_sized(type_id: TypeId, types: &Types) -> bool {
+    types.size_ignoring_alignment(type_id) == 0
+}
+
+fn type_name(id: TypeId, types: &Types) -> String {
+    match types.get_type(id) {
+        RocType::Unit | RocType::EmptyTagUnion => "void".to_string(),
+        RocType::RocStr => "RocStr".to_string(),
+        RocType::Bool => "bool".to_string(),
+        RocType::Num(RocNum::U8) => "u8".to_string(),
+        RocType::Num(RocNum::U16) => "u16".to_string(),
+        RocType::Num(RocNum::U32) => "u32".to_string(),
+        RocType::Num(RocNum::U64) => "u64".to_string(),
+        RocType::Num(RocNum::U128) => "u128".to_string(),
+        RocType::Num(RocNum::I8) => "i8".to_string(),
+        RocType::Num(RocNum::I16) => "i16".to_string(),
+        RocType::Num(RocNum::I32) => "i32".to_string(),
+        RocType::Num(RocNum::I64) => "i64".to_string(),
+        RocType::Num(RocNum::I128) => "i128".to_string(),
+        RocType::Num(RocNum::F32) => "f32".to_string(),
+        RocType::Num(RocNum::F64) => "f64".to_string(),
+        RocType::Num(RocNum::F128) => "f128".to_string(),
+        RocType::Num(RocNum::Dec) => "RocDec".to_string(),
+        // Like the Roc builtins, these don't track their element types.
+        RocType::RocDict(_, _) => "RocDict".to_string(),
+        RocType::RocSet(_) => "RocSet".to_string(),
+        RocType::RocList(_) => "RocList".to_string(),
+        RocType::RocBox(_) => "RocBox".to_string(),
+        RocType::RocResult(ok_id, err_id) => {
+            // Each Result type gets declared separately, e.g. RocResult_RocStr_u8
+            let to_ident = |id| {
+                type_name(id, types)
+                    .chars()
+                    .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+                    .collect::<String>()
+            };
+
+            format!("RocResult_{}_{}", to_ident(*ok_id), to_ident(*err_id))
+        }
+        RocType::Struct { name, .. }
+        | RocType::TagUnionPayload { name, .. }
+        | RocType::TagUnion(RocTagUnion::NonRecursive { name, .. })
+        | RocType::TagUnion(RocTagUnion::Recursive { name, .. })
+        | RocType::TagUnion(RocTagUnion::Enumeration { name, .. })
+        | RocType::TagUnion(RocTagUnion::NullableWrapped { name, .. })
+        | RocType::TagUnion(RocTagUnion::NullableUnwrapped { name, .. })
+        | RocType::TagUnion(RocTagUnion::NonNullableUnwrapped { name, .. })
+        | RocType::TagUnion(RocTagUnion::SingleTagStruct { name, .. }) => escape_kw(name.clone()),
+        RocType::RecursivePointer(content) => type_name(*content, types),
+        RocType::Function { name, .. } => escape_kw(name.clone()),
+    }
+}
+
+/// Indent every line but the first, so a declaration can be nested in a `switch`
+fn indent(body: &str) -> String {
+    body.lines()
+        .map(|line| {
+            if line.is_empty() {
+                line.to_string()
+            } else {
+                format!("{INDENT}{line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_start()
+        .to_string()
+}
+
+/// e.g. `.aarch64, .x86_64`
+fn arch_list(targets: &[TargetInfo]) -> String {
+    targets
+        .iter()
+        .map(|target_info| arch_to_str(target_info.architecture))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The name of the architecture in Zig's `std.Target.Cpu.Arch`
+fn arch_to_str(architecture: Architecture) -> &'static str {
+    match architecture {
+        Architecture::X86_64 => ".x86_64",
+        Architecture::X86_32 => ".i386",
+        Architecture::Aarch64 => ".aarch64",
+        Architecture::Aarch32 => ".arm",
+        Architecture::Wasm32 => ".wasm32",
+    }
+}
+
+fn max_pointer_tagged_variants(architecture: Architecture) -> usize {
+    match architecture {
+        // On a 64-bit system, pointers have 3 bits that are unused, so return 2^3 = 8
+        Architecture::X86_64 | Architecture::Aarch64 => 8,
+        // On a 32-bit system, pointers have 2 bits that are unused, so return 2^2 = 4
+        Architecture::X86_32 | Architecture::Aarch32 | Architecture::Wasm32 => 4,
+    }
+}
+
+// Based on https://ziglang.org/documentation/master/#Keyword-Reference, plus the primitive
+// values and types which can't be shadowed either.
+const RESERVED_KEYWORDS: &[&str] = &[
+    "addrspace",
+    "align",
+    "allowzero",
+    "and",
+    "anyframe",
+    "anytype",
+    "asm",
+    "async",
+    "await",
+    "break",
+    "callconv",
+    "catch",
+    "comptime",
+    "const",
+    "continue",
+    "defer",
+    "else",
+    "enum",
+    "errdefer",
+    "error",
+    "export",
+    "extern",
+    "fn",
+    "for",
+    "if",
+    "inline",
+    "linksection",
+    "noalias",
+    "noinline",
+    "nosuspend",
+    "opaque",
+    "or",
+    "orelse",
+    "packed",
+    "pub",
+    "resume",
+    "return",
+    "struct",
+    "suspend",
+    "switch",
+    "test",
+    "threadlocal",
+    "try",
+    "union",
+    "unreachable",
+    "usingnamespace",
+    "var",
+    "volatile",
+    "while",
+    "anyopaque",
+    "bool",
+    "false",
+    "null",
+    "true",
+    "type",
+    "undefined",
+    "void",
+];
+
+/// Escape a Zig reserved keyword, if necessary.
+fn escape_kw(input: String) -> String {
+    if RESERVED_KEYWORDS.contains(&input.as_str()) {
+        format!("@\"{input}\"")
+    } else {
+        input
+    }
+}
@@ -0,0 +1,155 @@
+// ⚠️ GENERATED CODE ⚠️ - this entire file was generated by the `roc glue` CLI command
+
+#pragma once
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#if defined(__x86_64__) || defined(_M_X64)
+#define ROC_ARCH_X86_64 1
+#elif defined(__i386__) || defined(_M_IX86)
+#define ROC_ARCH_X86 1
+#elif defined(__aarch64__) || defined(_M_ARM64)
+#define ROC_ARCH_AARCH64 1
+#elif defined(__arm__) || defined(_M_ARM)
+#define ROC_ARCH_ARM 1
+#elif defined(__wasm32__)
+#define ROC_ARCH_WASM32 1
+#else
+#error "Roc does not support this target architecture"
+#endif
+
+// The host provides these, and Roc calls them too.
+void *roc_alloc(size_t size, unsigned int alignment);
+void roc_dealloc(void *ptr, unsigned int alignment);
+
+typedef struct RocI128 {
+    _Alignas(16) uint8_t bytes[16];
+} RocI128;
+
+typedef struct RocU128 {
+    _Alignas(16) uint8_t bytes[16];
+} RocU128;
+
+typedef struct RocDec {
+    _Alignas(16) uint8_t bytes[16];
+} RocDec;
+
+typedef struct RocStr {
+    char *bytes;
+    size_t len;
+    size_t capacity;
+} RocStr;
+
+typedef struct RocList {
+    void *elements;
+    size_t length;
+    size_t capacity;
+} RocList;
+
+typedef struct RocDict {
+    RocList buckets;
+    RocList data;
+} RocDict;
+
+typedef RocDict RocSet;
+
+typedef void *RocBox;
+
+// Heap-allocated Roc values are preceded by their reference count. A count of 0 means the
+// value is readonly (e.g. a string literal in the binary), and is never freed. Otherwise,
+// the count starts at INTPTR_MIN for a single reference, and goes up from there.
+#define ROC_REFCOUNT_ONE INTPTR_MIN
+
+static inline intptr_t *roc_refcount_ptr(void *data) {
+    return ((intptr_t *)data) - 1;
+}
+
+static inline size_t roc_refcount_padding(uint32_t alignment) {
+    return alignment > sizeof(intptr_t) ? alignment : sizeof(intptr_t);
+}
+
+// Allocate room for a value of the given size, preceded by a reference count of one.
+// Returns a pointer to where the value goes.
+static inline void *roc_alloc_refcounted(size_t size, uint32_t alignment) {
+    size_t padding = roc_refcount_padding(alignment);
+    char *allocation = roc_alloc(padding + size, alignment);
+    void *data = allocation + padding;
+
+    *roc_refcount_ptr(data) = ROC_REFCOUNT_ONE;
+
+    return data;
+}
+
+static inline void roc_refcount_increment(void *data) {
+    intptr_t *refcount = roc_refcount_ptr(data);
+
+    if (*refcount != 0) {
+        *refcount += 1;
+    }
+}
+
+// Returns true if that was the last reference, in which case the allocation was freed.
+// This frees only the allocation itself; decrementing whatever the value refers to is up
+// to the caller, and must happen before this.
+static inline bool roc_refcount_decrement(void *data, uint32_t alignment) {
+    intptr_t *refcount = roc_refcount_ptr(data);
+
+    if (*refcount == ROC_REFCOUNT_ONE) {
+        roc_dealloc((char *)data - roc_refcount_padding(alignment), alignment);
+
+        return true;
+    } else if (*refcount != 0) {
+        *refcount -= 1;
+    }
+
+    return false;
+}
+
+// Strings of fewer than sizeof(RocStr) bytes are stored inline, with their length in the last
+// byte, whose highest bit is set to tell them apart from heap-allocated strings.
+static inline bool roc_str_is_small(const RocStr *str) {
+    return (intptr_t)str->capacity < 0;
+}
+
+static inline size_t roc_str_len(const RocStr *str) {
+    if (roc_str_is_small(str)) {
+        return ((const uint8_t *)str)[sizeof(RocStr) - 1] ^ 0x80;
+    } else {
+        return str->len;
+    }
+}
+
+static inline const char *roc_str_bytes(const RocStr *str) {
+    if (roc_str_is_small(str)) {
+        return (const char *)str;
+    } else {
+        return str->bytes;
+    }
+}
+
+static inline void roc_str_increment(const RocStr *str) {
+    if (!roc_str_is_small(str) && str->bytes != NULL) {
+        roc_refcount_increment(str->bytes);
+    }
+}
+
+static inline void roc_str_decrement(const RocStr *str) {
+    if (!roc_str_is_small(str) && str->bytes != NULL) {
+        roc_refcount_decrement(str->bytes, sizeof(intptr_t));
+    }
+}
+
+static inline void roc_list_increment(const RocList *list) {
+    if (list->elements != NULL) {
+        roc_refcount_increment(list->elements);
+    }
+}
+
+// This does not decrement the elements when the list is freed.
+static inline void roc_list_decrement(const RocList *list, uint32_t element_alignment) {
+    if (list->elements != NULL) {
+        roc_refcount_decrement(list->elements, element_alignment);
+    }
+}
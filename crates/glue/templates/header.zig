@@ -0,0 +1,133 @@
+// ⚠️ GENERATED CODE ⚠️ - this entire file was generated by the `roc glue` CLI command
+
+const std = @import("std");
+const builtin = @import("builtin");
+
+// The host provides these, and Roc calls them too.
+pub extern fn roc_alloc(size: usize, alignment: u32) callconv(.C) ?*anyopaque;
+pub extern fn roc_dealloc(c_ptr: *anyopaque, alignment: u32) callconv(.C) void;
+
+pub const RocDec = extern struct {
+    num: i128,
+};
+
+pub const RocStr = extern struct {
+    bytes: ?[*]u8,
+    len: usize,
+    capacity: usize,
+
+    // Strings of fewer than @sizeOf(RocStr) bytes are stored inline, with their length in the
+    // last byte, whose highest bit is set to tell them apart from heap-allocated strings.
+    pub fn isSmallStr(self: RocStr) bool {
+        return @bitCast(isize, self.capacity) < 0;
+    }
+
+    pub fn asSlice(self: *const RocStr) []const u8 {
+        if (self.isSmallStr()) {
+            const bytes = @ptrCast([*]const u8, self);
+
+            return bytes[0 .. bytes[@sizeOf(RocStr) - 1] ^ 0x80];
+        } else if (self.bytes) |bytes| {
+            return bytes[0..self.len];
+        } else {
+            return &[_]u8{};
+        }
+    }
+
+    pub fn increment(self: RocStr) void {
+        if (!self.isSmallStr()) {
+            if (self.bytes) |bytes| refcountIncrement(bytes);
+        }
+    }
+
+    pub fn decrement(self: RocStr) void {
+        if (!self.isSmallStr()) {
+            if (self.bytes) |bytes| _ = refcountDecrement(bytes, @alignOf(usize));
+        }
+    }
+};
+
+pub const RocList = extern struct {
+    elements: ?[*]u8,
+    length: usize,
+    capacity: usize,
+
+    pub fn increment(self: RocList) void {
+        if (self.elements) |elements| refcountIncrement(elements);
+    }
+
+    // This does not decrement the elements when the list is freed.
+    pub fn decrement(self: RocList, element_alignment: u32) void {
+        if (self.elements) |elements| _ = refcountDecrement(elements, element_alignment);
+    }
+};
+
+pub const RocDict = extern struct {
+    buckets: RocList,
+    data: RocList,
+};
+
+pub const RocSet = RocDict;
+
+pub const RocBox = ?*anyopaque;
+
+// Heap-allocated Roc values are preceded by their reference count. A count of 0 means the
+// value is readonly (e.g. a string literal in the binary), and is never freed. Otherwise,
+// the count starts at minInt(isize) for a single reference, and goes up from there.
+pub const REFCOUNT_ONE: isize = std.math.minInt(isize);
+
+fn refcountPtr(data: [*]u8) *isize {
+    return @intToPtr(*isize, @ptrToInt(data) - @sizeOf(isize));
+}
+
+fn refcountPadding(alignment: u32) usize {
+    return std.math.max(alignment, @sizeOf(usize));
+}
+
+// Allocate room for a value of the given type, preceded by a reference count of one.
+pub fn allocRefcounted(comptime T: type) *T {
+    const alignment: u32 = @alignOf(T);
+    const padding = refcountPadding(alignment);
+    const allocation = roc_alloc(padding + @sizeOf(T), alignment) orelse @panic("roc_alloc returned null");
+    const data = @ptrCast([*]u8, allocation) + padding;
+
+    refcountPtr(data).* = REFCOUNT_ONE;
+
+    return @ptrCast(*T, @alignCast(@alignOf(T), data));
+}
+
+pub fn refcountIncrement(data: [*]u8) void {
+    const refcount = refcountPtr(data);
+
+    if (refcount.* != 0) {
+        refcount.* += 1;
+    }
+}
+
+// Returns true if that was the last reference, in which case the allocation was freed.
+// This frees only the allocation itself; decrementing whatever the value refers to is up
+// to the caller, and must happen before this.
+pub fn refcountDecrement(data: [*]u8, alignment: u32) bool {
+    const refcount = refcountPtr(data);
+
+    if (refcount.* == REFCOUNT_ONE) {
+        roc_dealloc(data - refcountPadding(alignment), alignment);
+
+        return true;
+    } else if (refcount.* != 0) {
+        refcount.* -= 1;
+    }
+
+    return false;
+}
+
+// Fails compilation if Roc lays out the given type differently from Zig.
+pub fn checkLayout(comptime T: type, comptime size: usize, comptime alignment: usize) void {
+    if (@sizeOf(T) != size) {
+        @compileError(std.fmt.comptimePrint("{s} should have a size of {d}", .{ @typeName(T), size }));
+    }
+
+    if (@alignOf(T) != alignment) {
+        @compileError(std.fmt.comptimePrint("{s} should have an alignment of {d}", .{ @typeName(T), alignment }));
+    }
+}
@@ -0,0 +1,266 @@
+#[macro_use]
+extern crate pretty_assertions;
+
+#[macro_use]
+extern crate indoc;
+
+mod helpers;
+
+#[cfg(test)]
+mod test_gen_c {
+    use crate::helpers::{fixtures_dir, generate_c_bindings};
+    use roc_glue::c_glue;
+    use roc_glue::load::load_types;
+    use roc_load::Threading;
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+
+    #[test]
+    fn basic_record_aliased() {
+        let module = indoc!(
+            r#"
+            MyRcd : { a : U64, b : I128 }
+
+            main : MyRcd
+            main = { a: 1u64, b: 2i128 }
+        "#
+        );
+
+        assert_eq!(
+            generate_c_bindings(module)
+                .strip_prefix('\n')
+                .unwrap_or_default(),
+            indoc!(
+                r#"
+                #if ROC_ARCH_ARM || ROC_ARCH_AARCH64 || ROC_ARCH_WASM32 || ROC_ARCH_X86 || ROC_ARCH_X86_64
+
+                typedef struct MyRcd {
+                    RocI128 b;
+                    uint64_t a;
+                } MyRcd;
+
+                _Static_assert(sizeof(MyRcd) == 32, "MyRcd should have a size of 32");
+                _Static_assert(_Alignof(MyRcd) == 16, "MyRcd should have an alignment of 16");
+
+                #endif
+            "#
+            )
+        );
+    }
+
+    #[test]
+    fn enumeration() {
+        let module = indoc!(
+            r#"
+            MyEnum : [Foo, Bar, Baz]
+
+            main : MyEnum
+            main = Foo
+        "#
+        );
+
+        assert_eq!(
+            generate_c_bindings(module)
+                .strip_prefix('\n')
+                .unwrap_or_default(),
+            indoc!(
+                r#"
+                #if ROC_ARCH_ARM || ROC_ARCH_AARCH64 || ROC_ARCH_WASM32 || ROC_ARCH_X86 || ROC_ARCH_X86_64
+
+                enum MyEnum {
+                    MyEnum_Bar = 0,
+                    MyEnum_Baz = 1,
+                    MyEnum_Foo = 2,
+                };
+
+                typedef uint8_t MyEnum;
+
+                #endif
+            "#
+            )
+        );
+    }
+
+    #[test]
+    fn non_recursive_union() {
+        let module = indoc!(
+            r#"
+            MyUnion : [Foo Str, Bar U8, Baz]
+
+            main : MyUnion
+            main = Foo "x"
+        "#
+        );
+
+        assert_eq!(
+            generate_c_bindings(module)
+                .strip_prefix('\n')
+                .unwrap_or_default(),
+            indoc!(
+                r#"
+                #if ROC_ARCH_ARM || ROC_ARCH_AARCH64 || ROC_ARCH_WASM32 || ROC_ARCH_X86 || ROC_ARCH_X86_64
+
+                enum discriminant_MyUnion {
+                    discriminant_MyUnion_Bar = 0,
+                    discriminant_MyUnion_Baz = 1,
+                    discriminant_MyUnion_Foo = 2,
+                };
+
+                typedef uint8_t discriminant_MyUnion;
+
+                #endif
+
+                #if ROC_ARCH_ARM || ROC_ARCH_WASM32 || ROC_ARCH_X86
+
+                union payload_MyUnion {
+                    uint8_t Bar;
+                    RocStr Foo;
+                };
+
+                typedef struct MyUnion {
+                    union payload_MyUnion payload;
+                    discriminant_MyUnion discriminant;
+                } MyUnion;
+
+                _Static_assert(sizeof(MyUnion) == 16, "MyUnion should have a size of 16");
+                _Static_assert(_Alignof(MyUnion) == 4, "MyUnion should have an alignment of 4");
+                _Static_assert(offsetof(MyUnion, discriminant) == 12, "MyUnion should have its discriminant at offset 12");
+
+                static inline discriminant_MyUnion MyUnion_discriminant(MyUnion tag_union) {
+                    return tag_union.discriminant;
+                }
+
+                static inline MyUnion MyUnion_new_Bar(uint8_t payload) {
+                    return (MyUnion){ .payload = { .Bar = payload }, .discriminant = discriminant_MyUnion_Bar };
+                }
+
+                static inline MyUnion MyUnion_new_Baz(void) {
+                    return (MyUnion){ .discriminant = discriminant_MyUnion_Baz };
+                }
+
+                static inline MyUnion MyUnion_new_Foo(RocStr payload) {
+                    return (MyUnion){ .payload = { .Foo = payload }, .discriminant = discriminant_MyUnion_Foo };
+                }
+
+                #endif
+
+                #if ROC_ARCH_AARCH64 || ROC_ARCH_X86_64
+
+                union payload_MyUnion {
+                    uint8_t Bar;
+                    RocStr Foo;
+                };
+
+                typedef struct MyUnion {
+                    union payload_MyUnion payload;
+                    discriminant_MyUnion discriminant;
+                } MyUnion;
+
+                _Static_assert(sizeof(MyUnion) == 32, "MyUnion should have a size of 32");
+                _Static_assert(_Alignof(MyUnion) == 8, "MyUnion should have an alignment of 8");
+                _Static_assert(offsetof(MyUnion, discriminant) == 24, "MyUnion should have its discriminant at offset 24");
+
+                static inline discriminant_MyUnion MyUnion_discriminant(MyUnion tag_union) {
+                    return tag_union.discriminant;
+                }
+
+                static inline MyUnion MyUnion_new_Bar(uint8_t payload) {
+                    return (MyUnion){ .payload = { .Bar = payload }, .discriminant = discriminant_MyUnion_Bar };
+                }
+
+                static inline MyUnion MyUnion_new_Baz(void) {
+                    return (MyUnion){ .discriminant = discriminant_MyUnion_Baz };
+                }
+
+                static inline MyUnion MyUnion_new_Foo(RocStr payload) {
+                    return (MyUnion){ .payload = { .Foo = payload }, .discriminant = discriminant_MyUnion_Foo };
+                }
+
+                #endif
+            "#
+            )
+        );
+    }
+
+    #[test]
+    fn result_in_record() {
+        let module = indoc!(
+            r#"
+            Rcd : { r : Result Str U8 }
+
+            main : Rcd
+            main = { r: Ok "x" }
+        "#
+        );
+
+        let bindings = generate_c_bindings(module);
+
+        // The discriminant goes right after the larger payload, Str
+        assert!(bindings.contains(
+            "_Static_assert(offsetof(RocResult_RocStr_uint8_t, discriminant) == 24, \"RocResult_RocStr_uint8_t should have its discriminant at offset 24\");"
+        ));
+        // The Result is declared before the record which uses it
+        assert!(
+            bindings.find("} RocResult_RocStr_uint8_t;").unwrap()
+                < bindings.find("} Rcd;").unwrap()
+        );
+    }
+
+    /// Generate a C header for the platform in each of the fixtures/ directories,
+    /// and check that a C compiler accepts it. Each declaration comes with static
+    /// assertions of its size and alignment, so this also checks that the C compiler
+    /// lays out each type the same way Roc does.
+    #[test]
+    fn fixture_headers_compile() {
+        let fixtures = fs::read_dir(fixtures_dir("")).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+
+        for entry in fixtures {
+            let fixture_dir = entry.unwrap().path();
+
+            if fixture_dir.is_dir() {
+                let name = fixture_dir.file_name().unwrap().to_str().unwrap();
+                let types_and_targets =
+                    load_types(fixture_dir.join("platform.roc"), Threading::Single)
+                        .expect("had problems loading");
+
+                let mut header = std::str::from_utf8(c_glue::HEADER).unwrap().to_string();
+
+                header.push_str(&c_glue::emit(&types_and_targets));
+
+                let header_path = dir.path().join(format!("{name}.h"));
+
+                fs::write(&header_path, header).unwrap();
+
+                compile_header(&header_path);
+            }
+        }
+    }
+
+    fn compile_header(header_path: &Path) {
+        let main_path = header_path.with_extension("c");
+
+        fs::write(
+            &main_path,
+            format!(
+                "#include \"{}\"\n\nint main(void) {{\n    return 0;\n}}\n",
+                header_path.display()
+            ),
+        )
+        .unwrap();
+
+        let output = Command::new("cc")
+            .args(["-std=c11", "-Wall", "-Wextra", "-Werror", "-fsyntax-only"])
+            .arg(&main_path)
+            .output()
+            .expect("failed to run the C compiler");
+
+        assert!(
+            output.status.success(),
+            "The generated header {} did not compile:\n\n{}",
+            header_path.display(),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+}
@@ -233,4 +233,181 @@ mod test_gen_rs {
             )
         );
     }
+
+    #[test]
+    fn closure_returned_to_host() {
+        let module = indoc!(
+            r#"
+            Effect a := {} -> a
+
+            main : Str -> Effect Str as Fx
+            main = \s -> @Effect \{} -> s
+        "#
+        );
+
+        assert_eq!(
+            generate_bindings(module)
+                .strip_prefix('\n')
+                .unwrap_or_default(),
+            indoc!(
+                r#"
+                #[cfg(any(
+                    target_arch = "arm",
+                    target_arch = "aarch64",
+                    target_arch = "wasm32",
+                    target_arch = "x86",
+                    target_arch = "x86_64"
+                ))]
+                extern "C" {
+                    fn roc__main_1__Fx_caller(arg0: *const (), closure_data: *const u8, output: *mut roc_std::RocStr);
+
+                    fn roc__main_1__Fx_size() -> i64;
+
+                    fn roc__main_1__Fx_result_size() -> i64;
+                }
+
+                #[cfg(any(
+                    target_arch = "arm",
+                    target_arch = "aarch64",
+                    target_arch = "wasm32",
+                    target_arch = "x86",
+                    target_arch = "x86_64"
+                ))]
+                /// A Roc closure, along with the values it captured. Calling it hands those over
+                /// to Roc; dropping it without calling it leaks them.
+                pub struct Fx {
+                    closure_data: core::ptr::NonNull<u8>,
+                    layout: core::alloc::Layout,
+                }
+
+                #[cfg(any(
+                    target_arch = "arm",
+                    target_arch = "aarch64",
+                    target_arch = "wasm32",
+                    target_arch = "x86",
+                    target_arch = "x86_64"
+                ))]
+                extern "C" {
+                    fn roc__main_1_exposed_generic(output: *mut u8, arg0: *const roc_std::RocStr);
+
+                    fn roc__main_size() -> i64;
+                }
+
+                #[cfg(any(
+                    target_arch = "arm",
+                    target_arch = "aarch64",
+                    target_arch = "wasm32",
+                    target_arch = "x86",
+                    target_arch = "x86_64"
+                ))]
+                pub fn main(arg0: roc_std::RocStr) -> Fx {
+                    let arg0 = core::mem::ManuallyDrop::new(arg0);
+
+                    unsafe {
+                        let closure = Fx::alloc_closure_data(roc__main_size());
+
+                        roc__main_1_exposed_generic(closure.closure_data.as_ptr(), &*arg0);
+
+                        closure
+                    }
+                }
+
+                impl Fx {
+                    #[cfg(any(
+                        target_arch = "arm",
+                        target_arch = "aarch64",
+                        target_arch = "wasm32",
+                        target_arch = "x86",
+                        target_arch = "x86_64"
+                    ))]
+                    /// Allocate room for Roc to write a closure's captured values into.
+                    unsafe fn alloc_closure_data(size: i64) -> Self {
+                        // Roc doesn't tell us the alignment of the captured values,
+                        // so use the largest alignment a Roc value can have.
+                        let layout = core::alloc::Layout::from_size_align((size as usize).max(1), 16).unwrap();
+
+                        match core::ptr::NonNull::new(std::alloc::alloc(layout)) {
+                            Some(closure_data) => Self {
+                                closure_data,
+                                layout,
+                            },
+                            None => std::alloc::handle_alloc_error(layout),
+                        }
+                    }
+
+                    #[cfg(any(
+                        target_arch = "arm",
+                        target_arch = "aarch64",
+                        target_arch = "wasm32",
+                        target_arch = "x86",
+                        target_arch = "x86_64"
+                    ))]
+                    /// Call this closure, giving Roc ownership of the arguments and the captured values.
+                    pub fn call(self) -> roc_std::RocStr {
+                        let mut output = core::mem::MaybeUninit::<roc_std::RocStr>::uninit();
+
+                        unsafe {
+                            debug_assert_eq!(
+                                roc__main_1__Fx_result_size() as usize,
+                                core::mem::size_of::<roc_std::RocStr>()
+                            );
+
+                            roc__main_1__Fx_caller(&(), self.closure_data.as_ptr(), output.as_mut_ptr());
+
+                            output.assume_init()
+                        }
+                    }
+                }
+
+                impl Drop for Fx {
+                    #[cfg(any(
+                        target_arch = "arm",
+                        target_arch = "aarch64",
+                        target_arch = "wasm32",
+                        target_arch = "x86",
+                        target_arch = "x86_64"
+                    ))]
+                    fn drop(&mut self) {
+                        unsafe { std::alloc::dealloc(self.closure_data.as_ptr(), self.layout) }
+                    }
+                }
+            "#
+            )
+        );
+    }
+
+    #[test]
+    fn closure_returning_closure() {
+        let module = indoc!(
+            r#"
+            main : Str -> (U8 -> (Str -> Str) as Inner) as Fx
+            main = \s -> \n -> \t -> Str.concat s (Str.concat t (Num.toStr n))
+        "#
+        );
+
+        let bindings = generate_bindings(module);
+
+        // Roc writes the returned closure's captured values into room the host allocated
+        assert!(bindings.contains(
+            "fn roc__main_1__Fx_caller(arg0: *const u8, closure_data: *const u8, output: *mut u8);"
+        ));
+        assert!(bindings.contains("pub fn call(self, arg0: u8) -> Inner {"));
+        assert!(bindings
+            .contains("let output = Inner::alloc_closure_data(roc__main_1__Fx_result_size());"));
+        assert!(bindings.contains("pub fn call(self, arg0: roc_std::RocStr) -> roc_std::RocStr {"));
+    }
+
+    #[test]
+    fn closure_without_alias() {
+        let module = indoc!(
+            r#"
+            main : Str -> (U8 -> Str)
+            main = \s -> \n -> Str.concat s (Num.toStr n)
+        "#
+        );
+
+        // Only closures the platform gives a type alias get exported, so there is
+        // nothing the host could call this with.
+        assert_eq!(generate_bindings(module), "");
+    }
 }
@@ -0,0 +1,86 @@
+#[macro_use]
+extern crate pretty_assertions;
+
+#[macro_use]
+extern crate indoc;
+
+mod helpers;
+
+#[cfg(test)]
+mod test_gen_zig {
+    use crate::helpers::fixtures_dir;
+    use roc_glue::load::load_types;
+    use roc_glue::zig_glue;
+    use roc_load::Threading;
+    use std::fs;
+    use std::path::PathBuf;
+
+    /// Generate Zig glue for the platform in each of the fixtures/ directories, and compare
+    /// it to the expected glue in snapshots/zig/. To update those after changing the
+    /// generated glue, run the tests with ROC_GLUE_SNAPSHOT_TEST_OVERWRITE=1 set.
+    #[test]
+    fn fixture_snapshots() {
+        let mut fixture_names: Vec<String> = fs::read_dir(fixtures_dir(""))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.is_dir())
+            .map(|path| path.file_name().unwrap().to_str().unwrap().to_string())
+            .collect();
+
+        fixture_names.sort();
+
+        for name in fixture_names {
+            let types_and_targets =
+                load_types(fixtures_dir(&name).join("platform.roc"), Threading::Single)
+                    .expect("had problems loading");
+
+            snapshot_test(&name, zig_glue::emit(&types_and_targets));
+        }
+    }
+
+    #[test]
+    fn no_extra_snapshot_files() {
+        for entry in fs::read_dir(snapshots_dir()).unwrap() {
+            let path = entry.unwrap().path();
+            let name = path.file_stem().unwrap().to_str().unwrap();
+
+            assert!(
+                fixtures_dir(name).is_dir(),
+                "{} has no corresponding directory in tests/fixtures",
+                path.display()
+            );
+        }
+    }
+
+    fn snapshots_dir() -> PathBuf {
+        let mut path = fixtures_dir("");
+
+        path.pop();
+        path.push("snapshots");
+        path.push("zig");
+
+        path
+    }
+
+    fn snapshot_test(name: &str, actual: String) {
+        let snapshot_path = snapshots_dir().join(format!("{name}.zig"));
+
+        if std::env::var("ROC_GLUE_SNAPSHOT_TEST_OVERWRITE").is_ok() {
+            fs::write(&snapshot_path, actual).unwrap();
+        } else {
+            let expected = fs::read_to_string(&snapshot_path).unwrap_or_else(|err| {
+                panic!(
+                    "Error opening snapshot file {}:\n\
+                        {:?}
+                        Supposing the file is missing, consider running the tests with:\n\
+                        `env ROC_GLUE_SNAPSHOT_TEST_OVERWRITE=1 cargo test ...`\n\
+                        and committing the file that creates.",
+                    snapshot_path.display(),
+                    err
+                );
+            });
+
+            assert_eq!(expected, actual, "Zig glue for the {name} fixture changed");
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use roc_glue::load::load_types;
-use roc_glue::rust_glue;
+use roc_glue::types::Types;
+use roc_glue::{c_glue, rust_glue};
 use roc_load::Threading;
+use roc_target::TargetInfo;
 use std::env;
 use std::fs::File;
 use std::io::Write;
@@ -8,6 +10,15 @@ use std::path::PathBuf;
 
 #[allow(dead_code)]
 pub fn generate_bindings(decl_src: &str) -> String {
+    rust_glue::emit(&load_decls(decl_src))
+}
+
+#[allow(dead_code)]
+pub fn generate_c_bindings(decl_src: &str) -> String {
+    c_glue::emit(&load_decls(decl_src))
+}
+
+fn load_decls(decl_src: &str) -> Vec<(Types, TargetInfo)> {
     use tempfile::tempdir;
 
     let mut src = indoc!(
@@ -25,7 +36,7 @@ pub fn generate_bindings(decl_src: &str) -> String {
 
     src.push_str(decl_src);
 
-    let pairs = {
+    {
         let dir = tempdir().expect("Unable to create tempdir");
         let filename = PathBuf::from("platform.roc");
         let file_path = dir.path().join(filename);
@@ -38,9 +49,7 @@ pub fn generate_bindings(decl_src: &str) -> String {
         dir.close().expect("Unable to close tempdir");
 
         result.expect("had problems loading")
-    };
-
-    rust_glue::emit(&pairs)
+    }
 }
 
 #[allow(dead_code)]
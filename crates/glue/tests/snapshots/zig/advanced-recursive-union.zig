@@ -0,0 +1,176 @@
+
+pub const Rbt = extern struct {
+    default: Job,
+};
+
+pub const Job = extern struct {
+    pointer: ?*anyopaque,
+
+    const Self = @This();
+
+    pub const Payload = extern union {
+        Foo: Job_Foo,
+        Job: Job_Job,
+    };
+
+    pub const Tag = enum(u8) {
+        Foo = 0,
+        Job = 1,
+    };
+
+    // On a 64-bit system, pointers have 3 bits that are unused, and 2 on a 32-bit system.
+    const tag_mask: usize = if (@sizeOf(usize) == 8) 0b111 else 0b11;
+
+    pub fn discriminant(self: Self) Tag {
+        return @intToEnum(Tag, @intCast(u8, @ptrToInt(self.pointer) & tag_mask));
+    }
+
+    fn payloadPointer(self: Self) *Payload {
+        return @intToPtr(*Payload, @ptrToInt(self.pointer) & ~tag_mask);
+    }
+
+    pub fn initFoo(payload: Job_Foo) Self {
+        const payload_pointer = allocRefcounted(Payload);
+
+        payload_pointer.Foo = payload;
+
+        return .{ .pointer = @intToPtr(?*anyopaque, @ptrToInt(payload_pointer) | @enumToInt(Tag.Foo)) };
+    }
+
+    pub fn getFoo(self: Self) Job_Foo {
+        std.debug.assert(self.discriminant() == .Foo);
+
+        return self.payloadPointer().Foo;
+    }
+
+    pub fn initJob(payload: Job_Job) Self {
+        const payload_pointer = allocRefcounted(Payload);
+
+        payload_pointer.Job = payload;
+
+        return .{ .pointer = @intToPtr(?*anyopaque, @ptrToInt(payload_pointer) | @enumToInt(Tag.Job)) };
+    }
+
+    pub fn getJob(self: Self) Job_Job {
+        std.debug.assert(self.discriminant() == .Job);
+
+        return self.payloadPointer().Job;
+    }
+};
+
+pub const Job_Job = extern struct {
+    f0: R1,
+};
+
+pub const R1 = extern struct {
+    command: Command,
+    inputFiles: RocList,
+};
+
+pub const Command = extern struct {
+    f0: R2,
+
+    pub fn initCommand(f0: R2) @This() {
+        return .{ .f0 = f0 };
+    }
+};
+
+pub const R2 = extern struct {
+    tool: Tool,
+};
+
+pub const Tool = extern struct {
+    payload: Payload,
+    tag: Tag,
+
+    const Self = @This();
+
+
+    pub const Payload = extern union {
+        FromJob: R3,
+        SystemTool: R4,
+    };
+
+    pub const Tag = enum(u8) {
+        FromJob = 0,
+        SystemTool = 1,
+    };
+
+    pub fn discriminant(self: Self) Tag {
+        return self.tag;
+    }
+
+    pub fn initFromJob(payload: R3) Self {
+        return .{ .payload = .{ .FromJob = payload }, .tag = .FromJob };
+    }
+
+    pub fn getFromJob(self: Self) R3 {
+        std.debug.assert(self.tag == .FromJob);
+
+        return self.payload.FromJob;
+    }
+
+    pub fn initSystemTool(payload: R4) Self {
+        return .{ .payload = .{ .SystemTool = payload }, .tag = .SystemTool };
+    }
+
+    pub fn getSystemTool(self: Self) R4 {
+        std.debug.assert(self.tag == .SystemTool);
+
+        return self.payload.SystemTool;
+    }
+};
+
+pub const R4 = extern struct {
+    name: RocStr,
+    num: u32,
+};
+
+pub const R3 = extern struct {
+    job: Job,
+    num: u32,
+};
+
+pub const Job_Foo = extern struct {
+    f0: RocStr,
+};
+
+comptime {
+    switch (builtin.cpu.arch) {
+        .arm, .wasm32, .i386 => {
+            checkLayout(Rbt, 4, 4);
+            checkLayout(Job, 4, 4);
+            checkLayout(Job_Job, 32, 4);
+            checkLayout(R1, 32, 4);
+            checkLayout(Command, 20, 4);
+            checkLayout(R2, 20, 4);
+            checkLayout(Tool, 20, 4);
+            checkLayout(R4, 16, 4);
+            checkLayout(R3, 8, 4);
+            checkLayout(Job_Foo, 12, 4);
+        },
+        .aarch64, .x86_64 => {
+            checkLayout(Rbt, 8, 8);
+            checkLayout(Job, 8, 8);
+            checkLayout(Job_Job, 64, 8);
+            checkLayout(R1, 64, 8);
+            checkLayout(Command, 40, 8);
+            checkLayout(R2, 40, 8);
+            checkLayout(Tool, 40, 8);
+            checkLayout(R4, 32, 8);
+            checkLayout(R3, 16, 8);
+            checkLayout(Job_Foo, 24, 8);
+        },
+        else => {},
+    }
+}
+
+pub extern fn roc__mainForHost_1_exposed_generic(ret: *Rbt) void;
+
+pub fn mainForHost() Rbt {
+    var ret: Rbt = undefined;
+
+    roc__mainForHost_1_exposed_generic(&ret);
+
+    return ret;
+}
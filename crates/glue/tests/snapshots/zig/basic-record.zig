@@ -0,0 +1,24 @@
+
+pub const MyRcd = extern struct {
+    b: u128,
+    a: u64,
+};
+
+comptime {
+    switch (builtin.cpu.arch) {
+        .arm, .aarch64, .wasm32, .i386, .x86_64 => {
+            checkLayout(MyRcd, 32, 16);
+        },
+        else => {},
+    }
+}
+
+pub extern fn roc__mainForHost_1_exposed_generic(ret: *MyRcd) void;
+
+pub fn mainForHost() MyRcd {
+    var ret: MyRcd = undefined;
+
+    roc__mainForHost_1_exposed_generic(&ret);
+
+    return ret;
+}
@@ -0,0 +1,90 @@
+
+pub const Expr = extern struct {
+    pointer: ?*anyopaque,
+
+    const Self = @This();
+
+    pub const Payload = extern union {
+        Concat: Expr_Concat,
+        String: Expr_String,
+    };
+
+    pub const Tag = enum(u8) {
+        Concat = 0,
+        String = 1,
+    };
+
+    // On a 64-bit system, pointers have 3 bits that are unused, and 2 on a 32-bit system.
+    const tag_mask: usize = if (@sizeOf(usize) == 8) 0b111 else 0b11;
+
+    pub fn discriminant(self: Self) Tag {
+        return @intToEnum(Tag, @intCast(u8, @ptrToInt(self.pointer) & tag_mask));
+    }
+
+    fn payloadPointer(self: Self) *Payload {
+        return @intToPtr(*Payload, @ptrToInt(self.pointer) & ~tag_mask);
+    }
+
+    pub fn initConcat(payload: Expr_Concat) Self {
+        const payload_pointer = allocRefcounted(Payload);
+
+        payload_pointer.Concat = payload;
+
+        return .{ .pointer = @intToPtr(?*anyopaque, @ptrToInt(payload_pointer) | @enumToInt(Tag.Concat)) };
+    }
+
+    pub fn getConcat(self: Self) Expr_Concat {
+        std.debug.assert(self.discriminant() == .Concat);
+
+        return self.payloadPointer().Concat;
+    }
+
+    pub fn initString(payload: Expr_String) Self {
+        const payload_pointer = allocRefcounted(Payload);
+
+        payload_pointer.String = payload;
+
+        return .{ .pointer = @intToPtr(?*anyopaque, @ptrToInt(payload_pointer) | @enumToInt(Tag.String)) };
+    }
+
+    pub fn getString(self: Self) Expr_String {
+        std.debug.assert(self.discriminant() == .String);
+
+        return self.payloadPointer().String;
+    }
+};
+
+pub const Expr_String = extern struct {
+    f0: RocStr,
+};
+
+pub const Expr_Concat = extern struct {
+    f0: Expr,
+    f1: Expr,
+};
+
+comptime {
+    switch (builtin.cpu.arch) {
+        .arm, .wasm32, .i386 => {
+            checkLayout(Expr, 4, 4);
+            checkLayout(Expr_String, 12, 4);
+            checkLayout(Expr_Concat, 8, 4);
+        },
+        .aarch64, .x86_64 => {
+            checkLayout(Expr, 8, 8);
+            checkLayout(Expr_String, 24, 8);
+            checkLayout(Expr_Concat, 16, 8);
+        },
+        else => {},
+    }
+}
+
+pub extern fn roc__mainForHost_1_exposed_generic(ret: *Expr) void;
+
+pub fn mainForHost() Expr {
+    var ret: Expr = undefined;
+
+    roc__mainForHost_1_exposed_generic(&ret);
+
+    return ret;
+}
@@ -0,0 +1,10 @@
+
+pub extern fn roc__mainForHost_1_exposed_generic(ret: *RocDict) void;
+
+pub fn mainForHost() RocDict {
+    var ret: RocDict = undefined;
+
+    roc__mainForHost_1_exposed_generic(&ret);
+
+    return ret;
+}
@@ -0,0 +1,25 @@
+
+pub const MyEnum = enum(u8) {
+    Bar = 0,
+    Baz = 1,
+    Foo = 2,
+};
+
+comptime {
+    switch (builtin.cpu.arch) {
+        .arm, .aarch64, .wasm32, .i386, .x86_64 => {
+            checkLayout(MyEnum, 1, 1);
+        },
+        else => {},
+    }
+}
+
+pub extern fn roc__mainForHost_1_exposed_generic(ret: *MyEnum) void;
+
+pub fn mainForHost() MyEnum {
+    var ret: MyEnum = undefined;
+
+    roc__mainForHost_1_exposed_generic(&ret);
+
+    return ret;
+}
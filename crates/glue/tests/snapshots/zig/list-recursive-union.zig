@@ -0,0 +1,136 @@
+
+pub const Rbt = extern struct {
+    default: Job,
+};
+
+pub const Job = extern struct {
+    pointer: ?*anyopaque,
+
+    const Self = @This();
+
+    pub const Payload = extern union {
+        Blah: Job_Blah,
+        Job: Job_Job,
+    };
+
+    pub const Tag = enum(u8) {
+        Blah = 0,
+        Job = 1,
+    };
+
+    // On a 64-bit system, pointers have 3 bits that are unused, and 2 on a 32-bit system.
+    const tag_mask: usize = if (@sizeOf(usize) == 8) 0b111 else 0b11;
+
+    pub fn discriminant(self: Self) Tag {
+        return @intToEnum(Tag, @intCast(u8, @ptrToInt(self.pointer) & tag_mask));
+    }
+
+    fn payloadPointer(self: Self) *Payload {
+        return @intToPtr(*Payload, @ptrToInt(self.pointer) & ~tag_mask);
+    }
+
+    pub fn initBlah(payload: Job_Blah) Self {
+        const payload_pointer = allocRefcounted(Payload);
+
+        payload_pointer.Blah = payload;
+
+        return .{ .pointer = @intToPtr(?*anyopaque, @ptrToInt(payload_pointer) | @enumToInt(Tag.Blah)) };
+    }
+
+    pub fn getBlah(self: Self) Job_Blah {
+        std.debug.assert(self.discriminant() == .Blah);
+
+        return self.payloadPointer().Blah;
+    }
+
+    pub fn initJob(payload: Job_Job) Self {
+        const payload_pointer = allocRefcounted(Payload);
+
+        payload_pointer.Job = payload;
+
+        return .{ .pointer = @intToPtr(?*anyopaque, @ptrToInt(payload_pointer) | @enumToInt(Tag.Job)) };
+    }
+
+    pub fn getJob(self: Self) Job_Job {
+        std.debug.assert(self.discriminant() == .Job);
+
+        return self.payloadPointer().Job;
+    }
+};
+
+pub const Job_Job = extern struct {
+    f0: R1,
+};
+
+pub const R1 = extern struct {
+    command: Command,
+    inputFiles: RocList,
+    job: RocList,
+};
+
+pub const Command = extern struct {
+    f0: R2,
+
+    pub fn initCommand(f0: R2) @This() {
+        return .{ .f0 = f0 };
+    }
+};
+
+pub const R2 = extern struct {
+    args: RocList,
+    tool: Tool,
+};
+
+pub const Tool = extern struct {
+    f0: R3,
+
+    pub fn initSystemTool(f0: R3) @This() {
+        return .{ .f0 = f0 };
+    }
+};
+
+pub const R3 = extern struct {
+    name: RocStr,
+};
+
+pub const Job_Blah = extern struct {
+    f0: RocStr,
+};
+
+comptime {
+    switch (builtin.cpu.arch) {
+        .arm, .wasm32, .i386 => {
+            checkLayout(Rbt, 4, 4);
+            checkLayout(Job, 4, 4);
+            checkLayout(Job_Job, 48, 4);
+            checkLayout(R1, 48, 4);
+            checkLayout(Command, 24, 4);
+            checkLayout(R2, 24, 4);
+            checkLayout(Tool, 12, 4);
+            checkLayout(R3, 12, 4);
+            checkLayout(Job_Blah, 12, 4);
+        },
+        .aarch64, .x86_64 => {
+            checkLayout(Rbt, 8, 8);
+            checkLayout(Job, 8, 8);
+            checkLayout(Job_Job, 96, 8);
+            checkLayout(R1, 96, 8);
+            checkLayout(Command, 48, 8);
+            checkLayout(R2, 48, 8);
+            checkLayout(Tool, 24, 8);
+            checkLayout(R3, 24, 8);
+            checkLayout(Job_Blah, 24, 8);
+        },
+        else => {},
+    }
+}
+
+pub extern fn roc__mainForHost_1_exposed_generic(ret: *Rbt) void;
+
+pub fn mainForHost() Rbt {
+    var ret: Rbt = undefined;
+
+    roc__mainForHost_1_exposed_generic(&ret);
+
+    return ret;
+}
@@ -0,0 +1,47 @@
+
+pub const Combined = extern struct {
+    s1: DepStr1,
+    s2: DepStr2,
+};
+
+pub const DepStr2 = extern struct {
+    f0: RocStr,
+
+    pub fn initR(f0: RocStr) @This() {
+        return .{ .f0 = f0 };
+    }
+};
+
+pub const DepStr1 = extern struct {
+    f0: RocStr,
+
+    pub fn initS(f0: RocStr) @This() {
+        return .{ .f0 = f0 };
+    }
+};
+
+comptime {
+    switch (builtin.cpu.arch) {
+        .arm, .wasm32, .i386 => {
+            checkLayout(Combined, 24, 4);
+            checkLayout(DepStr2, 12, 4);
+            checkLayout(DepStr1, 12, 4);
+        },
+        .aarch64, .x86_64 => {
+            checkLayout(Combined, 48, 8);
+            checkLayout(DepStr2, 24, 8);
+            checkLayout(DepStr1, 24, 8);
+        },
+        else => {},
+    }
+}
+
+pub extern fn roc__mainForHost_1_exposed_generic(ret: *Combined) void;
+
+pub fn mainForHost() Combined {
+    var ret: Combined = undefined;
+
+    roc__mainForHost_1_exposed_generic(&ret);
+
+    return ret;
+}
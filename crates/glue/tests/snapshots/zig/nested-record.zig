@@ -0,0 +1,43 @@
+
+pub const Outer = switch (builtin.cpu.arch) {
+    .arm, .wasm32, .i386 => extern struct {
+        x: Inner,
+        y: RocStr,
+        z: RocList,
+    },
+    .aarch64, .x86_64 => extern struct {
+        y: RocStr,
+        z: RocList,
+        x: Inner,
+    },
+    else => @compileError("Roc does not support this target architecture"),
+};
+
+pub const Inner = extern struct {
+    b: f32,
+    a: u16,
+};
+
+comptime {
+    switch (builtin.cpu.arch) {
+        .arm, .wasm32, .i386 => {
+            checkLayout(Outer, 32, 4);
+            checkLayout(Inner, 8, 4);
+        },
+        .aarch64, .x86_64 => {
+            checkLayout(Outer, 56, 8);
+            checkLayout(Inner, 8, 4);
+        },
+        else => {},
+    }
+}
+
+pub extern fn roc__mainForHost_1_exposed_generic(ret: *Outer) void;
+
+pub fn mainForHost() Outer {
+    var ret: Outer = undefined;
+
+    roc__mainForHost_1_exposed_generic(&ret);
+
+    return ret;
+}
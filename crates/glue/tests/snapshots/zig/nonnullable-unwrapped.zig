@@ -0,0 +1,55 @@
+
+pub const StrRoseTree = extern struct {
+    pointer: ?*anyopaque,
+
+    const Self = @This();
+
+    pub const Payload = extern union {
+        Tree: StrRoseTree_Tree,
+    };
+
+    fn payloadPointer(self: Self) *Payload {
+        return @ptrCast(*Payload, @alignCast(@alignOf(Payload), self.pointer.?));
+    }
+
+    pub fn initTree(payload: StrRoseTree_Tree) Self {
+        const payload_pointer = allocRefcounted(Payload);
+
+        payload_pointer.Tree = payload;
+
+        return .{ .pointer = payload_pointer };
+    }
+
+    pub fn getTree(self: Self) StrRoseTree_Tree {
+        return self.payloadPointer().Tree;
+    }
+};
+
+pub const StrRoseTree_Tree = extern struct {
+    f0: RocStr,
+    f1: RocList,
+};
+
+comptime {
+    switch (builtin.cpu.arch) {
+        .arm, .wasm32, .i386 => {
+            checkLayout(StrRoseTree, 4, 4);
+            checkLayout(StrRoseTree_Tree, 24, 4);
+        },
+        .aarch64, .x86_64 => {
+            checkLayout(StrRoseTree, 8, 8);
+            checkLayout(StrRoseTree_Tree, 48, 8);
+        },
+        else => {},
+    }
+}
+
+pub extern fn roc__mainForHost_1_exposed_generic(ret: *StrRoseTree) void;
+
+pub fn mainForHost() StrRoseTree {
+    var ret: StrRoseTree = undefined;
+
+    roc__mainForHost_1_exposed_generic(&ret);
+
+    return ret;
+}
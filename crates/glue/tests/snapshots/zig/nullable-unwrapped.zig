@@ -0,0 +1,66 @@
+
+pub const StrConsList = extern struct {
+    pointer: ?*anyopaque,
+
+    const Self = @This();
+
+    pub const Tag = enum(u8) {
+        Cons = 0,
+        Nil = 1,
+    };
+
+    pub fn discriminant(self: Self) Tag {
+        if (self.pointer == null) {
+            return .Nil;
+        } else {
+            return .Cons;
+        }
+    }
+
+    pub fn initNil() Self {
+        return .{ .pointer = null };
+    }
+
+    pub fn initCons(payload: StrConsList_Cons) Self {
+        const payload_pointer = allocRefcounted(StrConsList_Cons);
+
+        payload_pointer.* = payload;
+
+        return .{ .pointer = payload_pointer };
+    }
+
+    pub fn getCons(self: Self) StrConsList_Cons {
+        std.debug.assert(self.pointer != null);
+
+        return @ptrCast(*StrConsList_Cons, @alignCast(@alignOf(StrConsList_Cons), self.pointer.?)).*;
+    }
+};
+
+pub const StrConsList_Cons = extern struct {
+    f0: RocStr,
+    f1: StrConsList,
+};
+
+comptime {
+    switch (builtin.cpu.arch) {
+        .arm, .wasm32, .i386 => {
+            checkLayout(StrConsList, 4, 4);
+            checkLayout(StrConsList_Cons, 16, 4);
+        },
+        .aarch64, .x86_64 => {
+            checkLayout(StrConsList, 8, 8);
+            checkLayout(StrConsList_Cons, 32, 8);
+        },
+        else => {},
+    }
+}
+
+pub extern fn roc__mainForHost_1_exposed_generic(ret: *StrConsList) void;
+
+pub fn mainForHost() StrConsList {
+    var ret: StrConsList = undefined;
+
+    roc__mainForHost_1_exposed_generic(&ret);
+
+    return ret;
+}
@@ -0,0 +1,99 @@
+
+pub const StrFingerTree = extern struct {
+    pointer: ?*anyopaque,
+
+    const Self = @This();
+
+    pub const Payload = extern union {
+        More: StrFingerTree_More,
+        Single: StrFingerTree_Single,
+    };
+
+    pub const Tag = enum(u8) {
+        Empty = 0,
+        More = 1,
+        Single = 2,
+    };
+
+    // On a 64-bit system, pointers have 3 bits that are unused, and 2 on a 32-bit system.
+    const tag_mask: usize = if (@sizeOf(usize) == 8) 0b111 else 0b11;
+
+    pub fn discriminant(self: Self) Tag {
+        if (self.pointer == null) {
+            return .Empty;
+        }
+
+        return @intToEnum(Tag, @intCast(u8, @ptrToInt(self.pointer) & tag_mask));
+    }
+
+    fn payloadPointer(self: Self) *Payload {
+        return @intToPtr(*Payload, @ptrToInt(self.pointer) & ~tag_mask);
+    }
+
+    pub fn initEmpty() Self {
+        return .{ .pointer = null };
+    }
+
+    pub fn initMore(payload: StrFingerTree_More) Self {
+        const payload_pointer = allocRefcounted(Payload);
+
+        payload_pointer.More = payload;
+
+        return .{ .pointer = @intToPtr(?*anyopaque, @ptrToInt(payload_pointer) | @enumToInt(Tag.More)) };
+    }
+
+    pub fn getMore(self: Self) StrFingerTree_More {
+        std.debug.assert(self.discriminant() == .More);
+
+        return self.payloadPointer().More;
+    }
+
+    pub fn initSingle(payload: StrFingerTree_Single) Self {
+        const payload_pointer = allocRefcounted(Payload);
+
+        payload_pointer.Single = payload;
+
+        return .{ .pointer = @intToPtr(?*anyopaque, @ptrToInt(payload_pointer) | @enumToInt(Tag.Single)) };
+    }
+
+    pub fn getSingle(self: Self) StrFingerTree_Single {
+        std.debug.assert(self.discriminant() == .Single);
+
+        return self.payloadPointer().Single;
+    }
+};
+
+pub const StrFingerTree_Single = extern struct {
+    f0: RocStr,
+};
+
+pub const StrFingerTree_More = extern struct {
+    f0: RocStr,
+    f1: StrFingerTree,
+};
+
+comptime {
+    switch (builtin.cpu.arch) {
+        .arm, .wasm32, .i386 => {
+            checkLayout(StrFingerTree, 4, 4);
+            checkLayout(StrFingerTree_Single, 12, 4);
+            checkLayout(StrFingerTree_More, 16, 4);
+        },
+        .aarch64, .x86_64 => {
+            checkLayout(StrFingerTree, 8, 8);
+            checkLayout(StrFingerTree_Single, 24, 8);
+            checkLayout(StrFingerTree_More, 32, 8);
+        },
+        else => {},
+    }
+}
+
+pub extern fn roc__mainForHost_1_exposed_generic(ret: *StrFingerTree) void;
+
+pub fn mainForHost() StrFingerTree {
+    var ret: StrFingerTree = undefined;
+
+    roc__mainForHost_1_exposed_generic(&ret);
+
+    return ret;
+}
@@ -0,0 +1,10 @@
+
+pub extern fn roc__mainForHost_1_exposed_generic(ret: *RocSet) void;
+
+pub fn mainForHost() RocSet {
+    var ret: RocSet = undefined;
+
+    roc__mainForHost_1_exposed_generic(&ret);
+
+    return ret;
+}
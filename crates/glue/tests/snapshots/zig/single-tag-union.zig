@@ -0,0 +1,8 @@
+
+pub extern fn roc__mainForHost_1_exposed_generic(ret: *u8) void;
+
+pub fn mainForHost() void {
+    var ret: u8 = undefined;
+
+    roc__mainForHost_1_exposed_generic(&ret);
+}
@@ -0,0 +1,84 @@
+
+pub const NonRecursive = extern struct {
+    payload: Payload,
+    tag: Tag,
+
+    const Self = @This();
+
+
+    pub const Payload = extern union {
+        Bar: i64,
+        Blah: i32,
+        Foo: RocStr,
+    };
+
+    pub const Tag = enum(u8) {
+        Bar = 0,
+        Baz = 1,
+        Blah = 2,
+        Foo = 3,
+    };
+
+    pub fn discriminant(self: Self) Tag {
+        return self.tag;
+    }
+
+    pub fn initBar(payload: i64) Self {
+        return .{ .payload = .{ .Bar = payload }, .tag = .Bar };
+    }
+
+    pub fn getBar(self: Self) i64 {
+        std.debug.assert(self.tag == .Bar);
+
+        return self.payload.Bar;
+    }
+
+    pub fn initBaz() Self {
+        return .{ .payload = undefined, .tag = .Baz };
+    }
+
+    pub fn initBlah(payload: i32) Self {
+        return .{ .payload = .{ .Blah = payload }, .tag = .Blah };
+    }
+
+    pub fn getBlah(self: Self) i32 {
+        std.debug.assert(self.tag == .Blah);
+
+        return self.payload.Blah;
+    }
+
+    pub fn initFoo(payload: RocStr) Self {
+        return .{ .payload = .{ .Foo = payload }, .tag = .Foo };
+    }
+
+    pub fn getFoo(self: Self) RocStr {
+        std.debug.assert(self.tag == .Foo);
+
+        return self.payload.Foo;
+    }
+};
+
+comptime {
+    switch (builtin.cpu.arch) {
+        .arm, .wasm32 => {
+            checkLayout(NonRecursive, 24, 8);
+        },
+        .aarch64, .x86_64 => {
+            checkLayout(NonRecursive, 32, 8);
+        },
+        .i386 => {
+            checkLayout(NonRecursive, 16, 4);
+        },
+        else => {},
+    }
+}
+
+pub extern fn roc__mainForHost_1_exposed_generic(ret: *NonRecursive) void;
+
+pub fn mainForHost() NonRecursive {
+    var ret: NonRecursive = undefined;
+
+    roc__mainForHost_1_exposed_generic(&ret);
+
+    return ret;
+}
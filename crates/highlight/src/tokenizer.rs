@@ -33,6 +33,7 @@ pub enum Token {
     KeywordEffects = 0b_0011_0000,
     KeywordPlatform = 0b_0011_0001,
     KeywordRequires = 0b_0011_0010,
+    KeywordDbg = 0b_0011_0100,
 
     Comma = 0b_0100_0000,
     Colon = 0b_0100_0001,
@@ -428,6 +429,7 @@ fn lex_ident(uppercase: bool, bytes: &[u8]) -> (Token, usize) {
         b"effects" => Token::KeywordEffects,
         b"platform" => Token::KeywordPlatform,
         b"requires" => Token::KeywordRequires,
+        b"dbg" => Token::KeywordDbg,
         ident => {
             if ident.contains(&b'_') {
                 Token::MalformedIdent
@@ -0,0 +1,417 @@
+//! Loading and type checking a single document, and answering questions about the result.
+//!
+//! Lines and columns in here are the ones the Language Server Protocol uses: columns count UTF-16
+//! code units, not bytes.
+
+use bumpalo::Bump;
+use roc_can::expr::Declarations;
+use roc_can::symbol_index::{Occurrence, SymbolIndex};
+use roc_collections::all::MutMap;
+use roc_load::{ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, Threading};
+use roc_module::symbol::{Interns, ModuleId};
+use roc_parse::parser::{Parser, SyntaxError};
+use roc_parse::state::State;
+use roc_region::all::{LineColumn, LineColumnRegion, LineInfo, Position, Region};
+use roc_reporting::report::{
+    can_problem, type_problem, RenderTarget, Report, RocDocAllocator, Severity,
+};
+use roc_target::TargetInfo;
+use roc_types::pretty_print::{name_and_print_var, DebugPrint};
+use roc_types::subs::Subs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub region: LineColumnRegion,
+    pub severity: Severity,
+    pub title: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hover {
+    pub region: LineColumnRegion,
+    pub type_str: String,
+}
+
+/// A region in some file; not necessarily the document that was analyzed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub path: PathBuf,
+    pub region: LineColumnRegion,
+}
+
+/// The result of checking one version of a document's source.
+pub struct Analysis {
+    positions: Positions,
+    diagnostics: Vec<Diagnostic>,
+    typed: Option<TypedModule>,
+}
+
+/// What we keep around of a [LoadedModule] to answer hover and navigation requests.
+struct TypedModule {
+    home: ModuleId,
+    interns: Interns,
+    subs: Subs,
+    declarations: Declarations,
+    symbol_index: SymbolIndex,
+    sources: MutMap<ModuleId, (PathBuf, Box<str>)>,
+}
+
+impl Analysis {
+    /// Parses, canonicalizes and type checks `source`, as if it were the contents of `path`.
+    /// Imports are resolved relative to the directory containing `path`.
+    ///
+    /// With a `cache_dir`, the solved types of imported modules are kept there, so that checking
+    /// the next edit of the document only has to solve the document itself again.
+    pub fn new(path: &Path, source: &str, cache_dir: Option<&Path>) -> Self {
+        let arena = Bump::new();
+        let positions = Positions::new(source);
+        let src_dir = path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        let load_config = LoadConfig {
+            target_info: TargetInfo::default_x86_64(),
+            render: RenderTarget::Generic,
+            threading: Threading::AllAvailable,
+            exec_mode: ExecutionMode::Check,
+            cache_dir: cache_dir.map(Path::to_path_buf),
+        };
+
+        let loaded = roc_load::load_and_typecheck_from_str(
+            &arena,
+            path.to_path_buf(),
+            source,
+            src_dir,
+            Default::default(),
+            load_config,
+        );
+
+        match loaded {
+            Ok(loaded) => {
+                let (diagnostics, typed) = diagnose_loaded(&arena, loaded, source, &positions);
+
+                Analysis {
+                    positions,
+                    diagnostics,
+                    typed,
+                }
+            }
+            Err(problem) => {
+                let diagnostic = diagnose_loading_problem(&arena, problem, source, &positions);
+
+                Analysis {
+                    positions,
+                    diagnostics: vec![diagnostic],
+                    typed: None,
+                }
+            }
+        }
+    }
+
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// The type of the innermost expression or pattern at the given line and column, if any.
+    pub fn hover(&mut self, line_column: LineColumn) -> Option<Hover> {
+        let position = self.positions.offset(line_column)?;
+        let typed = self.typed.as_mut()?;
+
+        let (region, var) = roc_can::traverse::find_closest_type_at(position, &typed.declarations)?;
+
+        let type_str = name_and_print_var(
+            var,
+            &mut typed.subs,
+            typed.home,
+            &typed.interns,
+            DebugPrint::NOTHING,
+        );
+
+        Some(Hover {
+            region: self.positions.region(region),
+            type_str,
+        })
+    }
+
+    /// Where the value at the given line and column is defined.
+    pub fn definition(&self, line_column: LineColumn) -> Option<Location> {
+        let typed = self.typed.as_ref()?;
+        let symbol = typed
+            .symbol_index
+            .symbol_at(typed.home, self.positions.offset(line_column)?)?;
+
+        typed.location(typed.symbol_index.definition(symbol)?)
+    }
+
+    /// Everywhere the value at the given line and column is used, and optionally where it is
+    /// defined as well.
+    pub fn references(&self, line_column: LineColumn, include_definition: bool) -> Vec<Location> {
+        let typed = match self.typed.as_ref() {
+            Some(typed) => typed,
+            None => return Vec::new(),
+        };
+
+        let symbol = match self
+            .positions
+            .offset(line_column)
+            .and_then(|position| typed.symbol_index.symbol_at(typed.home, position))
+        {
+            Some(symbol) => symbol,
+            None => return Vec::new(),
+        };
+
+        let definition = typed
+            .symbol_index
+            .definition(symbol)
+            .filter(|_| include_definition);
+
+        definition
+            .iter()
+            .chain(typed.symbol_index.references(symbol))
+            .filter_map(|occurrence| typed.location(*occurrence))
+            .collect()
+    }
+}
+
+/// Converts between positions in a source and LSP lines and columns. [LineInfo] counts columns in
+/// bytes, but LSP counts them in UTF-16 code units.
+struct Positions {
+    line_info: LineInfo,
+    lines: Vec<Box<str>>,
+}
+
+impl Positions {
+    fn new(source: &str) -> Self {
+        Positions {
+            line_info: LineInfo::new(source),
+            lines: source.split('\n').map(Box::from).collect(),
+        }
+    }
+
+    fn line(&self, line: u32) -> &str {
+        self.lines.get(line as usize).map_or("", |line| line)
+    }
+
+    fn line_column(&self, position: Position) -> LineColumn {
+        let LineColumn { line, column } = self.line_info.convert_pos(position);
+
+        let column = self
+            .line(line)
+            .char_indices()
+            .take_while(|(index, _)| *index < column as usize)
+            .map(|(_, c)| c.len_utf16() as u32)
+            .sum();
+
+        LineColumn { line, column }
+    }
+
+    fn region(&self, region: Region) -> LineColumnRegion {
+        LineColumnRegion {
+            start: self.line_column(region.start()),
+            end: self.line_column(region.end()),
+        }
+    }
+
+    /// The position at an LSP line and column, if that line is in the source
+    fn offset(&self, line_column: LineColumn) -> Option<Position> {
+        // LineInfo panics on lines past the end of the document
+        let line = self.lines.get(line_column.line as usize)?;
+
+        let mut code_units = 0;
+        let mut column = line.len();
+
+        for (index, c) in line.char_indices() {
+            if code_units >= line_column.column {
+                column = index;
+                break;
+            }
+
+            code_units += c.len_utf16() as u32;
+        }
+
+        Some(self.line_info.convert_line_column(LineColumn {
+            line: line_column.line,
+            column: column as u32,
+        }))
+    }
+}
+
+impl TypedModule {
+    fn location(&self, occurrence: Occurrence) -> Option<Location> {
+        let (path, src) = self.sources.get(&occurrence.module_id)?;
+
+        Some(Location {
+            path: path.clone(),
+            region: Positions::new(src).region(occurrence.region),
+        })
+    }
+}
+
+fn diagnose_loaded(
+    arena: &Bump,
+    mut loaded: LoadedModule,
+    source: &str,
+    positions: &Positions,
+) -> (Vec<Diagnostic>, Option<TypedModule>) {
+    let home = loaded.module_id;
+    let mut diagnostics = Vec::new();
+
+    let syntax_problems = loaded.syntax_problems.remove(&home).unwrap_or_default();
+
+    if !syntax_problems.is_empty() {
+        // These also come rendered, so we parse again to find out where they are.
+        let problem_positions = recovered_problem_positions(arena, source);
+
+        for (index, message) in syntax_problems.into_iter().enumerate() {
+            let position = problem_positions.get(index).copied().unwrap_or_default();
+
+            diagnostics.push(parse_diagnostic(message, position, positions));
+        }
+    }
+
+    // Problems in imported modules are reported when those documents are opened.
+    let can_problems = loaded.can_problems.remove(&home).unwrap_or_default();
+    let type_problems = loaded.type_problems.remove(&home).unwrap_or_default();
+
+    if let Some((path, src)) = loaded.sources.get(&home) {
+        let src_lines: Vec<&str> = src.split('\n').collect();
+        let alloc = RocDocAllocator::new(&src_lines, home, &loaded.interns);
+
+        for problem in can_problems {
+            let region = problem.region().unwrap_or_else(Region::zero);
+            let report = can_problem(&alloc, &positions.line_info, path.clone(), problem);
+
+            diagnostics.push(to_diagnostic(report, region, positions, &alloc));
+        }
+
+        for problem in type_problems {
+            let region = problem.region().unwrap_or_else(Region::zero);
+
+            if let Some(report) = type_problem(&alloc, &positions.line_info, path.clone(), problem)
+            {
+                diagnostics.push(to_diagnostic(report, region, positions, &alloc));
+            }
+        }
+    }
+
+    let typed = loaded
+        .declarations_by_id
+        .remove(&home)
+        .map(|declarations| TypedModule {
+            home,
+            interns: loaded.interns,
+            subs: loaded.solved.into_inner(),
+            declarations,
+            symbol_index: loaded.symbol_index,
+            sources: loaded.sources,
+        });
+
+    (diagnostics, typed)
+}
+
+fn to_diagnostic<'a>(
+    report: Report<'a>,
+    region: Region,
+    positions: &Positions,
+    alloc: &'a RocDocAllocator<'a>,
+) -> Diagnostic {
+    let Report {
+        title,
+        doc,
+        severity,
+        filename,
+    } = report;
+
+    // The title is reported separately, so leave it out of the rendered message.
+    let report = Report {
+        title: String::new(),
+        doc,
+        severity,
+        filename,
+    };
+
+    let mut message = String::new();
+    report.render_ci(&mut message, alloc);
+
+    Diagnostic {
+        region: positions.region(region),
+        severity,
+        title,
+        message: message.trim().to_string(),
+    }
+}
+
+fn diagnose_loading_problem(
+    arena: &Bump,
+    problem: LoadingProblem,
+    source: &str,
+    positions: &Positions,
+) -> Diagnostic {
+    let message = match problem {
+        LoadingProblem::FormattedReport(report) => report,
+        other => format!("{:?}", other),
+    };
+
+    // Loading only hands us an already-rendered report, so we parse again to find out where.
+    let position = parse_problem_position(arena, source).unwrap_or_default();
+
+    parse_diagnostic(message, position, positions)
+}
+
+fn parse_diagnostic(message: String, position: Position, positions: &Positions) -> Diagnostic {
+    let start = positions.line_column(position);
+
+    Diagnostic {
+        region: LineColumnRegion::from_pos(start),
+        severity: Severity::RuntimeError,
+        title: "PARSE PROBLEM".to_string(),
+        message: message.trim().to_string(),
+    }
+}
+
+fn parse_problem_position(arena: &Bump, source: &str) -> Option<Position> {
+    let state = State::new(source.as_bytes());
+
+    let (_, state) = match roc_parse::module::parse_header(arena, state) {
+        Ok(success) => success,
+        // The header starts the file, so point at the start of the file.
+        Err(_) => return Some(Position::zero()),
+    };
+
+    match roc_parse::module::module_defs().parse(arena, state) {
+        Ok(_) => None,
+        Err((_, problem, _)) => syntax_problem_position(&problem),
+    }
+}
+
+/// The positions of the syntax errors that loading recovered from, in the order they were found
+fn recovered_problem_positions(arena: &Bump, source: &str) -> Vec<Position> {
+    let state = State::new(source.as_bytes());
+
+    let (_, state) = match roc_parse::module::parse_header(arena, state) {
+        Ok(success) => success,
+        Err(_) => return Vec::new(),
+    };
+
+    let (_, problems) = roc_parse::module::module_defs_with_recovery(arena, state);
+
+    problems
+        .iter()
+        .map(|problem| syntax_problem_position(problem).unwrap_or_default())
+        .collect()
+}
+
+fn syntax_problem_position(problem: &SyntaxError) -> Option<Position> {
+    match problem {
+        SyntaxError::Expr(problem, _) => Some(problem.get_position()),
+        SyntaxError::NotEndOfFile(position) => Some(*position),
+        SyntaxError::Unexpected(region)
+        | SyntaxError::Eof(region)
+        | SyntaxError::ReservedKeyword(region)
+        | SyntaxError::ArgumentsBeforeEquals(region) => Some(region.start()),
+        _ => None,
+    }
+}
@@ -0,0 +1,23 @@
+//! A language server for Roc, which `roc lsp` runs over stdin and stdout.
+//!
+//! Every time a document is opened or changed, it gets loaded and type checked again (imports are
+//! resolved from disk, relative to the document), and any problems are published as diagnostics.
+//! The solved types of imported modules are cached, so an edit only re-solves the modules that
+//! changed. Hovering shows the type of the innermost expression under the cursor.
+pub mod analysis;
+pub mod rpc;
+pub mod server;
+
+use std::io;
+use std::path::PathBuf;
+
+pub use server::Server;
+
+/// Serve the Language Server Protocol over stdin and stdout, until the client asks us to exit.
+/// Returns the exit code the protocol asks for.
+pub fn run_stdio(cache_dir: Option<PathBuf>) -> io::Result<i32> {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+
+    Server::new(stdout.lock(), cache_dir).run(&mut stdin.lock())
+}
@@ -0,0 +1,136 @@
+//! JSON-RPC 2.0 messages, framed the way the Language Server Protocol expects:
+//! a `Content-Length` header, a blank line, and then exactly that many bytes of JSON.
+
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+
+/// The JSON-RPC error codes the server can respond with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    ParseError = -32700,
+    InvalidRequest = -32600,
+    MethodNotFound = -32601,
+    InvalidParams = -32602,
+    ServerNotInitialized = -32002,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    /// A message the other side expects a response to.
+    Request {
+        id: Value,
+        method: String,
+        params: Value,
+    },
+    /// A message the other side does not expect a response to.
+    Notification { method: String, params: Value },
+    /// A response to a request we sent. The server never sends requests, so these are ignored.
+    Response { id: Value },
+}
+
+impl Message {
+    pub fn from_json(value: Value) -> Result<Self, ErrorCode> {
+        let mut object = match value {
+            Value::Object(object) => object,
+            Value::Null => return Err(ErrorCode::ParseError),
+            _ => return Err(ErrorCode::InvalidRequest),
+        };
+
+        let params = object.remove("params").unwrap_or(Value::Null);
+
+        match (object.remove("id"), object.remove("method")) {
+            (Some(id), Some(Value::String(method))) => Ok(Message::Request { id, method, params }),
+            (None, Some(Value::String(method))) => Ok(Message::Notification { method, params }),
+            (Some(id), None) => Ok(Message::Response { id }),
+            _ => Err(ErrorCode::InvalidRequest),
+        }
+    }
+}
+
+/// Reads the next framed message. Returns `Ok(None)` once the input is exhausted.
+pub fn read_message<R: BufRead>(input: &mut R) -> io::Result<Option<Value>> {
+    let mut content_length = None;
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let header = line.trim_end_matches(&['\r', '\n'][..]);
+
+        if header.is_empty() {
+            if content_length.is_some() {
+                break;
+            } else {
+                // tolerate stray blank lines between messages
+                continue;
+            }
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("Content-Length") {
+                let length = value.trim().parse::<usize>().map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("invalid Content-Length header: {:?}", header),
+                    )
+                })?;
+
+                content_length = Some(length);
+            }
+
+            // Other headers (e.g. Content-Type) carry nothing we need.
+        }
+    }
+
+    let mut content = vec![0; content_length.unwrap_or_default()];
+    input.read_exact(&mut content)?;
+
+    match serde_json::from_slice(&content) {
+        Ok(value) => Ok(Some(value)),
+        // Hand malformed JSON to the caller, so it can respond with a ParseError.
+        Err(_) => Ok(Some(Value::Null)),
+    }
+}
+
+pub fn write_message<W: Write>(output: &mut W, message: &Value) -> io::Result<()> {
+    let content = message.to_string();
+
+    write!(
+        output,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )?;
+    output.flush()
+}
+
+pub fn response(id: Value, result: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "result": result,
+    })
+}
+
+pub fn error_response(id: Value, code: ErrorCode, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {
+            "code": code as i64,
+            "message": message,
+        },
+    })
+}
+
+pub fn notification(method: &str, params: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": method,
+        "params": params,
+    })
+}
@@ -0,0 +1,355 @@
+//! Dispatching Language Server Protocol messages to [Analysis]es of the open documents.
+
+use crate::analysis::{Analysis, Diagnostic, Hover, Location};
+use crate::rpc::{self, ErrorCode, Message};
+use roc_collections::all::MutMap;
+use roc_region::all::{LineColumn, LineColumnRegion};
+use roc_reporting::report::Severity;
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// LSP's `TextDocumentSyncKind.Full`: clients send the whole document on every change.
+const TEXT_DOCUMENT_SYNC_FULL: u8 = 1;
+
+/// LSP's `DiagnosticSeverity`
+const SEVERITY_ERROR: u8 = 1;
+const SEVERITY_WARNING: u8 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lifecycle {
+    Uninitialized,
+    Running,
+    ShuttingDown,
+}
+
+pub struct Server<W: Write> {
+    output: W,
+    lifecycle: Lifecycle,
+    documents: MutMap<String, Analysis>,
+    /// Where the solved types of the modules that documents import are kept between edits
+    cache_dir: Option<PathBuf>,
+}
+
+impl<W: Write> Server<W> {
+    pub fn new(output: W, cache_dir: Option<PathBuf>) -> Self {
+        Server {
+            output,
+            lifecycle: Lifecycle::Uninitialized,
+            documents: MutMap::default(),
+            cache_dir,
+        }
+    }
+
+    /// Handles messages until the client sends `exit` or closes the input.
+    /// Returns the process exit code the protocol asks for.
+    pub fn run<R: BufRead>(&mut self, input: &mut R) -> io::Result<i32> {
+        while let Some(value) = rpc::read_message(input)? {
+            let message = match Message::from_json(value) {
+                Ok(message) => message,
+                Err(code) => {
+                    let response = rpc::error_response(Value::Null, code, "malformed message");
+                    rpc::write_message(&mut self.output, &response)?;
+
+                    continue;
+                }
+            };
+
+            match message {
+                Message::Request { id, method, params } => {
+                    self.handle_request(id, &method, params)?;
+                }
+                Message::Notification { method, params } => {
+                    if method == "exit" {
+                        return Ok(match self.lifecycle {
+                            Lifecycle::ShuttingDown => 0,
+                            _ => 1,
+                        });
+                    }
+
+                    self.handle_notification(&method, params)?;
+                }
+                Message::Response { .. } => {
+                    // we never send requests, so there is nothing to match this up with
+                }
+            }
+        }
+
+        Ok(1)
+    }
+
+    fn handle_request(&mut self, id: Value, method: &str, params: Value) -> io::Result<()> {
+        let response = match (self.lifecycle, method) {
+            (Lifecycle::Uninitialized, "initialize") => {
+                self.lifecycle = Lifecycle::Running;
+
+                rpc::response(id, initialize_result())
+            }
+            (Lifecycle::Uninitialized, _) => rpc::error_response(
+                id,
+                ErrorCode::ServerNotInitialized,
+                "the server has not been initialized yet",
+            ),
+            (_, "shutdown") => {
+                self.lifecycle = Lifecycle::ShuttingDown;
+
+                rpc::response(id, Value::Null)
+            }
+            (Lifecycle::ShuttingDown, _) => {
+                rpc::error_response(id, ErrorCode::InvalidRequest, "the server is shutting down")
+            }
+            (Lifecycle::Running, "textDocument/hover") => match self.hover(&params) {
+                Ok(hover) => rpc::response(id, hover.map(hover_to_json).unwrap_or(Value::Null)),
+                Err(message) => rpc::error_response(id, ErrorCode::InvalidParams, message),
+            },
+            (Lifecycle::Running, "textDocument/definition") => match self.definition(&params) {
+                Ok(location) => {
+                    rpc::response(id, location.map(location_to_json).unwrap_or(Value::Null))
+                }
+                Err(message) => rpc::error_response(id, ErrorCode::InvalidParams, message),
+            },
+            (Lifecycle::Running, "textDocument/references") => match self.references(&params) {
+                Ok(locations) => rpc::response(
+                    id,
+                    Value::Array(locations.into_iter().map(location_to_json).collect()),
+                ),
+                Err(message) => rpc::error_response(id, ErrorCode::InvalidParams, message),
+            },
+            (Lifecycle::Running, _) => rpc::error_response(
+                id,
+                ErrorCode::MethodNotFound,
+                &format!("unsupported method: {}", method),
+            ),
+        };
+
+        rpc::write_message(&mut self.output, &response)
+    }
+
+    fn handle_notification(&mut self, method: &str, params: Value) -> io::Result<()> {
+        if self.lifecycle != Lifecycle::Running {
+            // notifications before initialization (or after shutdown) are dropped
+            return Ok(());
+        }
+
+        match method {
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+
+                if let (Some(uri), Some(text)) =
+                    (document["uri"].as_str(), document["text"].as_str())
+                {
+                    self.update_document(uri, text)?;
+                }
+            }
+            "textDocument/didChange" => {
+                let uri = params["textDocument"]["uri"].as_str();
+
+                // We only advertise full document sync, so the last change holds the whole text.
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+
+                if let (Some(uri), Some(text)) = (uri, text) {
+                    self.update_document(uri, text)?;
+                }
+            }
+            "textDocument/didClose" => {
+                if let Some(uri) = params["textDocument"]["uri"].as_str() {
+                    self.documents.remove(uri);
+
+                    // Clear out the diagnostics, since nothing will update them anymore.
+                    self.publish_diagnostics(uri, Vec::new())?;
+                }
+            }
+            _ => {
+                // e.g. `initialized`, `$/cancelRequest`, `workspace/didChangeConfiguration`
+            }
+        }
+
+        Ok(())
+    }
+
+    fn update_document(&mut self, uri: &str, text: &str) -> io::Result<()> {
+        let analysis = Analysis::new(&uri_to_path(uri), text, self.cache_dir.as_deref());
+        let diagnostics = analysis
+            .diagnostics()
+            .iter()
+            .map(diagnostic_to_json)
+            .collect();
+
+        self.documents.insert(uri.to_string(), analysis);
+
+        self.publish_diagnostics(uri, diagnostics)
+    }
+
+    fn publish_diagnostics(&mut self, uri: &str, diagnostics: Vec<Value>) -> io::Result<()> {
+        self.output_notification(
+            "textDocument/publishDiagnostics",
+            json!({ "uri": uri, "diagnostics": diagnostics }),
+        )
+    }
+
+    fn output_notification(&mut self, method: &str, params: Value) -> io::Result<()> {
+        rpc::write_message(&mut self.output, &rpc::notification(method, params))
+    }
+
+    fn hover(&mut self, params: &Value) -> Result<Option<Hover>, &'static str> {
+        let (uri, line_column) = document_position(params)?;
+
+        Ok(self
+            .documents
+            .get_mut(uri)
+            .and_then(|analysis| analysis.hover(line_column)))
+    }
+
+    fn definition(&self, params: &Value) -> Result<Option<Location>, &'static str> {
+        let (uri, line_column) = document_position(params)?;
+
+        Ok(self
+            .documents
+            .get(uri)
+            .and_then(|analysis| analysis.definition(line_column)))
+    }
+
+    fn references(&self, params: &Value) -> Result<Vec<Location>, &'static str> {
+        let (uri, line_column) = document_position(params)?;
+        let include_definition = params["context"]["includeDeclaration"]
+            .as_bool()
+            .unwrap_or(false);
+
+        Ok(self
+            .documents
+            .get(uri)
+            .map(|analysis| analysis.references(line_column, include_definition))
+            .unwrap_or_default())
+    }
+}
+
+/// The document and position of a `TextDocumentPositionParams`
+fn document_position(params: &Value) -> Result<(&str, LineColumn), &'static str> {
+    let uri = params["textDocument"]["uri"]
+        .as_str()
+        .ok_or("missing textDocument.uri")?;
+    let line_column = json_to_line_column(&params["position"]).ok_or("missing position")?;
+
+    Ok((uri, line_column))
+}
+
+fn initialize_result() -> Value {
+    json!({
+        "capabilities": {
+            "textDocumentSync": TEXT_DOCUMENT_SYNC_FULL,
+            "hoverProvider": true,
+            "definitionProvider": true,
+            "referencesProvider": true,
+        },
+        "serverInfo": {
+            "name": "roc",
+            "version": include_str!("../../../version.txt").trim(),
+        },
+    })
+}
+
+fn diagnostic_to_json(diagnostic: &Diagnostic) -> Value {
+    let severity = match diagnostic.severity {
+        Severity::RuntimeError => SEVERITY_ERROR,
+        Severity::Warning => SEVERITY_WARNING,
+    };
+
+    json!({
+        "range": region_to_json(diagnostic.region),
+        "severity": severity,
+        "source": "roc",
+        "code": diagnostic.title,
+        "message": diagnostic.message,
+    })
+}
+
+fn hover_to_json(hover: Hover) -> Value {
+    json!({
+        "contents": {
+            "kind": "markdown",
+            "value": format!("```roc\n{}\n```", hover.type_str),
+        },
+        "range": region_to_json(hover.region),
+    })
+}
+
+fn location_to_json(location: Location) -> Value {
+    json!({
+        "uri": path_to_uri(&location.path),
+        "range": region_to_json(location.region),
+    })
+}
+
+fn region_to_json(region: LineColumnRegion) -> Value {
+    json!({
+        "start": line_column_to_json(region.start),
+        "end": line_column_to_json(region.end),
+    })
+}
+
+fn line_column_to_json(line_column: LineColumn) -> Value {
+    json!({
+        "line": line_column.line,
+        "character": line_column.column,
+    })
+}
+
+fn json_to_line_column(value: &Value) -> Option<LineColumn> {
+    Some(LineColumn {
+        line: value["line"].as_u64()? as u32,
+        column: value["character"].as_u64()? as u32,
+    })
+}
+
+/// Turns a `file://` URI into a path, undoing percent-encoding.
+/// Anything that isn't a `file://` URI is used as a path as-is.
+pub fn uri_to_path(uri: &str) -> PathBuf {
+    let encoded = uri.strip_prefix("file://").unwrap_or(uri);
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let hex = bytes
+            .get(index + 1..index + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match (bytes[index], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+
+    PathBuf::from(String::from_utf8_lossy(&decoded).into_owned())
+}
+
+/// Turns a path into a `file://` URI, percent-encoding anything that isn't safe in one.
+pub fn path_to_uri(path: &Path) -> String {
+    // relative paths can only come from loading a document that wasn't given as a file:// URI
+    let path = std::env::current_dir()
+        .map(|cwd| cwd.join(path))
+        .unwrap_or_else(|_| path.to_path_buf());
+
+    let mut uri = String::from("file://");
+
+    for byte in path.to_string_lossy().bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    uri
+}
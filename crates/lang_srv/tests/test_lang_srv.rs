@@ -0,0 +1,494 @@
+#[macro_use]
+extern crate indoc;
+#[macro_use]
+extern crate pretty_assertions;
+
+#[cfg(test)]
+mod test_lang_srv {
+    use roc_lang_srv::rpc::{read_message, write_message};
+    use roc_lang_srv::Server;
+    use serde_json::{json, Value};
+    use std::io::Cursor;
+    use std::path::PathBuf;
+
+    const URI: &str = "file:///tmp/lang%20srv/Test.roc";
+
+    /// Plays the client's side of a session: sends every message in `script`, then returns the
+    /// exit code and every message the server sent back.
+    fn run_script(script: &[Value]) -> (i32, Vec<Value>) {
+        run_script_with_cache(script, None)
+    }
+
+    fn run_script_with_cache(script: &[Value], cache_dir: Option<PathBuf>) -> (i32, Vec<Value>) {
+        let mut input = Vec::new();
+
+        for message in script {
+            write_message(&mut input, message).unwrap();
+        }
+
+        let mut output = Vec::new();
+        let exit_code = Server::new(&mut output, cache_dir)
+            .run(&mut Cursor::new(input))
+            .unwrap();
+
+        let mut output = Cursor::new(output);
+        let mut responses = Vec::new();
+
+        while let Some(response) = read_message(&mut output).unwrap() {
+            responses.push(response);
+        }
+
+        (exit_code, responses)
+    }
+
+    fn request(id: u64, method: &str, params: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+    }
+
+    fn notification(method: &str, params: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "method": method, "params": params })
+    }
+
+    fn did_open(text: &str) -> Value {
+        notification(
+            "textDocument/didOpen",
+            json!({
+                "textDocument": { "uri": URI, "languageId": "roc", "version": 1, "text": text }
+            }),
+        )
+    }
+
+    /// A whole session around the given messages: initialize first, then shutdown and exit.
+    fn session(messages: Vec<Value>) -> Vec<Value> {
+        let mut script = vec![
+            request(0, "initialize", json!({ "capabilities": {} })),
+            notification("initialized", json!({})),
+        ];
+        script.extend(messages);
+        script.push(request(999, "shutdown", Value::Null));
+        script.push(notification("exit", Value::Null));
+
+        let (exit_code, mut responses) = run_script(&script);
+
+        assert_eq!(exit_code, 0);
+        assert_eq!(
+            responses.pop(),
+            Some(json!({ "jsonrpc": "2.0", "id": 999, "result": null }))
+        );
+        assert_eq!(responses.remove(0)["id"], json!(0));
+
+        responses
+    }
+
+    fn published_diagnostics(responses: &[Value]) -> Vec<Value> {
+        let published: Vec<&Value> = responses
+            .iter()
+            .filter(|response| response["method"] == "textDocument/publishDiagnostics")
+            .collect();
+
+        let last = published.last().expect("no diagnostics were published");
+
+        assert_eq!(last["params"]["uri"], json!(URI));
+
+        last["params"]["diagnostics"].as_array().unwrap().clone()
+    }
+
+    fn range(start: (u32, u32), end: (u32, u32)) -> Value {
+        json!({
+            "start": { "line": start.0, "character": start.1 },
+            "end": { "line": end.0, "character": end.1 },
+        })
+    }
+
+    #[test]
+    fn initialize_advertises_capabilities() {
+        let (exit_code, responses) = run_script(&[
+            request(1, "initialize", json!({ "capabilities": {} })),
+            request(2, "shutdown", Value::Null),
+            notification("exit", Value::Null),
+        ]);
+
+        assert_eq!(exit_code, 0);
+        assert_eq!(responses.len(), 2);
+        assert_eq!(
+            responses[0]["result"]["capabilities"],
+            json!({
+                "textDocumentSync": 1,
+                "hoverProvider": true,
+                "definitionProvider": true,
+                "referencesProvider": true,
+            })
+        );
+        assert_eq!(responses[0]["result"]["serverInfo"]["name"], json!("roc"));
+    }
+
+    #[test]
+    fn requests_before_initialize_are_rejected() {
+        let (exit_code, responses) = run_script(&[
+            request(1, "textDocument/hover", json!({})),
+            notification("exit", Value::Null),
+        ]);
+
+        // exiting without a shutdown request is an error
+        assert_eq!(exit_code, 1);
+        assert_eq!(responses[0]["error"]["code"], json!(-32002));
+    }
+
+    #[test]
+    fn unsupported_method() {
+        let responses = session(vec![request(1, "textDocument/rename", json!({}))]);
+
+        assert_eq!(responses[0]["id"], json!(1));
+        assert_eq!(responses[0]["error"]["code"], json!(-32601));
+    }
+
+    #[test]
+    fn clean_module_has_no_diagnostics() {
+        let responses = session(vec![did_open(indoc!(
+            r#"
+            interface Test exposes [x] imports []
+
+            x = 1
+            "#
+        ))]);
+
+        assert_eq!(published_diagnostics(&responses), Vec::<Value>::new());
+    }
+
+    #[test]
+    fn type_mismatch_is_an_error() {
+        let responses = session(vec![did_open(indoc!(
+            r#"
+            interface Test exposes [x] imports []
+
+            x : Str
+            x = 1
+            "#
+        ))]);
+
+        let diagnostics = published_diagnostics(&responses);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["severity"], json!(1));
+        assert_eq!(diagnostics[0]["source"], json!("roc"));
+        assert_eq!(diagnostics[0]["code"], json!("TYPE MISMATCH"));
+        assert_eq!(diagnostics[0]["range"], range((3, 4), (3, 5)));
+
+        let message = diagnostics[0]["message"].as_str().unwrap();
+        assert!(message.contains("Str"), "{}", message);
+    }
+
+    #[test]
+    fn unused_def_is_a_warning() {
+        let responses = session(vec![did_open(indoc!(
+            r#"
+            interface Test exposes [x] imports []
+
+            x =
+                unused = 2
+
+                1
+            "#
+        ))]);
+
+        let diagnostics = published_diagnostics(&responses);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["severity"], json!(2));
+        assert_eq!(diagnostics[0]["code"], json!("UNUSED DEFINITION"));
+        assert_eq!(diagnostics[0]["range"], range((3, 4), (3, 10)));
+    }
+
+    #[test]
+    fn parse_problem() {
+        let responses = session(vec![did_open(indoc!(
+            r#"
+            interface Test exposes [x, y] imports []
+
+            x = 1
+
+            y = ]
+            "#
+        ))]);
+
+        let diagnostics = published_diagnostics(&responses);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["severity"], json!(1));
+        assert_eq!(diagnostics[0]["range"], range((4, 4), (4, 5)));
+    }
+
+    #[test]
+    fn parse_problems_in_several_defs() {
+        let responses = session(vec![did_open(indoc!(
+            r#"
+            interface Test exposes [x, y, z] imports []
+
+            x = ]
+
+            y : Str
+            y = 1
+
+            z = ]
+            "#
+        ))]);
+
+        let diagnostics = published_diagnostics(&responses);
+        let codes: Vec<_> = diagnostics.iter().map(|d| d["code"].clone()).collect();
+
+        // the type mismatch in between is still found
+        assert_eq!(
+            codes,
+            vec![
+                json!("PARSE PROBLEM"),
+                json!("PARSE PROBLEM"),
+                json!("TYPE MISMATCH")
+            ]
+        );
+        assert_eq!(diagnostics[0]["range"], range((2, 4), (2, 5)));
+        assert_eq!(diagnostics[1]["range"], range((7, 4), (7, 5)));
+    }
+
+    #[test]
+    fn changes_are_rechecked() {
+        let responses = session(vec![
+            did_open(indoc!(
+                r#"
+                interface Test exposes [x] imports []
+
+                x : Str
+                x = 1
+                "#
+            )),
+            notification(
+                "textDocument/didChange",
+                json!({
+                    "textDocument": { "uri": URI, "version": 2 },
+                    "contentChanges": [{
+                        "text": "interface Test exposes [x] imports []\n\nx : Str\nx = \"one\"\n"
+                    }],
+                }),
+            ),
+        ]);
+
+        assert_eq!(published_diagnostics(&responses), Vec::<Value>::new());
+    }
+
+    #[test]
+    fn imported_modules_are_cached_between_edits() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_dir = dir.path().join("cache");
+
+        std::fs::write(
+            dir.path().join("Dep.roc"),
+            "interface Dep exposes [one] imports []\n\none : Str\none = \"one\"\n",
+        )
+        .unwrap();
+
+        let uri = roc_lang_srv::server::path_to_uri(&dir.path().join("Test.roc"));
+        let change = |version, text: &str| {
+            notification(
+                "textDocument/didChange",
+                json!({
+                    "textDocument": { "uri": uri, "version": version },
+                    "contentChanges": [{ "text": text }],
+                }),
+            )
+        };
+
+        let (exit_code, responses) = run_script_with_cache(
+            &[
+                request(0, "initialize", json!({ "capabilities": {} })),
+                notification(
+                    "textDocument/didOpen",
+                    json!({
+                        "textDocument": {
+                            "uri": uri,
+                            "languageId": "roc",
+                            "version": 1,
+                            "text": "interface Test exposes [x] imports [Dep]\n\nx : Str\nx = Dep.one\n",
+                        }
+                    }),
+                ),
+                change(
+                    2,
+                    "interface Test exposes [x] imports [Dep]\n\nx : Str\nx = 1\n",
+                ),
+                change(
+                    3,
+                    "interface Test exposes [x] imports [Dep]\n\nx : Str\nx = Str.concat Dep.one \"!\"\n",
+                ),
+                request(1, "shutdown", Value::Null),
+                notification("exit", Value::Null),
+            ],
+            Some(cache_dir.clone()),
+        );
+
+        assert_eq!(exit_code, 0);
+
+        let diagnostics: Vec<usize> = responses
+            .iter()
+            .filter(|response| response["method"] == "textDocument/publishDiagnostics")
+            .map(|response| response["params"]["diagnostics"].as_array().unwrap().len())
+            .collect();
+
+        assert_eq!(diagnostics, vec![0, 1, 0]);
+
+        // Dep was solved once, and then read back from the cache
+        assert!(std::fs::read_dir(&cache_dir).unwrap().next().is_some());
+    }
+
+    #[test]
+    fn closing_clears_diagnostics() {
+        let responses = session(vec![
+            did_open(indoc!(
+                r#"
+                interface Test exposes [x] imports []
+
+                x : Str
+                x = 1
+                "#
+            )),
+            notification(
+                "textDocument/didClose",
+                json!({ "textDocument": { "uri": URI } }),
+            ),
+        ]);
+
+        assert_eq!(published_diagnostics(&responses), Vec::<Value>::new());
+    }
+
+    fn hover(line: u32, character: u32) -> Value {
+        request(
+            1,
+            "textDocument/hover",
+            json!({
+                "textDocument": { "uri": URI },
+                "position": { "line": line, "character": character },
+            }),
+        )
+    }
+
+    fn location(start: (u32, u32), end: (u32, u32)) -> Value {
+        json!({ "uri": URI, "range": range(start, end) })
+    }
+
+    const HOVER_SRC: &str = indoc!(
+        r#"
+        interface Test exposes [swap] imports []
+
+        swap = \pair ->
+            when pair is
+                { fst, snd } -> { fst: snd, snd: fst }
+        "#
+    );
+
+    #[test]
+    fn hover_def_name() {
+        let responses = session(vec![did_open(HOVER_SRC), hover(2, 1)]);
+
+        assert_eq!(
+            responses[1]["result"]["contents"]["value"],
+            json!("```roc\n{ fst : a, snd : b }* -> { fst : b, snd : a }\n```")
+        );
+        assert_eq!(responses[1]["result"]["range"], range((2, 0), (2, 4)));
+    }
+
+    #[test]
+    fn hover_innermost_expr() {
+        let responses = session(vec![did_open(HOVER_SRC), hover(3, 10)]);
+
+        assert_eq!(
+            responses[1]["result"]["contents"]["value"],
+            json!("```roc\n{ fst : *, snd : * }*\n```")
+        );
+        assert_eq!(responses[1]["result"]["range"], range((3, 9), (3, 13)));
+    }
+
+    #[test]
+    fn hover_counts_utf16_code_units() {
+        let src = indoc!(
+            r#"
+            interface Test exposes [f] imports []
+
+            f = \pair -> { emoji: "é🎉", fst: pair.fst }
+            "#
+        );
+
+        // `pair` in `pair.fst` starts at byte 37, but at character 34: `é` is 2 bytes and 1 code
+        // unit, and `🎉` is 4 bytes and 2 code units.
+        let responses = session(vec![did_open(src), hover(2, 35)]);
+
+        let type_str = responses[1]["result"]["contents"]["value"]
+            .as_str()
+            .unwrap();
+        assert!(type_str.contains("fst"), "{}", type_str);
+        assert_eq!(responses[1]["result"]["range"], range((2, 34), (2, 38)));
+    }
+
+    #[test]
+    fn hover_outside_document() {
+        let responses = session(vec![did_open(HOVER_SRC), hover(100, 0)]);
+
+        assert_eq!(responses[1]["result"], Value::Null);
+    }
+
+    #[test]
+    fn definition() {
+        let responses = session(vec![
+            did_open(HOVER_SRC),
+            request(
+                1,
+                "textDocument/definition",
+                json!({
+                    "textDocument": { "uri": URI },
+                    "position": { "line": 3, "character": 10 },
+                }),
+            ),
+        ]);
+
+        assert_eq!(responses[1]["result"], location((2, 8), (2, 12)));
+    }
+
+    #[test]
+    fn references() {
+        let references = |include_declaration| {
+            let responses = session(vec![
+                did_open(HOVER_SRC),
+                request(
+                    1,
+                    "textDocument/references",
+                    json!({
+                        "textDocument": { "uri": URI },
+                        "position": { "line": 4, "character": 11 },
+                        "context": { "includeDeclaration": include_declaration },
+                    }),
+                ),
+            ]);
+
+            responses[1]["result"].clone()
+        };
+
+        assert_eq!(
+            references(true),
+            json!([location((4, 10), (4, 13)), location((4, 41), (4, 44))])
+        );
+        assert_eq!(references(false), json!([location((4, 41), (4, 44))]));
+    }
+
+    #[test]
+    fn uri_to_path() {
+        assert_eq!(
+            roc_lang_srv::server::uri_to_path(URI),
+            std::path::PathBuf::from("/tmp/lang srv/Test.roc")
+        );
+    }
+
+    #[test]
+    fn path_to_uri() {
+        assert_eq!(
+            roc_lang_srv::server::path_to_uri(std::path::Path::new("/tmp/lang srv/Test.roc")),
+            URI
+        );
+    }
+}
@@ -0,0 +1,29 @@
+    .section .rodata.app,"a",%progbits
+    .p2align 3
+app_size:
+    .quad 16
+app_greeting:
+    .asciz "Hello, World!"
+
+    .text
+    .globl roc__mainForHost_size
+    .type roc__mainForHost_size, %function
+roc__mainForHost_size:
+    adrp x0, app_size
+    ldr x0, [x0, :lo12:app_size]
+    ret
+    .size roc__mainForHost_size, .-roc__mainForHost_size
+
+    .globl roc__mainForHost_1_exposed_generic
+    .type roc__mainForHost_1_exposed_generic, %function
+roc__mainForHost_1_exposed_generic:
+    stp x29, x30, [sp, #-16]!
+    bl roc__mainForHost_size
+    adrp x1, app_greeting
+    add x1, x1, :lo12:app_greeting
+    adrp x2, :got:roc_alloc
+    ldr x2, [x2, :got_lo12:roc_alloc]
+    bl roc_alloc
+    ldp x29, x30, [sp], #16
+    b roc_dealloc
+    .size roc__mainForHost_1_exposed_generic, .-roc__mainForHost_1_exposed_generic
@@ -0,0 +1,38 @@
+    .section __TEXT,__const
+    .p2align 3
+l_app_size:
+    .quad 16
+
+    .section __TEXT,__cstring,cstring_literals
+l_app_greeting:
+    .asciz "Hello, World!"
+
+    .section __TEXT,__text,regular,pure_instructions
+    .globl _roc__mainForHost_size
+    .p2align 2
+_roc__mainForHost_size:
+    adrp x0, l_app_size@PAGE
+    ldr x0, [x0, l_app_size@PAGEOFF]
+    ret
+
+    .globl _roc__mainForHost_1_exposed_generic
+    .p2align 2
+_roc__mainForHost_1_exposed_generic:
+    stp x29, x30, [sp, #-16]!
+    bl _roc__mainForHost_size
+    adrp x1, l_app_greeting@PAGE
+    add x1, x1, l_app_greeting@PAGEOFF
+    adrp x3, l_app_greeting+7@PAGE
+    add x3, x3, l_app_greeting+7@PAGEOFF
+    adrp x2, _roc_alloc@GOTPAGE
+    ldr x2, [x2, _roc_alloc@GOTPAGEOFF]
+    bl _roc_alloc
+    ldp x29, x30, [sp], #16
+    b _roc_dealloc
+
+    .globl _roc__mainForHost_1_exposed
+    .p2align 2
+_roc__mainForHost_1_exposed:
+    b _roc__mainForHost_1_exposed_generic
+
+.subsections_via_symbols
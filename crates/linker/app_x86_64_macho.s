@@ -0,0 +1,34 @@
+    .section __TEXT,__const
+    .p2align 3
+L_app_size:
+    .quad 16
+
+    .section __TEXT,__cstring,cstring_literals
+L_app_greeting:
+    .asciz "Hello, World!"
+
+    .section __TEXT,__text,regular,pure_instructions
+    .globl _roc__mainForHost_size
+    .p2align 4
+_roc__mainForHost_size:
+    movq L_app_size(%rip), %rax
+    retq
+
+    .globl _roc__mainForHost_1_exposed_generic
+    .p2align 4
+_roc__mainForHost_1_exposed_generic:
+    pushq %rbp
+    callq _roc__mainForHost_size
+    leaq L_app_greeting(%rip), %rsi
+    leaq L_app_greeting+7(%rip), %rdi
+    movq _roc_alloc@GOTPCREL(%rip), %rdx
+    callq _roc_alloc
+    popq %rbp
+    jmp _roc_dealloc
+
+    .globl _roc__mainForHost_1_exposed
+    .p2align 4
+_roc__mainForHost_1_exposed:
+    jmp _roc__mainForHost_1_exposed_generic
+
+.subsections_via_symbols
@@ -0,0 +1,32 @@
+# A stand-in for a Roc app on Windows, covering the kinds of relocations an app object uses.
+
+    .text
+    .globl roc__mainForHost_1_exposed_generic
+roc__mainForHost_1_exposed_generic:
+    subq $40, %rsp
+    movl $16, %ecx
+    movl $8, %edx
+    # a call into the host
+    callq roc_alloc
+    # data in the app
+    leaq greeting(%rip), %rcx
+    movq %rcx, (%rax)
+    movq table(%rip), %rcx
+    addq $40, %rsp
+    retq
+
+    .globl roc__mainForHost_size
+roc__mainForHost_size:
+    movl $8, %eax
+    retq
+
+    .section .rdata,"dr"
+greeting:
+    .asciz "Hello, World!"
+    .p2align 3
+table:
+    # absolute addresses need base relocations
+    .quad roc__mainForHost_size
+    .quad greeting+7
+    # image relative addresses
+    .rva roc__mainForHost_1_exposed_generic
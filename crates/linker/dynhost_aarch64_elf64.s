@@ -0,0 +1,25 @@
+    .text
+    .globl roc_alloc
+    .type roc_alloc, %function
+roc_alloc:
+    ret
+    .size roc_alloc, .-roc_alloc
+
+    .globl roc_dealloc
+    .type roc_dealloc, %function
+roc_dealloc:
+    ret
+    .size roc_dealloc, .-roc_dealloc
+
+    .globl _start
+    .type _start, %function
+_start:
+    stp x29, x30, [sp, #-16]!
+    bl roc__mainForHost_size
+    bl roc__mainForHost_1_exposed_generic
+    adrp x8, :got:roc__mainForHost_size
+    ldr x8, [x8, :got_lo12:roc__mainForHost_size]
+    blr x8
+    ldp x29, x30, [sp], #16
+    b roc__mainForHost_1_exposed_generic
+    .size _start, .-_start
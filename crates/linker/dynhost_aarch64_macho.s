@@ -0,0 +1,31 @@
+    .section __TEXT,__text,regular,pure_instructions
+    .globl _roc_alloc
+    .p2align 2
+_roc_alloc:
+    b _malloc
+
+    .globl _roc_dealloc
+    .p2align 2
+_roc_dealloc:
+    adrp x8, _free@GOTPAGE
+    ldr x8, [x8, _free@GOTPAGEOFF]
+    br x8
+
+    .globl _main
+    .p2align 2
+_main:
+    stp x29, x30, [sp, #-16]!
+    bl _roc__mainForHost_size
+    bl _roc__mainForHost_1_exposed_generic
+    adrp x8, _roc__mainForHost_size@GOTPAGE
+    ldr x8, [x8, _roc__mainForHost_size@GOTPAGEOFF]
+    blr x8
+    ldp x29, x30, [sp], #16
+    b _roc__mainForHost_1_exposed_generic
+
+    .section __DATA,__data
+    .p2align 3
+_host_pointers:
+    .quad _malloc
+    .quad _roc__mainForHost_size
+    .quad _free
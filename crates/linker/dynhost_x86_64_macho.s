@@ -0,0 +1,29 @@
+    .section __TEXT,__text,regular,pure_instructions
+    .globl _roc_alloc
+    .p2align 4
+_roc_alloc:
+    jmp _malloc
+
+    .globl _roc_dealloc
+    .p2align 4
+_roc_dealloc:
+    movq _free@GOTPCREL(%rip), %rax
+    jmpq *%rax
+
+    .globl _main
+    .p2align 4
+_main:
+    pushq %rbp
+    callq _roc__mainForHost_size
+    callq _roc__mainForHost_1_exposed_generic
+    movq _roc__mainForHost_size@GOTPCREL(%rip), %rax
+    callq *%rax
+    popq %rbp
+    jmp _roc__mainForHost_1_exposed_generic
+
+    .section __DATA,__data
+    .p2align 3
+_host_pointers:
+    .quad _malloc
+    .quad _roc__mainForHost_size
+    .quad _free
@@ -0,0 +1,36 @@
+# A minimal Windows host for the surgical linker tests. The app functions are imported from the
+# dummy `roc-cheaty-lib.dll`, which sits between two other libraries in the import directory.
+
+    .text
+    .globl main
+main:
+    subq $40, %rsp
+    # through the thunk the linker generates
+    callq roc__mainForHost_size
+    # straight through the import address table
+    callq *__imp_roc__mainForHost_1_exposed_generic(%rip)
+    # through a pointer that needs a base relocation
+    movq host_pointers+8(%rip), %rax
+    callq *%rax
+    xorl %ecx, %ecx
+    callq *__imp_ExitProcess(%rip)
+    addq $40, %rsp
+    retq
+
+    .globl roc_alloc
+roc_alloc:
+    jmpq *__imp_malloc(%rip)
+
+    .globl roc_dealloc
+roc_dealloc:
+    jmpq *__imp_free(%rip)
+
+    .data
+    .globl host_pointers
+    .p2align 3
+host_pointers:
+    .quad roc__mainForHost_1_exposed_generic
+    .quad roc__mainForHost_size
+
+    .section .drectve,"yn"
+    .ascii " -export:roc_alloc -export:roc_dealloc"
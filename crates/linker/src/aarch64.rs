@@ -0,0 +1,418 @@
+//! Helpers for reading and patching AArch64 instructions.
+//!
+//! Unlike x86_64, AArch64 does not store branch offsets or addresses as plain little endian
+//! integers. They are split up into immediate fields of a fixed width 32 bit instruction,
+//! so every relocation and surgery needs to rewrite just those bits.
+
+use object::{elf, macho, Object, ObjectSymbol, RelocationKind, SectionIndex, SymbolKind};
+use std::ops::Range;
+
+/// The lazy binding stub at the start of the `.plt` section.
+pub const PLT_HEADER_SIZE: u64 = 0x20;
+pub const PLT_ENTRY_SIZE: u64 = 0x10;
+
+const INSTRUCTION_SIZE: usize = 4;
+const NOP: u32 = 0xd503_201f;
+
+fn read_instruction(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes(bytes[..INSTRUCTION_SIZE].try_into().unwrap())
+}
+
+fn write_instruction(bytes: &mut [u8], inst: u32) {
+    bytes[..INSTRUCTION_SIZE].copy_from_slice(&inst.to_le_bytes());
+}
+
+/// If `inst` is a `b` or `bl` located at `address`, returns the address it branches to.
+fn branch_target(inst: u32, address: u64) -> Option<u64> {
+    if inst & 0x7c00_0000 != 0x1400_0000 {
+        return None;
+    }
+
+    // Sign extend the 26 bit word offset.
+    let imm26 = (((inst & 0x03ff_ffff) << 6) as i32) >> 6;
+    Some(address.wrapping_add((imm26 as i64 * 4) as u64))
+}
+
+/// The address ranges of the section that hold instructions. These are the ranges of its
+/// function symbols, less the literal pools and jump tables `$d` mapping symbols mark in them.
+/// Any other word of the section may well look like a branch without being one.
+pub fn code_ranges(object: &object::File, section: SectionIndex) -> Vec<Range<u64>> {
+    let mut functions = Vec::new();
+    let mut mapping_symbols = Vec::new();
+
+    for sym in object.symbols() {
+        if sym.section_index() != Some(section) {
+            continue;
+        }
+
+        match sym.name() {
+            Ok(name) if name.starts_with("$d") => mapping_symbols.push((sym.address(), false)),
+            Ok(name) if name.starts_with("$x") => mapping_symbols.push((sym.address(), true)),
+            _ if sym.kind() == SymbolKind::Text && sym.size() > 0 => {
+                functions.push(sym.address()..sym.address() + sym.size())
+            }
+            _ => {}
+        }
+    }
+
+    functions.sort_unstable_by_key(|range| (range.start, range.end));
+    mapping_symbols.sort_unstable();
+
+    // Aliases and nested symbols would otherwise make us patch the same branch twice.
+    let mut merged: Vec<Range<u64>> = Vec::with_capacity(functions.len());
+    for function in functions {
+        match merged.last_mut() {
+            Some(last) if function.start <= last.end => last.end = last.end.max(function.end),
+            _ => merged.push(function),
+        }
+    }
+
+    let mut ranges = Vec::with_capacity(merged.len());
+    for function in merged {
+        let mut code_start = Some(function.start);
+
+        for &(address, is_code) in mapping_symbols
+            .iter()
+            .filter(|(address, _)| function.contains(address))
+        {
+            match code_start {
+                Some(start) if !is_code => {
+                    ranges.push(start..address);
+                    code_start = None;
+                }
+                None if is_code => code_start = Some(address),
+                _ => {}
+            }
+        }
+
+        if let Some(start) = code_start {
+            ranges.push(start..function.end);
+        }
+    }
+
+    ranges.retain(|range| !range.is_empty());
+    ranges
+}
+
+/// Iterates over every `b` and `bl` in `code`, yielding the offset of the instruction
+/// within `code` and the address it branches to.
+pub fn branches(code: &[u8], address: u64) -> impl Iterator<Item = (usize, u64)> + '_ {
+    code.chunks_exact(INSTRUCTION_SIZE)
+        .enumerate()
+        .filter_map(move |(i, bytes)| {
+            let offset = i * INSTRUCTION_SIZE;
+            branch_target(read_instruction(bytes), address + offset as u64)
+                .map(|target| (offset, target))
+        })
+}
+
+/// Points the `b` or `bl` at `address` to `target`.
+pub fn patch_branch(bytes: &mut [u8], address: i64, target: i64) -> Result<(), String> {
+    let delta = target - address;
+    if delta % 4 != 0 || !(-(1 << 27)..(1 << 27)).contains(&delta) {
+        return Err(format!(
+            "Branch from {:+x} to {:+x} is out of range",
+            address, target
+        ));
+    }
+
+    let inst = read_instruction(bytes);
+    write_instruction(
+        bytes,
+        (inst & 0xfc00_0000) | ((delta >> 2) as u32 & 0x03ff_ffff),
+    );
+
+    Ok(())
+}
+
+/// Points the `adrp` at `address` to the 4KiB page containing `target`.
+fn patch_adrp(bytes: &mut [u8], address: i64, target: i64) -> Result<(), String> {
+    let pages = (target >> 12) - (address >> 12);
+    if !(-(1 << 20)..(1 << 20)).contains(&pages) {
+        return Err(format!(
+            "Page of {:+x} is out of range of the adrp at {:+x}",
+            target, address
+        ));
+    }
+
+    let immlo = (pages as u32 & 0x3) << 29;
+    let immhi = ((pages >> 2) as u32 & 0x7_ffff) << 5;
+    let inst = read_instruction(bytes);
+    write_instruction(bytes, (inst & 0x9f00_001f) | immlo | immhi);
+
+    Ok(())
+}
+
+/// Sets the unsigned 12 bit immediate of an `add` or a load/store to the offset of `target`
+/// within its page. Loads and stores scale the immediate by the size of their access.
+fn patch_lo12(bytes: &mut [u8], target: i64, scale: u32) -> Result<(), String> {
+    let lo12 = (target & 0xfff) as u32;
+    if lo12 & ((1 << scale) - 1) != 0 {
+        return Err(format!(
+            "{:+x} is not aligned for a {} byte access",
+            target,
+            1 << scale
+        ));
+    }
+
+    let inst = read_instruction(bytes);
+    write_instruction(bytes, (inst & 0xffc0_03ff) | ((lo12 >> scale) << 10));
+
+    Ok(())
+}
+
+/// The app is linked without a global offset table, so rewrite
+/// `ldr xd, [xn, :got_lo12:sym]` into `add xd, xn, :lo12:sym`.
+/// Together with the preceding `adrp` this computes the address of `sym` directly.
+fn relax_got_load(bytes: &mut [u8], target: i64) {
+    let inst = read_instruction(bytes);
+    let rd = inst & 0x1f;
+    let rn = (inst >> 5) & 0x1f;
+    let lo12 = (target & 0xfff) as u32;
+
+    write_instruction(bytes, 0x9100_0000 | (lo12 << 10) | (rn << 5) | rd);
+}
+
+/// The immediate of a load or store is scaled by the size of its access, an `add` is not.
+fn lo12_scale(inst: u32) -> u32 {
+    // Load/store register (unsigned immediate)
+    if inst & 0x3b00_0000 == 0x3900_0000 {
+        let size = inst >> 30;
+        let is_simd = inst & 0x0400_0000 != 0;
+        let is_128_bit = size == 0 && inst & 0x0080_0000 != 0;
+
+        if is_simd && is_128_bit {
+            4
+        } else {
+            size
+        }
+    } else {
+        0
+    }
+}
+
+/// Applies a relocation to the instruction at the start of `bytes`, which will be loaded at
+/// `address`. `target` is the address of the symbol plus the addend.
+pub fn apply_relocation(
+    bytes: &mut [u8],
+    kind: RelocationKind,
+    address: i64,
+    target: i64,
+) -> Result<(), String> {
+    match kind {
+        RelocationKind::PltRelative
+        | RelocationKind::Elf(elf::R_AARCH64_CALL26 | elf::R_AARCH64_JUMP26) => {
+            patch_branch(bytes, address, target)
+        }
+        RelocationKind::Elf(
+            elf::R_AARCH64_ADR_PREL_PG_HI21
+            | elf::R_AARCH64_ADR_PREL_PG_HI21_NC
+            | elf::R_AARCH64_ADR_GOT_PAGE,
+        ) => patch_adrp(bytes, address, target),
+        RelocationKind::Elf(elf::R_AARCH64_ADD_ABS_LO12_NC | elf::R_AARCH64_LDST8_ABS_LO12_NC) => {
+            patch_lo12(bytes, target, 0)
+        }
+        RelocationKind::Elf(elf::R_AARCH64_LDST16_ABS_LO12_NC) => patch_lo12(bytes, target, 1),
+        RelocationKind::Elf(elf::R_AARCH64_LDST32_ABS_LO12_NC) => patch_lo12(bytes, target, 2),
+        RelocationKind::Elf(elf::R_AARCH64_LDST64_ABS_LO12_NC) => patch_lo12(bytes, target, 3),
+        RelocationKind::Elf(elf::R_AARCH64_LDST128_ABS_LO12_NC) => patch_lo12(bytes, target, 4),
+        RelocationKind::Elf(elf::R_AARCH64_LD64_GOT_LO12_NC) => {
+            relax_got_load(bytes, target);
+            Ok(())
+        }
+        RelocationKind::MachO { value, .. } => match value {
+            macho::ARM64_RELOC_BRANCH26 => patch_branch(bytes, address, target),
+            macho::ARM64_RELOC_PAGE21 | macho::ARM64_RELOC_GOT_LOAD_PAGE21 => {
+                patch_adrp(bytes, address, target)
+            }
+            macho::ARM64_RELOC_PAGEOFF12 => {
+                patch_lo12(bytes, target, lo12_scale(read_instruction(bytes)))
+            }
+            macho::ARM64_RELOC_GOT_LOAD_PAGEOFF12 => {
+                relax_got_load(bytes, target);
+                Ok(())
+            }
+            x => Err(format!("Mach-O relocation not yet supported: {}", x)),
+        },
+        x => Err(format!("Relocation Kind not yet support: {:?}", x)),
+    }
+}
+
+/// Replaces a PLT entry (or Mach-O stub) with a direct branch to `target`,
+/// padding the rest of `bytes` with `nop`s.
+pub fn write_plt_stub(bytes: &mut [u8], address: i64, target: i64) -> Result<(), String> {
+    write_instruction(bytes, 0x1400_0000);
+    patch_branch(bytes, address, target)?;
+
+    for padding in bytes[INSTRUCTION_SIZE..].chunks_exact_mut(4) {
+        write_instruction(padding, NOP);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn branch_round_trip() {
+        // bl #0
+        let mut bytes = 0x9400_0000u32.to_le_bytes();
+
+        patch_branch(&mut bytes, 0x1000, 0x800).unwrap();
+        assert_eq!(branch_target(read_instruction(&bytes), 0x1000), Some(0x800));
+        assert_eq!(read_instruction(&bytes) & 0xfc00_0000, 0x9400_0000);
+
+        patch_branch(&mut bytes, 0x1000, 0x20_0000).unwrap();
+        assert_eq!(
+            branch_target(read_instruction(&bytes), 0x1000),
+            Some(0x20_0000)
+        );
+
+        assert!(patch_branch(&mut bytes, 0, 1 << 28).is_err());
+    }
+
+    #[test]
+    fn code_ranges_skip_data() {
+        use object::{write, ObjectSection};
+
+        let mut obj = write::Object::new(
+            object::BinaryFormat::Elf,
+            object::Architecture::Aarch64,
+            object::Endianness::Little,
+        );
+        let text = obj.add_section(vec![], b".text".to_vec(), object::SectionKind::Text);
+
+        // f: bl #4 ; ret ; literal that reads as a bl
+        // g: b #-4
+        let words: [u32; 4] = [0x9400_0001, 0xd65f_03c0, 0x9400_0010, 0x17ff_ffff];
+        let data: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+        obj.append_section_data(text, &data, 4);
+
+        for (name, value, size, kind) in [
+            ("f", 0, 12, SymbolKind::Text),
+            ("g", 12, 4, SymbolKind::Text),
+            ("$x", 0, 0, SymbolKind::Unknown),
+            ("$d", 8, 0, SymbolKind::Unknown),
+            ("$x", 12, 0, SymbolKind::Unknown),
+        ] {
+            obj.add_symbol(write::Symbol {
+                name: name.as_bytes().to_vec(),
+                value,
+                size,
+                kind,
+                scope: object::SymbolScope::Compilation,
+                weak: false,
+                section: write::SymbolSection::Section(text),
+                flags: object::SymbolFlags::None,
+            });
+        }
+
+        let bytes = obj.write().unwrap();
+        let file = object::File::parse(bytes.as_slice()).unwrap();
+        let section = file.section_by_name(".text").unwrap().index();
+
+        let ranges = code_ranges(&file, section);
+        assert_eq!(ranges, [0..8, 12..16]);
+
+        let found: Vec<(usize, u64)> = ranges
+            .iter()
+            .flat_map(|range| {
+                let code = &data[range.start as usize..range.end as usize];
+                branches(code, range.start)
+                    .map(move |(offset, target)| (range.start as usize + offset, target))
+            })
+            .collect();
+        assert_eq!(found, [(0, 4), (12, 8)]);
+    }
+
+    #[test]
+    fn adrp_and_lo12() {
+        // adrp x1, #0 ; add x1, x1, #0 ; ldr x2, [x3]
+        let mut adrp = 0x9000_0001u32.to_le_bytes();
+        let mut add = 0x9100_0021u32.to_le_bytes();
+        let mut ldr = 0xf940_0062u32.to_le_bytes();
+
+        let target = 0x12_3458;
+        apply_relocation(
+            &mut adrp,
+            RelocationKind::Elf(elf::R_AARCH64_ADR_PREL_PG_HI21),
+            0x1_0ffc,
+            target,
+        )
+        .unwrap();
+        apply_relocation(
+            &mut add,
+            RelocationKind::Elf(elf::R_AARCH64_ADD_ABS_LO12_NC),
+            0x1_1000,
+            target,
+        )
+        .unwrap();
+        apply_relocation(
+            &mut ldr,
+            RelocationKind::Elf(elf::R_AARCH64_LDST64_ABS_LO12_NC),
+            0x1_1004,
+            target,
+        )
+        .unwrap();
+
+        // adrp x1, 0x123000 from 0x10000 is 0x113 pages forward.
+        assert_eq!(
+            read_instruction(&adrp),
+            0x9000_0001 | ((0x113 >> 2) << 5) | (3 << 29)
+        );
+        // add x1, x1, #0x458
+        assert_eq!(read_instruction(&add), 0x9111_6021);
+        // ldr x2, [x3, #0x458]
+        assert_eq!(read_instruction(&ldr), 0xf942_2c62);
+
+        assert!(apply_relocation(
+            &mut ldr,
+            RelocationKind::Elf(elf::R_AARCH64_LDST64_ABS_LO12_NC),
+            0,
+            0x1004,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn relax_got() {
+        // ldr x2, [x2]
+        let mut bytes = 0xf940_0042u32.to_le_bytes();
+
+        apply_relocation(
+            &mut bytes,
+            RelocationKind::Elf(elf::R_AARCH64_LD64_GOT_LO12_NC),
+            0,
+            0x4321,
+        )
+        .unwrap();
+
+        // add x2, x2, #0x321
+        assert_eq!(read_instruction(&bytes), 0x910c_8442);
+    }
+
+    #[test]
+    fn macho_pageoff_scale() {
+        // ldr x0, [x0] ; ldrb w1, [x1] ; ldr q0, [x2]
+        let mut ldr = 0xf940_0000u32.to_le_bytes();
+        let mut ldrb = 0x3940_0021u32.to_le_bytes();
+        let mut ldr_q = 0x3dc0_0040u32.to_le_bytes();
+
+        let kind = RelocationKind::MachO {
+            value: macho::ARM64_RELOC_PAGEOFF12,
+            relative: false,
+        };
+        apply_relocation(&mut ldr, kind, 0, 0x1238).unwrap();
+        apply_relocation(&mut ldrb, kind, 0, 0x1237).unwrap();
+        apply_relocation(&mut ldr_q, kind, 0, 0x1230).unwrap();
+
+        // ldr x0, [x0, #0x238]
+        assert_eq!(read_instruction(&ldr), 0xf941_1c00);
+        // ldrb w1, [x1, #0x237]
+        assert_eq!(read_instruction(&ldrb), 0x3948_dc21);
+        // ldr q0, [x2, #0x230]
+        assert_eq!(read_instruction(&ldr_q), 0x3dc0_8c40);
+    }
+}
@@ -0,0 +1,374 @@
+use object::macho;
+use object::{bytes_of, LittleEndian as LE, U32, U64};
+use target_lexicon::Triple;
+
+use crate::macho::write_uleb128;
+
+const DYLIB_NAME: &[u8] = b"libapp.dylib";
+
+/// Creates a dylib that exports `custom_names`, so the host can be linked against it.
+/// All symbols point to a single `ret` instruction, none of them will be called before the
+/// surgical linker replaces them with the real app.
+pub fn create_dylib_macho(
+    target: &Triple,
+    custom_names: &[String],
+) -> object::read::Result<Vec<u8>> {
+    let (cputype, cpusubtype, page_size, ret) = match target.architecture {
+        target_lexicon::Architecture::Aarch64(_) => (
+            macho::CPU_TYPE_ARM64,
+            macho::CPU_SUBTYPE_ARM64_ALL,
+            0x4000,
+            &[0xc0, 0x03, 0x5f, 0xd6][..],
+        ),
+        _ => (
+            macho::CPU_TYPE_X86_64,
+            macho::CPU_SUBTYPE_X86_64_ALL,
+            0x1000,
+            &[0xc3][..],
+        ),
+    };
+
+    // Mach-O symbols carry a leading underscore.
+    let names: Vec<Vec<u8>> = custom_names
+        .iter()
+        .map(|name| [b"_", name.as_bytes()].concat())
+        .collect();
+
+    let segment_size = std::mem::size_of::<macho::SegmentCommand64<LE>>();
+    let section_size = std::mem::size_of::<macho::Section64<LE>>();
+    let dylib_size = std::mem::size_of::<macho::DylibCommand<LE>>();
+    let id_dylib_size = align(dylib_size + DYLIB_NAME.len() + 1, 8);
+
+    let sizeofcmds = segment_size
+        + section_size
+        + segment_size
+        + id_dylib_size
+        + std::mem::size_of::<macho::DyldInfoCommand<LE>>()
+        + std::mem::size_of::<macho::SymtabCommand<LE>>()
+        + std::mem::size_of::<macho::DysymtabCommand<LE>>()
+        + std::mem::size_of::<macho::BuildVersionCommand<LE>>();
+    let ncmds = 7;
+
+    let text_offset = align(
+        std::mem::size_of::<macho::MachHeader64<LE>>() + sizeofcmds,
+        16,
+    );
+
+    // The linkedit segment holds the export trie, followed by the symbol and string tables.
+    let trie = export_trie(&names, text_offset as u64);
+    let trie_offset = page_size;
+    let symtab_offset = align(trie_offset + trie.len(), 8);
+    let nlist_size = std::mem::size_of::<macho::Nlist64<LE>>();
+    let strtab_offset = symtab_offset + names.len() * nlist_size;
+
+    let mut strtab = vec![b' ', 0];
+    let mut symtab = Vec::with_capacity(names.len() * nlist_size);
+    for name in names.iter() {
+        let nlist = macho::Nlist64 {
+            n_strx: U32::new(LE, strtab.len() as u32),
+            n_type: macho::N_SECT | macho::N_EXT,
+            n_sect: 1,
+            n_desc: Default::default(),
+            n_value: U64::new(LE, text_offset as u64),
+        };
+        symtab.extend_from_slice(bytes_of(&nlist));
+        strtab.extend_from_slice(name);
+        strtab.push(0);
+    }
+    strtab.resize(align(strtab.len(), 8), 0);
+
+    let linkedit_size = strtab_offset + strtab.len() - page_size;
+
+    let mut out = Vec::with_capacity(strtab_offset + strtab.len());
+
+    out.extend_from_slice(bytes_of(&macho::MachHeader64 {
+        magic: U32::new(object::BigEndian, macho::MH_CIGAM_64),
+        cputype: U32::new(LE, cputype),
+        cpusubtype: U32::new(LE, cpusubtype),
+        filetype: U32::new(LE, macho::MH_DYLIB),
+        ncmds: U32::new(LE, ncmds),
+        sizeofcmds: U32::new(LE, sizeofcmds as u32),
+        flags: U32::new(
+            LE,
+            macho::MH_NOUNDEFS
+                | macho::MH_DYLDLINK
+                | macho::MH_TWOLEVEL
+                | macho::MH_NO_REEXPORTED_DYLIBS,
+        ),
+        reserved: U32::default(),
+    }));
+
+    out.extend_from_slice(bytes_of(&segment(
+        b"__TEXT",
+        0,
+        page_size as u64,
+        1,
+        macho::VM_PROT_READ | macho::VM_PROT_EXECUTE,
+    )));
+    out.extend_from_slice(bytes_of(&macho::Section64 {
+        sectname: name16(b"__text"),
+        segname: name16(b"__TEXT"),
+        addr: U64::new(LE, text_offset as u64),
+        size: U64::new(LE, ret.len() as u64),
+        offset: U32::new(LE, text_offset as u32),
+        align: U32::new(LE, 4),
+        reloff: U32::default(),
+        nreloc: U32::default(),
+        flags: U32::new(
+            LE,
+            macho::S_REGULAR | macho::S_ATTR_PURE_INSTRUCTIONS | macho::S_ATTR_SOME_INSTRUCTIONS,
+        ),
+        reserved1: U32::default(),
+        reserved2: U32::default(),
+        reserved3: U32::default(),
+    }));
+
+    let mut linkedit = segment(
+        b"__LINKEDIT",
+        page_size as u64,
+        align(linkedit_size, page_size) as u64,
+        0,
+        macho::VM_PROT_READ,
+    );
+    linkedit.fileoff = U64::new(LE, page_size as u64);
+    linkedit.filesize = U64::new(LE, linkedit_size as u64);
+    out.extend_from_slice(bytes_of(&linkedit));
+
+    out.extend_from_slice(bytes_of(&macho::DylibCommand {
+        cmd: U32::new(LE, macho::LC_ID_DYLIB),
+        cmdsize: U32::new(LE, id_dylib_size as u32),
+        dylib: macho::Dylib {
+            name: macho::LcStr {
+                offset: U32::new(LE, dylib_size as u32),
+            },
+            timestamp: U32::new(LE, 1),
+            current_version: U32::new(LE, 0x1_0000),
+            compatibility_version: U32::new(LE, 0x1_0000),
+        },
+    }));
+    out.extend_from_slice(DYLIB_NAME);
+    out.resize(out.len() + id_dylib_size - dylib_size - DYLIB_NAME.len(), 0);
+
+    out.extend_from_slice(bytes_of(&macho::DyldInfoCommand {
+        cmd: U32::new(LE, macho::LC_DYLD_INFO_ONLY),
+        cmdsize: U32::new(LE, std::mem::size_of::<macho::DyldInfoCommand<LE>>() as u32),
+        rebase_off: U32::default(),
+        rebase_size: U32::default(),
+        bind_off: U32::default(),
+        bind_size: U32::default(),
+        weak_bind_off: U32::default(),
+        weak_bind_size: U32::default(),
+        lazy_bind_off: U32::default(),
+        lazy_bind_size: U32::default(),
+        export_off: U32::new(LE, trie_offset as u32),
+        export_size: U32::new(LE, trie.len() as u32),
+    }));
+
+    out.extend_from_slice(bytes_of(&macho::SymtabCommand {
+        cmd: U32::new(LE, macho::LC_SYMTAB),
+        cmdsize: U32::new(LE, std::mem::size_of::<macho::SymtabCommand<LE>>() as u32),
+        symoff: U32::new(LE, symtab_offset as u32),
+        nsyms: U32::new(LE, names.len() as u32),
+        stroff: U32::new(LE, strtab_offset as u32),
+        strsize: U32::new(LE, strtab.len() as u32),
+    }));
+
+    let dysymtab = macho::DysymtabCommand {
+        cmd: U32::new(LE, macho::LC_DYSYMTAB),
+        cmdsize: U32::new(LE, std::mem::size_of::<macho::DysymtabCommand<LE>>() as u32),
+        ilocalsym: U32::default(),
+        nlocalsym: U32::default(),
+        iextdefsym: U32::default(),
+        nextdefsym: U32::new(LE, names.len() as u32),
+        iundefsym: U32::new(LE, names.len() as u32),
+        nundefsym: U32::default(),
+        tocoff: U32::default(),
+        ntoc: U32::default(),
+        modtaboff: U32::default(),
+        nmodtab: U32::default(),
+        extrefsymoff: U32::default(),
+        nextrefsyms: U32::default(),
+        indirectsymoff: U32::default(),
+        nindirectsyms: U32::default(),
+        extreloff: U32::default(),
+        nextrel: U32::default(),
+        locreloff: U32::default(),
+        nlocrel: U32::default(),
+    };
+    out.extend_from_slice(bytes_of(&dysymtab));
+
+    out.extend_from_slice(bytes_of(&macho::BuildVersionCommand {
+        cmd: U32::new(LE, macho::LC_BUILD_VERSION),
+        cmdsize: U32::new(
+            LE,
+            std::mem::size_of::<macho::BuildVersionCommand<LE>>() as u32,
+        ),
+        platform: U32::new(LE, macho::PLATFORM_MACOS),
+        // 11.0, the first version to support arm64.
+        minos: U32::new(LE, 0xb_0000),
+        sdk: U32::new(LE, 0xb_0000),
+        ntools: U32::default(),
+    }));
+
+    out.resize(text_offset, 0);
+    out.extend_from_slice(ret);
+
+    out.resize(trie_offset, 0);
+    out.extend_from_slice(&trie);
+    out.resize(symtab_offset, 0);
+    out.extend_from_slice(&symtab);
+    out.extend_from_slice(&strtab);
+
+    Ok(out)
+}
+
+fn align(value: usize, alignment: usize) -> usize {
+    (value + alignment - 1) & !(alignment - 1)
+}
+
+fn name16(name: &[u8]) -> [u8; 16] {
+    let mut out = [0; 16];
+    out[..name.len()].copy_from_slice(name);
+    out
+}
+
+fn segment(
+    name: &[u8],
+    vmaddr: u64,
+    size: u64,
+    nsects: u32,
+    prot: u32,
+) -> macho::SegmentCommand64<LE> {
+    let cmdsize = std::mem::size_of::<macho::SegmentCommand64<LE>>()
+        + nsects as usize * std::mem::size_of::<macho::Section64<LE>>();
+
+    macho::SegmentCommand64 {
+        cmd: U32::new(LE, macho::LC_SEGMENT_64),
+        cmdsize: U32::new(LE, cmdsize as u32),
+        segname: name16(name),
+        vmaddr: U64::new(LE, vmaddr),
+        vmsize: U64::new(LE, size),
+        fileoff: U64::default(),
+        filesize: U64::new(LE, size),
+        maxprot: U32::new(LE, prot),
+        initprot: U32::new(LE, prot),
+        nsects: U32::new(LE, nsects),
+        flags: U32::default(),
+    }
+}
+
+fn uleb128_len(value: u64) -> usize {
+    let mut out = Vec::new();
+    write_uleb128(&mut out, value);
+    out.len()
+}
+
+struct TrieNode {
+    terminal: bool,
+    edges: Vec<(Vec<u8>, usize)>,
+    offset: usize,
+}
+
+/// Builds the export trie dyld (and the static linker) use to look up exported symbols.
+/// Each edge is labelled with a common prefix, so a name that is a prefix of another one
+/// (like `_roc__mainForHost_1_exposed` and `_roc__mainForHost_1_exposed_generic`) ends
+/// at an inner node.
+fn export_trie(sorted_names: &[Vec<u8>], address: u64) -> Vec<u8> {
+    fn insert(nodes: &mut Vec<TrieNode>, names: &[&[u8]]) -> usize {
+        let index = nodes.len();
+        nodes.push(TrieNode {
+            terminal: names.iter().any(|name| name.is_empty()),
+            edges: Vec::new(),
+            offset: 0,
+        });
+
+        let mut rest: Vec<&[u8]> = names.iter().copied().filter(|n| !n.is_empty()).collect();
+        rest.sort_unstable();
+
+        let mut start = 0;
+        while start < rest.len() {
+            let first = rest[start][0];
+            let end = start + rest[start..].iter().take_while(|n| n[0] == first).count();
+            let group = &rest[start..end];
+
+            let prefix_len = (1..=group[0].len())
+                .take_while(|&len| {
+                    group
+                        .iter()
+                        .all(|n| n.len() >= len && n[..len] == group[0][..len])
+                })
+                .last()
+                .unwrap();
+            let suffixes: Vec<&[u8]> = group.iter().map(|n| &n[prefix_len..]).collect();
+
+            let child = insert(nodes, &suffixes);
+            nodes[index]
+                .edges
+                .push((group[0][..prefix_len].to_vec(), child));
+
+            start = end;
+        }
+
+        index
+    }
+
+    let names: Vec<&[u8]> = sorted_names.iter().map(|n| n.as_slice()).collect();
+    let mut nodes = Vec::new();
+    insert(&mut nodes, &names);
+
+    let mut terminal_info = Vec::new();
+    // flags: EXPORT_SYMBOL_FLAGS_KIND_REGULAR
+    write_uleb128(&mut terminal_info, 0);
+    write_uleb128(&mut terminal_info, address);
+
+    let node_size = |node: &TrieNode, nodes: &[TrieNode]| {
+        let terminal_size = if node.terminal {
+            terminal_info.len()
+        } else {
+            0
+        };
+        let edges: usize = node
+            .edges
+            .iter()
+            .map(|(label, child)| label.len() + 1 + uleb128_len(nodes[*child].offset as u64))
+            .sum();
+
+        uleb128_len(terminal_size as u64) + terminal_size + 1 + edges
+    };
+
+    // Child offsets are uleb128 encoded, so the layout has to be iterated until it is stable.
+    loop {
+        let mut changed = false;
+        let mut offset = 0;
+        for i in 0..nodes.len() {
+            if nodes[i].offset != offset {
+                nodes[i].offset = offset;
+                changed = true;
+            }
+            offset += node_size(&nodes[i], &nodes);
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    let mut out = Vec::new();
+    for node in nodes.iter() {
+        if node.terminal {
+            write_uleb128(&mut out, terminal_info.len() as u64);
+            out.extend_from_slice(&terminal_info);
+        } else {
+            out.push(0);
+        }
+
+        out.push(node.edges.len() as u8);
+        for (label, child) in node.edges.iter() {
+            out.extend_from_slice(label);
+            out.push(0);
+            write_uleb128(&mut out, nodes[*child].offset as u64);
+        }
+    }
+
+    out
+}
@@ -1,12 +1,13 @@
 use target_lexicon::Triple;
 
 mod elf64;
+mod macho;
 mod pe;
 
 pub fn generate(target: &Triple, custom_names: &[String]) -> object::read::Result<Vec<u8>> {
     match target.binary_format {
-        target_lexicon::BinaryFormat::Elf => elf64::create_dylib_elf64(custom_names),
-        target_lexicon::BinaryFormat::Macho => todo!("macho dylib creation"),
+        target_lexicon::BinaryFormat::Elf => elf64::create_dylib_elf64(target, custom_names),
+        target_lexicon::BinaryFormat::Macho => macho::create_dylib_macho(target, custom_names),
         target_lexicon::BinaryFormat::Coff => Ok(pe::synthetic_dll(custom_names)),
         other => unimplemented!("dylib creation for {:?}", other),
     }
@@ -24,10 +25,17 @@ mod tests {
         let bytes = generate(target, &custom_names).unwrap();
         let object = object::File::parse(bytes.as_slice()).unwrap();
 
+        // Mach-O symbols carry a leading underscore.
+        let prefix = match target.binary_format {
+            target_lexicon::BinaryFormat::Macho => "_",
+            _ => "",
+        };
+
         let exports = object.exports().unwrap();
         for custom in custom_names {
+            let name = format!("{}{}", prefix, custom);
             assert!(
-                exports.iter().any(|e| e.name() == custom.as_bytes()),
+                exports.iter().any(|e| e.name() == name.as_bytes()),
                 "missing {}",
                 &custom
             );
@@ -46,6 +54,55 @@ mod tests {
         check_exports(&target);
     }
 
+    #[test]
+    fn check_exports_elf64_aarch64() {
+        let target = target_lexicon::Triple {
+            architecture: target_lexicon::Architecture::Aarch64(
+                target_lexicon::Aarch64Architecture::Aarch64,
+            ),
+            operating_system: target_lexicon::OperatingSystem::Linux,
+            binary_format: target_lexicon::BinaryFormat::Elf,
+            ..target_lexicon::Triple::host()
+        };
+
+        check_exports(&target);
+
+        let bytes = generate(&target, &["foo".to_string()]).unwrap();
+        let object = object::File::parse(bytes.as_slice()).unwrap();
+        assert_eq!(object.architecture(), object::Architecture::Aarch64);
+    }
+
+    #[test]
+    fn check_exports_macho() {
+        let target = target_lexicon::Triple {
+            architecture: target_lexicon::Architecture::X86_64,
+            operating_system: target_lexicon::OperatingSystem::Darwin,
+            binary_format: target_lexicon::BinaryFormat::Macho,
+            ..target_lexicon::Triple::host()
+        };
+
+        check_exports(&target);
+    }
+
+    #[test]
+    fn check_exports_macho_aarch64() {
+        let target = target_lexicon::Triple {
+            architecture: target_lexicon::Architecture::Aarch64(
+                target_lexicon::Aarch64Architecture::Aarch64,
+            ),
+            operating_system: target_lexicon::OperatingSystem::Darwin,
+            binary_format: target_lexicon::BinaryFormat::Macho,
+            ..target_lexicon::Triple::host()
+        };
+
+        check_exports(&target);
+
+        let bytes = generate(&target, &["foo".to_string()]).unwrap();
+        let object = object::File::parse(bytes.as_slice()).unwrap();
+        assert_eq!(object.architecture(), object::Architecture::Aarch64);
+        assert_eq!(object.format(), object::BinaryFormat::MachO);
+    }
+
     #[test]
     fn check_exports_coff() {
         let target = target_lexicon::Triple {
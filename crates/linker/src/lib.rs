@@ -1,7 +1,7 @@
 use bincode::{deserialize_from, serialize_into};
 use iced_x86::{Decoder, DecoderOptions, Instruction, OpCodeOperandKind, OpKind};
 use memmap2::{Mmap, MmapMut};
-use object::{elf, endian, macho};
+use object::{elf, endian};
 use object::{
     CompressedFileRange, CompressionFormat, LittleEndian, NativeEndian, Object, ObjectSection,
     ObjectSymbol, RelocationKind, RelocationTarget, Section, SectionIndex, SectionKind, Symbol,
@@ -22,7 +22,9 @@ use std::path::Path;
 use std::time::{Duration, Instant};
 use target_lexicon::Triple;
 
+mod aarch64;
 mod generate_dylib;
+mod macho;
 mod metadata;
 mod pe;
 use metadata::VirtualOffset;
@@ -31,7 +33,6 @@ const MIN_SECTION_ALIGNMENT: usize = 0x40;
 
 // TODO: Analyze if this offset is always correct.
 const PLT_ADDRESS_OFFSET: u64 = 0x10;
-const STUB_ADDRESS_OFFSET: u64 = 0x06;
 
 struct ElfDynamicDeps {
     got_app_syms: Vec<(String, usize)>,
@@ -40,44 +41,33 @@ struct ElfDynamicDeps {
     shared_lib_index: usize,
 }
 
-// struct MachoDynamicDeps {
-//     got_app_syms: Vec<(String, usize)>,
-//     got_sections: Vec<(usize, usize)>,
-//     dynamic_lib_count: usize,
-//     shared_lib_index: usize,
-// }
-
 fn report_timing(label: &str, duration: Duration) {
     println!("\t{:9.3} ms   {}", duration.as_secs_f64() * 1000.0, label,);
 }
 
 pub fn supported(link_type: LinkType, target: &Triple) -> bool {
     if let LinkType::Executable = link_type {
-        match target {
+        matches!(
+            target,
             Triple {
-                architecture: target_lexicon::Architecture::X86_64,
+                architecture: target_lexicon::Architecture::X86_64
+                    | target_lexicon::Architecture::Aarch64(_),
                 operating_system: target_lexicon::OperatingSystem::Linux,
                 binary_format: target_lexicon::BinaryFormat::Elf,
                 ..
-            } => true,
-
-            // macho support is incomplete
-            Triple {
+            } | Triple {
+                architecture: target_lexicon::Architecture::X86_64
+                    | target_lexicon::Architecture::Aarch64(_),
                 operating_system: target_lexicon::OperatingSystem::Darwin,
                 binary_format: target_lexicon::BinaryFormat::Macho,
                 ..
-            } => false,
-
-            // windows support is incomplete
-            Triple {
+            } | Triple {
                 architecture: target_lexicon::Architecture::X86_64,
                 operating_system: target_lexicon::OperatingSystem::Windows,
                 binary_format: target_lexicon::BinaryFormat::Coff,
                 ..
-            } => false,
-
-            _ => false,
-        }
+            }
+        )
     } else {
         false
     }
@@ -91,15 +81,14 @@ pub fn build_and_preprocess_host(
     exposed_to_host: Vec<String>,
     exported_closure_types: Vec<String>,
 ) {
-    let dummy_lib = if let target_lexicon::OperatingSystem::Windows = target.operating_system {
-        host_input_path.with_file_name("libapp.obj")
-    } else {
-        host_input_path.with_file_name("libapp.so")
+    let dummy_lib = match target.operating_system {
+        target_lexicon::OperatingSystem::Windows => host_input_path.with_file_name("libapp.obj"),
+        target_lexicon::OperatingSystem::Darwin => host_input_path.with_file_name("libapp.dylib"),
+        _ => host_input_path.with_file_name("libapp.so"),
     };
 
     generate_dynamic_lib(target, exposed_to_host, exported_closure_types, &dummy_lib);
-    rebuild_host(opt_level, target, host_input_path, Some(&dummy_lib));
-    let dynhost = host_input_path.with_file_name("dynhost");
+    let dynhost = rebuild_host(opt_level, target, host_input_path, Some(&dummy_lib));
     let metadata = host_input_path.with_file_name("metadata");
     // let prehost = host_input_path.with_file_name("preprocessedhost");
 
@@ -170,23 +159,20 @@ fn generate_dynamic_lib(
 fn object_matches_target<'a>(target: &Triple, object: &object::File<'a, &'a [u8]>) -> bool {
     use target_lexicon::{Architecture as TLA, OperatingSystem as TLO};
 
-    match target.architecture {
-        TLA::X86_64 => {
-            if object.architecture() != object::Architecture::X86_64 {
-                return false;
-            }
-
-            let target_format = match target.operating_system {
-                TLO::Linux => object::BinaryFormat::Elf,
-                TLO::Windows => object::BinaryFormat::Pe,
-                _ => todo!("surgical linker does not support target {:?}", target),
-            };
+    let target_architecture = match target.architecture {
+        TLA::X86_64 => object::Architecture::X86_64,
+        TLA::Aarch64(_) => object::Architecture::Aarch64,
+        _ => todo!("surgical linker does not support target {:?}", target),
+    };
 
-            object.format() == target_format
-        }
-        TLA::Aarch64(_) => object.architecture() == object::Architecture::Aarch64,
+    let target_format = match target.operating_system {
+        TLO::Linux => object::BinaryFormat::Elf,
+        TLO::Windows => object::BinaryFormat::Pe,
+        TLO::Darwin => object::BinaryFormat::MachO,
         _ => todo!("surgical linker does not support target {:?}", target),
-    }
+    };
+
+    object.architecture() == target_architecture && object.format() == target_format
 }
 
 /// Checks whether the dummy `.dll/.so` is up to date, in other words that it exports exactly the
@@ -217,7 +203,14 @@ fn dummy_lib_is_up_to_date(
     // we need all symbols to be there and in the correct order
     let dynamic_symbols: Vec<_> = object.exports().unwrap();
 
-    let it1 = dynamic_symbols.iter().map(|e| e.name());
+    // Mach-O symbols carry a leading underscore.
+    let prefix: &[u8] = match object.format() {
+        object::BinaryFormat::MachO => b"_",
+        _ => b"",
+    };
+    let it1 = dynamic_symbols
+        .iter()
+        .map(|e| e.name().strip_prefix(prefix).unwrap_or_else(|| e.name()));
     let it2 = custom_names.iter().map(|s| s.as_bytes());
 
     it1.eq(it2)
@@ -334,11 +327,17 @@ impl<'a> Surgeries<'a> {
         }
 
         for text_section in text_sections {
-            self.append_text_section(object_bytes, &text_section, verbose)
+            self.append_text_section(object_bytes, object, &text_section, verbose)
         }
     }
 
-    fn append_text_section(&mut self, object_bytes: &[u8], sec: &Section, verbose: bool) {
+    fn append_text_section(
+        &mut self,
+        object_bytes: &[u8],
+        object: &object::File,
+        sec: &Section,
+        verbose: bool,
+    ) {
         let (file_offset, compressed) = match sec.compressed_file_range() {
             Ok(CompressedFileRange {
                 format: CompressionFormat::None,
@@ -361,6 +360,18 @@ impl<'a> Surgeries<'a> {
                 internal_error!("Failed to load text section, {:+x?}: {}", sec, err);
             }
         };
+
+        if object.architecture() == object::Architecture::Aarch64 {
+            return self.append_aarch64_text_section(
+                object,
+                sec,
+                file_offset,
+                compressed,
+                &data,
+                verbose,
+            );
+        }
+
         let mut decoder = Decoder::with_ip(64, &data, sec.address(), DecoderOptions::NONE);
         let mut inst = Instruction::default();
 
@@ -452,6 +463,67 @@ impl<'a> Surgeries<'a> {
             }
         }
     }
+
+    /// AArch64 instructions are all 4 bytes, so rather than disassembling we can just look at
+    /// every word of the host's functions for a `b` or `bl` to one of the app functions.
+    /// As with x86_64, indirect branches are left to go through the plt, and so are branches
+    /// in code without function symbols.
+    fn append_aarch64_text_section(
+        &mut self,
+        object: &object::File,
+        sec: &Section,
+        file_offset: u64,
+        compressed: bool,
+        data: &[u8],
+        verbose: bool,
+    ) {
+        let code_ranges = aarch64::code_ranges(object, sec.index());
+
+        if code_ranges.is_empty() && verbose {
+            println!(
+                "No function symbols in {:+x?}, so its branches are left to go through the plt",
+                sec
+            );
+        }
+
+        let branches = code_ranges.into_iter().flat_map(|range| {
+            let start = (range.start - sec.address()) as usize;
+            let end = (range.end - sec.address()) as usize;
+
+            aarch64::branches(&data[start..end], range.start)
+                .map(move |(offset, target)| (start + offset, target))
+        });
+
+        for (offset, target) in branches {
+            if let Some(func_name) = self.app_func_addresses.get(&target) {
+                if compressed {
+                    internal_error!(
+                        "Surgical linking does not work with compressed text sections: {:+x?}",
+                        sec
+                    );
+                }
+
+                let address = sec.address() + offset as u64;
+                if verbose {
+                    println!(
+                        "Found branch from {:+x} to {:+x}({})",
+                        address, target, func_name
+                    );
+                }
+
+                // Unlike x86_64, the offset is relative to the branch itself rather than the
+                // next instruction.
+                self.surgeries
+                    .get_mut(*func_name)
+                    .unwrap()
+                    .push(metadata::SurgeryEntry {
+                        file_offset: file_offset + offset as u64,
+                        virtual_offset: VirtualOffset::Relative(address),
+                        size: 4,
+                    });
+            }
+        }
+    }
 }
 
 /// Constructs a `metadata::Metadata` from a host executable binary, and writes it to disk
@@ -468,6 +540,20 @@ pub fn preprocess(
         println!("Targeting: {}", target);
     }
 
+    // PE hosts are different enough that they have their own metadata
+    if let target_lexicon::BinaryFormat::Coff = target.binary_format {
+        pe::preprocess_windows(
+            exec_filename,
+            metadata_filename,
+            out_filename,
+            shared_lib,
+            verbose,
+            time,
+        )
+        .unwrap_or_else(|e| internal_error!("{}", e));
+        return;
+    }
+
     let total_start = Instant::now();
     let exec_parsing_start = total_start;
     let exec_file = fs::File::open(exec_filename).unwrap_or_else(|e| internal_error!("{}", e));
@@ -533,10 +619,22 @@ pub fn preprocess(
     let app_syms = collect_roc_undefined_symbols(&exec_obj, target);
 
     let mut app_func_addresses: MutMap<u64, &str> = MutMap::default();
-    let mut macho_load_so_offset = None;
 
     match target.binary_format {
         target_lexicon::BinaryFormat::Elf => {
+            // The plt starts with a stub for lazy binding, followed by one entry per jump slot.
+            let (jump_slot, plt_header_size, plt_entry_size) = match target.architecture {
+                target_lexicon::Architecture::Aarch64(_) => (
+                    elf::R_AARCH64_JUMP_SLOT,
+                    aarch64::PLT_HEADER_SIZE,
+                    aarch64::PLT_ENTRY_SIZE,
+                ),
+                _ => (
+                    elf::R_X86_64_JUMP_SLOT,
+                    PLT_ADDRESS_OFFSET,
+                    PLT_ADDRESS_OFFSET,
+                ),
+            };
             let plt_relocs = (match exec_obj.dynamic_relocations() {
                 Some(relocs) => relocs,
                 None => {
@@ -544,7 +642,7 @@ pub fn preprocess(
                 }
             })
             .filter_map(|(_, reloc)| {
-                if let RelocationKind::Elf(7) = reloc.kind() {
+                if reloc.kind() == RelocationKind::Elf(jump_slot) {
                     Some(reloc)
                 } else {
                     None
@@ -553,8 +651,9 @@ pub fn preprocess(
             for (i, reloc) in plt_relocs.enumerate() {
                 for symbol in app_syms.iter() {
                     if reloc.target() == RelocationTarget::Symbol(symbol.index()) {
-                        let func_address = (i as u64 + 1) * PLT_ADDRESS_OFFSET + plt_address;
-                        let func_offset = (i as u64 + 1) * PLT_ADDRESS_OFFSET + plt_offset;
+                        let plt_entry_offset = plt_header_size + i as u64 * plt_entry_size;
+                        let func_address = plt_entry_offset + plt_address;
+                        let func_offset = plt_entry_offset + plt_offset;
                         app_func_addresses.insert(func_address, symbol.name().unwrap());
                         md.plt_addresses.insert(
                             symbol.name().unwrap().to_string(),
@@ -566,137 +665,17 @@ pub fn preprocess(
             }
         }
         target_lexicon::BinaryFormat::Macho => {
-            use macho::{DyldInfoCommand, DylibCommand, Section64, SegmentCommand64};
-
-            let exec_header =
-                load_struct_inplace::<macho::MachHeader64<LittleEndian>>(exec_data, 0);
-            let num_load_cmds = exec_header.ncmds.get(NativeEndian);
-
-            let mut offset = mem::size_of_val(exec_header);
-
-            let mut stubs_symbol_index = None;
-            let mut stubs_symbol_count = None;
-
-            'cmds: for _ in 0..num_load_cmds {
-                let info =
-                    load_struct_inplace::<macho::LoadCommand<LittleEndian>>(exec_data, offset);
-                let cmd = info.cmd.get(NativeEndian);
-                let cmdsize = info.cmdsize.get(NativeEndian);
-
-                if cmd == macho::LC_SEGMENT_64 {
-                    let info =
-                        load_struct_inplace::<SegmentCommand64<LittleEndian>>(exec_data, offset);
-
-                    if &info.segname[0..6] == b"__TEXT" {
-                        let sections = info.nsects.get(NativeEndian);
-
-                        let sections_info = load_structs_inplace::<Section64<LittleEndian>>(
-                            exec_data,
-                            offset + mem::size_of_val(info),
-                            sections as usize,
-                        );
-
-                        for section_info in sections_info {
-                            if &section_info.sectname[0..7] == b"__stubs" {
-                                stubs_symbol_index = Some(section_info.reserved1.get(NativeEndian));
-                                stubs_symbol_count =
-                                    Some(section_info.size.get(NativeEndian) / STUB_ADDRESS_OFFSET);
-
-                                break 'cmds;
-                            }
-                        }
-                    }
-                }
-
-                offset += cmdsize as usize;
-            }
-
-            let stubs_symbol_index = stubs_symbol_index.unwrap_or_else(|| {
-                panic!("Could not find stubs symbol index.");
-            });
-            let stubs_symbol_count = stubs_symbol_count.unwrap_or_else(|| {
-                panic!("Could not find stubs symbol count.");
-            });
-
-            // Reset offset before looping through load commands again
-            offset = mem::size_of_val(exec_header);
-
-            let shared_lib_filename = shared_lib.file_name();
-
-            for _ in 0..num_load_cmds {
-                let info =
-                    load_struct_inplace::<macho::LoadCommand<LittleEndian>>(exec_data, offset);
-                let cmd = info.cmd.get(NativeEndian);
-                let cmdsize = info.cmdsize.get(NativeEndian);
-
-                if cmd == macho::LC_DYLD_INFO_ONLY {
-                    let info =
-                        load_struct_inplace::<DyldInfoCommand<LittleEndian>>(exec_data, offset);
-
-                    let lazy_bind_offset = info.lazy_bind_off.get(NativeEndian) as usize;
-
-                    let lazy_bind_symbols = mach_object::LazyBind::parse(
-                        &exec_data[lazy_bind_offset..],
-                        mem::size_of::<usize>(),
+            let (stub_symbols, stub_size) = macho::stub_symbols(exec_data);
+            for (i, index) in stub_symbols.into_iter().enumerate() {
+                if let Some(symbol) = app_syms.iter().find(|symbol| symbol.index() == index) {
+                    let func_address = plt_address + i as u64 * stub_size;
+                    let func_offset = plt_offset + i as u64 * stub_size;
+                    app_func_addresses.insert(func_address, symbol.name().unwrap());
+                    md.plt_addresses.insert(
+                        symbol.name().unwrap().to_string(),
+                        (func_offset, func_address),
                     );
-
-                    // Find all the lazily-bound roc symbols
-                    // (e.g. "_roc__mainForHost_1_exposed")
-                    // For Macho, we may need to deal with some GOT stuff here as well.
-                    for (i, symbol) in lazy_bind_symbols
-                        .skip(stubs_symbol_index as usize)
-                        .take(stubs_symbol_count as usize)
-                        .enumerate()
-                    {
-                        if let Some(sym) = app_syms
-                            .iter()
-                            .find(|app_sym| app_sym.name() == Ok(&symbol.name))
-                        {
-                            let func_address = (i as u64 + 1) * STUB_ADDRESS_OFFSET + plt_address;
-                            let func_offset = (i as u64 + 1) * STUB_ADDRESS_OFFSET + plt_offset;
-                            app_func_addresses.insert(func_address, sym.name().unwrap());
-                            md.plt_addresses.insert(
-                                sym.name().unwrap().to_string(),
-                                (func_offset, func_address),
-                            );
-                        }
-                    }
-                } else if cmd == macho::LC_LOAD_DYLIB {
-                    let info = load_struct_inplace::<DylibCommand<LittleEndian>>(exec_data, offset);
-                    let name_offset = info.dylib.name.offset.get(NativeEndian) as usize;
-                    let str_start_index = offset + name_offset;
-                    let str_end_index = offset + cmdsize as usize;
-                    let str_bytes = &exec_data[str_start_index..str_end_index];
-                    let path = {
-                        if str_bytes[str_bytes.len() - 1] == 0 {
-                            // If it's nul-terminated, it's a C String.
-                            // Use the unchecked version because these are
-                            // padded with 0s at the end, so since we don't
-                            // know the exact length, using the checked version
-                            // of this can fail due to the interior nul bytes.
-                            //
-                            // Also, we have to use from_ptr instead of
-                            // from_bytes_with_nul_unchecked because currently
-                            // std::ffi::CStr is actually not a char* under
-                            // the hood (!) but rather an array, so to strip
-                            // the trailing null bytes we have to use from_ptr.
-                            let c_str = unsafe { CStr::from_ptr(str_bytes.as_ptr() as *const i8) };
-
-                            Path::new(c_str.to_str().unwrap())
-                        } else {
-                            // It wasn't nul-terminated, so treat all the bytes
-                            // as the string
-
-                            Path::new(std::str::from_utf8(str_bytes).unwrap())
-                        }
-                    };
-
-                    if path.file_name() == shared_lib_filename {
-                        macho_load_so_offset = Some(offset);
-                    }
                 }
-
-                offset += cmdsize as usize;
             }
         }
         _ => {
@@ -782,41 +761,22 @@ pub fn preprocess(
                 target_lexicon::Endianness::Little => {
                     let scanning_dynamic_deps_start = Instant::now();
 
-                    // let ElfDynamicDeps {
-                    //     got_app_syms,
-                    //     got_sections,
-                    //     dynamic_lib_count,
-                    //     shared_lib_index,
-                    // } = scan_elf_dynamic_deps(
-                    //     &exec_obj, &mut md, &app_syms, shared_lib, exec_data, verbose,
-                    // );
-
                     scanning_dynamic_deps_duration = scanning_dynamic_deps_start.elapsed();
 
                     platform_gen_start = Instant::now();
 
-                    // TODO little endian
-                    let macho_load_so_offset = match macho_load_so_offset {
-                        Some(offset) => offset,
-                        None => {
-                            internal_error!(
-                                "Host does not link library `{}`!",
-                                shared_lib.display()
-                            );
-                        }
+                    // arm64 macOS uses 16KiB pages.
+                    md.load_align_constraint = match target.architecture {
+                        target_lexicon::Architecture::Aarch64(_) => 0x4000,
+                        _ => 0x1000,
                     };
 
-                    // TODO this is correct on modern Macs (they align to the page size)
-                    // but maybe someone can override the alignment somehow? Maybe in the
-                    // future this could change? Is there some way to make this more future-proof?
-                    md.load_align_constraint = 4096;
-
-                    gen_macho_le(
+                    macho::gen_macho_le(
                         exec_data,
                         &mut md,
                         out_filename,
-                        macho_load_so_offset,
-                        target,
+                        shared_lib,
+                        &app_syms,
                         verbose,
                     )
                 }
@@ -828,7 +788,8 @@ pub fn preprocess(
             }
         }
         target_lexicon::BinaryFormat::Coff => {
-            todo!("Roc does not yet support Windows hosts!");
+            // PE hosts were dealt with at the start
+            unreachable!()
         }
         target_lexicon::BinaryFormat::Wasm => {
             todo!("Roc does not yet support web assembly hosts!");
@@ -901,561 +862,6 @@ pub fn preprocess(
     }
 }
 
-fn gen_macho_le(
-    exec_data: &[u8],
-    md: &mut metadata::Metadata,
-    out_filename: &str,
-    macho_load_so_offset: usize,
-    _target: &Triple,
-    _verbose: bool,
-) -> (MmapMut, File) {
-    // Just adding some extra context/useful info here.
-    // I was talking to Jakub from the Zig team about macho linking and here are some useful comments:
-    // 1) Macho WILL run fine with multiple text segments (and theoretically data segments).
-    // 2) Theoretically the headers just need to be in a loadable segment,
-    //    but otherwise don't need to relate to the starting text segment (releated to some added byte shifting information below).
-    // 2) Apple tooling dislikes whenever you do something non-standard,
-    //    and there is a chance it won't work right (e.g. codesigning might fail)
-    // 3) Jakub wants to make apple tooling absolute is working on zignature for code signing and zig-deploy for ios apps
-    // https://github.com/kubkon/zignature
-    // https://github.com/kubkon/zig-deploy
-
-    use macho::{Section64, SegmentCommand64};
-
-    let exec_header = load_struct_inplace::<macho::MachHeader64<LittleEndian>>(exec_data, 0);
-    let num_load_cmds = exec_header.ncmds.get(NativeEndian);
-    let size_of_cmds = exec_header.sizeofcmds.get(NativeEndian) as usize;
-
-    // Add a new text segment and data segment
-    let segment_cmd_size = mem::size_of::<SegmentCommand64<LittleEndian>>();
-    let section_size = mem::size_of::<Section64<LittleEndian>>();
-
-    // We need the full command size, including the dynamic-length string at the end.
-    // To get that, we need to load the command.
-    let info =
-        load_struct_inplace::<macho::LoadCommand<LittleEndian>>(exec_data, macho_load_so_offset);
-    let total_cmd_size = info.cmdsize.get(NativeEndian) as usize;
-
-    // ======================== Important TODO ==========================
-    // TODO: we accidentally instroduced a big change here.
-    // We use a mix of added_bytes and md.added_byte_count.
-    // Theses should proabably be the same variable.
-    // Also, I just realized that I have been shifting a lot of virtual offsets.
-    // This should not be necessary. If we add a fully 4k of buffer to the file, all of the virtual offsets will still stay aligned.
-    // So a lot of the following work can probably be commented out if we fix that.
-    // Of coruse, it will cost about 4k bytes to a final executable.
-    // This is what the elf version currently does.
-    // Theoretically it is not needed (if we update all virtual addresses in the binary),
-    // but I definitely ran into problems with elf when not adding this extra buffering.
-
-    // Copy header and shift everything to enable more program sections.
-    let added_byte_count = ((2 * segment_cmd_size) + (2 * section_size) - total_cmd_size) as u64;
-    md.added_byte_count = added_byte_count
-        // add some alignment padding
-        + (MIN_SECTION_ALIGNMENT as u64 - added_byte_count % MIN_SECTION_ALIGNMENT as u64);
-
-    md.exec_len = exec_data.len() as u64 + md.added_byte_count;
-
-    let out_file = fs::OpenOptions::new()
-        .read(true)
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(out_filename)
-        .unwrap_or_else(|e| internal_error!("{}", e));
-    out_file
-        .set_len(md.exec_len)
-        .unwrap_or_else(|e| internal_error!("{}", e));
-    let mut out_mmap =
-        unsafe { MmapMut::map_mut(&out_file).unwrap_or_else(|e| internal_error!("{}", e)) };
-    let end_of_cmds = size_of_cmds + mem::size_of_val(exec_header);
-
-    // "Delete" the dylib load command - by copying all the bytes before it
-    // and all the bytes after it, while skipping over its bytes.
-    // It has a dynamic-length string at the end that we also need to delete,
-    // in addition to the header.
-    out_mmap[..macho_load_so_offset].copy_from_slice(&exec_data[..macho_load_so_offset]);
-
-    out_mmap[macho_load_so_offset..end_of_cmds - total_cmd_size]
-        .copy_from_slice(&exec_data[macho_load_so_offset + total_cmd_size..end_of_cmds]);
-
-    // Copy the rest of the file, leaving a gap for the surgical linking to add our 2 commands
-    // (which happens after preprocessing), and some zero padding at the end for alignemnt.
-    // (It seems to cause bugs if that padding isn't there!)
-    let rest_of_data = &exec_data[end_of_cmds..];
-    let start_index = end_of_cmds + md.added_byte_count as usize;
-
-    out_mmap[start_index..start_index + rest_of_data.len()].copy_from_slice(rest_of_data);
-
-    let out_header = load_struct_inplace_mut::<macho::MachHeader64<LittleEndian>>(&mut out_mmap, 0);
-
-    // TODO: this needs to change to adding the 2 new commands when we are ready.
-    // -1 because we're deleting 1 load command and then NOT adding 2 new ones.
-    {
-        let added_bytes = -(total_cmd_size as isize); // TODO: Change when add the new sections.
-        out_header.ncmds.set(LittleEndian, num_load_cmds - 1);
-        out_header
-            .sizeofcmds
-            .set(LittleEndian, (size_of_cmds as isize + added_bytes) as u32);
-    }
-
-    // Go through every command and shift it by added_bytes if it's absolute, unless it's inside the command header
-    let mut offset = mem::size_of_val(exec_header);
-
-    // TODO: Add this back in the future when needed.
-    // let cpu_type = match target.architecture {
-    //     target_lexicon::Architecture::X86_64 => macho::CPU_TYPE_X86_64,
-    //     target_lexicon::Architecture::Aarch64(_) => macho::CPU_TYPE_ARM64,
-    //     _ => {
-    //         // We should have verified this via supported() before calling this function
-    //         unreachable!()
-    //     }
-    // };
-
-    // minus one because we "deleted" a load command
-    for _ in 0..(num_load_cmds - 1) {
-        let info = load_struct_inplace::<macho::LoadCommand<LittleEndian>>(&out_mmap, offset);
-        let cmd_size = info.cmdsize.get(NativeEndian) as usize;
-
-        match info.cmd.get(NativeEndian) {
-            macho::LC_SEGMENT_64 => {
-                let cmd = load_struct_inplace_mut::<macho::SegmentCommand64<LittleEndian>>(
-                    &mut out_mmap,
-                    offset,
-                );
-
-                // Ignore page zero, it never moves.
-                if cmd.segname == "__PAGEZERO\0\0\0\0\0\0".as_bytes()
-                    || cmd.vmaddr.get(NativeEndian) == 0
-                {
-                    offset += cmd_size;
-                    continue;
-                }
-
-                let old_file_offest = cmd.fileoff.get(NativeEndian);
-                // The segment with file offset zero also includes the header.
-                // As such, its file offset does not change.
-                // Instead, its file size should be increased.
-                if old_file_offest > 0 {
-                    cmd.fileoff
-                        .set(LittleEndian, old_file_offest + md.added_byte_count as u64);
-                    cmd.vmaddr.set(
-                        LittleEndian,
-                        cmd.vmaddr.get(NativeEndian) + md.added_byte_count as u64,
-                    );
-                } else {
-                    cmd.filesize.set(
-                        LittleEndian,
-                        cmd.filesize.get(NativeEndian) + md.added_byte_count as u64,
-                    );
-                    cmd.vmsize.set(
-                        LittleEndian,
-                        cmd.vmsize.get(NativeEndian) + md.added_byte_count as u64,
-                    );
-                }
-
-                // let num_sections = cmd.nsects.get(NativeEndian);
-                // let sections = load_structs_inplace_mut::<macho::Section64<LittleEndian>>(
-                //     &mut out_mmap,
-                //     offset + mem::size_of::<macho::SegmentCommand64<LittleEndian>>(),
-                //     num_sections as usize,
-                // );
-                // struct Relocation {
-                //     offset: u32,
-                //     num_relocations: u32,
-                // }
-
-                // let mut relocation_offsets = Vec::with_capacity(sections.len());
-
-                // for section in sections {
-                //     section.addr.set(
-                //         LittleEndian,
-                //         section.addr.get(NativeEndian) + md.added_byte_count as u64,
-                //     );
-
-                //     // If offset is zero, don't update it.
-                //     // Zero is used for things like BSS that don't exist in the file.
-                //     let old_offset = section.offset.get(NativeEndian);
-                //     if old_offset > 0 {
-                //         section
-                //             .offset
-                //             .set(LittleEndian, old_offset + md.added_byte_count as u32);
-                //     }
-
-                //     // dbg!(&section.reloff.get(NativeEndian));
-                //     // dbg!(section.reloff.get(NativeEndian) as i32);
-                //     // dbg!(&section);
-                //     // dbg!(&md.added_byte_count);
-                //     // dbg!(String::from_utf8_lossy(&section.sectname));
-                //     if section.nreloc.get(NativeEndian) > 0 {
-                //         section.reloff.set(
-                //             LittleEndian,
-                //             section.reloff.get(NativeEndian) + md.added_byte_count as u32,
-                //         );
-                //     }
-
-                //     relocation_offsets.push(Relocation {
-                //         offset: section.reloff.get(NativeEndian),
-                //         num_relocations: section.nreloc.get(NativeEndian),
-                //     });
-                // }
-
-                // TODO FIXME this is necessary for ARM, but seems to be broken. Skipping for now since we're just targeting x86
-                // for Relocation {
-                //     offset,
-                //     num_relocations,
-                // } in relocation_offsets
-                // {
-                //     let relos = load_structs_inplace_mut::<macho::Relocation<LittleEndian>>(
-                //         &mut out_mmap,
-                //         offset as usize,
-                //         num_relocations as usize,
-                //     );
-
-                //     // TODO this has never been tested, because scattered relocations only come up on ARM!
-                //     for relo in relos.iter_mut() {
-                //         if relo.r_scattered(LittleEndian, cpu_type) {
-                //             let mut scattered_info = relo.scattered_info(NativeEndian);
-
-                //             if !scattered_info.r_pcrel {
-                //                 scattered_info.r_value += md.added_byte_count as u32;
-
-                //                 let new_info = scattered_info.relocation(LittleEndian);
-
-                //                 relo.r_word0 = new_info.r_word0;
-                //                 relo.r_word1 = new_info.r_word1;
-                //             }
-                //         }
-                //     }
-                // }
-
-                // TODO this seems to be wrong and unnecessary, and should probably be deleted.
-                // offset += num_sections as usize * mem::size_of::<macho::Section64<LittleEndian>>();
-            }
-            macho::LC_SYMTAB => {
-                let cmd = load_struct_inplace_mut::<macho::SymtabCommand<LittleEndian>>(
-                    &mut out_mmap,
-                    offset,
-                );
-
-                let sym_offset = cmd.symoff.get(NativeEndian);
-                let num_syms = cmd.nsyms.get(NativeEndian);
-
-                if num_syms > 0 {
-                    cmd.symoff
-                        .set(LittleEndian, sym_offset + md.added_byte_count as u32);
-                }
-
-                if cmd.strsize.get(NativeEndian) > 0 {
-                    cmd.stroff.set(
-                        LittleEndian,
-                        cmd.stroff.get(NativeEndian) + md.added_byte_count as u32,
-                    );
-                }
-
-                let table = load_structs_inplace_mut::<macho::Nlist64<LittleEndian>>(
-                    &mut out_mmap,
-                    sym_offset as usize + md.added_byte_count as usize,
-                    num_syms as usize,
-                );
-
-                for entry in table {
-                    let entry_type = entry.n_type & macho::N_TYPE;
-                    if entry_type == macho::N_ABS || entry_type == macho::N_SECT {
-                        entry.n_value.set(
-                            LittleEndian,
-                            entry.n_value.get(NativeEndian) + md.added_byte_count as u64,
-                        );
-                    }
-                }
-            }
-            macho::LC_DYSYMTAB => {
-                let cmd = load_struct_inplace_mut::<macho::DysymtabCommand<LittleEndian>>(
-                    &mut out_mmap,
-                    offset,
-                );
-
-                if cmd.ntoc.get(NativeEndian) > 0 {
-                    cmd.tocoff.set(
-                        LittleEndian,
-                        cmd.tocoff.get(NativeEndian) + md.added_byte_count as u32,
-                    );
-                }
-
-                if cmd.nmodtab.get(NativeEndian) > 0 {
-                    cmd.modtaboff.set(
-                        LittleEndian,
-                        cmd.modtaboff.get(NativeEndian) + md.added_byte_count as u32,
-                    );
-                }
-
-                if cmd.nextrefsyms.get(NativeEndian) > 0 {
-                    cmd.extrefsymoff.set(
-                        LittleEndian,
-                        cmd.extrefsymoff.get(NativeEndian) + md.added_byte_count as u32,
-                    );
-                }
-
-                if cmd.nindirectsyms.get(NativeEndian) > 0 {
-                    cmd.indirectsymoff.set(
-                        LittleEndian,
-                        cmd.indirectsymoff.get(NativeEndian) + md.added_byte_count as u32,
-                    );
-                }
-
-                if cmd.nextrel.get(NativeEndian) > 0 {
-                    cmd.extreloff.set(
-                        LittleEndian,
-                        cmd.extreloff.get(NativeEndian) + md.added_byte_count as u32,
-                    );
-                }
-
-                if cmd.nlocrel.get(NativeEndian) > 0 {
-                    cmd.locreloff.set(
-                        LittleEndian,
-                        cmd.locreloff.get(NativeEndian) + md.added_byte_count as u32,
-                    );
-                }
-
-                // TODO maybe we need to update something else too - relocations maybe?
-                // I think this also has symbols that need to get moved around.
-                // Look at otool -I at least for the indirect symbols.
-            }
-            macho::LC_TWOLEVEL_HINTS => {
-                let cmd = load_struct_inplace_mut::<macho::TwolevelHintsCommand<LittleEndian>>(
-                    &mut out_mmap,
-                    offset,
-                );
-
-                if cmd.nhints.get(NativeEndian) > 0 {
-                    cmd.offset.set(
-                        LittleEndian,
-                        cmd.offset.get(NativeEndian) + md.added_byte_count as u32,
-                    );
-                }
-            }
-            macho::LC_FUNCTION_STARTS => {
-                let cmd = load_struct_inplace_mut::<macho::LinkeditDataCommand<LittleEndian>>(
-                    &mut out_mmap,
-                    offset,
-                );
-
-                if cmd.datasize.get(NativeEndian) > 0 {
-                    cmd.dataoff.set(
-                        LittleEndian,
-                        cmd.dataoff.get(NativeEndian) + md.added_byte_count as u32,
-                    );
-                    // TODO: This lists the start of every function. Which, of course, have moved.
-                    // That being said, to my understanding this section is optional and may just be debug information.
-                    // As such, updating it should not be required.
-                    // It will be more work to update due to being in "DWARF-style ULEB128" values.
-                }
-            }
-            macho::LC_DATA_IN_CODE => {
-                let (offset, size) = {
-                    let cmd = load_struct_inplace_mut::<macho::LinkeditDataCommand<LittleEndian>>(
-                        &mut out_mmap,
-                        offset,
-                    );
-
-                    if cmd.datasize.get(NativeEndian) > 0 {
-                        cmd.dataoff.set(
-                            LittleEndian,
-                            cmd.dataoff.get(NativeEndian) + md.added_byte_count as u32,
-                        );
-                    }
-                    (
-                        cmd.dataoff.get(NativeEndian),
-                        cmd.datasize.get(NativeEndian),
-                    )
-                };
-
-                // Update every data in code entry.
-                if size > 0 {
-                    let entry_size = mem::size_of::<macho::DataInCodeEntry<LittleEndian>>();
-                    let entries = load_structs_inplace_mut::<macho::DataInCodeEntry<LittleEndian>>(
-                        &mut out_mmap,
-                        offset as usize,
-                        size as usize / entry_size,
-                    );
-                    for entry in entries.iter_mut() {
-                        entry.offset.set(
-                            LittleEndian,
-                            entry.offset.get(NativeEndian) + md.added_byte_count as u32,
-                        )
-                    }
-                }
-            }
-            macho::LC_CODE_SIGNATURE
-            | macho::LC_SEGMENT_SPLIT_INFO
-            | macho::LC_DYLIB_CODE_SIGN_DRS
-            | macho::LC_LINKER_OPTIMIZATION_HINT
-            | macho::LC_DYLD_EXPORTS_TRIE
-            | macho::LC_DYLD_CHAINED_FIXUPS => {
-                let cmd = load_struct_inplace_mut::<macho::LinkeditDataCommand<LittleEndian>>(
-                    &mut out_mmap,
-                    offset,
-                );
-
-                if cmd.datasize.get(NativeEndian) > 0 {
-                    cmd.dataoff.set(
-                        LittleEndian,
-                        cmd.dataoff.get(NativeEndian) + md.added_byte_count as u32,
-                    );
-                }
-            }
-            macho::LC_ENCRYPTION_INFO_64 => {
-                let cmd = load_struct_inplace_mut::<macho::EncryptionInfoCommand64<LittleEndian>>(
-                    &mut out_mmap,
-                    offset,
-                );
-
-                if cmd.cryptsize.get(NativeEndian) > 0 {
-                    cmd.cryptoff.set(
-                        LittleEndian,
-                        cmd.cryptoff.get(NativeEndian) + md.added_byte_count as u32,
-                    );
-                }
-            }
-            macho::LC_DYLD_INFO | macho::LC_DYLD_INFO_ONLY => {
-                let cmd = load_struct_inplace_mut::<macho::DyldInfoCommand<LittleEndian>>(
-                    &mut out_mmap,
-                    offset,
-                );
-
-                if cmd.rebase_size.get(NativeEndian) > 0 {
-                    cmd.rebase_off.set(
-                        LittleEndian,
-                        cmd.rebase_off.get(NativeEndian) + md.added_byte_count as u32,
-                    );
-                }
-
-                if cmd.bind_size.get(NativeEndian) > 0 {
-                    cmd.bind_off.set(
-                        LittleEndian,
-                        cmd.bind_off.get(NativeEndian) + md.added_byte_count as u32,
-                    );
-                }
-
-                if cmd.weak_bind_size.get(NativeEndian) > 0 {
-                    cmd.weak_bind_off.set(
-                        LittleEndian,
-                        cmd.weak_bind_off.get(NativeEndian) + md.added_byte_count as u32,
-                    );
-                }
-
-                if cmd.lazy_bind_size.get(NativeEndian) > 0 {
-                    cmd.lazy_bind_off.set(
-                        LittleEndian,
-                        cmd.lazy_bind_off.get(NativeEndian) + md.added_byte_count as u32,
-                    );
-                }
-
-                // TODO: Parse and update the related tables here.
-                // It is possible we may just need to delete things that point to stuff that will be in the roc app.
-                // We also may just be able to ignore it (lazy bindings should never run).
-                // This definitely has a list of virtual address that need to be updated.
-                // Some of them definitely will point to the roc app and should probably be removed.
-                // Also `xcrun dyldinfo` is useful for debugging this.
-            }
-            macho::LC_SYMSEG => {
-                let cmd = load_struct_inplace_mut::<macho::SymsegCommand<LittleEndian>>(
-                    &mut out_mmap,
-                    offset,
-                );
-
-                if cmd.size.get(NativeEndian) > 0 {
-                    cmd.offset.set(
-                        LittleEndian,
-                        cmd.offset.get(NativeEndian) + md.added_byte_count as u32,
-                    );
-                }
-            }
-            macho::LC_MAIN => {
-                let cmd = load_struct_inplace_mut::<macho::EntryPointCommand<LittleEndian>>(
-                    &mut out_mmap,
-                    offset,
-                );
-
-                cmd.entryoff.set(
-                    LittleEndian,
-                    cmd.entryoff.get(NativeEndian) + md.added_byte_count as u64,
-                );
-            }
-            macho::LC_NOTE => {
-                let cmd = load_struct_inplace_mut::<macho::NoteCommand<LittleEndian>>(
-                    &mut out_mmap,
-                    offset,
-                );
-
-                if cmd.size.get(NativeEndian) > 0 {
-                    cmd.offset.set(
-                        LittleEndian,
-                        cmd.offset.get(NativeEndian) + md.added_byte_count as u64,
-                    );
-                }
-            }
-            macho::LC_ID_DYLIB
-            | macho::LC_LOAD_WEAK_DYLIB
-            | macho::LC_LOAD_DYLIB
-            | macho::LC_REEXPORT_DYLIB
-            | macho::LC_SOURCE_VERSION
-            | macho::LC_IDENT
-            | macho::LC_LINKER_OPTION
-            | macho::LC_BUILD_VERSION
-            | macho::LC_VERSION_MIN_MACOSX
-            | macho::LC_VERSION_MIN_IPHONEOS
-            | macho::LC_VERSION_MIN_WATCHOS
-            | macho::LC_VERSION_MIN_TVOS
-            | macho::LC_UUID
-            | macho::LC_RPATH
-            | macho::LC_ID_DYLINKER
-            | macho::LC_LOAD_DYLINKER
-            | macho::LC_DYLD_ENVIRONMENT
-            | macho::LC_ROUTINES_64
-            | macho::LC_THREAD
-            | macho::LC_UNIXTHREAD
-            | macho::LC_PREBOUND_DYLIB
-            | macho::LC_SUB_FRAMEWORK
-            | macho::LC_SUB_CLIENT
-            | macho::LC_SUB_UMBRELLA
-            | macho::LC_SUB_LIBRARY => {
-                // These don't involve file offsets, so no change is needed for these.
-                // Just continue the loop!
-            }
-            macho::LC_PREBIND_CKSUM => {
-                // We don't *think* we need to change this, but
-                // maybe what we're doing will break checksums?
-            }
-            macho::LC_SEGMENT | macho::LC_ROUTINES | macho::LC_ENCRYPTION_INFO => {
-                // These are 32-bit and unsuppoted
-                unreachable!();
-            }
-            macho::LC_FVMFILE | macho::LC_IDFVMLIB | macho::LC_LOADFVMLIB => {
-                // These are obsolete and unsupported
-                unreachable!()
-            }
-            cmd => {
-                eprintln!(
-                    "- - - Unrecognized Mach-O command during linker preprocessing: 0x{:x?}",
-                    cmd
-                );
-                // panic!(
-                //     "Unrecognized Mach-O command during linker preprocessing: 0x{:x?}",
-                //     cmd
-                // );
-            }
-        }
-
-        offset += dbg!(cmd_size);
-    }
-
-    // cmd_loc should be where the last offset ended
-    md.macho_cmd_loc = offset as u64;
-
-    (out_mmap, out_file)
-}
-
 #[allow(clippy::too_many_arguments)]
 fn gen_elf_le(
     exec_data: &[u8],
@@ -1485,11 +891,22 @@ fn gen_elf_le(
         println!("SH Entry Count: {}", sh_num);
     }
 
+    let (relative, glob_dat, shift_alignment) = match exec_header.e_machine.get(NativeEndian) {
+        // `adrp` computes addresses relative to the 4KiB page it is in,
+        // so the code has to move by whole pages for those to stay valid.
+        elf::EM_AARCH64 => (elf::R_AARCH64_RELATIVE, elf::R_AARCH64_GLOB_DAT, 0x1000),
+        _ => (
+            elf::R_X86_64_RELATIVE,
+            elf::R_X86_64_GLOB_DAT,
+            MIN_SECTION_ALIGNMENT as u64,
+        ),
+    };
+
     // Copy header and shift everything to enable more program sections.
     let added_header_count = 2;
     md.added_byte_count = ph_ent_size as u64 * added_header_count;
-    md.added_byte_count = md.added_byte_count
-        + (MIN_SECTION_ALIGNMENT as u64 - md.added_byte_count % MIN_SECTION_ALIGNMENT as u64);
+    md.added_byte_count =
+        md.added_byte_count + (shift_alignment - md.added_byte_count % shift_alignment);
     let ph_end = ph_offset as usize + ph_num as usize * ph_ent_size as usize;
     let physical_shift_start = ph_end as u64;
 
@@ -1644,7 +1061,7 @@ fn gen_elf_le(
                 rel.r_offset = endian::U64::new(LittleEndian, r_offset + md.added_byte_count);
                 // Deal with potential adjusts to absolute jumps.
                 // TODO: Verify other relocation types.
-                if rel.r_type(LittleEndian, false) == elf::R_X86_64_RELATIVE {
+                if rel.r_type(LittleEndian, false) == relative {
                     let r_addend = rel.r_addend.get(LittleEndian);
                     rel.r_addend
                         .set(LittleEndian, r_addend + md.added_byte_count as i64);
@@ -1652,11 +1069,11 @@ fn gen_elf_le(
             }
             // If the relocation goes to a roc function, we need to surgically link it and change it to relative.
             let r_type = rel.r_type(NativeEndian, false);
-            if r_type == elf::R_X86_64_GLOB_DAT {
+            if r_type == glob_dat {
                 let r_sym = rel.r_sym(NativeEndian, false);
                 for (name, index) in got_app_syms.iter() {
                     if *index as u32 == r_sym {
-                        rel.set_r_info(LittleEndian, false, 0, elf::R_X86_64_RELATIVE);
+                        rel.set_r_info(LittleEndian, false, 0, relative);
                         let addend_addr = sec_offset as usize
                             + i * mem::size_of::<elf::Rela64<LittleEndian>>()
                             // This 16 skips the first 2 fields and gets to the addend field.
@@ -1935,6 +1352,10 @@ fn scan_elf_dynamic_deps(
         }
     }
 
+    let glob_dat = match exec_obj.architecture() {
+        object::Architecture::Aarch64 => elf::R_AARCH64_GLOB_DAT,
+        _ => elf::R_X86_64_GLOB_DAT,
+    };
     let got_app_syms: Vec<(String, usize)> = (match exec_obj.dynamic_relocations() {
         Some(relocs) => relocs,
         None => {
@@ -1943,7 +1364,7 @@ fn scan_elf_dynamic_deps(
         }
     })
     .filter_map(|(_, reloc)| {
-        if let RelocationKind::Elf(6) = reloc.kind() {
+        if reloc.kind() == RelocationKind::Elf(glob_dat) {
             for symbol in app_syms.iter() {
                 if reloc.target() == RelocationTarget::Symbol(symbol.index()) {
                     return Some((symbol.name().unwrap().to_string(), symbol.index().0));
@@ -1970,6 +1391,11 @@ pub fn surgery(
     time: bool,
     target: &Triple,
 ) {
+    if let target_lexicon::BinaryFormat::Coff = target.binary_format {
+        pe::surgery_pe(app_filename, metadata_filename, out_filename, verbose, time);
+        return;
+    }
+
     let total_start = Instant::now();
     let loading_metadata_start = total_start;
     let md: metadata::Metadata = {
@@ -2003,7 +1429,17 @@ pub fn surgery(
         .open(out_filename)
         .unwrap_or_else(|e| internal_error!("{}", e));
 
-    let max_out_len = md.exec_len + app_data.len() as u64 + md.load_align_constraint;
+    let max_out_len = match target.binary_format {
+        // Mach-O hosts also move their __LINKEDIT segment behind the app, and their code
+        // signature grows with the size of the file.
+        target_lexicon::BinaryFormat::Macho => {
+            md.exec_len
+                + app_data.len() as u64
+                + app_data.len() as u64 / 64
+                + 4 * md.load_align_constraint
+        }
+        _ => md.exec_len + app_data.len() as u64 + md.load_align_constraint,
+    };
     exec_file
         .set_len(max_out_len)
         .unwrap_or_else(|e| internal_error!("{}", e));
@@ -2019,17 +1455,9 @@ pub fn surgery(
         target_lexicon::BinaryFormat::Elf => {
             surgery_elf(verbose, &md, &mut exec_mmap, &mut offset, app_obj)
         }
-        target_lexicon::BinaryFormat::Macho => surgery_macho(
-            app_filename,
-            metadata_filename,
-            out_filename,
-            verbose,
-            time,
-            &md,
-            &mut exec_mmap,
-            &mut offset,
-            app_obj,
-        ),
+        target_lexicon::BinaryFormat::Macho => {
+            macho::surgery_macho(verbose, &md, &mut exec_mmap, &mut offset, app_obj)
+        }
         _ => {
             // We should have verified this via supported() before calling this function
             unreachable!()
@@ -2046,8 +1474,13 @@ pub fn surgery(
     // Also drop files to to ensure data is fully written here.
     drop(exec_mmap);
 
+    let out_len = match target.binary_format {
+        // The code signature has to be the very end of the file.
+        target_lexicon::BinaryFormat::Macho => offset as u64,
+        _ => offset as u64 + 1,
+    };
     exec_file
-        .set_len(offset as u64 + 1)
+        .set_len(out_len)
         .unwrap_or_else(|e| internal_error!("{}", e));
     drop(exec_file);
     let flushing_data_duration = flushing_data_start.elapsed();
@@ -2086,428 +1519,6 @@ pub fn surgery(
     }
 }
 
-#[allow(clippy::too_many_arguments)]
-pub fn surgery_macho(
-    _app_filename: &str,
-    _metadata_filename: &str,
-    _out_filename: &str,
-    verbose: bool,
-    _time: bool,
-    md: &metadata::Metadata,
-    exec_mmap: &mut MmapMut,
-    offset_ref: &mut usize, // TODO return this instead of taking a mutable reference to it
-    app_obj: object::File,
-) {
-    let mut offset = align_by_constraint(md.exec_len as usize, MIN_SECTION_ALIGNMENT);
-    // let new_rodata_section_offset = offset;
-
-    // Align physical and virtual address of new segment.
-    let mut virt_offset = align_to_offset_by_constraint(
-        md.last_vaddr as usize,
-        offset,
-        md.load_align_constraint as usize,
-    );
-    let new_rodata_section_vaddr = virt_offset;
-    if verbose {
-        println!();
-        println!(
-            "New Virtual Rodata Section Address: {:+x?}",
-            new_rodata_section_vaddr
-        );
-    }
-
-    // First decide on sections locations and then recode every exact symbol locations.
-
-    // Copy sections and resolve their symbols/relocations.
-    let symbols = app_obj.symbols().collect::<Vec<Symbol>>();
-    let mut section_offset_map: MutMap<SectionIndex, (usize, usize)> = MutMap::default();
-    let mut symbol_vaddr_map: MutMap<SymbolIndex, usize> = MutMap::default();
-    let mut app_func_vaddr_map: MutMap<String, usize> = MutMap::default();
-    let mut app_func_size_map: MutMap<String, u64> = MutMap::default();
-
-    // TODO: In the future Roc may use a data section to store memoized toplevel thunks
-    // in development builds for caching the results of top-level constants
-
-    let rodata_sections: Vec<Section> = app_obj
-        .sections()
-        .filter(|sec| sec.kind() == SectionKind::ReadOnlyData)
-        .collect();
-
-    // bss section is like rodata section, but it has zero file size and non-zero virtual size.
-    let bss_sections: Vec<Section> = app_obj
-        .sections()
-        .filter(|sec| sec.kind() == SectionKind::UninitializedData)
-        .collect();
-
-    let text_sections: Vec<Section> = app_obj
-        .sections()
-        .filter(|sec| sec.kind() == SectionKind::Text)
-        .collect();
-    if text_sections.is_empty() {
-        internal_error!("No text sections found. This application has no code.");
-    }
-
-    // Calculate addresses and load symbols.
-    // Note, it is important the bss sections come after the rodata sections.
-    for sec in rodata_sections
-        .iter()
-        .chain(bss_sections.iter())
-        .chain(text_sections.iter())
-    {
-        offset = align_by_constraint(offset, MIN_SECTION_ALIGNMENT);
-        virt_offset =
-            align_to_offset_by_constraint(virt_offset, offset, md.load_align_constraint as usize);
-        if verbose {
-            println!(
-                "Section, {}, is being put at offset: {:+x}(virt: {:+x})",
-                sec.name().unwrap(),
-                offset,
-                virt_offset
-            )
-        }
-        section_offset_map.insert(sec.index(), (offset, virt_offset));
-        for sym in symbols.iter() {
-            if sym.section() == SymbolSection::Section(sec.index()) {
-                let name = sym.name().unwrap_or_default().to_string();
-                if !md.roc_symbol_vaddresses.contains_key(&name) {
-                    symbol_vaddr_map.insert(sym.index(), virt_offset + sym.address() as usize);
-                }
-                if md.app_functions.contains(&name) {
-                    app_func_vaddr_map.insert(name.clone(), virt_offset + sym.address() as usize);
-                    app_func_size_map.insert(name, sym.size());
-                }
-            }
-        }
-        let section_size = match sec.file_range() {
-            Some((_, size)) => size,
-            None => 0,
-        };
-        if sec.name().unwrap_or_default().starts_with("__BSS") {
-            // bss sections only modify the virtual size.
-            virt_offset += sec.size() as usize;
-        } else if section_size != sec.size() {
-            internal_error!( "We do not deal with non bss sections that have different on disk and in memory sizes");
-        } else {
-            offset += section_size as usize;
-            virt_offset += sec.size() as usize;
-        }
-    }
-    if verbose {
-        println!("Data Relocation Offsets: {:+x?}", symbol_vaddr_map);
-        println!("Found App Function Symbols: {:+x?}", app_func_vaddr_map);
-    }
-
-    // let (new_text_section_offset, new_text_section_vaddr) = text_sections
-    //     .iter()
-    //     .map(|sec| section_offset_map.get(&sec.index()).unwrap())
-    //     .min()
-    //     .unwrap();
-    // let (new_text_section_offset, new_text_section_vaddr) =
-    //     (*new_text_section_offset, *new_text_section_vaddr);
-
-    // Move data and deal with relocations.
-    for sec in rodata_sections
-        .iter()
-        .chain(bss_sections.iter())
-        .chain(text_sections.iter())
-    {
-        let data = match sec.data() {
-            Ok(data) => data,
-            Err(err) => {
-                internal_error!(
-                    "Failed to load data for section, {:+x?}: {}",
-                    sec.name().unwrap(),
-                    err
-                );
-            }
-        };
-        let (section_offset, section_virtual_offset) =
-            section_offset_map.get(&sec.index()).unwrap();
-        let (section_offset, section_virtual_offset) = (*section_offset, *section_virtual_offset);
-        exec_mmap[section_offset..section_offset + data.len()].copy_from_slice(data);
-        // Deal with definitions and relocations for this section.
-        if verbose {
-            println!();
-            println!(
-                "Processing Relocations for Section: 0x{:+x?} @ {:+x} (virt: {:+x})",
-                sec, section_offset, section_virtual_offset
-            );
-        }
-        for rel in sec.relocations() {
-            if verbose {
-                println!("\tFound Relocation: {:+x?}", rel);
-            }
-            match rel.1.target() {
-                RelocationTarget::Symbol(index) => {
-                    let target_offset = if let Some(target_offset) = symbol_vaddr_map.get(&index) {
-                        if verbose {
-                            println!(
-                                "\t\tRelocation targets symbol in app at: {:+x}",
-                                target_offset
-                            );
-                        }
-                        Some(*target_offset as i64)
-                    } else {
-                        app_obj
-                            .symbol_by_index(index)
-                            .and_then(|sym| sym.name())
-                            .ok()
-                            .and_then(|name| {
-                                md.roc_symbol_vaddresses.get(name).map(|address| {
-                                    let vaddr = (*address + md.added_byte_count) as i64;
-                                    if verbose {
-                                        println!(
-                                            "\t\tRelocation targets symbol in host: {} @ {:+x}",
-                                            name, vaddr
-                                        );
-                                    }
-                                    vaddr
-                                })
-                            })
-                    };
-
-                    if let Some(target_offset) = target_offset {
-                        let virt_base = section_virtual_offset as usize + rel.0 as usize;
-                        let base = section_offset as usize + rel.0 as usize;
-                        let target: i64 = match rel.1.kind() {
-                            RelocationKind::Relative | RelocationKind::PltRelative => {
-                                target_offset - virt_base as i64 + rel.1.addend()
-                            }
-                            x => {
-                                internal_error!("Relocation Kind not yet support: {:?}", x);
-                            }
-                        };
-                        if verbose {
-                            println!(
-                                "\t\tRelocation base location: {:+x} (virt: {:+x})",
-                                base, virt_base
-                            );
-                            println!("\t\tFinal relocation target offset: {:+x}", target);
-                        }
-                        match rel.1.size() {
-                            32 => {
-                                let data = (target as i32).to_le_bytes();
-                                exec_mmap[base..base + 4].copy_from_slice(&data);
-                            }
-                            64 => {
-                                let data = target.to_le_bytes();
-                                exec_mmap[base..base + 8].copy_from_slice(&data);
-                            }
-                            x => {
-                                internal_error!("Relocation size not yet supported: {}", x);
-                            }
-                        }
-                    } else if matches!(app_obj.symbol_by_index(index), Ok(sym) if ["__divti3", "__udivti3", "___divti3", "___udivti3"].contains(&sym.name().unwrap_or_default()))
-                    {
-                        // Explicitly ignore some symbols that are currently always linked.
-                        continue;
-                    } else {
-                        internal_error!(
-                            "Undefined Symbol in relocation, {:+x?}: {:+x?}",
-                            rel,
-                            app_obj.symbol_by_index(index)
-                        );
-                    }
-                }
-
-                _ => {
-                    internal_error!("Relocation target not yet support: {:+x?}", rel);
-                }
-            }
-        }
-    }
-
-    // Flush app only data to speed up write to disk.
-    exec_mmap
-        .flush_async_range(md.exec_len as usize, offset - md.exec_len as usize)
-        .unwrap_or_else(|e| internal_error!("{}", e));
-
-    // TODO: look into merging symbol tables, debug info, and eh frames to enable better debugger experience.
-
-    // let mut cmd_offset = md.macho_cmd_loc as usize;
-
-    // // Load this section (we made room for it earlier) and then mutate all its data to make it the desired command
-    // {
-    //     let cmd =
-    //         load_struct_inplace_mut::<macho::SegmentCommand64<LittleEndian>>(exec_mmap, cmd_offset);
-    //     let size_of_section = mem::size_of::<macho::Section64<LittleEndian>>() as u32;
-    //     let size_of_cmd = mem::size_of_val(cmd);
-
-    //     cmd_offset += size_of_cmd;
-
-    //     cmd.cmd.set(LittleEndian, macho::LC_SEGMENT_64);
-    //     cmd.cmdsize
-    //         .set(LittleEndian, size_of_section + size_of_cmd as u32);
-    //     cmd.segname = *b"__DATA_CONST\0\0\0\0";
-    //     cmd.vmaddr
-    //         .set(LittleEndian, new_rodata_section_vaddr as u64);
-    //     cmd.vmsize.set(
-    //         LittleEndian,
-    //         (new_text_section_vaddr - new_rodata_section_vaddr) as u64,
-    //     );
-    //     cmd.fileoff
-    //         .set(LittleEndian, new_rodata_section_offset as u64);
-    //     cmd.filesize.set(
-    //         LittleEndian,
-    //         (new_text_section_offset - new_rodata_section_offset) as u64,
-    //     );
-    //     cmd.nsects.set(LittleEndian, 1);
-    //     cmd.maxprot.set(LittleEndian, 0x00000003);
-    //     cmd.initprot.set(LittleEndian, 0x00000003);
-
-    //     // TODO set protection
-    // }
-
-    // {
-    //     let cmd = load_struct_inplace_mut::<macho::Section64<LittleEndian>>(exec_mmap, cmd_offset);
-    //     let size_of_cmd = mem::size_of_val(cmd);
-
-    //     cmd_offset += size_of_cmd;
-
-    //     cmd.sectname = *b"__const\0\0\0\0\0\0\0\0\0";
-    //     cmd.segname = *b"__DATA_CONST\0\0\0\0";
-    //     cmd.addr.set(LittleEndian, new_rodata_section_vaddr as u64);
-    //     cmd.size.set(
-    //         LittleEndian,
-    //         (new_text_section_offset - new_rodata_section_offset) as u64,
-    //     );
-    //     cmd.offset.set(LittleEndian, 0); // TODO is this offset since the start of the file, or segment offset?
-    //     cmd.align.set(LittleEndian, 12); // TODO should this be 4096?
-    //     cmd.reloff.set(LittleEndian, 264); // TODO this should NOT be hardcoded! Should get it from somewhere.
-    // }
-
-    // {
-    //     let cmd =
-    //         load_struct_inplace_mut::<macho::SegmentCommand64<LittleEndian>>(exec_mmap, cmd_offset);
-    //     let size_of_section = mem::size_of::<macho::Section64<LittleEndian>>() as u32;
-    //     let size_of_cmd = mem::size_of_val(cmd);
-
-    //     cmd_offset += size_of_cmd;
-
-    //     cmd.cmd.set(LittleEndian, macho::LC_SEGMENT_64);
-    //     cmd.cmdsize
-    //         .set(LittleEndian, size_of_section + size_of_cmd as u32);
-    //     cmd.segname = *b"__TEXT\0\0\0\0\0\0\0\0\0\0";
-    //     cmd.vmaddr.set(LittleEndian, new_text_section_vaddr as u64);
-    //     cmd.vmsize
-    //         .set(LittleEndian, (offset - new_text_section_offset) as u64);
-    //     cmd.fileoff
-    //         .set(LittleEndian, new_text_section_offset as u64);
-    //     cmd.filesize
-    //         .set(LittleEndian, (offset - new_text_section_offset) as u64);
-    //     cmd.nsects.set(LittleEndian, 1);
-    //     cmd.maxprot.set(LittleEndian, 0x00000005); // this is what a zig-generated host had
-    //     cmd.initprot.set(LittleEndian, 0x00000005); // this is what a zig-generated host had
-    // }
-
-    // {
-    //     let cmd = load_struct_inplace_mut::<macho::Section64<LittleEndian>>(exec_mmap, cmd_offset);
-
-    //     cmd.segname = *b"__TEXT\0\0\0\0\0\0\0\0\0\0";
-    //     cmd.sectname = *b"__text\0\0\0\0\0\0\0\0\0\0";
-    //     cmd.addr.set(LittleEndian, new_text_section_vaddr as u64);
-    //     cmd.size
-    //         .set(LittleEndian, (offset - new_text_section_offset) as u64);
-    //     cmd.offset.set(LittleEndian, 0); // TODO is this offset since the start of the file, or segment offset?
-    //     cmd.align.set(LittleEndian, 12); // TODO this is 4096 (2^12) - which load_align_constraint does, above - but should it?
-    //     cmd.flags.set(LittleEndian, 0x80000400); // TODO this is what a zig-generated host had
-    //     cmd.reloff.set(LittleEndian, 264); // TODO this should NOT be hardcoded! Should get it from somewhere.
-    // }
-
-    // Update calls from platform and dynamic symbols.
-    // let dynsym_offset = md.dynamic_symbol_table_section_offset + md.added_byte_count;
-
-    for func_name in md.app_functions.iter() {
-        let func_virt_offset = match app_func_vaddr_map.get(func_name) {
-            Some(offset) => *offset as u64,
-            None => {
-                internal_error!("Function, {}, was not defined by the app", &func_name);
-            }
-        };
-        if verbose {
-            println!(
-                "Updating calls to {} to the address: {:+x}",
-                &func_name, func_virt_offset
-            );
-        }
-
-        for s in md.surgeries.get(func_name).unwrap_or(&vec![]) {
-            if verbose {
-                println!("\tPerforming surgery: {:+x?}", s);
-            }
-            let surgery_virt_offset = match s.virtual_offset {
-                VirtualOffset::Relative(vs) => (vs + md.added_byte_count) as i64,
-                VirtualOffset::Absolute => 0,
-            };
-            match s.size {
-                4 => {
-                    let target = (func_virt_offset as i64 - surgery_virt_offset) as i32;
-                    if verbose {
-                        println!("\tTarget Jump: {:+x}", target);
-                    }
-                    let data = target.to_le_bytes();
-                    exec_mmap[(s.file_offset + md.added_byte_count) as usize
-                        ..(s.file_offset + md.added_byte_count) as usize + 4]
-                        .copy_from_slice(&data);
-                }
-                8 => {
-                    let target = func_virt_offset as i64 - surgery_virt_offset;
-                    if verbose {
-                        println!("\tTarget Jump: {:+x}", target);
-                    }
-                    let data = target.to_le_bytes();
-                    exec_mmap[(s.file_offset + md.added_byte_count) as usize
-                        ..(s.file_offset + md.added_byte_count) as usize + 8]
-                        .copy_from_slice(&data);
-                }
-                x => {
-                    internal_error!("Surgery size not yet supported: {}", x);
-                }
-            }
-        }
-
-        // Replace plt call code with just a jump.
-        // This is a backup incase we missed a call to the plt.
-        if let Some((plt_off, plt_vaddr)) = md.plt_addresses.get(func_name) {
-            let plt_off = (*plt_off + md.added_byte_count) as usize;
-            let plt_vaddr = *plt_vaddr + md.added_byte_count;
-            let jmp_inst_len = 5;
-            let target =
-                (func_virt_offset as i64 - (plt_vaddr as i64 + jmp_inst_len as i64)) as i32;
-            if verbose {
-                println!("\tPLT: {:+x}, {:+x}", plt_off, plt_vaddr);
-                println!("\tTarget Jump: {:+x}", target);
-            }
-            let data = target.to_le_bytes();
-            exec_mmap[plt_off] = 0xE9;
-            exec_mmap[plt_off + 1..plt_off + jmp_inst_len].copy_from_slice(&data);
-            for i in jmp_inst_len..PLT_ADDRESS_OFFSET as usize {
-                exec_mmap[plt_off + i] = 0x90;
-            }
-        }
-
-        // Commented out because it doesn't apply to mach-o
-        // if let Some(i) = md.dynamic_symbol_indices.get(func_name) {
-        //     let sym = load_struct_inplace_mut::<elf::Sym64<LittleEndian>>(
-        //         exec_mmap,
-        //         dynsym_offset as usize + *i as usize * mem::size_of::<elf::Sym64<LittleEndian>>(),
-        //     );
-        //     sym.st_value = endian::U64::new(LittleEndian, func_virt_offset as u64);
-        //     sym.st_size = endian::U64::new(
-        //         LittleEndian,
-        //         match app_func_size_map.get(func_name) {
-        //             Some(size) => *size,
-        //             None => {
-        //                 internal_error!("Size missing for: {}", func_name);
-        //             }
-        //         },
-        //     );
-        // }
-    }
-
-    *offset_ref = offset;
-}
-
 pub fn surgery_elf(
     verbose: bool,
     md: &metadata::Metadata,
@@ -2522,6 +1533,7 @@ pub fn surgery_elf(
     }
     let exec_header = load_struct_inplace::<elf::FileHeader64<LittleEndian>>(exec_mmap, 0);
 
+    let is_aarch64 = exec_header.e_machine.get(NativeEndian) == elf::EM_AARCH64;
     let ph_offset = exec_header.e_phoff.get(NativeEndian);
     let ph_ent_size = exec_header.e_phentsize.get(NativeEndian);
     let ph_num = exec_header.e_phnum.get(NativeEndian);
@@ -2713,6 +1725,28 @@ pub fn surgery_elf(
                     if let Some(target_offset) = target_offset {
                         let virt_base = section_virtual_offset as usize + rel.0 as usize;
                         let base = section_offset as usize + rel.0 as usize;
+
+                        // Apart from plain data relocations,
+                        // AArch64 relocations patch the immediate fields of an instruction.
+                        if is_aarch64 && !matches!(rel.1.size(), 32 | 64) {
+                            let target = target_offset + rel.1.addend();
+                            if verbose {
+                                println!(
+                                    "\t\tRelocation base location: {:+x} (virt: {:+x})",
+                                    base, virt_base
+                                );
+                                println!("\t\tFinal relocation target: {:+x}", target);
+                            }
+                            aarch64::apply_relocation(
+                                &mut exec_mmap[base..],
+                                rel.1.kind(),
+                                virt_base as i64,
+                                target,
+                            )
+                            .unwrap_or_else(|e| internal_error!("{}", e));
+                            continue;
+                        }
+
                         let target: i64 = match rel.1.kind() {
                             RelocationKind::Relative | RelocationKind::PltRelative => {
                                 target_offset - virt_base as i64 + rel.1.addend()
@@ -2880,8 +1914,22 @@ pub fn surgery_elf(
             let surgery_virt_offset = match s.virtual_offset {
                 VirtualOffset::Relative(vs) => (vs + md.added_byte_count) as i64,
                 VirtualOffset::Absolute => 0,
+                VirtualOffset::ChainedRebase(_) => {
+                    internal_error!("Chained fixups are only used by Mach-O: {:+x?}", s);
+                }
             };
             match s.size {
+                4 if is_aarch64 => {
+                    if verbose {
+                        println!("\tTarget Jump: {:+x}", func_virt_offset);
+                    }
+                    aarch64::patch_branch(
+                        &mut exec_mmap[(s.file_offset + md.added_byte_count) as usize..],
+                        surgery_virt_offset,
+                        func_virt_offset as i64,
+                    )
+                    .unwrap_or_else(|e| internal_error!("{}", e));
+                }
                 4 => {
                     let target = (func_virt_offset as i64 - surgery_virt_offset) as i32;
                     if verbose {
@@ -2911,18 +1959,31 @@ pub fn surgery_elf(
         if let Some((plt_off, plt_vaddr)) = md.plt_addresses.get(func_name) {
             let plt_off = (*plt_off + md.added_byte_count) as usize;
             let plt_vaddr = *plt_vaddr + md.added_byte_count;
-            let jmp_inst_len = 5;
-            let target =
-                (func_virt_offset as i64 - (plt_vaddr as i64 + jmp_inst_len as i64)) as i32;
-            if verbose {
-                println!("\tPLT: {:+x}, {:+x}", plt_off, plt_vaddr);
-                println!("\tTarget Jump: {:+x}", target);
-            }
-            let data = target.to_le_bytes();
-            exec_mmap[plt_off] = 0xE9;
-            exec_mmap[plt_off + 1..plt_off + jmp_inst_len].copy_from_slice(&data);
-            for i in jmp_inst_len..PLT_ADDRESS_OFFSET as usize {
-                exec_mmap[plt_off + i] = 0x90;
+            if is_aarch64 {
+                if verbose {
+                    println!("\tPLT: {:+x}, {:+x}", plt_off, plt_vaddr);
+                    println!("\tTarget Jump: {:+x}", func_virt_offset);
+                }
+                aarch64::write_plt_stub(
+                    &mut exec_mmap[plt_off..][..aarch64::PLT_ENTRY_SIZE as usize],
+                    plt_vaddr as i64,
+                    func_virt_offset as i64,
+                )
+                .unwrap_or_else(|e| internal_error!("{}", e));
+            } else {
+                let jmp_inst_len = 5;
+                let target =
+                    (func_virt_offset as i64 - (plt_vaddr as i64 + jmp_inst_len as i64)) as i32;
+                if verbose {
+                    println!("\tPLT: {:+x}, {:+x}", plt_off, plt_vaddr);
+                    println!("\tTarget Jump: {:+x}", target);
+                }
+                let data = target.to_le_bytes();
+                exec_mmap[plt_off] = 0xE9;
+                exec_mmap[plt_off + 1..plt_off + jmp_inst_len].copy_from_slice(&data);
+                for i in jmp_inst_len..PLT_ADDRESS_OFFSET as usize {
+                    exec_mmap[plt_off + i] = 0x90;
+                }
             }
         }
 
@@ -2999,8 +2060,17 @@ fn load_structs_inplace_mut<T>(bytes: &mut [u8], offset: usize, count: usize) ->
 mod tests {
     use super::*;
 
+    use object::ObjectSegment;
+
     const ELF64_DYNHOST: &[u8] = include_bytes!("../dynhost_benchmarks_elf64") as &[_];
 
+    // Assembled from the `.s` files next to them. The host is then linked with e.g.
+    //   ld.lld -pie --dynamic-linker /lib/ld-linux-aarch64.so.1 -e _start \
+    //       dynhost_aarch64_elf64.o libapp.so -o dynhost_aarch64_elf64
+    // where libapp.so is a shared library with the soname `libapp.so` defining the app functions.
+    const AARCH64_ELF64_DYNHOST: &[u8] = include_bytes!("../dynhost_aarch64_elf64") as &[_];
+    const AARCH64_ELF64_APP: &[u8] = include_bytes!("../app_aarch64_elf64.o") as &[_];
+
     #[test]
     fn collect_definitions() {
         let object = object::File::parse(ELF64_DYNHOST).unwrap();
@@ -3051,4 +2121,214 @@ mod tests {
             keys.as_slice()
         )
     }
+
+    fn aarch64_linux() -> Triple {
+        "aarch64-unknown-linux-gnu".parse().unwrap()
+    }
+
+    pub(crate) fn symbol_address(object: &object::File, name: &str) -> u64 {
+        object
+            .symbols()
+            .chain(object.dynamic_symbols())
+            .find(|sym| sym.name() == Ok(name) && sym.is_definition())
+            .unwrap_or_else(|| panic!("{} is not defined", name))
+            .address()
+    }
+
+    pub(crate) fn read_instructions(object: &object::File, address: u64, count: u64) -> Vec<u32> {
+        read_data(object, address, count * 4)
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+            .collect()
+    }
+
+    pub(crate) fn branch_target(inst: u32, address: u64) -> u64 {
+        let bytes = inst.to_le_bytes();
+        let branch = aarch64::branches(&bytes, address).next();
+
+        match branch {
+            Some((_, target)) => target,
+            None => panic!("{:08x} at {:+x} is not a branch", inst, address),
+        }
+    }
+
+    /// The page an `adrp` at `address` loads into its register.
+    pub(crate) fn adrp_page(adrp: u32, address: u64) -> u64 {
+        assert_eq!(
+            adrp & 0x9f00_0000,
+            0x9000_0000,
+            "{:08x} is not an adrp",
+            adrp
+        );
+
+        let immlo = ((adrp >> 29) & 0x3) as i64;
+        let immhi = ((((adrp >> 5) & 0x7_ffff) << 13) as i32 >> 13) as i64;
+        ((address & !0xfff) as i64 + ((immhi << 2) | immlo) * 0x1000) as u64
+    }
+
+    /// The unsigned immediate of an `add` or a load, scaled by the size of the load.
+    pub(crate) fn lo12(inst: u32, scale: u32) -> u64 {
+        (((inst >> 10) & 0xfff) << scale) as u64
+    }
+
+    pub(crate) fn read_data(object: &object::File, address: u64, size: u64) -> Vec<u8> {
+        object
+            .segments()
+            .find_map(|segment| segment.data_range(address, size).unwrap())
+            .unwrap_or_else(|| panic!("{:+x} is not loaded from the file", address))
+            .to_vec()
+    }
+
+    #[test]
+    fn supported_aarch64_linux() {
+        assert!(supported(LinkType::Executable, &aarch64_linux()));
+        assert!(!supported(LinkType::Dylib, &aarch64_linux()));
+    }
+
+    #[test]
+    fn supported_macho() {
+        for target in ["x86_64-apple-darwin", "aarch64-apple-darwin"] {
+            let target: Triple = target.parse().unwrap();
+            assert!(supported(LinkType::Executable, &target));
+            assert!(!supported(LinkType::Dylib, &target));
+        }
+    }
+
+    #[test]
+    fn supported_windows() {
+        let target: Triple = "x86_64-pc-windows-msvc".parse().unwrap();
+        assert!(supported(LinkType::Executable, &target));
+        assert!(!supported(LinkType::Dylib, &target));
+    }
+
+    #[test]
+    fn preprocess_and_surgery_elf_aarch64() {
+        let target = aarch64_linux();
+
+        let dir = tempfile::tempdir().unwrap();
+        let dynhost = dir.path().join("dynhost");
+        let metadata = dir.path().join("metadata");
+        let preprocessed_host = dir.path().join("preprocessedhost");
+        let app = dir.path().join("app.o");
+        let output = dir.path().join("output");
+
+        std::fs::write(&dynhost, AARCH64_ELF64_DYNHOST).unwrap();
+        std::fs::write(&app, AARCH64_ELF64_APP).unwrap();
+
+        preprocess(
+            &target,
+            dynhost.to_str().unwrap(),
+            metadata.to_str().unwrap(),
+            preprocessed_host.to_str().unwrap(),
+            Path::new("libapp.so"),
+            false,
+            false,
+        );
+
+        let md: metadata::Metadata =
+            deserialize_from(BufReader::new(File::open(&metadata).unwrap())).unwrap();
+
+        // adrp must keep pointing at the right pages.
+        assert_eq!(md.added_byte_count % 0x1000, 0);
+
+        // `_start` calls both functions directly, and also loads the size function from the GOT.
+        let surgeries = &md.surgeries["roc__mainForHost_1_exposed_generic"];
+        assert_eq!(surgeries.len(), 2);
+        assert!(surgeries.iter().all(|s| s.size == 4));
+        let surgeries = &md.surgeries["roc__mainForHost_size"];
+        assert_eq!(surgeries.len(), 2);
+        assert!(surgeries
+            .iter()
+            .any(|s| s.virtual_offset == VirtualOffset::Absolute && s.size == 8));
+
+        std::fs::copy(&preprocessed_host, &output).unwrap();
+        surgery(
+            app.to_str().unwrap(),
+            metadata.to_str().unwrap(),
+            output.to_str().unwrap(),
+            false,
+            false,
+            &target,
+        );
+
+        let output_data = std::fs::read(&output).unwrap();
+        let object = object::File::parse(output_data.as_slice()).unwrap();
+        assert_eq!(object.architecture(), object::Architecture::Aarch64);
+
+        // The section headers are written last, and followed by a single byte.
+        let header = load_struct_inplace::<elf::FileHeader64<LittleEndian>>(&output_data, 0);
+        let sh_end = header.e_shoff.get(NativeEndian)
+            + header.e_shnum.get(NativeEndian) as u64 * header.e_shentsize.get(NativeEndian) as u64;
+        assert_eq!(output_data.len() as u64, sh_end + 1);
+
+        let main = symbol_address(&object, "roc__mainForHost_1_exposed_generic");
+        let size = symbol_address(&object, "roc__mainForHost_size");
+        let roc_alloc = symbol_address(&object, "roc_alloc");
+        let roc_dealloc = symbol_address(&object, "roc_dealloc");
+
+        // The app functions are defined in a new executable segment.
+        for address in [main, size] {
+            assert!(object.sections().any(|sec| {
+                sec.kind() == SectionKind::Text
+                    && (sec.address()..sec.address() + sec.size()).contains(&address)
+            }));
+        }
+
+        // The host no longer depends on the dummy library.
+        let dynamic = object.section_by_name(".dynamic").unwrap();
+        let needed = dynamic
+            .data()
+            .unwrap()
+            .chunks_exact(16)
+            .map(|entry| u64::from_le_bytes(entry[..8].try_into().unwrap()))
+            .take_while(|tag| *tag != elf::DT_NULL as u64)
+            .filter(|tag| *tag == elf::DT_NEEDED as u64)
+            .count();
+        assert_eq!(needed, 0);
+
+        // stp; bl size; bl main; adrp; ldr; blr; ldp; b main
+        let start = symbol_address(&object, "_start");
+        let host_code = read_instructions(&object, start, 8);
+        assert_eq!(branch_target(host_code[1], start + 4), size);
+        assert_eq!(branch_target(host_code[2], start + 8), main);
+        assert_eq!(branch_target(host_code[7], start + 28), main);
+
+        // The GOT entry is now filled in by a relative relocation.
+        let (_, got_reloc) = object
+            .dynamic_relocations()
+            .unwrap()
+            .find(|(_, reloc)| reloc.kind() == RelocationKind::Elf(elf::R_AARCH64_RELATIVE))
+            .unwrap();
+        assert_eq!(got_reloc.addend(), size as i64);
+
+        // Any call that still goes through the plt jumps straight to the app.
+        for (name, address) in [
+            ("roc__mainForHost_1_exposed_generic", main),
+            ("roc__mainForHost_size", size),
+        ] {
+            let (_, plt_vaddr) = md.plt_addresses[name];
+            let plt_vaddr = plt_vaddr + md.added_byte_count;
+            let plt_code = read_instructions(&object, plt_vaddr, 1);
+            assert_eq!(branch_target(plt_code[0], plt_vaddr), address);
+        }
+
+        // adrp; ldr; ret
+        let size_code = read_instructions(&object, size, 2);
+        let size_data = adrp_page(size_code[0], size) + lo12(size_code[1], 3);
+        let size_bytes = read_data(&object, size_data, 8);
+        assert_eq!(u64::from_le_bytes(size_bytes.try_into().unwrap()), 16);
+
+        // stp; bl size; adrp; add; adrp; add (was ldr from the GOT); bl roc_alloc; ldp; b roc_dealloc
+        let main_code = read_instructions(&object, main, 9);
+        assert_eq!(branch_target(main_code[1], main + 4), size);
+
+        let greeting = adrp_page(main_code[2], main + 8) + lo12(main_code[3], 0);
+        assert_eq!(read_data(&object, greeting, 13), b"Hello, World!");
+
+        assert_eq!(main_code[5] & 0xffc0_0000, 0x9100_0000);
+        let alloc = adrp_page(main_code[4], main + 16) + lo12(main_code[5], 0);
+        assert_eq!(alloc, roc_alloc);
+        assert_eq!(branch_target(main_code[6], main + 24), roc_alloc);
+        assert_eq!(branch_target(main_code[8], main + 32), roc_dealloc);
+    }
 }
@@ -0,0 +1,2163 @@
+//! Preprocessing and surgery for Mach-O hosts.
+//!
+//! Unlike with ELF, nothing in the host is shifted during preprocessing. The load commands for
+//! the app's segments fit in the padding that follows the existing load commands, and the app is
+//! placed where `__LINKEDIT` starts. Surgery then moves `__LINKEDIT` behind the app, which only
+//! means updating the load commands that point into it.
+
+use memmap2::MmapMut;
+use object::macho;
+use object::{
+    LittleEndian as LE, Object, ObjectSection, ObjectSymbol, RelocationEncoding, RelocationKind,
+    RelocationTarget, Section, SectionIndex, SectionKind, Symbol, SymbolIndex, SymbolSection,
+};
+use roc_collections::all::MutMap;
+use roc_error_macros::internal_error;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::mem;
+use std::path::Path;
+
+use crate::metadata::{self, VirtualOffset};
+use crate::{
+    aarch64, align_by_constraint, load_struct_inplace, load_struct_inplace_mut,
+    MIN_SECTION_ALIGNMENT,
+};
+
+const HEADER_SIZE: usize = mem::size_of::<macho::MachHeader64<LE>>();
+const SEGMENT_COMMAND_SIZE: usize = mem::size_of::<macho::SegmentCommand64<LE>>();
+const SECTION_SIZE: usize = mem::size_of::<macho::Section64<LE>>();
+const NLIST_SIZE: usize = mem::size_of::<macho::Nlist64<LE>>();
+
+/// The segments that will hold the app. Preprocessing adds a load command for each of them, with
+/// a single section, right before `__LINKEDIT`.
+const APP_RODATA_SEGMENT: (&[u8], &[u8]) = (b"__ROC_RODATA", b"__const");
+const APP_TEXT_SEGMENT: (&[u8], &[u8]) = (b"__ROC_TEXT", b"__text");
+
+/// x86_64 stubs are a single `jmp *got(%rip)`, which we replace with a direct `jmp`.
+const X86_64_STUB_SIZE: usize = 6;
+/// AArch64 stubs load the GOT entry with `adrp`, `ldr` and then branch with `br`.
+const AARCH64_STUB_SIZE: usize = 12;
+
+const CSMAGIC_EMBEDDED_SIGNATURE: u32 = 0xfade_0cc0;
+const CSMAGIC_CODEDIRECTORY: u32 = 0xfade_0c02;
+const CSSLOT_CODEDIRECTORY: u32 = 0;
+/// The code directory version that includes the executable segment fields.
+const CS_SUPPORTSEXECSEG: u32 = 0x20400;
+const CS_ADHOC: u32 = 0x2;
+const CS_LINKER_SIGNED: u32 = 0x2_0000;
+const CS_HASHTYPE_SHA256: u8 = 2;
+const CS_EXECSEG_MAIN_BINARY: u64 = 0x1;
+const CODE_DIRECTORY_SIZE: usize = 88;
+/// The size of a super blob with a single index entry, aligned to 8 bytes.
+const SUPER_BLOB_SIZE: usize = 24;
+const CODE_SIGNATURE_PAGE_SHIFT: u8 = 12;
+const CODE_SIGNATURE_PAGE_SIZE: usize = 1 << CODE_SIGNATURE_PAGE_SHIFT;
+
+#[derive(Debug)]
+struct LoadCommand {
+    offset: usize,
+    cmd: u32,
+    size: usize,
+}
+
+fn load_commands(data: &[u8]) -> Vec<LoadCommand> {
+    let header = load_struct_inplace::<macho::MachHeader64<LE>>(data, 0);
+    let mut offset = HEADER_SIZE;
+
+    (0..header.ncmds.get(LE))
+        .map(|_| {
+            let info = load_struct_inplace::<macho::LoadCommand<LE>>(data, offset);
+            let command = LoadCommand {
+                offset,
+                cmd: info.cmd.get(LE),
+                size: info.cmdsize.get(LE) as usize,
+            };
+            offset += command.size;
+            command
+        })
+        .collect()
+}
+
+fn find_command(cmds: &[LoadCommand], cmd: u32) -> Option<&LoadCommand> {
+    cmds.iter().find(|command| command.cmd == cmd)
+}
+
+fn segment_commands<'a>(
+    data: &'a [u8],
+    cmds: &[LoadCommand],
+) -> Vec<(usize, &'a macho::SegmentCommand64<LE>)> {
+    cmds.iter()
+        .filter(|command| command.cmd == macho::LC_SEGMENT_64)
+        .map(|command| {
+            let segment = load_struct_inplace::<macho::SegmentCommand64<LE>>(data, command.offset);
+            (command.offset, segment)
+        })
+        .collect()
+}
+
+fn sections<'a>(
+    data: &'a [u8],
+    segment_offset: usize,
+    segment: &macho::SegmentCommand64<LE>,
+) -> impl Iterator<Item = &'a macho::Section64<LE>> {
+    (0..segment.nsects.get(LE) as usize).map(move |i| {
+        load_struct_inplace::<macho::Section64<LE>>(
+            data,
+            segment_offset + SEGMENT_COMMAND_SIZE + i * SECTION_SIZE,
+        )
+    })
+}
+
+fn name_is(name: &[u8; 16], expected: &[u8]) -> bool {
+    name.split(|&b| b == 0).next() == Some(expected)
+}
+
+fn linkedit_index(segments: &[(usize, &macho::SegmentCommand64<LE>)]) -> usize {
+    segments
+        .iter()
+        .position(|(_, segment)| name_is(&segment.segname, b"__LINKEDIT"))
+        .unwrap_or_else(|| internal_error!("Host does not have a __LINKEDIT segment"))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..][..4].try_into().unwrap())
+}
+
+fn write_u32(data: &mut [u8], offset: usize, value: u32) {
+    data[offset..][..4].copy_from_slice(&value.to_le_bytes());
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..][..8].try_into().unwrap())
+}
+
+fn write_u64(data: &mut [u8], offset: usize, value: u64) {
+    data[offset..][..8].copy_from_slice(&value.to_le_bytes());
+}
+
+fn write_u32_be(data: &mut [u8], offset: usize, value: u32) {
+    data[offset..][..4].copy_from_slice(&value.to_be_bytes());
+}
+
+fn write_u64_be(data: &mut [u8], offset: usize, value: u64) {
+    data[offset..][..8].copy_from_slice(&value.to_be_bytes());
+}
+
+fn read_uleb128(data: &[u8], pos: &mut usize) -> u64 {
+    let mut value = 0;
+    let mut shift = 0;
+    loop {
+        let byte = data[*pos];
+        *pos += 1;
+        if shift < 64 {
+            value |= ((byte & 0x7f) as u64) << shift;
+        }
+        shift += 7;
+        if byte & 0x80 == 0 {
+            return value;
+        }
+    }
+}
+
+fn read_sleb128(data: &[u8], pos: &mut usize) -> i64 {
+    let mut value = 0;
+    let mut shift = 0;
+    loop {
+        let byte = data[*pos];
+        *pos += 1;
+        if shift < 64 {
+            value |= ((byte & 0x7f) as i64) << shift;
+        }
+        shift += 7;
+        if byte & 0x80 == 0 {
+            if shift < 64 && byte & 0x40 != 0 {
+                value |= -1 << shift;
+            }
+            return value;
+        }
+    }
+}
+
+pub(crate) fn write_uleb128(out: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn write_sleb128(out: &mut Vec<u8>, mut value: i64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        let done = (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0);
+        if done {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+/// Overwrites the ULEB128 in `bytes` with `value`, keeping its length by padding it with
+/// continuation bytes.
+fn overwrite_uleb128(bytes: &mut [u8], mut value: u64) {
+    let last = bytes.len() - 1;
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if i != last {
+            *byte |= 0x80;
+        }
+    }
+}
+
+/// Returns the symbol index behind every stub in `__stubs`, in order, and the size of a stub.
+/// Stubs jump through the pointers of the symbols in the indirect symbol table, starting at the
+/// index in the section's `reserved1`.
+pub(crate) fn stub_symbols(exec_data: &[u8]) -> (Vec<SymbolIndex>, u64) {
+    let cmds = load_commands(exec_data);
+    let dysymtab = match find_command(&cmds, macho::LC_DYSYMTAB) {
+        Some(command) => {
+            load_struct_inplace::<macho::DysymtabCommand<LE>>(exec_data, command.offset)
+        }
+        None => internal_error!("Host does not have a dynamic symbol table"),
+    };
+    let indirect_offset = dysymtab.indirectsymoff.get(LE) as usize;
+
+    for (segment_offset, segment) in segment_commands(exec_data, &cmds) {
+        for section in sections(exec_data, segment_offset, segment) {
+            if section.flags.get(LE) & macho::SECTION_TYPE == macho::S_SYMBOL_STUBS {
+                let first = section.reserved1.get(LE) as usize;
+                let stub_size = section.reserved2.get(LE) as u64;
+                let count = (section.size.get(LE) / stub_size) as usize;
+
+                let symbols = (first..first + count)
+                    .map(|i| SymbolIndex(read_u32(exec_data, indirect_offset + 4 * i) as usize))
+                    .collect();
+
+                return (symbols, stub_size);
+            }
+        }
+    }
+
+    internal_error!("Failed to find the stubs section. Probably an malformed executable.");
+}
+
+/// A table in `__LINKEDIT` and the load command fields that point to it.
+#[derive(Debug)]
+struct LinkeditTable {
+    cmd: u32,
+    cmd_offset: usize,
+    /// The offsets of the file offset and size fields in the load command.
+    offset_field: usize,
+    size_field: usize,
+    /// The size field counts entries of this size.
+    entry_size: usize,
+    offset: usize,
+    size: usize,
+}
+
+impl LinkeditTable {
+    fn alignment(&self) -> usize {
+        if self.cmd == macho::LC_CODE_SIGNATURE {
+            16
+        } else {
+            8
+        }
+    }
+
+    fn is(&self, cmd: u32, offset_field: usize) -> bool {
+        self.cmd == cmd && self.offset_field == offset_field
+    }
+
+    fn contents(&self, data: &[u8]) -> Vec<u8> {
+        data[self.offset..][..self.size].to_vec()
+    }
+}
+
+/// Collects all of the tables in `__LINKEDIT`, in the order they appear in the file.
+fn linkedit_tables(data: &[u8], cmds: &[LoadCommand]) -> Vec<LinkeditTable> {
+    let mut tables = Vec::new();
+
+    for command in cmds {
+        // (offset field, size field, entry size)
+        let fields: &[(usize, usize, usize)] = match command.cmd {
+            // rebase, bind, weak bind, lazy bind and export info.
+            macho::LC_DYLD_INFO | macho::LC_DYLD_INFO_ONLY => &[
+                (8, 12, 1),
+                (16, 20, 1),
+                (24, 28, 1),
+                (32, 36, 1),
+                (40, 44, 1),
+            ],
+            // The symbol table and its strings.
+            macho::LC_SYMTAB => &[(8, 12, NLIST_SIZE), (16, 20, 1)],
+            // Table of contents, modules, external references, indirect symbols, external
+            // relocations and local relocations.
+            macho::LC_DYSYMTAB => &[
+                (32, 36, 8),
+                (40, 44, 56),
+                (48, 52, 4),
+                (56, 60, 4),
+                (64, 68, 8),
+                (72, 76, 8),
+            ],
+            macho::LC_CODE_SIGNATURE
+            | macho::LC_SEGMENT_SPLIT_INFO
+            | macho::LC_FUNCTION_STARTS
+            | macho::LC_DATA_IN_CODE
+            | macho::LC_DYLIB_CODE_SIGN_DRS
+            | macho::LC_LINKER_OPTIMIZATION_HINT
+            | macho::LC_DYLD_EXPORTS_TRIE
+            | macho::LC_DYLD_CHAINED_FIXUPS => &[(8, 12, 1)],
+            _ => &[],
+        };
+
+        for &(offset_field, size_field, entry_size) in fields {
+            tables.push(LinkeditTable {
+                cmd: command.cmd,
+                cmd_offset: command.offset,
+                offset_field,
+                size_field,
+                entry_size,
+                offset: read_u32(data, command.offset + offset_field) as usize,
+                size: read_u32(data, command.offset + size_field) as usize * entry_size,
+            });
+        }
+    }
+
+    // Empty tables may not have an offset, those that become non-empty are placed first.
+    tables.sort_by_key(|table| table.offset);
+
+    tables
+}
+
+/// Writes the tables one after another from `start` and points their load commands at them.
+/// Returns the end of the last table.
+fn write_linkedit(
+    data: &mut [u8],
+    tables: &[LinkeditTable],
+    contents: &[Vec<u8>],
+    start: usize,
+) -> usize {
+    let mut offset = start;
+
+    for (table, content) in tables.iter().zip(contents) {
+        if table.offset == 0 && content.is_empty() {
+            continue;
+        }
+
+        offset = align_by_constraint(offset, table.alignment());
+        data[offset..][..content.len()].copy_from_slice(content);
+        write_u32(data, table.cmd_offset + table.offset_field, offset as u32);
+        write_u32(
+            data,
+            table.cmd_offset + table.size_field,
+            (content.len() / table.entry_size) as u32,
+        );
+        offset += content.len();
+    }
+
+    offset
+}
+
+fn dylib_name<'a>(data: &'a [u8], command: &LoadCommand) -> &'a [u8] {
+    let dylib = load_struct_inplace::<macho::DylibCommand<LE>>(data, command.offset);
+    let name = &data[command.offset..][..command.size][dylib.dylib.name.offset.get(LE) as usize..];
+
+    name.split(|&b| b == 0).next().unwrap_or_default()
+}
+
+fn is_dylib_command(cmd: u32) -> bool {
+    matches!(
+        cmd,
+        macho::LC_LOAD_DYLIB
+            | macho::LC_LOAD_WEAK_DYLIB
+            | macho::LC_REEXPORT_DYLIB
+            | macho::LC_LAZY_LOAD_DYLIB
+            | macho::LC_LOAD_UPWARD_DYLIB
+    )
+}
+
+fn app_segment_command(names: (&[u8], &[u8]), initprot: u32, flags: u32) -> Vec<u8> {
+    let (segname, sectname) = names;
+    let mut bytes = vec![0; SEGMENT_COMMAND_SIZE + SECTION_SIZE];
+    let cmdsize = bytes.len() as u32;
+
+    let segment = load_struct_inplace_mut::<macho::SegmentCommand64<LE>>(&mut bytes, 0);
+    segment.cmd.set(LE, macho::LC_SEGMENT_64);
+    segment.cmdsize.set(LE, cmdsize);
+    segment.segname[..segname.len()].copy_from_slice(segname);
+    segment.maxprot.set(LE, initprot);
+    segment.initprot.set(LE, initprot);
+    segment.nsects.set(LE, 1);
+
+    let section = load_struct_inplace_mut::<macho::Section64<LE>>(&mut bytes, SEGMENT_COMMAND_SIZE);
+    section.sectname[..sectname.len()].copy_from_slice(sectname);
+    section.segname[..segname.len()].copy_from_slice(segname);
+    section
+        .align
+        .set(LE, MIN_SECTION_ALIGNMENT.trailing_zeros());
+    section.flags.set(LE, flags);
+
+    bytes
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Rebase {
+    segment: u8,
+    offset: u64,
+    kind: u8,
+}
+
+fn decode_rebases(stream: &[u8]) -> Vec<Rebase> {
+    let mut rebases = Vec::new();
+    let mut rebase = Rebase {
+        segment: 0,
+        offset: 0,
+        kind: 0,
+    };
+    let mut pos = 0;
+
+    while pos < stream.len() {
+        let byte = stream[pos];
+        pos += 1;
+        let immediate = byte & macho::REBASE_IMMEDIATE_MASK;
+
+        let (count, skip) = match byte & macho::REBASE_OPCODE_MASK {
+            macho::REBASE_OPCODE_DONE => break,
+            macho::REBASE_OPCODE_SET_TYPE_IMM => {
+                rebase.kind = immediate;
+                continue;
+            }
+            macho::REBASE_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB => {
+                rebase.segment = immediate;
+                rebase.offset = read_uleb128(stream, &mut pos);
+                continue;
+            }
+            macho::REBASE_OPCODE_ADD_ADDR_ULEB => {
+                rebase.offset = rebase.offset.wrapping_add(read_uleb128(stream, &mut pos));
+                continue;
+            }
+            macho::REBASE_OPCODE_ADD_ADDR_IMM_SCALED => {
+                rebase.offset += immediate as u64 * 8;
+                continue;
+            }
+            macho::REBASE_OPCODE_DO_REBASE_IMM_TIMES => (immediate as u64, 0),
+            macho::REBASE_OPCODE_DO_REBASE_ULEB_TIMES => (read_uleb128(stream, &mut pos), 0),
+            macho::REBASE_OPCODE_DO_REBASE_ADD_ADDR_ULEB => (1, read_uleb128(stream, &mut pos)),
+            macho::REBASE_OPCODE_DO_REBASE_ULEB_TIMES_SKIPPING_ULEB => {
+                let count = read_uleb128(stream, &mut pos);
+                (count, read_uleb128(stream, &mut pos))
+            }
+            x => internal_error!("Unknown rebase opcode: {:#x}", x),
+        };
+
+        for _ in 0..count {
+            rebases.push(rebase);
+            rebase.offset = rebase.offset.wrapping_add(skip + 8);
+        }
+    }
+
+    rebases
+}
+
+fn encode_rebases(rebases: &[Rebase]) -> Vec<u8> {
+    let mut stream = Vec::new();
+    let mut current: Option<Rebase> = None;
+    let mut i = 0;
+
+    while i < rebases.len() {
+        let rebase = rebases[i];
+
+        if current.map(|c| c.kind) != Some(rebase.kind) {
+            stream.push(macho::REBASE_OPCODE_SET_TYPE_IMM | rebase.kind);
+        }
+        match current {
+            Some(c) if c.segment == rebase.segment && c.offset == rebase.offset => {}
+            Some(c) if c.segment == rebase.segment && c.offset < rebase.offset => {
+                stream.push(macho::REBASE_OPCODE_ADD_ADDR_ULEB);
+                write_uleb128(&mut stream, rebase.offset - c.offset);
+            }
+            _ => {
+                stream.push(macho::REBASE_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB | rebase.segment);
+                write_uleb128(&mut stream, rebase.offset);
+            }
+        }
+
+        // Rebase a run of consecutive pointers at once.
+        let mut count = 1;
+        while count < macho::REBASE_IMMEDIATE_MASK as usize
+            && i + count < rebases.len()
+            && rebases[i + count]
+                == (Rebase {
+                    offset: rebase.offset + 8 * count as u64,
+                    ..rebase
+                })
+        {
+            count += 1;
+        }
+        stream.push(macho::REBASE_OPCODE_DO_REBASE_IMM_TIMES | count as u8);
+
+        current = Some(Rebase {
+            offset: rebase.offset + 8 * count as u64,
+            ..rebase
+        });
+        i += count;
+    }
+
+    stream.push(macho::REBASE_OPCODE_DONE);
+    stream
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Bind {
+    segment: u8,
+    offset: u64,
+    ordinal: i64,
+    name: Vec<u8>,
+    flags: u8,
+    kind: u8,
+    addend: i64,
+}
+
+fn decode_binds(stream: &[u8]) -> Vec<Bind> {
+    let mut binds = Vec::new();
+    let mut bind = Bind {
+        segment: 0,
+        offset: 0,
+        ordinal: 0,
+        name: Vec::new(),
+        flags: 0,
+        kind: macho::BIND_TYPE_POINTER,
+        addend: 0,
+    };
+    let mut pos = 0;
+
+    while pos < stream.len() {
+        let byte = stream[pos];
+        pos += 1;
+        let immediate = byte & macho::BIND_IMMEDIATE_MASK;
+
+        let (count, skip) = match byte & macho::BIND_OPCODE_MASK {
+            macho::BIND_OPCODE_DONE => break,
+            macho::BIND_OPCODE_SET_DYLIB_ORDINAL_IMM => {
+                bind.ordinal = immediate as i64;
+                continue;
+            }
+            macho::BIND_OPCODE_SET_DYLIB_ORDINAL_ULEB => {
+                bind.ordinal = read_uleb128(stream, &mut pos) as i64;
+                continue;
+            }
+            macho::BIND_OPCODE_SET_DYLIB_SPECIAL_IMM => {
+                // The special ordinals are small negative numbers.
+                bind.ordinal = if immediate == 0 {
+                    0
+                } else {
+                    (immediate | macho::BIND_OPCODE_MASK) as i8 as i64
+                };
+                continue;
+            }
+            macho::BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM => {
+                let length = stream[pos..].iter().position(|&b| b == 0).unwrap();
+                bind.flags = immediate;
+                bind.name = stream[pos..][..length].to_vec();
+                pos += length + 1;
+                continue;
+            }
+            macho::BIND_OPCODE_SET_TYPE_IMM => {
+                bind.kind = immediate;
+                continue;
+            }
+            macho::BIND_OPCODE_SET_ADDEND_SLEB => {
+                bind.addend = read_sleb128(stream, &mut pos);
+                continue;
+            }
+            macho::BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB => {
+                bind.segment = immediate;
+                bind.offset = read_uleb128(stream, &mut pos);
+                continue;
+            }
+            macho::BIND_OPCODE_ADD_ADDR_ULEB => {
+                bind.offset = bind.offset.wrapping_add(read_uleb128(stream, &mut pos));
+                continue;
+            }
+            macho::BIND_OPCODE_DO_BIND => (1, 0),
+            macho::BIND_OPCODE_DO_BIND_ADD_ADDR_ULEB => (1, read_uleb128(stream, &mut pos)),
+            macho::BIND_OPCODE_DO_BIND_ADD_ADDR_IMM_SCALED => (1, immediate as u64 * 8),
+            macho::BIND_OPCODE_DO_BIND_ULEB_TIMES_SKIPPING_ULEB => {
+                let count = read_uleb128(stream, &mut pos);
+                (count, read_uleb128(stream, &mut pos))
+            }
+            macho::BIND_OPCODE_THREADED => {
+                internal_error!("Threaded binds are not yet supported in Mach-O hosts");
+            }
+            x => internal_error!("Unknown bind opcode: {:#x}", x),
+        };
+
+        for _ in 0..count {
+            binds.push(bind.clone());
+            bind.offset = bind.offset.wrapping_add(skip + 8);
+        }
+    }
+
+    binds
+}
+
+fn encode_binds(binds: &[Bind]) -> Vec<u8> {
+    let mut stream = Vec::new();
+    let mut current: Option<&Bind> = None;
+    let mut next_offset = 0;
+
+    for bind in binds {
+        if current.map(|c| c.ordinal) != Some(bind.ordinal) {
+            if bind.ordinal <= 0 {
+                stream.push(
+                    macho::BIND_OPCODE_SET_DYLIB_SPECIAL_IMM
+                        | (bind.ordinal as u8 & macho::BIND_IMMEDIATE_MASK),
+                );
+            } else if bind.ordinal <= macho::BIND_IMMEDIATE_MASK as i64 {
+                stream.push(macho::BIND_OPCODE_SET_DYLIB_ORDINAL_IMM | bind.ordinal as u8);
+            } else {
+                stream.push(macho::BIND_OPCODE_SET_DYLIB_ORDINAL_ULEB);
+                write_uleb128(&mut stream, bind.ordinal as u64);
+            }
+        }
+        if current.map(|c| (&c.name, c.flags)) != Some((&bind.name, bind.flags)) {
+            stream.push(macho::BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM | bind.flags);
+            stream.extend_from_slice(&bind.name);
+            stream.push(0);
+        }
+        if current.map(|c| c.kind) != Some(bind.kind) {
+            stream.push(macho::BIND_OPCODE_SET_TYPE_IMM | bind.kind);
+        }
+        if current.map(|c| c.addend).unwrap_or(0) != bind.addend {
+            stream.push(macho::BIND_OPCODE_SET_ADDEND_SLEB);
+            write_sleb128(&mut stream, bind.addend);
+        }
+        match current {
+            Some(c) if c.segment == bind.segment && next_offset == bind.offset => {}
+            Some(c) if c.segment == bind.segment && next_offset < bind.offset => {
+                stream.push(macho::BIND_OPCODE_ADD_ADDR_ULEB);
+                write_uleb128(&mut stream, bind.offset - next_offset);
+            }
+            _ => {
+                stream.push(macho::BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB | bind.segment);
+                write_uleb128(&mut stream, bind.offset);
+            }
+        }
+        stream.push(macho::BIND_OPCODE_DO_BIND);
+
+        current = Some(bind);
+        next_offset = bind.offset + 8;
+    }
+
+    stream.push(macho::BIND_OPCODE_DONE);
+    stream
+}
+
+/// Lazy binds are referenced by their offset from the stub helpers, so they are patched in
+/// place: the records for the app are turned into no-ops, and higher library ordinals are
+/// renumbered.
+fn remove_app_lazy_binds(stream: &mut [u8], app_ordinal: u64) {
+    let mut record_start = 0;
+    let mut ordinal = 0;
+    let mut pos = 0;
+
+    while pos < stream.len() {
+        let opcode_pos = pos;
+        let byte = stream[pos];
+        pos += 1;
+        let immediate = byte & macho::BIND_IMMEDIATE_MASK;
+
+        match byte & macho::BIND_OPCODE_MASK {
+            // Lazy binds are separated by `DONE`.
+            macho::BIND_OPCODE_DONE => record_start = pos,
+            macho::BIND_OPCODE_SET_DYLIB_ORDINAL_IMM => {
+                ordinal = immediate as u64;
+                if ordinal > app_ordinal {
+                    stream[opcode_pos] = macho::BIND_OPCODE_SET_DYLIB_ORDINAL_IMM | (immediate - 1);
+                }
+            }
+            macho::BIND_OPCODE_SET_DYLIB_ORDINAL_ULEB => {
+                ordinal = read_uleb128(stream, &mut pos);
+                if ordinal > app_ordinal {
+                    overwrite_uleb128(&mut stream[opcode_pos + 1..pos], ordinal - 1);
+                }
+            }
+            macho::BIND_OPCODE_SET_DYLIB_SPECIAL_IMM => ordinal = 0,
+            macho::BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM => {
+                pos += stream[pos..].iter().position(|&b| b == 0).unwrap() + 1;
+            }
+            macho::BIND_OPCODE_SET_TYPE_IMM => {}
+            macho::BIND_OPCODE_SET_ADDEND_SLEB => {
+                read_sleb128(stream, &mut pos);
+            }
+            macho::BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB | macho::BIND_OPCODE_ADD_ADDR_ULEB => {
+                read_uleb128(stream, &mut pos);
+            }
+            macho::BIND_OPCODE_DO_BIND => {
+                if ordinal == app_ordinal {
+                    stream[record_start..pos].fill(macho::BIND_OPCODE_DONE);
+                }
+            }
+            x => internal_error!("Unexpected lazy bind opcode: {:#x}", x),
+        }
+    }
+}
+
+/// Replaces the binds to the app with rebases, and records where the addresses of the app's
+/// functions need to be written during surgery.
+fn rebind_dyld_info(
+    tables: &[LinkeditTable],
+    contents: &mut [Vec<u8>],
+    segments: &[(u64, u64)],
+    app_ordinal: u64,
+    md: &mut metadata::Metadata,
+) {
+    let position = |offset_field| {
+        tables.iter().position(|table| {
+            table.is(macho::LC_DYLD_INFO_ONLY, offset_field)
+                || table.is(macho::LC_DYLD_INFO, offset_field)
+        })
+    };
+    let (rebase, bind, lazy_bind) = match (position(8), position(16), position(32)) {
+        (Some(rebase), Some(bind), Some(lazy_bind)) => (rebase, bind, lazy_bind),
+        _ => return,
+    };
+
+    let mut rebases = decode_rebases(&contents[rebase]);
+    let mut binds = Vec::new();
+
+    for mut bind in decode_binds(&contents[bind]) {
+        if bind.ordinal as u64 == app_ordinal {
+            let name = String::from_utf8_lossy(&bind.name).into_owned();
+            if bind.kind != macho::BIND_TYPE_POINTER || bind.addend != 0 {
+                internal_error!("Unsupported bind to app symbol: {}", name);
+            }
+
+            rebases.push(Rebase {
+                segment: bind.segment,
+                offset: bind.offset,
+                kind: macho::REBASE_TYPE_POINTER,
+            });
+
+            let (segment_offset, _) = segments[bind.segment as usize];
+            md.surgeries
+                .entry(name)
+                .or_default()
+                .push(metadata::SurgeryEntry {
+                    file_offset: segment_offset + bind.offset,
+                    virtual_offset: VirtualOffset::Absolute,
+                    size: 8,
+                });
+        } else {
+            if bind.ordinal > app_ordinal as i64 {
+                bind.ordinal -= 1;
+            }
+            binds.push(bind);
+        }
+    }
+    rebases.sort_unstable();
+
+    contents[rebase] = encode_rebases(&rebases);
+    contents[bind] = encode_binds(&binds);
+    remove_app_lazy_binds(&mut contents[lazy_bind], app_ordinal);
+}
+
+const DYLD_CHAINED_IMPORT: u32 = 1;
+const DYLD_CHAINED_IMPORT_ADDEND: u32 = 2;
+const DYLD_CHAINED_IMPORT_ADDEND64: u32 = 3;
+const DYLD_CHAINED_PTR_64: u16 = 2;
+const DYLD_CHAINED_PTR_64_OFFSET: u16 = 6;
+const DYLD_CHAINED_PTR_START_NONE: u16 = 0xffff;
+const DYLD_CHAINED_PTR_START_MULTI: u16 = 0x8000;
+/// The bits of a 64-bit chained rebase that hold its target.
+const CHAINED_REBASE_TARGET_MASK: u64 = (1 << 36) - 1;
+/// The bits of a 64-bit chained fixup that hold the (4-byte) stride to the next fixup.
+const CHAINED_NEXT_MASK: u64 = 0xfff << 51;
+
+/// Replaces the chained binds to the app with rebases, and records where the addresses of the
+/// app's functions need to be written during surgery. The app's imports are kept to not renumber
+/// the others, but are made weak so that dyld does not look for them.
+///
+/// Also makes room in the fixups for the segments that will hold the app.
+fn rebind_chained_fixups(
+    data: &mut [u8],
+    fixups: &mut Vec<u8>,
+    segments: &[(u64, u64)],
+    linkedit_index: usize,
+    image_base: u64,
+    app_ordinal: u64,
+    md: &mut metadata::Metadata,
+) {
+    let starts_offset = read_u32(fixups, 4) as usize;
+    let imports_offset = read_u32(fixups, 8) as usize;
+    let symbols_offset = read_u32(fixups, 12) as usize;
+    let imports_count = read_u32(fixups, 16) as usize;
+    let imports_format = read_u32(fixups, 20);
+    if read_u32(fixups, 24) != 0 {
+        internal_error!("Compressed chained fixup symbols are not yet supported");
+    }
+
+    let (import_size, ordinal_bits) = match imports_format {
+        DYLD_CHAINED_IMPORT => (4, 8),
+        DYLD_CHAINED_IMPORT_ADDEND => (8, 8),
+        DYLD_CHAINED_IMPORT_ADDEND64 => (16, 16),
+        x => internal_error!("Unknown chained import format: {}", x),
+    };
+    let ordinal_mask: u64 = (1 << ordinal_bits) - 1;
+    let weak_import: u64 = 1 << ordinal_bits;
+    // The special ordinals are small negative numbers.
+    let first_special_ordinal = ordinal_mask - 0xf;
+    let flat_lookup = macho::BIND_SPECIAL_DYLIB_FLAT_LOOKUP as u64 & ordinal_mask;
+
+    let mut app_imports = MutMap::default();
+    for i in 0..imports_count {
+        let entry = imports_offset + i * import_size;
+        let (import, name_offset, addend) = if imports_format == DYLD_CHAINED_IMPORT_ADDEND64 {
+            let import = read_u64(fixups, entry);
+            (import, import >> 32, read_u64(fixups, entry + 8) as i64)
+        } else {
+            let import = read_u32(fixups, entry) as u64;
+            let addend = if imports_format == DYLD_CHAINED_IMPORT_ADDEND {
+                read_u32(fixups, entry + 4) as i32 as i64
+            } else {
+                0
+            };
+            (import, import >> 9, addend)
+        };
+
+        let ordinal = import & ordinal_mask;
+        let new_ordinal = if ordinal == app_ordinal {
+            let name = &fixups[symbols_offset + name_offset as usize..];
+            let name = name.split(|&b| b == 0).next().unwrap_or_default();
+            app_imports.insert(
+                i as u64,
+                (String::from_utf8_lossy(name).into_owned(), addend),
+            );
+
+            flat_lookup | weak_import
+        } else if ordinal > app_ordinal && ordinal < first_special_ordinal {
+            ordinal - 1
+        } else {
+            ordinal
+        };
+        let import = (import & !ordinal_mask) | new_ordinal;
+
+        if imports_format == DYLD_CHAINED_IMPORT_ADDEND64 {
+            write_u64(fixups, entry, import);
+        } else {
+            write_u32(fixups, entry, import as u32);
+        }
+    }
+
+    let seg_count = read_u32(fixups, starts_offset) as usize;
+    for (seg_index, &(segment_offset, _)) in segments.iter().enumerate().take(seg_count) {
+        let seg_info_offset = read_u32(fixups, starts_offset + 4 + 4 * seg_index) as usize;
+        if seg_info_offset == 0 {
+            continue;
+        }
+
+        let seg_info = starts_offset + seg_info_offset;
+        let page_size = u16::from_le_bytes([fixups[seg_info + 4], fixups[seg_info + 5]]) as u64;
+        let pointer_format = u16::from_le_bytes([fixups[seg_info + 6], fixups[seg_info + 7]]);
+        let page_count = u16::from_le_bytes([fixups[seg_info + 20], fixups[seg_info + 21]]);
+
+        let rebase_base = match pointer_format {
+            DYLD_CHAINED_PTR_64 => 0,
+            DYLD_CHAINED_PTR_64_OFFSET => image_base,
+            x => internal_error!("Chained pointer format not yet supported: {}", x),
+        };
+
+        for page in 0..page_count as usize {
+            let start = seg_info + 22 + 2 * page;
+            let start = u16::from_le_bytes([fixups[start], fixups[start + 1]]);
+            if start == DYLD_CHAINED_PTR_START_NONE {
+                continue;
+            }
+            if start & DYLD_CHAINED_PTR_START_MULTI != 0 {
+                internal_error!("Multiple chain starts per page are not yet supported");
+            }
+
+            let mut offset = (segment_offset + page as u64 * page_size + start as u64) as usize;
+            loop {
+                let fixup = read_u64(data, offset);
+                let is_bind = fixup >> 63 == 1;
+
+                if let Some((name, import_addend)) = app_imports.get(&(fixup & 0xff_ffff)) {
+                    if is_bind {
+                        let addend = import_addend + ((fixup >> 24) & 0xff) as i64;
+                        write_u64(data, offset, fixup & CHAINED_NEXT_MASK);
+                        md.surgeries.entry(name.clone()).or_default().push(
+                            metadata::SurgeryEntry {
+                                file_offset: offset as u64,
+                                virtual_offset: VirtualOffset::ChainedRebase(
+                                    rebase_base.wrapping_sub(addend as u64),
+                                ),
+                                size: 8,
+                            },
+                        );
+                    }
+                }
+
+                let next = (fixup & CHAINED_NEXT_MASK) >> 51;
+                if next == 0 {
+                    break;
+                }
+                offset += next as usize * 4;
+            }
+        }
+    }
+
+    // The app segments are inserted right before __LINKEDIT, without any fixups.
+    for i in 0..seg_count {
+        let seg_info_offset = read_u32(fixups, starts_offset + 4 + 4 * i);
+        if seg_info_offset != 0 {
+            write_u32(fixups, starts_offset + 4 + 4 * i, seg_info_offset + 8);
+        }
+    }
+    write_u32(fixups, starts_offset, seg_count as u32 + 2);
+    let app_seg_infos = starts_offset + 4 + 4 * linkedit_index.min(seg_count);
+    fixups.splice(app_seg_infos..app_seg_infos, [0; 8]);
+    for field in [8, 12] {
+        let offset = read_u32(fixups, field) as usize;
+        if offset > starts_offset {
+            write_u32(fixups, field, offset as u32 + 8);
+        }
+    }
+}
+
+/// The app's symbols become definitions in the app's text section, and library ordinals after
+/// the app's dylib are renumbered. The dynamic symbol table expects the definitions to be
+/// grouped together, so the app's symbols are moved and the indirect symbol table is updated.
+#[allow(clippy::too_many_arguments)]
+fn update_symbol_table(
+    out: &mut [u8],
+    cmds: &[LoadCommand],
+    tables: &[LinkeditTable],
+    contents: &mut [Vec<u8>],
+    app_syms: &[Symbol],
+    app_ordinal: u64,
+    app_text_section: u8,
+    md: &mut metadata::Metadata,
+) {
+    let position = |cmd, offset_field| {
+        tables
+            .iter()
+            .position(|table| table.is(cmd, offset_field))
+            .unwrap_or_else(|| internal_error!("Host does not have a symbol table"))
+    };
+    let symtab = position(macho::LC_SYMTAB, 8);
+    let indirect_symbols = position(macho::LC_DYSYMTAB, 56);
+
+    let dysymtab = match find_command(cmds, macho::LC_DYSYMTAB) {
+        Some(command) => load_struct_inplace_mut::<macho::DysymtabCommand<LE>>(out, command.offset),
+        None => internal_error!("Host does not have a dynamic symbol table"),
+    };
+    if dysymtab.nextrel.get(LE) != 0 || dysymtab.nlocrel.get(LE) != 0 {
+        internal_error!("Mach-O hosts with relocations are not yet supported");
+    }
+
+    let iundefsym = dysymtab.iundefsym.get(LE) as usize;
+    let nundefsym = dysymtab.nundefsym.get(LE) as usize;
+    let old_symbols = contents[symtab].clone();
+    let nsyms = old_symbols.len() / NLIST_SIZE;
+    let is_app = |i: usize| app_syms.iter().any(|sym| sym.index().0 == i);
+    let app_count = (iundefsym..iundefsym + nundefsym)
+        .filter(|&i| is_app(i))
+        .count();
+
+    // Locals and definitions, the app's symbols, and then the remaining undefined symbols.
+    let order = (0..iundefsym)
+        .chain((iundefsym..nsyms).filter(|&i| is_app(i)))
+        .chain((iundefsym..nsyms).filter(|&i| !is_app(i)));
+
+    let mut new_indices = vec![0; nsyms];
+    for (new_index, old_index) in order.enumerate() {
+        new_indices[old_index] = new_index;
+
+        let mut symbol =
+            *load_struct_inplace::<macho::Nlist64<LE>>(&old_symbols, old_index * NLIST_SIZE);
+        if is_app(old_index) {
+            symbol.n_type = macho::N_SECT | macho::N_EXT;
+            symbol.n_sect = app_text_section;
+            symbol.n_desc.set(LE, 0);
+            symbol.n_value.set(LE, 0);
+        } else if symbol.n_type & (macho::N_STAB | macho::N_TYPE) == macho::N_UNDF {
+            let desc = symbol.n_desc.get(LE);
+            let ordinal = (desc >> 8) as u64;
+            if ordinal > app_ordinal && ordinal < macho::DYNAMIC_LOOKUP_ORDINAL as u64 {
+                symbol.n_desc.set(LE, desc - 0x100);
+            }
+        }
+        *load_struct_inplace_mut::<macho::Nlist64<LE>>(
+            &mut contents[symtab],
+            new_index * NLIST_SIZE,
+        ) = symbol;
+    }
+
+    for entry in contents[indirect_symbols].chunks_exact_mut(4) {
+        let index = u32::from_le_bytes(entry.try_into().unwrap());
+        if index & (macho::INDIRECT_SYMBOL_LOCAL | macho::INDIRECT_SYMBOL_ABS) == 0 {
+            entry.copy_from_slice(&(new_indices[index as usize] as u32).to_le_bytes());
+        }
+    }
+
+    dysymtab
+        .nextdefsym
+        .set(LE, dysymtab.nextdefsym.get(LE) + app_count as u32);
+    dysymtab.iundefsym.set(LE, (iundefsym + app_count) as u32);
+    dysymtab.nundefsym.set(LE, (nundefsym - app_count) as u32);
+
+    for sym in app_syms {
+        md.dynamic_symbol_indices.insert(
+            sym.name().unwrap().to_string(),
+            new_indices[sym.index().0] as u64,
+        );
+    }
+}
+
+/// Preprocesses a Mach-O host, see the module documentation.
+pub(crate) fn gen_macho_le(
+    exec_data: &[u8],
+    md: &mut metadata::Metadata,
+    out_filename: &str,
+    shared_lib: &Path,
+    app_syms: &[Symbol],
+    verbose: bool,
+) -> (MmapMut, File) {
+    let cmds = load_commands(exec_data);
+    let header = load_struct_inplace::<macho::MachHeader64<LE>>(exec_data, 0);
+    let sizeofcmds = header.sizeofcmds.get(LE) as usize;
+    let page_size = md.load_align_constraint as usize;
+
+    // Binds refer to the dylibs by their ordinal, which starts at 1.
+    let mut app_dylib = None;
+    for (ordinal, command) in cmds
+        .iter()
+        .filter(|command| is_dylib_command(command.cmd))
+        .enumerate()
+    {
+        let name = std::str::from_utf8(dylib_name(exec_data, command)).unwrap_or_default();
+        if Path::new(name).file_name() == shared_lib.file_name() {
+            app_dylib = Some((command, ordinal as u64 + 1));
+        }
+    }
+    let (app_dylib, app_ordinal) = match app_dylib {
+        Some(app_dylib) => app_dylib,
+        None => internal_error!("Host does not link library `{}`!", shared_lib.display()),
+    };
+
+    let segments = segment_commands(exec_data, &cmds);
+    let linkedit_index = linkedit_index(&segments);
+    let (linkedit_cmd_offset, linkedit) = segments[linkedit_index];
+    let linkedit_offset = linkedit.fileoff.get(LE) as usize;
+    if linkedit_offset + linkedit.filesize.get(LE) as usize != exec_data.len() {
+        internal_error!("Expected __LINKEDIT to be at the end of the host");
+    }
+
+    let image_base = segments
+        .iter()
+        .find(|(_, segment)| segment.fileoff.get(LE) == 0 && segment.filesize.get(LE) != 0)
+        .map(|(_, segment)| segment.vmaddr.get(LE))
+        .unwrap_or_default();
+    let segment_locations: Vec<(u64, u64)> = segments
+        .iter()
+        .map(|(_, segment)| (segment.fileoff.get(LE), segment.vmaddr.get(LE)))
+        .collect();
+
+    let section_count: usize = segments
+        .iter()
+        .map(|(_, segment)| segment.nsects.get(LE) as usize)
+        .sum();
+    let app_text_section = match u8::try_from(section_count + 2) {
+        Ok(index) => index,
+        Err(_) => internal_error!("Host has too many sections to add the app's"),
+    };
+
+    let first_section_offset = segments
+        .iter()
+        .flat_map(|&(offset, segment)| sections(exec_data, offset, segment))
+        .map(|section| section.offset.get(LE) as usize)
+        .filter(|&offset| offset != 0)
+        .min()
+        .unwrap_or(linkedit_offset);
+    let app_cmds_size = 2 * (SEGMENT_COMMAND_SIZE + SECTION_SIZE);
+    let new_sizeofcmds = sizeofcmds - app_dylib.size + app_cmds_size;
+    if HEADER_SIZE + new_sizeofcmds > first_section_offset {
+        internal_error!(
+            "The host does not have room for {} more bytes of load commands. Try linking it with `-headerpad 0x1000`.",
+            app_cmds_size - app_dylib.size
+        );
+    }
+
+    if verbose {
+        println!();
+        println!("App dylib ordinal: {}", app_ordinal);
+        println!(
+            "Load command padding: {:+x}",
+            first_section_offset - HEADER_SIZE - sizeofcmds
+        );
+    }
+
+    // Drop the app's dylib, and add the app's segments before __LINKEDIT.
+    let mut new_cmds = Vec::with_capacity(new_sizeofcmds);
+    for command in cmds.iter() {
+        if command.offset == app_dylib.offset {
+            continue;
+        }
+        if command.offset == linkedit_cmd_offset {
+            md.macho_cmd_loc = (HEADER_SIZE + new_cmds.len()) as u64;
+            new_cmds.extend(app_segment_command(
+                APP_RODATA_SEGMENT,
+                macho::VM_PROT_READ,
+                macho::S_REGULAR,
+            ));
+            new_cmds.extend(app_segment_command(
+                APP_TEXT_SEGMENT,
+                macho::VM_PROT_READ | macho::VM_PROT_EXECUTE,
+                macho::S_REGULAR
+                    | macho::S_ATTR_PURE_INSTRUCTIONS
+                    | macho::S_ATTR_SOME_INSTRUCTIONS,
+            ));
+        }
+        new_cmds.extend_from_slice(&exec_data[command.offset..][..command.size]);
+    }
+
+    let mut out = exec_data.to_vec();
+    out[HEADER_SIZE..][..sizeofcmds.max(new_sizeofcmds)].fill(0);
+    out[HEADER_SIZE..][..new_sizeofcmds].copy_from_slice(&new_cmds);
+    let header = load_struct_inplace_mut::<macho::MachHeader64<LE>>(&mut out, 0);
+    header.ncmds.set(LE, cmds.len() as u32 + 1);
+    header.sizeofcmds.set(LE, new_sizeofcmds as u32);
+
+    let cmds = load_commands(&out);
+    let tables = linkedit_tables(&out, &cmds);
+    let mut contents: Vec<_> = tables.iter().map(|table| table.contents(&out)).collect();
+
+    update_symbol_table(
+        &mut out,
+        &cmds,
+        &tables,
+        &mut contents,
+        app_syms,
+        app_ordinal,
+        app_text_section,
+        md,
+    );
+    rebind_dyld_info(&tables, &mut contents, &segment_locations, app_ordinal, md);
+    if let Some(fixups) = tables
+        .iter()
+        .position(|table| table.cmd == macho::LC_DYLD_CHAINED_FIXUPS)
+    {
+        rebind_chained_fixups(
+            &mut out,
+            &mut contents[fixups],
+            &segment_locations,
+            linkedit_index,
+            image_base,
+            app_ordinal,
+            md,
+        );
+    }
+
+    // The tables may have grown, so lay out __LINKEDIT again.
+    let max_linkedit_size: usize = contents.iter().map(|content| content.len() + 16).sum();
+    out.truncate(linkedit_offset);
+    out.resize(linkedit_offset + max_linkedit_size, 0);
+    let linkedit_end = write_linkedit(&mut out, &tables, &contents, linkedit_offset);
+    out.truncate(linkedit_end);
+
+    let linkedit = load_struct_inplace_mut::<macho::SegmentCommand64<LE>>(
+        &mut out,
+        md.macho_cmd_loc as usize + app_cmds_size,
+    );
+    let linkedit_size = (linkedit_end - linkedit_offset) as u64;
+    linkedit.filesize.set(LE, linkedit_size);
+    linkedit.vmsize.set(
+        LE,
+        align_by_constraint(linkedit_size as usize, page_size) as u64,
+    );
+
+    md.exec_len = out.len() as u64;
+
+    let out_file = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(out_filename)
+        .unwrap_or_else(|e| internal_error!("{}", e));
+    out_file
+        .set_len(md.exec_len)
+        .unwrap_or_else(|e| internal_error!("{}", e));
+    let mut out_mmap =
+        unsafe { MmapMut::map_mut(&out_file).unwrap_or_else(|e| internal_error!("{}", e)) };
+    out_mmap.copy_from_slice(&out);
+
+    (out_mmap, out_file)
+}
+
+fn fill_app_segment(
+    data: &mut [u8],
+    cmd_offset: usize,
+    names: (&[u8], &[u8]),
+    (fileoff, filesize): (usize, usize),
+    (vmaddr, vmsize): (usize, usize),
+    initprot: u32,
+) {
+    let segment = load_struct_inplace_mut::<macho::SegmentCommand64<LE>>(data, cmd_offset);
+    if !name_is(&segment.segname, names.0) {
+        internal_error!("Failed to find the app's segments in the preprocessed host");
+    }
+    segment.vmaddr.set(LE, vmaddr as u64);
+    segment.vmsize.set(LE, vmsize as u64);
+    segment.fileoff.set(LE, fileoff as u64);
+    segment.filesize.set(LE, filesize as u64);
+    segment.maxprot.set(LE, initprot);
+    segment.initprot.set(LE, initprot);
+
+    let section =
+        load_struct_inplace_mut::<macho::Section64<LE>>(data, cmd_offset + SEGMENT_COMMAND_SIZE);
+    section.addr.set(LE, vmaddr as u64);
+    section.size.set(LE, filesize as u64);
+    section.offset.set(LE, fileoff as u32);
+}
+
+pub(crate) fn surgery_macho(
+    verbose: bool,
+    md: &metadata::Metadata,
+    exec_mmap: &mut MmapMut,
+    offset_ref: &mut usize, // TODO return this instead of taking a mutable reference to it
+    app_obj: object::File,
+) {
+    let header = load_struct_inplace::<macho::MachHeader64<LE>>(exec_mmap, 0);
+    let is_aarch64 = header.cputype.get(LE) == macho::CPU_TYPE_ARM64;
+    let page_size = md.load_align_constraint as usize;
+
+    let cmds = load_commands(exec_mmap);
+    let segments = segment_commands(exec_mmap, &cmds);
+    let (linkedit_cmd_offset, linkedit) = segments[linkedit_index(&segments)];
+    let linkedit_offset = linkedit.fileoff.get(LE) as usize;
+    let linkedit_vaddr = linkedit.vmaddr.get(LE) as usize;
+    let exec_segment = segments
+        .iter()
+        .find(|(_, segment)| name_is(&segment.segname, b"__TEXT"))
+        .map(|(_, segment)| (segment.fileoff.get(LE), segment.filesize.get(LE)))
+        .unwrap_or_else(|| internal_error!("Host does not have a __TEXT segment"));
+
+    // Save __LINKEDIT, it is moved behind the app. The code signature is regenerated.
+    let mut tables = linkedit_tables(exec_mmap, &cmds);
+    let signature = tables
+        .iter()
+        .position(|table| table.cmd == macho::LC_CODE_SIGNATURE)
+        .map(|index| {
+            let table = tables.remove(index);
+            let identifier = code_signature_identifier(&table.contents(exec_mmap));
+            let identifier = identifier.unwrap_or_else(|| {
+                internal_error!("Failed to read the code signature of the host")
+            });
+            (table, identifier)
+        });
+    let contents: Vec<_> = tables
+        .iter()
+        .map(|table| table.contents(exec_mmap))
+        .collect();
+    exec_mmap[linkedit_offset..].fill(0);
+
+    let is_excluded = |sec: &Section| matches!(sec.name(), Ok("__eh_frame" | "__gcc_except_tab"));
+    let rodata_sections: Vec<Section> = app_obj
+        .sections()
+        .filter(|sec| {
+            matches!(
+                sec.kind(),
+                SectionKind::ReadOnlyData | SectionKind::ReadOnlyString | SectionKind::Data
+            ) && !is_excluded(sec)
+        })
+        .collect();
+    let bss_sections: Vec<Section> = app_obj
+        .sections()
+        .filter(|sec| {
+            matches!(
+                sec.kind(),
+                SectionKind::UninitializedData | SectionKind::Common
+            )
+        })
+        .collect();
+    let text_sections: Vec<Section> = app_obj
+        .sections()
+        .filter(|sec| sec.kind() == SectionKind::Text)
+        .collect();
+    if text_sections.is_empty() {
+        internal_error!("No text sections found. This application has no code.");
+    }
+
+    // The app's read only data and bss go where __LINKEDIT was, followed by its code in a new
+    // segment. The file offsets and addresses of the segments match up to the page size.
+    let section_alignment = |sec: &Section| MIN_SECTION_ALIGNMENT.max(sec.align() as usize);
+    let mut section_offset_map: MutMap<SectionIndex, (usize, usize)> = MutMap::default();
+
+    let mut offset = linkedit_offset;
+    for sec in rodata_sections.iter() {
+        offset = align_by_constraint(offset, section_alignment(sec));
+        let virt_offset = linkedit_vaddr + offset - linkedit_offset;
+        section_offset_map.insert(sec.index(), (offset, virt_offset));
+        offset += sec.size() as usize;
+    }
+    let rodata_file_size = offset - linkedit_offset;
+
+    let mut virt_offset = linkedit_vaddr + rodata_file_size;
+    for sec in bss_sections.iter() {
+        virt_offset = align_by_constraint(virt_offset, section_alignment(sec));
+        section_offset_map.insert(sec.index(), (0, virt_offset));
+        virt_offset += sec.size() as usize;
+    }
+    // A segment can not be empty, so always reserve at least a page.
+    let rodata_vm_size = align_by_constraint((virt_offset - linkedit_vaddr).max(1), page_size);
+
+    let text_offset = align_by_constraint(offset, page_size);
+    let text_vaddr = linkedit_vaddr + rodata_vm_size;
+    offset = text_offset;
+    for sec in text_sections.iter() {
+        offset = align_by_constraint(offset, section_alignment(sec));
+        section_offset_map.insert(sec.index(), (offset, text_vaddr + offset - text_offset));
+        offset += sec.size() as usize;
+    }
+    let text_size = offset - text_offset;
+
+    let new_linkedit_offset = align_by_constraint(offset, page_size);
+    let new_linkedit_vaddr = text_vaddr + align_by_constraint(text_size, page_size);
+
+    if verbose {
+        println!();
+        println!(
+            "New Rodata Segment: {:+x} (virt: {:+x})",
+            linkedit_offset, linkedit_vaddr
+        );
+        println!(
+            "New Text Segment: {:+x} (virt: {:+x})",
+            text_offset, text_vaddr
+        );
+        println!(
+            "Moved __LINKEDIT: {:+x} (virt: {:+x})",
+            new_linkedit_offset, new_linkedit_vaddr
+        );
+    }
+
+    // Mach-O symbol addresses include the address of their section in the object file.
+    let mut symbol_vaddr_map: MutMap<SymbolIndex, usize> = MutMap::default();
+    let mut app_func_vaddr_map: MutMap<String, usize> = MutMap::default();
+    for sym in app_obj.symbols() {
+        let (index, (_, section_vaddr)) = match sym.section() {
+            SymbolSection::Section(index) => match section_offset_map.get(&index) {
+                Some(location) => (index, location),
+                None => continue,
+            },
+            _ => continue,
+        };
+        let section_address = app_obj.section_by_index(index).unwrap().address();
+        let vaddr = section_vaddr + (sym.address() - section_address) as usize;
+
+        let name = sym.name().unwrap_or_default();
+        if !md
+            .roc_symbol_vaddresses
+            .contains_key(name.trim_start_matches('_'))
+        {
+            symbol_vaddr_map.insert(sym.index(), vaddr);
+        }
+        if md.app_functions.iter().any(|func| func == name) {
+            app_func_vaddr_map.insert(name.to_string(), vaddr);
+        }
+    }
+    if verbose {
+        println!("Data Relocation Offsets: {:+x?}", symbol_vaddr_map);
+        println!("Found App Function Symbols: {:+x?}", app_func_vaddr_map);
+    }
+
+    let symbol_target = |index: SymbolIndex| -> Option<i64> {
+        if let Some(vaddr) = symbol_vaddr_map.get(&index) {
+            return Some(*vaddr as i64);
+        }
+
+        let name = app_obj.symbol_by_index(index).ok()?.name().ok()?;
+        md.roc_symbol_vaddresses
+            .get(name.trim_start_matches('_'))
+            .map(|vaddr| *vaddr as i64)
+    };
+
+    // Move data and deal with relocations.
+    for sec in rodata_sections.iter().chain(text_sections.iter()) {
+        let data = sec.data().unwrap_or_else(|err| {
+            internal_error!(
+                "Failed to load data for section, {:+x?}: {err}",
+                sec.name().unwrap(),
+            )
+        });
+        let (section_offset, section_virtual_offset) = section_offset_map[&sec.index()];
+        exec_mmap[section_offset..][..data.len()].copy_from_slice(data);
+
+        if verbose {
+            println!();
+            println!(
+                "Processing Relocations for Section: 0x{:+x?} @ {:+x} (virt: {:+x})",
+                sec, section_offset, section_virtual_offset
+            );
+        }
+
+        // An `ARM64_RELOC_ADDEND` holds the addend for the relocation that follows it.
+        let mut addend = 0;
+        for (rel_offset, rel) in sec.relocations() {
+            if verbose {
+                println!("\tFound Relocation: {:+x?}", rel);
+            }
+            let base = section_offset + rel_offset as usize;
+            let virt_base = (section_virtual_offset + rel_offset as usize) as i64;
+
+            if let RelocationKind::MachO {
+                value: macho::ARM64_RELOC_ADDEND,
+                ..
+            } = rel.kind()
+            {
+                if let RelocationTarget::Section(SectionIndex(value)) = rel.target() {
+                    // A 24 bit signed value.
+                    addend = ((value as i64) << 40) >> 40;
+                }
+                continue;
+            }
+
+            // The implicit addend of x86_64 relocations is stored in the instruction.
+            let implicit_addend = if is_aarch64 {
+                mem::take(&mut addend)
+            } else if rel.size() == 32 {
+                i32::from_le_bytes(exec_mmap[base..][..4].try_into().unwrap()) as i64
+            } else {
+                internal_error!("Relocation size not yet supported: {}", rel.size());
+            };
+
+            let target = match rel.target() {
+                RelocationTarget::Symbol(index) => match symbol_target(index) {
+                    Some(target) => target + implicit_addend,
+                    None => {
+                        let name = app_obj
+                            .symbol_by_index(index)
+                            .and_then(|sym| sym.name())
+                            .unwrap_or_default();
+                        if ["___divti3", "___udivti3"].contains(&name) {
+                            // Explicitly ignore some symbols that are currently always linked.
+                            continue;
+                        }
+                        internal_error!("Undefined Symbol in relocation, {:+x?}: {}", rel, name);
+                    }
+                },
+                // Section relocations point to the original address of their target.
+                RelocationTarget::Section(index) if !is_aarch64 => {
+                    let original_target =
+                        sec.address() as i64 + rel_offset as i64 + implicit_addend - rel.addend();
+                    let target_section = app_obj.section_by_index(index).unwrap();
+                    match section_offset_map.get(&index) {
+                        Some((_, vaddr)) => {
+                            *vaddr as i64 + original_target - target_section.address() as i64
+                        }
+                        None => internal_error!(
+                            "Relocation targets a section that is not linked: {:+x?}",
+                            target_section
+                        ),
+                    }
+                }
+                _ => internal_error!("Relocation target not yet support: {:+x?}", rel),
+            };
+
+            if verbose {
+                println!(
+                    "\t\tRelocation base location: {:+x} (virt: {:+x})",
+                    base, virt_base
+                );
+                println!("\t\tFinal relocation target: {:+x}", target);
+            }
+
+            if is_aarch64 {
+                aarch64::apply_relocation(&mut exec_mmap[base..], rel.kind(), virt_base, target)
+                    .unwrap_or_else(|e| internal_error!("{}", e));
+                continue;
+            }
+
+            match (rel.kind(), rel.encoding()) {
+                (RelocationKind::Relative, _) => {}
+                // The app is linked without a global offset table, so load the address of the
+                // target directly by turning the `movq` into a `leaq`.
+                (RelocationKind::GotRelative, RelocationEncoding::X86RipRelativeMovq)
+                    if exec_mmap[base - 2] == 0x8b =>
+                {
+                    exec_mmap[base - 2] = 0x8d;
+                }
+                x => {
+                    internal_error!("Relocation Kind not yet support: {:?}", x);
+                }
+            }
+            let value = (target + rel.addend() - virt_base) as i32;
+            exec_mmap[base..][..4].copy_from_slice(&value.to_le_bytes());
+        }
+    }
+
+    let rodata_prot = if bss_sections.is_empty() {
+        macho::VM_PROT_READ
+    } else {
+        macho::VM_PROT_READ | macho::VM_PROT_WRITE
+    };
+    let rodata_cmd_offset = md.macho_cmd_loc as usize;
+    let text_cmd_offset = rodata_cmd_offset + SEGMENT_COMMAND_SIZE + SECTION_SIZE;
+    fill_app_segment(
+        exec_mmap,
+        rodata_cmd_offset,
+        APP_RODATA_SEGMENT,
+        (linkedit_offset, rodata_file_size),
+        (linkedit_vaddr, rodata_vm_size),
+        rodata_prot,
+    );
+    fill_app_segment(
+        exec_mmap,
+        text_cmd_offset,
+        APP_TEXT_SEGMENT,
+        (text_offset, text_size),
+        (text_vaddr, new_linkedit_vaddr - text_vaddr),
+        macho::VM_PROT_READ | macho::VM_PROT_EXECUTE,
+    );
+
+    // Move __LINKEDIT, and make room for a new code signature.
+    let mut linkedit_end = write_linkedit(exec_mmap, &tables, &contents, new_linkedit_offset);
+    let signature = signature.map(|(table, identifier)| {
+        let signature_offset = align_by_constraint(linkedit_end, table.alignment());
+        let signature_size = code_signature_size(&identifier, signature_offset);
+        write_u32(
+            exec_mmap,
+            table.cmd_offset + table.offset_field,
+            signature_offset as u32,
+        );
+        write_u32(
+            exec_mmap,
+            table.cmd_offset + table.size_field,
+            signature_size as u32,
+        );
+        linkedit_end = signature_offset + signature_size;
+        (signature_offset, identifier)
+    });
+
+    let linkedit =
+        load_struct_inplace_mut::<macho::SegmentCommand64<LE>>(exec_mmap, linkedit_cmd_offset);
+    let linkedit_size = linkedit_end - new_linkedit_offset;
+    linkedit.fileoff.set(LE, new_linkedit_offset as u64);
+    linkedit.vmaddr.set(LE, new_linkedit_vaddr as u64);
+    linkedit.filesize.set(LE, linkedit_size as u64);
+    linkedit
+        .vmsize
+        .set(LE, align_by_constraint(linkedit_size, page_size) as u64);
+
+    let symtab = match find_command(&cmds, macho::LC_SYMTAB) {
+        Some(command) => load_struct_inplace::<macho::SymtabCommand<LE>>(exec_mmap, command.offset),
+        None => internal_error!("Host does not have a symbol table"),
+    };
+    let symbols_offset = symtab.symoff.get(LE) as usize;
+
+    for func_name in md.app_functions.iter() {
+        let func_virt_offset = match app_func_vaddr_map.get(func_name) {
+            Some(offset) => *offset as u64,
+            None => {
+                internal_error!("Function, {}, was not defined by the app", &func_name);
+            }
+        };
+        if verbose {
+            println!(
+                "Updating calls to {} to the address: {:+x}",
+                &func_name, func_virt_offset
+            );
+        }
+
+        for s in md.surgeries.get(func_name).unwrap_or(&vec![]) {
+            if verbose {
+                println!("\tPerforming surgery: {:+x?}", s);
+            }
+            let file_offset = s.file_offset as usize;
+            match s.virtual_offset {
+                VirtualOffset::Relative(address) if is_aarch64 => {
+                    aarch64::patch_branch(
+                        &mut exec_mmap[file_offset..],
+                        address as i64,
+                        func_virt_offset as i64,
+                    )
+                    .unwrap_or_else(|e| internal_error!("{}", e));
+                }
+                VirtualOffset::Relative(next_ip) if s.size == 4 => {
+                    let target = (func_virt_offset as i64 - next_ip as i64) as i32;
+                    exec_mmap[file_offset..][..4].copy_from_slice(&target.to_le_bytes());
+                }
+                VirtualOffset::Absolute if s.size == 8 => {
+                    write_u64(exec_mmap, file_offset, func_virt_offset);
+                }
+                VirtualOffset::ChainedRebase(base) => {
+                    let fixup = read_u64(exec_mmap, file_offset) & !CHAINED_REBASE_TARGET_MASK;
+                    let target = func_virt_offset.wrapping_sub(base) & CHAINED_REBASE_TARGET_MASK;
+                    write_u64(exec_mmap, file_offset, fixup | target);
+                }
+                _ => {
+                    internal_error!("Surgery size not yet supported: {}", s.size);
+                }
+            }
+        }
+
+        // Replace the stub with just a jump.
+        // This is a backup incase we missed a call to the stub.
+        if let Some((stub_offset, stub_vaddr)) = md.plt_addresses.get(func_name) {
+            let stub_offset = *stub_offset as usize;
+            if verbose {
+                println!("\tStub: {:+x}, {:+x}", stub_offset, stub_vaddr);
+            }
+            if is_aarch64 {
+                aarch64::write_plt_stub(
+                    &mut exec_mmap[stub_offset..][..AARCH64_STUB_SIZE],
+                    *stub_vaddr as i64,
+                    func_virt_offset as i64,
+                )
+                .unwrap_or_else(|e| internal_error!("{}", e));
+            } else {
+                let jmp_inst_len = 5;
+                let target =
+                    (func_virt_offset as i64 - (*stub_vaddr as i64 + jmp_inst_len as i64)) as i32;
+                exec_mmap[stub_offset] = 0xE9;
+                exec_mmap[stub_offset + 1..stub_offset + jmp_inst_len]
+                    .copy_from_slice(&target.to_le_bytes());
+                exec_mmap[stub_offset + jmp_inst_len..stub_offset + X86_64_STUB_SIZE].fill(0x90);
+            }
+        }
+
+        if let Some(i) = md.dynamic_symbol_indices.get(func_name) {
+            let symbol = load_struct_inplace_mut::<macho::Nlist64<LE>>(
+                exec_mmap,
+                symbols_offset + *i as usize * NLIST_SIZE,
+            );
+            symbol.n_value.set(LE, func_virt_offset);
+        }
+    }
+
+    // The signature covers everything before it, so it has to be written last.
+    if let Some((signature_offset, identifier)) = signature {
+        write_code_signature(exec_mmap, signature_offset, &identifier, exec_segment);
+    }
+
+    *offset_ref = linkedit_end;
+}
+
+fn read_u32_be(data: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes(data[offset..][..4].try_into().unwrap())
+}
+
+/// The identifier in the code directory of an existing code signature.
+fn code_signature_identifier(signature: &[u8]) -> Option<Vec<u8>> {
+    if read_u32_be(signature, 0) != CSMAGIC_EMBEDDED_SIGNATURE {
+        return None;
+    }
+
+    let count = read_u32_be(signature, 8) as usize;
+    let code_directory = (0..count).find_map(|i| {
+        let slot = read_u32_be(signature, 12 + 8 * i);
+        let offset = read_u32_be(signature, 16 + 8 * i) as usize;
+        (slot == CSSLOT_CODEDIRECTORY).then(|| &signature[offset..])
+    })?;
+    if read_u32_be(code_directory, 0) != CSMAGIC_CODEDIRECTORY {
+        return None;
+    }
+
+    let identifier = &code_directory[read_u32_be(code_directory, 20) as usize..];
+    identifier
+        .split(|&b| b == 0)
+        .next()
+        .map(|name| name.to_vec())
+}
+
+fn code_signature_size(identifier: &[u8], code_limit: usize) -> usize {
+    let pages = (code_limit + CODE_SIGNATURE_PAGE_SIZE - 1) / CODE_SIGNATURE_PAGE_SIZE;
+    let identifier_size = align_by_constraint(identifier.len() + 1, 16);
+    let hashes_size = pages * Sha256::output_size();
+
+    align_by_constraint(
+        SUPER_BLOB_SIZE + CODE_DIRECTORY_SIZE + identifier_size + hashes_size,
+        16,
+    )
+}
+
+/// Signs everything before `signature_offset` with an ad-hoc signature, like the one the linker
+/// adds for arm64. Without a valid signature, macOS refuses to run arm64 executables.
+fn write_code_signature(
+    data: &mut [u8],
+    signature_offset: usize,
+    identifier: &[u8],
+    (exec_segment_base, exec_segment_limit): (u64, u64),
+) {
+    let size = code_signature_size(identifier, signature_offset);
+    let (code, signature) = data.split_at_mut(signature_offset);
+    let signature = &mut signature[..size];
+    signature.fill(0);
+
+    write_u32_be(signature, 0, CSMAGIC_EMBEDDED_SIGNATURE);
+    write_u32_be(signature, 4, size as u32);
+    write_u32_be(signature, 8, 1);
+    write_u32_be(signature, 12, CSSLOT_CODEDIRECTORY);
+    write_u32_be(signature, 16, SUPER_BLOB_SIZE as u32);
+
+    let code_directory = &mut signature[SUPER_BLOB_SIZE..];
+    let hash_size = Sha256::output_size();
+    let pages = code.chunks(CODE_SIGNATURE_PAGE_SIZE);
+    let hash_offset = CODE_DIRECTORY_SIZE + align_by_constraint(identifier.len() + 1, 16);
+
+    write_u32_be(code_directory, 0, CSMAGIC_CODEDIRECTORY);
+    write_u32_be(code_directory, 4, (size - SUPER_BLOB_SIZE) as u32);
+    write_u32_be(code_directory, 8, CS_SUPPORTSEXECSEG);
+    write_u32_be(code_directory, 12, CS_ADHOC | CS_LINKER_SIGNED);
+    write_u32_be(code_directory, 16, hash_offset as u32);
+    write_u32_be(code_directory, 20, CODE_DIRECTORY_SIZE as u32);
+    write_u32_be(code_directory, 28, pages.len() as u32);
+    write_u32_be(code_directory, 32, signature_offset as u32);
+    code_directory[36] = hash_size as u8;
+    code_directory[37] = CS_HASHTYPE_SHA256;
+    code_directory[39] = CODE_SIGNATURE_PAGE_SHIFT;
+    write_u64_be(code_directory, 64, exec_segment_base);
+    write_u64_be(code_directory, 72, exec_segment_limit);
+    write_u64_be(code_directory, 80, CS_EXECSEG_MAIN_BINARY);
+    code_directory[CODE_DIRECTORY_SIZE..][..identifier.len()].copy_from_slice(identifier);
+
+    for (i, page) in pages.enumerate() {
+        code_directory[hash_offset + i * hash_size..][..hash_size]
+            .copy_from_slice(&Sha256::digest(page));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::{
+        adrp_page, branch_target, lo12, read_data, read_instructions, symbol_address,
+    };
+    use bincode::deserialize_from;
+    use object::ObjectSegment;
+    use std::io::BufReader;
+    use target_lexicon::Triple;
+
+    // Assembled from the `.s` files next to them for `x86_64-apple-darwin` and
+    // `aarch64-apple-darwin`. The hosts are then linked with e.g.
+    //   ld64.lld -arch arm64 -platform_version macos 11.0 11.0 -headerpad 0x1000 -fixup_chains \
+    //       -e _main dynhost_aarch64_macho.o libapp.dylib libSystem.tbd -o dynhost_aarch64_macho
+    // where libapp.dylib is the dummy library generated for the app functions, and libSystem.tbd
+    // a stub for the system library. The x86_64 host is linked without `-fixup_chains`, so
+    // between them they cover both the classic dyld info and chained fixups.
+    const X86_64_MACHO_DYNHOST: &[u8] = include_bytes!("../dynhost_x86_64_macho") as &[_];
+    const X86_64_MACHO_APP: &[u8] = include_bytes!("../app_x86_64_macho.o") as &[_];
+    const AARCH64_MACHO_DYNHOST: &[u8] = include_bytes!("../dynhost_aarch64_macho") as &[_];
+    const AARCH64_MACHO_APP: &[u8] = include_bytes!("../app_aarch64_macho.o") as &[_];
+
+    const AARCH64_NOP: u32 = 0xd503_201f;
+
+    fn link(target: &str, host: &[u8], app: &[u8]) -> (metadata::Metadata, Vec<u8>) {
+        let target: Triple = target.parse().unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let dynhost = dir.path().join("dynhost");
+        let metadata = dir.path().join("metadata");
+        let preprocessed_host = dir.path().join("preprocessedhost");
+        let app_path = dir.path().join("app.o");
+        let output = dir.path().join("output");
+
+        fs::write(&dynhost, host).unwrap();
+        fs::write(&app_path, app).unwrap();
+
+        crate::preprocess(
+            &target,
+            dynhost.to_str().unwrap(),
+            metadata.to_str().unwrap(),
+            preprocessed_host.to_str().unwrap(),
+            Path::new("libapp.dylib"),
+            false,
+            false,
+        );
+
+        let md: metadata::Metadata =
+            deserialize_from(BufReader::new(File::open(&metadata).unwrap())).unwrap();
+
+        fs::copy(&preprocessed_host, &output).unwrap();
+        crate::surgery(
+            app_path.to_str().unwrap(),
+            metadata.to_str().unwrap(),
+            output.to_str().unwrap(),
+            false,
+            false,
+            &target,
+        );
+
+        (md, fs::read(&output).unwrap())
+    }
+
+    /// Checks what both hosts have in common after surgery.
+    fn check_linked(object: &object::File, data: &[u8]) {
+        assert_eq!(object.format(), object::BinaryFormat::MachO);
+
+        // The host no longer depends on the dummy library, and the ordinals of the libraries
+        // that followed it have been updated.
+        let cmds = load_commands(data);
+        let dylibs: Vec<_> = cmds
+            .iter()
+            .filter(|command| is_dylib_command(command.cmd))
+            .map(|command| dylib_name(data, command))
+            .collect();
+        assert_eq!(dylibs, [b"/usr/lib/libSystem.B.dylib"]);
+
+        let mut imports: Vec<_> = object
+            .imports()
+            .unwrap()
+            .iter()
+            .map(|import| (import.library(), import.name()))
+            .collect();
+        imports.sort_unstable();
+        assert_eq!(
+            imports,
+            [
+                (&b"/usr/lib/libSystem.B.dylib"[..], &b"_free"[..]),
+                (&b"/usr/lib/libSystem.B.dylib"[..], &b"_malloc"[..]),
+                (&b"/usr/lib/libSystem.B.dylib"[..], &b"dyld_stub_binder"[..]),
+            ]
+        );
+
+        let segments: Vec<_> = object
+            .segments()
+            .map(|segment| segment.name().unwrap().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            &segments[segments.len() - 3..],
+            ["__ROC_RODATA", "__ROC_TEXT", "__LINKEDIT"]
+        );
+
+        // The app functions are defined in the new executable segment.
+        let text = object
+            .segments()
+            .find(|segment| segment.name() == Ok(Some("__ROC_TEXT")))
+            .unwrap();
+        for name in [
+            "_roc__mainForHost_1_exposed_generic",
+            "_roc__mainForHost_size",
+        ] {
+            let address = symbol_address(object, name);
+            assert!((text.address()..text.address() + text.size()).contains(&address));
+        }
+
+        // __LINKEDIT still ends the file.
+        let linkedit = object.segments().last().unwrap();
+        let (offset, size) = linkedit.file_range();
+        assert_eq!(offset + size, data.len() as u64);
+    }
+
+    /// The target of the x86_64 rip relative displacement at `address`.
+    fn rip_relative(object: &object::File, address: u64) -> u64 {
+        let displacement = read_data(object, address, 4);
+        let displacement = i32::from_le_bytes(displacement.try_into().unwrap());
+        (address as i64 + 4 + displacement as i64) as u64
+    }
+
+    fn read_u64_at(object: &object::File, address: u64) -> u64 {
+        u64::from_le_bytes(read_data(object, address, 8).try_into().unwrap())
+    }
+
+    #[test]
+    fn preprocess_and_surgery_macho_x86_64() {
+        let (md, output) = link(
+            "x86_64-apple-darwin",
+            X86_64_MACHO_DYNHOST,
+            X86_64_MACHO_APP,
+        );
+        let object = object::File::parse(output.as_slice()).unwrap();
+        assert_eq!(object.architecture(), object::Architecture::X86_64);
+        check_linked(&object, &output);
+
+        let main = symbol_address(&object, "_roc__mainForHost_1_exposed_generic");
+        let size = symbol_address(&object, "_roc__mainForHost_size");
+        let roc_alloc = symbol_address(&object, "_roc_alloc");
+        let roc_dealloc = symbol_address(&object, "_roc_dealloc");
+        let host_main = symbol_address(&object, "_main");
+        let host_pointers = symbol_address(&object, "_host_pointers");
+
+        // push; call size; call main; movq size@GOTPCREL; call *%rax; pop; jmp main
+        assert_eq!(rip_relative(&object, host_main + 2), size);
+        assert_eq!(rip_relative(&object, host_main + 7), main);
+        let got_entry = rip_relative(&object, host_main + 14);
+        assert_eq!(read_u64_at(&object, got_entry), size);
+        assert_eq!(rip_relative(&object, host_main + 22), main);
+        assert_eq!(read_u64_at(&object, host_pointers + 8), size);
+
+        // Any call that still goes through a stub jumps straight to the app.
+        for (name, (_, stub)) in md.plt_addresses.iter() {
+            let stub_code = read_data(&object, *stub, X86_64_STUB_SIZE as u64);
+            assert_eq!(stub_code[0], 0xe9);
+            assert_eq!(
+                rip_relative(&object, stub + 1),
+                symbol_address(&object, name)
+            );
+            assert_eq!(stub_code[5], 0x90);
+        }
+
+        // The pointers to the app are rebased rather than bound.
+        let cmds = load_commands(&output);
+        let info = find_command(&cmds, macho::LC_DYLD_INFO_ONLY).unwrap();
+        let info = load_struct_inplace::<macho::DyldInfoCommand<LE>>(&output, info.offset);
+        let segments: Vec<_> = object.segments().map(|segment| segment.address()).collect();
+
+        let rebases = decode_rebases(
+            &output[info.rebase_off.get(LE) as usize..][..info.rebase_size.get(LE) as usize],
+        );
+        let rebased: Vec<_> = rebases
+            .iter()
+            .map(|rebase| segments[rebase.segment as usize] + rebase.offset)
+            .collect();
+        assert!(rebased.contains(&got_entry));
+        assert!(rebased.contains(&(host_pointers + 8)));
+
+        let binds = decode_binds(
+            &output[info.bind_off.get(LE) as usize..][..info.bind_size.get(LE) as usize],
+        );
+        assert!(!binds.is_empty());
+        assert!(binds.iter().all(|bind| bind.ordinal == 1));
+
+        // movq L_app_size(%rip), %rax; ret
+        assert_eq!(read_u64_at(&object, rip_relative(&object, size + 3)), 16);
+
+        // push; call size; leaq; leaq; leaq (was movq from the GOT); call roc_alloc; pop; jmp
+        assert_eq!(rip_relative(&object, main + 2), size);
+        let greeting = rip_relative(&object, main + 9);
+        assert_eq!(read_data(&object, greeting, 13), b"Hello, World!");
+        assert_eq!(rip_relative(&object, main + 16), greeting + 7);
+        assert_eq!(read_data(&object, main + 20, 3), [0x48, 0x8d, 0x15]);
+        assert_eq!(rip_relative(&object, main + 23), roc_alloc);
+        assert_eq!(rip_relative(&object, main + 28), roc_alloc);
+        assert_eq!(rip_relative(&object, main + 34), roc_dealloc);
+    }
+
+    /// Walks the chained fixups, returning the address and target of every rebase, and the
+    /// library ordinal of every bind.
+    fn walk_chained_fixups(data: &[u8]) -> (MutMap<u64, u64>, Vec<u64>) {
+        let cmds = load_commands(data);
+        let segments = segment_commands(data, &cmds);
+        let image_base = segments[1].1.vmaddr.get(LE);
+        let fixups = find_command(&cmds, macho::LC_DYLD_CHAINED_FIXUPS).unwrap();
+        let fixups = &data[read_u32(data, fixups.offset + 8) as usize..];
+
+        let starts = read_u32(fixups, 4) as usize;
+        let imports = read_u32(fixups, 8) as usize;
+        assert_eq!(read_u32(fixups, 20), DYLD_CHAINED_IMPORT);
+        assert_eq!(read_u32(fixups, starts) as usize, segments.len());
+
+        let mut rebases = MutMap::default();
+        let mut binds = Vec::new();
+        for (i, (_, segment)) in segments.iter().enumerate() {
+            let seg_info = read_u32(fixups, starts + 4 + 4 * i) as usize;
+            if seg_info == 0 {
+                continue;
+            }
+
+            let seg_info = &fixups[starts + seg_info..];
+            let page_size = u16::from_le_bytes([seg_info[4], seg_info[5]]) as u64;
+            let base = match u16::from_le_bytes([seg_info[6], seg_info[7]]) {
+                DYLD_CHAINED_PTR_64 => 0,
+                DYLD_CHAINED_PTR_64_OFFSET => image_base,
+                x => panic!("unexpected pointer format {}", x),
+            };
+
+            for page in 0..u16::from_le_bytes([seg_info[20], seg_info[21]]) as usize {
+                let start = u16::from_le_bytes([seg_info[22 + 2 * page], seg_info[23 + 2 * page]]);
+                if start == DYLD_CHAINED_PTR_START_NONE {
+                    continue;
+                }
+
+                let mut offset = page as u64 * page_size + start as u64;
+                loop {
+                    let fixup = read_u64(data, (segment.fileoff.get(LE) + offset) as usize);
+                    if fixup >> 63 == 1 {
+                        let import = (fixup & 0xff_ffff) as usize;
+                        binds.push(read_u32(fixups, imports + 4 * import) as u64 & 0xff);
+                    } else {
+                        let target = (fixup & CHAINED_REBASE_TARGET_MASK) + base;
+                        rebases.insert(segment.vmaddr.get(LE) + offset, target);
+                    }
+
+                    let next = (fixup & CHAINED_NEXT_MASK) >> 51;
+                    if next == 0 {
+                        break;
+                    }
+                    offset += next * 4;
+                }
+            }
+        }
+
+        (rebases, binds)
+    }
+
+    fn code_signature_range(data: &[u8]) -> (usize, usize) {
+        let cmds = load_commands(data);
+        let signature = find_command(&cmds, macho::LC_CODE_SIGNATURE).unwrap();
+        let signature =
+            load_struct_inplace::<macho::LinkeditDataCommand<LE>>(data, signature.offset);
+        (
+            signature.dataoff.get(LE) as usize,
+            signature.datasize.get(LE) as usize,
+        )
+    }
+
+    fn check_code_signature(data: &[u8], identifier: &[u8]) {
+        let (offset, size) = code_signature_range(data);
+        assert_eq!(offset + size, data.len());
+
+        let signature = &data[offset..];
+        assert_eq!(code_signature_identifier(signature).unwrap(), identifier);
+
+        let code_directory = &signature[read_u32_be(signature, 16) as usize..];
+        let hash_offset = read_u32_be(code_directory, 16) as usize;
+        let hash_count = read_u32_be(code_directory, 28) as usize;
+        assert_eq!(read_u32_be(code_directory, 32) as usize, offset);
+        assert_eq!(hash_count, (offset + 4095) / 4096);
+
+        for (i, page) in data[..offset].chunks(4096).enumerate() {
+            let hash = &code_directory[hash_offset + 32 * i..][..32];
+            assert_eq!(hash, Sha256::digest(page).as_slice());
+        }
+    }
+
+    #[test]
+    fn preprocess_and_surgery_macho_aarch64() {
+        let (offset, _) = code_signature_range(AARCH64_MACHO_DYNHOST);
+        let identifier = code_signature_identifier(&AARCH64_MACHO_DYNHOST[offset..]).unwrap();
+        let (md, output) = link(
+            "aarch64-apple-darwin",
+            AARCH64_MACHO_DYNHOST,
+            AARCH64_MACHO_APP,
+        );
+        let object = object::File::parse(output.as_slice()).unwrap();
+        assert_eq!(object.architecture(), object::Architecture::Aarch64);
+        check_linked(&object, &output);
+        check_code_signature(&output, &identifier);
+
+        let main = symbol_address(&object, "_roc__mainForHost_1_exposed_generic");
+        let size = symbol_address(&object, "_roc__mainForHost_size");
+        let roc_alloc = symbol_address(&object, "_roc_alloc");
+        let roc_dealloc = symbol_address(&object, "_roc_dealloc");
+        let host_main = symbol_address(&object, "_main");
+        let host_pointers = symbol_address(&object, "_host_pointers");
+
+        // stp; bl size; bl main; adrp; ldr; blr; ldp; b main
+        let host_code = read_instructions(&object, host_main, 8);
+        assert_eq!(branch_target(host_code[1], host_main + 4), size);
+        assert_eq!(branch_target(host_code[2], host_main + 8), main);
+        assert_eq!(branch_target(host_code[7], host_main + 28), main);
+        let got_entry = adrp_page(host_code[3], host_main + 12) + lo12(host_code[4], 3);
+
+        // The pointers to the app are rebased rather than bound.
+        let (rebases, binds) = walk_chained_fixups(&output);
+        assert_eq!(rebases.get(&got_entry), Some(&size));
+        assert_eq!(rebases.get(&(host_pointers + 8)), Some(&size));
+        assert_eq!(binds.len(), 4);
+        assert!(binds.iter().all(|ordinal| *ordinal == 1));
+
+        // Any call that still goes through a stub jumps straight to the app.
+        for (name, (_, stub)) in md.plt_addresses.iter() {
+            let stub_code = read_instructions(&object, *stub, 3);
+            assert_eq!(
+                branch_target(stub_code[0], *stub),
+                symbol_address(&object, name)
+            );
+            assert_eq!(stub_code[1..], [AARCH64_NOP, AARCH64_NOP]);
+        }
+
+        // adrp; ldr; ret
+        let size_code = read_instructions(&object, size, 2);
+        let size_data = adrp_page(size_code[0], size) + lo12(size_code[1], 3);
+        assert_eq!(read_u64_at(&object, size_data), 16);
+
+        // stp; bl size; adrp; add; adrp; add; adrp; add (was ldr from the GOT); bl roc_alloc;
+        // ldp; b roc_dealloc
+        let main_code = read_instructions(&object, main, 11);
+        assert_eq!(branch_target(main_code[1], main + 4), size);
+        let greeting = adrp_page(main_code[2], main + 8) + lo12(main_code[3], 0);
+        assert_eq!(read_data(&object, greeting, 13), b"Hello, World!");
+        let world = adrp_page(main_code[4], main + 16) + lo12(main_code[5], 0);
+        assert_eq!(world, greeting + 7);
+        assert_eq!(main_code[7] & 0xffc0_0000, 0x9100_0000);
+        let alloc = adrp_page(main_code[6], main + 24) + lo12(main_code[7], 0);
+        assert_eq!(alloc, roc_alloc);
+        assert_eq!(branch_target(main_code[8], main + 32), roc_alloc);
+        assert_eq!(branch_target(main_code[10], main + 40), roc_dealloc);
+    }
+
+    #[test]
+    fn dyld_info_round_trip() {
+        let rebases =
+            [(1, 0x10), (1, 0x18), (1, 0x20), (2, 0x8), (2, 0x1000)].map(|(segment, offset)| {
+                Rebase {
+                    segment,
+                    offset,
+                    kind: macho::REBASE_TYPE_POINTER,
+                }
+            });
+        assert_eq!(decode_rebases(&encode_rebases(&rebases)), rebases);
+
+        let bind = |segment, offset, ordinal, name: &[u8]| Bind {
+            segment,
+            offset,
+            ordinal,
+            name: name.to_vec(),
+            flags: 0,
+            kind: macho::BIND_TYPE_POINTER,
+            addend: 0,
+        };
+        let binds = [
+            bind(2, 0x0, 1, b"_free"),
+            bind(2, 0x8, 1, b"_malloc"),
+            bind(3, 0x10, 20, b"_foo"),
+            bind(3, 0x8, -2, b"_bar"),
+            Bind {
+                addend: -8,
+                ..bind(3, 0x20, 2, b"_baz")
+            },
+        ];
+        assert_eq!(decode_binds(&encode_binds(&binds)), binds);
+    }
+
+    #[test]
+    fn remove_lazy_binds() {
+        let lazy_bind = |ordinal, name: &[u8]| {
+            let mut stream = vec![macho::BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB | 2, 0x10];
+            stream.push(macho::BIND_OPCODE_SET_DYLIB_ORDINAL_IMM | ordinal);
+            stream.push(macho::BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM);
+            stream.extend_from_slice(name);
+            stream.extend([0, macho::BIND_OPCODE_DO_BIND, macho::BIND_OPCODE_DONE]);
+            stream
+        };
+
+        let mut stream = [
+            lazy_bind(1, b"_free"),
+            lazy_bind(2, b"_roc"),
+            lazy_bind(3, b"_x"),
+        ]
+        .concat();
+        remove_app_lazy_binds(&mut stream, 2);
+
+        // The bind to the app is gone, without moving the binds after it.
+        let expected = [
+            lazy_bind(1, b"_free"),
+            vec![macho::BIND_OPCODE_DONE; lazy_bind(2, b"_roc").len()],
+            lazy_bind(2, b"_x"),
+        ]
+        .concat();
+        assert_eq!(stream, expected);
+    }
+}
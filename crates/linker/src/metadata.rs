@@ -5,6 +5,9 @@ use serde::{Deserialize, Serialize};
 pub enum VirtualOffset {
     Absolute,
     Relative(u64),
+    /// A rebase in a Mach-O chain of fixups. Only the low 36 bits of the pointer hold its
+    /// target, which is relative to the given address; the other bits link up the chain.
+    ChainedRebase(u64),
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
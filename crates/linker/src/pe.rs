@@ -1,45 +1,242 @@
-use std::{path::Path, time::Instant};
+use std::{
+    fs,
+    io::{BufReader, BufWriter},
+    path::Path,
+    time::Instant,
+};
 
+use bincode::{deserialize_from, serialize_into};
 use memmap2::{Mmap, MmapMut};
 use object::{
-    pe::{ImageImportDescriptor, ImageNtHeaders64, ImageSectionHeader, ImageThunkData64},
-    read::pe::ImportTable,
+    pe::{
+        self, ImageDataDirectory, ImageDebugDirectory, ImageImportDescriptor, ImageNtHeaders64,
+        ImageSectionHeader, ImageThunkData64,
+    },
+    read::pe::{ImportTable, SectionTable},
+    Object, ObjectSection, ObjectSymbol, RelocationKind, RelocationTarget, Section, SectionIndex,
+    Symbol, SymbolIndex, SymbolSection,
 };
 use roc_collections::MutMap;
 use roc_error_macros::internal_error;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    align_by_constraint, load_struct_inplace_mut, load_structs_inplace_mut, report_timing,
+    MIN_SECTION_ALIGNMENT,
+};
 
-use crate::load_struct_inplace_mut;
+/// The section headers that preprocessing adds to the host, for the app's data and code and for
+/// the base relocations that the app needs on top of those of the host.
+const APP_SECTIONS: [[u8; 8]; 3] = [*b".rdata\0\0", *b".text\0\0\0", *b".reloc\0\0"];
+
+/// What surgery needs to know about the preprocessed host
+#[derive(Debug, Serialize, Deserialize)]
+struct PeMetadata {
+    /// Size of the preprocessed host; the app goes right after it
+    dynhost_file_size: usize,
+    image_base: u64,
+    file_alignment: u32,
+    section_alignment: u32,
+    /// File offset of the NT headers
+    nt_headers_offset: usize,
+    /// File offset of the section headers that preprocessing added for the app
+    app_section_headers_offset: usize,
+    /// Virtual address (relative to the image base) just past the host's last section
+    last_host_section_end: u32,
+    /// File offset and size of the host's base relocations
+    base_relocations: (u32, u32),
+    /// The functions that the host imports from the app
+    imports: Vec<PeImport>,
+    /// The roc_* functions that the host provides to the app, by virtual address
+    exports: MutMap<String, u64>,
+}
+
+/// A slot in the host's import address table where the loader would have stored the address of
+/// an app function
+#[derive(Debug, Serialize, Deserialize)]
+struct PeImport {
+    name: String,
+    /// Virtual address of the slot, relative to the image base
+    rva: u32,
+    file_offset: u32,
+}
 
-#[allow(dead_code)]
 pub(crate) fn preprocess_windows(
     exec_filename: &str,
-    _metadata_filename: &str,
-    _out_filename: &str,
+    metadata_filename: &str,
+    out_filename: &str,
     _shared_lib: &Path,
-    _verbose: bool,
-    _time: bool,
+    verbose: bool,
+    time: bool,
 ) -> object::read::Result<()> {
+    use object::read::pe::{ImageNtHeaders, ImageOptionalHeader};
+    use object::LittleEndian as LE;
+
     let total_start = Instant::now();
     let exec_parsing_start = total_start;
-    let exec_file = std::fs::File::open(exec_filename).unwrap_or_else(|e| internal_error!("{}", e));
+    let exec_file = fs::File::open(exec_filename).unwrap_or_else(|e| internal_error!("{}", e));
     let exec_mmap = unsafe { Mmap::map(&exec_file).unwrap_or_else(|e| internal_error!("{}", e)) };
     let exec_data = &*exec_mmap;
-    let _exec_obj = match object::read::pe::PeFile64::parse(exec_data) {
+    let exec_obj = match object::File::parse(exec_data) {
         Ok(obj) => obj,
         Err(err) => {
             internal_error!("Failed to parse executable file: {}", err);
         }
     };
 
-    let _exec_parsing_duration = exec_parsing_start.elapsed();
+    let exec_parsing_duration = exec_parsing_start.elapsed();
+
+    let import_processing_start = Instant::now();
 
     let dynamic_relocations = DynamicRelocationsPe::new(exec_data);
+    let exports = collect_roc_exports(&exec_obj);
+
+    if verbose {
+        // println!("{:#x?}", dynamic_relocations);
+        println!("Found roc function exports: {:+x?}", exports);
+    }
 
-    println!("{:#x?}", dynamic_relocations);
+    let import_processing_duration = import_processing_start.elapsed();
+
+    let platform_gen_start = Instant::now();
+
+    let mut result = Preprocessor::preprocess(Path::new(out_filename), exec_data, &APP_SECTIONS);
+
+    // everything after the headers has moved by this much
+    let shift = (result.len() - exec_data.len()) as u32;
+
+    remove_dummy_dll_import_table(
+        &mut result,
+        dynamic_relocations.imports_offset_in_file + shift,
+        dynamic_relocations.dummy_import_index,
+    );
+
+    let dos_header = pe::ImageDosHeader::parse(&*result)?;
+    let mut offset = dos_header.nt_headers_offset().into();
+    let nt_headers_offset = offset as usize;
+    let (nt_headers, data_directories) = ImageNtHeaders64::parse(&*result, &mut offset)?;
+    let sections = nt_headers.sections(&*result, offset)?;
+    let host_section_count = sections.len() - APP_SECTIONS.len();
+
+    let last_host_section_end = sections
+        .iter()
+        .take(host_section_count)
+        .map(|section| section.virtual_address.get(LE) + section.virtual_size.get(LE))
+        .max()
+        .unwrap_or_default();
+
+    let base_relocations = match data_directories.get(pe::IMAGE_DIRECTORY_ENTRY_BASERELOC) {
+        Some(dir) if dir.size.get(LE) > 0 => {
+            let (offset, _) = sections
+                .pe_file_range_at(dir.virtual_address.get(LE))
+                .unwrap_or_else(|| internal_error!("Invalid base relocation directory"));
+            (offset, dir.size.get(LE))
+        }
+        _ => (0, 0),
+    };
+
+    let mut imports: Vec<_> = dynamic_relocations
+        .address_and_offset
+        .into_iter()
+        .map(|(name, (rva, file_offset))| PeImport {
+            name,
+            rva,
+            file_offset: file_offset + shift,
+        })
+        .collect();
+    imports.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+    let md = PeMetadata {
+        dynhost_file_size: result.len(),
+        image_base: nt_headers.optional_header().image_base(),
+        file_alignment: nt_headers.optional_header().file_alignment(),
+        section_alignment: nt_headers.optional_header().section_alignment(),
+        nt_headers_offset,
+        app_section_headers_offset: offset as usize
+            + host_section_count * Preprocessor::SECTION_HEADER_WIDTH,
+        last_host_section_end,
+        base_relocations,
+        imports,
+        exports,
+    };
+
+    let platform_gen_duration = platform_gen_start.elapsed();
+
+    if verbose {
+        println!();
+        println!("{:+x?}", md);
+    }
+
+    let saving_metadata_start = Instant::now();
+    {
+        let output =
+            fs::File::create(metadata_filename).unwrap_or_else(|e| internal_error!("{}", e));
+        let output = BufWriter::new(output);
+        if let Err(err) = serialize_into(output, &md) {
+            internal_error!("Failed to serialize metadata: {}", err);
+        };
+    }
+    let saving_metadata_duration = saving_metadata_start.elapsed();
+
+    let flushing_data_start = Instant::now();
+    result.flush().unwrap_or_else(|e| internal_error!("{}", e));
+    drop(result);
+    let flushing_data_duration = flushing_data_start.elapsed();
+
+    let total_duration = total_start.elapsed();
+
+    if verbose || time {
+        println!();
+        println!("Timings");
+        report_timing("Executable Parsing", exec_parsing_duration);
+        report_timing("Import Processing", import_processing_duration);
+        report_timing("Generate Modified Platform", platform_gen_duration);
+        report_timing("Saving Metadata", saving_metadata_duration);
+        report_timing("Flushing Data to Disk", flushing_data_duration);
+        report_timing(
+            "Other",
+            total_duration
+                - exec_parsing_duration
+                - import_processing_duration
+                - platform_gen_duration
+                - saving_metadata_duration
+                - flushing_data_duration,
+        );
+        report_timing("Total", total_duration);
+    }
 
     Ok(())
 }
 
+/// Executables are usually stripped, so besides the symbol table (if any) we look at what the
+/// host exports to find the roc_* functions it provides.
+fn collect_roc_exports(exec_obj: &object::File) -> MutMap<String, u64> {
+    let mut vaddresses = crate::collect_roc_definitions(exec_obj);
+
+    let exports = exec_obj
+        .exports()
+        .unwrap_or_else(|e| internal_error!("{}", e));
+
+    for export in exports {
+        let name = String::from_utf8_lossy(export.name());
+
+        if !name.starts_with("roc_") {
+            continue;
+        }
+
+        // special exceptions for memcpy and memset.
+        if name == "roc_memcpy" {
+            vaddresses.insert("memcpy".to_string(), export.address());
+        } else if name == "roc_memset" {
+            vaddresses.insert("memset".to_string(), export.address());
+        }
+
+        vaddresses.insert(name.to_string(), export.address());
+    }
+
+    vaddresses
+}
+
 #[derive(Debug)]
 struct DynamicRelocationsPe {
     name_by_virtual_address: MutMap<u32, String>,
@@ -48,18 +245,10 @@ struct DynamicRelocationsPe {
     /// where the actual address of this symbol is stored
     address_and_offset: MutMap<String, (u32, u32)>,
 
-    /// Virtual offset of the first thunk
-    section_virtual_address: u32,
-
-    /// Offset in the file of the first thunk
-    section_offset_in_file: u32,
-
     /// Offset in the file of the imports directory
-    #[allow(dead_code)]
     imports_offset_in_file: u32,
 
     /// The dummy .dll is the `dummy_import_index`th import of the host .exe
-    #[allow(dead_code)]
     dummy_import_index: u32,
 }
 
@@ -92,17 +281,22 @@ impl DynamicRelocationsPe {
     /// Append metadata for the functions (e.g. mainForHost) that the host needs from the app
     fn append_roc_imports(
         &mut self,
+        sections: &SectionTable,
         import_table: &ImportTable,
         roc_dll_descriptor: &ImageImportDescriptor,
     ) -> object::read::Result<()> {
         use object::LittleEndian as LE;
 
-        // offset of first thunk from the start of the section
-        let thunk_start_offset =
-            roc_dll_descriptor.original_first_thunk.get(LE) - self.section_virtual_address;
+        // The loader stores the addresses of the imported functions in the import address table.
+        // Their names are in the lookup table, which older linkers may leave out.
+        let first_thunk = roc_dll_descriptor.first_thunk.get(LE);
+        let lookup_table = match roc_dll_descriptor.original_first_thunk.get(LE) {
+            0 => first_thunk,
+            original_first_thunk => original_first_thunk,
+        };
         let mut thunk_offset = 0;
 
-        let mut thunks = import_table.thunks(roc_dll_descriptor.original_first_thunk.get(LE))?;
+        let mut thunks = import_table.thunks(lookup_table)?;
         while let Some(thunk_data) = thunks.next::<ImageNtHeaders64>()? {
             use object::read::pe::ImageThunkData;
 
@@ -110,8 +304,11 @@ impl DynamicRelocationsPe {
             let (_, name) = import_table.hint_name(temporary_address as _)?;
             let name = String::from_utf8_lossy(name).to_string();
 
-            let offset_in_file = self.section_offset_in_file + thunk_start_offset + thunk_offset;
-            let virtual_address = roc_dll_descriptor.original_first_thunk.get(LE) + thunk_offset;
+            let virtual_address = first_thunk + thunk_offset;
+            let offset_in_file = match sections.pe_file_range_at(virtual_address) {
+                Some((offset, _)) => offset,
+                None => internal_error!("Import address table is not in the file"),
+            };
 
             self.name_by_virtual_address
                 .insert(virtual_address, name.clone());
@@ -125,7 +322,6 @@ impl DynamicRelocationsPe {
     }
 
     fn new_help(data: &[u8]) -> object::read::Result<Self> {
-        use object::pe;
         use object::read::pe::ImageNtHeaders;
         use object::LittleEndian as LE;
 
@@ -161,24 +357,26 @@ impl DynamicRelocationsPe {
         let imports_offset_in_section = import_va.wrapping_sub(section_va);
         let imports_offset_in_file = offset_in_file + imports_offset_in_section;
 
-        let (descriptor, dummy_import_index) = Self::find_roc_dummy_dll(&import_table)?.unwrap();
+        let (descriptor, dummy_import_index) = match Self::find_roc_dummy_dll(&import_table)? {
+            Some(found) => found,
+            None => internal_error!("The host does not import anything from the app"),
+        };
 
         let mut this = Self {
             name_by_virtual_address: Default::default(),
             address_and_offset: Default::default(),
-            section_virtual_address: section_va,
-            section_offset_in_file: offset_in_file,
             imports_offset_in_file,
             dummy_import_index,
         };
 
-        this.append_roc_imports(&import_table, &descriptor)?;
+        this.append_roc_imports(&sections, &import_table, &descriptor)?;
 
         Ok(this)
     }
 }
 
-#[allow(dead_code)]
+/// Remove the dummy .dll from the import directory, so the loader does not go looking for it.
+/// The descriptors that follow it (including the terminating null descriptor) move up one place.
 fn remove_dummy_dll_import_table(
     data: &mut [u8],
     imports_offset_in_file: u32,
@@ -187,9 +385,13 @@ fn remove_dummy_dll_import_table(
     const W: usize = std::mem::size_of::<ImageImportDescriptor>();
 
     let start = imports_offset_in_file as usize + W * dummy_import_index as usize;
-    for b in data[start..][..W].iter_mut() {
-        *b = 0;
+
+    let mut end = start + W;
+    while data[end..][..W].iter().any(|b| *b != 0) {
+        end += W;
     }
+
+    data.copy_within(start + W..end + W, start);
 }
 
 /// Preprocess the host's .exe to make space for extra sections
@@ -198,7 +400,6 @@ fn remove_dummy_dll_import_table(
 /// preprocessed host. That means we need to do some bookkeeping: add extra entries to the
 /// section table, update the header with the new section count, and (because we added data)
 /// update existing section headers to point to a different (shifted) location in the file
-#[allow(dead_code)]
 struct Preprocessor {
     header_offset: u64,
     additional_length: usize,
@@ -215,7 +416,6 @@ struct Preprocessor {
 impl Preprocessor {
     const SECTION_HEADER_WIDTH: usize = std::mem::size_of::<ImageSectionHeader>();
 
-    #[allow(dead_code)]
     fn preprocess(output_path: &Path, data: &[u8], extra_sections: &[[u8; 8]]) -> MmapMut {
         let this = Self::new(data, extra_sections);
         let mut result = mmap_mut(output_path, data.len() + this.additional_length);
@@ -227,7 +427,6 @@ impl Preprocessor {
     }
 
     fn new(data: &[u8], extra_sections: &[[u8; 8]]) -> Self {
-        use object::pe;
         use object::read::pe::ImageNtHeaders;
         use object::LittleEndian as LE;
 
@@ -252,6 +451,19 @@ impl Preprocessor {
         let extra_alignments = (extra_sections_width + file_alignment - 1) / file_alignment;
         let new_headers_size = old_headers_size + extra_alignments * file_alignment;
 
+        // the headers are loaded into memory just before the first section
+        let first_section_address = sections
+            .iter()
+            .map(|section| section.virtual_address.get(LE) as usize)
+            .min()
+            .unwrap_or(usize::MAX);
+        if new_headers_size > first_section_address {
+            internal_error!(
+                "The host does not have room for {} more bytes of headers",
+                new_headers_size - old_headers_size
+            );
+        }
+
         let additional_length = new_headers_size - old_headers_size;
 
         Self {
@@ -342,6 +554,17 @@ impl Preprocessor {
             .size_of_headers
             .set(LE, self.new_headers_size as u32);
 
+        let shift = (self.new_headers_size - self.old_headers_size) as u32;
+
+        // a COFF symbol table (if any) comes after the sections
+        let symbol_table = nt_headers.file_header.pointer_to_symbol_table.get(LE);
+        if symbol_table != 0 {
+            nt_headers
+                .file_header
+                .pointer_to_symbol_table
+                .set(LE, symbol_table + shift);
+        }
+
         // update the section file offsets
         //
         // Sections:
@@ -356,22 +579,74 @@ impl Preprocessor {
         //
         // The file offset of the sections has changed (we inserted some bytes) and this value must
         // now be updated to point to the correct place in the file
-        let shift = self.new_headers_size - self.old_headers_size;
-        let mut offset = self.section_table_offset as usize;
-        loop {
-            let header = load_struct_inplace_mut::<object::pe::ImageSectionHeader>(result, offset);
-
+        let section_headers = load_structs_inplace_mut::<ImageSectionHeader>(
+            result,
+            self.section_table_offset as usize,
+            self.old_section_count,
+        );
+        for header in section_headers.iter_mut() {
+            // sections of uninitialized data are not in the file
             let old = header.pointer_to_raw_data.get(LE);
-            header.pointer_to_raw_data.set(LE, old + shift as u32);
+            if old != 0 {
+                header.pointer_to_raw_data.set(LE, old + shift);
+            }
+        }
+
+        self.fix_debug_directory(result, shift);
+
+        // the certificate table is not loaded into memory, but refers to its data by file offset.
+        // Any signature in there no longer matches anyway, so we drop it.
+        let security = load_struct_inplace_mut::<ImageDataDirectory>(
+            result,
+            self.data_directory_offset(pe::IMAGE_DIRECTORY_ENTRY_SECURITY),
+        );
+        security.virtual_address.set(LE, 0);
+        security.size.set(LE, 0);
+    }
 
-            // stop when we hit the NULL section
-            if header.name == [0; 8] {
-                break;
+    /// The entries of the debug directory refer to their data by file offset, as well as by
+    /// virtual address
+    fn fix_debug_directory(&self, result: &mut MmapMut, shift: u32) {
+        use object::read::pe::ImageNtHeaders;
+        use object::LittleEndian as LE;
+
+        let (entries_offset, entry_count) = {
+            let mut offset = self.header_offset;
+            let (nt_headers, data_directories) = ImageNtHeaders64::parse(&**result, &mut offset)
+                .unwrap_or_else(|e| internal_error!("{e}"));
+            let sections = nt_headers
+                .sections(&**result, offset)
+                .unwrap_or_else(|e| internal_error!("{e}"));
+
+            match data_directories.get(pe::IMAGE_DIRECTORY_ENTRY_DEBUG) {
+                Some(dir) if dir.size.get(LE) > 0 => {
+                    // the section headers have already been updated
+                    let (offset, _) = sections
+                        .pe_file_range_at(dir.virtual_address.get(LE))
+                        .unwrap_or_else(|| internal_error!("Invalid debug directory"));
+                    let count =
+                        dir.size.get(LE) as usize / std::mem::size_of::<ImageDebugDirectory>();
+                    (offset as usize, count)
+                }
+                _ => return,
             }
+        };
 
-            offset += std::mem::size_of::<object::pe::ImageSectionHeader>();
+        let entries =
+            load_structs_inplace_mut::<ImageDebugDirectory>(result, entries_offset, entry_count);
+        for entry in entries.iter_mut() {
+            let old = entry.pointer_to_raw_data.get(LE);
+            if old != 0 {
+                entry.pointer_to_raw_data.set(LE, old + shift);
+            }
         }
     }
+
+    fn data_directory_offset(&self, index: usize) -> usize {
+        self.header_offset as usize
+            + std::mem::size_of::<ImageNtHeaders64>()
+            + index * std::mem::size_of::<ImageDataDirectory>()
+    }
 }
 
 fn mmap_mut(path: &Path, length: usize) -> MmapMut {
@@ -389,10 +664,508 @@ fn mmap_mut(path: &Path, length: usize) -> MmapMut {
     unsafe { MmapMut::map_mut(&out_file).unwrap_or_else(|e| internal_error!("{e}")) }
 }
 
+/// Lay out app sections one after the other, starting at the given file offset and virtual
+/// address (relative to the image base). Returns how much of the file and of the address space
+/// they take up.
+fn place_sections(
+    sections: &[Section],
+    file_offset: usize,
+    rva: usize,
+    section_offset_map: &mut MutMap<SectionIndex, (usize, usize)>,
+) -> (usize, usize) {
+    let mut file_size = 0;
+    let mut virtual_size = 0;
+
+    for sec in sections {
+        virtual_size = align_by_constraint(virtual_size, MIN_SECTION_ALIGNMENT);
+        section_offset_map.insert(
+            sec.index(),
+            (file_offset + virtual_size, rva + virtual_size),
+        );
+        virtual_size += sec.size() as usize;
+
+        match sec.file_range() {
+            Some((_, size)) if size == sec.size() => file_size = virtual_size,
+            // uninitialized data only takes up address space, so it has to come last
+            None => {}
+            Some(_) => {
+                internal_error!("We do not deal with non bss sections that have different on disk and in memory sizes");
+            }
+        }
+    }
+
+    (file_size, virtual_size)
+}
+
+fn write_section_header(
+    header: &mut ImageSectionHeader,
+    (file_offset, file_size): (usize, usize),
+    (rva, virtual_size): (usize, usize),
+    file_alignment: usize,
+    characteristics: u32,
+) {
+    use object::LittleEndian as LE;
+
+    let size_of_raw_data = align_by_constraint(file_size, file_alignment);
+
+    header.virtual_size.set(LE, virtual_size as u32);
+    header.virtual_address.set(LE, rva as u32);
+    header.size_of_raw_data.set(LE, size_of_raw_data as u32);
+    // sections without data in the file should not point into it
+    let pointer_to_raw_data = if size_of_raw_data == 0 {
+        0
+    } else {
+        file_offset
+    };
+    header
+        .pointer_to_raw_data
+        .set(LE, pointer_to_raw_data as u32);
+    header.characteristics.set(LE, characteristics);
+}
+
+/// Encode base relocations for the 64-bit addresses at the given virtual addresses (relative to
+/// the image base). There is a block of relocations for every 4KiB page that has any.
+fn base_relocation_blocks(rvas: &mut [u32]) -> Vec<u8> {
+    rvas.sort_unstable();
+
+    let mut blocks = Vec::new();
+    let mut i = 0;
+    while i < rvas.len() {
+        let page = rvas[i] & !0xfff;
+        let count = rvas[i..]
+            .iter()
+            .take_while(|rva| **rva & !0xfff == page)
+            .count();
+
+        // blocks must start on a 32-bit boundary, so an odd number of entries is padded with an
+        // `IMAGE_REL_BASED_ABSOLUTE` entry, which the loader skips
+        let padded_count = count + count % 2;
+
+        blocks.extend(page.to_le_bytes());
+        blocks.extend((8 + 2 * padded_count as u32).to_le_bytes());
+        for rva in &rvas[i..i + count] {
+            let entry = (pe::IMAGE_REL_BASED_DIR64 << 12) | (*rva & 0xfff) as u16;
+            blocks.extend(entry.to_le_bytes());
+        }
+        if padded_count != count {
+            blocks.extend(pe::IMAGE_REL_BASED_ABSOLUTE.to_le_bytes());
+        }
+
+        i += count;
+    }
+
+    blocks
+}
+
+/// Append the app to a preprocessed host. Its data and code go into the sections that
+/// preprocessing reserved, and the host's import address table entries for the app functions
+/// now point at them. Every absolute address gets a base relocation, so the loader can still put
+/// the executable anywhere in memory.
+pub(crate) fn surgery_pe(
+    app_filename: &str,
+    metadata_filename: &str,
+    out_filename: &str,
+    verbose: bool,
+    time: bool,
+) {
+    use object::LittleEndian as LE;
+
+    let total_start = Instant::now();
+    let loading_metadata_start = total_start;
+    let md: PeMetadata = {
+        let input = fs::File::open(metadata_filename).unwrap_or_else(|e| internal_error!("{}", e));
+        let input = BufReader::new(input);
+        match deserialize_from(input) {
+            Ok(data) => data,
+            Err(err) => {
+                internal_error!("Failed to deserialize metadata: {}", err);
+            }
+        }
+    };
+    let loading_metadata_duration = loading_metadata_start.elapsed();
+
+    let app_parsing_start = Instant::now();
+    let app_file = fs::File::open(app_filename).unwrap_or_else(|e| internal_error!("{}", e));
+    let app_mmap = unsafe { Mmap::map(&app_file).unwrap_or_else(|e| internal_error!("{}", e)) };
+    let app_data = &*app_mmap;
+    let app_obj = match object::File::parse(app_data) {
+        Ok(obj) => obj,
+        Err(err) => {
+            internal_error!("Failed to parse application file: {}", err);
+        }
+    };
+    let app_parsing_duration = app_parsing_start.elapsed();
+
+    let load_and_mmap_start = Instant::now();
+
+    let image_base = md.image_base;
+    let file_alignment = md.file_alignment as usize;
+    let section_alignment = md.section_alignment as usize;
+
+    // bss sections are like rdata sections, but they only take up space in memory, so they come
+    // after all the sections that have data in the file.
+    let rdata_sections = app_obj
+        .sections()
+        .filter(|sec| sec.name().unwrap_or_default().starts_with(".rdata"));
+    let bss_sections = app_obj
+        .sections()
+        .filter(|sec| sec.name().unwrap_or_default().starts_with(".bss"));
+    let data_sections: Vec<Section> = rdata_sections.chain(bss_sections).collect();
+
+    let text_sections: Vec<Section> = app_obj
+        .sections()
+        .filter(|sec| sec.name().unwrap_or_default().starts_with(".text"))
+        .collect();
+    if text_sections.is_empty() {
+        internal_error!("No text sections found. This application has no code.");
+    }
+
+    // The new sections come after everything that is already in the host, in the file as well as
+    // in memory.
+    let mut section_offset_map: MutMap<SectionIndex, (usize, usize)> = MutMap::default();
+
+    let rdata_offset = align_by_constraint(md.dynhost_file_size, file_alignment);
+    let rdata_rva = align_by_constraint(md.last_host_section_end as usize, section_alignment);
+    let (rdata_file_size, rdata_virtual_size) = place_sections(
+        &data_sections,
+        rdata_offset,
+        rdata_rva,
+        &mut section_offset_map,
+    );
+    // the loader expects every section to take up some memory, even if the app has no data
+    let rdata_virtual_size = rdata_virtual_size.max(1);
+
+    let text_offset = align_by_constraint(rdata_offset + rdata_file_size, file_alignment);
+    let text_rva = align_by_constraint(rdata_rva + rdata_virtual_size, section_alignment);
+    let (text_file_size, text_virtual_size) = place_sections(
+        &text_sections,
+        text_offset,
+        text_rva,
+        &mut section_offset_map,
+    );
+
+    let reloc_offset = align_by_constraint(text_offset + text_file_size, file_alignment);
+    let reloc_rva = align_by_constraint(text_rva + text_virtual_size, section_alignment);
+
+    if verbose {
+        println!();
+        println!(
+            "New Rdata Section: {:+x} (virt: {:+x})",
+            rdata_offset, rdata_rva
+        );
+        println!(
+            "New Text Section: {:+x} (virt: {:+x})",
+            text_offset, text_rva
+        );
+        println!(
+            "New Reloc Section: {:+x} (virt: {:+x})",
+            reloc_offset, reloc_rva
+        );
+    }
+
+    // Besides the host's own base relocations, we need one for every absolute address in the app
+    // and for every entry in the import address table that now points to the app. At worst, each
+    // of those needs a block of its own.
+    let absolute_relocation_count = data_sections
+        .iter()
+        .chain(text_sections.iter())
+        .flat_map(|sec| sec.relocations())
+        .filter(|(_, rel)| rel.kind() == RelocationKind::Absolute)
+        .count();
+    let (host_relocations_offset, host_relocations_size) = md.base_relocations;
+    let max_reloc_size =
+        host_relocations_size as usize + 12 * (absolute_relocation_count + md.imports.len());
+    let max_out_len = reloc_offset + align_by_constraint(max_reloc_size, file_alignment);
+
+    let exec_file = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(out_filename)
+        .unwrap_or_else(|e| internal_error!("{}", e));
+    exec_file
+        .set_len(max_out_len as u64)
+        .unwrap_or_else(|e| internal_error!("{}", e));
+
+    let mut exec_mmap =
+        unsafe { MmapMut::map_mut(&exec_file).unwrap_or_else(|e| internal_error!("{}", e)) };
+
+    let load_and_mmap_duration = load_and_mmap_start.elapsed();
+    let out_gen_start = Instant::now();
+
+    // Find where the app symbols end up.
+    let symbols = app_obj.symbols().collect::<Vec<Symbol>>();
+    let mut symbol_vaddr_map: MutMap<SymbolIndex, u64> = MutMap::default();
+    let mut app_func_vaddr_map: MutMap<&str, u64> = MutMap::default();
+    for sym in symbols.iter() {
+        if let SymbolSection::Section(index) = sym.section() {
+            if let Some((_, rva)) = section_offset_map.get(&index) {
+                let vaddr = image_base + *rva as u64 + sym.address();
+                symbol_vaddr_map.insert(sym.index(), vaddr);
+                if sym.is_global() {
+                    app_func_vaddr_map.insert(sym.name().unwrap_or_default(), vaddr);
+                }
+            }
+        }
+    }
+    if verbose {
+        println!("Data Relocation Offsets: {:+x?}", symbol_vaddr_map);
+        println!("Found App Function Symbols: {:+x?}", app_func_vaddr_map);
+    }
+
+    // Move data and deal with relocations.
+    let mut base_relocations: Vec<u32> = Vec::new();
+    for sec in data_sections.iter().chain(text_sections.iter()) {
+        let (section_offset, section_rva) = *section_offset_map.get(&sec.index()).unwrap();
+        if sec.file_range().is_some() {
+            let data = sec.data().unwrap_or_else(|err| {
+                internal_error!(
+                    "Failed to load data for section, {:+x?}: {err}",
+                    sec.name().unwrap(),
+                )
+            });
+            exec_mmap[section_offset..][..data.len()].copy_from_slice(data);
+        }
+
+        if verbose {
+            println!();
+            println!(
+                "Processing Relocations for Section: 0x{:+x?} @ {:+x} (virt: {:+x})",
+                sec, section_offset, section_rva
+            );
+        }
+
+        for (rel_offset, rel) in sec.relocations() {
+            if verbose {
+                println!("\tFound Relocation: {:+x?}", rel);
+            }
+
+            let target_vaddr = match rel.target() {
+                RelocationTarget::Symbol(index) => match symbol_vaddr_map.get(&index) {
+                    Some(vaddr) => *vaddr,
+                    None => {
+                        let name = app_obj
+                            .symbol_by_index(index)
+                            .and_then(|sym| sym.name())
+                            .unwrap_or_default();
+
+                        match md.exports.get(name) {
+                            Some(vaddr) => *vaddr,
+                            // Explicitly ignore some symbols that are currently always linked.
+                            None if ["__divti3", "__udivti3"].contains(&name) => continue,
+                            None => {
+                                internal_error!(
+                                    "Undefined Symbol in relocation, {:+x?}: {:+x?}",
+                                    rel,
+                                    app_obj.symbol_by_index(index)
+                                );
+                            }
+                        }
+                    }
+                },
+                _ => {
+                    internal_error!("Relocation target not yet support: {:+x?}", rel);
+                }
+            };
+
+            let base = section_offset + rel_offset as usize;
+            let rva = section_rva + rel_offset as usize;
+
+            // COFF relocations keep (part of) the addend in the place they apply to
+            let implicit_addend = if !rel.has_implicit_addend() {
+                0
+            } else {
+                match rel.size() {
+                    32 => i32::from_le_bytes(exec_mmap[base..][..4].try_into().unwrap()) as i64,
+                    64 => i64::from_le_bytes(exec_mmap[base..][..8].try_into().unwrap()),
+                    x => {
+                        internal_error!("Relocation size not yet supported: {}", x);
+                    }
+                }
+            };
+            let target = target_vaddr as i64 + rel.addend() + implicit_addend;
+
+            let value = match (rel.kind(), rel.size()) {
+                (RelocationKind::Absolute, 64) => {
+                    base_relocations.push(rva as u32);
+                    target
+                }
+                (RelocationKind::ImageOffset, _) => target - image_base as i64,
+                (RelocationKind::Relative, _) => target - (image_base + rva as u64) as i64,
+                (kind, size) => {
+                    internal_error!(
+                        "Relocation Kind not yet support: {:?} ({} bits)",
+                        kind,
+                        size
+                    );
+                }
+            };
+            if verbose {
+                println!(
+                    "\t\tRelocation base location: {:+x} (virt: {:+x})",
+                    base,
+                    image_base + rva as u64
+                );
+                println!("\t\tFinal relocation value: {:+x}", value);
+            }
+
+            match rel.size() {
+                32 => exec_mmap[base..][..4].copy_from_slice(&(value as i32).to_le_bytes()),
+                64 => exec_mmap[base..][..8].copy_from_slice(&value.to_le_bytes()),
+                x => {
+                    internal_error!("Relocation size not yet supported: {}", x);
+                }
+            }
+        }
+    }
+
+    // The host calls the app functions through its import address table. The loader no longer
+    // fills those entries in, so we point them at the app ourselves.
+    for import in md.imports.iter() {
+        let vaddr = match app_func_vaddr_map.get(import.name.as_str()) {
+            Some(vaddr) => *vaddr,
+            None => {
+                internal_error!("Function, {}, was not defined by the app", &import.name);
+            }
+        };
+        if verbose {
+            println!(
+                "Pointing the import of {} at {:+x} to the address: {:+x}",
+                &import.name, import.file_offset, vaddr
+            );
+        }
+
+        exec_mmap[import.file_offset as usize..][..8].copy_from_slice(&vaddr.to_le_bytes());
+        base_relocations.push(import.rva);
+    }
+
+    // The new base relocations go after those of the host, in a section of their own.
+    exec_mmap.copy_within(
+        host_relocations_offset as usize
+            ..(host_relocations_offset + host_relocations_size) as usize,
+        reloc_offset,
+    );
+    let new_relocations = base_relocation_blocks(&mut base_relocations);
+    exec_mmap[reloc_offset + host_relocations_size as usize..][..new_relocations.len()]
+        .copy_from_slice(&new_relocations);
+    let reloc_size = host_relocations_size as usize + new_relocations.len();
+
+    let section_headers = load_structs_inplace_mut::<ImageSectionHeader>(
+        &mut exec_mmap,
+        md.app_section_headers_offset,
+        APP_SECTIONS.len(),
+    );
+    write_section_header(
+        &mut section_headers[0],
+        (rdata_offset, rdata_file_size),
+        (rdata_rva, rdata_virtual_size),
+        file_alignment,
+        pe::IMAGE_SCN_CNT_INITIALIZED_DATA | pe::IMAGE_SCN_MEM_READ,
+    );
+    write_section_header(
+        &mut section_headers[1],
+        (text_offset, text_file_size),
+        (text_rva, text_virtual_size),
+        file_alignment,
+        pe::IMAGE_SCN_CNT_CODE | pe::IMAGE_SCN_MEM_EXECUTE | pe::IMAGE_SCN_MEM_READ,
+    );
+    write_section_header(
+        &mut section_headers[2],
+        (reloc_offset, reloc_size),
+        (reloc_rva, reloc_size),
+        file_alignment,
+        pe::IMAGE_SCN_CNT_INITIALIZED_DATA | pe::IMAGE_SCN_MEM_DISCARDABLE | pe::IMAGE_SCN_MEM_READ,
+    );
+
+    let nt_headers =
+        load_struct_inplace_mut::<ImageNtHeaders64>(&mut exec_mmap, md.nt_headers_offset);
+    let optional_header = &mut nt_headers.optional_header;
+    let size_of_code = optional_header.size_of_code.get(LE);
+    optional_header.size_of_code.set(
+        LE,
+        size_of_code + align_by_constraint(text_file_size, file_alignment) as u32,
+    );
+    let size_of_initialized_data = optional_header.size_of_initialized_data.get(LE);
+    optional_header.size_of_initialized_data.set(
+        LE,
+        size_of_initialized_data
+            + align_by_constraint(rdata_file_size, file_alignment) as u32
+            + align_by_constraint(reloc_size, file_alignment) as u32,
+    );
+    optional_header.size_of_image.set(
+        LE,
+        align_by_constraint(reloc_rva + reloc_size, section_alignment) as u32,
+    );
+    // the checksum is only verified for drivers and the like, but it is wrong now
+    optional_header.check_sum.set(LE, 0);
+
+    let base_relocation_directory = load_struct_inplace_mut::<ImageDataDirectory>(
+        &mut exec_mmap,
+        md.nt_headers_offset
+            + std::mem::size_of::<ImageNtHeaders64>()
+            + pe::IMAGE_DIRECTORY_ENTRY_BASERELOC * std::mem::size_of::<ImageDataDirectory>(),
+    );
+    base_relocation_directory
+        .virtual_address
+        .set(LE, reloc_rva as u32);
+    base_relocation_directory.size.set(LE, reloc_size as u32);
+
+    let out_len = reloc_offset + align_by_constraint(reloc_size, file_alignment);
+
+    let out_gen_duration = out_gen_start.elapsed();
+    let flushing_data_start = Instant::now();
+
+    exec_mmap
+        .flush()
+        .unwrap_or_else(|e| internal_error!("{}", e));
+    // Also drop files to to ensure data is fully written here.
+    drop(exec_mmap);
+
+    exec_file
+        .set_len(out_len as u64)
+        .unwrap_or_else(|e| internal_error!("{}", e));
+    drop(exec_file);
+    let flushing_data_duration = flushing_data_start.elapsed();
+
+    let total_duration = total_start.elapsed();
+
+    if verbose || time {
+        println!("\nTimings");
+        report_timing("Loading Metadata", loading_metadata_duration);
+        report_timing("Application Parsing", app_parsing_duration);
+        report_timing("Loading and mmap-ing", load_and_mmap_duration);
+        report_timing("Output Generation", out_gen_duration);
+        report_timing("Flushing Data to Disk", flushing_data_duration);
+        report_timing(
+            "Other",
+            total_duration
+                - loading_metadata_duration
+                - app_parsing_duration
+                - load_and_mmap_duration
+                - out_gen_duration
+                - flushing_data_duration,
+        );
+        report_timing("Total", total_duration);
+    }
+}
+
 #[cfg(test)]
 mod test {
     const PE_DYNHOST: &[u8] = include_bytes!("../dynhost_benchmarks_windows.exe") as &[_];
 
+    // A small host and app, built from the assembly next to them with
+    //
+    //     llvm-mc -triple x86_64-pc-windows-msvc -filetype=obj
+    //
+    // The import libraries come from .def files with just the functions we need, through
+    // `llvm-dlltool -m i386:x86-64 -d <lib>.def -l <lib>.lib`, after which the host is linked as
+    //
+    //     lld-link /entry:main /subsystem:console /out:dynhost_x86_64_pe.exe \
+    //         host.obj kernel32.lib roc.lib ucrtbase.lib
+    //
+    // so the dummy `roc-cheaty-lib.dll` ends up between KERNEL32.dll and ucrtbase.dll.
+    const PE_SMALL_DYNHOST: &[u8] = include_bytes!("../dynhost_x86_64_pe.exe") as &[_];
+    const PE_SMALL_APP: &[u8] = include_bytes!("../app_x86_64_pe.obj") as &[_];
+
     use std::ops::Deref;
 
     use object::read::pe::PeFile64;
@@ -414,37 +1187,37 @@ mod test {
         //   1 .rdata        000369d4  000000014007d000  000000014007d000  0007c000  2**4
         //
 
-        // we parse that into this structure. Note how the VMA and File off correspond to the
-        // values that we find. Also note how the delta between virtual address and offset is the
-        // same for values of `address_and_offset` and the section values that we find
+        // we parse that into this structure. The addresses are those of the slots in the import
+        // address table, and the delta between virtual address and file offset is the one of the
+        // .rdata section
         //
         // DynamicRelocationsPe {
         //     name_by_virtual_address: {
-        //         0xaf4e0: "roc__mainForHost_size",
-        //         0xaf4c8: "roc__mainForHost_1__Fx_caller",
-        //         0xaf4d0: "roc__mainForHost_1__Fx_result_size",
-        //         0xaf4d8: "roc__mainForHost_1_exposed_generic",
+        //         0xaf760: "roc__mainForHost_1_exposed_generic",
+        //         0xaf750: "roc__mainForHost_1__Fx_caller",
+        //         0xaf758: "roc__mainForHost_1__Fx_result_size",
+        //         0xaf768: "roc__mainForHost_size",
         //     },
         //     address_and_offset: {
         //         "roc__mainForHost_1__Fx_result_size": (
-        //             0xaf4d0,
-        //             0xae4d0,
+        //             0xaf758,
+        //             0xae758,
         //         ),
         //         "roc__mainForHost_1__Fx_caller": (
-        //             0xaf4c8,
-        //             0xae4c8,
+        //             0xaf750,
+        //             0xae750,
         //         ),
         //         "roc__mainForHost_1_exposed_generic": (
-        //             0xaf4d8,
-        //             0xae4d8,
+        //             0xaf760,
+        //             0xae760,
         //         ),
         //         "roc__mainForHost_size": (
-        //             0xaf4e0,
-        //             0xae4e0,
+        //             0xaf768,
+        //             0xae768,
         //         ),
         //     },
-        //     section_virtual_address: 0x7d000,
-        //     section_offset_in_file: 0x7c000,
+        //     imports_offset_in_file: 0xae200,
+        //     dummy_import_index: 0x3,
         // }
 
         let dynamic_relocations = DynamicRelocationsPe::new(PE_DYNHOST);
@@ -621,4 +1394,220 @@ mod test {
 
         assert_eq!(new_sections, names.as_slice());
     }
+
+    fn imports_by_library(data: &[u8]) -> Vec<(String, String)> {
+        let object = object::File::parse(data).unwrap();
+
+        let mut imports: Vec<_> = object
+            .imports()
+            .unwrap()
+            .iter()
+            .map(|import| {
+                (
+                    String::from_utf8_lossy(import.library()).to_string(),
+                    String::from_utf8_lossy(import.name()).to_string(),
+                )
+            })
+            .collect();
+        imports.sort_unstable();
+
+        imports
+    }
+
+    #[test]
+    fn remove_dummy_dll_import_keeps_others() {
+        let dynamic_relocations = DynamicRelocationsPe::new(PE_SMALL_DYNHOST);
+        let mut data = PE_SMALL_DYNHOST.to_vec();
+
+        // the dummy library is not the last one, so the ones after it have to move up
+        assert_eq!(dynamic_relocations.dummy_import_index, 1);
+
+        remove_dummy_dll_import_table(
+            &mut data,
+            dynamic_relocations.imports_offset_in_file,
+            dynamic_relocations.dummy_import_index,
+        );
+
+        assert_eq!(
+            imports_by_library(&data),
+            [
+                ("KERNEL32.dll".to_string(), "ExitProcess".to_string()),
+                ("ucrtbase.dll".to_string(), "free".to_string()),
+                ("ucrtbase.dll".to_string(), "malloc".to_string()),
+            ]
+        );
+    }
+
+    fn base_relocations(object: &PeFile64) -> Vec<u32> {
+        let mut blocks = object
+            .data_directories()
+            .relocation_blocks(object.data(), &object.section_table())
+            .unwrap()
+            .unwrap();
+
+        let mut relocations = Vec::new();
+        while let Some(block) = blocks.next().unwrap() {
+            for relocation in block {
+                assert_eq!(relocation.typ, pe::IMAGE_REL_BASED_DIR64);
+                relocations.push(relocation.virtual_address);
+            }
+        }
+
+        relocations
+    }
+
+    #[test]
+    fn preprocess_and_surgery_pe() {
+        use object::read::pe::{ImageNtHeaders, ImageOptionalHeader};
+        use object::LittleEndian as LE;
+
+        let dir = tempfile::tempdir().unwrap();
+        let dynhost = dir.path().join("dynhost.exe");
+        let metadata = dir.path().join("metadata");
+        let preprocessed_host = dir.path().join("preprocessedhost");
+        let app = dir.path().join("app.obj");
+        let output = dir.path().join("output.exe");
+
+        std::fs::write(&dynhost, PE_SMALL_DYNHOST).unwrap();
+        std::fs::write(&app, PE_SMALL_APP).unwrap();
+
+        preprocess_windows(
+            dynhost.to_str().unwrap(),
+            metadata.to_str().unwrap(),
+            preprocessed_host.to_str().unwrap(),
+            Path::new("libapp.obj"),
+            false,
+            false,
+        )
+        .unwrap();
+
+        std::fs::copy(&preprocessed_host, &output).unwrap();
+        surgery_pe(
+            app.to_str().unwrap(),
+            metadata.to_str().unwrap(),
+            output.to_str().unwrap(),
+            false,
+            false,
+        );
+
+        let data = std::fs::read(&output).unwrap();
+        let object = PeFile64::parse(data.as_slice()).unwrap();
+        let image_base = object.relative_address_base();
+        let optional_header = object.nt_headers().optional_header();
+        let section_alignment = optional_header.section_alignment();
+        let file_alignment = optional_header.file_alignment();
+
+        // The app sections come after those of the host, in memory as well as in the file.
+        let sections: Vec<_> = object.section_table().iter().collect();
+        let names: Vec<_> = sections.iter().map(|section| section.name).collect();
+        assert_eq!(names[names.len() - APP_SECTIONS.len()..], APP_SECTIONS);
+
+        let mut next_address = 0;
+        let mut next_offset = 0;
+        for section in sections.iter() {
+            let address = section.virtual_address.get(LE);
+            assert_eq!(address % section_alignment, 0);
+            assert!(address >= next_address);
+            next_address = address + section.virtual_size.get(LE);
+
+            let offset = section.pointer_to_raw_data.get(LE);
+            let size = section.size_of_raw_data.get(LE);
+            assert_eq!(offset % file_alignment, 0);
+            assert_eq!(size % file_alignment, 0);
+            if size > 0 {
+                assert!(offset >= next_offset);
+                next_offset = offset + size;
+            }
+        }
+        assert_eq!(
+            optional_header.size_of_image(),
+            align_by_constraint(next_address as usize, section_alignment as usize) as u32
+        );
+        assert_eq!(data.len(), next_offset as usize);
+
+        // The dummy library is gone, but the real ones remain.
+        assert_eq!(
+            imports_by_library(&data),
+            [
+                ("KERNEL32.dll".to_string(), "ExitProcess".to_string()),
+                ("ucrtbase.dll".to_string(), "free".to_string()),
+                ("ucrtbase.dll".to_string(), "malloc".to_string()),
+            ]
+        );
+
+        // The host still provides its roc_* functions.
+        let exports: Vec<_> = object
+            .exports()
+            .unwrap()
+            .iter()
+            .map(|export| {
+                (
+                    String::from_utf8_lossy(export.name()).to_string(),
+                    export.address(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            exports,
+            [
+                ("roc_alloc".to_string(), image_base + 0x1025),
+                ("roc_dealloc".to_string(), image_base + 0x102b),
+            ]
+        );
+
+        let read_u32 = |address: u64| {
+            let (offset, _) = object
+                .section_table()
+                .pe_file_range_at((address - image_base) as u32)
+                .unwrap();
+            u32::from_le_bytes(data[offset as usize..][..4].try_into().unwrap())
+        };
+        let read_u64 =
+            |address: u64| read_u32(address) as u64 | (read_u32(address + 4) as u64) << 32;
+
+        let text = object.section_by_name(".text").unwrap();
+        let rdata = object.section_by_name(".rdata").unwrap();
+        let app_text = sections[sections.len() - 2].virtual_address.get(LE) as u64 + image_base;
+        let app_rdata = sections[sections.len() - 3].virtual_address.get(LE) as u64 + image_base;
+        assert_ne!(app_text, text.address());
+        assert_ne!(app_rdata, rdata.address());
+
+        let exposed_generic = app_text;
+        let size = app_text + 0x29;
+        let greeting = app_rdata;
+        let table = app_rdata + 0x10;
+
+        // The host calls the app through its import address table.
+        assert_eq!(read_u64(image_base + 0x2110), exposed_generic);
+        assert_eq!(read_u64(image_base + 0x2118), size);
+
+        // call roc_alloc
+        let rel = read_u32(app_text + 0xf) as i32 as i64;
+        assert_eq!((app_text + 0x13) as i64 + rel, image_base as i64 + 0x1025);
+        // lea greeting(%rip), %rcx
+        let rel = read_u32(app_text + 0x16) as i32 as i64;
+        assert_eq!((app_text + 0x1a) as i64 + rel, greeting as i64);
+        // mov table(%rip), %rcx
+        let rel = read_u32(app_text + 0x20) as i32 as i64;
+        assert_eq!((app_text + 0x24) as i64 + rel, table as i64);
+
+        assert_eq!(read_u64(table), size);
+        assert_eq!(read_u64(table + 8), greeting + 7);
+        assert_eq!(read_u32(table + 16) as u64, exposed_generic - image_base);
+
+        // Every absolute address can be fixed up when the image is loaded elsewhere.
+        let relocations = base_relocations(&object);
+        let rva = |address: u64| (address - image_base) as u32;
+        for address in [
+            image_base + 0x3000,
+            image_base + 0x3008,
+            image_base + 0x2110,
+            image_base + 0x2118,
+            table,
+            table + 8,
+        ] {
+            assert!(relocations.contains(&rva(address)), "{:x}", address);
+        }
+        assert_eq!(relocations.len(), 6);
+    }
 }
@@ -11,6 +11,7 @@ use rustyline::validate::{self, ValidationContext, ValidationResult, Validator};
 use rustyline_derive::{Completer, Helper, Hinter};
 use std::borrow::Cow;
 use std::io;
+use std::path::Path;
 use target_lexicon::Triple;
 
 use roc_build::link::llvm_module_to_dylib;
@@ -20,9 +21,8 @@ use roc_gen_llvm::{run_jit_function, run_jit_function_dynamic_type};
 use roc_load::{EntryPoint, MonomorphizedModule};
 use roc_mono::ir::OptLevel;
 use roc_parse::ast::Expr;
-use roc_parse::parser::{EExpr, ELambda, SyntaxError};
 use roc_repl_eval::eval::jit_to_ast;
-use roc_repl_eval::gen::{compile_to_mono, format_answer, ReplOutput};
+use roc_repl_eval::gen::{format_answer, is_incomplete, Compiled, ReplOutput, ReplState};
 use roc_repl_eval::{ReplApp, ReplAppMemory};
 use roc_reporting::report::DEFAULT_PALETTE;
 use roc_std::RocStr;
@@ -48,17 +48,25 @@ pub const INSTRUCTIONS: &str = "Enter an expression, or :help, or :q to quit.\n"
 pub const PROMPT: &str = concatcp!("\n", BLUE, "»", END_COL, " ");
 pub const CONT_PROMPT: &str = concatcp!(BLUE, "…", END_COL, " ");
 
+const HELP: &str = "\
+Enter an expression to evaluate it, or a def like `x = 5` followed by a blank line
+to use `x` in later inputs.
+
+    :type <expr>            Print the type of an expression without evaluating it
+    :load <path/Module.roc> Bring the values an interface module exposes into scope
+    :browse <Module>        List the values a loaded module exposes, with their types
+    :reset                  Forget all defs and loaded modules
+    :exit, :quit, :q        Exit the REPL";
+
 #[derive(Completer, Helper, Hinter)]
 struct ReplHelper {
     validator: InputValidator,
-    pending_src: String,
 }
 
 impl ReplHelper {
     pub(crate) fn new() -> ReplHelper {
         ReplHelper {
             validator: InputValidator::new(),
-            pending_src: String::new(),
         }
     }
 }
@@ -106,19 +114,16 @@ impl Validator for InputValidator {
     fn validate(&self, ctx: &mut ValidationContext) -> rustyline::Result<ValidationResult> {
         if ctx.input().is_empty() {
             Ok(ValidationResult::Incomplete)
+        } else if ctx.input().ends_with('\n') {
+            // A blank line ends the input. That's how top-level defs get submitted without a
+            // final expression, and it also means you can't get stuck on unfinished input.
+            Ok(ValidationResult::Valid(None))
+        } else if is_incomplete(ctx.input()) {
+            // The parser ran out of input, so keep reading lines
+            Ok(ValidationResult::Incomplete)
         } else {
-            let arena = bumpalo::Bump::new();
-            let state = roc_parse::state::State::new(ctx.input().trim().as_bytes());
-
-            match roc_parse::expr::parse_loc_expr(0, &arena, state) {
-                // Special case some syntax errors to allow for multi-line inputs
-                Err((_, EExpr::DefMissingFinalExpr(_), _))
-                | Err((_, EExpr::DefMissingFinalExpr2(_, _), _))
-                | Err((_, EExpr::Lambda(ELambda::Body(_, _), _), _)) => {
-                    Ok(ValidationResult::Incomplete)
-                }
-                _ => Ok(ValidationResult::Valid(None)),
-            }
+            // Either it parses, or there is a syntax error that more input won't fix
+            Ok(ValidationResult::Valid(None))
         }
     }
 }
@@ -254,6 +259,7 @@ pub fn mono_module_to_dylib<'a>(
         mode: LlvmBackendMode::GenTest, // so roc_panic is generated
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
+        debug_sources: Default::default(),
     };
 
     // Add roc_alloc, roc_realloc, and roc_dealloc, since the repl has no
@@ -308,20 +314,13 @@ pub fn mono_module_to_dylib<'a>(
 }
 
 fn gen_and_eval_llvm<'a>(
-    src: &str,
+    arena: &'a Bump,
+    mut loaded: MonomorphizedModule<'a>,
     target: Triple,
     opt_level: OptLevel,
-) -> Result<ReplOutput, SyntaxError<'a>> {
-    let arena = Bump::new();
+) -> ReplOutput {
     let target_info = TargetInfo::from(&target);
 
-    let mut loaded = match compile_to_mono(&arena, src, target_info, DEFAULT_PALETTE) {
-        Ok(x) => x,
-        Err(prob_strings) => {
-            return Ok(ReplOutput::Problems(prob_strings));
-        }
-    };
-
     debug_assert_eq!(loaded.exposed_to_host.values.len(), 1);
     let (main_fn_symbol, main_fn_var) = loaded.exposed_to_host.values.iter().next().unwrap();
     let main_fn_symbol = *main_fn_symbol;
@@ -340,22 +339,22 @@ fn gen_and_eval_llvm<'a>(
     let (_, main_fn_layout) = match loaded.procedures.keys().find(|(s, _)| *s == main_fn_symbol) {
         Some(layout) => *layout,
         None => {
-            return Ok(ReplOutput::NoProblems {
+            return ReplOutput::NoProblems {
                 expr: "<function>".to_string(),
                 expr_type: expr_type_str,
-            });
+            };
         }
     };
 
     let interns = loaded.interns.clone();
 
     let (lib, main_fn_name, subs, layout_interner) =
-        mono_module_to_dylib(&arena, target, loaded, opt_level).expect("we produce a valid Dylib");
+        mono_module_to_dylib(arena, target, loaded, opt_level).expect("we produce a valid Dylib");
 
     let mut app = CliApp { lib };
 
     let res_answer = jit_to_ast(
-        &arena,
+        arena,
         &mut app,
         main_fn_name,
         main_fn_layout,
@@ -366,11 +365,10 @@ fn gen_and_eval_llvm<'a>(
         target_info,
     );
 
-    let formatted = format_answer(&arena, res_answer, expr_type_str);
-    Ok(formatted)
+    format_answer(arena, res_answer, expr_type_str)
 }
 
-fn eval_and_format<'a>(src: &str) -> Result<String, SyntaxError<'a>> {
+fn eval_and_format(state: &mut ReplState, src: &str) -> String {
     let format_output = |output| match output {
         ReplOutput::NoProblems { expr, expr_type } => {
             format!("\n{} {}:{} {}", expr, PINK, END_COL, expr_type)
@@ -378,11 +376,94 @@ fn eval_and_format<'a>(src: &str) -> Result<String, SyntaxError<'a>> {
         ReplOutput::Problems(lines) => format!("\n{}\n", lines.join("\n\n")),
     };
 
-    gen_and_eval_llvm(src, Triple::host(), OptLevel::Normal).map(format_output)
+    let arena = Bump::new();
+    let target = Triple::host();
+    let target_info = TargetInfo::from(&target);
+
+    let (compiled, warnings) = match state.compile(&arena, src, target_info, DEFAULT_PALETTE) {
+        Ok(success) => success,
+        Err(prob_strings) => return format_output(ReplOutput::Problems(prob_strings)),
+    };
+
+    // Warnings don't stop the input from running, so they come before its result.
+    let mut output = if warnings.is_empty() {
+        String::new()
+    } else {
+        format_output(ReplOutput::Problems(warnings))
+    };
+
+    match compiled {
+        Compiled::Expr(loaded) => {
+            output.push_str(&format_output(gen_and_eval_llvm(
+                &arena,
+                loaded,
+                target,
+                OptLevel::Normal,
+            )));
+        }
+        Compiled::Defs(names_and_types) => {
+            for (name, type_str) in names_and_types.iter() {
+                output.push_str(&format!("\n{} {}:{} {}", name, PINK, END_COL, type_str));
+            }
+        }
+    }
+
+    output
 }
 
-fn report_parse_error(fail: SyntaxError) {
-    println!("TODO Gracefully report parse error in repl: {:?}", fail);
+fn eval_command(state: &mut ReplState, line: &str) -> String {
+    let (command, arg) = match line.split_once(char::is_whitespace) {
+        Some((command, arg)) => (command, arg.trim()),
+        None => (line, ""),
+    };
+
+    let arena = Bump::new();
+    let target_info = TargetInfo::from(&Triple::host());
+    let format_problems = |lines: Vec<String>| format!("\n{}\n", lines.join("\n\n"));
+
+    match (command, arg) {
+        (":type" | ":load" | ":browse", "") => {
+            format!(
+                "\nWhat should {} be used on? Use :help to see how.",
+                command
+            )
+        }
+        (":type", src) => match state.type_of(&arena, src, target_info, DEFAULT_PALETTE) {
+            Ok(type_str) => format!("\n{} {}:{} {}", src, PINK, END_COL, type_str),
+            Err(lines) => format_problems(lines),
+        },
+        (":load", path) => {
+            match state.load(&arena, Path::new(path), target_info, DEFAULT_PALETTE) {
+                Ok((module_name, exposed)) if exposed.is_empty() => {
+                    format!("\nLoaded {}, which exposes no values.", module_name)
+                }
+                Ok((module_name, exposed)) => format!(
+                    "\nLoaded {}, which exposes {}.",
+                    module_name,
+                    exposed.join(", ")
+                ),
+                Err(lines) => format_problems(lines),
+            }
+        }
+        (":browse", module_name) => {
+            match state.browse(&arena, module_name, target_info, DEFAULT_PALETTE) {
+                Ok(names_and_types) => names_and_types
+                    .iter()
+                    .map(|(name, type_str)| format!("\n{} {}:{} {}", name, PINK, END_COL, type_str))
+                    .collect(),
+                Err(lines) => format_problems(lines),
+            }
+        }
+        (":reset", _) => {
+            state.reset();
+
+            "\nForgot all defs and loaded modules.".to_string()
+        }
+        _ => format!(
+            "\nI don't know the command {}. Use :help to see the commands.",
+            command
+        ),
+    }
 }
 
 pub fn main() -> io::Result<()> {
@@ -394,7 +475,7 @@ pub fn main() -> io::Result<()> {
     // <RUN WITH:> RUST_LOG=rustyline=debug cargo run repl 2> debug.log
     print!("{}{}", WELCOME_MESSAGE, INSTRUCTIONS);
 
-    let mut prev_line_blank = false;
+    let mut state = ReplState::new();
     let mut editor = Editor::<ReplHelper>::new();
     let repl_helper = ReplHelper::new();
     editor.set_helper(Some(repl_helper));
@@ -407,65 +488,21 @@ pub fn main() -> io::Result<()> {
                 let trim_line = line.trim();
                 editor.add_history_entry(trim_line);
 
-                let pending_src = &mut editor
-                    .helper_mut()
-                    .expect("Editor helper was not set")
-                    .pending_src;
-
                 match trim_line.to_lowercase().as_str() {
                     "" => {
-                        if pending_src.is_empty() {
-                            print!("\n{}", INSTRUCTIONS);
-                        } else if prev_line_blank {
-                            // After two blank lines in a row, give up and try parsing it
-                            // even though it's going to fail. This way you don't get stuck.
-                            match eval_and_format(pending_src.as_str()) {
-                                Ok(output) => {
-                                    println!("{}", output);
-                                }
-                                Err(fail) => {
-                                    report_parse_error(fail);
-                                }
-                            }
-
-                            pending_src.clear();
-                        } else {
-                            pending_src.push('\n');
-
-                            prev_line_blank = true;
-                            continue; // Skip the part where we reset prev_line_blank to false
-                        }
+                        print!("\n{}", INSTRUCTIONS);
                     }
                     ":help" => {
-                        println!("Use :exit or :quit or :q to exit.");
+                        println!("{}", HELP);
                     }
                     ":exit" | ":quit" | ":q" => {
                         break;
                     }
+                    _ if trim_line.starts_with(':') => {
+                        println!("{}", eval_command(&mut state, trim_line));
+                    }
                     _ => {
-                        let result = if pending_src.is_empty() {
-                            eval_and_format(trim_line)
-                        } else {
-                            pending_src.push('\n');
-                            pending_src.push_str(trim_line);
-
-                            eval_and_format(pending_src.as_str())
-                        };
-
-                        match result {
-                            Ok(output) => {
-                                println!("{}", output);
-                                pending_src.clear();
-                            }
-                            //                            Err(Fail {
-                            //                                reason: FailReason::Eof(_),
-                            //                                ..
-                            //                            }) => {}
-                            Err(fail) => {
-                                report_parse_error(fail);
-                                pending_src.clear();
-                            }
-                        }
+                        println!("{}", eval_and_format(&mut state, trim_line));
                     }
                 }
             }
@@ -474,18 +511,6 @@ pub fn main() -> io::Result<()> {
                 break;
             }
             Err(ReadlineError::Eof) => {
-                // If we hit an eof, and we're allowed to keep going,
-                // append the str to the src we're building up and continue.
-                // (We only need to append it here if it was empty before;
-                // otherwise, we already appended it before calling eval_and_format.)
-                let pending_src = &mut editor
-                    .helper_mut()
-                    .expect("Editor helper was not set")
-                    .pending_src;
-
-                if pending_src.is_empty() {
-                    pending_src.push_str("");
-                }
                 break;
             }
             Err(err) => {
@@ -493,8 +518,6 @@ pub fn main() -> io::Result<()> {
                 break;
             }
         }
-
-        prev_line_blank = false;
     }
 
     Ok(())
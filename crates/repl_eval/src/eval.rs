@@ -10,14 +10,16 @@ use roc_module::ident::TagName;
 use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_mono::ir::ProcLayout;
 use roc_mono::layout::{
-    self, union_sorted_tags_pub, Builtin, Layout, LayoutCache, LayoutInterner, UnionLayout,
-    UnionVariant, WrappedVariant,
+    self, cmp_fields, union_sorted_tags_pub, Builtin, Layout, LayoutCache, LayoutInterner,
+    UnionLayout, UnionVariant, WrappedVariant,
 };
 use roc_parse::ast::{AssignedField, Collection, Expr, StrLiteral};
 use roc_region::all::{Loc, Region};
 use roc_std::RocDec;
 use roc_target::TargetInfo;
-use roc_types::subs::{Content, FlatType, GetSubsSlice, RecordFields, Subs, UnionTags, Variable};
+use roc_types::subs::{
+    Content, FlatType, GetSubsSlice, RecordFields, Subs, TupleElems, UnionTags, Variable,
+};
 
 use crate::{ReplApp, ReplAppMemory};
 
@@ -79,12 +81,14 @@ pub fn jit_to_ast<'a, A: ReplApp<'a>>(
 enum NewtypeKind {
     Tag(TagName),
     RecordField(String),
+    TupleElem,
     Opaque(Symbol),
 }
 
 /// Unrolls types that are newtypes. These include
 ///   - Singleton tags with one type argument (e.g. `Container Str`)
 ///   - Records with exactly one field (e.g. `{ number: Nat }`)
+///   - Tuples with exactly one element
 ///
 /// This is important in synchronizing `Content`s with `Layout`s, since `Layout`s will
 /// always unwrap newtypes and use the content of the underlying type.
@@ -124,6 +128,14 @@ fn unroll_newtypes_and_aliases<'a, 'env>(
                 newtype_containers.push(NewtypeKind::RecordField(label.to_string()));
                 content = env.subs.get_content_without_compacting(field.into_inner());
             }
+            Content::Structure(FlatType::Tuple(elems, _)) if elems.len() == 1 => {
+                let (_, elem_var) = elems
+                    .sorted_iterator(env.subs, Variable::EMPTY_TUPLE)
+                    .next()
+                    .unwrap();
+                newtype_containers.push(NewtypeKind::TupleElem);
+                content = env.subs.get_content_without_compacting(elem_var);
+            }
             Content::Alias(name, _, real_var, kind) => {
                 // We need to pass through aliases too, because their underlying types may have
                 // unrolled newtypes. For example,
@@ -169,6 +181,10 @@ fn apply_newtypes<'a>(
                 let field = Loc::at_zero(AssignedField::RequiredValue(label, &[], field_val));
                 expr = Expr::Record(Collection::with_items(&*arena.alloc([field])))
             }
+            NewtypeKind::TupleElem => {
+                let elem_val = &*arena.alloc(Loc::at_zero(expr));
+                expr = Expr::Tuple(Collection::with_items(arena.alloc([elem_val])))
+            }
             NewtypeKind::Opaque(name) => {
                 let opaque_name = arena.alloc(format!("@{}", name.as_str(env.interns)));
                 let opaque_ref = &*arena.alloc(Loc::at_zero(Expr::OpaqueRef(opaque_name)));
@@ -376,6 +392,9 @@ fn jit_to_ast_help<'a, A: ReplApp<'a>>(
                 Content::Structure(FlatType::EmptyRecord) => {
                     Ok(struct_to_ast(env, mem, addr, RecordFields::empty()))
                 }
+                Content::Structure(FlatType::Tuple(elems, _)) => {
+                    Ok(tuple_to_ast(env, mem, addr, *elems))
+                }
                 Content::Structure(FlatType::TagUnion(tags, _)) => {
                     debug_assert_eq!(tags.len(), 1);
 
@@ -578,6 +597,7 @@ fn addr_to_ast<'a, M: ReplAppMemory>(
             Content::Structure(FlatType::EmptyRecord) => {
                 struct_to_ast(env, mem, addr, RecordFields::empty())
             }
+            Content::Structure(FlatType::Tuple(elems, _)) => tuple_to_ast(env, mem, addr, *elems),
             other => {
                 unreachable!(
                     "Something had a Struct layout, but instead of a Record type, it had: {:?}",
@@ -1058,6 +1078,67 @@ fn struct_to_ast<'a, 'env, M: ReplAppMemory>(
     }
 }
 
+fn tuple_to_ast<'a, 'env, M: ReplAppMemory>(
+    env: &mut Env<'a, 'env>,
+    mem: &'a M,
+    addr: usize,
+    tuple_elems: TupleElems,
+) -> Expr<'a> {
+    let arena = env.arena;
+    let subs = env.subs;
+
+    let mut elems = std::vec::Vec::with_capacity(tuple_elems.len());
+
+    for (index, elem_var) in tuple_elems.sorted_iterator(subs, Variable::EMPTY_TUPLE) {
+        let elem_layout = env.layout_cache.from_var(arena, elem_var, subs).unwrap();
+
+        elems.push((index, elem_var, elem_layout));
+    }
+
+    // The elements are stored sorted by descending alignment, then by index; find each one's
+    // address in that order, but render them in the order of the type.
+    let mut in_memory = elems.clone();
+    in_memory.sort_by(|(index1, _, layout1), (index2, _, layout2)| {
+        cmp_fields(
+            &env.layout_cache.interner,
+            index1,
+            layout1,
+            index2,
+            layout2,
+            env.target_info,
+        )
+    });
+
+    let mut elem_addrs = MutMap::default();
+    let mut elem_addr = addr;
+
+    for (index, _, elem_layout) in in_memory {
+        elem_addrs.insert(index, elem_addr);
+
+        // Advance the element pointer to the next element.
+        elem_addr += elem_layout.stack_size(&env.layout_cache.interner, env.target_info) as usize;
+    }
+
+    let mut output = Vec::with_capacity_in(elems.len(), arena);
+
+    for (index, elem_var, elem_layout) in elems {
+        let content = subs.get_content_without_compacting(elem_var);
+
+        let loc_expr = &*arena.alloc(Loc::at_zero(addr_to_ast(
+            env,
+            mem,
+            elem_addrs[&index],
+            &elem_layout,
+            WhenRecursive::Unreachable,
+            content,
+        )));
+
+        output.push(loc_expr);
+    }
+
+    Expr::Tuple(Collection::with_items(output.into_bump_slice()))
+}
+
 fn unpack_single_element_tag_union(subs: &Subs, tags: UnionTags) -> (&TagName, &[Variable]) {
     let (tag_name_index, payload_vars_index) = tags.iter_all().next().unwrap();
 
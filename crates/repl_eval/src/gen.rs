@@ -1,15 +1,27 @@
 use bumpalo::Bump;
 use roc_load::{ExecutionMode, LoadConfig, Threading};
-use roc_reporting::report::Palette;
-use std::path::PathBuf;
+use roc_reporting::report::{Palette, Severity};
+use std::path::{Path, PathBuf};
 
+use roc_can::expr::DeclarationTag;
+use roc_collections::MutMap;
 use roc_fmt::annotation::Formattable;
 use roc_fmt::annotation::{Newlines, Parens};
-use roc_load::{LoadingProblem, MonomorphizedModule};
-use roc_parse::ast::Expr;
-use roc_region::all::LineInfo;
-use roc_reporting::report::{can_problem, type_problem, RocDocAllocator};
+use roc_load::docs::{DocEntry, RecordField, TypeAnnotation};
+use roc_load::{LoadedModule, LoadingProblem, MonomorphizedModule};
+use roc_module::ident::ModuleName;
+use roc_module::symbol::{Interns, ModuleId};
+use roc_parse::ast::{Expr, ExtractSpaces, Module, Pattern, TypeDef, ValueDef};
+use roc_parse::expr::test_parse_expr;
+use roc_parse::module::module_defs;
+use roc_parse::parser::{EExpr, Parser, SyntaxError};
+use roc_parse::state::State;
+use roc_problem::can::Problem;
+use roc_region::all::{LineInfo, Position};
+use roc_reporting::report::{can_problem, parse_problem, type_problem, RocDocAllocator};
+use roc_solve_problem::TypeError;
 use roc_target::TargetInfo;
+use roc_types::pretty_print::{name_and_print_var, DebugPrint};
 
 use crate::eval::ToAstProblem;
 
@@ -42,29 +54,523 @@ pub fn format_answer(
     }
 }
 
-pub fn compile_to_mono<'a>(
-    arena: &'a Bump,
-    src: &str,
-    target_info: TargetInfo,
-    palette: Palette,
-) -> Result<MonomorphizedModule<'a>, Vec<String>> {
-    let filename = PathBuf::from("");
-    let src_dir = PathBuf::from("fake/test/path");
+/// What an input turned into
+#[allow(clippy::large_enum_variant)]
+pub enum Compiled<'a> {
+    /// The input was an expression. This module provides its value as `replOutput`.
+    Expr(MonomorphizedModule<'a>),
+    /// The input was a top-level def or annotation, which later inputs can now refer to.
+    /// These are the names it defined, with their types.
+    Defs(Vec<(String, String)>),
+}
+
+/// The top-level defs and annotations entered so far, and the modules loaded with `:load`.
+/// Every input is compiled in a module that contains all of them.
+#[derive(Debug, Default, Clone)]
+pub struct ReplState {
+    past_defs: Vec<PastDef>,
+    loaded_modules: Vec<LoadedInterface>,
+    /// The directory the loaded modules are in, which is where imports are looked up
+    src_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone)]
+struct PastDef {
+    /// The names this def introduces: `x` for `x = 5`, and `a` and `b` for `{ a, b } = r`
+    names: Vec<String>,
+    kind: DefKind,
+    src: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DefKind {
+    /// Only annotations, like `x : U64`, waiting for their bodies
+    Annotation,
+    /// A single def without an annotation, like `x = 5`
+    Body,
+    Other,
+}
+
+#[derive(Debug, Clone)]
+struct LoadedInterface {
+    name: String,
+    path: PathBuf,
+    /// The values it exposes, which are imported unqualified
+    exposed: Vec<String>,
+}
+
+impl ReplState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget all defs and loaded modules
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Along with what the input turned into, this returns the warnings to show before it.
+    pub fn compile<'a>(
+        &mut self,
+        arena: &'a Bump,
+        src: &str,
+        target_info: TargetInfo,
+        palette: Palette,
+    ) -> Result<(Compiled<'a>, Vec<String>), Vec<String>> {
+        if let Some(report) = parse_problem_report(src, &palette) {
+            return Err(vec![report]);
+        }
+
+        match parse_defs(src) {
+            Some(def) => self
+                .add_def(arena, def, target_info, palette)
+                .map(|(defs, warnings)| (Compiled::Defs(defs), warnings)),
+            None => self
+                .compile_expr(arena, src, target_info, palette)
+                .map(|(module, warnings)| (Compiled::Expr(module), warnings)),
+        }
+    }
+
+    /// The type of an expression, without running it
+    pub fn type_of(
+        &self,
+        arena: &Bump,
+        src: &str,
+        target_info: TargetInfo,
+        palette: Palette,
+    ) -> Result<String, Vec<String>> {
+        if let Some(report) = parse_problem_report(src, &palette) {
+            return Err(vec![report]);
+        }
+
+        let module_src = arena.alloc(self.module_src(src));
+        let (mut loaded, _) = self.typecheck(arena, module_src, target_info, &palette)?;
+
+        let var = *loaded.exposed_to_host.values().next().unwrap();
+
+        Ok(name_and_print_var(
+            var,
+            loaded.solved.inner_mut(),
+            loaded.module_id,
+            &loaded.interns,
+            DebugPrint::NOTHING,
+        ))
+    }
+
+    /// Import the values an interface module exposes into the scope of later inputs.
+    /// Returns the name of the module, and the names it exposes.
+    pub fn load(
+        &mut self,
+        arena: &Bump,
+        path: &Path,
+        target_info: TargetInfo,
+        palette: Palette,
+    ) -> Result<(String, Vec<String>), Vec<String>> {
+        let (loaded, exposed) = load_interface(arena, path, target_info, &palette)?;
+
+        let name = loaded.interns.module_name(loaded.module_id).to_string();
+
+        // `Foo.Bar` is in `Foo/Bar.roc`, relative to the directory imports are looked up in
+        let mut src_dir = path.to_path_buf();
+
+        for _ in name.split('.') {
+            src_dir.pop();
+        }
+
+        match &self.src_dir {
+            Some(dir) if dir != &src_dir => {
+                return Err(vec![format!(
+                    "I can only load modules from one directory, and {} is not in {}. Use :reset to start over from a different directory.",
+                    path.display(),
+                    dir.display()
+                )]);
+            }
+            _ => {}
+        }
+
+        let interface = LoadedInterface {
+            name: name.clone(),
+            path: path.to_path_buf(),
+            exposed: exposed.clone(),
+        };
+
+        // Make sure the exposed names don't clash with the defs entered so far
+        let mut next = self.clone();
+        next.loaded_modules.retain(|module| module.name != name);
+        next.loaded_modules.push(interface);
+        next.src_dir = Some(src_dir);
+
+        let module_src = arena.alloc(next.module_src("{}"));
+        next.typecheck(arena, module_src, target_info, &palette)?;
+
+        *self = next;
+
+        Ok((name, exposed))
+    }
+
+    /// The values a loaded module exposes, with their types. Annotated values are shown with
+    /// their annotation as written in the module.
+    pub fn browse(
+        &self,
+        arena: &Bump,
+        module_name: &str,
+        target_info: TargetInfo,
+        palette: Palette,
+    ) -> Result<Vec<(String, String)>, Vec<String>> {
+        let interface = match self
+            .loaded_modules
+            .iter()
+            .find(|module| module.name == module_name)
+        {
+            Some(interface) => interface,
+            None => {
+                return Err(vec![format!(
+                    "There is no loaded module named {}. Use :load to load it first.",
+                    module_name
+                )])
+            }
+        };
+
+        let (mut loaded, exposed) = load_interface(arena, &interface.path, target_info, &palette)?;
+
+        let mut names_and_types = Vec::with_capacity(exposed.len());
+
+        if let Some(docs) = loaded.documentation.get(&loaded.module_id) {
+            for entry in docs.entries.iter() {
+                if let DocEntry::DocDef(def) = entry {
+                    if exposed.contains(&def.name) {
+                        let mut buf = String::new();
+                        doc_type_to_string(&def.type_annotation, &mut buf, false);
+                        names_and_types.push((def.name.clone(), buf));
+                    }
+                }
+            }
+        }
+
+        let unannotated: Vec<String> = exposed
+            .iter()
+            .filter(|name| !names_and_types.iter().any(|(n, _)| n == *name))
+            .cloned()
+            .collect();
+
+        names_and_types.extend(types_of(&mut loaded, &unannotated));
+
+        // in the order the module exposes them
+        names_and_types.sort_by_key(|(name, _)| exposed.iter().position(|e| e == name));
+
+        Ok(names_and_types)
+    }
+
+    fn compile_expr<'a>(
+        &self,
+        arena: &'a Bump,
+        src: &str,
+        target_info: TargetInfo,
+        palette: Palette,
+    ) -> Result<(MonomorphizedModule<'a>, Vec<String>), Vec<String>> {
+        let filename = PathBuf::from("");
+
+        let module_src = arena.alloc(self.module_src(src));
+
+        // The module is only loaded once: its problems (say, the input defining a name that one
+        // of the earlier defs already has) are reported from the monomorphized module, before
+        // any code is generated for it.
+        let exposed_types = Default::default();
+        let loaded = roc_load::load_and_monomorphize_from_str(
+            arena,
+            filename,
+            module_src,
+            self.src_dir(),
+            exposed_types,
+            LoadConfig {
+                target_info,
+                render: roc_reporting::report::RenderTarget::ColorTerminal,
+                threading: Threading::Single,
+                exec_mode: ExecutionMode::Executable,
+                cache_dir: None,
+            },
+        );
+
+        let mut loaded = match loaded {
+            Ok(v) => v,
+            Err(LoadingProblem::FormattedReport(report)) => {
+                return Err(vec![report]);
+            }
+            Err(e) => {
+                panic!("error while loading module: {:?}", e)
+            }
+        };
+
+        let MonomorphizedModule {
+            interns,
+            sources,
+            syntax_problems,
+            can_problems,
+            type_problems,
+            ..
+        } = &mut loaded;
+
+        let reports = self.report_problems(
+            sources,
+            syntax_problems,
+            can_problems,
+            type_problems,
+            interns,
+            &palette,
+        );
+
+        reports.into_result(loaded)
+    }
+
+    /// Typecheck the new def together with the earlier ones, and keep it if that succeeds.
+    fn add_def(
+        &mut self,
+        arena: &Bump,
+        mut def: PastDef,
+        target_info: TargetInfo,
+        palette: Palette,
+    ) -> Result<(Vec<(String, String)>, Vec<String>), Vec<String>> {
+        // A def replaces the earlier ones with the same names. The exception is an annotation
+        // entered on its own: that one applies to the body entered after it.
+        let mut next = self.clone();
+        next.past_defs.clear();
+
+        for past in self.past_defs.iter() {
+            if !past.names.iter().any(|name| def.names.contains(name)) {
+                next.past_defs.push(past.clone());
+            } else if past.kind == DefKind::Annotation
+                && def.kind == DefKind::Body
+                && past.names == def.names
+            {
+                def.src = format!("{}\n{}", past.src, def.src);
+                def.kind = DefKind::Other;
+            }
+        }
+
+        let names = def.names.clone();
+        next.past_defs.push(def);
+
+        let module_src = arena.alloc(next.module_src("{}"));
+        let (mut loaded, warnings) = next.typecheck(arena, module_src, target_info, &palette)?;
+
+        *self = next;
+
+        Ok((types_of(&mut loaded, &names), warnings))
+    }
+
+    fn src_dir(&self) -> PathBuf {
+        match &self.src_dir {
+            Some(dir) => dir.clone(),
+            None => PathBuf::from("fake/test/path"),
+        }
+    }
+
+    fn module_src(&self, src: &str) -> String {
+        let mut buffer = String::from("app \"app\"");
+
+        if !self.loaded_modules.is_empty() {
+            buffer.push_str(" imports [");
+
+            for (index, module) in self.loaded_modules.iter().enumerate() {
+                if index > 0 {
+                    buffer.push_str(", ");
+                }
+
+                buffer.push_str(&module.name);
+
+                if !module.exposed.is_empty() {
+                    buffer.push_str(".{ ");
+                    buffer.push_str(&module.exposed.join(", "));
+                    buffer.push_str(" }");
+                }
+            }
+
+            buffer.push(']');
+        }
+
+        buffer.push_str(" provides [replOutput] to \"./platform\"\n\n");
+
+        for def in self.past_defs.iter() {
+            buffer.push_str(&def.src);
+            buffer.push_str("\n\n");
+        }
+
+        buffer.push_str("replOutput =\n");
+
+        for line in src.lines() {
+            // indent the body!
+            buffer.push_str("    ");
+            buffer.push_str(line);
+            buffer.push('\n');
+        }
+
+        buffer
+    }
+
+    fn typecheck<'a>(
+        &self,
+        arena: &'a Bump,
+        module_src: &'a str,
+        target_info: TargetInfo,
+        palette: &Palette,
+    ) -> Result<(LoadedModule, Vec<String>), Vec<String>> {
+        let loaded = roc_load::load_and_typecheck_str(
+            arena,
+            PathBuf::from(""),
+            module_src,
+            self.src_dir(),
+            Default::default(),
+            target_info,
+            roc_reporting::report::RenderTarget::ColorTerminal,
+        );
+
+        let mut loaded = match loaded {
+            Ok(v) => v,
+            Err(LoadingProblem::FormattedReport(report)) => {
+                return Err(vec![report]);
+            }
+            Err(e) => {
+                panic!("error while loading module: {:?}", e)
+            }
+        };
+
+        let LoadedModule {
+            interns,
+            sources,
+            syntax_problems,
+            can_problems,
+            type_problems,
+            ..
+        } = &mut loaded;
+
+        let reports = self.report_problems(
+            sources,
+            syntax_problems,
+            can_problems,
+            type_problems,
+            interns,
+            palette,
+        );
+
+        reports.into_result(loaded)
+    }
+
+    /// Warnings are reported for the input itself, but not for the modules it imports.
+    fn report_problems(
+        &self,
+        sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+        syntax_problems: &mut MutMap<ModuleId, Vec<String>>,
+        can_problems: &mut MutMap<ModuleId, Vec<Problem>>,
+        type_problems: &mut MutMap<ModuleId, Vec<TypeError>>,
+        interns: &Interns,
+        palette: &Palette,
+    ) -> Reports {
+        let mut reports = Reports::default();
+
+        for (home, (module_path, src)) in sources.iter() {
+            // syntax errors are rendered by the loader, as soon as the module is parsed
+            let syntax_probs = syntax_problems.remove(home).unwrap_or_default();
+
+            reports.has_errors |= !syntax_probs.is_empty();
+            reports.lines.extend(syntax_probs);
+
+            let mut can_probs = can_problems.remove(home).unwrap_or_default();
+            let type_probs = type_problems.remove(home).unwrap_or_default();
+
+            let is_input = interns.module_name(*home).as_str() == ModuleName::APP;
+
+            if is_input {
+                // The earlier defs and the loaded modules are there for later inputs to use,
+                // so of course most inputs don't.
+                can_probs.retain(|problem| match problem {
+                    Problem::UnusedDef(symbol, _) => !self
+                        .past_defs
+                        .iter()
+                        .any(|def| def.names.iter().any(|name| name == symbol.as_str(interns))),
+                    Problem::UnusedImport(module_id, _) => !self
+                        .loaded_modules
+                        .iter()
+                        .any(|module| module.name == interns.module_name(*module_id).as_str()),
+                    _ => true,
+                });
+            }
+
+            let error_count = can_probs.len() + type_probs.len();
 
-    let module_src = arena.alloc(promote_expr_to_module(src));
+            if error_count == 0 {
+                continue;
+            }
+
+            let line_info = LineInfo::new(src);
+            let src_lines: Vec<&str> = src.split('\n').collect();
+
+            // Report parsing and canonicalization problems
+            let alloc = RocDocAllocator::new(&src_lines, *home, interns);
+
+            let module_reports = can_probs
+                .into_iter()
+                .map(|problem| can_problem(&alloc, &line_info, module_path.clone(), problem))
+                .chain(type_probs.into_iter().filter_map(|problem| {
+                    type_problem(&alloc, &line_info, module_path.clone(), problem)
+                }));
+
+            for report in module_reports {
+                if report.severity == Severity::Warning {
+                    if !is_input {
+                        continue;
+                    }
+                } else {
+                    reports.has_errors = true;
+                }
+
+                let mut buf = String::new();
+
+                report.render_color_terminal(&mut buf, &alloc, palette);
+
+                reports.lines.push(buf);
+            }
+        }
+
+        reports
+    }
+}
 
-    let exposed_types = Default::default();
-    let loaded = roc_load::load_and_monomorphize_from_str(
+/// The rendered problems of a module, in the order they were found
+#[derive(Debug, Default)]
+struct Reports {
+    lines: Vec<String>,
+    /// Only errors stop an input from running
+    has_errors: bool,
+}
+
+impl Reports {
+    /// Without errors, the warnings are shown along with the result instead.
+    fn into_result<T>(self, value: T) -> Result<(T, Vec<String>), Vec<String>> {
+        if self.has_errors {
+            Err(self.lines)
+        } else {
+            Ok((value, self.lines))
+        }
+    }
+}
+
+/// The module, and the values its header exposes
+fn load_interface(
+    arena: &Bump,
+    path: &Path,
+    target_info: TargetInfo,
+    palette: &Palette,
+) -> Result<(LoadedModule, Vec<String>), Vec<String>> {
+    let loaded = roc_load::load_and_typecheck(
         arena,
-        filename,
-        module_src,
-        src_dir,
-        exposed_types,
+        path.to_path_buf(),
+        Default::default(),
         LoadConfig {
             target_info,
             render: roc_reporting::report::RenderTarget::ColorTerminal,
             threading: Threading::Single,
-            exec_mode: ExecutionMode::Executable,
+            exec_mode: ExecutionMode::Check,
+            cache_dir: None,
         },
     );
 
@@ -74,73 +580,447 @@ pub fn compile_to_mono<'a>(
             return Err(vec![report]);
         }
         Err(e) => {
-            panic!("error while loading module: {:?}", e)
+            return Err(vec![format!(
+                "I could not load {}: {:?}",
+                path.display(),
+                e
+            )]);
+        }
+    };
+
+    let (_, src) = &loaded.sources[&loaded.module_id];
+    let header_arena = Bump::new();
+    let state = State::new(src.as_bytes());
+
+    let exposed: Vec<String> = match roc_parse::module::parse_header(&header_arena, state) {
+        Ok((Module::Interface { header }, _)) => header
+            .exposes
+            .iter()
+            .map(|name| -> &str { name.value.extract_spaces().item.into() })
+            // types are exposed too, but only values can be imported unqualified
+            .filter(|name| name.starts_with(|c: char| c.is_lowercase()))
+            .map(String::from)
+            .collect(),
+        _ => {
+            return Err(vec![format!(
+                "{} is not an interface module. Only interface modules can be loaded.",
+                path.display()
+            )]);
         }
     };
 
-    let MonomorphizedModule {
+    let LoadedModule {
         interns,
         sources,
+        syntax_problems,
         can_problems,
         type_problems,
         ..
     } = &mut loaded;
 
-    let mut lines = Vec::new();
+    // This is never the input, so there are no warnings to show.
+    let reports = ReplState::default().report_problems(
+        sources,
+        syntax_problems,
+        can_problems,
+        type_problems,
+        interns,
+        palette,
+    );
+
+    reports
+        .into_result(loaded)
+        .map(|(loaded, _)| (loaded, exposed))
+}
+
+/// The inferred types of these top-level names in the root module
+fn types_of(loaded: &mut LoadedModule, names: &[String]) -> Vec<(String, String)> {
+    let LoadedModule {
+        module_id: home,
+        interns,
+        solved,
+        declarations_by_id,
+        ..
+    } = loaded;
 
-    for (home, (module_path, src)) in sources.iter() {
-        let can_probs = can_problems.remove(home).unwrap_or_default();
-        let type_probs = type_problems.remove(home).unwrap_or_default();
+    let mut symbol_vars = Vec::new();
 
-        let error_count = can_probs.len() + type_probs.len();
+    if let Some(decls) = declarations_by_id.get(home) {
+        for (index, tag) in decls.declarations.iter().enumerate() {
+            match tag {
+                DeclarationTag::Destructure(destructure_index) => {
+                    let destructure = &decls.destructs[destructure_index.index()];
 
-        if error_count == 0 {
-            continue;
+                    symbol_vars.extend(destructure.pattern_vars.iter().map(|(s, v)| (*s, *v)));
+                }
+                _ => symbol_vars.push((decls.symbols[index].value, decls.variables[index])),
+            }
         }
+    }
 
-        let line_info = LineInfo::new(module_src);
-        let src_lines: Vec<&str> = src.split('\n').collect();
+    let mut names_and_types = Vec::with_capacity(names.len());
 
-        // Report parsing and canonicalization problems
-        let alloc = RocDocAllocator::new(&src_lines, *home, interns);
+    for name in names.iter() {
+        let found = symbol_vars
+            .iter()
+            .find(|(symbol, _)| symbol.as_str(interns) == name.as_str());
 
-        for problem in can_probs.into_iter() {
-            let report = can_problem(&alloc, &line_info, module_path.clone(), problem);
-            let mut buf = String::new();
+        // type defs don't have a variable
+        if let Some((_, var)) = found {
+            let type_str = name_and_print_var(
+                *var,
+                solved.inner_mut(),
+                *home,
+                interns,
+                DebugPrint::NOTHING,
+            );
 
-            report.render_color_terminal(&mut buf, &alloc, &palette);
+            names_and_types.push((name.clone(), type_str));
+        }
+    }
 
-            lines.push(buf);
+    names_and_types
+}
+
+/// Like the annotation in the docs, but on a single line
+fn doc_type_to_string(type_ann: &TypeAnnotation, buf: &mut String, needs_parens: bool) {
+    match type_ann {
+        TypeAnnotation::TagUnion { tags, extension } => {
+            buf.push('[');
+
+            for (index, tag) in tags.iter().enumerate() {
+                if index > 0 {
+                    buf.push_str(", ");
+                }
+
+                buf.push_str(&tag.name);
+
+                for value in tag.values.iter() {
+                    buf.push(' ');
+                    doc_type_to_string(value, buf, true);
+                }
+            }
+
+            buf.push(']');
+            doc_type_to_string(extension, buf, true);
         }
+        TypeAnnotation::Function { args, output } => {
+            if needs_parens {
+                buf.push('(');
+            }
 
-        for problem in type_probs {
-            if let Some(report) = type_problem(&alloc, &line_info, module_path.clone(), problem) {
-                let mut buf = String::new();
+            for (index, arg) in args.iter().enumerate() {
+                if index > 0 {
+                    buf.push_str(", ");
+                }
+
+                doc_type_to_string(arg, buf, matches!(arg, TypeAnnotation::Function { .. }));
+            }
+
+            buf.push_str(" -> ");
+            doc_type_to_string(output, buf, false);
+
+            if needs_parens {
+                buf.push(')');
+            }
+        }
+        TypeAnnotation::BoundVariable(name) => buf.push_str(name),
+        TypeAnnotation::Apply { name, parts } => {
+            let parens = needs_parens && !parts.is_empty();
+
+            if parens {
+                buf.push('(');
+            }
+
+            buf.push_str(name);
+
+            for part in parts.iter() {
+                buf.push(' ');
+                doc_type_to_string(part, buf, true);
+            }
+
+            if parens {
+                buf.push(')');
+            }
+        }
+        TypeAnnotation::Record { fields, extension } => {
+            if fields.is_empty() {
+                buf.push_str("{}");
+            } else {
+                buf.push_str("{ ");
 
-                report.render_color_terminal(&mut buf, &alloc, &palette);
+                for (index, field) in fields.iter().enumerate() {
+                    if index > 0 {
+                        buf.push_str(", ");
+                    }
 
-                lines.push(buf);
+                    match field {
+                        RecordField::RecordField {
+                            name,
+                            type_annotation,
+                        } => {
+                            buf.push_str(name);
+                            buf.push_str(" : ");
+                            doc_type_to_string(type_annotation, buf, false);
+                        }
+                        RecordField::OptionalField {
+                            name,
+                            type_annotation,
+                        } => {
+                            buf.push_str(name);
+                            buf.push_str(" ? ");
+                            doc_type_to_string(type_annotation, buf, false);
+                        }
+                        RecordField::LabelOnly { name } => buf.push_str(name),
+                    }
+                }
+
+                buf.push_str(" }");
             }
+
+            doc_type_to_string(extension, buf, true);
         }
+        TypeAnnotation::Ability { members } => {
+            buf.push_str("has ");
+
+            let names: Vec<&str> = members.iter().map(|member| member.name.as_str()).collect();
+            buf.push_str(&names.join(", "));
+        }
+        TypeAnnotation::ObscuredTagUnion => buf.push_str("[@..]"),
+        TypeAnnotation::ObscuredRecord => buf.push_str("{ @.. }"),
+        TypeAnnotation::Wildcard => buf.push('*'),
+        TypeAnnotation::NoTypeAnn => {}
     }
+}
 
-    if !lines.is_empty() {
-        Err(lines)
-    } else {
-        Ok(loaded)
+/// Whether the input only fails to parse because it stops too soon, so that another line of
+/// input might complete it.
+pub fn is_incomplete(src: &str) -> bool {
+    let arena = Bump::new();
+    let src = src.trim();
+
+    match test_parse_expr(0, &arena, State::new(src.as_bytes())) {
+        Ok(_) => false,
+        Err(problem) => stuck_at_end(&problem, src.len() as u32),
     }
 }
 
-fn promote_expr_to_module(src: &str) -> String {
-    let mut buffer =
-        String::from("app \"app\" provides [replOutput] to \"./platform\"\n\nreplOutput =\n");
+/// Nested problems are reported at the start of the construct that contains them, so look
+/// through to where the parser actually got stuck.
+fn stuck_at_end(problem: &EExpr, end: u32) -> bool {
+    use roc_parse::parser::{EExpect, EIf, EInParens, ELambda, EList, ERecord, EString, EWhen};
+
+    let pos = match problem {
+        EExpr::DefMissingFinalExpr2(expr, _)
+        | EExpr::Lambda(ELambda::Body(expr, _), _)
+        | EExpr::When(EWhen::Condition(expr, _), _)
+        | EExpr::When(EWhen::Branch(expr, _), _)
+        | EExpr::When(EWhen::IfGuard(expr, _), _)
+        | EExpr::If(EIf::Condition(expr, _), _)
+        | EExpr::If(EIf::ThenBranch(expr, _), _)
+        | EExpr::If(EIf::ElseBranch(expr, _), _)
+        | EExpr::Expect(EExpect::Condition(expr, _), _)
+        | EExpr::Expect(EExpect::Continuation(expr, _), _)
+        | EExpr::Dbg(EExpect::Condition(expr, _), _)
+        | EExpr::Dbg(EExpect::Continuation(expr, _), _)
+        | EExpr::InParens(EInParens::Expr(expr, _), _)
+        | EExpr::Record(ERecord::Expr(expr, _), _)
+        | EExpr::List(EList::Expr(expr, _), _)
+        | EExpr::Str(EString::Format(expr, _), _) => return stuck_at_end(expr, end),
+
+        // a multi-line string is reported where it starts
+        EExpr::Str(EString::EndlessMulti(_), _) => return true,
+
+        EExpr::Lambda(
+            ELambda::Space(_, pos)
+            | ELambda::Start(pos)
+            | ELambda::Arrow(pos)
+            | ELambda::Comma(pos)
+            | ELambda::Arg(pos)
+            | ELambda::Pattern(_, pos)
+            | ELambda::IndentArrow(pos)
+            | ELambda::IndentBody(pos)
+            | ELambda::IndentArg(pos),
+            _,
+        )
+        | EExpr::When(
+            EWhen::Space(_, pos)
+            | EWhen::When(pos)
+            | EWhen::Is(pos)
+            | EWhen::Pattern(_, pos)
+            | EWhen::Arrow(pos)
+            | EWhen::Bar(pos)
+            | EWhen::IfToken(pos)
+            | EWhen::IndentIs(pos)
+            | EWhen::IndentCondition(pos)
+            | EWhen::IndentPattern(pos)
+            | EWhen::IndentArrow(pos)
+            | EWhen::IndentBranch(pos)
+            | EWhen::IndentIfGuard(pos)
+            | EWhen::PatternAlignment(_, pos),
+            _,
+        )
+        | EExpr::If(
+            EIf::Space(_, pos)
+            | EIf::If(pos)
+            | EIf::Then(pos)
+            | EIf::Else(pos)
+            | EIf::IndentCondition(pos)
+            | EIf::IndentIf(pos)
+            | EIf::IndentThenToken(pos)
+            | EIf::IndentElseToken(pos)
+            | EIf::IndentThenBranch(pos)
+            | EIf::IndentElseBranch(pos),
+            _,
+        )
+        | EExpr::InParens(
+            EInParens::End(pos)
+            | EInParens::Open(pos)
+            | EInParens::Empty(pos)
+            | EInParens::Space(_, pos)
+            | EInParens::IndentOpen(pos)
+            | EInParens::IndentEnd(pos),
+            _,
+        )
+        | EExpr::Record(
+            ERecord::End(pos)
+            | ERecord::Open(pos)
+            | ERecord::Updateable(pos)
+            | ERecord::Field(pos)
+            | ERecord::Colon(pos)
+            | ERecord::QuestionMark(pos)
+            | ERecord::Bar(pos)
+            | ERecord::Ampersand(pos)
+            | ERecord::Space(_, pos)
+            | ERecord::IndentOpen(pos)
+            | ERecord::IndentColon(pos)
+            | ERecord::IndentBar(pos)
+            | ERecord::IndentAmpersand(pos)
+            | ERecord::IndentEnd(pos),
+            _,
+        )
+        | EExpr::List(
+            EList::Open(pos)
+            | EList::End(pos)
+            | EList::Space(_, pos)
+            | EList::IndentOpen(pos)
+            | EList::IndentEnd(pos),
+            _,
+        ) => *pos,
+
+        other => other.get_position(),
+    };
+
+    pos.offset >= end
+}
+
+/// A report for input that doesn't parse. It points into the input as the user typed it, rather
+/// than into the module the input gets wrapped in.
+fn parse_problem_report(src: &str, palette: &Palette) -> Option<String> {
+    let arena = Bump::new();
+    let src = src.trim();
+    let state = State::new(src.as_bytes());
+
+    let problem = match test_parse_expr(0, &arena, state.clone()) {
+        Ok(_) => return None,
+        // defs with no final expression are fine, as long as the defs themselves parse
+        Err(EExpr::DefMissingFinalExpr(_) | EExpr::DefMissingFinalExpr2(_, _))
+            if module_defs().parse(&arena, state.clone()).is_ok() =>
+        {
+            return None;
+        }
+        Err(problem) => problem,
+    };
+
+    let file_error =
+        SyntaxError::Expr(problem, Position::default()).into_file_error(PathBuf::new(), &state);
+
+    let mut interns = Interns::default();
+    let home = interns.module_ids.get_or_insert(&ModuleName::APP.into());
+    let src_lines: Vec<&str> = src.split('\n').collect();
+    let alloc = RocDocAllocator::new(&src_lines, home, &interns);
+    let report = parse_problem(&alloc, &LineInfo::new(src), PathBuf::new(), 0, file_error);
+
+    let mut buf = String::new();
+
+    report.render_color_terminal(&mut buf, &alloc, palette);
+
+    Some(buf)
+}
+
+/// `Some` if the input is one or more top-level defs, rather than an expression
+fn parse_defs(src: &str) -> Option<PastDef> {
+    let arena = Bump::new();
+    let src = src.trim();
+    let state = State::new(src.as_bytes());
 
-    for line in src.lines() {
-        // indent the body!
-        buffer.push_str("    ");
-        buffer.push_str(line);
-        buffer.push('\n');
+    let (_, defs, _) = module_defs().parse(&arena, state).ok()?;
+
+    if defs.is_empty() {
+        return None;
     }
 
-    buffer
+    let mut names = Vec::new();
+    let mut annotations_only = true;
+
+    for def in defs.defs() {
+        match def {
+            Ok(TypeDef::Alias { header, .. })
+            | Ok(TypeDef::Opaque { header, .. })
+            | Ok(TypeDef::Ability { header, .. }) => {
+                names.push(header.name.value.to_string());
+                annotations_only = false;
+            }
+            Err(ValueDef::Annotation(pattern, _)) => {
+                pattern_idents(&pattern.value, &mut names);
+            }
+            Err(ValueDef::Body(pattern, _)) => {
+                pattern_idents(&pattern.value, &mut names);
+                annotations_only = false;
+            }
+            Err(ValueDef::AnnotatedBody { body_pattern, .. }) => {
+                pattern_idents(&body_pattern.value, &mut names);
+                annotations_only = false;
+            }
+            // a top-level expect can't be referred to, so there is no point in keeping it
+            Err(ValueDef::Expect { .. }) | Err(ValueDef::ExpectFx { .. }) => return None,
+        }
+    }
+
+    let kind = if annotations_only {
+        DefKind::Annotation
+    } else if defs.len() == 1 && matches!(defs.last(), Some(Err(ValueDef::Body(..)))) {
+        DefKind::Body
+    } else {
+        DefKind::Other
+    };
+
+    Some(PastDef {
+        names,
+        kind,
+        src: src.to_string(),
+    })
+}
+
+fn pattern_idents(pattern: &Pattern, idents: &mut Vec<String>) {
+    use Pattern::*;
+
+    match pattern {
+        Identifier(ident) => idents.push(ident.to_string()),
+        Apply(_, args) => {
+            for arg in args.iter() {
+                pattern_idents(&arg.value, idents);
+            }
+        }
+        RecordDestructure(patterns) | Tuple(patterns) | List(patterns) => {
+            for pattern in patterns.iter() {
+                pattern_idents(&pattern.value, idents);
+            }
+        }
+        ListRest(Some(name)) => idents.push(name.value.to_string()),
+        RequiredField(_, pattern) => pattern_idents(&pattern.value, idents),
+        OptionalField(field, _) => idents.push(field.to_string()),
+        SpaceBefore(pattern, _) | SpaceAfter(pattern, _) => pattern_idents(pattern, idents),
+        _ => {}
+    }
 }
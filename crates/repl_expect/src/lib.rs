@@ -120,6 +120,7 @@ mod test {
             render: RenderTarget::ColorTerminal,
             threading: Threading::Single,
             exec_mode: ExecutionMode::Test,
+            cache_dir: None,
         };
         let loaded = roc_load::load_and_monomorphize_from_str(
             arena,
@@ -160,7 +161,7 @@ mod test {
         unsafe { set_shared_buffer((shared_buffer.as_mut_ptr(), BUFFER_SIZE), &mut result) };
 
         let mut writer = Vec::with_capacity(1024);
-        let (_failed, _passed) = crate::run::run_expects_with_memory(
+        let _outcomes = crate::run::run_expects_with_memory(
             &mut writer,
             RenderTarget::ColorTerminal,
             arena,
@@ -180,11 +181,16 @@ mod test {
         let bytes = strip_ansi_escapes::strip(writer).unwrap();
         let actual = String::from_utf8(bytes).unwrap();
 
+        // the path of the tempdir changes between test runs
+        let actual = actual.replace(&format!("{}/", src_dir.path().display()), "");
+
         if !actual.is_empty() {
-            // trim off the first line; it contains a path in a tempdir that
+            // trim off the report headers; they contain a path in a tempdir that
             // changes between test runs
-            let p = actual.bytes().position(|c| c == b'\n').unwrap();
-            let (_, x) = actual.split_at(p);
+            let x: String = actual
+                .split_inclusive('\n')
+                .filter(|line| !line.starts_with("── "))
+                .collect();
             let x = x.trim_start();
 
             if x != expected {
@@ -934,4 +940,101 @@ mod test {
             ),
         );
     }
+
+    #[test]
+    fn dbg_in_passing_expect() {
+        run_expect_test(
+            indoc!(
+                r#"
+                interface A exposes [] imports []
+
+                expect
+                    list = [1, 2, 3]
+
+                    dbg List.len list
+
+                    List.len list == 3
+                "#
+            ),
+            indoc!(
+                r#"
+                [Test.roc:6] List.len list = 3
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn dbg_value_then_failure() {
+        run_expect_test(
+            indoc!(
+                r#"
+                interface A exposes [] imports []
+
+                expect
+                    name = dbg Str.concat "Ro" "c"
+
+                    name == "Elm"
+                "#
+            ),
+            indoc!(
+                r#"
+                [Test.roc:4] Str.concat "Ro" "c" = "Roc"
+
+                This expectation failed:
+
+                3│>  expect
+                4│>      name = dbg Str.concat "Ro" "c"
+                5│>
+                6│>      name == "Elm"
+
+                When it failed, these variables had these values:
+
+                name : Str
+                name = "Roc"
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn decode_error_message() {
+        run_expect_test(
+            indoc!(
+                r#"
+                interface A exposes [] imports [Decode, Json]
+
+                expect
+                    bytes = Str.toUtf8 "{\"first\":\"ab\",\"second\":true}"
+                    decoded = Decode.fromBytesPartial bytes Json.fromUtf8
+                    message =
+                        when decoded.result is
+                            Ok { first, second } -> Str.concat first (Num.toStr (second + 1u8))
+                            Err err -> Decode.errorToStr err
+
+                    message == "ab2"
+                "#
+            ),
+            indoc!(
+                r#"
+                This expectation failed:
+
+                 3│>  expect
+                 4│>      bytes = Str.toUtf8 "{\"first\":\"ab\",\"second\":true}"
+                 5│>      decoded = Decode.fromBytesPartial bytes Json.fromUtf8
+                 6│>      message =
+                 7│>          when decoded.result is
+                 8│>              Ok { first, second } -> Str.concat first (Num.toStr (second + 1u8))
+                 9│>              Err err -> Decode.errorToStr err
+                10│>
+                11│>      message == "ab2"
+
+                When it failed, these variables had these values:
+
+                message : Str
+                message = "value.second: expected U8, found true}"
+                "#
+            ),
+        );
+    }
 }
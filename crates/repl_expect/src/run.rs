@@ -1,3 +1,4 @@
+use std::time::{Duration, Instant};
 use std::{os::unix::process::parent_id, sync::Arc};
 
 use bumpalo::collections::Vec as BumpVec;
@@ -73,6 +74,12 @@ impl<'a> ExpectMemory<'a> {
         }
     }
 
+    fn unlink(&self) {
+        if let Some(shm_name) = &self.shm_name {
+            unsafe { libc::shm_unlink(shm_name.as_ptr()) };
+        }
+    }
+
     fn set_shared_buffer(&mut self, lib: &libloading::Library) {
         let set_shared_buffer = run_roc_dylib!(lib, "set_shared_buffer", (*mut u8, usize), ());
         let mut result = RocCallResult::default();
@@ -90,7 +97,7 @@ pub fn run_expects<'a, W: std::io::Write>(
     lib: &libloading::Library,
     expectations: &mut VecMap<ModuleId, Expectations>,
     expects: ExpectFunctions<'_>,
-) -> std::io::Result<(usize, usize)> {
+) -> std::io::Result<Vec<ExpectOutcome>> {
     let shm_name = format!("/roc_expect_buffer_{}", std::process::id());
     let mut memory = ExpectMemory::create_or_reuse_mmap(&shm_name);
 
@@ -118,12 +125,12 @@ pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
     expectations: &mut VecMap<ModuleId, Expectations>,
     expects: ExpectFunctions<'_>,
     memory: &mut ExpectMemory,
-) -> std::io::Result<(usize, usize)> {
-    let mut failed = 0;
-    let mut passed = 0;
+) -> std::io::Result<Vec<ExpectOutcome>> {
+    let mut outcomes = Vec::with_capacity(expects.fx.len() + expects.pure.len());
 
     for expect in expects.fx {
-        let result = run_expect_fx(
+        let start = Instant::now();
+        let passed = run_expect_fx(
             writer,
             render_target,
             arena,
@@ -135,16 +142,18 @@ pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
             expect,
         )?;
 
-        match result {
-            true => passed += 1,
-            false => failed += 1,
-        }
+        outcomes.push(ExpectOutcome {
+            symbol: expect.symbol,
+            passed,
+            duration: start.elapsed(),
+        });
     }
 
     memory.set_shared_buffer(lib);
 
     for expect in expects.pure {
-        let result = run_expect_pure(
+        let start = Instant::now();
+        let passed = run_expect_pure(
             writer,
             render_target,
             arena,
@@ -156,13 +165,14 @@ pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
             expect,
         )?;
 
-        match result {
-            true => passed += 1,
-            false => failed += 1,
-        }
+        outcomes.push(ExpectOutcome {
+            symbol: expect.symbol,
+            passed,
+            duration: start.elapsed(),
+        });
     }
 
-    Ok((failed, passed))
+    Ok(outcomes)
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -185,7 +195,19 @@ fn run_expect_pure<'a, W: std::io::Write>(
 
     let shared_memory_ptr: *const u8 = shared_memory.ptr.cast();
 
-    if result.is_err() || sequence.count_failures() > 0 {
+    let failures = render_frames(
+        writer,
+        render_target,
+        arena,
+        Some(expect),
+        expectations,
+        interns,
+        layout_interner,
+        shared_memory_ptr,
+        sequence.count_frames(),
+    )?;
+
+    if let Err(roc_panic_message) = &result {
         let module_id = expect.symbol.module_id();
         let data = expectations.get_mut(&module_id).unwrap();
 
@@ -195,28 +217,14 @@ fn run_expect_pure<'a, W: std::io::Write>(
 
         let renderer = Renderer::new(arena, interns, render_target, module_id, filename, &source);
 
-        if let Err(roc_panic_message) = result {
-            renderer.render_panic(writer, &roc_panic_message, expect.region)?;
-        } else {
-            let mut offset = ExpectSequence::START_OFFSET;
+        renderer.render_panic(writer, roc_panic_message, expect.region)?;
+    }
 
-            for _ in 0..sequence.count_failures() {
-                offset += render_expect_failure(
-                    writer,
-                    &renderer,
-                    arena,
-                    Some(expect),
-                    expectations,
-                    interns,
-                    layout_interner,
-                    shared_memory_ptr,
-                    offset,
-                )?;
-            }
+    if result.is_err() || failures > 0 {
+        if !matches!(render_target, RenderTarget::Json) {
+            writeln!(writer)?;
         }
 
-        writeln!(writer)?;
-
         Ok(false)
     } else {
         Ok(true)
@@ -257,7 +265,7 @@ fn run_expect_fx<'a, W: std::io::Write>(
                 panic!("roc panic {}", msg);
             }
 
-            if sequence.count_failures() > 0 {
+            if sequence.count_frames() > 0 {
                 libc::kill(parent_id() as _, SIGUSR1);
             }
 
@@ -281,37 +289,26 @@ fn run_expect_fx<'a, W: std::io::Write>(
                         return Ok(has_succeeded);
                     }
                     SIGUSR1 => {
-                        // this is the signal we use for an expect failure. Let's see what the child told us
-                        has_succeeded = false;
-
-                        let frame =
-                            ExpectFrame::at_offset(parent_memory.ptr, ExpectSequence::START_OFFSET);
-                        let module_id = frame.module_id;
+                        // this is the signal we use for an expect failure or a `dbg`. Let's see what the child told us
+                        let sequence = ExpectSequence {
+                            ptr: parent_memory.ptr,
+                        };
 
-                        let data = expectations.get_mut(&module_id).unwrap();
-                        let filename = data.path.to_owned();
-                        let source = std::fs::read_to_string(&data.path).unwrap();
-
-                        let renderer = Renderer::new(
-                            arena,
-                            interns,
-                            render_target,
-                            module_id,
-                            filename,
-                            &source,
-                        );
-
-                        render_expect_failure(
+                        let failures = render_frames(
                             writer,
-                            &renderer,
+                            render_target,
                             arena,
                             None,
                             expectations,
                             interns,
                             layout_interner,
                             parent_memory.ptr,
-                            ExpectSequence::START_OFFSET,
+                            sequence.count_frames(),
                         )?;
+
+                        if failures > 0 {
+                            has_succeeded = false;
+                        }
                     }
                     _ => println!("received signal {}", sig),
                 }
@@ -323,6 +320,86 @@ fn run_expect_fx<'a, W: std::io::Write>(
     }
 }
 
+/// Runs a program built by `roc run` in a child process, and renders the value of every `dbg` the
+/// moment the program reaches it. Returns the exit code of the program.
+///
+/// `exec` runs in the child process, and should replace it with the program.
+pub fn run_with_dbgs<'a, W: std::io::Write>(
+    writer: &mut W,
+    arena: &'a Bump,
+    interns: &'a Interns,
+    expectations: &mut VecMap<ModuleId, Expectations>,
+    exec: impl FnOnce(),
+) -> std::io::Result<i32> {
+    use signal_hook::{consts::signal::SIGCHLD, consts::signal::SIGUSR1, iterator::Signals};
+
+    // the program finds this buffer by the pid of its parent
+    let shm_name = format!("/roc_dbg_buffer_{}", std::process::id());
+    let memory = ExpectMemory::create_or_reuse_mmap(&shm_name);
+    let sequence = ExpectSequence::new(memory.ptr);
+
+    let layout_interner = GlobalInterner::with_capacity(4);
+    let mut signals = Signals::new(&[SIGCHLD, SIGUSR1])?;
+
+    let child_pid = match unsafe { libc::fork() } {
+        0 => {
+            exec();
+
+            // only reached when the program could not be started
+            std::process::exit(1)
+        }
+        -1 => {
+            memory.unlink();
+
+            return Err(std::io::Error::last_os_error());
+        }
+        pid => pid,
+    };
+
+    let mut exit_code = 1;
+
+    for sig in &mut signals {
+        match sig {
+            SIGUSR1 => {
+                render_frames(
+                    writer,
+                    RenderTarget::ColorTerminal,
+                    arena,
+                    None,
+                    expectations,
+                    interns,
+                    &layout_interner,
+                    memory.ptr,
+                    sequence.count_frames(),
+                )?;
+                writer.flush()?;
+
+                // the program waits for this before it continues
+                sequence.clear();
+            }
+            SIGCHLD => {
+                // SIGCHLD is also sent when the program is stopped or continued
+                let mut status = 0;
+
+                if unsafe { libc::waitpid(child_pid, &mut status, libc::WNOHANG) } == child_pid {
+                    exit_code = if libc::WIFEXITED(status) {
+                        libc::WEXITSTATUS(status)
+                    } else {
+                        128 + libc::WTERMSIG(status)
+                    };
+
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    memory.unlink();
+
+    Ok(exit_code)
+}
+
 pub fn roc_dev_expect<'a>(
     writer: &mut impl std::io::Write,
     arena: &'a Bump,
@@ -332,32 +409,147 @@ pub fn roc_dev_expect<'a>(
     shared_ptr: *mut u8,
 ) -> std::io::Result<usize> {
     let frame = ExpectFrame::at_offset(shared_ptr, ExpectSequence::START_OFFSET);
+
+    render_frame(
+        writer,
+        RenderTarget::ColorTerminal,
+        arena,
+        None,
+        expectations,
+        interns,
+        layout_interner,
+        shared_ptr,
+        &frame,
+    )?;
+
+    Ok(frame.end_offset)
+}
+
+/// Renders the frames in shared memory in the order they were written,
+/// and returns how many of them are failed expectations (as opposed to `dbg`s)
+#[allow(clippy::too_many_arguments)]
+fn render_frames<'a>(
+    writer: &mut impl std::io::Write,
+    render_target: RenderTarget,
+    arena: &'a Bump,
+    expect: Option<ToplevelExpect>,
+    expectations: &mut VecMap<ModuleId, Expectations>,
+    interns: &'a Interns,
+    layout_interner: &Arc<GlobalInterner<'a, Layout<'a>>>,
+    start: *const u8,
+    frame_count: usize,
+) -> std::io::Result<usize> {
+    let mut offset = ExpectSequence::START_OFFSET;
+    let mut failures = 0;
+
+    for _ in 0..frame_count {
+        let frame = ExpectFrame::at_offset(start, offset);
+
+        let is_failure = render_frame(
+            writer,
+            render_target,
+            arena,
+            expect,
+            expectations,
+            interns,
+            layout_interner,
+            start,
+            &frame,
+        )?;
+
+        if is_failure {
+            failures += 1;
+        }
+
+        offset = frame.end_offset;
+    }
+
+    Ok(failures)
+}
+
+/// Returns whether the frame was a failed expectation
+#[allow(clippy::too_many_arguments)]
+fn render_frame<'a>(
+    writer: &mut impl std::io::Write,
+    render_target: RenderTarget,
+    arena: &'a Bump,
+    expect: Option<ToplevelExpect>,
+    expectations: &mut VecMap<ModuleId, Expectations>,
+    interns: &'a Interns,
+    layout_interner: &Arc<GlobalInterner<'a, Layout<'a>>>,
+    start: *const u8,
+    frame: &ExpectFrame,
+) -> std::io::Result<bool> {
     let module_id = frame.module_id;
 
     let data = expectations.get_mut(&module_id).unwrap();
     let filename = data.path.to_owned();
     let source = std::fs::read_to_string(&data.path).unwrap();
+    let is_dbg = data.dbgs.contains_key(&frame.region);
 
-    let renderer = Renderer::new(
-        arena,
-        interns,
-        RenderTarget::ColorTerminal,
-        module_id,
-        filename,
-        &source,
-    );
+    let renderer = Renderer::new(arena, interns, render_target, module_id, filename, &source);
 
-    render_expect_failure(
-        writer,
-        &renderer,
+    if is_dbg {
+        render_dbg(
+            writer,
+            &renderer,
+            arena,
+            expectations,
+            interns,
+            layout_interner,
+            start,
+            frame,
+        )?;
+    } else {
+        render_expect_failure(
+            writer,
+            &renderer,
+            arena,
+            expect,
+            expectations,
+            interns,
+            layout_interner,
+            start,
+            frame,
+        )?;
+    }
+
+    Ok(!is_dbg)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn render_dbg<'a>(
+    writer: &mut impl std::io::Write,
+    renderer: &Renderer,
+    arena: &'a Bump,
+    expectations: &mut VecMap<ModuleId, Expectations>,
+    interns: &'a Interns,
+    layout_interner: &Arc<GlobalInterner<'a, Layout<'a>>>,
+    start: *const u8,
+    frame: &ExpectFrame,
+) -> std::io::Result<()> {
+    // we always run programs as the host
+    let target_info = (&target_lexicon::Triple::host()).into();
+
+    let data = expectations.get_mut(&frame.module_id).unwrap();
+
+    let (_, variable) = data.dbgs.get(&frame.region).unwrap();
+    let variable = *variable;
+    let subs = arena.alloc(&mut data.subs);
+
+    let (_, expressions) = crate::get_values(
+        target_info,
         arena,
-        None,
-        expectations,
+        subs,
         interns,
         layout_interner,
-        shared_ptr,
-        ExpectSequence::START_OFFSET,
+        start,
+        frame.start_offset,
+        &[variable],
     )
+    .unwrap();
+
+    renderer.render_dbg(writer, &expressions[0], frame.region)
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -370,12 +562,11 @@ fn render_expect_failure<'a>(
     interns: &'a Interns,
     layout_interner: &Arc<GlobalInterner<'a, Layout<'a>>>,
     start: *const u8,
-    offset: usize,
-) -> std::io::Result<usize> {
+    frame: &ExpectFrame,
+) -> std::io::Result<()> {
     // we always run programs as the host
     let target_info = (&target_lexicon::Triple::host()).into();
 
-    let frame = ExpectFrame::at_offset(start, offset);
     let module_id = frame.module_id;
 
     let failure_region = frame.region;
@@ -391,7 +582,7 @@ fn render_expect_failure<'a>(
 
     let (symbols, variables): (Vec<_>, Vec<_>) = current.iter().map(|(a, b)| (*a, *b)).unzip();
 
-    let (offset, expressions) = crate::get_values(
+    let (_, expressions) = crate::get_values(
         target_info,
         arena,
         subs,
@@ -411,9 +602,7 @@ fn render_expect_failure<'a>(
         &expressions,
         expect_region,
         failure_region,
-    )?;
-
-    Ok(offset)
+    )
 }
 
 struct ExpectSequence {
@@ -438,15 +627,30 @@ impl ExpectSequence {
         }
     }
 
-    fn count_failures(&self) -> usize {
+    fn count_frames(&self) -> usize {
         unsafe { *(self.ptr as *const usize).add(Self::COUNT_INDEX) }
     }
+
+    /// Makes room for new frames. The count goes last: a program that waits for its frames to be
+    /// read continues as soon as it sees a count of zero.
+    fn clear(&self) {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        unsafe {
+            let ptr = self.ptr as *mut usize;
+            std::ptr::write_unaligned(ptr.add(Self::OFFSET_INDEX), Self::START_OFFSET);
+
+            let count = &*(ptr.add(Self::COUNT_INDEX) as *const AtomicUsize);
+            count.store(0, Ordering::SeqCst);
+        }
+    }
 }
 
 struct ExpectFrame {
     region: Region,
     module_id: ModuleId,
     start_offset: usize,
+    end_offset: usize,
 }
 
 impl ExpectFrame {
@@ -457,13 +661,16 @@ impl ExpectFrame {
         let module_id_bytes: [u8; 4] = unsafe { *(start.add(offset + 8).cast()) };
         let module_id: ModuleId = unsafe { std::mem::transmute(module_id_bytes) };
 
-        // skip to frame, 8 bytes for region, 4 for module id
-        let start_offset = offset + 12;
+        let end_offset = unsafe { std::ptr::read_unaligned(start.add(offset + 12).cast()) };
+
+        // skip to frame, 8 bytes for region, 4 for module id, 8 for the end of the frame
+        let start_offset = offset + 20;
 
         Self {
             region,
             module_id,
             start_offset,
+            end_offset,
         }
     }
 }
@@ -475,6 +682,15 @@ pub struct ToplevelExpect<'a> {
     pub region: Region,
 }
 
+/// How running a top-level `expect` went. Failures have already been reported by then.
+#[derive(Debug, Clone, Copy)]
+pub struct ExpectOutcome {
+    pub symbol: Symbol,
+    pub passed: bool,
+    /// Includes the time it took to report failures and `dbg`s
+    pub duration: Duration,
+}
+
 #[derive(Debug)]
 pub struct ExpectFunctions<'a> {
     pub pure: BumpVec<'a, ToplevelExpect<'a>>,
@@ -532,6 +748,7 @@ pub fn expect_mono_module_to_dylib<'a>(
         mode,
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
+        debug_sources: Default::default(),
     };
 
     // Add roc_alloc, roc_realloc, and roc_dealloc, since the repl has no
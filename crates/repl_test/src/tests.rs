@@ -659,6 +659,126 @@ fn multiline_input() {
     )
 }
 
+#[test]
+fn def_confirmation() {
+    // The blank line says no final expression is coming
+    expect_success("fortyTwo = 42\n", "fortyTwo : Num *");
+}
+
+#[test]
+fn function_def_confirmation() {
+    expect_success("addOne = \\n -> n + 1\n", "addOne : Num a -> Num a");
+}
+
+#[cfg(not(feature = "wasm"))]
+#[test]
+fn def_used_by_later_input() {
+    expect_success(
+        indoc!(
+            r#"
+                x = 5
+
+                x + 1
+                "#
+        ),
+        "x : Num *\n\n6 : Num *",
+    )
+}
+
+#[cfg(not(feature = "wasm"))]
+#[test]
+fn annotation_used_by_later_def() {
+    expect_success(
+        indoc!(
+            r#"
+                x : U8
+
+                x = 5
+
+                x
+                "#
+        ),
+        "x : U8\n\nx : U8\n\n5 : U8",
+    )
+}
+
+#[cfg(not(feature = "wasm"))]
+#[test]
+fn later_def_replaces_earlier_one() {
+    expect_success(
+        indoc!(
+            r#"
+                x = 5
+
+                x = "five"
+
+                x
+                "#
+        ),
+        "x : Num *\n\nx : Str\n\n\"five\" : Str",
+    )
+}
+
+#[cfg(feature = "wasm")]
+#[test]
+fn def_used_by_later_input() {
+    // The compiler instance is shared between tests, so use a name no other test uses
+    expect_success("laterInputX = 5\n", "laterInputX : Num *");
+    expect_success("laterInputX + 1", "6 : Num *");
+}
+
+#[cfg(not(feature = "wasm"))]
+#[test]
+fn type_command() {
+    expect_success(":type List.map", "List.map : List a, (a -> b) -> List b");
+}
+
+#[cfg(not(feature = "wasm"))]
+#[test]
+fn load_and_browse_commands() {
+    expect_success(
+        indoc!(
+            r#"
+                :load test_modules/Greeting.roc
+                :browse Greeting
+                greet "Roc"
+                "#
+        ),
+        indoc!(
+            r#"
+                Loaded Greeting, which exposes greet, greeting.
+
+                greet : Str -> Str
+                greeting : Str
+
+                "Hello, Roc!" : Str"#
+        ),
+    )
+}
+
+#[cfg(not(feature = "wasm"))]
+#[test]
+fn reset_command() {
+    expect_success(
+        indoc!(
+            r#"
+                :load test_modules/Greeting.roc
+                :reset
+                :browse Greeting
+                "#
+        ),
+        indoc!(
+            r#"
+                Loaded Greeting, which exposes greet, greeting.
+
+                Forgot all defs and loaded modules.
+
+                There is no loaded module named Greeting. Use :load to load it first.
+                "#
+        ),
+    )
+}
+
 #[test]
 fn recursive_tag_union_flat_variant() {
     expect_success(
@@ -899,11 +1019,8 @@ fn parse_problem() {
 
                 I am partway through parsing a definition, but I got stuck here:
 
-                1│  app "app" provides [replOutput] to "./platform"
-                2│
-                3│  replOutput =
-                4│      add m n = m + n
-                            ^^^
+                1│  add m n = m + n
+                        ^^^
 
                 Looks like you are trying to define a function. In roc, functions are
                 always written as a lambda, like increment = \n -> n + 1.
@@ -912,6 +1029,31 @@ fn parse_problem() {
     );
 }
 
+#[cfg(not(feature = "wasm"))] // TODO: mismatch is due to terminal control codes!
+#[test]
+fn unfinished_input_problem() {
+    expect_failure(
+        "[1, 2",
+        indoc!(
+            r#"
+                ── UNFINISHED LIST ─────────────────────────────────────────────────────────────
+
+                I am partway through started parsing a list, but I got stuck here:
+
+                1│  [1, 2
+                         ^
+
+                I was expecting to see a closing square bracket before this, so try
+                adding a ] and see if that helps?
+
+                Note: When I get stuck like this, it usually means that there is a
+                missing parenthesis or bracket somewhere earlier. It could also be a
+                stray keyword or operator.
+                "#
+        ),
+    );
+}
+
 #[cfg(not(feature = "wasm"))] // TODO: mismatch is due to terminal control codes!
 #[test]
 fn exhaustiveness_problem() {
@@ -980,6 +1122,30 @@ fn issue_2343_complete_mono_with_shadowed_vars() {
     );
 }
 
+#[cfg(not(feature = "wasm"))]
+#[test]
+fn warning_shown_with_result() {
+    expect_success(
+        r#"(\x -> 5) 1"#,
+        indoc!(
+            r#"
+                ── UNUSED ARGUMENT ─────────────────────────────────────────────────────────────
+
+                This function doesn't use `x`.
+
+                4│      (\x -> 5) 1
+                          ^
+
+                If you don't need `x`, then you can just remove it. However, if you
+                really do need `x` as an argument of this function, prefix it with an
+                underscore, like this: "_`x`". Adding an underscore at the start of a
+                variable name is a way of saying that the variable is not used.
+
+                5 : Num *"#
+        ),
+    );
+}
+
 #[test]
 fn record_with_type_behind_alias() {
     expect_success(
@@ -0,0 +1,8 @@
+interface Greeting
+    exposes [greet, greeting]
+    imports []
+
+greeting : Str
+greeting = "Hello"
+
+greet = \name -> "\(greeting), \(name)!"
@@ -1,4 +1,5 @@
 use bumpalo::{collections::vec::Vec, Bump};
+use std::cell::RefCell;
 use std::mem::size_of;
 
 use roc_collections::all::MutSet;
@@ -7,7 +8,7 @@ use roc_load::MonomorphizedModule;
 use roc_parse::ast::Expr;
 use roc_repl_eval::{
     eval::jit_to_ast,
-    gen::{compile_to_mono, format_answer, ReplOutput},
+    gen::{format_answer, Compiled, ReplOutput, ReplState},
     ReplApp, ReplAppMemory,
 };
 use roc_reporting::report::DEFAULT_PALETTE_HTML;
@@ -18,6 +19,11 @@ use crate::{js_create_app, js_get_result_and_memory, js_run_app};
 
 const WRAPPER_NAME: &str = "wrapper";
 
+thread_local! {
+    /// The defs entered in earlier inputs. The browser calls the entrypoint once per input.
+    static REPL_STATE: RefCell<ReplState> = RefCell::new(ReplState::new());
+}
+
 pub struct WasmReplApp<'a> {
     arena: &'a Bump,
 }
@@ -175,11 +181,39 @@ pub async fn entrypoint_from_js(src: String) -> Result<String, String> {
 
     // Compile the app
     let target_info = TargetInfo::default_wasm32();
-    let mono = match compile_to_mono(arena, &src, target_info, DEFAULT_PALETTE_HTML) {
-        Ok(m) => m,
+    let compiled = REPL_STATE.with(|state| {
+        state
+            .borrow_mut()
+            .compile(arena, &src, target_info, DEFAULT_PALETTE_HTML)
+    });
+
+    let (compiled, warnings) = match compiled {
+        Ok(success) => success,
         Err(messages) => return Err(messages.join("\n\n")),
     };
 
+    // Warnings don't stop the input from running, so they come before its result.
+    let mut output = String::new();
+
+    for warning in warnings.iter() {
+        output.push_str(warning);
+        output.push_str("\n\n");
+    }
+
+    let mono = match compiled {
+        Compiled::Expr(m) => m,
+        Compiled::Defs(names_and_types) => {
+            let lines: std::vec::Vec<String> = names_and_types
+                .iter()
+                .map(|(name, type_str)| format!("{} : {}", name, type_str))
+                .collect();
+
+            output.push_str(&lines.join("\n"));
+
+            return Ok(output);
+        }
+    };
+
     let MonomorphizedModule {
         module_id,
         procedures,
@@ -207,7 +241,11 @@ pub async fn entrypoint_from_js(src: String) -> Result<String, String> {
 
     let (_, main_fn_layout) = match procedures.keys().find(|(s, _)| *s == main_fn_symbol) {
         Some(layout) => *layout,
-        None => return Ok(format!("<function> : {}", expr_type_str)),
+        None => {
+            output.push_str(&format!("<function> : {}", expr_type_str));
+
+            return Ok(output);
+        }
     };
 
     let app_module_bytes = {
@@ -274,7 +312,11 @@ pub async fn entrypoint_from_js(src: String) -> Result<String, String> {
     // Transform the Expr to a string
     // `Result::Err` becomes a JS exception that will be caught and displayed
     match format_answer(arena, res_answer, expr_type_str) {
-        ReplOutput::NoProblems { expr, expr_type } => Ok(format!("{} : {}", expr, expr_type)),
+        ReplOutput::NoProblems { expr, expr_type } => {
+            output.push_str(&format!("{} : {}", expr, expr_type));
+
+            Ok(output)
+        }
         ReplOutput::Problems(lines) => Err(format!("\n{}\n", lines.join("\n\n"))),
     }
 }
@@ -3,7 +3,8 @@ use roc_module::ident::{Ident, Lowercase, ModuleName};
 use roc_module::symbol::DERIVABLE_ABILITIES;
 use roc_problem::can::PrecedenceProblem::BothNonAssociative;
 use roc_problem::can::{
-    BadPattern, ExtensionTypeKind, FloatErrorKind, IntErrorKind, Problem, RuntimeError, ShadowKind,
+    BadPattern, DbgIgnoredReason, ExtensionTypeKind, FloatErrorKind, IntErrorKind, Problem,
+    RuntimeError, ShadowKind,
 };
 use roc_region::all::{LineColumn, LineColumnRegion, LineInfo, Loc, Region};
 use roc_types::types::AliasKind;
@@ -586,6 +587,7 @@ pub fn can_problem<'b>(
                 ExtensionTypeKind::TagUnion => {
                     ("tag union", "a type variable or another tag union")
                 }
+                ExtensionTypeKind::Tuple => ("tuple", "a type variable or another tuple"),
             };
 
             doc = alloc.stack([
@@ -977,6 +979,32 @@ pub fn can_problem<'b>(
             title = "OVERLOADED SPECIALIZATION".to_string();
             severity = Severity::Warning;
         }
+        Problem::DbgIgnored { region, reason } => {
+            let explanation = match reason {
+                DbgIgnoredReason::Optimize => alloc.concat([
+                    alloc.reflow("Programs built with "),
+                    alloc.keyword("--optimize"),
+                    alloc.reflow(" leave out every "),
+                    alloc.keyword("dbg"),
+                    alloc.reflow(". Build without it to see this value."),
+                ]),
+                DbgIgnoredReason::Unsupported => {
+                    alloc.reflow("Values are only shown by builds for 64-bit Linux.")
+                }
+            };
+
+            doc = alloc.stack([
+                alloc.concat([
+                    alloc.reflow("This "),
+                    alloc.keyword("dbg"),
+                    alloc.reflow(" will not show anything when the program runs:"),
+                ]),
+                alloc.region(lines.convert_region(region)),
+                explanation,
+            ]);
+            title = "DBG IGNORED".to_string();
+            severity = Severity::Warning;
+        }
     };
 
     Report {
@@ -1442,6 +1470,7 @@ fn pretty_runtime_error<'b>(
                 QualifiedIdentifier => " qualified ",
                 EmptySingleQuote => " empty character literal ",
                 MultipleCharsInSingleQuote => " overfull literal ",
+                DuplicateListRestPattern => " list ",
             };
 
             let tip = match problem {
@@ -1454,6 +1483,11 @@ fn pretty_runtime_error<'b>(
                 QualifiedIdentifier => alloc
                     .tip()
                     .append(alloc.reflow("In patterns, only tags can be qualified")),
+                DuplicateListRestPattern => alloc.tip().append(alloc.concat([
+                    alloc.reflow("A list pattern can only have one "),
+                    alloc.parser_suggestion(".."),
+                    alloc.reflow(", which stands for the rest of the list."),
+                ])),
             };
 
             doc = alloc.stack([
@@ -1555,6 +1589,15 @@ fn pretty_runtime_error<'b>(
         RuntimeError::MalformedClosure(_) => {
             todo!("");
         }
+        RuntimeError::MalformedExpr(region) => {
+            // the syntax error itself is reported by the parser
+            doc = alloc.stack([
+                alloc.reflow("This definition could not be parsed:"),
+                alloc.region(lines.convert_region(region)),
+            ]);
+
+            title = SYNTAX_PROBLEM;
+        }
         RuntimeError::InvalidFloat(sign @ FloatErrorKind::PositiveInfinity, region, _raw_str)
         | RuntimeError::InvalidFloat(sign @ FloatErrorKind::NegativeInfinity, region, _raw_str) => {
             let tip = alloc
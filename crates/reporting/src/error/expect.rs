@@ -15,6 +15,7 @@ pub struct Renderer<'a> {
     arena: &'a Bump,
     alloc: RocDocAllocator<'a>,
     filename: PathBuf,
+    source: &'a str,
     line_info: LineInfo,
     render_target: RenderTarget,
 }
@@ -38,6 +39,7 @@ impl<'a> Renderer<'a> {
             alloc,
             line_info,
             filename,
+            source,
             render_target,
         }
     }
@@ -159,7 +161,7 @@ impl<'a> Renderer<'a> {
             &crate::report::DEFAULT_PALETTE,
         );
 
-        write!(writer, "{}", buf)
+        self.write_report(writer, &buf)
     }
 
     pub fn render_panic<W>(
@@ -199,6 +201,47 @@ impl<'a> Renderer<'a> {
             &crate::report::DEFAULT_PALETTE,
         );
 
-        write!(writer, "{}", buf)
+        self.write_report(writer, &buf)
+    }
+
+    fn write_report<W>(&self, writer: &mut W, report: &str) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        match self.render_target {
+            // every JSON report goes on its own line
+            RenderTarget::Json => writeln!(writer, "{}", report),
+            RenderTarget::ColorTerminal | RenderTarget::Generic => write!(writer, "{}", report),
+        }
+    }
+
+    /// Prints the value of a `dbg` on a single line, prefixed with its location,
+    /// e.g. `[Test.roc:4] List.len list = 3`
+    pub fn render_dbg<W>(
+        &self,
+        writer: &mut W,
+        expr: &Expr<'_>,
+        dbg_region: Region,
+    ) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        use roc_fmt::annotation::Formattable;
+
+        let line = self.line_info.convert_pos(dbg_region.start()).line + 1;
+        let dbg_source =
+            &self.source[dbg_region.start().offset as usize..dbg_region.end().offset as usize];
+
+        let mut buf = roc_fmt::Buf::new_in(self.arena);
+        expr.format(&mut buf, 0);
+
+        writeln!(
+            writer,
+            "[{}:{}] {} = {}",
+            self.filename.display(),
+            line,
+            dbg_source,
+            buf.into_bump_str()
+        )
     }
 }
@@ -988,6 +988,35 @@ fn to_expr_in_parens_report<'a>(
                 severity: Severity::RuntimeError,
             }
         }
+        EInParens::Empty(pos) => {
+            let surroundings = Region::new(start, pos);
+            let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
+
+            let doc = alloc.stack([
+                alloc.reflow("I am partway through parsing a parenthesized expression or tuple:"),
+                alloc.region_with_subregion(lines.convert_region(surroundings), region),
+                alloc.concat([
+                    alloc.reflow(
+                        r"I was expecting to see an expression inside these parentheses, like ",
+                    ),
+                    alloc.parser_suggestion("(32)"),
+                    alloc.reflow(r" or "),
+                    alloc.parser_suggestion("(1, 2)"),
+                    alloc.reflow(". Note that Roc does not use "),
+                    alloc.parser_suggestion("()"),
+                    alloc.reflow(" as a unit value; try the empty record "),
+                    alloc.parser_suggestion("{}"),
+                    alloc.reflow(" instead."),
+                ]),
+            ]);
+
+            Report {
+                filename,
+                doc,
+                title: "EMPTY PARENTHESES".to_string(),
+                severity: Severity::RuntimeError,
+            }
+        }
         EInParens::Open(pos) | EInParens::IndentOpen(pos) => {
             let surroundings = Region::new(start, pos);
             let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
@@ -1579,6 +1608,7 @@ fn to_pattern_report<'a>(
         EPattern::PInParens(inparens, pos) => {
             to_pattern_in_parens_report(alloc, lines, filename, inparens, *pos)
         }
+        EPattern::List(list, pos) => to_plist_report(alloc, lines, filename, list, *pos),
         &EPattern::NumLiteral(ENumber::End, pos) => {
             to_malformed_number_literal_report(alloc, lines, filename, pos)
         }
@@ -1897,6 +1927,36 @@ fn to_pattern_in_parens_report<'a>(
             }
         }
 
+        PInParens::Empty(pos) => {
+            let surroundings = Region::new(start, pos);
+            let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
+
+            let doc = alloc.stack([
+                alloc.reflow(
+                    "I am partway through parsing a pattern in parentheses, but I got stuck here:",
+                ),
+                alloc.region_with_subregion(lines.convert_region(surroundings), region),
+                alloc.concat([
+                    alloc.reflow(
+                        r"I was expecting to see a pattern inside these parentheses, like ",
+                    ),
+                    alloc.parser_suggestion("(x, y)"),
+                    alloc.reflow(". Note that Roc does not use "),
+                    alloc.parser_suggestion("()"),
+                    alloc.reflow(" as a unit value; try the empty record pattern "),
+                    alloc.parser_suggestion("{}"),
+                    alloc.reflow(" instead."),
+                ]),
+            ]);
+
+            Report {
+                filename,
+                doc,
+                title: "EMPTY PARENTHESES".to_string(),
+                severity: Severity::RuntimeError,
+            }
+        }
+
         PInParens::Pattern(pattern, pos) => to_pattern_report(alloc, lines, filename, pattern, pos),
 
         PInParens::IndentOpen(pos) => {
@@ -1975,6 +2035,102 @@ fn to_pattern_in_parens_report<'a>(
     }
 }
 
+fn to_plist_report<'a>(
+    alloc: &'a RocDocAllocator<'a>,
+    lines: &LineInfo,
+    filename: PathBuf,
+    parse_problem: &roc_parse::parser::PList<'a>,
+    start: Position,
+) -> Report<'a> {
+    use roc_parse::parser::PList;
+
+    match *parse_problem {
+        PList::Open(pos) | PList::IndentOpen(pos) => {
+            let surroundings = Region::new(start, pos);
+            let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
+
+            let doc = alloc.stack([
+                alloc.reflow(r"I just started parsing a list pattern, but I got stuck here:"),
+                alloc.region_with_subregion(lines.convert_region(surroundings), region),
+                list_patterns_look_like(alloc),
+            ]);
+
+            Report {
+                filename,
+                doc,
+                title: "UNFINISHED LIST PATTERN".to_string(),
+                severity: Severity::RuntimeError,
+            }
+        }
+
+        PList::End(pos) | PList::IndentEnd(pos) => {
+            let surroundings = Region::new(start, pos);
+            let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
+
+            let doc = alloc.stack([
+                alloc.reflow("I am partway through parsing a list pattern, but I got stuck here:"),
+                alloc.region_with_subregion(lines.convert_region(surroundings), region),
+                alloc.concat([
+                    alloc.reflow(
+                        r"I was expecting to see a closing square bracket before this, so try adding a ",
+                    ),
+                    alloc.parser_suggestion("]"),
+                    alloc.reflow(" and see if that helps?"),
+                ]),
+            ]);
+
+            Report {
+                filename,
+                doc,
+                title: "UNFINISHED LIST PATTERN".to_string(),
+                severity: Severity::RuntimeError,
+            }
+        }
+
+        PList::Rest(pos) => {
+            let surroundings = Region::new(start, pos);
+            let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
+
+            let doc = alloc.stack([
+                alloc.reflow("I am partway through parsing a list pattern, but I got stuck here:"),
+                alloc.region_with_subregion(lines.convert_region(surroundings), region),
+                alloc.concat([
+                    alloc.reflow(r"I was expecting a name for the rest of the list after "),
+                    alloc.keyword("as"),
+                    alloc.reflow(", like "),
+                    alloc.parser_suggestion(".. as rest"),
+                    alloc.reflow("."),
+                ]),
+            ]);
+
+            Report {
+                filename,
+                doc,
+                title: "UNFINISHED LIST PATTERN".to_string(),
+                severity: Severity::RuntimeError,
+            }
+        }
+
+        PList::Pattern(pattern, pos) => to_pattern_report(alloc, lines, filename, pattern, pos),
+
+        PList::Space(error, pos) => to_space_report(alloc, lines, filename, &error, pos),
+    }
+}
+
+fn list_patterns_look_like<'a>(alloc: &'a RocDocAllocator<'a>) -> RocDocBuilder<'a> {
+    alloc.concat([
+        alloc.reflow(r"A list pattern looks like "),
+        alloc.parser_suggestion("[]"),
+        alloc.reflow(", "),
+        alloc.parser_suggestion("[x]"),
+        alloc.reflow(", "),
+        alloc.parser_suggestion("[first, .. as rest]"),
+        alloc.reflow(" or "),
+        alloc.parser_suggestion("[.., last]"),
+        alloc.reflow("."),
+    ])
+}
+
 fn to_malformed_number_literal_report<'a>(
     alloc: &'a RocDocAllocator<'a>,
     lines: &LineInfo,
@@ -2721,6 +2877,34 @@ fn to_tinparens_report<'a>(
             }
         }
 
+        ETypeInParens::Empty(pos) => {
+            let surroundings = Region::new(start, pos);
+            let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
+
+            let doc = alloc.stack([
+                alloc.reflow(
+                    r"I am partway through parsing a type in parentheses, but I got stuck here:",
+                ),
+                alloc.region_with_subregion(lines.convert_region(surroundings), region),
+                alloc.concat([
+                    alloc.reflow(r"I was expecting to see a type inside these parentheses, like "),
+                    alloc.parser_suggestion("(Str, U64)"),
+                    alloc.reflow(". Note that Roc does not have a "),
+                    alloc.parser_suggestion("()"),
+                    alloc.reflow(" type; try the empty record type "),
+                    alloc.parser_suggestion("{}"),
+                    alloc.reflow(" instead."),
+                ]),
+            ]);
+
+            Report {
+                filename,
+                doc,
+                title: "EMPTY PARENTHESES".to_string(),
+                severity: Severity::RuntimeError,
+            }
+        }
+
         ETypeInParens::Type(tipe, pos) => to_type_report(alloc, lines, filename, tipe, pos),
 
         ETypeInParens::IndentOpen(pos) => {
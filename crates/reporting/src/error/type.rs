@@ -1705,6 +1705,17 @@ fn format_category<'b>(
             ]),
             alloc.text(" is a:"),
         ),
+        Tuple => (
+            alloc.concat([this_is, alloc.text(" a tuple")]),
+            alloc.text(" of type:"),
+        ),
+        TupleAccess(index) => (
+            alloc.concat([
+                alloc.text(format!("{}he value at ", t)),
+                alloc.text(format!(".{}", index)),
+            ]),
+            alloc.text(" is a:"),
+        ),
         CallResult(
             Some(_),
             CalledVia::BinOp(
@@ -1930,6 +1941,30 @@ fn to_pattern_report<'b>(
                     severity: Severity::RuntimeError,
                 }
             }
+            PReason::ListElem => {
+                let doc = alloc.stack([
+                    alloc.reflow("This list element doesn't match the types of other elements in the pattern:"),
+                    alloc.region_with_subregion(
+                        lines.convert_region(region),
+                        lines.convert_region(expr_region),
+                    ),
+                    pattern_type_comparison(
+                        alloc,
+                        found,
+                        expected_type,
+                        add_pattern_category(alloc, alloc.text("It matches"), &category),
+                        alloc.text("But the other elements in this list pattern match:"),
+                        vec![],
+                    ),
+                ]);
+
+                Report {
+                    filename,
+                    title: "TYPE MISMATCH".to_string(),
+                    doc,
+                    severity: Severity::RuntimeError,
+                }
+            }
             PReason::TagArg { .. } | PReason::PatternGuard => {
                 unreachable!("I didn't think this could trigger. Please tell Folkert about it!")
             }
@@ -1974,6 +2009,8 @@ fn add_pattern_category<'b>(
     let rest = match category {
         Record => alloc.reflow(" record values of type:"),
         EmptyRecord => alloc.reflow(" an empty record:"),
+        Tuple => alloc.reflow(" tuple values of type:"),
+        List => alloc.reflow(" lists of type:"),
         PatternGuard => alloc.reflow(" a pattern guard of type:"),
         PatternDefault => alloc.reflow(" an optional field of type:"),
         Set => alloc.reflow(" sets of type:"),
@@ -2321,6 +2358,15 @@ fn to_doc_help<'b>(
             )
         }
 
+        Tuple(elems, ext) => report_text::tuple(
+            alloc,
+            elems
+                .into_iter()
+                .map(|(_, elem)| to_doc_help(ctx, alloc, Parens::Unnecessary, elem))
+                .collect(),
+            ext_to_doc(alloc, ext),
+        ),
+
         TagUnion(tags_map, ext) => {
             let mut tags = tags_map
                 .into_iter()
@@ -3249,6 +3295,24 @@ mod report_text {
         }
     }
 
+    pub fn tuple<'b>(
+        alloc: &'b RocDocAllocator<'b>,
+        entries: Vec<RocDocBuilder<'b>>,
+        opt_ext: Option<RocDocBuilder<'b>>,
+    ) -> RocDocBuilder<'b> {
+        let ext_doc = if let Some(t) = opt_ext {
+            t
+        } else {
+            alloc.nil()
+        };
+
+        alloc
+            .text("(")
+            .append(alloc.intersperse(entries, alloc.reflow(", ")))
+            .append(alloc.text(")"))
+            .append(ext_doc)
+    }
+
     pub fn to_suggestion_record<'b>(
         alloc: &'b RocDocAllocator<'b>,
         f: (Lowercase, RecordField<ErrorType>),
@@ -3594,6 +3658,7 @@ fn type_problem_to_pretty<'b>(
                 RigidVar(y) | RigidAbleVar(y, _) => bad_double_rigid(x, y),
                 Function(_, _, _) => bad_rigid_var(x, alloc.reflow("a function value")),
                 Record(_, _) => bad_rigid_var(x, alloc.reflow("a record value")),
+                Tuple(_, _) => bad_rigid_var(x, alloc.reflow("a tuple value")),
                 TagUnion(_, _) | RecursiveTagUnion(_, _, _) => {
                     bad_rigid_var(x, alloc.reflow("a tag value"))
                 }
@@ -3986,7 +4051,7 @@ fn pattern_to_doc_help<'b>(
                             Anything => {
                                 arg_docs.push(alloc.text(label.to_string()));
                             }
-                            Literal(_) | Ctor(_, _, _) => {
+                            Literal(_) | Ctor(_, _, _) | List(_, _) => {
                                 arg_docs.push(
                                     alloc
                                         .text(label.to_string())
@@ -4002,6 +4067,16 @@ fn pattern_to_doc_help<'b>(
                         .append(alloc.intersperse(arg_docs, alloc.reflow(", ")))
                         .append(" }")
                 }
+                RenderAs::Tuple => {
+                    let arg_docs = args
+                        .into_iter()
+                        .map(|v| pattern_to_doc_help(alloc, v, false));
+
+                    alloc
+                        .text("(")
+                        .append(alloc.intersperse(arg_docs, alloc.reflow(", ")))
+                        .append(")")
+                }
                 RenderAs::Tag | RenderAs::Opaque => {
                     let ctor = &union.alternatives[tag_id.0 as usize];
                     match &ctor.name {
@@ -4044,5 +4119,30 @@ fn pattern_to_doc_help<'b>(
                 }
             }
         }
+        List(arity, mut args) => {
+            let rest = match arity {
+                roc_exhaustive::ListArity::Exact(_) => None,
+                roc_exhaustive::ListArity::Slice(before, _) => Some(args.split_off(before)),
+            };
+
+            let mut arg_docs: Vec<_> = args
+                .into_iter()
+                .map(|v| pattern_to_doc_help(alloc, v, false))
+                .collect();
+
+            if let Some(suffix) = rest {
+                arg_docs.push(alloc.text(".."));
+                arg_docs.extend(
+                    suffix
+                        .into_iter()
+                        .map(|v| pattern_to_doc_help(alloc, v, false)),
+                );
+            }
+
+            alloc
+                .text("[")
+                .append(alloc.intersperse(arg_docs, alloc.reflow(", ")))
+                .append("]")
+        }
     }
 }
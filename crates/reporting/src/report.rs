@@ -115,6 +115,8 @@ pub enum Severity {
 pub enum RenderTarget {
     ColorTerminal,
     Generic,
+    /// One JSON object per report, for tools that consume diagnostics (e.g. CI annotators)
+    Json,
 }
 
 /// A textual report.
@@ -136,11 +138,12 @@ impl<'b> Report<'b> {
         match target {
             RenderTarget::Generic => self.render_ci(buf, alloc),
             RenderTarget::ColorTerminal => self.render_color_terminal(buf, alloc, palette),
+            RenderTarget::Json => self.render_json(buf),
         }
     }
 
     /// Render to CI console output, where no colors are available.
-    pub fn render_ci(self, buf: &'b mut String, alloc: &'b RocDocAllocator<'b>) {
+    pub fn render_ci(self, buf: &mut String, alloc: &'b RocDocAllocator<'b>) {
         let err_msg = "<buffer is not a utf-8 encoded string>";
 
         self.pretty(alloc)
@@ -164,6 +167,53 @@ impl<'b> Report<'b> {
             .expect(err_msg);
     }
 
+    /// Render as a single line of JSON. The message is rendered like [Report::render_ci],
+    /// but without the header; its title, severity and file are separate fields instead.
+    ///
+    /// The region is the first source snippet the report shows, with lines and columns
+    /// starting at 1, or `null` if the report does not point at any source code.
+    /// The hints are the paragraphs of the message that give a tip on how to fix the problem.
+    pub fn render_json(self, buf: &mut String) {
+        let err_msg = "<buffer is not a utf-8 encoded string>";
+
+        let mut message = String::new();
+        let mut writer = JsonWrite::new(&mut message);
+
+        self.doc.1.render_raw(70, &mut writer).expect(err_msg);
+
+        let region = writer.region.map(|region| {
+            let start = region.start();
+            let end = region.end();
+
+            serde_json::json!({
+                "start": { "line": start.line + 1, "column": start.column + 1 },
+                "end": { "line": end.line + 1, "column": end.column + 1 },
+            })
+        });
+
+        let hints: Vec<&str> = message
+            .split("\n\n")
+            .map(str::trim)
+            .filter(|paragraph| paragraph.starts_with("Tip:") || paragraph.starts_with("Hint:"))
+            .collect();
+
+        let severity = match self.severity {
+            Severity::RuntimeError => "error",
+            Severity::Warning => "warning",
+        };
+
+        let json = serde_json::json!({
+            "title": self.title,
+            "severity": severity,
+            "file": self.filename.to_string_lossy(),
+            "region": region,
+            "message": message.trim(),
+            "hints": hints,
+        });
+
+        buf.push_str(&json.to_string());
+    }
+
     pub fn pretty(self, alloc: &'b RocDocAllocator<'b>) -> RocDocBuilder<'b> {
         if self.title.is_empty() {
             self.doc
@@ -621,7 +671,9 @@ impl<'a> RocDocAllocator<'a> {
             result = result.append(highlight_line);
         }
 
-        result.annotate(Annotation::CodeBlock)
+        result
+            .annotate(Annotation::CodeBlock)
+            .annotate(Annotation::SourceRegion(sub_region1))
     }
 
     pub fn region_with_subregion(
@@ -714,7 +766,7 @@ impl<'a> RocDocAllocator<'a> {
             result = result.append(highlight_line);
         }
 
-        result
+        result.annotate(Annotation::SourceRegion(sub_region))
     }
 
     pub fn region(&'a self, region: LineColumnRegion) -> DocBuilder<'a, Self, Annotation> {
@@ -821,6 +873,9 @@ pub enum Annotation {
     Tip,
     Header,
     ParserSuggestion,
+    /// Marks a snippet of source code, and the region in it the report is about.
+    /// This does not affect how the snippet looks.
+    SourceRegion(LineColumnRegion),
 }
 
 /// Render with minimal formatting
@@ -859,6 +914,21 @@ impl<'a, W> ColorWrite<'a, W> {
     }
 }
 
+/// Render like [CiWrite], and remember the first region of source code that is shown
+pub struct JsonWrite<W> {
+    ci: CiWrite<W>,
+    region: Option<LineColumnRegion>,
+}
+
+impl<W> JsonWrite<W> {
+    pub fn new(upstream: W) -> JsonWrite<W> {
+        JsonWrite {
+            ci: CiWrite::new(upstream),
+            region: None,
+        }
+    }
+}
+
 impl<W> Render for CiWrite<W>
 where
     W: fmt::Write,
@@ -946,6 +1016,38 @@ where
     }
 }
 
+impl<W> Render for JsonWrite<W>
+where
+    W: fmt::Write,
+{
+    type Error = fmt::Error;
+
+    fn write_str(&mut self, s: &str) -> Result<usize, fmt::Error> {
+        self.ci.write_str(s)
+    }
+
+    fn write_str_all(&mut self, s: &str) -> fmt::Result {
+        self.ci.write_str_all(s)
+    }
+}
+
+impl<W> RenderAnnotated<Annotation> for JsonWrite<W>
+where
+    W: fmt::Write,
+{
+    fn push_annotation(&mut self, annotation: &Annotation) -> Result<(), Self::Error> {
+        if let (Annotation::SourceRegion(region), None) = (annotation, self.region) {
+            self.region = Some(*region);
+        }
+
+        self.ci.push_annotation(annotation)
+    }
+
+    fn pop_annotation(&mut self) -> Result<(), Self::Error> {
+        self.ci.pop_annotation()
+    }
+}
+
 impl<'a, W> Render for ColorWrite<'a, W>
 where
     W: fmt::Write,
@@ -1026,6 +1128,7 @@ where
                 self.write_str(self.palette.parser_suggestion)?;
             }
             TypeBlock | InlineTypeBlock | Tag | RecordField => { /* nothing yet */ }
+            SourceRegion(_) => {}
         }
         self.style_stack.push(*annotation);
         Ok(())
@@ -1044,6 +1147,7 @@ where
                 }
 
                 TypeBlock | InlineTypeBlock | Tag | Opaque | RecordField => { /* nothing yet */ }
+                SourceRegion(_) => {}
             },
         }
         Ok(())
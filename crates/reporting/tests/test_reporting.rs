@@ -88,6 +88,7 @@ mod test_reporting {
                 render: RenderTarget::Generic,
                 threading: Threading::Single,
                 exec_mode: ExecutionMode::Check,
+                cache_dir: None,
             };
             let result =
                 roc_load::load_and_typecheck(arena, full_file_path, exposed_types, load_config);
@@ -117,12 +118,18 @@ mod test_reporting {
         let (module_src, result) = run_load_and_infer(subdir, arena, expr_src);
         let LoadedModule {
             module_id: home,
+            mut syntax_problems,
             mut can_problems,
             mut type_problems,
             interns,
             ..
         } = result?;
 
+        // syntax errors come pre-rendered, like a problem that stops the load
+        if let Some(reports) = syntax_problems.remove(&home) {
+            return Err(LoadingProblem::FormattedReport(reports.concat()));
+        }
+
         let can_problems = can_problems.remove(&home).unwrap_or_default();
         let type_problems = type_problems.remove(&home).unwrap_or_default();
 
@@ -767,6 +774,100 @@ mod test_reporting {
         );
     }
 
+    #[test]
+    fn report_region_as_json() {
+        let src: &str = indoc!(
+            r#"
+                isDisabled = \user -> user.isAdmin
+
+                theAdmin
+                    |> isDisabled
+            "#
+        );
+
+        let arena = Bump::new();
+        let (_type_problems, can_problems, home, interns) =
+            infer_expr_help(&arena, src).expect("parse error");
+
+        let src_lines: Vec<&str> = src.split('\n').collect();
+        let lines = LineInfo::new(src);
+        let filename = filename_from_string(r"/code/proj/Main.roc");
+
+        let alloc = RocDocAllocator::new(&src_lines, home, &interns);
+
+        let mut buf = String::new();
+        can_problem(&alloc, &lines, filename, can_problems[0].clone()).render_json(&mut buf);
+
+        let json: serde_json::Value = serde_json::from_str(&buf).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "title": "UNRECOGNIZED NAME",
+                "severity": "error",
+                "file": "/code/proj/Main.roc",
+                "region": {
+                    "start": { "line": 3, "column": 1 },
+                    "end": { "line": 3, "column": 9 },
+                },
+                "message": "Nothing is named `theAdmin` in this scope.\n\n3│  theAdmin\n    ^^^^^^^^\n\nDid you mean one of these?\n\n    List\n    True\n    Box\n    Str",
+                "hints": [],
+            })
+        );
+        assert!(!buf.contains('\n'), "a report is a single line of JSON");
+    }
+
+    #[test]
+    fn report_hints_as_json() {
+        let src: &str = indoc!(
+            r#"
+                f : [Red, Green] -> [Yes, No]
+                f = \_ -> Yes
+
+                f Blue
+            "#
+        );
+
+        let arena = Bump::new();
+        let (type_problems, _can_problems, home, interns) =
+            infer_expr_help(&arena, src).expect("parse error");
+
+        let src_lines: Vec<&str> = src.split('\n').collect();
+        let lines = LineInfo::new(src);
+        let filename = filename_from_string(r"/code/proj/Main.roc");
+
+        let alloc = RocDocAllocator::new(&src_lines, home, &interns);
+
+        let mut buf = String::new();
+        type_problem(&alloc, &lines, filename, type_problems[0].clone())
+            .unwrap()
+            .render_json(&mut buf);
+
+        let json: serde_json::Value = serde_json::from_str(&buf).unwrap();
+        let hints: Vec<&str> = json["hints"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|hint| hint.as_str().unwrap())
+            .collect();
+
+        assert_eq!(json["title"], "TYPE MISMATCH");
+        assert_eq!(
+            json["region"],
+            serde_json::json!({
+                "start": { "line": 4, "column": 3 },
+                "end": { "line": 4, "column": 7 },
+            })
+        );
+        assert_eq!(hints.len(), 2);
+        assert_eq!(
+            hints[0],
+            "Tip: Seems like a tag typo. Maybe `Blue` should be `Red`?"
+        );
+        assert!(hints[1].starts_with("Tip: Can more type annotations be added?"));
+        assert!(json["message"].as_str().unwrap().contains(hints[1]));
+    }
+
     test_report!(
         if_condition_not_bool,
         indoc!(
@@ -2098,7 +2199,7 @@ mod test_reporting {
         Ok
         U8
         Box
-        f
+        Eq
     "###
     );
 
@@ -2745,6 +2846,149 @@ mod test_reporting {
     "###
     );
 
+    test_report!(
+        list_pattern_not_exhaustive,
+        indoc!(
+            r#"
+            when [] is
+                [] -> {}
+                [_] -> {}
+            "#
+        ),
+        @r###"
+    ── UNSAFE PATTERN ──────────────────────────────────────── /code/proj/Main.roc ─
+
+    This `when` does not cover all the possibilities:
+
+    4│>      when [] is
+    5│>          [] -> {}
+    6│>          [_] -> {}
+
+    Other possibilities include:
+
+        [_, _, ..]
+
+    I would have to crash if I saw one of those! Add branches for them!
+    "###
+    );
+
+    test_report!(
+        list_pattern_rest_not_exhaustive,
+        indoc!(
+            r#"
+            when [] is
+                [_, ..] -> {}
+                [A, .., B] -> {}
+            "#
+        ),
+        @r###"
+    ── REDUNDANT PATTERN ───────────────────────────────────── /code/proj/Main.roc ─
+
+    The 2nd pattern is redundant:
+
+    4│      when [] is
+    5│          [_, ..] -> {}
+    6│          [A, .., B] -> {}
+                ^^^^^^^^^^
+
+    Any value of this shape will be handled by a previous pattern, so this
+    one should be removed.
+
+    ── UNSAFE PATTERN ──────────────────────────────────────── /code/proj/Main.roc ─
+
+    This `when` does not cover all the possibilities:
+
+    4│>      when [] is
+    5│>          [_, ..] -> {}
+    6│>          [A, .., B] -> {}
+
+    Other possibilities include:
+
+        []
+
+    I would have to crash if I saw one of those! Add branches for them!
+    "###
+    );
+
+    test_report!(
+        list_pattern_redundant,
+        indoc!(
+            r#"
+            when [] is
+                [_, ..] -> {}
+                [] -> {}
+                [.., _] -> {}
+            "#
+        ),
+        @r###"
+    ── REDUNDANT PATTERN ───────────────────────────────────── /code/proj/Main.roc ─
+
+    The 3rd pattern is redundant:
+
+    4│      when [] is
+    5│          [_, ..] -> {}
+    6│          [] -> {}
+    7│          [.., _] -> {}
+                ^^^^^^^
+
+    Any value of this shape will be handled by a previous pattern, so this
+    one should be removed.
+    "###
+    );
+
+    test_report!(
+        list_pattern_exact_after_rest_redundant,
+        indoc!(
+            r#"
+            when [] is
+                [.., x] -> x
+                [x, y] -> x + y
+                [] -> 0
+            "#
+        ),
+        @r###"
+    ── REDUNDANT PATTERN ───────────────────────────────────── /code/proj/Main.roc ─
+
+    The 2nd pattern is redundant:
+
+    4│       when [] is
+    5│           [.., x] -> x
+    6│>          [x, y] -> x + y
+    7│           [] -> 0
+
+    Any value of this shape will be handled by a previous pattern, so this
+    one should be removed.
+    "###
+    );
+
+    test_report!(
+        list_pattern_element_mismatch,
+        indoc!(
+            r#"
+            when [] is
+                [1, ""] -> {}
+                _ -> {}
+            "#
+        ),
+        @r###"
+    ── TYPE MISMATCH ───────────────────────────────────────── /code/proj/Main.roc ─
+
+    This list element doesn't match the types of other elements in the
+    pattern:
+
+    5│          [1, ""] -> {}
+                    ^^
+
+    It matches strings:
+
+        Str
+
+    But the other elements in this list pattern match:
+
+        Num a
+    "###
+    );
+
     test_report!(
         unify_alias_other,
         indoc!(
@@ -3616,7 +3860,7 @@ mod test_reporting {
         Set
         List
         Dict
-        Result
+        Hash
 
     ── SYNTAX PROBLEM ──────────────────────────────────────── /code/proj/Main.roc ─
 
@@ -5912,7 +6156,9 @@ All branches in an `if` must have the same type!
     here:
 
     4│      \( a,
-                ^
+    5│
+    6│
+        ^
 
     I was expecting to see a closing parenthesis before this, so try
     adding a ) and see if that helps?
@@ -6247,7 +6493,7 @@ All branches in an `if` must have the same type!
         indoc!(
             r#"
             f : a -> (_ -> b)
-            f = \x -> \y -> if x == y then x else y
+            f = \x -> \y -> if True then x else y
             f
             "#
         ),
@@ -6258,8 +6504,8 @@ All branches in an `if` must have the same type!
     Something is off with the body of the `f` definition:
 
     4│      f : a -> (_ -> b)
-    5│      f = \x -> \y -> if x == y then x else y
-                      ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+    5│      f = \x -> \y -> if True then x else y
+                      ^^^^^^^^^^^^^^^^^^^^^^^^^^^
 
     The body is an anonymous function of type:
 
@@ -8320,23 +8566,23 @@ All branches in an `if` must have the same type!
             r#"
             app "test" provides [] to "./platform"
 
-            Eq has eq : a, b -> Bool.Bool | a has Eq, b has Eq
+            MEq has eq : a, b -> Bool.Bool | a has MEq, b has MEq
             "#
         ),
         @r#"
         ── ABILITY MEMBER BINDS MULTIPLE VARIABLES ─────────────── /code/proj/Main.roc ─
 
         The definition of the ability member `eq` includes multiple variables
-        bound to the `Eq`` ability:`
+        bound to the `MEq`` ability:`
 
-        3│  Eq has eq : a, b -> Bool.Bool | a has Eq, b has Eq
-                                            ^^^^^^^^^^^^^^^^^^
+        3│  MEq has eq : a, b -> Bool.Bool | a has MEq, b has MEq
+                                             ^^^^^^^^^^^^^^^^^^^^
 
         Ability members can only bind one type variable to their parent
         ability. Otherwise, I wouldn't know what type implements an ability by
         looking at specializations!
 
-        Hint: Did you mean to only bind `a` to `Eq`?
+        Hint: Did you mean to only bind `a` to `MEq`?
         "#
     );
 
@@ -8415,11 +8661,11 @@ All branches in an `if` must have the same type!
             r#"
             app "test" provides [eq, le] to "./platform"
 
-            Eq has
-                eq : a, a -> Bool | a has Eq
-                le : a, a -> Bool | a has Eq
+            MEq has
+                eq : a, a -> Bool | a has MEq
+                le : a, a -> Bool | a has MEq
 
-            Id := U64 has [Eq {eq}]
+            Id := U64 has [MEq {eq}]
 
             eq = \@Id m, @Id n -> m == n
             "#
@@ -8427,10 +8673,10 @@ All branches in an `if` must have the same type!
         @r###"
     ── INCOMPLETE ABILITY IMPLEMENTATION ───────────────────── /code/proj/Main.roc ─
 
-    This type does not fully implement the `Eq` ability:
+    This type does not fully implement the `MEq` ability:
 
-    7│  Id := U64 has [Eq {eq}]
-                       ^^^^^^^
+    7│  Id := U64 has [MEq {eq}]
+                       ^^^^^^^^
 
     The following necessary members are missing implementations:
 
@@ -8554,10 +8800,10 @@ All branches in an `if` must have the same type!
             r#"
             app "test" provides [eq] to "./platform"
 
-            Eq has
-                eq : a, a -> Bool | a has Eq
+            MEq has
+                eq : a, a -> Bool | a has MEq
 
-            You := {} has [Eq {eq}]
+            You := {} has [MEq {eq}]
             AndI := {}
 
             eq = \@You {}, @AndI {} -> False
@@ -9179,9 +9425,9 @@ All branches in an `if` must have the same type!
             r#"
             app "test" provides [A] to "./platform"
 
-            Eq has eq : a, a -> U64 | a has Eq
+            MEq has eq : a, a -> U64 | a has MEq
 
-            A := U8 has [Eq {eq}]
+            A := U8 has [MEq {eq}]
             "#
         ),
         @r###"
@@ -9189,8 +9435,8 @@ All branches in an `if` must have the same type!
 
     An implementation of `eq` could not be found in this scope:
 
-    5│  A := U8 has [Eq {eq}]
-                         ^^
+    5│  A := U8 has [MEq {eq}]
+                          ^^
 
     Tip: consider adding a value of name `eq` in this scope, or using
     another variable that implements this ability member, like
@@ -9198,10 +9444,10 @@ All branches in an `if` must have the same type!
 
     ── INCOMPLETE ABILITY IMPLEMENTATION ───────────────────── /code/proj/Main.roc ─
 
-    This type does not fully implement the `Eq` ability:
+    This type does not fully implement the `MEq` ability:
 
-    5│  A := U8 has [Eq {eq}]
-                     ^^^^^^^
+    5│  A := U8 has [MEq {eq}]
+                     ^^^^^^^^
 
     The following necessary members are missing implementations:
 
@@ -9215,9 +9461,9 @@ All branches in an `if` must have the same type!
             r#"
             app "test" provides [A, myEq] to "./platform"
 
-            Eq has eq : a, a -> Bool | a has Eq
+            MEq has eq : a, a -> Bool | a has MEq
 
-            A := U8 has [ Eq {eq: aEq} ]
+            A := U8 has [ MEq {eq: aEq} ]
 
             myEq = \m, n -> m == n
             "#
@@ -9227,22 +9473,22 @@ All branches in an `if` must have the same type!
 
     Nothing is named `aEq` in this scope.
 
-    5│  A := U8 has [ Eq {eq: aEq} ]
-                              ^^^
+    5│  A := U8 has [ MEq {eq: aEq} ]
+                               ^^^
 
     Did you mean one of these?
 
         Eq
+        MEq
         myEq
         eq
-        U8
 
     ── INCOMPLETE ABILITY IMPLEMENTATION ───────────────────── /code/proj/Main.roc ─
 
-    This type does not fully implement the `Eq` ability:
+    This type does not fully implement the `MEq` ability:
 
-    5│  A := U8 has [ Eq {eq: aEq} ]
-                      ^^^^^^^^^^^^
+    5│  A := U8 has [ MEq {eq: aEq} ]
+                      ^^^^^^^^^^^^^
 
     The following necessary members are missing implementations:
 
@@ -9256,9 +9502,9 @@ All branches in an `if` must have the same type!
             r#"
             app "test" provides [A, myEq] to "./platform"
 
-            Eq has eq : a, a -> Bool | a has Eq
+            MEq has eq : a, a -> Bool | a has MEq
 
-            A := U8 has [ Eq {eq ? aEq} ]
+            A := U8 has [ MEq {eq ? aEq} ]
 
             myEq = \m, n -> m == n
             "#
@@ -9268,8 +9514,8 @@ All branches in an `if` must have the same type!
 
     Ability implementations cannot be optional:
 
-    5│  A := U8 has [ Eq {eq ? aEq} ]
-                          ^^^^^^^^
+    5│  A := U8 has [ MEq {eq ? aEq} ]
+                           ^^^^^^^^
 
     Custom implementations must be supplied fully.
 
@@ -9277,10 +9523,10 @@ All branches in an `if` must have the same type!
 
     ── INCOMPLETE ABILITY IMPLEMENTATION ───────────────────── /code/proj/Main.roc ─
 
-    This type does not fully implement the `Eq` ability:
+    This type does not fully implement the `MEq` ability:
 
-    5│  A := U8 has [ Eq {eq ? aEq} ]
-                      ^^^^^^^^^^^^^
+    5│  A := U8 has [ MEq {eq ? aEq} ]
+                      ^^^^^^^^^^^^^^
 
     The following necessary members are missing implementations:
 
@@ -9334,9 +9580,9 @@ All branches in an `if` must have the same type!
             r#"
             app "test" provides [A] to "./platform"
 
-            Eq has eq : a, a -> Bool | a has Eq
+            MEq has eq : a, a -> Bool | a has MEq
 
-            A := U8 has [ Eq {eq : Bool.eq} ]
+            A := U8 has [ MEq {eq : Bool.eq} ]
             "#
         ),
         @r###"
@@ -9344,18 +9590,18 @@ All branches in an `if` must have the same type!
 
     This ability implementation is qualified:
 
-    5│  A := U8 has [ Eq {eq : Bool.eq} ]
-                               ^^^^^^^
+    5│  A := U8 has [ MEq {eq : Bool.eq} ]
+                                ^^^^^^^
 
     Custom implementations must be defined in the local scope, and
     unqualified.
 
     ── INCOMPLETE ABILITY IMPLEMENTATION ───────────────────── /code/proj/Main.roc ─
 
-    This type does not fully implement the `Eq` ability:
+    This type does not fully implement the `MEq` ability:
 
-    5│  A := U8 has [ Eq {eq : Bool.eq} ]
-                      ^^^^^^^^^^^^^^^^^
+    5│  A := U8 has [ MEq {eq : Bool.eq} ]
+                      ^^^^^^^^^^^^^^^^^^
 
     The following necessary members are missing implementations:
 
@@ -9369,9 +9615,9 @@ All branches in an `if` must have the same type!
             r#"
             app "test" provides [A] to "./platform"
 
-            Eq has eq : a, a -> Bool | a has Eq
+            MEq has eq : a, a -> Bool | a has MEq
 
-            A := U8 has [ Eq {eq : \m, n -> m == n} ]
+            A := U8 has [ MEq {eq : \m, n -> m == n} ]
             "#
         ),
         @r###"
@@ -9379,8 +9625,8 @@ All branches in an `if` must have the same type!
 
     This ability implementation is not an identifier:
 
-    5│  A := U8 has [ Eq {eq : \m, n -> m == n} ]
-                               ^^^^^^^^^^^^^^^
+    5│  A := U8 has [ MEq {eq : \m, n -> m == n} ]
+                                ^^^^^^^^^^^^^^^
 
     Custom ability implementations defined in this position can only be
     unqualified identifiers, not arbitrary expressions.
@@ -9389,10 +9635,10 @@ All branches in an `if` must have the same type!
 
     ── INCOMPLETE ABILITY IMPLEMENTATION ───────────────────── /code/proj/Main.roc ─
 
-    This type does not fully implement the `Eq` ability:
+    This type does not fully implement the `MEq` ability:
 
-    5│  A := U8 has [ Eq {eq : \m, n -> m == n} ]
-                      ^^^^^^^^^^^^^^^^^^^^^^^^^
+    5│  A := U8 has [ MEq {eq : \m, n -> m == n} ]
+                      ^^^^^^^^^^^^^^^^^^^^^^^^^^
 
     The following necessary members are missing implementations:
 
@@ -9406,9 +9652,9 @@ All branches in an `if` must have the same type!
             r#"
             app "test" provides [A] to "./platform"
 
-            Eq has eq : a, a -> Bool | a has Eq
+            MEq has eq : a, a -> Bool | a has MEq
 
-            A := U8 has [ Eq {eq: eqA, eq: eqA} ]
+            A := U8 has [ MEq {eq: eqA, eq: eqA} ]
 
             eqA = \@A m, @A n -> m == n
             "#
@@ -9418,13 +9664,13 @@ All branches in an `if` must have the same type!
 
     This ability member implementation is duplicate:
 
-    5│  A := U8 has [ Eq {eq: eqA, eq: eqA} ]
-                                   ^^^^^^^
+    5│  A := U8 has [ MEq {eq: eqA, eq: eqA} ]
+                                    ^^^^^^^
 
     The first implementation was defined here:
 
-    5│  A := U8 has [ Eq {eq: eqA, eq: eqA} ]
-                          ^^^^^^^
+    5│  A := U8 has [ MEq {eq: eqA, eq: eqA} ]
+                           ^^^^^^^
 
     Only one custom implementation can be defined for an ability member.
     "###
@@ -5,32 +5,41 @@ use core::{
     mem::{align_of, ManuallyDrop},
 };
 
+/// A Roc `Dict`. Its associations are stored in insertion order in `data`; `buckets` is the hash
+/// table, where each bucket holds either 0 (empty) or an index into `data` plus one.
 #[derive(Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct RocDict<K, V>(RocList<RocDictItem<K, V>>);
+#[repr(C)]
+pub struct RocDict<K, V> {
+    buckets: RocList<usize>,
+    data: RocList<RocDictItem<K, V>>,
+}
 
 impl<K, V> RocDict<K, V> {
     pub fn len(&self) -> usize {
-        self.0.len()
+        self.data.len()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        self.data.is_empty()
     }
 
     pub fn with_capacity(capacity: usize) -> Self {
-        Self(RocList::with_capacity(capacity))
+        Self {
+            buckets: RocList::empty(),
+            data: RocList::with_capacity(capacity),
+        }
     }
 
     pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
-        self.0.iter().map(|item| (item.key(), item.value()))
+        self.data.iter().map(|item| (item.key(), item.value()))
     }
 
     pub fn iter_keys(&self) -> impl Iterator<Item = &K> {
-        self.0.iter().map(|item| item.key())
+        self.data.iter().map(|item| item.key())
     }
 
     pub fn iter_values(&self) -> impl Iterator<Item = &V> {
-        self.0.iter().map(|item| item.value())
+        self.data.iter().map(|item| item.value())
     }
 }
 
@@ -76,7 +85,7 @@ impl<'a, K, V> Iterator for IntoIter<'a, K, V> {
     fn next(&mut self) -> Option<Self::Item> {
         let item = self
             .items
-            .0
+            .data
             .get(self.index)
             .map(|item| (item.key(), item.value()));
 
@@ -86,7 +95,7 @@ impl<'a, K, V> Iterator for IntoIter<'a, K, V> {
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let remaining = self.items.0.len() - self.index;
+        let remaining = self.items.data.len() - self.index;
 
         (remaining, Some(remaining))
     }